@@ -5,6 +5,7 @@ use proc_macro2::TokenStream;
 use proc_macro2::{Literal, Span};
 use quote::{format_ident, quote, ToTokens, TokenStreamExt};
 use std::collections::HashSet;
+use syn::ext::IdentExt;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{
@@ -22,6 +23,8 @@ pub fn expand_component_derive(ast: syn::DeriveInput) -> TokenStream {
 
 struct ParsedOptions {
     ident: syn::Ident,
+    generics: syn::Generics,
+    attrs: Vec<syn::Attribute>,
     fields: Vec<ParsedField>,
 }
 
@@ -49,17 +52,132 @@ impl TryFrom<syn::DeriveInput> for ParsedOptions {
 
         Ok(ParsedOptions {
             ident: input.ident,
+            generics: input.generics,
+            attrs: input.attrs,
             fields,
         })
     }
 }
 
+/// The query for the root element of a component that can be resolved from the driver.
+struct RootArgs {
+    by: TokenStream,
+    ignore_errors: Option<bool>,
+    description: Option<Literal>,
+    wait: Option<WaitOptions>,
+    nowait: Option<bool>,
+}
+
+impl RootArgs {
+    /// Parse a root selector such as `css:#app`.
+    fn try_from_lit(lit: &syn::LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        let by = match value.split_once(':') {
+            Some((kind, selector)) => {
+                let selector = Literal::string(selector);
+                match kind {
+                    "id" => quote!(::thirtyfour::By::Id(#selector)),
+                    "tag" => quote!(::thirtyfour::By::Tag(#selector)),
+                    "link" => quote!(::thirtyfour::By::LinkText(#selector)),
+                    "css" => quote!(::thirtyfour::By::Css(#selector)),
+                    "xpath" => quote!(::thirtyfour::By::XPath(#selector)),
+                    "name" => quote!(::thirtyfour::By::Name(#selector)),
+                    "class" => quote!(::thirtyfour::By::ClassName(#selector)),
+                    _ => bail!(
+                        lit.span(),
+                        "unknown selector kind '{kind}' (must be one of id, tag, link, css, xpath, name or class)"
+                    ),
+                }
+            }
+            None => bail!(lit.span(), "root selector must start with its kind, e.g. \"css:#app\""),
+        };
+
+        Ok(Self {
+            by,
+            ignore_errors: None,
+            description: None,
+            wait: None,
+            nowait: None,
+        })
+    }
+
+    /// Parse a `#[by(..)]` attribute on the struct.
+    fn try_from_by_attr(attr: &syn::Attribute) -> syn::Result<Self> {
+        let mut t = ByTokens::try_from(attr)?;
+        t.take_single(); // This is the default.
+        let s = Self {
+            by: t.take_by(),
+            ignore_errors: t.take_ignore_errors(),
+            description: t.take_description(),
+            wait: t.take_wait_options(),
+            nowait: t.take_nowait(),
+        };
+        if !t.tokens.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "only the selector, description, wait, nowait and ignore_errors can be used \
+                 for the root element of a component",
+            ));
+        }
+        Ok(s)
+    }
+}
+
+/// The options in the `#[component(..)]` attribute on the struct.
+#[derive(Default)]
+struct StructOptions {
+    await_ready: bool,
+    root: Option<RootArgs>,
+}
+
+impl StructOptions {
+    fn try_from_attrs(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|x| x.path().is_ident("component")) {
+            attr.parse_nested_meta(|nested| match &nested.path {
+                k if k.is_ident("await_ready") => {
+                    if options.await_ready {
+                        return Err(nested.error("cannot specify await_ready twice"));
+                    }
+                    options.await_ready = true;
+                    Ok(())
+                }
+                k if k.is_ident("root") => {
+                    if options.root.is_some() {
+                        return Err(nested.error("cannot specify root twice"));
+                    }
+                    let lit: syn::LitStr = nested.value()?.parse()?;
+                    options.root = Some(RootArgs::try_from_lit(&lit)?);
+                    Ok(())
+                }
+                e => Err(nested.error(format_args!(
+                    "unknown attribute {} (must be await_ready or root)",
+                    e.to_token_stream()
+                ))),
+            })?;
+        }
+        for attr in attrs.iter().filter(|x| x.path().is_ident("by")) {
+            if options.root.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "cannot specify the root element with both #[component(root = ..)] and #[by(..)]",
+                ));
+            }
+            options.root = Some(RootArgs::try_from_by_attr(attr)?);
+        }
+        Ok(options)
+    }
+}
+
 /// The args from which we will generate the Component code.
 struct ComponentArgs {
     ident: syn::Ident,
+    generics: syn::Generics,
     base_ident: syn::Ident,
+    options: StructOptions,
     fields: Vec<TokenStream>,
     field_initialisers: Vec<TokenStream>,
+    refresh_fields: Vec<RefreshField>,
 }
 
 impl TryFrom<ParsedOptions> for ComponentArgs {
@@ -67,9 +185,14 @@ impl TryFrom<ParsedOptions> for ComponentArgs {
 
     fn try_from(opts: ParsedOptions) -> Result<Self, Self::Error> {
         let ident = opts.ident;
+        let mut generics = opts.generics;
+        let options = StructOptions::try_from_attrs(&opts.attrs)?;
+        let type_params: HashSet<syn::Ident> =
+            generics.type_params().map(|x| x.ident.clone()).collect();
         let mut base_ident = None;
         let mut fields = Vec::with_capacity(opts.fields.len());
         let mut field_initialisers = Vec::with_capacity(opts.fields.len());
+        let mut refresh_fields = Vec::new();
 
         for field in opts.fields {
             if field.is_base() {
@@ -93,9 +216,14 @@ impl TryFrom<ParsedOptions> for ComponentArgs {
                 continue;
             }
             let field_def = field.get_def();
-            let initialiser = field.get_initialiser()?;
+            let initialiser = field.get_initialiser(&ident)?;
+            if !type_params.is_empty() {
+                let predicates = field.get_bounds(&type_params)?;
+                generics.make_where_clause().predicates.extend(predicates);
+            }
             fields.push(field_def);
             field_initialisers.push(initialiser);
+            refresh_fields.extend(field.get_refresh()?);
         }
 
         let base_ident = match base_ident {
@@ -110,23 +238,84 @@ impl TryFrom<ParsedOptions> for ComponentArgs {
 
         Ok(ComponentArgs {
             ident,
+            generics,
             base_ident,
+            options,
             fields,
             field_initialisers,
+            refresh_fields,
         })
     }
 }
 
+impl ComponentArgs {
+    /// Generate `invalidate_all()` and `refresh_all()` for the resolver fields.
+    fn refresh_tokens(&self) -> TokenStream {
+        if self.refresh_fields.is_empty() {
+            return quote!();
+        }
+
+        let cfg_attrs: Vec<_> = self.refresh_fields.iter().map(|x| &x.cfg_attr).collect();
+        let idents: Vec<_> = self.refresh_fields.iter().map(|x| &x.ident).collect();
+        let names = idents.iter().map(|x| Literal::string(&x.unraw().to_string()));
+        let (invalidate, refresh): (Vec<_>, Vec<_>) = self
+            .refresh_fields
+            .iter()
+            .map(|x| match x.custom {
+                // The output of a custom resolver function may not be a component, so only
+                // use the methods covered by the bounds from `get_bounds()`.
+                true => (quote!(invalidate), quote!(resolve_force)),
+                false => (quote!(invalidate_all), quote!(refresh_all)),
+            })
+            .unzip();
+
+        quote!(
+            fn invalidate_all(&self) {
+                #(
+                    #cfg_attrs
+                    self.#idents.#invalidate();
+                )*
+            }
+
+            fn refresh_all(
+                &self,
+            ) -> impl ::std::future::Future<Output = ::thirtyfour::error::WebDriverResult<()>>
+                   + ::std::marker::Send {
+                // Invalidate everything up front, so nothing stale is left behind on error.
+                self.invalidate_all();
+                let component = ::std::any::type_name::<Self>();
+                #(
+                    #cfg_attrs
+                    let #idents = self.#idents.clone();
+                )*
+                async move {
+                    #(
+                        #cfg_attrs
+                        #idents.#refresh().await.map_err(|e| {
+                            ::thirtyfour::error::WebDriverError::ComponentRefreshFailed(
+                                format!("{}.{}", component, #names),
+                                e,
+                            )
+                        })?;
+                    )*
+                    Ok(())
+                }
+            }
+        )
+    }
+}
+
 impl ToTokens for ComponentArgs {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         // Generate impl.
         let ident = &self.ident;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
         let base_ident = &self.base_ident;
         let fields = &self.fields;
         let field_initialisers = &self.field_initialisers;
 
         tokens.append_all(quote!(
-            impl #ident {
+            impl #impl_generics #ident #ty_generics #where_clause {
                 pub fn new(base: ::thirtyfour::WebElement) -> Self {
                     #(#field_initialisers)*
                     Self {
@@ -140,7 +329,9 @@ impl ToTokens for ComponentArgs {
         // impl From<WebElement>
         tokens.append_all(quote!(
             #[automatically_derived]
-            impl From<::thirtyfour::WebElement> for #ident {
+            impl #impl_generics From<::thirtyfour::WebElement> for #ident #ty_generics
+            #where_clause
+            {
                 fn from(elem: ::thirtyfour::WebElement) -> Self {
                     Self::new(elem)
                 }
@@ -148,17 +339,65 @@ impl ToTokens for ComponentArgs {
         ));
 
         // impl Component
+        let await_ready = self.options.await_ready.then(|| {
+            quote!(
+                fn await_ready(
+                    &self,
+                ) -> impl ::std::future::Future<Output = ::thirtyfour::error::WebDriverResult<()>>
+                       + ::std::marker::Send {
+                    ::thirtyfour::components::ComponentLifecycle::on_resolved(self)
+                }
+            )
+        });
+        let refresh = self.refresh_tokens();
         tokens.append_all(quote!(
             #[automatically_derived]
-            impl ::thirtyfour::components::Component for #ident {
+            impl #impl_generics ::thirtyfour::components::Component for #ident #ty_generics
+            #where_clause
+            {
                 fn base_element(&self) -> ::thirtyfour::WebElement {
                     self.#base_ident.clone()
                 }
+
+                #await_ready
+
+                #refresh
             }
         ));
+
+        // impl RootComponent
+        if let Some(root) = &self.options.root {
+            let by = &root.by;
+            // Name the component in errors, unless another description was given.
+            let description =
+                root.description.clone().unwrap_or_else(|| Literal::string(&ident.to_string()));
+            let opts =
+                query_options(&root.ignore_errors, &Some(description), &root.wait, &root.nowait);
+            tokens.append_all(quote!(
+                #[automatically_derived]
+                impl #impl_generics ::thirtyfour::components::RootComponent for #ident #ty_generics
+                #where_clause
+                {
+                    fn root_selector() -> ::thirtyfour::By {
+                        #by
+                    }
+
+                    fn root_options() -> ::thirtyfour::extensions::query::ElementQueryOptions {
+                        #opts
+                    }
+                }
+            ));
+        }
     }
 }
 
+/// A resolver field, to be invalidated by `invalidate_all()` and resolved by `refresh_all()`.
+struct RefreshField {
+    cfg_attr: Option<syn::Attribute>,
+    ident: syn::Ident,
+    custom: bool,
+}
+
 struct ParsedField {
     ident: syn::Ident,
     ty: syn::Type,
@@ -202,23 +441,44 @@ impl ParsedField {
     /// Self {
     ///     some_field,
     /// }
-    pub fn get_initialiser(&self) -> syn::Result<TokenStream> {
+    pub fn get_initialiser(&self, component: &syn::Ident) -> syn::Result<TokenStream> {
         let cfg_attr = self.cfg_attr();
         let ident = &self.ident;
 
         match (&self.ty, self.by_attr()) {
             (syn::Type::Path(p), Some(by_attr)) => {
-                let by_tokens = ByTokens::try_from(by_attr)?;
+                let mut by_tokens = ByTokens::try_from(by_attr)?;
                 let ty = fix_type(p.path.clone());
+                let kind = resolver_kind(&p.path, &by_tokens);
+                by_tokens.check_resolver_kind(kind, by_attr)?;
+                let auto_refresh = match by_tokens.take_no_auto_refresh() {
+                    Some(true) => quote!(.set_auto_refresh(false)),
+                    _ => quote!(),
+                };
+                let shadow = match by_tokens.take_shadow() {
+                    Some(true) => {
+                        let description =
+                            Literal::string(&format!("{}.{}", component.unraw(), ident.unraw()));
+                        quote!(.in_shadow_root(#description))
+                    }
+                    _ => quote!(),
+                };
+                if kind == ResolverKind::Stream {
+                    let stream_args = StreamArgs::try_new(ty, by_tokens)?;
 
-                // Use type or attribute to infer single/multi resolver.
-                if by_tokens.is_multi() || is_multi_resolver(&p.path) {
+                    Ok(quote!(
+                        #cfg_attr
+                        let #ident = {
+                            #stream_args
+                        };
+                    ))
+                } else if kind == ResolverKind::Multi {
                     let multi_args = MultiResolverArgs::try_new(ty, by_tokens)?;
 
                     Ok(quote!(
                         #cfg_attr
                         let #ident = {
-                            #multi_args
+                            #multi_args #auto_refresh #shadow
                         };
                     ))
                 } else {
@@ -227,7 +487,7 @@ impl ParsedField {
                     Ok(quote!(
                         #cfg_attr
                         let #ident = {
-                            #single_args
+                            #single_args #auto_refresh #shadow
                         };
                     ))
                 }
@@ -238,6 +498,70 @@ impl ParsedField {
             )),
         }
     }
+
+    /// Get the details needed to refresh this field, if it is a resolver.
+    ///
+    /// Streams are not included since they do not cache any elements.
+    pub fn get_refresh(&self) -> syn::Result<Option<RefreshField>> {
+        let (syn::Type::Path(p), Some(by_attr)) = (&self.ty, self.by_attr()) else {
+            return Ok(None);
+        };
+        let by_tokens = ByTokens::try_from(by_attr)?;
+        if resolver_kind(&p.path, &by_tokens) == ResolverKind::Stream {
+            return Ok(None);
+        }
+
+        Ok(Some(RefreshField {
+            cfg_attr: self.cfg_attr().cloned(),
+            ident: self.ident.clone(),
+            custom: by_tokens.is_custom(),
+        }))
+    }
+
+    /// Get the where-clause bounds that new() needs for this field, if its type uses any of
+    /// the struct's type parameters.
+    ///
+    /// For example, `rows: ElementResolver<Vec<T>>` needs `T` to be a component, while
+    /// `marker: PhantomData<T>` only needs `PhantomData<T>: Default`.
+    pub fn get_bounds(
+        &self,
+        params: &HashSet<syn::Ident>,
+    ) -> syn::Result<Vec<syn::WherePredicate>> {
+        if !mentions_type_param(self.ty.to_token_stream(), params) {
+            return Ok(Vec::new());
+        }
+
+        let ty = &self.ty;
+        let bounds = match (ty, self.by_attr()) {
+            (syn::Type::Path(p), Some(by_attr)) => {
+                let by_tokens = ByTokens::try_from(by_attr)?;
+                let kind = resolver_kind(&p.path, &by_tokens);
+                match element_type(&p.path, kind) {
+                    // A custom resolver function can return anything the resolver can hold.
+                    // These bounds must also cover `invalidate()` and `resolve_force()`,
+                    // which the derived `refresh_all()` calls on custom fields.
+                    Some((arg, _)) if by_tokens.is_custom() => {
+                        vec![syn::parse_quote!(#arg: Clone + Send + Sync + 'static)]
+                    }
+                    Some((_, elem)) if kind == ResolverKind::Stream => vec![syn::parse_quote!(
+                        #elem: From<::thirtyfour::WebElement> + Send + 'static
+                    )],
+                    Some((_, elem)) if mentions_type_param(elem.to_token_stream(), params) => {
+                        vec![syn::parse_quote!(
+                            #elem: ::thirtyfour::components::Component
+                                + Clone
+                                + Send
+                                + Sync
+                                + 'static
+                        )]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            _ => vec![syn::parse_quote!(#ty: Default)],
+        };
+        Ok(bounds)
+    }
 }
 
 #[derive(Clone)]
@@ -280,6 +604,11 @@ enum ByToken {
     Wait(WaitOptions),
     NoWait,
     CustomFn(Expr),
+    Stream,
+    PageSize(Expr),
+    Scroll(Expr),
+    NoAutoRefresh,
+    Shadow,
 }
 
 impl ByToken {
@@ -305,6 +634,11 @@ impl ByToken {
             ByToken::Wait(_) => "wait",
             ByToken::NoWait => "nowait",
             ByToken::CustomFn(_) => "custom",
+            ByToken::Stream => "stream",
+            ByToken::PageSize(_) => "page_size",
+            ByToken::Scroll(_) => "scroll",
+            ByToken::NoAutoRefresh => "no_auto_refresh",
+            ByToken::Shadow => "shadow",
         }
     }
 
@@ -320,6 +654,9 @@ impl ByToken {
             ByToken::IgnoreErrors | ByToken::Description(_) => vec!["custom"],
             ByToken::Wait(_) => vec!["custom", "nowait"],
             ByToken::NoWait => vec!["custom", "wait"],
+            ByToken::Stream => vec!["multi", "custom"],
+            ByToken::PageSize(_) | ByToken::Scroll(_) => vec!["custom"],
+            ByToken::Shadow => vec!["custom", "stream"],
             ByToken::CustomFn(_) => {
                 vec![
                     "multi",
@@ -331,6 +668,10 @@ impl ByToken {
                     "wait",
                     "nowait",
                     "allow_empty",
+                    "stream",
+                    "page_size",
+                    "scroll",
+                    "shadow",
                 ]
             }
             _ => vec![],
@@ -338,6 +679,30 @@ impl ByToken {
     }
 }
 
+/// Explain why two options cannot be used together, where that is not obvious.
+fn conflict_hint(a: &str, b: &str) -> Option<&'static str> {
+    let either = |x: &[&str], y: &[&str]| {
+        (x.contains(&a) && y.contains(&b)) || (x.contains(&b) && y.contains(&a))
+    };
+
+    if either(&["wait"], &["nowait"]) {
+        Some("use wait(..) to change how long the query polls, or nowait to not poll at all")
+    } else if a == "custom" || b == "custom" {
+        Some("a custom resolver function performs its own query, so it cannot be configured")
+    } else if either(&["single"], &["first"]) {
+        Some("'first' already resolves a single element")
+    } else if either(&["allow_empty"], &["not_empty"]) {
+        Some("'not_empty' is the default, remove it to allow an empty Vec")
+    } else if either(&["single", "first"], &["allow_empty", "not_empty"]) {
+        Some(
+            "'single' and 'first' resolve one element, \
+             'allow_empty' and 'not_empty' resolve a Vec of elements",
+        )
+    } else {
+        None
+    }
+}
+
 /// Convert `Meta` into `ByToken`.
 ///
 /// This is where all tokens are parsed into `ByToken` variants.
@@ -354,6 +719,9 @@ impl TryFrom<Meta> for ByToken {
                 k if k.is_ident("first") => Ok(ByToken::First),
                 k if k.is_ident("ignore_errors") => Ok(ByToken::IgnoreErrors),
                 k if k.is_ident("nowait") => Ok(ByToken::NoWait),
+                k if k.is_ident("stream") => Ok(ByToken::Stream),
+                k if k.is_ident("no_auto_refresh") => Ok(ByToken::NoAutoRefresh),
+                k if k.is_ident("shadow") => Ok(ByToken::Shadow),
                 e => Err(syn::Error::new(
                     e.span(),
                     format!("unknown attribute {}", e.to_token_stream()),
@@ -361,6 +729,17 @@ impl TryFrom<Meta> for ByToken {
             },
             Meta::List(list) => {
                 match list.path {
+                    // ignore_errors(stale)
+                    ref p if p.is_ident("ignore_errors") => {
+                        list.parse_nested_meta(|nested| match &nested.path {
+                            k if k.is_ident("stale") => Ok(()),
+                            e => Err(nested.error(format_args!(
+                                "unknown error kind {} (must be stale)",
+                                e.to_token_stream()
+                            ))),
+                        })?;
+                        Ok(ByToken::IgnoreErrors)
+                    }
                     // wait(timeout_ms = u32, interval_ms = u32)
                     ref p if p.is_ident("wait") => {
                         let mut timeout: Option<Expr> = None;
@@ -466,6 +845,8 @@ impl TryFrom<Meta> for ByToken {
                     }),
                 ) if k.is_ident("description") => Ok(ByToken::Description(v.token())),
                 (k, expr) if k.is_ident("custom") => Ok(ByToken::CustomFn(expr)),
+                (k, expr) if k.is_ident("page_size") => Ok(ByToken::PageSize(expr)),
+                (k, expr) if k.is_ident("scroll") => Ok(ByToken::Scroll(expr)),
                 (k, ..) => Err(syn::Error::new(
                     k.span(),
                     format_args!("unknown attribute: {}", k.to_token_stream()),
@@ -498,7 +879,10 @@ impl ByTokens {
             for t in disallowed {
                 if unique_tokens.contains(t) {
                     let unique = token.get_unique_type();
-                    bail!(span, "cannot specify '{unique}' with '{t}'")
+                    match conflict_hint(unique, t) {
+                        Some(hint) => bail!(span, "cannot specify '{unique}' with '{t}': {hint}"),
+                        None => bail!(span, "cannot specify '{unique}' with '{t}'"),
+                    }
                 }
             }
         }
@@ -536,10 +920,60 @@ impl ByTokens {
         }
     }
 
+    /// Reject options that do not apply to this kind of resolver.
+    ///
+    /// Without this, such options would only be reported as unexpected extra args.
+    pub fn check_resolver_kind(
+        &self,
+        kind: ResolverKind,
+        attr: &syn::Attribute,
+    ) -> syn::Result<()> {
+        for token in self.tokens.iter() {
+            let t = token.get_unique_type();
+            let message = match (kind, token) {
+                (
+                    ResolverKind::Stream,
+                    ByToken::Single
+                    | ByToken::First
+                    | ByToken::NotEmpty
+                    | ByToken::AllowEmpty
+                    | ByToken::IgnoreErrors
+                    | ByToken::Description(_)
+                    | ByToken::Wait(_)
+                    | ByToken::NoWait
+                    | ByToken::CustomFn(_)
+                    | ByToken::NoAutoRefresh
+                    | ByToken::Shadow,
+                ) => format!("'{t}' cannot be used with a ComponentStream"),
+                (ResolverKind::Multi, ByToken::Single | ByToken::First) => {
+                    format!("'{t}' cannot be used with a multi-element resolver")
+                }
+                (ResolverKind::Single, ByToken::NotEmpty | ByToken::AllowEmpty) => format!(
+                    "'{t}' can only be used with a multi-element resolver, e.g. ElementResolver<Vec<T>>"
+                ),
+                (
+                    ResolverKind::Single | ResolverKind::Multi,
+                    ByToken::PageSize(_) | ByToken::Scroll(_),
+                ) => format!("'{t}' can only be used with a ComponentStream"),
+                _ => continue,
+            };
+            return Err(syn::Error::new_spanned(attr, message));
+        }
+        Ok(())
+    }
+
+    pub fn is_stream(&self) -> bool {
+        self.tokens.iter().any(|x| matches!(&x, ByToken::Stream))
+    }
+
     pub fn is_multi(&self) -> bool {
         self.tokens.iter().any(|x| matches!(&x, ByToken::Multi))
     }
 
+    pub fn is_custom(&self) -> bool {
+        self.tokens.iter().any(|x| matches!(&x, ByToken::CustomFn(_)))
+    }
+
     pub fn take_one<F, T>(&mut self, f: F) -> Option<T>
     where
         F: Fn(&ByToken) -> Option<T>,
@@ -626,6 +1060,41 @@ impl ByTokens {
         })
     }
 
+    pub fn take_stream(&mut self) -> Option<bool> {
+        self.take_one(|x| match x {
+            ByToken::Stream => Some(true),
+            _ => None,
+        })
+    }
+
+    pub fn take_page_size(&mut self) -> Option<Expr> {
+        self.take_one(|x| match x {
+            ByToken::PageSize(n) => Some(n.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn take_scroll(&mut self) -> Option<Expr> {
+        self.take_one(|x| match x {
+            ByToken::Scroll(f) => Some(f.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn take_no_auto_refresh(&mut self) -> Option<bool> {
+        self.take_one(|x| match x {
+            ByToken::NoAutoRefresh => Some(true),
+            _ => None,
+        })
+    }
+
+    pub fn take_shadow(&mut self) -> Option<bool> {
+        self.take_one(|x| match x {
+            ByToken::Shadow => Some(true),
+            _ => None,
+        })
+    }
+
     pub fn take_custom(&mut self) -> Option<Expr> {
         self.take_one(|x| match x {
             ByToken::CustomFn(f) => Some(f.clone()),
@@ -687,9 +1156,14 @@ impl ToTokens for DebugByTokens {
                 | ByToken::Single
                 | ByToken::First
                 | ByToken::IgnoreErrors
-                | ByToken::NoWait => tokens.append(format_ident!("{}", by_token.get_unique_type())),
+                | ByToken::NoWait
+                | ByToken::Stream
+                | ByToken::NoAutoRefresh
+                | ByToken::Shadow => tokens.append(format_ident!("{}", by_token.get_unique_type())),
                 // misc
-                ByToken::CustomFn(expr) => expr.to_tokens(tokens),
+                ByToken::CustomFn(expr) | ByToken::PageSize(expr) | ByToken::Scroll(expr) => {
+                    expr.to_tokens(tokens)
+                }
                 ByToken::Wait(opts) => opts.to_tokens(tokens),
             }
         }
@@ -745,7 +1219,107 @@ fn is_multi_resolver(path: &syn::Path) -> bool {
     }
 }
 
+/// Return true if this path should be treated as a `ComponentStream`.
+fn is_component_stream(path: &syn::Path) -> bool {
+    is_type(
+        path,
+        &["ComponentStream", "components|ComponentStream", "thirtyfour|components|ComponentStream"],
+    )
+}
+
+/// Use the type or attribute to infer single/multi resolver, or stream.
+fn resolver_kind(path: &syn::Path, by_tokens: &ByTokens) -> ResolverKind {
+    if by_tokens.is_stream() || is_component_stream(path) {
+        ResolverKind::Stream
+    } else if by_tokens.is_multi() || is_multi_resolver(path) {
+        ResolverKind::Multi
+    } else {
+        ResolverKind::Single
+    }
+}
+
+/// Get the type argument of a resolver or stream, along with the type of each element.
+///
+/// For `ElementResolver<Vec<T>>` this returns `(Vec<T>, T)`, and for `ElementResolver<T>`
+/// or `ComponentStream<T>` it returns `(T, T)`.
+fn element_type(path: &syn::Path, kind: ResolverKind) -> Option<(&syn::Type, &syn::Type)> {
+    let arg = match &path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(x) => x.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(t) => Some(t),
+            _ => None,
+        })?,
+        _ => return None,
+    };
+
+    match arg {
+        syn::Type::Path(t) if kind == ResolverKind::Multi => {
+            match &t.path.segments.last()?.arguments {
+                PathArguments::AngleBracketed(x) => x.args.iter().find_map(|x| match x {
+                    GenericArgument::Type(elem) => Some((arg, elem)),
+                    _ => None,
+                }),
+                _ => None,
+            }
+        }
+        _ => Some((arg, arg)),
+    }
+}
+
+/// Return true if the tokens mention any of the specified type parameters.
+fn mentions_type_param(tokens: TokenStream, params: &HashSet<syn::Ident>) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        proc_macro2::TokenTree::Ident(ident) => params.contains(&ident),
+        proc_macro2::TokenTree::Group(group) => mentions_type_param(group.stream(), params),
+        _ => false,
+    })
+}
+
+/// The kind of resolver to generate for a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResolverKind {
+    Single,
+    Multi,
+    Stream,
+}
+
+/// Generate the `ElementQueryOptions` for the specified options.
+fn query_options(
+    ignore_errors: &Option<bool>,
+    description: &Option<Literal>,
+    wait: &Option<WaitOptions>,
+    nowait: &Option<bool>,
+) -> TokenStream {
+    let ignore_errors_ident = match ignore_errors {
+        Some(true) => quote!(::std::option::Option::Some(true)),
+        _ => quote!(::std::option::Option::None),
+    };
+    let description_ident = match description {
+        Some(desc) => {
+            quote!(::std::option::Option::Some(::std::string::ToString::to_string(&#desc)))
+        }
+        None => quote!(::std::option::Option::None),
+    };
+    let wait_ident = match wait {
+        Some(opts) => quote!(#opts),
+        None => match nowait {
+            Some(true) => {
+                quote! {
+                    ::std::option::Option::Some(::thirtyfour::extensions::query::ElementQueryWaitOptions::NoWait)
+                }
+            }
+            _ => quote!(::std::option::Option::None),
+        },
+    };
+    quote!(
+        ::thirtyfour::extensions::query::ElementQueryOptions::default()
+            .set_ignore_errors(#ignore_errors_ident)
+            .set_description::<String>(#description_ident)
+            .set_wait(#wait_ident)
+    )
+}
+
 /// All args for a single element resolver.
+#[allow(clippy::large_enum_variant)]
 enum SingleResolverOptions {
     CustomFn(Expr),
     Opts {
@@ -813,33 +1387,7 @@ impl ToTokens for SingleResolverArgs {
                 wait,
                 nowait,
             } => {
-                let ignore_errors_ident = match ignore_errors {
-                    Some(true) => quote!(::std::option::Option::Some(true)),
-                    _ => quote!(::std::option::Option::None),
-                };
-                let description_ident = match description {
-                    Some(desc) => {
-                        quote!(::std::option::Option::Some(::std::string::ToString::to_string(&#desc)))
-                    }
-                    None => quote!(::std::option::Option::None),
-                };
-                let wait_ident = match wait {
-                    Some(opts) => quote!(#opts),
-                    None => match nowait {
-                        Some(true) => {
-                            quote! {
-                                ::std::option::Option::Some(::thirtyfour::extensions::query::ElementQueryWaitOptions::NoWait)
-                            }
-                        }
-                        _ => quote!(::std::option::Option::None),
-                    },
-                };
-                let opts_ident = quote!(
-                    ::thirtyfour::extensions::query::ElementQueryOptions::default()
-                        .set_ignore_errors(#ignore_errors_ident)
-                        .set_description::<String>(#description_ident)
-                        .set_wait(#wait_ident)
-                );
+                let opts_ident = query_options(ignore_errors, description, wait, nowait);
 
                 match first {
                     Some(true) => {
@@ -859,6 +1407,7 @@ impl ToTokens for SingleResolverArgs {
 }
 
 /// All args for a multi-element resolver.
+#[allow(clippy::large_enum_variant)]
 enum MultiResolverOptions {
     CustomFn(Expr),
     Opts {
@@ -927,33 +1476,7 @@ impl ToTokens for MultiResolverArgs {
                 wait,
                 nowait,
             } => {
-                let ignore_errors_ident = match ignore_errors {
-                    Some(true) => quote!(::std::option::Option::Some(true)),
-                    _ => quote!(::std::option::Option::None),
-                };
-                let description_ident = match description {
-                    Some(desc) => {
-                        quote!(::std::option::Option::Some(::std::string::ToString::to_string(&#desc)))
-                    }
-                    None => quote!(::std::option::Option::None),
-                };
-                let wait_ident = match wait {
-                    Some(opts) => quote!(#opts),
-                    None => match nowait {
-                        Some(true) => {
-                            quote! {
-                                Some(::thirtyfour::extensions::query::ElementQueryWaitOptions::NoWait)
-                            }
-                        }
-                        _ => quote!(None),
-                    },
-                };
-                let opts_ident = quote!(
-                    ::thirtyfour::extensions::query::ElementQueryOptions::default()
-                        .set_ignore_errors(#ignore_errors_ident)
-                        .set_description::<String>(#description_ident)
-                        .set_wait(#wait_ident)
-                );
+                let opts_ident = query_options(ignore_errors, description, wait, nowait);
 
                 match allow_empty {
                     Some(true) => {
@@ -972,11 +1495,51 @@ impl ToTokens for MultiResolverArgs {
     }
 }
 
+/// All args for a `ComponentStream`.
+struct StreamArgs {
+    ty: TokenStream,
+    by: TokenStream,
+    page_size: Option<Expr>,
+    scroll: Option<Expr>,
+}
+
+impl StreamArgs {
+    fn try_new(ty: TokenStream, mut t: ByTokens) -> syn::Result<Self> {
+        t.take_stream(); // Not used here.
+        let s = Self {
+            ty,
+            by: t.take_by(),
+            page_size: t.take_page_size(),
+            scroll: t.take_scroll(),
+        };
+        if !t.tokens.is_empty() {
+            return Err(syn::Error::new_spanned(DebugByTokens(t), "unexpected extra args"));
+        }
+        Ok(s)
+    }
+}
+
+impl ToTokens for StreamArgs {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ty = &self.ty;
+        let by = &self.by;
+        let page_size = self.page_size.iter();
+        let scroll = self.scroll.iter();
+        tokens.append_all(quote!(
+            #ty::new(base.clone(), #by)
+                #(.set_page_size(#page_size))*
+                #(.set_scroll_fn(#scroll))*
+        ));
+    }
+}
+
 /// Converts GenericType<Args> to GenericType::<Args> to call ::new_*() on it.
 ///
 /// Non-generic types will be returned as is.
 fn fix_type(mut ty: syn::Path) -> TokenStream {
     let last = ty.segments.pop();
+    // Drop the trailing `::` left behind by `pop()` on qualified paths.
+    ty.segments.pop_punct();
     match last {
         Some(pair) => {
             let (p, _) = pair.into_tuple();
@@ -10,6 +10,14 @@ use syn::{parse_macro_input, DeriveInput};
 
 mod component;
 
+macro_rules! bail {
+    ($span: expr, $($fmt:tt)*) => {
+        return Err(syn::Error::new($span, format_args!($($fmt)*)))
+    };
+}
+
+pub(crate) use bail;
+
 /// Derive macro for a wrapped `Component`.
 ///
 /// A `Component` contains a base [`WebElement`] from which all element queries will be performed.
@@ -31,6 +39,20 @@ mod component;
 /// If you use this attribute, you cannot also have another
 /// element named `base`.
 ///
+/// ### `#[component(..)]`
+/// Options for the component itself are specified with the `#[component(..)]` attribute on
+/// the struct:
+/// - `await_ready`: Call `ComponentLifecycle::on_resolved()` whenever an `ElementResolver`
+///   resolves this component, before returning it. You must implement `ComponentLifecycle`
+///   for the component. Errors are returned as `ComponentNotReady`, naming the component type.
+/// - `root = "css:#app"`: Implement `RootComponent`, so the component can be resolved from the
+///   driver with `from_driver()`. The selector kind can be `id`, `tag`, `link`, `css`,
+///   `xpath`, `name` or `class`.
+///
+/// Alternatively, put a `#[by(..)]` attribute on the struct to specify the root element.
+/// This also supports the `description`, `wait`, `nowait` and `ignore_errors` options.
+/// The description defaults to the name of the component, so that it appears in errors.
+///
 /// ### `#[by(..)]`
 /// Components use the `#[by(..)]` attribute to specify all the details of the query.
 ///
@@ -45,24 +67,79 @@ mod component;
 ///
 /// Optional attributes available within `#[by(..)]` include:
 /// - `single`: (default, single element only) Return `NoSuchElement` if the number of elements
-///             found is != 1.
+///   found is != 1.
 /// - `first`: (single element only) Select the first element that matches the query.
-///            By default, a query will return `NoSuchElement` if multiple elements match.
-///            This default is designed to catch instances where a query is not specific enough.
+///   By default, a query will return `NoSuchElement` if multiple elements match.
+///   This default is designed to catch instances where a query is not specific enough.
 /// - `not_empty`: (default, multi elements only) Return `NoSuchElement` if no elements were found.
 /// - `allow_empty`: (multi elements only) Return an empty Vec if no elements were found.
-///                  By default a multi-element query will return `NoSuchElement` if no
-///                  elements were found.
+///   By default a multi-element query will return `NoSuchElement` if no
+///   elements were found.
 /// - `description = "..."`: Set the element description to be displayed in `NoSuchElement` errors.
-/// - `allow_errors`: Ignore errors such as stale elements while polling.
-/// - `wait(timeout_ms = 10000, interval_ms=500)`: Override the default polling options.
+/// - `ignore_errors`: Ignore errors such as stale elements while polling.
+///   This can also be written as `ignore_errors(stale)`.
+/// - `wait(timeout_ms = 10000, interval_ms = 500)`: Override the default polling options.
 /// - `nowait`: Turn off polling for this element query.
+/// - `no_auto_refresh`: Do not automatically re-resolve the element(s) if they become stale.
+///   See `ElementResolver::with_resolved()`.
+/// - `shadow`: Run the query inside the shadow root of the base element, for components that
+///   wrap a web component. If the shadow root is closed, resolving the field returns a
+///   `NoSuchShadowRoot` error naming the component and field.
 /// - `custom = "my_resolve_fn"`: Use the specified function to resolve the element or component.
-///                      **NOTE**: The `custom` attribute cannot be specified with any other
-///                      attribute.
+///   **NOTE**: The `custom` attribute cannot be specified with any other
+///   attribute.
+///
+/// The following attributes are for [`ComponentStream`] fields only:
+/// - `stream`: Treat the field as a `ComponentStream`. This is implied by the field type, but
+///   can be specified to be explicit.
+/// - `page_size = 50`: The number of elements to request per page.
+/// - `scroll = my_scroll_fn`: Use the specified function to reveal more elements, for
+///   virtualized lists. It has the same signature as a custom resolver function returning `()`.
 ///
 /// See [`ElementQueryOptions`] for more details on how each option is used.
 ///
+/// ### Nested components
+///
+/// The element type of a resolver can be any type that implements `Component`, as well as
+/// [`WebElement`]. Use `ElementResolver<MyComponent>` or `ElementResolver<Vec<MyComponent>>`
+/// and the element found by the `#[by(..)]` query becomes the base element of the nested
+/// component. All of the options above work the same way on nested fields.
+///
+/// Fields typed as `ElementResolver<Vec<T>>` are treated as multi-element resolvers.
+/// Using `single` or `first` on them, or `not_empty` or `allow_empty` on a single-element
+/// resolver, is a compile error.
+///
+/// ### Invalidating and refreshing
+///
+/// The derive implements `Component::invalidate_all()`, which invalidates every resolver
+/// field, along with the resolvers of any nested components they have cached. Use it after
+/// the page has been re-rendered, instead of calling `invalidate()` on each field.
+///
+/// `Component::refresh_all().await` invalidates everything and then resolves every field
+/// again, including the fields of nested components. If a field cannot be resolved, it
+/// returns a `ComponentRefreshFailed` error naming that field. Fields using a `custom`
+/// resolver function are resolved again, but not recursed into, and `ComponentStream`
+/// fields are skipped since they do not cache anything.
+///
+/// ### Generic components
+///
+/// Components can be generic over their nested components, for example a list panel that
+/// can hold different kinds of rows:
+///
+/// ```ignore
+/// #[derive(Debug, Clone, Component)]
+/// pub struct ListPanel<T> {
+///     base: WebElement,
+///     #[by(css = "tbody tr")]
+///     rows: ElementResolver<Vec<T>>,
+/// }
+/// ```
+///
+/// The generated impls carry over the struct's generics and where-clauses, and add only
+/// the bounds the fields need: `T: Component + Clone + Send + Sync + 'static` for the
+/// element type of a resolver, `T: From<WebElement> + Send + 'static` for a
+/// `ComponentStream<T>`, and `Default` for fields without a `#[by(..)]` attribute.
+///
 /// ### Custom resolver functions
 ///
 /// When using `custom = "my_resolve_fn"`, your function signature should look something like this:
@@ -116,17 +193,10 @@ mod component;
 /// ```
 /// [`WebElement`]: https://docs.rs/thirtyfour/latest/thirtyfour/struct.WebElement.html
 /// [`ElementResolver`]: https://docs.rs/thirtyfour/0.31.0-alpha.1/thirtyfour/components/struct.ElementResolver.html
+/// [`ComponentStream`]: https://docs.rs/thirtyfour/latest/thirtyfour/components/struct.ComponentStream.html
 /// [`ElementQueryOptions`]: https://docs.rs/thirtyfour/0.31.0-alpha.1/thirtyfour/extensions/query/struct.ElementQueryOptions.html
 /// [`ElementQueryFn<T>`]: https://docs.rs/thirtyfour/0.31.0-alpha.1/thirtyfour/common/types/type.ElementQueryFn.html
-
-macro_rules! bail {
-    ($span: expr, $($fmt:tt)*) => {
-        return Err(syn::Error::new($span, format_args!($($fmt)*)))
-    };
-}
-
-pub(crate) use bail;
-#[proc_macro_derive(Component, attributes(base, by))]
+#[proc_macro_derive(Component, attributes(base, by, component))]
 pub fn derive_component_fn(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     expand_component_derive(ast).into()
@@ -1,34 +1,248 @@
 use crate::session::handle::SessionHandle;
 use crate::{
     common::{
-        action::{ActionSource, KeyAction, PointerAction, PointerActionType},
+        action::{
+            ActionSource, KeyAction, MouseButton, PenProperties, PointerAction, PointerActionType,
+            WheelAction,
+        },
         command::{Actions, Command},
-        keys::TypingData,
+        keys::{Key, TypingData},
+        types::ElementId,
     },
-    error::WebDriverResult,
+    error::{WebDriverError, WebDriverErrorInfo, WebDriverErrorInner, WebDriverResult},
+    js::{ELEMENT_CENTER, HTML5_DRAG_AND_DROP, WRITE_CLIPBOARD},
     WebElement,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::ops::Range;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// The ActionChain struct allows you to perform multiple input actions in
-/// a sequence, including drag-and-drop, send keystrokes to an element, and
-/// hover the mouse over an element.
+/// a sequence, including drag-and-drop, send keystrokes to an element,
+/// hover the mouse over an element, and scroll with the mouse wheel.
 ///
 /// The easiest way to construct an ActionChain struct is via the WebDriver
 /// struct.
 ///
+/// A chain can be cloned to perform the same actions several times, or reused with
+/// [`ActionChain::perform_and_clear()`].
+///
 /// # Example:
 /// ```ignore
 /// driver.action_chain().drag_and_drop_element(elem_src, elem_target).perform().await?;
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ActionChain {
     handle: Arc<SessionHandle>,
     key_actions: ActionSource<KeyAction>,
     pointer_actions: ActionSource<PointerAction>,
+    wheel_actions: ActionSource<WheelAction>,
+    extra_pointers: Vec<ActionSource<PointerAction>>,
+    scroll_targets: Vec<WebElement>,
+    /// The elements to focus with script when performing, each with the tick it is
+    /// focused before.
+    focus_targets: Vec<(usize, WebElement)>,
+    /// The text to write to the clipboard when performing, each with the ticks of the key
+    /// chord that pastes it.
+    paste_targets: Vec<(Range<usize>, String)>,
+    /// The pointer position in the viewport after the actions so far, if known.
+    pointer_position: Option<(i64, i64)>,
+}
+
+impl fmt::Display for ActionChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string_pretty(&self.to_json()).map_err(|_| fmt::Error)?;
+        write!(f, "{json}")
+    }
+}
+
+/// Timing and size details of an action sequence, returned by
+/// [`ActionChain::perform_timed()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionTelemetry {
+    /// The wall-clock time taken to perform the actions, including the round trip to the
+    /// driver.
+    pub duration: Duration,
+    /// The number of ticks sent, which is the number of actions in each input source.
+    pub ticks: usize,
+    /// The id of each input source sent and the number of actions added to it, before
+    /// the sources were padded with pauses to the same length.
+    pub source_actions: Vec<(String, usize)>,
+    /// The size of the serialized `PerformActions` payload, in bytes.
+    pub payload_size: usize,
+}
+
+/// Typing speed presets for [`ActionChain::send_keys_with_delay()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypingSpeed {
+    /// A fast typist, with 50ms between keystrokes.
+    Fast,
+    /// An average typist, with 150ms between keystrokes.
+    Normal,
+    /// A slow typist, with 300ms between keystrokes.
+    Slow,
+}
+
+impl TypingSpeed {
+    /// The delay between keystrokes.
+    pub fn delay(self) -> Duration {
+        match self {
+            TypingSpeed::Fast => Duration::from_millis(50),
+            TypingSpeed::Normal => Duration::from_millis(150),
+            TypingSpeed::Slow => Duration::from_millis(300),
+        }
+    }
+}
+
+impl From<TypingSpeed> for Duration {
+    fn from(speed: TypingSpeed) -> Self {
+        speed.delay()
+    }
+}
+
+/// The direction of a swipe for [`ActionChain::swipe_element()`], which is the direction
+/// the finger moves in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwipeDirection {
+    /// Swipe towards the top of the viewport.
+    Up,
+    /// Swipe towards the bottom of the viewport.
+    Down,
+    /// Swipe towards the left of the viewport.
+    Left,
+    /// Swipe towards the right of the viewport.
+    Right,
+}
+
+impl SwipeDirection {
+    /// The X and Y offsets of a swipe of the specified distance in this direction.
+    fn offset(self, distance: i64) -> (i64, i64) {
+        match self {
+            SwipeDirection::Up => (0, -distance),
+            SwipeDirection::Down => (0, distance),
+            SwipeDirection::Left => (-distance, 0),
+            SwipeDirection::Right => (distance, 0),
+        }
+    }
+}
+
+/// The interval between the moves of a swipe or pinch, which is about one frame.
+const SWIPE_STEP_INTERVAL: Duration = Duration::from_millis(16);
+
+/// The number of moves for a swipe or pinch taking the specified duration, which is at
+/// least 2.
+fn swipe_steps(duration: Duration) -> u32 {
+    let frames = duration.as_millis() / SWIPE_STEP_INTERVAL.as_millis();
+    u32::try_from(frames).ok().unwrap_or(u32::MAX).max(2)
+}
+
+/// A recording of the actions in an [`ActionChain`], returned by
+/// [`ActionChain::to_recording()`].
+///
+/// The recording can be saved with serde, for example as JSON, and replayed later with
+/// [`ActionChain::from_recording()`]. Moves and scrolls relative to an element refer to
+/// the element by its id, which is only valid in the session and page it was recorded in.
+/// To replay them anywhere else, use [`ActionRecording::resolve_elements()`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRecording {
+    key_actions: ActionSource<KeyAction>,
+    pointer_actions: ActionSource<PointerAction>,
+    wheel_actions: ActionSource<WheelAction>,
+    #[serde(default)]
+    extra_pointers: Vec<ActionSource<PointerAction>>,
+    #[serde(default)]
+    scroll_targets: Vec<ElementId>,
+    #[serde(default)]
+    focus_targets: Vec<(usize, ElementId)>,
+    #[serde(default)]
+    paste_targets: Vec<(Range<usize>, String)>,
+    #[serde(default)]
+    pointer_position: Option<(i64, i64)>,
+}
+
+impl ActionRecording {
+    /// The ids of the elements referred to by this recording, in the order they are first
+    /// used.
+    pub fn element_ids(&self) -> Vec<ElementId> {
+        let mut ids: Vec<ElementId> = Vec::new();
+        for id in self.clone().element_ids_mut() {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+        ids
+    }
+
+    /// Replace each element id in this recording with the element returned by the
+    /// specified function, for example by finding the element again in a new session.
+    ///
+    /// The function is called once for each element id, in the order they are first used.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::action_chain::{ActionChain, ActionRecording};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// # let json = String::new();
+    /// let recording: ActionRecording = serde_json::from_str(&json)?;
+    /// // The recording only ever clicks the same button.
+    /// let recording = recording
+    ///     .resolve_elements(|_| driver.find(By::Id("button1")))
+    ///     .await?;
+    /// ActionChain::from_recording(driver.handle.clone(), recording).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn resolve_elements<F, Fut>(mut self, mut resolve: F) -> WebDriverResult<Self>
+    where
+        F: FnMut(ElementId) -> Fut,
+        Fut: Future<Output = WebDriverResult<WebElement>>,
+    {
+        let mut resolved = HashMap::new();
+        for id in self.element_ids() {
+            let element = resolve(id.clone()).await?;
+            resolved.insert(id, element.element_id());
+        }
+        self.replace_element_ids(&resolved);
+        Ok(self)
+    }
+
+    /// Replace the element ids that are keys of the specified map with their values.
+    fn replace_element_ids(&mut self, resolved: &HashMap<ElementId, ElementId>) {
+        for id in self.element_ids_mut() {
+            if let Some(new_id) = resolved.get(id) {
+                *id = new_id.clone();
+            }
+        }
+    }
+
+    fn element_ids_mut(&mut self) -> impl Iterator<Item = &mut ElementId> {
+        self.pointer_actions
+            .element_ids_mut()
+            .chain(self.wheel_actions.element_ids_mut())
+            .chain(self.extra_pointers.iter_mut().flat_map(|x| x.element_ids_mut()))
+            .chain(self.scroll_targets.iter_mut())
+            .chain(self.focus_targets.iter_mut().map(|(_, id)| id))
+    }
 }
 
+/// The id of an additional pointer source in an [`ActionChain`], returned by
+/// [`ActionChain::add_pointer_source()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointerSourceId(usize);
+
 impl ActionChain {
     /// Create a new ActionChain struct.
     ///
@@ -43,6 +257,12 @@ impl ActionChain {
                 PointerActionType::Mouse,
                 None,
             ),
+            wheel_actions: ActionSource::<WheelAction>::new("wheel", None),
+            extra_pointers: Vec::new(),
+            scroll_targets: Vec::new(),
+            focus_targets: Vec::new(),
+            paste_targets: Vec::new(),
+            pointer_position: None,
         }
     }
 
@@ -67,6 +287,67 @@ impl ActionChain {
                 PointerActionType::Mouse,
                 pointer_delay,
             ),
+            wheel_actions: ActionSource::<WheelAction>::new("wheel", None),
+            extra_pointers: Vec::new(),
+            scroll_targets: Vec::new(),
+            focus_targets: Vec::new(),
+            paste_targets: Vec::new(),
+            pointer_position: None,
+        }
+    }
+
+    /// Create a new ActionChain struct with the specified pointer type, such as a pen.
+    ///
+    /// See [WebDriver::action_chain_with_pointer_type()](../struct.WebDriver.html#method.action_chain_with_pointer_type)
+    /// for more details.
+    pub fn new_with_pointer_type(
+        handle: Arc<SessionHandle>,
+        pointer_type: PointerActionType,
+    ) -> Self {
+        ActionChain {
+            handle,
+            key_actions: ActionSource::<KeyAction>::new("key", None),
+            pointer_actions: ActionSource::<PointerAction>::new("pointer", pointer_type, None),
+            wheel_actions: ActionSource::<WheelAction>::new("wheel", None),
+            extra_pointers: Vec::new(),
+            scroll_targets: Vec::new(),
+            focus_targets: Vec::new(),
+            paste_targets: Vec::new(),
+            pointer_position: None,
+        }
+    }
+
+    /// Create a new ActionChain struct with the specified ids for its key and pointer input
+    /// sources, instead of `key` and `pointer`.
+    ///
+    /// The driver keeps the state of each input source between calls to `perform()`,
+    /// by id. Keys and buttons that a chain leaves pressed stay pressed, and the pointer
+    /// stays where it was moved, so a later chain that uses the same ids continues from
+    /// that state, even if it was created separately. Chains with different ids use
+    /// separate virtual devices, each starting with nothing pressed. An id can only be
+    /// used for one type of input source in a session, and
+    /// [`ActionChain::reset_actions()`] releases everything for all ids.
+    ///
+    /// The ids must be different from each other, and from `wheel` and the names of any
+    /// additional pointer sources.
+    ///
+    /// See [WebDriver::action_chain_named()](../struct.WebDriver.html#method.action_chain_named)
+    /// for more details.
+    pub fn new_named(handle: Arc<SessionHandle>, key_id: &str, pointer_id: &str) -> Self {
+        ActionChain {
+            handle,
+            key_actions: ActionSource::<KeyAction>::new(key_id, None),
+            pointer_actions: ActionSource::<PointerAction>::new(
+                pointer_id,
+                PointerActionType::Mouse,
+                None,
+            ),
+            wheel_actions: ActionSource::<WheelAction>::new("wheel", None),
+            extra_pointers: Vec::new(),
+            scroll_targets: Vec::new(),
+            focus_targets: Vec::new(),
+            paste_targets: Vec::new(),
+            pointer_position: None,
         }
     }
 
@@ -103,13 +384,28 @@ impl ActionChain {
 
     /// Perform the action sequence. No actions are actually performed until
     /// this method is called.
+    ///
+    /// If the driver rejects wheel input and the only wheel actions in the chain are
+    /// from [`ActionChain::scroll_to_element()`], the chain is performed in parts split
+    /// at each of those scrolls, and each element is scrolled into view with script in
+    /// between. Any other wheel action, such as [`ActionChain::scroll_by()`], cannot be
+    /// replaced, so the error is returned instead.
+    ///
+    /// If the chain focuses elements with [`ActionChain::focus_element_and_send_keys()`],
+    /// it is also performed in parts, focusing each element with script before the
+    /// actions added after it. Text added with [`ActionChain::paste_text()`] is written
+    /// to the clipboard in the same way, just before the keys that paste it.
     pub async fn perform(&self) -> WebDriverResult<()> {
-        let actions = Actions::from(serde_json::json!([self.key_actions, self.pointer_actions]));
-        self.handle.cmd(Command::PerformActions(actions)).await?;
+        self.perform_with_fallback().await?;
         Ok(())
     }
 
-    /// Click and release the left mouse button.
+    /// Perform the action sequence as [`ActionChain::perform()`] does, and return how
+    /// long it took along with the number of actions and the size of the payload sent.
+    ///
+    /// This is useful for diagnosing slow tests. If the driver rejects wheel input, the
+    /// details are for the actions that were performed without the scrolls, and the
+    /// payload size is the total for all parts of the chain.
     ///
     /// # Example:
     /// ```no_run
@@ -122,23 +418,164 @@ impl ActionChain {
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
     /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain().move_to_element_center(&elem).click().perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// let telemetry = driver.action_chain().click_element(&elem).perform_timed().await?;
+    /// println!("{} ticks in {:?}", telemetry.ticks, telemetry.duration);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn click(mut self) -> Self {
-        self.pointer_actions.click();
-        // Click = 2 actions (PointerDown + PointerUp).
-        self.key_actions.pause();
-        self.key_actions.pause();
-        self
+    pub async fn perform_timed(&self) -> WebDriverResult<ActionTelemetry> {
+        let start = Instant::now();
+        let (with_wheel, payload_size) = self.perform_with_fallback().await?;
+        let duration = start.elapsed();
+        let mut source_actions = vec![
+            (self.key_actions.id().to_string(), self.key_actions.len()),
+            (self.pointer_actions.id().to_string(), self.pointer_actions.len()),
+        ];
+        if with_wheel && self.has_scrolls() {
+            source_actions.push((self.wheel_actions.id().to_string(), self.wheel_actions.len()));
+        }
+        source_actions.extend(self.extra_pointers.iter().map(|x| (x.id().to_string(), x.len())));
+        Ok(ActionTelemetry {
+            duration,
+            ticks: self.ticks(),
+            source_actions,
+            payload_size,
+        })
     }
 
-    /// Click on the specified element using the left mouse button and release.
+    /// Perform the actions, falling back to scrolling with script if wheel input is
+    /// rejected. Returns whether the wheel actions were sent, and the payload size.
+    async fn perform_with_fallback(&self) -> WebDriverResult<(bool, usize)> {
+        let mut with_wheel = true;
+        let mut payload_size = 0;
+        for (element, ticks) in self.focus_segments() {
+            if let Some(element) = element {
+                element.focus().await?;
+            }
+            for (ticks, text) in self.paste_segments(ticks) {
+                let (sent_wheel, size) = match text {
+                    Some(text) => self.perform_paste(text, ticks).await?,
+                    None if ticks.is_empty() => continue,
+                    None => self.perform_ticks_with_fallback(ticks).await?,
+                };
+                with_wheel &= sent_wheel;
+                payload_size += size;
+            }
+        }
+        Ok((with_wheel, payload_size))
+    }
+
+    /// Write the specified text to the clipboard and perform the specified ticks, which
+    /// paste it. If the clipboard cannot be written, the text is typed instead.
+    async fn perform_paste(
+        &self,
+        text: &str,
+        ticks: Range<usize>,
+    ) -> WebDriverResult<(bool, usize)> {
+        let ret = self.handle.execute(WRITE_CLIPBOARD, vec![serde_json::json!(text)]).await?;
+        let Some(error) = ret.convert::<Option<String>>()? else {
+            return self.perform_ticks_with_fallback(ticks).await;
+        };
+        tracing::debug!(%error, "cannot write to the clipboard, typing the text instead");
+        let typing = ActionChain::new_named(
+            self.handle.clone(),
+            self.key_actions.id(),
+            self.pointer_actions.id(),
+        )
+        .send_keys(text);
+        let payload_size = typing.perform_actions(false, 0..typing.ticks()).await?;
+        Ok((true, payload_size))
+    }
+
+    /// Perform the actions in the specified ticks, falling back to scrolling with script
+    /// if wheel input is rejected.
+    async fn perform_ticks_with_fallback(
+        &self,
+        ticks: Range<usize>,
+    ) -> WebDriverResult<(bool, usize)> {
+        let e = match self.perform_actions(true, ticks.clone()).await {
+            Ok(payload_size) => return Ok((true, payload_size)),
+            Err(e) => e,
+        };
+        let Some(segments) = self.script_scroll_segments(ticks).filter(|_| is_wheel_rejected(&e))
+        else {
+            return Err(e);
+        };
+        tracing::debug!(error = %e, "wheel actions rejected, scrolling with script instead");
+        let mut payload_size = 0;
+        for (ticks, element) in segments {
+            if !ticks.is_empty() {
+                payload_size += self.perform_actions(false, ticks).await?;
+            }
+            if let Some(element) = element {
+                element.scroll_into_view().await?;
+            }
+        }
+        Ok((false, payload_size))
+    }
+
+    /// The ticks to perform between the elements focused by
+    /// [`ActionChain::focus_element_and_send_keys()`], each after the element to focus
+    /// first, if any.
+    fn focus_segments(&self) -> Vec<(Option<&WebElement>, Range<usize>)> {
+        let mut start = 0;
+        let mut element = None;
+        let mut segments = Vec::new();
+        for (tick, target) in &self.focus_targets {
+            segments.push((element, start..*tick));
+            (start, element) = (*tick, Some(target));
+        }
+        segments.push((element, start..self.ticks()));
+        segments
+    }
+
+    /// The ticks to perform within the specified ticks, split so that the key chords added
+    /// by [`ActionChain::paste_text()`] are separate, each with the text that it pastes.
+    fn paste_segments(&self, ticks: Range<usize>) -> Vec<(Range<usize>, Option<&str>)> {
+        let mut start = ticks.start;
+        let mut segments = Vec::new();
+        for (chord, text) in &self.paste_targets {
+            if ticks.contains(&chord.start) {
+                segments.push((start..chord.start, None));
+                segments.push((chord.clone(), Some(text.as_str())));
+                start = chord.end;
+            }
+        }
+        segments.push((start..ticks.end, None));
+        segments
+    }
+
+    /// The ticks to perform between the scrolls added by
+    /// [`ActionChain::scroll_to_element()`] within the specified ticks, each followed by
+    /// the element to scroll into view, or `None` if the chain has no such scrolls or has
+    /// other wheel actions.
+    fn script_scroll_segments(
+        &self,
+        ticks: Range<usize>,
+    ) -> Option<Vec<(Range<usize>, Option<&WebElement>)>> {
+        let scrolls = self.wheel_actions.scroll_indices();
+        if self.scroll_targets.is_empty() || scrolls.len() != self.scroll_targets.len() {
+            return None;
+        }
+        let mut start = ticks.start;
+        let mut segments = Vec::new();
+        for (tick, element) in scrolls.into_iter().zip(&self.scroll_targets) {
+            if ticks.contains(&tick) {
+                segments.push((start..tick, Some(element)));
+                start = tick + 1;
+            }
+        }
+        segments.push((start..ticks.end, None));
+        Some(segments)
+    }
+
+    /// Perform the action sequence, then remove all actions from the chain so that it can
+    /// be reused. If performing fails, the actions are kept.
+    ///
+    /// Any additional pointer sources are kept, without their actions, so their ids
+    /// remain valid.
     ///
     /// # Example:
     /// ```no_run
@@ -150,20 +587,49 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain().click_element(&elem).perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// let mut chain = driver.action_chain();
+    /// for page in 1..=3 {
+    ///     let next = driver.find(By::LinkText(&format!("Page {page}"))).await?;
+    ///     chain = chain.click_element(&next);
+    ///     chain.perform_and_clear().await?;
+    /// }
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn click_element(self, element: &WebElement) -> Self {
-        self.move_to_element_center(element).click()
+    pub async fn perform_and_clear(&mut self) -> WebDriverResult<()> {
+        self.perform().await?;
+        self.key_actions.clear();
+        self.pointer_actions.clear();
+        self.wheel_actions.clear();
+        for source in &mut self.extra_pointers {
+            source.clear();
+        }
+        self.scroll_targets.clear();
+        self.focus_targets.clear();
+        self.paste_targets.clear();
+        Ok(())
     }
 
-    /// Click the left mouse button and hold it down.
+    /// Send the actions in the specified ticks to the driver, returning the size of the
+    /// payload in bytes.
+    async fn perform_actions(
+        &self,
+        with_wheel: bool,
+        ticks: Range<usize>,
+    ) -> WebDriverResult<usize> {
+        let sources = self.sources(with_wheel, ticks);
+        let payload_size = serde_json::json!({ "actions": &sources }).to_string().len();
+        self.handle.cmd(Command::PerformActions(Actions::from(sources))).await?;
+        Ok(payload_size)
+    }
+
+    /// Get the action sequence as the JSON payload that `perform()` would send, without
+    /// performing it.
+    ///
+    /// This is useful for debugging and for snapshot tests of action sequences. The chain
+    /// can also be printed with `{}` as pretty JSON.
     ///
     /// # Example:
     /// ```no_run
@@ -174,55 +640,146 @@ impl ActionChain {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
-    /// #         driver.get("http://webappdemo").await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "None");
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain().move_to_element_center(&elem).click_and_hold().perform().await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 down");
-    /// #         driver.action_chain().release().perform().await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// let chain = driver.action_chain().move_to(10, 20).click();
+    /// let payload = chain.to_json();
+    /// assert_eq!(payload["actions"][1]["actions"][0]["type"], "pointerMove");
+    /// println!("{chain}");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn click_and_hold(mut self) -> Self {
-        self.pointer_actions.click_and_hold();
-        self.key_actions.pause();
-        self
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({ "actions": self.sources(true, 0..self.ticks()) })
     }
 
-    /// Click on the specified element using the left mouse button and
-    /// hold the button down.
+    /// Whether the chain has any scroll actions. If not, the wheel source is not sent, so
+    /// that drivers without wheel input support can still perform the chain.
+    fn has_scrolls(&self) -> bool {
+        !self.wheel_actions.scroll_indices().is_empty()
+    }
+
+    /// The input sources in the order they are sent: key, pointer, wheel if included and
+    /// the chain has any scrolls, then any additional pointers, with only the actions in the
+    /// specified ticks.
+    fn sources(&self, with_wheel: bool, ticks: Range<usize>) -> serde_json::Value {
+        let mut chain = self.clone();
+        chain.align();
+        let mut sources = vec![
+            serde_json::json!(chain.key_actions.slice(ticks.clone())),
+            serde_json::json!(chain.pointer_actions.slice(ticks.clone())),
+        ];
+        if with_wheel && self.has_scrolls() {
+            sources.push(serde_json::json!(chain.wheel_actions.slice(ticks.clone())));
+        }
+        let extra = chain.extra_pointers.iter().map(|source| source.slice(ticks.clone()));
+        sources.extend(extra.map(|source| serde_json::json!(source)));
+        serde_json::Value::Array(sources)
+    }
+
+    /// The number of actions in each input source, in the order they are sent, before the
+    /// sources are padded.
+    ///
+    /// This is intended for tests and debugging. Each input source only holds the actions
+    /// added to it, and every source is padded with pauses to the length of the longest
+    /// before the chain is performed.
+    #[doc(hidden)]
+    pub fn validate(&self) -> Vec<(String, usize)> {
+        let mut lengths = vec![
+            (self.key_actions.id().to_string(), self.key_actions.len()),
+            (self.pointer_actions.id().to_string(), self.pointer_actions.len()),
+            (self.wheel_actions.id().to_string(), self.wheel_actions.len()),
+        ];
+        lengths.extend(self.extra_pointers.iter().map(|x| (x.id().to_string(), x.len())));
+        lengths
+    }
+
+    /// Append the actions of the specified chain to this chain, so that they are performed
+    /// after the actions of this chain.
+    ///
+    /// This allows gestures to be built in separate functions and combined. Additional
+    /// pointer sources are matched by name, and the sources are padded so that they stay
+    /// aligned. The pointer position used by [`ActionChain::move_to_smooth()`] is taken
+    /// from the other chain if it has any actions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chains were created for different sessions, or if a pointer source
+    /// has a different pointer type in each chain.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::action_chain::ActionChain;
     /// #
+    /// fn select_all(chain: ActionChain) -> ActionChain {
+    ///     chain.key_chord([Key::Control], 'a')
+    /// }
+    ///
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "None");
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain().click_and_hold_element(&elem).perform().await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 down");
-    /// #         driver.action_chain().release().perform().await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// let elem = driver.find(By::Id("input-result")).await?;
+    /// let copy = driver.action_chain().key_chord([Key::Control], 'c');
+    /// select_all(driver.action_chain().click_element(&elem)).then(copy).perform().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn click_and_hold_element(self, element: &WebElement) -> Self {
-        self.move_to_element_center(element).click_and_hold()
+    pub fn then(mut self, mut other: ActionChain) -> Self {
+        assert!(
+            self.handle.session_id() == other.handle.session_id(),
+            "cannot join action chains created for different sessions"
+        );
+        assert!(
+            self.pointer_actions.pointer_type() == other.pointer_actions.pointer_type(),
+            "cannot join action chains with different pointer types"
+        );
+        self.align();
+        other.align();
+        let tick = self.ticks();
+        let other_ticks = other.ticks();
+        self.key_actions.append(other.key_actions);
+        self.pointer_actions.append(other.pointer_actions);
+        self.wheel_actions.append(other.wheel_actions);
+        for source in other.extra_pointers {
+            match self.extra_pointers.iter_mut().find(|x| x.id() == source.id()) {
+                Some(pointer) => {
+                    assert!(
+                        pointer.pointer_type() == source.pointer_type(),
+                        "cannot join action chains with different types for pointer '{}'",
+                        source.id()
+                    );
+                    pointer.pad_to(tick);
+                    pointer.append(source);
+                }
+                None => {
+                    let mut pointer = source.clone();
+                    pointer.clear();
+                    pointer.pad_to(tick);
+                    pointer.append(source);
+                    self.extra_pointers.push(pointer);
+                }
+            }
+        }
+        self.scroll_targets.extend(other.scroll_targets);
+        let focus_targets = other.focus_targets.into_iter().map(|(x, element)| (x + tick, element));
+        self.focus_targets.extend(focus_targets);
+        let paste_targets =
+            other.paste_targets.into_iter().map(|(x, text)| (x.start + tick..x.end + tick, text));
+        self.paste_targets.extend(paste_targets);
+        if other_ticks > 0 {
+            self.pointer_position = other.pointer_position;
+        }
+        self
     }
 
-    /// Click and release the right mouse button.
+    /// Record the actions in this chain, so that they can be saved and replayed later with
+    /// [`ActionChain::from_recording()`].
     ///
     /// # Example:
     /// ```no_run
@@ -234,126 +791,157 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain().move_to_element_center(&elem).context_click().perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 right-clicked");
+    /// let chain = driver.action_chain().move_to(100, 100).click();
+    /// let json = serde_json::to_string(&chain.to_recording())?;
+    /// std::fs::write("gesture.json", json)?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn context_click(mut self) -> Self {
-        self.pointer_actions.context_click();
-        // Click = 2 actions (PointerDown + PointerUp).
-        self.key_actions.pause();
-        self.key_actions.pause();
-        self
+    pub fn to_recording(&self) -> ActionRecording {
+        let mut chain = self.clone();
+        chain.align();
+        ActionRecording {
+            key_actions: chain.key_actions,
+            pointer_actions: chain.pointer_actions,
+            wheel_actions: chain.wheel_actions,
+            extra_pointers: chain.extra_pointers,
+            scroll_targets: self.scroll_targets.iter().map(|x| x.element_id()).collect(),
+            focus_targets: self.focus_targets.iter().map(|(x, y)| (*x, y.element_id())).collect(),
+            paste_targets: self.paste_targets.clone(),
+            pointer_position: self.pointer_position,
+        }
     }
 
-    /// Click on the specified element using the right mouse button and release.
+    /// Create an ActionChain with the actions from the specified recording, for the
+    /// specified session.
+    ///
+    /// More actions can be added to the chain as usual, but without the key and pointer
+    /// delays of the original chain. See [`ActionRecording`] for how to replay actions
+    /// relative to elements.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::action_chain::ActionChain;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain().context_click_element(&elem).perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 right-clicked");
+    /// let recording = serde_json::from_str(&std::fs::read_to_string("gesture.json")?)?;
+    /// ActionChain::from_recording(driver.handle.clone(), recording).perform().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn context_click_element(self, element: &WebElement) -> Self {
-        self.move_to_element_center(element).context_click()
+    pub fn from_recording(handle: Arc<SessionHandle>, recording: ActionRecording) -> Self {
+        let scroll_targets = recording
+            .scroll_targets
+            .into_iter()
+            .map(|id| WebElement::new(id, handle.clone()))
+            .collect();
+        let focus_targets = recording
+            .focus_targets
+            .into_iter()
+            .map(|(tick, id)| (tick, WebElement::new(id, handle.clone())))
+            .collect();
+        ActionChain {
+            handle,
+            key_actions: recording.key_actions,
+            pointer_actions: recording.pointer_actions,
+            wheel_actions: recording.wheel_actions,
+            extra_pointers: recording.extra_pointers,
+            scroll_targets,
+            focus_targets,
+            paste_targets: recording.paste_targets,
+            pointer_position: recording.pointer_position,
+        }
     }
 
-    /// Double-click the left mouse button.
+    /// Wait for the specified duration before performing the next action.
+    ///
+    /// The pause is added to every input source, so the sequences stay aligned and keys
+    /// or buttons held down remain held during the pause. A zero duration still adds a
+    /// tick to the sequence.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
+    /// // Long-press the button.
     /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain().move_to_element_center(&elem).double_click().perform().await?;
+    /// driver.action_chain()
+    ///     .click_and_hold_element(&elem)
+    ///     .pause(Duration::from_millis(500))
+    ///     .release()
+    ///     .perform().await?;
     /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 double-clicked");
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn double_click(mut self) -> Self {
-        self.pointer_actions.double_click();
-        // Each click = 2 actions (PointerDown + PointerUp).
-        for _ in 0..4 {
-            self.key_actions.pause();
-        }
+    pub fn pause(mut self, duration: Duration) -> Self {
+        let duration_ms = u64::try_from(duration.as_millis()).ok().unwrap_or(u64::MAX);
+        self.align();
+        self.key_actions.pause_for(duration_ms);
+        self.pointer_actions.pause_for(duration_ms);
+        self.wheel_actions.pause_for(duration_ms);
         self
     }
 
-    /// Double-click on the specified element.
+    /// Wait for the specified duration before the next pointer action, instead of the
+    /// pointer delay of the chain.
+    ///
+    /// This allows a single slow step in a fast chain, for example to hover over a drop
+    /// zone for a while before releasing. The wait is added to every input source like
+    /// [`ActionChain::pause()`], and if the next pointer action is a move, the move is
+    /// immediate rather than taking the pointer delay.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain().double_click_element(&elem).perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 double-clicked");
+    /// let source = driver.find(By::Id("draggable")).await?;
+    /// let target = driver.find(By::Id("droppable")).await?;
+    /// driver.action_chain_with_delay(None, Some(Duration::ZERO))
+    ///     .click_and_hold_element(&source)
+    ///     .move_to_element_center(&target)
+    ///     .with_next_delay(Duration::from_millis(300))
+    ///     .release()
+    ///     .perform().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn double_click_element(self, element: &WebElement) -> Self {
-        self.move_to_element_center(element).double_click()
-    }
-
-    /// Drag the mouse cursor from the center of the source element to the
-    /// center of the target element.
-    pub fn drag_and_drop_element(self, source: &WebElement, target: &WebElement) -> Self {
-        self.click_and_hold_element(source).release_on_element(target)
-    }
-
-    /// Drag the mouse cursor by the specified X and Y offsets.
-    pub fn drag_and_drop_by_offset(self, x_offset: i64, y_offset: i64) -> Self {
-        self.click_and_hold().move_by_offset(x_offset, y_offset)
-    }
-
-    /// Drag the mouse cursor by the specified X and Y offsets, starting
-    /// from the center of the specified element.
-    pub fn drag_and_drop_element_by_offset(
-        self,
-        element: &WebElement,
-        x_offset: i64,
-        y_offset: i64,
-    ) -> Self {
-        self.click_and_hold_element(element).move_by_offset(x_offset, y_offset)
+    pub fn with_next_delay(self, delay: Duration) -> Self {
+        let mut chain = self.pause(delay);
+        chain.pointer_actions.set_next_duration(0);
+        chain
     }
 
-    /// Press the specified key down.
+    /// Click and release the left mouse button.
     ///
     /// # Example:
     /// ```no_run
@@ -365,26 +953,21 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
-    /// let elem = driver.find(By::Name("input1")).await?;
-    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
-    /// driver.action_chain().click_element(&elem).key_down('a').perform().await?;
-    /// #         assert_eq!(elem.value().await?, Some("a".to_string()));
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().move_to_element_center(&elem).click().perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn key_down<T>(mut self, value: T) -> Self
-    where
-        T: Into<char>,
-    {
-        self.key_actions.key_down(value.into());
-        self.pointer_actions.pause();
+    pub fn click(mut self) -> Self {
+        self.pointer().click();
         self
     }
 
-    /// Click the specified element and then press the specified key down.
+    /// Click on the specified element using the left mouse button and release.
     ///
     /// # Example:
     /// ```no_run
@@ -396,24 +979,1636 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
-    /// let elem = driver.find(By::Name("input1")).await?;
-    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
-    /// driver.action_chain().key_down_on_element(&elem, 'a').perform().await?;
-    /// #         assert_eq!(elem.value().await?, Some("a".to_string()));
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().click_element(&elem).perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn key_down_on_element<T>(self, element: &WebElement, value: T) -> Self
-    where
-        T: Into<char>,
-    {
-        self.click_element(element).key_down(value)
+    pub fn click_element(self, element: &WebElement) -> Self {
+        self.move_to_element_center(element).click()
     }
 
-    /// Release the specified key. This usually follows a `key_down()` action.
+    /// Click the left mouse button at the specified offsets from the center of the specified element.
+    ///
+    /// The offsets are relative to the center of the element, as for
+    /// [`ActionChain::move_to_element_with_offset()`], not to its top left corner. Negative
+    /// offsets are above and to the left of the center.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let sheet = driver.find(By::Id("sheet")).await?;
+    /// driver.action_chain().click_element_with_offset(&sheet, -100, -50).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn click_element_with_offset(
+        self,
+        element: &WebElement,
+        x_offset: i64,
+        y_offset: i64,
+    ) -> Self {
+        self.move_to_element_with_offset(element, x_offset, y_offset).click()
+    }
+
+    /// Click the left mouse button while holding the specified modifier key down, for
+    /// example to shift-click or ctrl-click.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// // Select two options in a multiple select.
+    /// let options = driver.find_all(By::Css("select[multiple] option")).await?;
+    /// driver.action_chain()
+    ///     .click_element(&options[0])
+    ///     .move_to_element_center(&options[2])
+    ///     .click_with_modifier(Key::Control)
+    ///     .perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn click_with_modifier<T>(self, modifier: T) -> Self
+    where
+        T: Into<char>,
+    {
+        let modifier = modifier.into();
+        self.key_down(modifier).click().key_up(modifier)
+    }
+
+    /// Click the left mouse button and hold it down.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "None");
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().move_to_element_center(&elem).click_and_hold().perform().await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 down");
+    /// #         driver.action_chain().release().perform().await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn click_and_hold(mut self) -> Self {
+        self.pointer().click_and_hold();
+        self
+    }
+
+    /// Press the left mouse button, hold it down for the specified duration, then
+    /// release it, for long-press gestures.
+    ///
+    /// The whole gesture is a single sequence, so the button is not released early
+    /// between `perform()` calls.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain()
+    ///     .move_to_element_center(&elem)
+    ///     .click_and_hold_for(Duration::from_secs(1))
+    ///     .perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn click_and_hold_for(self, duration: Duration) -> Self {
+        self.click_and_hold().pause(duration).release()
+    }
+
+    /// Long-press the center of the specified element for the specified duration.
+    ///
+    /// See [`ActionChain::click_and_hold_for()`] for details.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().long_press_element(&elem, Duration::from_secs(1)).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn long_press_element(self, element: &WebElement, duration: Duration) -> Self {
+        self.move_to_element_center(element).click_and_hold_for(duration)
+    }
+
+    /// Click on the specified element using the left mouse button and
+    /// hold the button down.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "None");
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().click_and_hold_element(&elem).perform().await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 down");
+    /// #         driver.action_chain().release().perform().await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn click_and_hold_element(self, element: &WebElement) -> Self {
+        self.move_to_element_center(element).click_and_hold()
+    }
+
+    /// Click and release the right mouse button.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().move_to_element_center(&elem).context_click().perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 right-clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn context_click(mut self) -> Self {
+        self.pointer().context_click();
+        self
+    }
+
+    /// Click on the specified element using the right mouse button and release.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().context_click_element(&elem).perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 right-clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn context_click_element(self, element: &WebElement) -> Self {
+        self.move_to_element_center(element).context_click()
+    }
+
+    /// Click the right mouse button at the specified offsets from the center of the specified element.
+    ///
+    /// The offsets are relative to the center of the element, as for
+    /// [`ActionChain::move_to_element_with_offset()`], not to its top left corner. Negative
+    /// offsets are above and to the left of the center.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let sheet = driver.find(By::Id("sheet")).await?;
+    /// driver.action_chain().context_click_element_with_offset(&sheet, -100, -50).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn context_click_element_with_offset(
+        self,
+        element: &WebElement,
+        x_offset: i64,
+        y_offset: i64,
+    ) -> Self {
+        self.move_to_element_with_offset(element, x_offset, y_offset).context_click()
+    }
+
+    /// Click and release the middle mouse button.
+    ///
+    /// In most browsers, middle-clicking a link opens it in a new background tab.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let link = driver.find(By::LinkText("Other page")).await?;
+    /// driver.action_chain().move_to_element_center(&link).middle_click().perform().await?;
+    /// #         assert_eq!(driver.windows().await?.len(), 2);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn middle_click(self) -> Self {
+        self.click_button(MouseButton::Middle)
+    }
+
+    /// Click on the specified element using the middle mouse button and release.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let link = driver.find(By::LinkText("Other page")).await?;
+    /// driver.action_chain().middle_click_element(&link).perform().await?;
+    /// #         assert_eq!(driver.windows().await?.len(), 2);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn middle_click_element(self, element: &WebElement) -> Self {
+        self.move_to_element_center(element).middle_click()
+    }
+
+    /// Click and release the specified mouse button, such as the back button.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::common::action::MouseButton;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// driver.action_chain().click_button(MouseButton::Back).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn click_button(mut self, button: MouseButton) -> Self {
+        self.pointer().click_button(button);
+        self
+    }
+
+    /// Double-click the left mouse button.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().move_to_element_center(&elem).double_click().perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 double-clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn double_click(mut self) -> Self {
+        self.pointer().double_click();
+        self
+    }
+
+    /// Double-click on the specified element.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().double_click_element(&elem).perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 double-clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn double_click_element(self, element: &WebElement) -> Self {
+        self.move_to_element_center(element).double_click()
+    }
+
+    /// Double-click at the specified offsets from the center of the specified element.
+    ///
+    /// The offsets are relative to the center of the element, as for
+    /// [`ActionChain::move_to_element_with_offset()`], not to its top left corner. Negative
+    /// offsets are above and to the left of the center. This is useful for
+    /// targeting a region within a large element, such as a cell in a canvas.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let sheet = driver.find(By::Id("sheet")).await?;
+    /// driver.action_chain().double_click_element_with_offset(&sheet, -100, -50).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn double_click_element_with_offset(
+        self,
+        element: &WebElement,
+        x_offset: i64,
+        y_offset: i64,
+    ) -> Self {
+        self.move_to_element_with_offset(element, x_offset, y_offset).double_click()
+    }
+
+    /// Move the mouse cursor to the specified X and Y coordinates and click the left mouse
+    /// button.
+    ///
+    /// The coordinates are relative to the top left corner of the viewport, as for all
+    /// pointer moves in the W3C WebDriver spec, not to the page.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// driver.action_chain().click_at(120, 80).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn click_at(self, x: i64, y: i64) -> Self {
+        self.move_to(x, y).click()
+    }
+
+    /// Move the mouse cursor to the specified X and Y coordinates and click the right mouse
+    /// button.
+    ///
+    /// The coordinates are relative to the top left corner of the viewport, as for all
+    /// pointer moves in the W3C WebDriver spec, not to the page.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// driver.action_chain().context_click_at(120, 80).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn context_click_at(self, x: i64, y: i64) -> Self {
+        self.move_to(x, y).context_click()
+    }
+
+    /// Move the mouse cursor to the specified X and Y coordinates and double-click the left
+    /// mouse button.
+    ///
+    /// The coordinates are relative to the top left corner of the viewport, as for all
+    /// pointer moves in the W3C WebDriver spec, not to the page.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// driver.action_chain().double_click_at(120, 80).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn double_click_at(self, x: i64, y: i64) -> Self {
+        self.move_to(x, y).double_click()
+    }
+
+    /// Triple-click the left mouse button, which selects the paragraph under the mouse
+    /// cursor in most browsers.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Tag("p")).await?;
+    /// driver.action_chain().move_to_element_center(&elem).triple_click().perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn triple_click(self) -> Self {
+        self.click_n_times(3)
+    }
+
+    /// Triple-click on the specified element.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Tag("p")).await?;
+    /// driver.action_chain().triple_click_element(&elem).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn triple_click_element(self, element: &WebElement) -> Self {
+        self.move_to_element_center(element).triple_click()
+    }
+
+    /// Click the left mouse button the specified number of times in quick succession.
+    ///
+    /// The clicks have no moves or pauses between them, so that the browser counts them
+    /// as a single multi-click, even if the chain was created with a pointer delay.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain().move_to_element_center(&elem).click_n_times(4).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn click_n_times(mut self, n: u32) -> Self {
+        self.pointer().click_n_times(n);
+        self
+    }
+
+    /// Drag the mouse cursor from the center of the source element to the
+    /// center of the target element.
+    pub fn drag_and_drop_element(self, source: &WebElement, target: &WebElement) -> Self {
+        self.click_and_hold_element(source).release_on_element(target)
+    }
+
+    /// Drag the source element to the target element by dispatching HTML5 drag and drop
+    /// events, such as `dragstart` and `drop`.
+    ///
+    /// Drivers do not fire these events for pointer actions, so
+    /// [`ActionChain::drag_and_drop_element()`] does nothing on pages using the HTML5 drag
+    /// and drop API. Unlike the other methods, this runs immediately rather than in
+    /// `perform()`.
+    ///
+    /// Returns an `InvalidArgument` error if the source element is not draggable, or if the
+    /// target element does not accept the drop.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let card = driver.find(By::Id("card")).await?;
+    /// let column = driver.find(By::Id("done")).await?;
+    /// driver.action_chain().drag_and_drop_element_html5(&card, &column).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn drag_and_drop_element_html5(
+        &self,
+        source: &WebElement,
+        target: &WebElement,
+    ) -> WebDriverResult<()> {
+        let ret = self
+            .handle
+            .execute(HTML5_DRAG_AND_DROP, vec![source.to_json()?, target.to_json()?])
+            .await?;
+        match ret.convert::<Option<String>>()? {
+            Some(message) => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                format!("HTML5 drag and drop failed: {message}"),
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Drag the mouse cursor by the specified X and Y offsets.
+    pub fn drag_and_drop_by_offset(self, x_offset: i64, y_offset: i64) -> Self {
+        self.click_and_hold().move_by_offset(x_offset, y_offset)
+    }
+
+    /// Drag the mouse cursor by the specified X and Y offsets, starting
+    /// from the center of the specified element.
+    pub fn drag_and_drop_element_by_offset(
+        self,
+        element: &WebElement,
+        x_offset: i64,
+        y_offset: i64,
+    ) -> Self {
+        self.click_and_hold_element(element).move_by_offset(x_offset, y_offset)
+    }
+
+    /// Press the specified key down.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
+    /// driver.action_chain().click_element(&elem).key_down('a').perform().await?;
+    /// #         assert_eq!(elem.value().await?, Some("a".to_string()));
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn key_down<T>(mut self, value: T) -> Self
+    where
+        T: Into<char>,
+    {
+        self.keys().key_down(value.into());
+        self
+    }
+
+    /// Press the specified modifier keys, tap the specified key, then release the
+    /// modifiers in reverse order.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// elem.send_keys("selenium").await?;
+    /// // Select all the text and replace it.
+    /// driver.action_chain()
+    ///     .key_chord([Key::Control], 'a')
+    ///     .send_keys("b")
+    ///     .perform().await?;
+    /// assert_eq!(elem.value().await?, Some("b".to_string()));
+    /// // Shift is released before Control.
+    /// driver.action_chain().key_chord([Key::Control, Key::Shift], 't').perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn key_chord<I, T>(mut self, modifiers: I, key: T) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<char>,
+        T: Into<char>,
+    {
+        let modifiers: Vec<char> = modifiers.into_iter().map(Into::into).collect();
+        for &modifier in &modifiers {
+            self = self.key_down(modifier);
+        }
+        let key = key.into();
+        self = self.key_down(key).key_up(key);
+        for &modifier in modifiers.iter().rev() {
+            self = self.key_up(modifier);
+        }
+        self
+    }
+
+    /// Click the specified element and then press the specified key down.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
+    /// driver.action_chain().key_down_on_element(&elem, 'a').perform().await?;
+    /// #         assert_eq!(elem.value().await?, Some("a".to_string()));
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn key_down_on_element<T>(self, element: &WebElement, value: T) -> Self
+    where
+        T: Into<char>,
+    {
+        self.click_element(element).key_down(value)
+    }
+
+    /// Release the specified key. This usually follows a `key_down()` action.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
+    /// elem.send_keys("selenium").await?;
+    /// assert_eq!(elem.value().await?, Some("selenium".to_string()));
+    /// driver.action_chain()
+    ///     .key_down_on_element(&elem, Key::Control).key_down('a')
+    ///     .key_up(Key::Control).key_up('a')
+    ///     .key_down('b')
+    ///     .perform().await?;
+    /// assert_eq!(elem.value().await?, Some("b".to_string()));
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn key_up<T>(mut self, value: T) -> Self
+    where
+        T: Into<char>,
+    {
+        self.keys().key_up(value.into());
+        self
+    }
+
+    /// Press the specified key down and repeat it the specified number of times, as if
+    /// the key was held and auto-repeated, with the specified interval between repeats.
+    ///
+    /// WebDriver does not auto-repeat keys that are held down, so each repeat releases
+    /// and presses the key again. The key is left pressed, so release it with
+    /// [`ActionChain::key_up()`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let list = driver.find(By::Id("results")).await?;
+    /// driver
+    ///     .action_chain()
+    ///     .click_element(&list)
+    ///     .key_down_repeating(Key::Down, 20, Duration::from_millis(30))
+    ///     .key_up(Key::Down)
+    ///     .perform()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn key_down_repeating<T>(mut self, value: T, count: u32, interval: Duration) -> Self
+    where
+        T: Into<char>,
+    {
+        let interval_ms = u64::try_from(interval.as_millis()).ok().unwrap_or(u64::MAX);
+        let key = value.into();
+        self.keys().key_down(key);
+        for _ in 0..count {
+            self.keys().pause_for(interval_ms);
+            self.keys().key_up(key);
+            self.keys().key_down(key);
+        }
+        self
+    }
+
+    /// Click the specified element and release the specified key.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
+    /// elem.send_keys("selenium").await?;
+    /// assert_eq!(elem.value().await?, Some("selenium".to_string()));
+    /// driver.action_chain()
+    ///     .key_down_on_element(&elem, Key::Control).key_down('a')
+    ///     .key_up_on_element(&elem, 'a').key_up_on_element(&elem, Key::Control)
+    ///     .key_down('b')
+    ///     .perform().await?;
+    /// assert_eq!(elem.value().await?, Some("b".to_string()));
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn key_up_on_element<T>(self, element: &WebElement, value: T) -> Self
+    where
+        T: Into<char>,
+    {
+        self.click_element(element).key_up(value)
+    }
+
+    /// Press the pen down with the specified pressure, from 0.0 to 1.0.
+    ///
+    /// This requires an action chain with a pen pointer. See
+    /// [`ActionChain::move_to_with_pen_properties()`] for an example.
+    pub fn pen_down_with_pressure(mut self, pressure: f64) -> Self {
+        self.pointer().press_with_properties(PenProperties::with_pressure(pressure));
+        self
+    }
+
+    /// Move the pointer to the specified X and Y coordinates, with the specified pen
+    /// properties such as pressure and tilt.
+    ///
+    /// This requires an action chain with a pen pointer, created with
+    /// `action_chain_with_pointer_type(PointerActionType::Pen)`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::common::action::{PenProperties, PointerActionType};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// // Draw a line, pressing harder towards the end.
+    /// let pressed = |pressure| PenProperties {
+    ///     pressure: Some(pressure),
+    ///     tilt_x: Some(30),
+    ///     ..Default::default()
+    /// };
+    /// driver.action_chain_with_pointer_type(PointerActionType::Pen)
+    ///     .move_to(20, 20)
+    ///     .pen_down_with_pressure(0.2)
+    ///     .move_to_with_pen_properties(100, 100, pressed(0.5))
+    ///     .move_to_with_pen_properties(180, 180, pressed(0.9))
+    ///     .release()
+    ///     .perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn move_to_with_pen_properties(
+        mut self,
+        x: i64,
+        y: i64,
+        properties: PenProperties,
+    ) -> Self {
+        self.pointer().move_to_with_properties(x, y, properties);
+        self.pointer_position = Some((x, y));
+        self
+    }
+
+    /// Move the mouse cursor to the specified X and Y coordinates.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// let center = elem.rect().await?.icenter();
+    /// driver.action_chain()
+    ///     .move_to(center.0, center.1)
+    ///     .click()
+    ///     .perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn move_to(mut self, x: i64, y: i64) -> Self {
+        self.pointer().move_to(x, y);
+        self.pointer_position = Some((x, y));
+        self
+    }
+
+    /// Move the mouse cursor to the specified X and Y coordinates in the specified number
+    /// of steps, taking the specified duration in total.
+    ///
+    /// Some canvas apps and hover-intent menus ignore a single jump, and need the
+    /// intermediate moves. The steps start from the last position set by `move_to()`,
+    /// `move_to_smooth()` or `move_by_offset()` in this chain, or from the top left corner
+    /// of the viewport if the position is not known, for example after moving to an
+    /// element. With fewer than 2 steps, this is a single move.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// // Draw a line in 20 steps.
+    /// driver.action_chain()
+    ///     .move_to(10, 10)
+    ///     .click_and_hold()
+    ///     .move_to_smooth(200, 120, 20, Duration::from_millis(500))
+    ///     .release()
+    ///     .perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn move_to_smooth(mut self, x: i64, y: i64, steps: u32, duration: Duration) -> Self {
+        let start = self.pointer_position.unwrap_or((0, 0));
+        self.pointer().move_smooth(start, (x, y), steps, duration);
+        self.pointer_position = Some((x, y));
+        self
+    }
+
+    /// Move the mouse cursor to the center of the specified element in the specified
+    /// number of steps, taking the specified duration in total.
+    ///
+    /// The position of the element in the viewport is read when this is called, so this
+    /// is async and can fail. See [`ActionChain::move_to_smooth()`] for details.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let menu = driver.find(By::Id("menu")).await?;
+    /// driver.action_chain()
+    ///     .move_to(0, 0)
+    ///     .move_to_element_smooth(&menu, 10, Duration::from_millis(300)).await?
+    ///     .perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn move_to_element_smooth(
+        self,
+        element: &WebElement,
+        steps: u32,
+        duration: Duration,
+    ) -> WebDriverResult<Self> {
+        let ret = self.handle.execute(ELEMENT_CENTER, vec![element.to_json()?]).await?;
+        let (x, y): (i64, i64) = ret.convert()?;
+        Ok(self.move_to_smooth(x, y, steps, duration))
+    }
+
+    /// Move the mouse cursor by the specified X and Y offsets.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem1 = driver.find(By::Id("button1")).await?;
+    /// let elem2 = driver.find(By::Id("button2")).await?;
+    /// // We will calculate the distance between the two center points and then
+    /// // use action_chain() to move to the second button before clicking.
+    /// let offset = elem2.rect().await?.center().0 as i64 - elem1.rect().await?.center().0 as i64;
+    /// driver.action_chain()
+    ///     .move_to_element_center(&elem1)
+    ///     .move_by_offset(offset, 0)
+    ///     .click()
+    ///     .perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 2 clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn move_by_offset(mut self, x_offset: i64, y_offset: i64) -> Self {
+        self.pointer().move_by(x_offset, y_offset);
+        self.pointer_position = self.pointer_position.map(|(x, y)| (x + x_offset, y + y_offset));
+        self
+    }
+
+    /// Move the mouse cursor to the center of the specified element.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain()
+    ///     .move_to_element_center(&elem)
+    ///     .click()
+    ///     .perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn move_to_element_center(mut self, element: &WebElement) -> Self {
+        self.pointer().move_to_element_center(element.element_id.clone());
+        self.pointer_position = None;
+        self
+    }
+
+    /// Move the mouse cursor to the specified offsets relative to the specified
+    /// element's center position.
+    ///
+    /// # Example:
+    ///
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         driver.find(By::Id("button1")).await?.click().await?;
+    /// // Select the text in the source element and copy it to the clipboard.
+    /// let elem = driver.find(By::Id("button-result")).await?;
+    /// let width = elem.rect().await?.width;
+    /// driver.action_chain()
+    ///     .move_to_element_with_offset(&elem, (-width / 2.0) as i64, 0)
+    ///     .drag_and_drop_by_offset(width as i64, 0)
+    ///     .key_down(Key::Control)
+    ///     .key_down('c').key_up('c')
+    ///     .key_up(Key::Control)
+    ///     .perform().await?;
+    ///
+    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
+    /// // Now paste the text into the input field.
+    /// let elem_tgt = driver.find(By::Name("input1")).await?;
+    /// elem_tgt.send_keys(Key::Control + "v").await?;
+    /// #         assert_eq!(elem_tgt.value().await?, Some("Button 1 clicked".to_string()));
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn move_to_element_with_offset(
+        mut self,
+        element: &WebElement,
+        x_offset: i64,
+        y_offset: i64,
+    ) -> Self {
+        self.pointer().move_to_element(element.element_id.clone(), x_offset, y_offset);
+        self.pointer_position = None;
+        self
+    }
+
+    /// Release the left mouse button.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         let elem = driver.find(By::Id("button1")).await?;
+    /// #         driver.action_chain().click_and_hold_element(&elem).perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 down");
+    /// driver.action_chain().release().perform().await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn release(mut self) -> Self {
+        self.pointer().release();
+        self
+    }
+
+    /// Move the mouse to the specified element and release the mouse button.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// #         let elem = driver.find(By::Id("button1")).await?;
+    /// #         driver.action_chain().click_and_hold_element(&elem).perform().await?;
+    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 down");
+    /// driver.action_chain().release_on_element(&elem).perform().await?;
+    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn release_on_element(self, element: &WebElement) -> Self {
+        self.move_to_element_center(element).release()
+    }
+
+    /// Release every key and button that is held down at this point in the chain, so that
+    /// the chain ends in a clean state.
+    ///
+    /// Keys are released in the reverse order they were pressed, then the buttons of each
+    /// pointer source. Only keys and buttons pressed by this chain are released, so use
+    /// `reset_actions()` to release input left over from earlier chains.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain()
+    ///     .key_down(Key::Control)
+    ///     .key_down(Key::Shift)
+    ///     .click_and_hold_element(&elem)
+    ///     .release_all()
+    ///     .perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn release_all(mut self) -> Self {
+        for key in self.key_actions.held_keys().into_iter().rev() {
+            self = self.key_up(key);
+        }
+        for button in self.pointer_actions.held_buttons().into_iter().rev() {
+            self.pointer().release_button(button);
+        }
+        for index in 0..self.extra_pointers.len() {
+            for button in self.extra_pointers[index].held_buttons().into_iter().rev() {
+                self = self
+                    .with_pointer(PointerSourceId(index), |pointer| pointer.release_button(button));
+            }
+        }
+        self
+    }
+
+    /// Swipe from the specified X and Y coordinates to the other specified X and Y
+    /// coordinates, taking the specified duration.
+    ///
+    /// This puts the pointer down, moves it in steps of about one frame, and lifts it, so
+    /// that the page sees the velocity of the swipe. Create the chain with
+    /// [`PointerActionType::Touch`] for a touch swipe, otherwise this is a mouse drag.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// use thirtyfour::common::action::PointerActionType;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// // Flick the page up to scroll down.
+    /// driver.action_chain_with_pointer_type(PointerActionType::Touch)
+    ///     .swipe(200, 600, 200, 100, Duration::from_millis(200))
+    ///     .perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn swipe(self, from_x: i64, from_y: i64, to_x: i64, to_y: i64, duration: Duration) -> Self {
+        self.move_to(from_x, from_y)
+            .click_and_hold()
+            .move_to_smooth(to_x, to_y, swipe_steps(duration), duration)
+            .release()
+    }
+
+    /// Swipe the specified distance in the specified direction, starting from the center
+    /// of the specified element and taking the specified duration.
+    ///
+    /// The position of the element in the viewport is read when this is called, so this
+    /// is async and can fail. See [`ActionChain::swipe()`] for details.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// use thirtyfour::action_chain::SwipeDirection;
+    /// use thirtyfour::common::action::PointerActionType;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let row = driver.find(By::Css(".row")).await?;
+    /// driver.action_chain_with_pointer_type(PointerActionType::Touch)
+    ///     .swipe_element(&row, SwipeDirection::Left, 150, Duration::from_millis(200)).await?
+    ///     .perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn swipe_element(
+        self,
+        element: &WebElement,
+        direction: SwipeDirection,
+        distance: i64,
+        duration: Duration,
+    ) -> WebDriverResult<Self> {
+        let ret = self.handle.execute(ELEMENT_CENTER, vec![element.to_json()?]).await?;
+        let (x, y): (i64, i64) = ret.convert()?;
+        let (dx, dy) = direction.offset(distance);
+        Ok(self.swipe(x, y, x + dx, y + dy, duration))
+    }
+
+    /// Add another pointer input source, such as a second finger for multi-touch gestures,
+    /// and return its id for use with methods such as [`ActionChain::touch_down_at()`].
+    ///
+    /// The name must be unique within the chain, and must not be `key`, `pointer` or
+    /// `wheel`. The source starts at the current point in the chain, and all sources are
+    /// padded with pauses so that they stay aligned.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::common::action::PointerActionType;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// // Pinch out to zoom in.
+    /// let mut chain = driver.action_chain();
+    /// let finger1 = chain.add_pointer_source("finger1", PointerActionType::Touch);
+    /// let finger2 = chain.add_pointer_source("finger2", PointerActionType::Touch);
+    /// chain
+    ///     .touch_down_at(finger1, 150, 150)
+    ///     .touch_down_at(finger2, 170, 170)
+    ///     .touch_move_together(&[(finger1, 50, 50), (finger2, 270, 270)])
+    ///     .touch_up(finger1)
+    ///     .touch_up(finger2)
+    ///     .perform()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn add_pointer_source(
+        &mut self,
+        name: &str,
+        pointer_type: PointerActionType,
+    ) -> PointerSourceId {
+        self.extra_pointers.push(ActionSource::<PointerAction>::new(name, pointer_type, None));
+        PointerSourceId(self.extra_pointers.len() - 1)
+    }
+
+    /// Put the specified pointer down at the specified X and Y coordinates. For a touch
+    /// pointer, this touches the screen.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id was returned by a different action chain.
+    pub fn touch_down_at(self, source: PointerSourceId, x: i64, y: i64) -> Self {
+        self.with_pointer(source, |pointer| pointer.move_to(x, y))
+            .with_pointer(source, |pointer| pointer.click_and_hold())
+    }
+
+    /// Move the specified pointer to the specified X and Y coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id was returned by a different action chain.
+    pub fn touch_move_to(self, source: PointerSourceId, x: i64, y: i64) -> Self {
+        self.with_pointer(source, |pointer| pointer.move_to(x, y))
+    }
+
+    /// Move several pointers at the same time, each to its X and Y coordinates, for
+    /// gestures such as pinching.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any id was returned by a different action chain.
+    pub fn touch_move_together(mut self, moves: &[(PointerSourceId, i64, i64)]) -> Self {
+        let tick = self.ticks();
+        for &(PointerSourceId(index), x, y) in moves {
+            let pointer = &mut self.extra_pointers[index];
+            pointer.pad_to(tick);
+            pointer.move_to(x, y);
+        }
+        self
+    }
+
+    /// Lift the specified pointer. For a touch pointer, this stops touching the screen.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id was returned by a different action chain.
+    pub fn touch_up(self, source: PointerSourceId) -> Self {
+        self.with_pointer(source, |pointer| pointer.release())
+    }
+
+    /// Pinch with two fingers, which start the specified distance apart either side of
+    /// the specified X and Y coordinates, and move apart or together until they are the
+    /// end distance apart, taking the specified duration.
+    ///
+    /// A pinch out, where the end distance is larger, usually zooms in, and a pinch in
+    /// zooms out. The fingers are touch pointer sources named `pinch1` and `pinch2`,
+    /// which are added to the chain if it does not have them already. They touch the
+    /// screen at the same time, move in step with each other, and lift at the same time.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// // Zoom in on the middle of a map.
+    /// driver.action_chain().pinch(200, 200, 40, 200, Duration::from_millis(300)).perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn pinch(
+        mut self,
+        center_x: i64,
+        center_y: i64,
+        start_distance: i64,
+        end_distance: i64,
+        duration: Duration,
+    ) -> Self {
+        let fingers = [self.touch_source("pinch1"), self.touch_source("pinch2")];
+        // The fingers are level with the center, one either side of it.
+        let positions = |distance: i64| {
+            let left = distance / 2;
+            [(center_x - left, center_y), (center_x + distance - left, center_y)]
+        };
+        let (start, end) = (positions(start_distance), positions(end_distance));
+        let steps = swipe_steps(duration);
+        let tick = self.ticks();
+        for (i, PointerSourceId(index)) in fingers.into_iter().enumerate() {
+            let pointer = &mut self.extra_pointers[index];
+            pointer.pad_to(tick);
+            pointer.move_to(start[i].0, start[i].1);
+            pointer.click_and_hold();
+            pointer.move_smooth(start[i], end[i], steps, duration);
+            pointer.release();
+        }
+        self
+    }
+
+    /// Pinch over the center of the specified element to zoom it by the specified scale
+    /// factor, taking the specified duration.
+    ///
+    /// A scale factor above 1 pinches out to zoom in, and below 1 pinches in to zoom out.
+    /// The fingers stay within the element, so the larger of the start and end distances
+    /// is half the shorter side of the element. The position and size of the element are
+    /// read when this is called, so this is async and can fail. Returns an
+    /// `InvalidArgument` error if the scale factor is not a positive number.
+    ///
+    /// See [`ActionChain::pinch()`] for details.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let map = driver.find(By::Id("map")).await?;
+    /// driver
+    ///     .action_chain()
+    ///     .zoom_element(&map, 2.0, Duration::from_millis(300))
+    ///     .await?
+    ///     .perform()
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn zoom_element(
+        self,
+        element: &WebElement,
+        scale_factor: f64,
+        duration: Duration,
+    ) -> WebDriverResult<Self> {
+        if !(scale_factor.is_finite() && scale_factor > 0.0) {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+                "zoom scale factor must be a positive number, got {scale_factor}"
+            ))));
+        }
+        let ret = self.handle.execute(ELEMENT_CENTER, vec![element.to_json()?]).await?;
+        let (x, y): (i64, i64) = ret.convert()?;
+        let rect = element.rect().await?;
+        let outer = rect.width.min(rect.height) / 2.0;
+        let (start, end) = if scale_factor >= 1.0 {
+            (outer / scale_factor, outer)
+        } else {
+            (outer, outer * scale_factor)
+        };
+        Ok(self.pinch(x, y, start.round() as i64, end.round() as i64, duration))
+    }
+
+    /// The id of the touch pointer source with the specified name, which is added if the
+    /// chain does not have it.
+    fn touch_source(&mut self, name: &str) -> PointerSourceId {
+        match self.extra_pointers.iter().position(|x| x.id() == name) {
+            Some(index) => PointerSourceId(index),
+            None => self.add_pointer_source(name, PointerActionType::Touch),
+        }
+    }
+
+    /// The number of ticks in the chain, which is the length of the longest input source.
+    ///
+    /// Each source only has the actions added to it, so the other sources are padded with
+    /// pauses before an action is added, and before the chain is performed.
+    fn ticks(&self) -> usize {
+        let extra = self.extra_pointers.iter().map(ActionSource::len);
+        [self.key_actions.len(), self.pointer_actions.len(), self.wheel_actions.len()]
+            .into_iter()
+            .chain(extra)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Pad every input source to the same number of ticks.
+    fn align(&mut self) {
+        let ticks = self.ticks();
+        self.key_actions.pad_to(ticks);
+        self.pointer_actions.pad_to(ticks);
+        self.wheel_actions.pad_to(ticks);
+        for source in &mut self.extra_pointers {
+            source.pad_to(ticks);
+        }
+    }
+
+    /// The key source, padded so that the next action is added as the next tick.
+    fn keys(&mut self) -> &mut ActionSource<KeyAction> {
+        let ticks = self.ticks();
+        self.key_actions.pad_to(ticks);
+        &mut self.key_actions
+    }
+
+    /// The pointer source, padded so that the next action is added as the next tick.
+    fn pointer(&mut self) -> &mut ActionSource<PointerAction> {
+        let ticks = self.ticks();
+        self.pointer_actions.pad_to(ticks);
+        &mut self.pointer_actions
+    }
+
+    /// The wheel source, padded so that the next action is added as the next tick.
+    fn wheel(&mut self) -> &mut ActionSource<WheelAction> {
+        let ticks = self.ticks();
+        self.wheel_actions.pad_to(ticks);
+        &mut self.wheel_actions
+    }
+
+    /// Add a single action to an additional pointer source, as the next tick.
+    fn with_pointer(
+        mut self,
+        PointerSourceId(index): PointerSourceId,
+        add: impl FnOnce(&mut ActionSource<PointerAction>),
+    ) -> Self {
+        let tick = self.ticks();
+        let pointer = &mut self.extra_pointers[index];
+        pointer.pad_to(tick);
+        add(pointer);
+        self
+    }
+
+    /// Scroll the page with the mouse wheel by the specified X and Y deltas, in pixels.
+    ///
+    /// Unlike scrolling with `window.scrollBy()`, this dispatches `wheel` events, so it
+    /// triggers the same handlers as a real mouse wheel. The scroll happens at the top
+    /// left corner of the viewport.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// driver.action_chain().scroll_by(0, 500).perform().await?;
+    /// let scroll_y: i64 = driver.execute("return window.scrollY;", Vec::new()).await?.convert()?;
+    /// assert_eq!(scroll_y, 500);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn scroll_by(mut self, delta_x: i64, delta_y: i64) -> Self {
+        self.wheel().scroll(0, 0, delta_x, delta_y);
+        self
+    }
+
+    /// Scroll with the mouse wheel by the specified X and Y deltas, in pixels, over the
+    /// center of the specified element.
+    ///
+    /// This scrolls the element if it is scrollable, or otherwise its nearest scrollable
+    /// ancestor. If the element is outside the viewport, it is scrolled into view first.
     ///
     /// # Example:
     /// ```no_run
@@ -425,32 +2620,25 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
-    /// let elem = driver.find(By::Name("input1")).await?;
-    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
-    /// elem.send_keys("selenium").await?;
-    /// assert_eq!(elem.value().await?, Some("selenium".to_string()));
-    /// driver.action_chain()
-    ///     .key_down_on_element(&elem, Key::Control).key_down('a')
-    ///     .key_up(Key::Control).key_up('a')
-    ///     .key_down('b')
-    ///     .perform().await?;
-    /// assert_eq!(elem.value().await?, Some("b".to_string()));
+    /// let list = driver.find(By::Id("results")).await?;
+    /// driver.action_chain().scroll_from_element(&list, 0, 200).perform().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn key_up<T>(mut self, value: T) -> Self
-    where
-        T: Into<char>,
-    {
-        self.key_actions.key_up(value.into());
-        self.pointer_actions.pause();
+    pub fn scroll_from_element(mut self, element: &WebElement, delta_x: i64, delta_y: i64) -> Self {
+        self.wheel().scroll_from_element(element.element_id.clone(), 0, 0, delta_x, delta_y);
         self
     }
 
-    /// Click the specified element and release the specified key.
+    /// Scroll within the specified scrollable element, such as an overflowing list or
+    /// table body, with the mouse wheel by the specified X and Y deltas, in pixels.
+    ///
+    /// The wheel events are dispatched to the element under the center of the specified
+    /// element, so only the innermost scrollable container at that point is scrolled and
+    /// the page stays where it is, as long as the element is in the viewport and can
+    /// scroll further.
     ///
     /// # Example:
     /// ```no_run
@@ -462,30 +2650,22 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
-    /// let elem = driver.find(By::Name("input1")).await?;
-    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
-    /// elem.send_keys("selenium").await?;
-    /// assert_eq!(elem.value().await?, Some("selenium".to_string()));
-    /// driver.action_chain()
-    ///     .key_down_on_element(&elem, Key::Control).key_down('a')
-    ///     .key_up_on_element(&elem, 'a').key_up_on_element(&elem, Key::Control)
-    ///     .key_down('b')
-    ///     .perform().await?;
-    /// assert_eq!(elem.value().await?, Some("b".to_string()));
+    /// let rows = driver.find(By::Css("table tbody")).await?;
+    /// driver.action_chain().scroll_within_element(&rows, 0, 300).perform().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn key_up_on_element<T>(self, element: &WebElement, value: T) -> Self
-    where
-        T: Into<char>,
-    {
-        self.click_element(element).key_up(value)
+    pub fn scroll_within_element(self, element: &WebElement, delta_x: i64, delta_y: i64) -> Self {
+        self.scroll_within_element_with_offset(element, 0, 0, delta_x, delta_y)
     }
 
-    /// Move the mouse cursor to the specified X and Y coordinates.
+    /// Scroll with the mouse wheel by the specified X and Y deltas, in pixels, at the
+    /// specified offset from the center of the specified element.
+    ///
+    /// This is useful when the center of a container is covered by another element, such
+    /// as a sticky header. See [`ActionChain::scroll_within_element()`] for details.
     ///
     /// # Example:
     /// ```no_run
@@ -497,26 +2677,41 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// let elem = driver.find(By::Id("button1")).await?;
-    /// let center = elem.rect().await?.icenter();
-    /// driver.action_chain()
-    ///     .move_to(center.0, center.1)
-    ///     .click()
-    ///     .perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// let table = driver.find(By::Tag("table")).await?;
+    /// driver
+    ///     .action_chain()
+    ///     .scroll_within_element_with_offset(&table, 0, 100, 0, 300)
+    ///     .perform()
+    ///     .await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn move_to(mut self, x: i64, y: i64) -> Self {
-        self.pointer_actions.move_to(x, y);
-        self.key_actions.pause();
+    pub fn scroll_within_element_with_offset(
+        mut self,
+        element: &WebElement,
+        x_offset: i64,
+        y_offset: i64,
+        delta_x: i64,
+        delta_y: i64,
+    ) -> Self {
+        self.wheel().scroll_from_element(
+            element.element_id.clone(),
+            x_offset,
+            y_offset,
+            delta_x,
+            delta_y,
+        );
         self
     }
 
-    /// Move the mouse cursor by the specified X and Y offsets.
+    /// Scroll the specified element into view, including within any scrollable
+    /// containers.
+    ///
+    /// This scrolls with the mouse wheel over the element, which drivers scroll into view
+    /// first. Drivers that do not support wheel input fall back to `scrollIntoView()`
+    /// when the chain is performed, as described in [`ActionChain::perform()`].
     ///
     /// # Example:
     /// ```no_run
@@ -528,30 +2723,22 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// let elem1 = driver.find(By::Id("button1")).await?;
-    /// let elem2 = driver.find(By::Id("button2")).await?;
-    /// // We will calculate the distance between the two center points and then
-    /// // use action_chain() to move to the second button before clicking.
-    /// let offset = elem2.rect().await?.center().0 as i64 - elem1.rect().await?.center().0 as i64;
-    /// driver.action_chain()
-    ///     .move_to_element_center(&elem1)
-    ///     .move_by_offset(offset, 0)
-    ///     .click()
-    ///     .perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 2 clicked");
+    /// let elem = driver.find(By::Id("footer")).await?;
+    /// driver.action_chain().scroll_to_element(&elem).perform().await?;
+    /// assert!(elem.is_displayed().await?);
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn move_by_offset(mut self, x_offset: i64, y_offset: i64) -> Self {
-        self.pointer_actions.move_by(x_offset, y_offset);
-        self.key_actions.pause();
-        self
+    pub fn scroll_to_element(mut self, element: &WebElement) -> Self {
+        self.scroll_targets.push(element.clone());
+        self.scroll_from_element(element, 0, 0)
     }
 
-    /// Move the mouse cursor to the center of the specified element.
+    /// Scroll the specified element into view, then click it with the left mouse button.
+    ///
+    /// See [`ActionChain::scroll_to_element()`] for details.
     ///
     /// # Example:
     /// ```no_run
@@ -564,10 +2751,7 @@ impl ActionChain {
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
     /// let elem = driver.find(By::Id("button1")).await?;
-    /// driver.action_chain()
-    ///     .move_to_element_center(&elem)
-    ///     .click()
-    ///     .perform().await?;
+    /// driver.action_chain().scroll_and_click_element(&elem).perform().await?;
     /// #         let elem_result = driver.find(By::Id("button-result")).await?;
     /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
     /// #         driver.quit().await?;
@@ -575,18 +2759,15 @@ impl ActionChain {
     /// #     })
     /// # }
     /// ```
-    pub fn move_to_element_center(mut self, element: &WebElement) -> Self {
-        self.pointer_actions.move_to_element_center(element.element_id.clone());
-        self.key_actions.pause();
-        self
+    pub fn scroll_and_click_element(self, element: &WebElement) -> Self {
+        self.scroll_to_element(element).click_element(element)
     }
 
-    /// Move the mouse cursor to the specified offsets relative to the specified
-    /// element's center position.
+    /// Send the specified keystrokes to the active element.
     ///
     /// # Example:
-    ///
     /// ```no_run
+    /// use thirtyfour::Key;
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
     /// #
@@ -595,69 +2776,97 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         driver.find(By::Id("button1")).await?.click().await?;
-    /// // Select the text in the source element and copy it to the clipboard.
-    /// let elem = driver.find(By::Id("button-result")).await?;
-    /// let width = elem.rect().await?.width;
+    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// let button = driver.find(By::Id("button-set")).await?;
+    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
     /// driver.action_chain()
-    ///     .move_to_element_with_offset(&elem, (-width / 2.0) as i64, 0)
-    ///     .drag_and_drop_by_offset(width as i64, 0)
-    ///     .key_down(Key::Control)
-    ///     .key_down('c').key_up('c')
-    ///     .key_up(Key::Control)
+    ///     .click_element(&elem)
+    ///     .send_keys("selenium")
+    ///     .click_element(&button)
     ///     .perform().await?;
-    ///
-    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
-    /// // Now paste the text into the input field.
-    /// let elem_tgt = driver.find(By::Name("input1")).await?;
-    /// elem_tgt.send_keys(Key::Control + "v").await?;
-    /// #         assert_eq!(elem_tgt.value().await?, Some("Button 1 clicked".to_string()));
+    /// #         let elem_result = driver.find(By::Id("input-result")).await?;
+    /// #         assert_eq!(elem_result.text().await?, "selenium");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn move_to_element_with_offset(
-        mut self,
-        element: &WebElement,
-        x_offset: i64,
-        y_offset: i64,
-    ) -> Self {
-        self.pointer_actions.move_to_element(element.element_id.clone(), x_offset, y_offset);
-        self.key_actions.pause();
+    pub fn send_keys<S>(mut self, text: S) -> Self
+    where
+        S: Into<TypingData>,
+    {
+        let typing: TypingData = text.into();
+        for c in typing.as_vec() {
+            self = self.key_down(c).key_up(c);
+        }
         self
     }
 
-    /// Release the left mouse button.
+    /// Send the specified keystrokes to the active element, waiting for the specified
+    /// delay between each character, or a [`TypingSpeed`] preset.
+    ///
+    /// Some autocomplete widgets and rate-limited inputs miss keystrokes sent by
+    /// `send_keys()`, which sends them all at once.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         let elem = driver.find(By::Id("button1")).await?;
-    /// #         driver.action_chain().click_and_hold_element(&elem).perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 down");
-    /// driver.action_chain().release().perform().await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// driver.action_chain()
+    ///     .click_element(&elem)
+    ///     .send_keys_with_delay("selenium", Duration::from_millis(50))
+    ///     .perform().await?;
+    /// assert_eq!(elem.value().await?, Some("selenium".to_string()));
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn release(mut self) -> Self {
-        self.pointer_actions.release();
-        self.key_actions.pause();
+    pub fn send_keys_with_delay<S, D>(mut self, text: S, delay: D) -> Self
+    where
+        S: Into<TypingData>,
+        D: Into<Duration>,
+    {
+        let delay_ms = u64::try_from(delay.into().as_millis()).ok().unwrap_or(u64::MAX);
+        let typing: TypingData = text.into();
+        let chars = typing.as_vec();
+        for (i, &c) in chars.iter().enumerate() {
+            self.keys().key_down(c);
+            // Rather than adding a tick for each delay, the pointer pauses while the key is
+            // released, as a tick lasts as long as its longest action.
+            let tick = self.ticks();
+            self.keys().key_up(c);
+            if i + 1 < chars.len() {
+                self.pointer_actions.pad_to(tick);
+                self.pointer_actions.pause_for(delay_ms);
+            }
+        }
         self
     }
 
-    /// Move the mouse to the specified element and release the mouse button.
+    /// Paste the specified text with the clipboard, which is much faster than typing long
+    /// text with [`ActionChain::send_keys()`].
+    ///
+    /// The chain presses Command+V on macOS or Control+V elsewhere, based on the
+    /// `platformName` capability of the session. The text is written to the clipboard
+    /// when the chain is performed, just before those keys, so a chain can paste several
+    /// different texts.
+    ///
+    /// Writing to the clipboard needs a secure context such as `https` or `localhost`, a
+    /// focused document, and the `clipboard-write` permission, which Chrome grants to
+    /// secure pages by default. It can be granted with
+    /// `WebDriver::set_permission(PermissionName::ClipboardWrite, PermissionState::Granted)`.
+    /// If the clipboard cannot be written, the text is typed with `send_keys()` instead.
     ///
     /// # Example:
     /// ```no_run
@@ -669,26 +2878,34 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         let elem = driver.find(By::Id("button1")).await?;
-    /// #         driver.action_chain().click_and_hold_element(&elem).perform().await?;
-    /// #         let elem_result = driver.find(By::Id("button-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 down");
-    /// driver.action_chain().release_on_element(&elem).perform().await?;
-    /// #         assert_eq!(elem_result.text().await?, "Button 1 clicked");
+    /// let elem = driver.find(By::Id("input-result")).await?;
+    /// let text = "lorem ipsum ".repeat(1000);
+    /// driver.action_chain().click_element(&elem).paste_text(&text).perform().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn release_on_element(self, element: &WebElement) -> Self {
-        self.move_to_element_center(element).release()
+    pub fn paste_text(mut self, text: &str) -> Self {
+        let modifier = if self.handle.driver_info().is_mac() {
+            Key::Command
+        } else {
+            Key::Control
+        };
+        let start = self.ticks();
+        self = self.key_chord([modifier], 'v');
+        self.paste_targets.push((start..self.ticks(), text.to_string()));
+        self
     }
 
-    /// Send the specified keystrokes to the active element.
+    /// Hold down the specified key while performing the actions added by the specified
+    /// function, and release it afterwards.
+    ///
+    /// The key is released even if the function leaves other keys or buttons held down,
+    /// and calls can be nested to hold several keys.
     ///
     /// # Example:
     /// ```no_run
-    /// use thirtyfour::Key;
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
     /// #
@@ -697,31 +2914,24 @@ impl ActionChain {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
     /// #         driver.get("http://webappdemo").await?;
-    /// #         driver.find(By::Id("pagetextinput")).await?.click().await?;
-    /// let elem = driver.find(By::Name("input1")).await?;
-    /// let button = driver.find(By::Id("button-set")).await?;
-    /// #         assert_eq!(elem.value().await?, Some("".to_string()));
+    /// let first = driver.find(By::Id("item1")).await?;
+    /// let last = driver.find(By::Id("item3")).await?;
+    /// // Shift-drag from the first item to the last.
     /// driver.action_chain()
-    ///     .click_element(&elem)
-    ///     .send_keys("selenium")
-    ///     .click_element(&button)
+    ///     .with_key_held(Key::Shift, |chain| chain.drag_and_drop_element(&first, &last))
     ///     .perform().await?;
-    /// #         let elem_result = driver.find(By::Id("input-result")).await?;
-    /// #         assert_eq!(elem_result.text().await?, "selenium");
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub fn send_keys<S>(mut self, text: S) -> Self
+    pub fn with_key_held<T, F>(self, key: T, f: F) -> Self
     where
-        S: Into<TypingData>,
+        T: Into<char>,
+        F: FnOnce(ActionChain) -> ActionChain,
     {
-        let typing: TypingData = text.into();
-        for c in typing.as_vec() {
-            self = self.key_down(c).key_up(c);
-        }
-        self
+        let key = key.into();
+        f(self.key_down(key)).key_up(key)
     }
 
     /// Click on the specified element and send the specified keystrokes.
@@ -757,4 +2967,519 @@ impl ActionChain {
     {
         self.click_element(element).send_keys(text)
     }
+
+    /// Focus the specified element with script and send the specified keystrokes, without
+    /// clicking it.
+    ///
+    /// Unlike [`ActionChain::send_keys_to_element()`], the chain has no pointer actions
+    /// for the element, so no `click`, `mousedown` or `pointerdown` events are fired. This
+    /// avoids the side effects of clicking, such as closing an open autocomplete list.
+    ///
+    /// Script cannot run within an action sequence, so the element is focused when the
+    /// chain is performed, between the actions added before this and the keystrokes,
+    /// which are sent as a separate sequence. The focus step is not included in
+    /// [`ActionChain::to_json()`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444/wd/hub", caps).await?;
+    /// #         driver.get("http://webappdemo").await?;
+    /// let elem = driver.find(By::Name("input1")).await?;
+    /// driver.action_chain().focus_element_and_send_keys(&elem, "selenium").perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn focus_element_and_send_keys<S>(mut self, element: &WebElement, text: S) -> Self
+    where
+        S: Into<TypingData>,
+    {
+        self.focus_targets.push((self.ticks(), element.clone()));
+        self.send_keys(text)
+    }
+}
+
+/// Return true if the error shows that the driver does not support wheel input sources.
+///
+/// Drivers reject the whole action sequence with an invalid argument or unsupported
+/// operation error, so only errors that mention the wheel source are matched.
+fn is_wheel_rejected(e: &WebDriverError) -> bool {
+    match e.as_inner() {
+        WebDriverErrorInner::InvalidArgument(info)
+        | WebDriverErrorInner::UnsupportedOperation(info) => {
+            info.value.message.to_lowercase().contains("wheel")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::{Request, Response};
+    use serde_json::json;
+
+    use super::*;
+    use crate::common::types::SessionId;
+    use crate::session::http::{Body, HttpClient};
+
+    /// A client for chains that are never performed.
+    struct NoClient;
+
+    #[async_trait::async_trait]
+    impl HttpClient for NoClient {
+        async fn send(&self, _: Request<Body<'_>>) -> WebDriverResult<Response<Bytes>> {
+            unreachable!("action chain tests do not send requests")
+        }
+
+        async fn new(&self) -> Arc<dyn HttpClient> {
+            Arc::new(NoClient)
+        }
+    }
+
+    fn handle() -> Arc<SessionHandle> {
+        let handle =
+            SessionHandle::new(Arc::new(NoClient), "http://localhost:4444", SessionId::from("s"))
+                .unwrap();
+        // Dropping the handle would otherwise try to quit the session.
+        handle.leak().unwrap();
+        Arc::new(handle)
+    }
+
+    fn element(handle: &Arc<SessionHandle>, id: &str) -> WebElement {
+        WebElement::new(ElementId::from(id), handle.clone())
+    }
+
+    /// Each source as its id and a compact form of its actions, to compare sequences.
+    fn summary(chain: &ActionChain) -> Vec<String> {
+        let json = chain.to_json();
+        json["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|source| {
+                let actions: Vec<String> =
+                    source["actions"].as_array().unwrap().iter().map(action_summary).collect();
+                format!("{}: {}", source["id"].as_str().unwrap(), actions.join(" "))
+            })
+            .collect()
+    }
+
+    fn action_summary(action: &serde_json::Value) -> String {
+        let origin = match &action["origin"] {
+            serde_json::Value::Object(x) => {
+                format!("{}+", x.values().next().unwrap().as_str().unwrap())
+            }
+            x if x == "pointer" => "~".to_string(),
+            _ => String::new(),
+        };
+        let pressure = match &action["pressure"] {
+            serde_json::Value::Null => String::new(),
+            x => format!("!{x}"),
+        };
+        let (duration, button) = (&action["duration"], &action["button"]);
+        let (x, y) = (&action["x"], &action["y"]);
+        match action["type"].as_str().unwrap() {
+            "pause" if duration == 0 => ".".to_string(),
+            "pause" => format!("p{duration}"),
+            "keyDown" => format!("kd:{}", action["value"].as_str().unwrap().escape_default()),
+            "keyUp" => format!("ku:{}", action["value"].as_str().unwrap().escape_default()),
+            "pointerDown" => format!("pd{button}{pressure}"),
+            "pointerUp" => format!("pu{button}"),
+            "pointerMove" => format!("m({origin}{x},{y})@{duration}{pressure}"),
+            "scroll" => {
+                format!("s({origin}{x},{y};{},{})@{duration}", action["deltaX"], action["deltaY"])
+            }
+            kind => panic!("unexpected action {kind}"),
+        }
+    }
+
+    #[test]
+    fn test_chain_actions() {
+        let handle = handle();
+        let e = element(&handle, "e");
+        let f = element(&handle, "f");
+        let chains = vec![
+            ActionChain::new(handle.clone())
+                .move_to(10, 20)
+                .click()
+                .context_click()
+                .double_click()
+                .click_and_hold()
+                .release(),
+            ActionChain::new(handle.clone())
+                .key_down('a')
+                .key_up('a')
+                .send_keys("bc")
+                .key_chord([Key::Control], 'v')
+                .click_with_modifier(Key::Shift),
+            ActionChain::new(handle.clone())
+                .click_element(&e)
+                .middle_click()
+                .click_button(MouseButton::Back)
+                .triple_click()
+                .click_at(1, 2)
+                .context_click_at(3, 4)
+                .double_click_at(5, 6)
+                .context_click_element(&f)
+                .middle_click_element(&e)
+                .double_click_element(&f),
+            ActionChain::new(handle.clone())
+                .move_by_offset(1, 2)
+                .move_to_element_center(&e)
+                .move_to_element_with_offset(&f, 3, 4)
+                .click_and_hold_element(&e)
+                .release_on_element(&f)
+                .drag_and_drop_by_offset(5, 6)
+                .drag_and_drop_element(&e, &f)
+                .drag_and_drop_element_by_offset(&e, 7, 8),
+            ActionChain::new(handle.clone())
+                .scroll_by(0, 100)
+                .scroll_from_element(&e, 10, 0)
+                .scroll_and_click_element(&f)
+                .key_down_on_element(&e, 'x')
+                .key_up_on_element(&e, 'x')
+                .send_keys_to_element(&f, "y"),
+            ActionChain::new_with_delay(handle.clone(), None, Some(Duration::from_millis(50)))
+                .pause(Duration::from_millis(100))
+                .move_to(1, 1)
+                .with_next_delay(Duration::from_millis(300))
+                .move_to(2, 2)
+                .click_and_hold_for(Duration::from_millis(20))
+                .long_press_element(&e, Duration::from_millis(30))
+                .send_keys_with_delay("ab", Duration::from_millis(40)),
+            ActionChain::new_with_pointer_type(handle.clone(), PointerActionType::Pen)
+                .move_to(1, 1)
+                .pen_down_with_pressure(0.5)
+                .move_to_with_pen_properties(2, 2, PenProperties::with_pressure(0.7))
+                .move_to_smooth(6, 10, 2, Duration::from_millis(100))
+                .release()
+                .swipe(0, 0, 10, 0, Duration::from_millis(40)),
+            ActionChain::new(handle.clone())
+                .key_down(Key::Shift)
+                .click_and_hold()
+                .release_all()
+                .click_n_times(2)
+                .with_key_held(Key::Alt, |chain| chain.click()),
+        ];
+        let mut touch =
+            ActionChain::new_with_pointer_type(handle.clone(), PointerActionType::Touch);
+        touch = touch.move_to(5, 5);
+        let finger1 = touch.add_pointer_source("finger1", PointerActionType::Touch);
+        let finger2 = touch.add_pointer_source("finger2", PointerActionType::Touch);
+        touch = touch
+            .touch_down_at(finger1, 1, 1)
+            .key_down('a')
+            .touch_down_at(finger2, 2, 2)
+            .touch_move_together(&[(finger1, 3, 3), (finger2, 4, 4)])
+            .touch_move_to(finger1, 5, 5)
+            .touch_up(finger1)
+            .release_all();
+        let mut other =
+            ActionChain::new_with_pointer_type(handle.clone(), PointerActionType::Touch);
+        let finger2 = other.add_pointer_source("finger2", PointerActionType::Touch);
+        let finger3 = other.add_pointer_source("finger3", PointerActionType::Touch);
+        other = other.touch_down_at(finger3, 6, 6).touch_up(finger2).click();
+        let chains: Vec<_> = chains.into_iter().chain([touch.then(other)]).collect();
+        let expected: [&[&str]; 9] = [
+            &[
+                "key: . . . . . . . . . . .",
+                "pointer: m(10,20)@250 pd0 pu0 pd2 pu2 pd0 pu0 pd0 pu0 pd0 pu0",
+            ],
+            &[
+                "key: kd:a ku:a kd:b ku:b kd:c ku:c kd:\\u{e009} kd:v ku:v ku:\\u{e009} kd:\\u{e008} . . ku:\\u{e008}",
+                "pointer: . . . . . . . . . . . pd0 pu0 .",
+            ],
+            &[
+                "key: . . . . . . . . . . . . . . . . . . . . . . . . . . . . . . . . . . .",
+                "pointer: m(e+0,0)@250 pd0 pu0 pd1 pu1 pd3 pu3 pd0 pu0 pd0 pu0 pd0 pu0 m(1,2)@250 pd0 pu0 m(3,4)@250 pd2 pu2 m(5,6)@250 pd0 pu0 pd0 pu0 m(f+0,0)@250 pd2 pu2 m(e+0,0)@250 pd1 pu1 m(f+0,0)@250 pd0 pu0 pd0 pu0",
+            ],
+            &[
+                "key: . . . . . . . . . . . . . . . .",
+                "pointer: m(~1,2)@250 m(e+0,0)@250 m(f+3,4)@250 m(e+0,0)@250 pd0 m(f+0,0)@250 pu0 pd0 m(~5,6)@250 m(e+0,0)@250 pd0 m(f+0,0)@250 pu0 m(e+0,0)@250 pd0 m(~7,8)@250",
+            ],
+            &[
+                "key: . . . . . . . . . kd:x . . . ku:x . . . kd:y ku:y",
+                "pointer: . . . m(f+0,0)@250 pd0 pu0 m(e+0,0)@250 pd0 pu0 . m(e+0,0)@250 pd0 pu0 . m(f+0,0)@250 pd0 pu0 . .",
+                "wheel: s(0,0;0,100)@0 s(e+0,0;10,0)@0 s(f+0,0;0,0)@0 . . . . . . . . . . . . . . . .",
+            ],
+            &[
+                "key: p100 . p300 . . p20 . . . p30 . kd:a ku:a kd:b ku:b",
+                "pointer: p100 m(1,1)@50 p300 m(2,2)@0 pd0 p20 pu0 m(e+0,0)@50 pd0 p30 pu0 . p40 . .",
+            ],
+            &[
+                "key: . . . . . . . . . . .",
+                "pointer: m(1,1)@250 pd0!0.5 m(2,2)@250!0.7 m(4,6)@50 m(6,10)@50 pu0 m(0,0)@250 pd0 m(5,0)@20 m(10,0)@20 pu0",
+            ],
+            &[
+                "key: kd:\\u{e008} . ku:\\u{e008} . . . . . kd:\\u{e00a} . . ku:\\u{e00a}",
+                "pointer: . pd0 . pu0 pd0 pu0 pd0 pu0 . pd0 pu0 .",
+            ],
+            &[
+                "key: . . . kd:a . . . . . ku:a . . . . . .",
+                "pointer: m(5,5)@250 . . . . . . . . . . . . . pd0 pu0",
+                "finger1: . m(1,1)@250 pd0 . . . m(3,3)@250 m(5,5)@250 pu0 . . . . . . .",
+                "finger2: . . . . m(2,2)@250 pd0 m(4,4)@250 . . . pu0 . . pu0 . .",
+                "finger3: . . . . . . . . . . . m(6,6)@250 pd0 . . .",
+            ],
+        ];
+        for (chain, expected) in chains.iter().zip(expected) {
+            assert_eq!(summary(chain), expected);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let handle = handle();
+        let chain = ActionChain::new(handle.clone()).key_down('a').click();
+        let lengths = |chain: &ActionChain| {
+            chain.validate().into_iter().map(|(_, len)| len).collect::<Vec<_>>()
+        };
+        assert_eq!(lengths(&chain), [1, 3, 0]);
+        let sources = chain.to_json()["actions"].as_array().unwrap().clone();
+        assert!(sources.iter().all(|x| x["actions"].as_array().unwrap().len() == 3));
+
+        let chain = chain.pause(Duration::from_millis(100)).scroll_by(0, 10);
+        assert_eq!(lengths(&chain), [4, 4, 5]);
+    }
+
+    /// The payloads sent for these chains before the input sources were aligned
+    /// automatically, which must not change.
+    #[test]
+    fn test_chain_payload_unchanged() {
+        let handle = handle();
+        let (e, f) = (element(&handle, "e"), element(&handle, "f"));
+        let pause = json!({ "type": "pause", "duration": 0 });
+        let down = json!({ "type": "pointerDown", "button": 0, "duration": 0 });
+        let up = json!({ "type": "pointerUp", "button": 0, "duration": 0 });
+        let move_to = |id: &str| {
+            json!({
+                "type": "pointerMove",
+                "duration": 250,
+                "origin": { "element-6066-11e4-a52e-4f735466cecf": id },
+                "x": 0,
+                "y": 0,
+            })
+        };
+        let payload = |key: Vec<serde_json::Value>, pointer: Vec<serde_json::Value>| {
+            json!({
+                "actions": [
+                    { "id": "key", "type": "key", "actions": key },
+                    {
+                        "id": "pointer",
+                        "type": "pointer",
+                        "parameters": { "pointerType": "mouse" },
+                        "actions": pointer,
+                    },
+                ]
+            })
+        };
+
+        let chain = ActionChain::new(handle.clone()).click_element(&e);
+        assert_eq!(
+            chain.to_json(),
+            payload(vec![pause.clone(); 3], vec![move_to("e"), down.clone(), up.clone()])
+        );
+
+        let chain = ActionChain::new(handle.clone()).double_click();
+        assert_eq!(
+            chain.to_json(),
+            payload(
+                vec![pause.clone(); 4],
+                vec![down.clone(), up.clone(), down.clone(), up.clone()]
+            )
+        );
+
+        let chain = ActionChain::new(handle.clone()).key_down('a');
+        assert_eq!(
+            chain.to_json(),
+            payload(vec![json!({ "type": "keyDown", "value": "a" })], vec![pause.clone()])
+        );
+
+        let chain = ActionChain::new(handle.clone()).drag_and_drop_element(&e, &f);
+        assert_eq!(
+            chain.to_json(),
+            payload(vec![pause.clone(); 4], vec![move_to("e"), down, move_to("f"), up])
+        );
+    }
+
+    #[test]
+    fn test_script_scroll_segments() {
+        let handle = handle();
+        let (e, f) = (element(&handle, "e"), element(&handle, "f"));
+        let chain = ActionChain::new(handle.clone())
+            .click()
+            .scroll_to_element(&e)
+            .key_down('a')
+            .scroll_to_element(&f)
+            .scroll_to_element(&e);
+        let segments = |ticks: Range<usize>| -> Vec<_> {
+            chain
+                .script_scroll_segments(ticks)
+                .unwrap()
+                .into_iter()
+                .map(|(ticks, x)| (ticks, x.map(|x| x.element_id().to_string())))
+                .collect()
+        };
+        let id = |x: &str| Some(x.to_string());
+        assert_eq!(
+            segments(0..6),
+            [(0..2, id("e")), (3..4, id("f")), (5..5, id("e")), (6..6, None)]
+        );
+        assert_eq!(segments(3..5), [(3..4, id("f")), (5..5, None)]);
+
+        let sources = chain.sources(false, 3..4);
+        assert_eq!(sources[0]["actions"], json!([{ "type": "keyDown", "value": "a" }]));
+        assert_eq!(sources.as_array().unwrap().len(), 2);
+
+        assert!(chain.clone().scroll_by(0, 10).script_scroll_segments(0..7).is_none());
+        assert!(ActionChain::new(handle).click().script_scroll_segments(0..2).is_none());
+    }
+
+    #[test]
+    fn test_focus_segments() {
+        let handle = handle();
+        let (e, f) = (element(&handle, "e"), element(&handle, "f"));
+        let segments = |chain: &ActionChain| -> Vec<_> {
+            let segments = chain.focus_segments().into_iter();
+            segments.map(|(x, ticks)| (x.map(|x| x.element_id().to_string()), ticks)).collect()
+        };
+        let id = |x: &str| Some(x.to_string());
+
+        let chain = ActionChain::new(handle.clone()).click();
+        assert_eq!(segments(&chain), [(None, 0..2)]);
+
+        let chain = chain.focus_element_and_send_keys(&e, "ab").click();
+        assert_eq!(segments(&chain), [(None, 0..2), (id("e"), 2..8)]);
+        assert_eq!(summary(&chain)[0], "key: . . kd:a ku:a kd:b ku:b . .");
+
+        let chain = ActionChain::new(handle.clone())
+            .focus_element_and_send_keys(&e, "a")
+            .then(ActionChain::new(handle).focus_element_and_send_keys(&f, "b"));
+        assert_eq!(segments(&chain), [(None, 0..0), (id("e"), 0..2), (id("f"), 2..4)]);
+        let recording = chain.to_recording();
+        assert_eq!(recording.element_ids(), [ElementId::from("e"), ElementId::from("f")]);
+    }
+
+    #[test]
+    fn test_paste_segments() {
+        let handle = handle();
+        let chain = ActionChain::new(handle.clone()).click().paste_text("a");
+        assert_eq!(summary(&chain)[0], "key: . . kd:\\u{e009} kd:v ku:v ku:\\u{e009}");
+        let chain = chain.then(ActionChain::new(handle.clone()).paste_text("b").click());
+        assert_eq!(
+            chain.paste_segments(0..chain.ticks()),
+            [(0..2, None), (2..6, Some("a")), (6..6, None), (6..10, Some("b")), (10..12, None)]
+        );
+        assert_eq!(chain.paste_segments(6..12), [(6..6, None), (6..10, Some("b")), (10..12, None)]);
+        let replayed = ActionChain::from_recording(handle, chain.to_recording());
+        assert_eq!(replayed.paste_segments(0..12), chain.paste_segments(0..12));
+    }
+
+    #[test]
+    fn test_is_wheel_rejected() {
+        let error = |message: &str| {
+            WebDriverError::InvalidArgument(WebDriverErrorInfo::new(message.to_string()))
+        };
+        assert!(is_wheel_rejected(&error("Unsupported action source type: wheel")));
+        assert!(!is_wheel_rejected(&error("x is not an integer")));
+        let unknown = WebDriverError::UnknownCommand(WebDriverErrorInfo::new("wheel".to_string()));
+        assert!(!is_wheel_rejected(&unknown));
+    }
+
+    #[test]
+    fn test_key_down_repeating() {
+        let chain = ActionChain::new(handle())
+            .move_to(1, 2)
+            .key_down_repeating('a', 2, Duration::from_millis(30))
+            .key_up('a');
+        assert_eq!(
+            summary(&chain),
+            ["key: . kd:a p30 ku:a kd:a p30 ku:a kd:a ku:a", "pointer: m(1,2)@250 . . . . . . . .",]
+        );
+        let chain = ActionChain::new(handle()).key_down_repeating('a', 0, Duration::ZERO);
+        assert_eq!(summary(&chain)[0], "key: kd:a");
+    }
+
+    #[test]
+    fn test_pinch() {
+        let chain = ActionChain::new(handle())
+            .key_down('a')
+            .pinch(100, 50, 20, 61, Duration::from_millis(32))
+            .pinch(100, 50, 61, 20, Duration::ZERO);
+        assert_eq!(
+            summary(&chain),
+            [
+                "key: kd:a . . . . . . . . . .",
+                "pointer: . . . . . . . . . . .",
+                "pinch1: . m(90,50)@250 pd0 m(80,50)@16 m(70,50)@16 pu0 \
+                 m(70,50)@250 pd0 m(80,50)@0 m(90,50)@0 pu0",
+                "pinch2: . m(110,50)@250 pd0 m(121,50)@16 m(131,50)@16 pu0 \
+                 m(131,50)@250 pd0 m(120,50)@0 m(110,50)@0 pu0",
+            ]
+        );
+    }
+
+    fn recording() -> ActionRecording {
+        let mut key_actions = ActionSource::<KeyAction>::new("key", None);
+        let mut pointer_actions =
+            ActionSource::<PointerAction>::new("pointer", PointerActionType::Pen, None);
+        let mut wheel_actions = ActionSource::<WheelAction>::new("wheel", None);
+        pointer_actions.move_to_element_center(ElementId::from("a"));
+        pointer_actions.press_with_properties(PenProperties::with_pressure(0.5));
+        pointer_actions.move_to_element(ElementId::from("b"), 5, 5);
+        pointer_actions.release_button(MouseButton::Left);
+        key_actions.key_down('x');
+        key_actions.key_up('x');
+        wheel_actions.scroll_from_element(ElementId::from("a"), 0, 0, 0, 100);
+        key_actions.pause();
+        pointer_actions.pause();
+        pointer_actions.pause();
+        wheel_actions.pad_to(key_actions.len());
+        ActionRecording {
+            key_actions,
+            pointer_actions,
+            wheel_actions,
+            extra_pointers: Vec::new(),
+            scroll_targets: vec![ElementId::from("c")],
+            focus_targets: vec![(1, ElementId::from("d"))],
+            paste_targets: Vec::new(),
+            pointer_position: None,
+        }
+    }
+
+    #[test]
+    fn test_recording_round_trip() {
+        let recording = recording();
+        let json = serde_json::to_value(&recording).unwrap();
+        assert_eq!(json["pointer_actions"]["parameters"], json!({ "pointerType": "pen" }));
+        assert_eq!(
+            json["pointer_actions"]["actions"][1],
+            json!({ "type": "pointerDown", "button": 0, "duration": 0, "pressure": 0.5 })
+        );
+        let parsed: ActionRecording = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn test_recording_element_ids() {
+        let mut recording = recording();
+        let ids = ["a", "b", "c", "d"].map(ElementId::from);
+        assert_eq!(recording.element_ids(), ids);
+
+        let resolved = HashMap::from([(ids[0].clone(), ElementId::from("z"))]);
+        recording.replace_element_ids(&resolved);
+        assert_eq!(recording.element_ids(), ["z", "b", "c", "d"].map(ElementId::from));
+        let json = serde_json::to_value(&recording).unwrap();
+        let origin = json!({ "element-6066-11e4-a52e-4f735466cecf": "z" });
+        assert_eq!(json["pointer_actions"]["actions"][0]["origin"], origin);
+        assert_eq!(json["wheel_actions"]["actions"][0]["origin"], origin);
+    }
 }
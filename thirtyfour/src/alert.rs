@@ -1,8 +1,98 @@
 use crate::common::command::Command;
-use crate::error::WebDriverResult;
+use crate::error::{TimeoutErrorBuilder, WebDriverError, WebDriverErrorInner, WebDriverResult};
+use crate::extensions::query::{ElementPollerWithTimeout, IntoElementPoller};
 use crate::session::handle::SessionHandle;
 use crate::TypingData;
-use std::sync::Arc;
+use serde_json::Value;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The kind of user prompt that is currently open.
+///
+/// See [`Alert::prompt_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    /// A `window.alert()` or `window.confirm()` dialog.
+    ///
+    /// WebDriver does not provide a way to distinguish between these.
+    AlertOrConfirm,
+    /// A `window.prompt()` dialog, which accepts text input.
+    Prompt,
+    /// A `beforeunload` dialog asking whether to leave the page.
+    BeforeUnload,
+}
+
+/// The action to take for an alert that interrupted a command.
+///
+/// See `WebDriver::on_alert()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertDecision {
+    /// Leave the alert to the next handler.
+    Ignore,
+    /// Accept the alert.
+    Accept,
+    /// Dismiss the alert.
+    Dismiss,
+    /// Send the specified text to the prompt and then accept it.
+    SendKeysAndAccept(String),
+}
+
+/// Identifies an alert handler registered via `on_alert()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AlertHandlerId(u64);
+
+type AlertHandlerFn = Arc<dyn Fn(String) -> AlertDecision + Send + Sync>;
+
+/// The alert handlers registered for a session.
+#[derive(Default)]
+pub(crate) struct AlertHandlers {
+    next_id: AtomicU64,
+    handlers: Mutex<Vec<(AlertHandlerId, AlertHandlerFn)>>,
+}
+
+impl Debug for AlertHandlers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlertHandlers").field("len", &self.lock().len()).finish()
+    }
+}
+
+impl AlertHandlers {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(AlertHandlerId, AlertHandlerFn)>> {
+        self.handlers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn add(&self, handler: AlertHandlerFn) -> AlertHandlerId {
+        let id = AlertHandlerId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.lock().push((id, handler));
+        id
+    }
+
+    fn remove(&self, id: AlertHandlerId) -> bool {
+        let mut handlers = self.lock();
+        let len = handlers.len();
+        handlers.retain(|(x, _)| *x != id);
+        handlers.len() != len
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Consult the handlers, most recently registered first.
+    /// The first decision other than `Ignore` wins.
+    fn decide(&self, text: &str) -> AlertDecision {
+        // Clone the handlers so that a handler can register or remove handlers.
+        let handlers: Vec<AlertHandlerFn> = self.lock().iter().map(|(_, h)| h.clone()).collect();
+        handlers
+            .iter()
+            .rev()
+            .map(|h| h(text.to_string()))
+            .find(|d| *d != AlertDecision::Ignore)
+            .unwrap_or(AlertDecision::Ignore)
+    }
+}
 
 /// Struct for managing alerts.
 #[derive(Debug)]
@@ -12,7 +102,7 @@ pub struct Alert {
 
 impl Alert {
     /// Create a new Alert struct. This is typically created internally
-    /// via a call to `WebDriver::switch_to().alert()`.
+    /// via a call to `WebDriver::wait_for_alert()`.
     pub fn new(handle: Arc<SessionHandle>) -> Self {
         Self {
             handle,
@@ -47,6 +137,9 @@ impl Alert {
     }
 
     /// Send the specified text to the active alert if there is one.
+    ///
+    /// Only `window.prompt()` dialogs accept text. For other dialogs this returns
+    /// an `ElementNotInteractable` or `UnsupportedOperation` error.
     #[deprecated(
         since = "0.30.0",
         note = "This method has been moved to WebDriver::send_alert_text()"
@@ -54,6 +147,26 @@ impl Alert {
     pub async fn send_keys(&self, keys: impl Into<TypingData>) -> WebDriverResult<()> {
         self.handle.send_alert_text(keys.into()).await
     }
+
+    /// Send the specified text to the active prompt and then accept it.
+    pub async fn accept_with_text(&self, keys: impl Into<TypingData>) -> WebDriverResult<()> {
+        self.handle.accept_alert_with_text(keys).await
+    }
+
+    /// Get the kind of the active prompt.
+    ///
+    /// See [`SessionHandle::get_prompt_kind`] for details.
+    pub async fn prompt_kind(&self) -> WebDriverResult<PromptKind> {
+        self.handle.get_prompt_kind().await
+    }
+
+    /// Get the kind of the active prompt, leaving the specified text in the text field of
+    /// a `window.prompt()` dialog.
+    ///
+    /// See [`SessionHandle::get_prompt_kind_with_text`] for details.
+    pub async fn prompt_kind_with_text(&self, text: &str) -> WebDriverResult<PromptKind> {
+        self.handle.get_prompt_kind_with_text(text).await
+    }
 }
 
 impl SessionHandle {
@@ -163,4 +276,268 @@ impl SessionHandle {
         self.cmd(Command::SendAlertText(keys.into())).await?;
         Ok(())
     }
+
+    /// Send the specified keys to the active prompt and then accept it.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.accept_alert_with_text("selenium").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn accept_alert_with_text(&self, keys: impl Into<TypingData>) -> WebDriverResult<()> {
+        self.send_alert_text(keys).await?;
+        self.accept_alert().await
+    }
+
+    /// Get the kind of the active prompt.
+    ///
+    /// This is a best-effort classification based on how the driver responds to
+    /// sending text to the prompt, as described in the WebDriver spec.
+    /// Alerts and confirm dialogs cannot be distinguished from one another.
+    ///
+    /// **NOTE**: Classifying a `window.prompt()` dialog requires sending it text, so this
+    /// clears any default value in its text field, and accepting the prompt afterwards
+    /// returns an empty string. WebDriver cannot read the default value back, so if it is
+    /// known, use [`SessionHandle::get_prompt_kind_with_text`] to put it back instead.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::alert::PromptKind;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// if driver.get_prompt_kind().await? == PromptKind::Prompt {
+    ///     driver.accept_alert_with_text("selenium").await?;
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn get_prompt_kind(&self) -> WebDriverResult<PromptKind> {
+        self.get_prompt_kind_with_text("").await
+    }
+
+    /// Get the kind of the active prompt, as [`SessionHandle::get_prompt_kind`] does, but
+    /// classify a `window.prompt()` dialog by sending it the specified text.
+    ///
+    /// Pass the default value the page gave the prompt, to leave its text field as it was.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::alert::PromptKind;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// // The page called `prompt("Your name?", "guest")`.
+    /// if driver.get_prompt_kind_with_text("guest").await? == PromptKind::Prompt {
+    ///     driver.accept_alert().await?;
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn get_prompt_kind_with_text(&self, text: &str) -> WebDriverResult<PromptKind> {
+        match self.send_alert_text(text).await {
+            Ok(()) => Ok(PromptKind::Prompt),
+            Err(e) => match e.as_inner() {
+                WebDriverErrorInner::ElementNotInteractable(_) => Ok(PromptKind::AlertOrConfirm),
+                WebDriverErrorInner::UnsupportedOperation(_) => Ok(PromptKind::BeforeUnload),
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Return true if an alert is currently open.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// if driver.has_alert().await? {
+    ///     driver.dismiss_alert().await?;
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn has_alert(&self) -> WebDriverResult<bool> {
+        match self.report_errors(self.get_alert_text()).await {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.as_inner(), WebDriverErrorInner::NoSuchAlert(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Register a handler for alerts that interrupt a command.
+    ///
+    /// Whenever a command fails with an `UnexpectedAlertOpen` error, the alert text is
+    /// passed to the registered handlers, most recently registered first. The first
+    /// decision other than [`AlertDecision::Ignore`] is performed and the original
+    /// command is retried once. If every handler ignores the alert, the original error
+    /// is returned.
+    ///
+    /// Handlers are shared by all clones of this session and stay registered until
+    /// removed via `remove_alert_handler()`.
+    ///
+    /// **NOTE**: With the default `unhandledPromptBehavior` of "dismiss and notify",
+    ///       the driver has already dismissed the alert by the time the handler runs.
+    ///       Set the capability to "ignore" for the decision to take effect.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::alert::AlertDecision;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let id = driver.on_alert(|text| {
+    ///     if text.contains("Session expiring") {
+    ///         AlertDecision::Accept
+    ///     } else {
+    ///         AlertDecision::Ignore
+    ///     }
+    /// });
+    /// driver.find(By::Id("button")).await?.click().await?;
+    /// driver.remove_alert_handler(id);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn on_alert<F>(&self, handler: F) -> AlertHandlerId
+    where
+        F: Fn(String) -> AlertDecision + Send + Sync + 'static,
+    {
+        self.alert_handlers().add(Arc::new(handler))
+    }
+
+    /// Remove an alert handler registered via `on_alert()`.
+    ///
+    /// Returns false if the handler was already removed.
+    pub fn remove_alert_handler(&self, id: AlertHandlerId) -> bool {
+        self.alert_handlers().remove(id)
+    }
+
+    /// Consult the alert handlers about the alert that caused the specified error,
+    /// and perform the resulting decision.
+    ///
+    /// Returns true if the alert was handled and the command should be retried.
+    pub(crate) async fn handle_unexpected_alert(
+        &self,
+        error: &WebDriverError,
+    ) -> WebDriverResult<bool> {
+        if self.alert_handlers().is_empty() {
+            return Ok(false);
+        }
+
+        let text = match error.as_inner() {
+            WebDriverErrorInner::UnexpectedAlertOpen(info) => info
+                .value
+                .data
+                .as_ref()
+                .and_then(|data| data.get("text"))
+                .and_then(Value::as_str)
+                .map(ToString::to_string),
+            _ => return Ok(false),
+        };
+        let text = match text {
+            Some(text) => text,
+            None => match self.cmd_unhandled(Command::GetAlertText).await {
+                Ok(resp) => resp.value::<String>()?,
+                Err(e) if matches!(e.as_inner(), WebDriverErrorInner::NoSuchAlert(_)) => {
+                    return Ok(false)
+                }
+                Err(e) => return Err(e),
+            },
+        };
+
+        let result = match self.alert_handlers().decide(&text) {
+            AlertDecision::Ignore => return Ok(false),
+            AlertDecision::Accept => self.cmd_unhandled(Command::AcceptAlert).await,
+            AlertDecision::Dismiss => self.cmd_unhandled(Command::DismissAlert).await,
+            AlertDecision::SendKeysAndAccept(keys) => {
+                match self.cmd_unhandled(Command::SendAlertText(keys.into())).await {
+                    Ok(_) => self.cmd_unhandled(Command::AcceptAlert).await,
+                    Err(e) => Err(e),
+                }
+            }
+        };
+        match result {
+            Ok(_) => Ok(true),
+            // The driver may already have closed the alert, depending on the
+            // `unhandledPromptBehavior` capability.
+            Err(e) if matches!(e.as_inner(), WebDriverErrorInner::NoSuchAlert(_)) => Ok(true),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Wait for an alert to open, up to the specified timeout.
+    ///
+    /// Returns a `Timeout` error if no alert opened within the timeout.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.wait_for_alert(Duration::from_secs(5)).await?;
+    /// assert_eq!(driver.get_alert_text().await?, "Are you sure?");
+    /// driver.accept_alert().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn wait_for_alert(self: &Arc<Self>, timeout: Duration) -> WebDriverResult<Alert> {
+        let mut wait = TimeoutErrorBuilder::new("an alert to open");
+        let mut poller = ElementPollerWithTimeout::new(timeout, Duration::from_millis(500)).start();
+        let poll = async {
+            loop {
+                wait.attempt();
+                if self.has_alert().await? {
+                    return Ok(Alert::new(self.clone()));
+                }
+                wait.observed("no alert");
+
+                if !poller.tick().await {
+                    return Err(wait.build());
+                }
+            }
+        };
+        self.report_errors(poll).await
+    }
 }
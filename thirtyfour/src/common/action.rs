@@ -1,5 +1,6 @@
-use serde::Serialize;
-use serde_repr::Serialize_repr;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::ops::Range;
 use std::time::Duration;
 
 use crate::common::{keys::TypingData, types::ElementId};
@@ -30,7 +31,7 @@ impl Action for NullAction {
 }
 
 /// Key Action.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum KeyAction {
     /// Pause action.
@@ -59,7 +60,7 @@ impl Action for KeyAction {
 }
 
 /// Mouse Button.
-#[derive(Debug, Clone, Serialize_repr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
 #[repr(u8)]
 pub enum MouseButton {
     /// Left mouse button.
@@ -68,10 +69,14 @@ pub enum MouseButton {
     Middle = 1,
     /// Right mouse button.
     Right = 2,
+    /// Back mouse button, usually on the side of the mouse.
+    Back = 3,
+    /// Forward mouse button, usually on the side of the mouse.
+    Forward = 4,
 }
 
 /// Pointer Origin.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PointerOrigin {
     /// Pointer origin is the viewport.
@@ -83,8 +88,53 @@ pub enum PointerOrigin {
     WebElement(ElementId),
 }
 
+impl PointerOrigin {
+    /// The id of the element, if the origin is a WebElement.
+    fn element_id_mut(&mut self) -> Option<&mut ElementId> {
+        match self {
+            PointerOrigin::WebElement(element_id) => Some(element_id),
+            _ => None,
+        }
+    }
+}
+
+/// Pen properties for pointer down and move actions.
+///
+/// Properties that are `None` are left out, so the driver uses its defaults. Mouse
+/// pointers ignore these properties.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PenProperties {
+    /// The normalized pressure of the pen, from 0.0 to 1.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<f64>,
+    /// The normalized tangential pressure, such as from an airbrush barrel wheel, from
+    /// -1.0 to 1.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tangential_pressure: Option<f64>,
+    /// The tilt of the pen along the X axis, from -90 to 90 degrees.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tilt_x: Option<i64>,
+    /// The tilt of the pen along the Y axis, from -90 to 90 degrees.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tilt_y: Option<i64>,
+    /// The clockwise rotation of the pen around its own axis, from 0 to 359 degrees.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub twist: Option<u16>,
+}
+
+impl PenProperties {
+    /// Pen properties with the specified pressure.
+    pub fn with_pressure(pressure: f64) -> Self {
+        Self {
+            pressure: Some(pressure),
+            ..Default::default()
+        }
+    }
+}
+
 /// Pointer Action.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum PointerAction {
     /// Pause action.
@@ -98,6 +148,9 @@ pub enum PointerAction {
         button: MouseButton,
         /// Duration of the action in milliseconds.
         duration: u64,
+        /// The pen properties, such as pressure.
+        #[serde(flatten)]
+        properties: PenProperties,
     },
     /// Pointer up action.
     PointerUp {
@@ -116,6 +169,9 @@ pub enum PointerAction {
         x: i64,
         /// The y coordinate to move to.
         y: i64,
+        /// The pen properties, such as pressure.
+        #[serde(flatten)]
+        properties: PenProperties,
     },
     /// Pointer cancel action.
     PointerCancel,
@@ -129,8 +185,44 @@ impl Action for PointerAction {
     }
 }
 
+/// Wheel Action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WheelAction {
+    /// Pause action.
+    Pause {
+        /// Duration of the pause in milliseconds.
+        duration: u64,
+    },
+    /// Scroll action.
+    Scroll {
+        /// Duration of the scroll in milliseconds.
+        duration: u64,
+        /// The scroll origin. This can be the viewport or a WebElement, but not the pointer.
+        origin: PointerOrigin,
+        /// The x coordinate to scroll at, relative to the origin.
+        x: i64,
+        /// The y coordinate to scroll at, relative to the origin.
+        y: i64,
+        /// The distance to scroll right, or left if negative.
+        #[serde(rename = "deltaX")]
+        delta_x: i64,
+        /// The distance to scroll down, or up if negative.
+        #[serde(rename = "deltaY")]
+        delta_y: i64,
+    },
+}
+
+impl Action for WheelAction {
+    fn get_pause(duration_ms: u64) -> Self {
+        WheelAction::Pause {
+            duration: duration_ms,
+        }
+    }
+}
+
 /// Parameters for Pointer Actions.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PointerParameters {
     /// The type of pointer.
@@ -138,12 +230,12 @@ pub struct PointerParameters {
 }
 
 /// Action Source.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionSource<T: Action + Clone> {
     /// The ID of the action source.
     id: String,
     /// The type of action source.
-    #[serde(rename(serialize = "type"))]
+    #[serde(rename = "type")]
     action_type: String,
     /// Parameters for the action source.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -151,8 +243,11 @@ pub struct ActionSource<T: Action + Clone> {
     /// The actions to perform.
     actions: Vec<T>,
     /// The duration of the action source in milliseconds.
-    #[serde(skip_serializing)]
+    #[serde(skip)]
     duration: u64,
+    /// The duration to use for the next action instead, in milliseconds.
+    #[serde(skip)]
+    next_duration: Option<u64>,
 }
 
 impl<T> ActionSource<T>
@@ -160,8 +255,25 @@ where
     T: Action + Clone,
 {
     /// Add the specified action to this action source.
+    ///
+    /// This also clears the duration set with `set_next_duration()`.
     pub fn add_action(&mut self, action: T) {
         self.actions.push(action);
+        self.next_duration = None;
+    }
+
+    /// Use the specified duration in milliseconds for the next action, instead of the
+    /// duration of this action source.
+    ///
+    /// Only actions that take the duration of the source, such as moves, use it, but
+    /// any action other than a pause clears it.
+    pub fn set_next_duration(&mut self, duration_ms: u64) {
+        self.next_duration = Some(duration_ms);
+    }
+
+    /// The duration in milliseconds for the next action.
+    fn next_duration(&self) -> u64 {
+        self.next_duration.unwrap_or(self.duration)
     }
 
     /// Add a pause action so this action source.
@@ -178,6 +290,46 @@ where
     pub fn id(&self) -> &str {
         &self.id
     }
+
+    /// Get the number of actions in this action source.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Return true if this action source has no actions.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Remove all actions from this action source.
+    pub fn clear(&mut self) {
+        self.actions.clear();
+    }
+
+    /// Add pause actions until this action source has the specified number of actions.
+    pub fn pad_to(&mut self, len: usize) {
+        while self.actions.len() < len {
+            self.pause();
+        }
+    }
+
+    /// Get the pointer type of this action source, if it is a pointer source.
+    pub fn pointer_type(&self) -> Option<&str> {
+        self.parameters.as_ref().map(|x| x.pointer_type.as_str())
+    }
+
+    /// Append the actions of the specified action source to this action source.
+    pub fn append(&mut self, other: Self) {
+        self.actions.extend(other.actions);
+    }
+
+    /// A copy of this action source with only the actions in the specified range.
+    pub(crate) fn slice(&self, range: Range<usize>) -> Self {
+        Self {
+            actions: self.actions[range].to_vec(),
+            ..self.clone()
+        }
+    }
 }
 
 impl ActionSource<KeyAction> {
@@ -200,6 +352,7 @@ impl ActionSource<KeyAction> {
             parameters: None,
             actions: Vec::new(),
             duration,
+            next_duration: None,
         }
     }
 
@@ -217,6 +370,24 @@ impl ActionSource<KeyAction> {
         });
     }
 
+    /// Get the keys that are held down at the end of this action source, in the order
+    /// they were pressed.
+    pub fn held_keys(&self) -> Vec<char> {
+        let mut held = Vec::new();
+        for action in &self.actions {
+            match *action {
+                KeyAction::KeyDown {
+                    value,
+                } if !held.contains(&value) => held.push(value),
+                KeyAction::KeyUp {
+                    value,
+                } => held.retain(|&x| x != value),
+                _ => {}
+            }
+        }
+        held
+    }
+
     /// Send multiple keys as a string of Key Up and Key Down actions.
     pub fn send_keys(&mut self, text: TypingData) {
         for c in text.as_vec() {
@@ -263,46 +434,114 @@ impl ActionSource<PointerAction> {
             }),
             actions: Vec::new(),
             duration,
+            next_duration: None,
         }
     }
 
     /// Add a move action to the specified coordinates.
     pub fn move_to(&mut self, x: i64, y: i64) {
         self.add_action(PointerAction::PointerMove {
-            duration: self.duration,
+            duration: self.next_duration(),
             origin: PointerOrigin::Viewport,
             x,
             y,
+            properties: PenProperties::default(),
         });
     }
 
+    /// Add `steps` move actions from the start to the end coordinates, in equal steps
+    /// taking the specified duration in total.
+    ///
+    /// The coordinates of each step are rounded to the nearest pixel, and the last step is
+    /// exactly at the end. With fewer than 2 steps, this adds a single move to the end.
+    pub fn move_smooth(
+        &mut self,
+        start: (i64, i64),
+        end: (i64, i64),
+        steps: u32,
+        duration: Duration,
+    ) {
+        let total_ms = u64::try_from(duration.as_millis()).ok().unwrap_or(u64::MAX);
+        if steps < 2 {
+            self.add_action(PointerAction::PointerMove {
+                duration: total_ms,
+                origin: PointerOrigin::Viewport,
+                x: end.0,
+                y: end.1,
+                properties: PenProperties::default(),
+            });
+            return;
+        }
+        let steps = u64::from(steps);
+        let lerp = |from: i64, to: i64, step: u64| {
+            from + ((to - from) as f64 * step as f64 / steps as f64).round() as i64
+        };
+        // Each step ends at its share of the total, so the durations add up exactly.
+        let elapsed =
+            |step: u64| (u128::from(total_ms) * u128::from(step) / u128::from(steps)) as u64;
+        for step in 1..=steps {
+            self.add_action(PointerAction::PointerMove {
+                duration: elapsed(step) - elapsed(step - 1),
+                origin: PointerOrigin::Viewport,
+                x: lerp(start.0, end.0, step),
+                y: lerp(start.1, end.1, step),
+                properties: PenProperties::default(),
+            });
+        }
+    }
+
     /// Add a move action by the specified coordinates.
     pub fn move_by(&mut self, x: i64, y: i64) {
         self.add_action(PointerAction::PointerMove {
-            duration: self.duration,
+            duration: self.next_duration(),
             origin: PointerOrigin::Pointer,
             x,
             y,
+            properties: PenProperties::default(),
         });
     }
 
     /// Add a move action to the specified coordinates relative to the element.
     pub fn move_to_element(&mut self, element_id: ElementId, x: i64, y: i64) {
         self.add_action(PointerAction::PointerMove {
-            duration: self.duration,
+            duration: self.next_duration(),
             origin: PointerOrigin::WebElement(element_id),
             x,
             y,
+            properties: PenProperties::default(),
         });
     }
 
     /// Add a move action to the center of the specified element.
     pub fn move_to_element_center(&mut self, element_id: ElementId) {
         self.add_action(PointerAction::PointerMove {
-            duration: self.duration,
+            duration: self.next_duration(),
             origin: PointerOrigin::WebElement(element_id),
             x: 0,
             y: 0,
+            properties: PenProperties::default(),
+        });
+    }
+
+    /// Add a move action to the specified coordinates, with the specified pen properties.
+    pub fn move_to_with_properties(&mut self, x: i64, y: i64, properties: PenProperties) {
+        self.add_action(PointerAction::PointerMove {
+            duration: self.next_duration(),
+            origin: PointerOrigin::Viewport,
+            x,
+            y,
+            properties,
+        });
+    }
+
+    /// Add a press action with the specified pen properties.
+    ///
+    /// For a pen or touch pointer, this puts the pointer in contact with the surface.
+    pub fn press_with_properties(&mut self, properties: PenProperties) {
+        self.add_action(PointerAction::PointerDown {
+            button: MouseButton::Left,
+            duration: 0,
+            properties,
         });
     }
 
@@ -311,6 +550,7 @@ impl ActionSource<PointerAction> {
         self.add_action(PointerAction::PointerDown {
             button: MouseButton::Left,
             duration: 0,
+            properties: PenProperties::default(),
         });
         self.add_action(PointerAction::PointerUp {
             button: MouseButton::Left,
@@ -320,12 +560,18 @@ impl ActionSource<PointerAction> {
 
     /// Add a right-click action.
     pub fn context_click(&mut self) {
+        self.click_button(MouseButton::Right);
+    }
+
+    /// Add a click action with the specified mouse button.
+    pub fn click_button(&mut self, button: MouseButton) {
         self.add_action(PointerAction::PointerDown {
-            button: MouseButton::Right,
+            button,
             duration: 0,
+            properties: PenProperties::default(),
         });
         self.add_action(PointerAction::PointerUp {
-            button: MouseButton::Right,
+            button,
             duration: 0,
         });
     }
@@ -335,6 +581,7 @@ impl ActionSource<PointerAction> {
         self.add_action(PointerAction::PointerDown {
             button: MouseButton::Left,
             duration: 0,
+            properties: PenProperties::default(),
         });
     }
 
@@ -352,12 +599,58 @@ impl ActionSource<PointerAction> {
         });
     }
 
+    /// Add a release action for the specified button.
+    pub fn release_button(&mut self, button: MouseButton) {
+        self.add_action(PointerAction::PointerUp {
+            button,
+            duration: 0,
+        });
+    }
+
+    /// Get the buttons that are held down at the end of this action source, in the order
+    /// they were pressed.
+    pub fn held_buttons(&self) -> Vec<MouseButton> {
+        let mut held = Vec::new();
+        for action in &self.actions {
+            match *action {
+                PointerAction::PointerDown {
+                    button,
+                    ..
+                } if !held.contains(&button) => held.push(button),
+                PointerAction::PointerUp {
+                    button,
+                    ..
+                } => held.retain(|&x| x != button),
+                _ => {}
+            }
+        }
+        held
+    }
+
+    /// The ids of the elements used as the origin of moves, in order.
+    pub(crate) fn element_ids_mut(&mut self) -> impl Iterator<Item = &mut ElementId> {
+        self.actions.iter_mut().filter_map(|action| match action {
+            PointerAction::PointerMove {
+                origin,
+                ..
+            } => origin.element_id_mut(),
+            _ => None,
+        })
+    }
+
     /// Add a double-click action.
     pub fn double_click(&mut self) {
         self.click();
         self.click();
     }
 
+    /// Add the specified number of click actions, with no moves or pauses between them.
+    pub fn click_n_times(&mut self, n: u32) {
+        for _ in 0..n {
+            self.click();
+        }
+    }
+
     /// Add a double-click action on the specified element.
     pub fn double_click_element(&mut self, element_id: ElementId) {
         self.move_to_element_center(element_id);
@@ -365,10 +658,87 @@ impl ActionSource<PointerAction> {
     }
 }
 
+impl ActionSource<WheelAction> {
+    /// Create a new Wheel action source.
+    ///
+    /// Duration represents the time taken by each scroll.
+    /// Defaults to 0ms
+    pub fn new(name: &str, duration: Option<Duration>) -> Self {
+        let duration = match duration {
+            Some(duration) => {
+                let millis = duration.as_millis();
+                u64::try_from(millis).ok().unwrap_or(u64::MAX)
+            }
+            None => 0,
+        };
+
+        ActionSource {
+            id: name.to_owned(),
+            action_type: String::from("wheel"),
+            parameters: None,
+            actions: Vec::new(),
+            duration,
+            next_duration: None,
+        }
+    }
+
+    /// Add a scroll action by the specified deltas, at the specified coordinates in the
+    /// viewport.
+    pub fn scroll(&mut self, x: i64, y: i64, delta_x: i64, delta_y: i64) {
+        self.add_action(WheelAction::Scroll {
+            duration: self.next_duration(),
+            origin: PointerOrigin::Viewport,
+            x,
+            y,
+            delta_x,
+            delta_y,
+        });
+    }
+
+    /// Add a scroll action by the specified deltas, at the specified coordinates relative
+    /// to the center of the element.
+    pub fn scroll_from_element(
+        &mut self,
+        element_id: ElementId,
+        x: i64,
+        y: i64,
+        delta_x: i64,
+        delta_y: i64,
+    ) {
+        self.add_action(WheelAction::Scroll {
+            duration: self.next_duration(),
+            origin: PointerOrigin::WebElement(element_id),
+            x,
+            y,
+            delta_x,
+            delta_y,
+        });
+    }
+
+    /// The indices of the scroll actions, in order.
+    pub(crate) fn scroll_indices(&self) -> Vec<usize> {
+        let scrolls = self.actions.iter().enumerate();
+        scrolls.filter(|(_, x)| matches!(x, WheelAction::Scroll { .. })).map(|(i, _)| i).collect()
+    }
+
+    /// The ids of the elements used as the origin of scrolls, in order.
+    pub(crate) fn element_ids_mut(&mut self) -> impl Iterator<Item = &mut ElementId> {
+        self.actions.iter_mut().filter_map(|action| match action {
+            WheelAction::Scroll {
+                origin,
+                ..
+            } => origin.element_id_mut(),
+            _ => None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
 
+    use crate::common::keys::Key;
+
     use super::*;
 
     fn compare_null_action(action: NullAction, value: serde_json::Value) {
@@ -379,6 +749,7 @@ mod tests {
             parameters: None,
             actions,
             duration: 0,
+            next_duration: None,
         };
 
         let value_got = serde_json::to_value(source);
@@ -426,6 +797,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_held_keys_and_buttons() {
+        let mut keys = ActionSource::<KeyAction>::new("key", None);
+        keys.key_down(Key::Control.into());
+        keys.key_down(Key::Shift.into());
+        keys.send_keys(TypingData::from("a"));
+        keys.key_down(Key::Control.into());
+        assert_eq!(keys.held_keys(), [char::from(Key::Control), char::from(Key::Shift)]);
+        keys.key_up(Key::Control.into());
+        assert_eq!(keys.held_keys(), [char::from(Key::Shift)]);
+
+        let mut pointer =
+            ActionSource::<PointerAction>::new("pointer", PointerActionType::Mouse, None);
+        pointer.click_and_hold();
+        pointer.click_button(MouseButton::Right);
+        pointer.add_action(PointerAction::PointerDown {
+            button: MouseButton::Middle,
+            duration: 0,
+            properties: PenProperties::default(),
+        });
+        assert_eq!(pointer.held_buttons(), [MouseButton::Left, MouseButton::Middle]);
+        pointer.release();
+        assert_eq!(pointer.held_buttons(), [MouseButton::Middle]);
+    }
+
+    #[test]
+    fn test_next_duration() {
+        let mut pointer =
+            ActionSource::<PointerAction>::new("pointer", PointerActionType::Mouse, None);
+        pointer.set_next_duration(40);
+        pointer.pause();
+        pointer.move_to(1, 1);
+        pointer.move_to(2, 2);
+        pointer.set_next_duration(40);
+        pointer.click();
+        pointer.move_by(3, 3);
+        let durations: Vec<_> = serde_json::to_value(&pointer).unwrap()["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["duration"].as_u64().unwrap())
+            .collect();
+        assert_eq!(durations, [0, 40, 250, 0, 0, 250]);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut first = ActionSource::<PointerAction>::new("pointer", PointerActionType::Pen, None);
+        first.move_to(1, 2);
+        let mut second = first.clone();
+        second.clear();
+        second.pause();
+        second.click();
+        first.append(second);
+        assert_eq!(first.len(), 4);
+        assert_eq!(first.pointer_type(), Some("pen"));
+        assert_eq!(ActionSource::<KeyAction>::new("key", None).pointer_type(), None);
+    }
+
     #[test]
     fn test_key_action_pause() {
         compare_key_action(
@@ -521,6 +951,7 @@ mod tests {
             PointerAction::PointerDown {
                 button: MouseButton::Left,
                 duration: 0,
+                properties: PenProperties::default(),
             },
             json!({"type": "pointerDown", "button": 0, "duration": 0}),
         );
@@ -529,6 +960,7 @@ mod tests {
             PointerAction::PointerDown {
                 button: MouseButton::Middle,
                 duration: 0,
+                properties: PenProperties::default(),
             },
             json!({"type": "pointerDown", "button": 1, "duration": 0}),
         );
@@ -537,10 +969,28 @@ mod tests {
             PointerAction::PointerDown {
                 button: MouseButton::Right,
                 duration: 0,
+                properties: PenProperties::default(),
             },
             json!({"type": "pointerDown", "button": 2, "duration": 0}),
         );
 
+        compare_pointer_action(
+            PointerAction::PointerDown {
+                button: MouseButton::Back,
+                duration: 0,
+                properties: PenProperties::default(),
+            },
+            json!({"type": "pointerDown", "button": 3, "duration": 0}),
+        );
+
+        compare_pointer_action(
+            PointerAction::PointerUp {
+                button: MouseButton::Forward,
+                duration: 0,
+            },
+            json!({"type": "pointerUp", "button": 4, "duration": 0}),
+        );
+
         compare_pointer_action(
             PointerAction::PointerUp {
                 button: MouseButton::Left,
@@ -574,6 +1024,7 @@ mod tests {
                 x: 0,
                 y: 0,
                 origin: PointerOrigin::Viewport,
+                properties: PenProperties::default(),
             },
             json!({
             "type": "pointerMove", "origin": "viewport", "x": 0, "y": 0, "duration": 0
@@ -586,6 +1037,7 @@ mod tests {
                 x: 0,
                 y: 0,
                 origin: PointerOrigin::Pointer,
+                properties: PenProperties::default(),
             },
             json!({
             "type": "pointerMove", "origin": "pointer", "x": 0, "y": 0, "duration": 0
@@ -598,6 +1050,7 @@ mod tests {
                 x: 0,
                 y: 0,
                 origin: PointerOrigin::WebElement(ElementId::from("id1234")),
+                properties: PenProperties::default(),
             },
             json!({
             "type": "pointerMove", "origin": {"element-6066-11e4-a52e-4f735466cecf": "id1234"}, "x": 0, "y": 0, "duration": 0
@@ -610,6 +1063,7 @@ mod tests {
                 x: 100,
                 y: 200,
                 origin: PointerOrigin::Viewport,
+                properties: PenProperties::default(),
             },
             json!({
                 "type": "pointerMove",
@@ -626,6 +1080,7 @@ mod tests {
                 x: 100,
                 y: 200,
                 origin: PointerOrigin::Pointer,
+                properties: PenProperties::default(),
             },
             json!({
                 "type": "pointerMove",
@@ -642,6 +1097,7 @@ mod tests {
                 x: 100,
                 y: 200,
                 origin: PointerOrigin::WebElement(ElementId::from("someid")),
+                properties: PenProperties::default(),
             },
             json!({
                 "type": "pointerMove",
@@ -657,4 +1113,141 @@ mod tests {
     fn test_pointer_action_cancel() {
         compare_pointer_action(PointerAction::PointerCancel, json!({"type": "pointerCancel"}));
     }
+
+    #[test]
+    fn test_pen_properties() {
+        let mut source = ActionSource::<PointerAction>::new("pen", PointerActionType::Pen, None);
+        source.move_to_with_properties(
+            10,
+            20,
+            PenProperties {
+                tilt_x: Some(-30),
+                tilt_y: Some(45),
+                twist: Some(90),
+                ..Default::default()
+            },
+        );
+        source.press_with_properties(PenProperties::with_pressure(0.5));
+        source.release();
+
+        assert_eq!(
+            serde_json::to_value(source).unwrap(),
+            json!({
+                "id": "pen",
+                "type": "pointer",
+                "parameters": {
+                    "pointerType": "pen"
+                },
+                "actions": [
+                    {
+                        "type": "pointerMove",
+                        "x": 10,
+                        "y": 20,
+                        "duration": 250,
+                        "origin": "viewport",
+                        "tiltX": -30,
+                        "tiltY": 45,
+                        "twist": 90
+                    },
+                    {"type": "pointerDown", "button": 0, "duration": 0, "pressure": 0.5},
+                    {"type": "pointerUp", "button": 0, "duration": 0}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_pad_to() {
+        let mut source =
+            ActionSource::<PointerAction>::new("finger", PointerActionType::Touch, None);
+        assert!(source.is_empty());
+        source.pad_to(2);
+        source.move_to(1, 2);
+        source.pad_to(2);
+        assert_eq!(source.len(), 3);
+        source.pad_to(4);
+        let padded = source.clone();
+        source.clear();
+        assert!(source.is_empty());
+        let source = padded;
+
+        let value = serde_json::to_value(source).unwrap();
+        let types: Vec<_> =
+            value["actions"].as_array().unwrap().iter().map(|x| &x["type"]).collect();
+        assert_eq!(types, ["pause", "pause", "pointerMove", "pause"]);
+        assert_eq!(value["parameters"], json!({"pointerType": "touch"}));
+    }
+
+    #[test]
+    fn test_move_smooth() {
+        let moves = |start, end, steps, duration_ms| {
+            let mut source =
+                ActionSource::<PointerAction>::new("mouse", PointerActionType::Mouse, None);
+            source.move_smooth(start, end, steps, Duration::from_millis(duration_ms));
+            let value = serde_json::to_value(source).unwrap();
+            value["actions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|x| {
+                    (
+                        x["x"].as_i64().unwrap(),
+                        x["y"].as_i64().unwrap(),
+                        x["duration"].as_u64().unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            moves((0, 0), (10, -5), 3, 100),
+            [(3, -2), (7, -3), (10, -5)]
+                .iter()
+                .zip([33, 33, 34])
+                .map(|(&(x, y), d)| (x, y, d))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            moves((100, 50), (0, 50), 4, 0),
+            [(75, 50, 0), (50, 50, 0), (25, 50, 0), (0, 50, 0)]
+        );
+        assert_eq!(moves((5, 5), (7, 9), 1, 250), [(7, 9, 250)]);
+        assert_eq!(moves((5, 5), (7, 9), 0, 250), [(7, 9, 250)]);
+    }
+
+    #[test]
+    fn test_wheel_action() {
+        let mut source = ActionSource::<WheelAction>::new("wheel", None);
+        source.pause();
+        source.scroll(10, 20, 0, 300);
+        source.scroll_from_element(ElementId::from("someid"), 0, 0, -5, 0);
+
+        assert_eq!(
+            serde_json::to_value(source).unwrap(),
+            json!({
+                "id": "wheel",
+                "type": "wheel",
+                "actions": [
+                    {"type": "pause", "duration": 0},
+                    {
+                        "type": "scroll",
+                        "x": 10,
+                        "y": 20,
+                        "deltaX": 0,
+                        "deltaY": 300,
+                        "duration": 0,
+                        "origin": "viewport"
+                    },
+                    {
+                        "type": "scroll",
+                        "x": 0,
+                        "y": 0,
+                        "deltaX": -5,
+                        "deltaY": 0,
+                        "duration": 0,
+                        "origin": {"element-6066-11e4-a52e-4f735466cecf": "someid"}
+                    }
+                ]
+            })
+        );
+    }
 }
@@ -0,0 +1,150 @@
+use crate::extensions::addons::firefox::PrefValue;
+
+/// Accessibility settings to start the browser with, for
+/// `FirefoxCapabilities::apply_a11y_profile()` and
+/// `ChromiumLikeCapabilities::apply_a11y_profile()`.
+///
+/// Each setting that is `false` is left as the browser's default, rather than being
+/// turned off.
+///
+/// Not every setting is available in every browser:
+///
+/// | Setting          | Firefox                                  | Chromium-based browsers                         |
+/// |------------------|------------------------------------------|-------------------------------------------------|
+/// | `forced_colors`  | `browser.display.document_color_use` = 2 | `--force-high-contrast`                         |
+/// | `reduced_motion` | `ui.prefersReducedMotion` = 1            | `--force-prefers-reduced-motion`                |
+/// | `caret_browsing` | `accessibility.browsewithcaret`          | `settings.a11y.caretbrowsing.enabled` (profile) |
+/// | `reader_mode`    | `reader.parse-on-load.force-enabled`     | Not available, ignored                          |
+///
+/// ## Example
+/// ```no_run
+/// use thirtyfour::{A11yProfile, DesiredCapabilities};
+/// let mut caps = DesiredCapabilities::firefox();
+/// caps.apply_a11y_profile(A11yProfile {
+///     forced_colors: true,
+///     reduced_motion: true,
+///     ..Default::default()
+/// })
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct A11yProfile {
+    /// Replace the colors of pages with the user's colors, so that the `forced-colors`
+    /// media query matches `active`.
+    pub forced_colors: bool,
+    /// Ask pages to minimize animations, so that the `prefers-reduced-motion` media
+    /// query matches `reduce`.
+    pub reduced_motion: bool,
+    /// Show a movable caret in pages, for navigating text with the keyboard.
+    pub caret_browsing: bool,
+    /// Offer reader view on every page, rather than only on pages that look like
+    /// articles. Firefox only.
+    pub reader_mode: bool,
+}
+
+impl A11yProfile {
+    /// The Firefox preferences for this profile.
+    pub(crate) fn firefox_prefs(&self) -> Vec<(&'static str, PrefValue)> {
+        let mut prefs = Vec::new();
+        if self.forced_colors {
+            // 2 means always use the user's colors rather than the page's.
+            prefs.push(("browser.display.document_color_use", PrefValue::Int(2)));
+        }
+        if self.reduced_motion {
+            prefs.push(("ui.prefersReducedMotion", PrefValue::Int(1)));
+        }
+        if self.caret_browsing {
+            prefs.push(("accessibility.browsewithcaret", PrefValue::Bool(true)));
+        }
+        if self.reader_mode {
+            prefs.push(("reader.parse-on-load.force-enabled", PrefValue::Bool(true)));
+        }
+        prefs
+    }
+
+    /// The command-line arguments for Chromium-based browsers for this profile.
+    pub(crate) fn chromium_args(&self) -> Vec<&'static str> {
+        let mut args = Vec::new();
+        if self.forced_colors {
+            args.push("--force-high-contrast");
+        }
+        if self.reduced_motion {
+            args.push("--force-prefers-reduced-motion");
+        }
+        args
+    }
+
+    /// The profile preferences for Chromium-based browsers for this profile.
+    pub(crate) fn chromium_prefs(&self) -> Vec<(&'static str, PrefValue)> {
+        let mut prefs = Vec::new();
+        if self.caret_browsing {
+            prefs.push(("settings.a11y.caretbrowsing.enabled", PrefValue::Bool(true)));
+        }
+        prefs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ChromiumLikeCapabilities, DesiredCapabilities};
+
+    #[test]
+    fn test_a11y_profile() {
+        let profile = A11yProfile {
+            forced_colors: true,
+            reader_mode: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            profile.firefox_prefs(),
+            [
+                ("browser.display.document_color_use", PrefValue::Int(2)),
+                ("reader.parse-on-load.force-enabled", PrefValue::Bool(true))
+            ]
+        );
+        assert_eq!(profile.chromium_args(), ["--force-high-contrast"]);
+        assert!(profile.chromium_prefs().is_empty());
+
+        let profile = A11yProfile::default();
+        assert!(profile.firefox_prefs().is_empty());
+        assert!(profile.chromium_args().is_empty());
+    }
+
+    #[test]
+    fn test_apply_a11y_profile() {
+        let profile = A11yProfile {
+            forced_colors: true,
+            caret_browsing: true,
+            ..Default::default()
+        };
+
+        let mut caps = DesiredCapabilities::firefox();
+        caps.set_pref("browser.startup.page", 0).unwrap();
+        caps.apply_a11y_profile(profile).unwrap();
+        let caps = serde_json::to_value(caps).unwrap();
+        assert_eq!(
+            caps["moz:firefoxOptions"]["prefs"],
+            json!({
+                "browser.startup.page": 0,
+                "browser.display.document_color_use": 2,
+                "accessibility.browsewithcaret": true,
+            })
+        );
+
+        let mut caps = DesiredCapabilities::chrome();
+        caps.add_arg("--headless").unwrap();
+        caps.apply_a11y_profile(profile).unwrap();
+        let caps = serde_json::to_value(caps).unwrap();
+        assert_eq!(
+            caps["goog:chromeOptions"]["args"],
+            json!(["--headless", "--force-high-contrast"])
+        );
+        assert_eq!(
+            caps["goog:chromeOptions"]["prefs"],
+            json!({ "settings.a11y.caretbrowsing.enabled": true })
+        );
+    }
+}
@@ -5,7 +5,9 @@ use paste::paste;
 use serde::Serialize;
 use serde_json::{json, to_value, Value};
 
+use crate::common::capabilities::a11y::A11yProfile;
 use crate::error::WebDriverResult;
+use crate::extensions::cdp::UserAgentOverride;
 use crate::{BrowserCapabilitiesHelper, Capabilities, CapabilitiesHelper};
 
 macro_rules! chromium_arg_wrapper {
@@ -31,6 +33,19 @@ macro_rules! chromium_arg_wrapper {
     }
 }
 
+/// Remove every `name=...` argument, then add `name=value` if a value is specified.
+fn replace_switch<C: ChromiumLikeCapabilities + ?Sized>(
+    caps: &mut C,
+    name: &str,
+    value: Option<&str>,
+) -> WebDriverResult<()> {
+    let prefix = format!("{name}=");
+    let mut args = caps.args();
+    args.retain(|x| !x.starts_with(&prefix));
+    args.extend(value.map(|value| format!("{prefix}{value}")));
+    caps.insert_browser_option("args", to_value(args)?)
+}
+
 /// Capabilities helper methods for all Chromium-based browsers.
 pub trait ChromiumLikeCapabilities: BrowserCapabilitiesHelper {
     /// Get the current list of Chrome extensions as a vec.
@@ -175,6 +190,85 @@ pub trait ChromiumLikeCapabilities: BrowserCapabilitiesHelper {
         }
     }
 
+    /// Report the specified user agent from the start of the session, so that it also
+    /// applies to the first page loaded in every window.
+    ///
+    /// A user agent whose client hints report a mobile device is set via the
+    /// `mobileEmulation` option, along with the client hints. Any other user agent is set
+    /// via the `--user-agent` argument, so Chrome derives the client hints from its own
+    /// version and platform, and `UserAgentOverride::ua_metadata` is ignored. The
+    /// `Accept-Language` header is set via the `--accept-lang` argument. Any user agent or
+    /// `Accept-Language` header set previously is replaced.
+    ///
+    /// The browser does not support changing `navigator.platform` here, so
+    /// `UserAgentOverride::platform` is ignored. Use `ChromeDevTools::set_user_agent()` to
+    /// change it, or to set the client hints of a desktop user agent.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::common::capabilities::chromium::ChromiumLikeCapabilities;
+    /// use thirtyfour::extensions::cdp::UserAgentOverride;
+    /// use thirtyfour::DesiredCapabilities;
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.set_user_agent_override(&UserAgentOverride::chrome_windows()).unwrap();
+    /// ```
+    fn set_user_agent_override(&mut self, user_agent: &UserAgentOverride) -> WebDriverResult<()> {
+        user_agent.validate()?;
+        let mut emulation: serde_json::Map<String, Value> =
+            self.browser_option("mobileEmulation").unwrap_or_default();
+        emulation.remove("userAgent");
+        emulation.remove("clientHints");
+        let mut switch = None;
+        if user_agent.is_mobile() {
+            emulation.insert("userAgent".to_string(), json!(user_agent.user_agent));
+            if let Some(hints) = &user_agent.ua_metadata {
+                emulation.insert("clientHints".to_string(), to_value(hints)?);
+            }
+        } else {
+            switch = Some(user_agent.user_agent.as_str());
+        }
+        match emulation.is_empty() {
+            true => self.remove_browser_option("mobileEmulation"),
+            false => self.insert_browser_option("mobileEmulation", emulation)?,
+        }
+        replace_switch(self, "--user-agent", switch)?;
+        if let Some(accept_language) = &user_agent.accept_language {
+            replace_switch(self, "--accept-lang", Some(accept_language))?;
+        }
+        Ok(())
+    }
+
+    /// Start the browser with the specified accessibility settings, using command-line
+    /// arguments and profile preferences. Some settings are not available in
+    /// Chromium-based browsers, see [`A11yProfile`].
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use thirtyfour::common::capabilities::chromium::ChromiumLikeCapabilities;
+    /// use thirtyfour::{A11yProfile, DesiredCapabilities};
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.apply_a11y_profile(A11yProfile {
+    ///     forced_colors: true,
+    ///     ..Default::default()
+    /// })
+    /// .unwrap();
+    /// ```
+    fn apply_a11y_profile(&mut self, profile: A11yProfile) -> WebDriverResult<()> {
+        for arg in profile.chromium_args() {
+            self.add_arg(arg)?;
+        }
+        let prefs = profile.chromium_prefs();
+        if !prefs.is_empty() {
+            let mut existing: serde_json::Map<String, Value> =
+                self.browser_option("prefs").unwrap_or_default();
+            for (name, value) in prefs {
+                existing.insert(name.to_string(), value.into());
+            }
+            self.add_experimental_option("prefs", existing)?;
+        }
+        Ok(())
+    }
+
     chromium_arg_wrapper! {
         headless => "--headless",
         disable_web_security => "--disable-web-security",
@@ -235,3 +329,45 @@ impl From<ChromiumCapabilities> for Capabilities {
         caps.capabilities
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::DesiredCapabilities;
+
+    #[test]
+    fn test_set_user_agent_override() {
+        let mut caps = DesiredCapabilities::chrome();
+        caps.add_arg("--headless").unwrap();
+        caps.add_arg("--accept-lang=fr").unwrap();
+        let desktop = UserAgentOverride::chrome_windows().with_accept_language("en-AU");
+        caps.set_user_agent_override(&desktop).unwrap();
+        let options = &serde_json::to_value(&caps).unwrap()["goog:chromeOptions"];
+        assert_eq!(
+            options["args"],
+            json!([
+                "--headless",
+                format!("--user-agent={}", desktop.user_agent),
+                "--accept-lang=en-AU"
+            ])
+        );
+        assert_eq!(options.get("mobileEmulation"), None);
+
+        let mobile = UserAgentOverride::pixel_7().with_accept_language("de");
+        caps.set_user_agent_override(&mobile).unwrap();
+        let options = &serde_json::to_value(&caps).unwrap()["goog:chromeOptions"];
+        assert_eq!(options["args"], json!(["--headless", "--accept-lang=de"]));
+        let emulation = &options["mobileEmulation"];
+        assert_eq!(emulation["userAgent"], mobile.user_agent);
+        assert_eq!(emulation["clientHints"]["model"], "Pixel 7");
+
+        caps.set_user_agent_override(&UserAgentOverride::new("bot")).unwrap();
+        let options = &serde_json::to_value(&caps).unwrap()["goog:chromeOptions"];
+        assert_eq!(options["args"], json!(["--headless", "--accept-lang=de", "--user-agent=bot"]));
+        assert_eq!(options.get("mobileEmulation"), None);
+
+        assert!(caps.set_user_agent_override(&UserAgentOverride::new("")).is_err());
+    }
+}
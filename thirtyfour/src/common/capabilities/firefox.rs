@@ -2,7 +2,9 @@ use paste::paste;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, json, to_value, Value};
 
+use crate::common::capabilities::a11y::A11yProfile;
 use crate::error::WebDriverResult;
+use crate::extensions::addons::firefox::PrefValue;
 use crate::CapabilitiesHelper;
 use crate::{BrowserCapabilitiesHelper, Capabilities};
 
@@ -90,6 +92,34 @@ impl FirefoxCapabilities {
         self.insert_browser_option("prefs", preferences)
     }
 
+    /// Ask geckodriver to start the Firefox remote agent and return its address in the
+    /// `moz:debuggerAddress` capability, so that the CDP features of `ChromeDevTools`
+    /// that need a DevTools connection can be used with Firefox.
+    ///
+    /// Firefox only implements part of CDP. Commands it does not implement fail with the
+    /// error reported by Firefox. See `WebDriver::debugger_address()`.
+    pub fn enable_debugger_address(&mut self) -> WebDriverResult<()> {
+        self.set_base_capability("moz:debuggerAddress", true)
+    }
+
+    /// Set the specified firefox preference, keeping any other preferences already set.
+    ///
+    /// Use `FirefoxTools::set_pref()` to change a preference during the session.
+    pub fn set_pref(&mut self, name: &str, value: impl Into<PrefValue>) -> WebDriverResult<()> {
+        let mut preferences: FirefoxPreferences = self.browser_option("prefs").unwrap_or_default();
+        preferences.set(name, Value::from(value.into()))?;
+        self.set_preferences(preferences)
+    }
+
+    /// Set the preferences for the specified accessibility settings, keeping any other
+    /// preferences already set. See [`A11yProfile`] for the preferences used.
+    pub fn apply_a11y_profile(&mut self, profile: A11yProfile) -> WebDriverResult<()> {
+        for (name, value) in profile.firefox_prefs() {
+            self.set_pref(name, value)?;
+        }
+        Ok(())
+    }
+
     /// Get the firefox profile zip as a base64-encoded string.
     pub fn encoded_profile(&self) -> Option<String> {
         self.browser_option("profile")
@@ -1,3 +1,5 @@
+/// Accessibility settings.
+pub mod a11y;
 /// Capabilities for Chrome.
 pub mod chrome;
 /// Capabilities for Chromium.
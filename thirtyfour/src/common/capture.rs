@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Options for capturing a sequence of screenshots.
+///
+/// See `WebDriver::capture_while()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureOptions {
+    /// The interval between screenshots. If this is zero, each screenshot is captured as
+    /// soon as the previous one is done.
+    pub interval: Duration,
+    /// The maximum number of screenshots to capture.
+    pub max_frames: usize,
+    /// If set, write each screenshot to a new file in this directory instead of
+    /// keeping it in memory.
+    pub output_dir: Option<PathBuf>,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(100),
+            max_frames: 100,
+            output_dir: None,
+        }
+    }
+}
+
+/// A screenshot captured as part of a sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedFrame {
+    /// When the screenshot was captured, according to a monotonic clock.
+    pub at: Instant,
+    /// The screenshot as PNG bytes. This is empty if the screenshot was written to a file.
+    pub png: Vec<u8>,
+    /// The file the screenshot was written to, if `CaptureOptions::output_dir` was set.
+    pub path: Option<PathBuf>,
+}
+
+/// Options for capturing failure artifacts when an element operation fails.
+///
+/// When set with `WebDriverConfigBuilder::capture_on_error()`, a screenshot, the page
+/// source, the current URL and the browser console log are captured whenever `click()`,
+/// `clear()`, `send_keys()` or `text()` fails, or an element query fails to find a match.
+/// They are attached to the error and can be read with `WebDriverError::attachments()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorCaptureOptions {
+    /// The directory to write screenshots and page sources to.
+    pub output_dir: PathBuf,
+    /// If true, capture a screenshot of the current window.
+    pub screenshot: bool,
+    /// If true, capture the page source.
+    pub page_source: bool,
+    /// If true, capture the browser console log. This is only captured for Chromium-based
+    /// browsers.
+    pub console: bool,
+    /// The maximum time spent capturing artifacts for a single error. Anything not
+    /// captured within this time is left out, so that a hung driver does not delay
+    /// the error for much longer.
+    pub timeout: Duration,
+}
+
+impl ErrorCaptureOptions {
+    /// The default capture timeout.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Capture every artifact into the specified directory, with the default timeout.
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            screenshot: true,
+            page_source: true,
+            console: true,
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Set whether to capture a screenshot.
+    pub fn screenshot(mut self, screenshot: bool) -> Self {
+        self.screenshot = screenshot;
+        self
+    }
+
+    /// Set whether to capture the page source.
+    pub fn page_source(mut self, page_source: bool) -> Self {
+        self.page_source = page_source;
+        self
+    }
+
+    /// Set whether to capture the browser console log.
+    pub fn console(mut self, console: bool) -> Self {
+        self.console = console;
+        self
+    }
+
+    /// Set the maximum time spent capturing artifacts for a single error.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
@@ -5,8 +5,10 @@ use crate::common::{
     capabilities::desiredcapabilities::make_w3c_caps,
     cookie::Cookie,
     keys::TypingData,
+    permissions::{PermissionName, PermissionState},
     print::PrintParameters,
     types::{ElementId, OptionRect, SessionId, TimeoutConfiguration, WindowHandle},
+    webauthn::{encode_credential_id, AuthenticatorId, Credential, VirtualAuthenticatorOptions},
 };
 use crate::IntoArcStr;
 use crate::RequestData;
@@ -132,6 +134,23 @@ impl By {
     }
 }
 
+impl By {
+    /// Describe this selector for error messages, for example "css `#submit`".
+    pub(crate) fn describe(&self) -> String {
+        let (kind, query) = match &self.selector {
+            BySelector::Id(x) => ("id", x),
+            BySelector::XPath(x) => ("xpath", x),
+            BySelector::LinkText(x) => ("link text", x),
+            BySelector::PartialLinkText(x) => ("partial link text", x),
+            BySelector::Name(x) => ("name", x),
+            BySelector::Tag(x) => ("tag", x),
+            BySelector::ClassName(x) => ("class", x),
+            BySelector::Css(x) => ("css", x),
+        };
+        format!("{kind} `{query}`")
+    }
+}
+
 impl fmt::Display for BySelector {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -252,6 +271,14 @@ pub enum Command {
     PrintPage(PrintParameters),
     TakeScreenshot,
     TakeElementScreenshot(ElementId),
+    SetPermission(PermissionName, PermissionState),
+    AddVirtualAuthenticator(VirtualAuthenticatorOptions),
+    RemoveVirtualAuthenticator(AuthenticatorId),
+    AddCredential(AuthenticatorId, Credential),
+    GetCredentials(AuthenticatorId),
+    RemoveCredential(AuthenticatorId, Arc<[u8]>),
+    RemoveAllCredentials(AuthenticatorId),
+    SetUserVerified(AuthenticatorId, bool),
     ExtensionCommand(Box<dyn ExtensionCommand + Send + Sync>),
 }
 
@@ -503,6 +530,55 @@ impl FormatRequestData for Command {
                 Method::GET,
                 format!("session/{}/element/{}/screenshot", session_id, element_id),
             ),
+            Command::SetPermission(name, state) => {
+                RequestData::new(Method::POST, format!("session/{}/permissions", session_id))
+                    .add_body(json!({ "descriptor": { "name": name }, "state": state }))
+            }
+            Command::AddVirtualAuthenticator(options) => RequestData::new(
+                Method::POST,
+                format!("session/{}/webauthn/authenticator", session_id),
+            )
+            .add_body(json!(options)),
+            Command::RemoveVirtualAuthenticator(authenticator_id) => RequestData::new(
+                Method::DELETE,
+                format!("session/{}/webauthn/authenticator/{}", session_id, authenticator_id),
+            ),
+            Command::AddCredential(authenticator_id, credential) => RequestData::new(
+                Method::POST,
+                format!(
+                    "session/{}/webauthn/authenticator/{}/credential",
+                    session_id, authenticator_id
+                ),
+            )
+            .add_body(json!(credential)),
+            Command::GetCredentials(authenticator_id) => RequestData::new(
+                Method::GET,
+                format!(
+                    "session/{}/webauthn/authenticator/{}/credentials",
+                    session_id, authenticator_id
+                ),
+            ),
+            Command::RemoveCredential(authenticator_id, credential_id) => RequestData::new(
+                Method::DELETE,
+                format!(
+                    "session/{}/webauthn/authenticator/{}/credentials/{}",
+                    session_id,
+                    authenticator_id,
+                    encode_credential_id(credential_id)
+                ),
+            ),
+            Command::RemoveAllCredentials(authenticator_id) => RequestData::new(
+                Method::DELETE,
+                format!(
+                    "session/{}/webauthn/authenticator/{}/credentials",
+                    session_id, authenticator_id
+                ),
+            ),
+            Command::SetUserVerified(authenticator_id, verified) => RequestData::new(
+                Method::POST,
+                format!("session/{}/webauthn/authenticator/{}/uv", session_id, authenticator_id),
+            )
+            .add_body(json!({ "isUserVerified": verified })),
             Command::ExtensionCommand(command) => {
                 let request_data = RequestData::new(
                     command.method(),
@@ -1,3 +1,5 @@
+use crate::common::capture::ErrorCaptureOptions;
+use crate::common::retry::RetryPolicy;
 use crate::error::WebDriverError;
 use crate::{
     extensions::query::{ElementPollerWithTimeout, IntoElementPoller},
@@ -22,6 +24,12 @@ pub struct WebDriverConfig {
     pub user_agent: HeaderValue,
     /// The timeout duration for reqwest client requests.
     pub reqwest_timeout: Duration,
+    /// If true, discard `beforeunload` handlers in every window before quitting.
+    pub discard_unload_on_quit: bool,
+    /// The policy for retrying element operations that fail with transient errors.
+    pub retry_policy: RetryPolicy,
+    /// If set, capture failure artifacts when element operations fail.
+    pub error_capture: Option<ErrorCaptureOptions>,
 }
 
 impl Default for WebDriverConfig {
@@ -72,6 +80,9 @@ pub struct WebDriverConfigBuilder {
     poller: Option<Arc<dyn IntoElementPoller + Send + Sync>>,
     user_agent: Option<WebDriverResult<HeaderValue>>,
     reqwest_timeout: Duration,
+    discard_unload_on_quit: bool,
+    retry_policy: RetryPolicy,
+    error_capture: Option<ErrorCaptureOptions>,
 }
 
 impl Default for WebDriverConfigBuilder {
@@ -88,6 +99,9 @@ impl WebDriverConfigBuilder {
             poller: None,
             user_agent: None,
             reqwest_timeout: Duration::from_secs(120),
+            discard_unload_on_quit: false,
+            retry_policy: RetryPolicy::none(),
+            error_capture: None,
         }
     }
 
@@ -119,6 +133,32 @@ impl WebDriverConfigBuilder {
         self
     }
 
+    /// Set whether to discard `beforeunload` handlers before quitting.
+    ///
+    /// Pages with unsaved-changes guards can otherwise cause `WebDriver::quit()` to hang
+    /// or fail on some drivers. See `WebDriver::discard_unload_handlers()`.
+    pub fn discard_unload_on_quit(mut self, discard: bool) -> Self {
+        self.discard_unload_on_quit = discard;
+        self
+    }
+
+    /// Set the policy for retrying element operations that fail with transient errors.
+    ///
+    /// By default nothing is retried. See [`RetryPolicy`] for the operations it applies to.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Capture failure artifacts when element operations fail.
+    ///
+    /// By default nothing is captured. See [`ErrorCaptureOptions`] for the operations it
+    /// applies to.
+    pub fn capture_on_error(mut self, options: ErrorCaptureOptions) -> Self {
+        self.error_capture = Some(options);
+        self
+    }
+
     /// Build `WebDriverConfig` using builder options.
     pub fn build(self) -> WebDriverResult<WebDriverConfig> {
         Ok(WebDriverConfig {
@@ -126,6 +166,9 @@ impl WebDriverConfigBuilder {
             poller: self.poller.unwrap_or_else(|| Arc::new(ElementPollerWithTimeout::default())),
             user_agent: self.user_agent.transpose()?.unwrap_or(WebDriverConfig::DEFAULT_USER_AGENT),
             reqwest_timeout: self.reqwest_timeout,
+            discard_unload_on_quit: self.discard_unload_on_quit,
+            retry_policy: self.retry_policy,
+            error_capture: self.error_capture,
         })
     }
 }
@@ -1,7 +1,8 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use url::{Host, Url};
 
 /// Enum representing the SameSite attribute of a cookie.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SameSite {
     /// Strict SameSite attribute.
     Strict,
@@ -12,7 +13,7 @@ pub enum SameSite {
 }
 
 /// Cookie struct used to create new cookies.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Cookie {
     /// The name of the cookie.
     pub name: String,
@@ -33,6 +34,38 @@ pub struct Cookie {
     /// The sameSite attribute of the cookie.
     #[serde(skip_serializing_if = "Option::is_none", rename = "sameSite")]
     pub same_site: Option<SameSite>,
+    /// The top-level site this cookie is partitioned under (CHIPS), e.g. `https://example.com`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "partitionKey",
+        deserialize_with = "deserialize_partition_key"
+    )]
+    pub partition_key: Option<String>,
+}
+
+/// The partition key may be reported either as the top-level site or as an object
+/// containing the top-level site, depending on the browser version.
+fn deserialize_partition_key<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PartitionKey {
+        Site(String),
+        Object {
+            #[serde(rename = "topLevelSite")]
+            top_level_site: String,
+        },
+    }
+
+    Ok(Option::<PartitionKey>::deserialize(deserializer)?.map(|key| match key {
+        PartitionKey::Site(site) => site,
+        PartitionKey::Object {
+            top_level_site,
+        } => top_level_site,
+    }))
 }
 
 impl Cookie {
@@ -46,6 +79,7 @@ impl Cookie {
             secure: None,
             expiry: None,
             same_site: None,
+            partition_key: None,
         }
     }
 
@@ -73,4 +107,343 @@ impl Cookie {
     pub fn set_same_site(&mut self, same_site: SameSite) {
         self.same_site = Some(same_site);
     }
+
+    /// Set the partition key of the cookie.
+    ///
+    /// This is the top-level site the cookie should be partitioned under,
+    /// e.g. `https://example.com`. Partitioned cookies must also be secure.
+    pub fn set_partition_key(&mut self, partition_key: impl Into<String>) {
+        self.partition_key = Some(partition_key.into());
+    }
+
+    /// Return true if this cookie would be sent with a request to the specified URL.
+    ///
+    /// This implements the domain-matching, path-matching and secure-scheme rules from
+    /// [RFC 6265](https://www.rfc-editor.org/rfc/rfc6265#section-5.4).
+    ///
+    /// A domain with a leading dot (e.g. `.example.com`) is treated as a domain cookie and
+    /// also matches subdomains, while a domain without a leading dot is treated as a
+    /// host-only cookie, matching the way browsers report cookies over WebDriver.
+    /// Domain cookies never match IP address hosts other than the exact address.
+    /// A cookie without a domain matches any host, and a cookie without a path matches
+    /// any path.
+    pub fn matches_url(&self, url: &Url) -> bool {
+        if self.secure.unwrap_or(false) && !matches!(url.scheme(), "https" | "wss") {
+            return false;
+        }
+
+        self.matches_domain(url) && self.matches_path(url.path())
+    }
+
+    fn matches_domain(&self, url: &Url) -> bool {
+        let (host, is_ip) = match url.host() {
+            Some(Host::Domain(domain)) => (domain.to_ascii_lowercase(), false),
+            Some(Host::Ipv4(ip)) => (ip.to_string(), true),
+            Some(Host::Ipv6(ip)) => (ip.to_string(), true),
+            None => return false,
+        };
+
+        let Some(domain) = self.domain.as_deref() else {
+            return true;
+        };
+        let (domain, host_only) = match domain.strip_prefix('.') {
+            Some(domain) => (domain, false),
+            None => (domain, true),
+        };
+        let domain = domain.trim_start_matches('[').trim_end_matches(']').to_ascii_lowercase();
+
+        if host == domain {
+            return true;
+        }
+
+        !host_only
+            && !is_ip
+            && host.len() > domain.len()
+            && host.ends_with(&domain)
+            && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+    }
+
+    fn matches_path(&self, request_path: &str) -> bool {
+        let cookie_path = match self.path.as_deref() {
+            None | Some("") => return true,
+            Some(path) => path,
+        };
+        let request_path = if request_path.is_empty() {
+            "/"
+        } else {
+            request_path
+        };
+
+        match request_path.strip_prefix(cookie_path) {
+            Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+            None => false,
+        }
+    }
+}
+
+/// A change to a cookie, as reported by `WebDriver::watch_cookie()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CookieChange {
+    /// The cookie was added.
+    Added(Cookie),
+    /// The cookie was changed.
+    Updated {
+        /// The cookie before the change.
+        old: Cookie,
+        /// The cookie after the change.
+        new: Cookie,
+    },
+    /// The cookie was removed.
+    Removed(Cookie),
+}
+
+impl CookieChange {
+    /// Get the change between two observations of the same cookie, if any.
+    ///
+    /// `None` means the cookie did not exist at the time of the observation.
+    pub fn diff(old: Option<&Cookie>, new: Option<&Cookie>) -> Option<Self> {
+        match (old, new) {
+            (None, None) => None,
+            (None, Some(new)) => Some(CookieChange::Added(new.clone())),
+            (Some(old), None) => Some(CookieChange::Removed(old.clone())),
+            (Some(old), Some(new)) if old == new => None,
+            (Some(old), Some(new)) => Some(CookieChange::Updated {
+                old: old.clone(),
+                new: new.clone(),
+            }),
+        }
+    }
+
+    /// The cookie as it is after this change, if it still exists.
+    pub fn cookie(&self) -> Option<&Cookie> {
+        match self {
+            CookieChange::Added(cookie) => Some(cookie),
+            CookieChange::Updated {
+                new,
+                ..
+            } => Some(new),
+            CookieChange::Removed(_) => None,
+        }
+    }
+}
+
+/// A collection of cookies, typically as returned by `WebDriver::get_all_cookies()`.
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    /// Create a new CookieJar containing the specified cookies.
+    pub fn new(cookies: Vec<Cookie>) -> Self {
+        Self {
+            cookies,
+        }
+    }
+
+    /// Get all cookies in this jar.
+    pub fn cookies(&self) -> &[Cookie] {
+        &self.cookies
+    }
+
+    /// Consume the jar and return the cookies it contains.
+    pub fn into_cookies(self) -> Vec<Cookie> {
+        self.cookies
+    }
+
+    /// Get all cookies that would be sent with a request to the specified URL.
+    ///
+    /// See [`Cookie::matches_url`] for details on how cookies are matched.
+    pub fn for_url(&self, url: &Url) -> Vec<&Cookie> {
+        self.cookies.iter().filter(|c| c.matches_url(url)).collect()
+    }
+}
+
+impl From<Vec<Cookie>> for CookieJar {
+    fn from(cookies: Vec<Cookie>) -> Self {
+        Self::new(cookies)
+    }
+}
+
+impl IntoIterator for CookieJar {
+    type Item = Cookie;
+    type IntoIter = std::vec::IntoIter<Cookie>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cookies.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    fn cookie(domain: Option<&str>, path: Option<&str>) -> Cookie {
+        let mut cookie = Cookie::new("name", "value");
+        if let Some(domain) = domain {
+            cookie.set_domain(domain);
+        }
+        if let Some(path) = path {
+            cookie.set_path(path);
+        }
+        cookie
+    }
+
+    #[test]
+    fn test_host_only_cookie() {
+        let c = cookie(Some("example.com"), None);
+        assert!(c.matches_url(&url("http://example.com/")));
+        assert!(c.matches_url(&url("http://EXAMPLE.com/")));
+        assert!(!c.matches_url(&url("http://www.example.com/")));
+        assert!(!c.matches_url(&url("http://notexample.com/")));
+    }
+
+    #[test]
+    fn test_domain_cookie() {
+        let c = cookie(Some(".example.com"), None);
+        assert!(c.matches_url(&url("http://example.com/")));
+        assert!(c.matches_url(&url("http://www.example.com/")));
+        assert!(c.matches_url(&url("http://a.b.example.com/")));
+        assert!(!c.matches_url(&url("http://notexample.com/")));
+        assert!(!c.matches_url(&url("http://example.com.evil.org/")));
+        assert!(!c.matches_url(&url("http://com/")));
+
+        let c = cookie(Some(".Example.COM"), None);
+        assert!(c.matches_url(&url("http://www.example.com/")));
+    }
+
+    #[test]
+    fn test_no_domain_cookie() {
+        let c = cookie(None, None);
+        assert!(c.matches_url(&url("http://example.com/")));
+        assert!(c.matches_url(&url("http://127.0.0.1/")));
+        assert!(!c.matches_url(&url("data:text/plain,hello")));
+    }
+
+    #[test]
+    fn test_ip_hosts() {
+        let c = cookie(Some("127.0.0.1"), None);
+        assert!(c.matches_url(&url("http://127.0.0.1:8080/")));
+        assert!(!c.matches_url(&url("http://127.0.0.2/")));
+
+        let c = cookie(Some(".0.0.1"), None);
+        assert!(!c.matches_url(&url("http://127.0.0.1/")));
+
+        let c = cookie(Some("::1"), None);
+        assert!(c.matches_url(&url("http://[::1]/")));
+        let c = cookie(Some("[::1]"), None);
+        assert!(c.matches_url(&url("http://[::1]/")));
+    }
+
+    #[test]
+    fn test_path_matching() {
+        let c = cookie(None, Some("/docs"));
+        assert!(c.matches_url(&url("http://example.com/docs")));
+        assert!(c.matches_url(&url("http://example.com/docs/")));
+        assert!(c.matches_url(&url("http://example.com/docs/web")));
+        assert!(!c.matches_url(&url("http://example.com/docsets")));
+        assert!(!c.matches_url(&url("http://example.com/")));
+        assert!(!c.matches_url(&url("http://example.com/Docs")));
+
+        let c = cookie(None, Some("/docs/"));
+        assert!(c.matches_url(&url("http://example.com/docs/")));
+        assert!(c.matches_url(&url("http://example.com/docs/web")));
+        assert!(!c.matches_url(&url("http://example.com/docs")));
+
+        let c = cookie(None, Some("/"));
+        assert!(c.matches_url(&url("http://example.com")));
+        assert!(c.matches_url(&url("http://example.com/any/path")));
+
+        let c = cookie(None, None);
+        assert!(c.matches_url(&url("http://example.com/any/path")));
+    }
+
+    #[test]
+    fn test_secure_cookie() {
+        let mut c = cookie(Some("example.com"), Some("/"));
+        c.set_secure(true);
+        assert!(c.matches_url(&url("https://example.com/")));
+        assert!(c.matches_url(&url("wss://example.com/")));
+        assert!(!c.matches_url(&url("http://example.com/")));
+        assert!(!c.matches_url(&url("ws://example.com/")));
+
+        c.set_secure(false);
+        assert!(c.matches_url(&url("http://example.com/")));
+    }
+
+    #[test]
+    fn test_cookie_jar_for_url() {
+        let mut secure = cookie(Some(".example.com"), Some("/"));
+        secure.name = "secure".to_string();
+        secure.set_secure(true);
+        let mut docs = cookie(Some("www.example.com"), Some("/docs"));
+        docs.name = "docs".to_string();
+        let mut other = cookie(Some("other.org"), Some("/"));
+        other.name = "other".to_string();
+
+        let jar = CookieJar::from(vec![secure, docs, other]);
+        let names = |u: &str| -> Vec<String> {
+            jar.for_url(&url(u)).into_iter().map(|c| c.name.clone()).collect()
+        };
+        assert_eq!(names("https://www.example.com/docs/a"), vec!["secure", "docs"]);
+        assert_eq!(names("http://www.example.com/docs/a"), vec!["docs"]);
+        assert_eq!(names("https://api.example.com/docs"), vec!["secure"]);
+        assert_eq!(names("https://other.org/"), vec!["other"]);
+        assert!(names("http://example.net/").is_empty());
+    }
+
+    #[test]
+    fn test_cookie_change_diff() {
+        let a = Cookie::new("name", "a");
+        let b = Cookie::new("name", "b");
+        assert_eq!(CookieChange::diff(None, None), None);
+        assert_eq!(CookieChange::diff(Some(&a), Some(&a)), None);
+        assert_eq!(CookieChange::diff(None, Some(&a)), Some(CookieChange::Added(a.clone())));
+        assert_eq!(CookieChange::diff(Some(&a), None), Some(CookieChange::Removed(a.clone())));
+        assert_eq!(
+            CookieChange::diff(Some(&a), Some(&b)),
+            Some(CookieChange::Updated {
+                old: a.clone(),
+                new: b.clone()
+            })
+        );
+
+        let mut c = a.clone();
+        c.set_path("/other");
+        assert_eq!(
+            CookieChange::diff(Some(&a), Some(&c)).and_then(|x| x.cookie().cloned()),
+            Some(c)
+        );
+    }
+
+    #[test]
+    fn test_partition_key_serde() {
+        let mut c = Cookie::new("name", "value");
+        let value = serde_json::to_value(&c).unwrap();
+        assert!(value.get("partitionKey").is_none());
+
+        c.set_partition_key("https://example.com");
+        let value = serde_json::to_value(&c).unwrap();
+        assert_eq!(value["partitionKey"], "https://example.com");
+
+        let c: Cookie = serde_json::from_value(value).unwrap();
+        assert_eq!(c.partition_key.as_deref(), Some("https://example.com"));
+
+        let c: Cookie = serde_json::from_value(serde_json::json!({
+            "name": "name",
+            "value": "value",
+            "partitionKey": { "topLevelSite": "https://example.com", "hasCrossSiteAncestor": false }
+        }))
+        .unwrap();
+        assert_eq!(c.partition_key.as_deref(), Some("https://example.com"));
+
+        let c: Cookie =
+            serde_json::from_value(serde_json::json!({ "name": "name", "value": "value" }))
+                .unwrap();
+        assert!(c.partition_key.is_none());
+    }
 }
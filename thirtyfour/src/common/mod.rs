@@ -2,6 +2,8 @@
 pub mod action;
 /// Support for desired capabilities.
 pub mod capabilities;
+/// Types used with screenshot capture sequences.
+pub mod capture;
 /// Helpers for webdriver commands.
 pub mod command;
 /// Configuration options for a `WebDriver` instance.
@@ -10,9 +12,17 @@ pub mod config;
 pub mod cookie;
 /// Types for working with keyboard input.
 pub mod keys;
+/// Types used with the permissions commands.
+pub mod permissions;
 /// Types used with print commands.
 pub mod print;
+/// Reporting of failed commands.
+pub mod report;
 /// Type for request method and body.
 pub mod requestdata;
+/// Retry policies for element operations.
+pub mod retry;
 /// Common types used within thirtyfour.
 pub mod types;
+/// Types used with virtual authenticators.
+pub mod webauthn;
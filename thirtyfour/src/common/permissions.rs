@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// The name of a permission that pages can request, for `WebDriver::set_permission()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PermissionName {
+    /// Access to the position of the device, via `navigator.geolocation`.
+    Geolocation,
+    /// Showing notifications.
+    Notifications,
+    /// Access to MIDI devices.
+    Midi,
+    /// Access to the camera.
+    Camera,
+    /// Access to the microphone.
+    Microphone,
+    /// Reading the clipboard, via `navigator.clipboard.read()` and `readText()`.
+    ClipboardRead,
+    /// Writing to the clipboard, via `navigator.clipboard.write()` and `writeText()`.
+    ClipboardWrite,
+    /// Syncing in the background after the page is closed.
+    BackgroundSync,
+    /// Storage that is not cleared under storage pressure.
+    PersistentStorage,
+    /// Keeping the screen on, via `navigator.wakeLock`.
+    ScreenWakeLock,
+    /// Access to unpartitioned cookies and storage in third-party frames.
+    StorageAccess,
+}
+
+/// The state to set a permission to, for `WebDriver::set_permission()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionState {
+    /// The page may use the feature without asking.
+    Granted,
+    /// The page may not use the feature, and is not asked.
+    Denied,
+    /// The user is asked when the page wants to use the feature.
+    Prompt,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_permission_serialize() {
+        assert_eq!(
+            serde_json::to_value(PermissionName::ClipboardRead).unwrap(),
+            json!("clipboard-read")
+        );
+        assert_eq!(
+            serde_json::to_value(PermissionName::ScreenWakeLock).unwrap(),
+            json!("screen-wake-lock")
+        );
+        assert_eq!(serde_json::to_value(PermissionState::Granted).unwrap(), json!("granted"));
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::{Debug, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+
+use crate::error::{WebDriverError, WebDriverResult};
+
+/// Identifies an error hook registered via `on_error()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorHookId(u64);
+
+type ErrorHookFn = Arc<dyn Fn(&WebDriverError) + Send + Sync>;
+
+/// The error hooks registered for a session.
+#[derive(Default)]
+pub(crate) struct ErrorHooks {
+    next_id: AtomicU64,
+    hooks: Mutex<Vec<(ErrorHookId, ErrorHookFn)>>,
+}
+
+impl Debug for ErrorHooks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrorHooks").field("len", &self.lock().len()).finish()
+    }
+}
+
+impl ErrorHooks {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(ErrorHookId, ErrorHookFn)>> {
+        self.hooks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn add(&self, hook: ErrorHookFn) -> ErrorHookId {
+        let id = ErrorHookId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.lock().push((id, hook));
+        id
+    }
+
+    pub(crate) fn remove(&self, id: ErrorHookId) -> bool {
+        let mut hooks = self.lock();
+        let len = hooks.len();
+        hooks.retain(|(x, _)| *x != id);
+        hooks.len() != len
+    }
+
+    /// Pass the error to every hook, in the order they were registered.
+    pub(crate) fn call(&self, error: &WebDriverError) {
+        // Clone the hooks so that a hook can register or remove hooks.
+        let hooks: Vec<ErrorHookFn> = self.lock().iter().map(|(_, h)| h.clone()).collect();
+        for hook in hooks {
+            hook(error);
+        }
+    }
+}
+
+enum ReportMessage {
+    Line(String),
+    Flush(Sender<()>),
+}
+
+/// Writes failed commands to a file as newline-delimited JSON, one line per error, using
+/// the schema described in [`WebDriverError::to_json`].
+///
+/// Lines are written by a background thread, so reporting an error never blocks the
+/// command that failed. The file is flushed whenever the thread has no more lines to
+/// write, when [`FailureReporter::flush`] is called, and when the last clone of the
+/// reporter is dropped.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use thirtyfour::FailureReporter;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let reporter = FailureReporter::new("target/failures.ndjson")?;
+/// let id = driver.on_error({
+///     let reporter = reporter.clone();
+///     move |error| reporter.report(error)
+/// });
+/// driver.goto("https://www.rust-lang.org/").await?;
+/// driver.remove_error_hook(id);
+/// reporter.flush();
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FailureReporter {
+    path: Arc<PathBuf>,
+    sender: Sender<ReportMessage>,
+}
+
+impl Debug for FailureReporter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FailureReporter").field("path", &self.path).finish()
+    }
+}
+
+impl FailureReporter {
+    /// Create a reporter that appends to the specified file, creating it if necessary.
+    pub fn new(path: impl Into<PathBuf>) -> WebDriverResult<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let (sender, receiver) = channel();
+        std::thread::Builder::new().name("thirtyfour-failure-reporter".to_string()).spawn(
+            move || {
+                let mut writer = BufWriter::new(file);
+                let write = |writer: &mut BufWriter<File>, line: &str| {
+                    if let Err(e) = writeln!(writer, "{line}") {
+                        tracing::warn!("failed to write failure report: {e}");
+                    }
+                };
+                while let Ok(message) = receiver.recv() {
+                    let mut flushed = None;
+                    match message {
+                        ReportMessage::Line(line) => write(&mut writer, &line),
+                        ReportMessage::Flush(done) => flushed = Some(done),
+                    }
+                    // Write everything that is already queued before flushing.
+                    for message in receiver.try_iter() {
+                        match message {
+                            ReportMessage::Line(line) => write(&mut writer, &line),
+                            ReportMessage::Flush(done) => flushed = Some(done),
+                        }
+                    }
+                    if let Err(e) = writer.flush() {
+                        tracing::warn!("failed to flush failure report: {e}");
+                    }
+                    if let Some(done) = flushed {
+                        let _ = done.send(());
+                    }
+                }
+            },
+        )?;
+        Ok(Self {
+            path: Arc::new(path),
+            sender,
+        })
+    }
+
+    /// The file that failures are written to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Queue the error to be written to the file. This does not wait for it to be written.
+    pub fn report(&self, error: &WebDriverError) {
+        // The thread only stops once every sender is dropped, so this cannot fail.
+        let _ = self.sender.send(ReportMessage::Line(error.to_json().to_string()));
+    }
+
+    /// Wait until every error reported so far has been written to the file.
+    pub fn flush(&self) {
+        let (done, wait) = channel();
+        if self.sender.send(ReportMessage::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WebDriverErrorInfo;
+
+    #[test]
+    fn test_failure_reporter() {
+        let path = std::env::temp_dir()
+            .join(format!("thirtyfour-failure-reporter-{}.ndjson", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let hooks = ErrorHooks::default();
+        let reporter = FailureReporter::new(&path).unwrap();
+        let id = hooks.add({
+            let reporter = reporter.clone();
+            Arc::new(move |error| reporter.report(error))
+        });
+        hooks.call(&WebDriverError::Timeout("timeout".to_string()));
+        hooks.call(&WebDriverError::NoSuchElement(WebDriverErrorInfo::new("boom".to_string())));
+        assert!(hooks.remove(id));
+        assert!(!hooks.remove(id));
+        hooks.call(&WebDriverError::Timeout("not reported".to_string()));
+        reporter.flush();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> =
+            contents.lines().map(|x| serde_json::from_str(x).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["kind"], "timeout");
+        assert_eq!(lines[1]["kind"], "no_such_element");
+        assert_eq!(lines[1]["message"], "boom");
+
+        // Reporters append to existing files.
+        drop(reporter);
+        let reporter = FailureReporter::new(&path).unwrap();
+        reporter.report(&WebDriverError::Timeout("timeout".to_string()));
+        reporter.flush();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::{ErrorKind, WebDriverError, WebDriverResult};
+use crate::support;
+
+/// Details of a failed attempt that is about to be retried, passed to the
+/// [`RetryPolicy::on_retry`] hook.
+#[derive(Debug)]
+pub struct RetryEvent<'a> {
+    /// The name of the operation, for example "click".
+    pub operation: &'a str,
+    /// The attempt that failed, starting from 1.
+    pub attempt: u32,
+    /// The error returned by the attempt.
+    pub error: &'a WebDriverError,
+}
+
+/// The hook called before each retry.
+pub type RetryHook = dyn Fn(&RetryEvent<'_>) + Send + Sync;
+
+/// A policy for retrying element operations that fail with transient errors.
+///
+/// The policy for a session is set with [`WebDriverConfigBuilder::retry_policy`], and is
+/// applied by [`WebElement::click`], [`WebElement::send_keys`], [`WebElement::clear`] and
+/// [`WebElement::text`], and by `find()` and `find_all()` if [`RetryPolicy::retry_find`]
+/// is set. Methods such as [`WebElement::click_with_policy`] override it for a single call.
+/// Individual WebDriver commands are never retried on their own.
+///
+/// Retries repeat the operation on the same element. They do not resolve the element again,
+/// so a stale element will stay stale.
+///
+/// Each retry is logged with `tracing` at debug level, and passed to the
+/// [`RetryPolicy::on_retry`] hook if one is set.
+///
+/// The default policy makes a single attempt, so nothing is retried.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use std::time::Duration;
+/// use thirtyfour::common::config::WebDriverConfig;
+/// use thirtyfour::{is_transient, RetryPolicy};
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let config = WebDriverConfig::builder().retry_policy(RetryPolicy::transient()).build()?;
+/// let driver = driver.clone_with_config(config);
+///
+/// // Retry a click for up to 2 seconds while an animation finishes.
+/// let elem = driver.find(By::Id("save")).await?;
+/// let policy = RetryPolicy::new(is_transient, 10, Duration::from_millis(200));
+/// elem.click_with_policy(&policy).await?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+///
+/// [`WebDriverConfigBuilder::retry_policy`]: crate::common::config::WebDriverConfigBuilder::retry_policy
+/// [`WebElement::click`]: crate::WebElement::click
+/// [`WebElement::send_keys`]: crate::WebElement::send_keys
+/// [`WebElement::clear`]: crate::WebElement::clear
+/// [`WebElement::text`]: crate::WebElement::text
+/// [`WebElement::click_with_policy`]: crate::WebElement::click_with_policy
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Return true if the error should be retried.
+    pub retryable: fn(&WebDriverError) -> bool,
+    /// The maximum number of attempts, including the first. A value of 0 is treated as 1.
+    pub max_attempts: u32,
+    /// The delay before each retry.
+    pub backoff: Duration,
+    /// If true, also retry `find()` and `find_all()` on the driver and on elements.
+    pub retry_find: bool,
+    /// The hook called before each retry.
+    pub on_retry: Option<Arc<RetryHook>>,
+}
+
+impl Debug for RetryPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("backoff", &self.backoff)
+            .field("retry_find", &self.retry_find)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl RetryPolicy {
+    /// Create a new `RetryPolicy`.
+    pub fn new(
+        retryable: fn(&WebDriverError) -> bool,
+        max_attempts: u32,
+        backoff: Duration,
+    ) -> Self {
+        Self {
+            retryable,
+            max_attempts,
+            backoff,
+            retry_find: false,
+            on_retry: None,
+        }
+    }
+
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::new(|_| false, 1, Duration::ZERO)
+    }
+
+    /// A policy that retries errors matching [`is_transient`] up to 3 attempts,
+    /// 250 milliseconds apart.
+    pub fn transient() -> Self {
+        Self::new(is_transient, 3, Duration::from_millis(250))
+    }
+
+    /// Set whether `find()` and `find_all()` are retried.
+    pub fn retry_find(mut self, retry_find: bool) -> Self {
+        self.retry_find = retry_find;
+        self
+    }
+
+    /// Set the hook called before each retry.
+    pub fn on_retry(mut self, hook: impl Fn(&RetryEvent<'_>) + Send + Sync + 'static) -> Self {
+        self.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    /// Run the operation, retrying it according to this policy.
+    pub(crate) async fn run<T, F, Fut>(&self, operation: &str, mut f: F) -> WebDriverResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = WebDriverResult<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match f().await {
+                Err(error) if attempt < self.max_attempts && (self.retryable)(&error) => {
+                    tracing::debug!(operation, attempt, %error, "retrying element operation");
+                    if let Some(hook) = &self.on_retry {
+                        hook(&RetryEvent {
+                            operation,
+                            attempt,
+                            error: &error,
+                        });
+                    }
+                    support::sleep(self.backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Run a find operation, retrying it according to this policy if `retry_find` is set.
+    pub(crate) async fn run_find<T, F, Fut>(&self, operation: &str, mut f: F) -> WebDriverResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = WebDriverResult<T>>,
+    {
+        match self.retry_find {
+            true => self.run(operation, f).await,
+            false => f().await,
+        }
+    }
+}
+
+/// Return true for errors that usually go away if the operation is tried again shortly,
+/// such as stale elements, intercepted clicks and elements that are not interactable
+/// during an animation.
+pub fn is_transient(error: &WebDriverError) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::Stale | ErrorKind::ClickIntercepted | ErrorKind::NotInteractable
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WebDriverErrorInfo;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn intercepted() -> WebDriverError {
+        WebDriverError::ElementClickIntercepted(WebDriverErrorInfo::new("intercepted".into()))
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy() {
+        let retries = Arc::new(AtomicU32::new(0));
+        let policy = RetryPolicy::new(is_transient, 3, Duration::ZERO).on_retry({
+            let retries = retries.clone();
+            move |event| {
+                assert_eq!(event.operation, "click");
+                retries.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        // Succeeds on the second attempt.
+        let attempts = AtomicU32::new(0);
+        let result = policy
+            .run("click", || async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(intercepted()),
+                    _ => Ok(()),
+                }
+            })
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(retries.load(Ordering::SeqCst), 1);
+
+        // Gives up after the maximum number of attempts.
+        let attempts = AtomicU32::new(0);
+        let result: WebDriverResult<()> = policy
+            .run("click", || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(intercepted())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(retries.load(Ordering::SeqCst), 3);
+
+        // Other errors are not retried.
+        let attempts = AtomicU32::new(0);
+        let result: WebDriverResult<()> = policy
+            .run("click", || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(WebDriverError::Timeout("timeout".into()))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -4,9 +4,10 @@ use std::sync::Arc;
 use std::{fmt, time::Duration};
 
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 use crate::error::WebDriverResult;
-use crate::WebElement;
+use crate::{By, WebElement};
 
 mod sealed {
     use crate::error::{WebDriverError, WebDriverResult};
@@ -255,6 +256,72 @@ impl fmt::Display for WindowType {
     }
 }
 
+/// The title and URL of a window at the time it was inspected.
+///
+/// See `WebDriver::windows_info()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    /// The window handle.
+    pub handle: WindowHandle,
+    /// The title of the document in the window.
+    pub title: String,
+    /// The URL of the document in the window.
+    pub url: Url,
+}
+
+impl fmt::Display for WindowInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({:?}, {})", self.handle, self.title, self.url)
+    }
+}
+
+/// A single step used when switching into nested frames.
+///
+/// See `WebDriver::switch_into_path()`.
+#[derive(Debug, Clone)]
+pub enum FrameSelector {
+    /// Select a frame by index. The first frame in the document has index 0.
+    Index(u16),
+    /// Select a frame by its `name` or `id` attribute.
+    Name(String),
+    /// Select a frame element using the specified selector.
+    By(By),
+}
+
+impl fmt::Display for FrameSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrameSelector::Index(index) => write!(f, "Index({index})"),
+            FrameSelector::Name(name) => write!(f, "Name({name})"),
+            FrameSelector::By(by) => write!(f, "{by}"),
+        }
+    }
+}
+
+impl From<u16> for FrameSelector {
+    fn from(value: u16) -> Self {
+        FrameSelector::Index(value)
+    }
+}
+
+impl From<&str> for FrameSelector {
+    fn from(value: &str) -> Self {
+        FrameSelector::Name(value.to_string())
+    }
+}
+
+impl From<String> for FrameSelector {
+    fn from(value: String) -> Self {
+        FrameSelector::Name(value)
+    }
+}
+
+impl From<By> for FrameSelector {
+    fn from(value: By) -> Self {
+        FrameSelector::By(value)
+    }
+}
+
 /// Rectangle position and dimensions.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Rect {
@@ -478,12 +545,154 @@ pub struct WebDriverStatus {
     pub message: String,
 }
 
+/// The browser and driver negotiated for a session, from the capabilities returned by the
+/// WebDriver server when the session was created.
+///
+/// See `WebDriver::driver_info()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriverInfo {
+    /// The `browserName` capability, in lowercase, for example "chrome" or "firefox".
+    /// This is empty if the browser is not known.
+    pub browser_name: String,
+    /// The `browserVersion` capability.
+    pub browser_version: String,
+    /// The version of the driver, if the driver reports it.
+    pub driver_version: Option<String>,
+    /// The `platformName` capability, in lowercase, for example "linux", "mac" or
+    /// "windows". This is empty if the platform is not known.
+    pub platform_name: String,
+    /// Where to reach the browser's DevTools, if it exposes them. This is either a
+    /// websocket URL (the `se:cdp` capability set by Selenium) or a `host:port` address
+    /// (the `debuggerAddress` capability set by chromedriver and msedgedriver, or the
+    /// `moz:debuggerAddress` capability set by geckodriver when it is requested).
+    pub devtools_address: Option<String>,
+}
+
+impl DriverInfo {
+    /// Get the browser and driver from the capabilities returned by the WebDriver server.
+    pub fn from_capabilities(capabilities: &serde_json::Value) -> Self {
+        let get = |pointer: &str| capabilities.pointer(pointer).and_then(|x| x.as_str());
+        let browser_name = get("/browserName").unwrap_or_default().to_lowercase();
+        let browser_version = get("/browserVersion").unwrap_or_default().to_string();
+        let driver_version = match browser_name.as_str() {
+            "firefox" => get("/moz:geckodriverVersion"),
+            "msedge" | "microsoftedge" => get("/msedge/msedgedriverVersion"),
+            // safaridriver is released with Safari.
+            "safari" => Some(browser_version.as_str()).filter(|x| !x.is_empty()),
+            _ => get("/chrome/chromedriverVersion"),
+        };
+        let devtools_address = get("/se:cdp")
+            .or_else(|| get("/goog:chromeOptions/debuggerAddress"))
+            .or_else(|| get("/ms:edgeOptions/debuggerAddress"))
+            .or_else(|| get("/moz:debuggerAddress"));
+        Self {
+            devtools_address: devtools_address.map(ToString::to_string),
+            driver_version: driver_version.map(ToString::to_string),
+            platform_name: get("/platformName").unwrap_or_default().to_lowercase(),
+            browser_name,
+            browser_version,
+        }
+    }
+
+    /// True if the browser is based on Chromium, and so supports the Chrome DevTools
+    /// Protocol via the driver.
+    pub(crate) fn is_chromium(&self) -> bool {
+        matches!(
+            self.browser_name.as_str(),
+            "chrome" | "chromium" | "chrome-headless-shell" | "msedge" | "microsoftedge"
+        )
+    }
+
+    /// True if the browser runs on macOS, where shortcuts use the Command key.
+    pub(crate) fn is_mac(&self) -> bool {
+        matches!(self.platform_name.as_str(), "mac" | "macos" | "darwin" | "os x")
+    }
+
+    /// The major version of the browser, for example 120 for "120.0.6099.109".
+    pub(crate) fn browser_major_version(&self) -> Option<u32> {
+        self.browser_version.split('.').next()?.parse().ok()
+    }
+
+    /// The numeric components of the driver version, for example `[120, 0, 6099, 109]`
+    /// for "120.0.6099.109 (3419140ab665596f21b385ce136419fde0924272)".
+    pub(crate) fn driver_version_parts(&self) -> Option<Vec<u32>> {
+        let version = self.driver_version.as_deref()?.split_whitespace().next()?;
+        let parts: Vec<u32> = version.split('.').map_while(|x| x.parse().ok()).collect();
+        (!parts.is_empty()).then_some(parts)
+    }
+}
+
+/// An entry from a browser log, such as the console log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// The log level, for example "SEVERE" or "INFO".
+    pub level: String,
+    /// The log message.
+    pub message: String,
+    /// When the entry was logged, in milliseconds since the Unix epoch.
+    pub timestamp: i64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use assert_matches::assert_matches;
     use serde_json::json;
 
+    #[test]
+    fn test_driver_info() {
+        let info = DriverInfo::from_capabilities(&json!({
+            "browserName": "chrome",
+            "browserVersion": "120.0.6099.109",
+            "chrome": {
+                "chromedriverVersion": "120.0.6099.109 (3419140ab665596f21b385ce136419fde0924272-refs/branch-heads/6099@{#1483})"
+            }
+        }));
+        assert_eq!(info.browser_name, "chrome");
+        assert!(info.is_chromium());
+        assert_eq!(info.driver_version_parts(), Some(vec![120, 0, 6099, 109]));
+        assert_eq!(info.devtools_address, None);
+
+        let info = DriverInfo::from_capabilities(&json!({
+            "browserName": "chrome",
+            "goog:chromeOptions": { "debuggerAddress": "localhost:40315" },
+        }));
+        assert_eq!(info.devtools_address.as_deref(), Some("localhost:40315"));
+
+        let info = DriverInfo::from_capabilities(&json!({
+            "browserName": "firefox",
+            "browserVersion": "121.0",
+            "moz:geckodriverVersion": "0.34.0"
+        }));
+        assert_eq!(info.driver_version.as_deref(), Some("0.34.0"));
+        assert_eq!(info.driver_version_parts(), Some(vec![0, 34, 0]));
+        assert!(!info.is_chromium());
+        assert_eq!(info.devtools_address, None);
+
+        let info = DriverInfo::from_capabilities(&json!({
+            "browserName": "firefox",
+            "moz:debuggerAddress": "127.0.0.1:9222"
+        }));
+        assert_eq!(info.devtools_address.as_deref(), Some("127.0.0.1:9222"));
+
+        let info = DriverInfo::from_capabilities(&json!({
+            "browserName": "Safari",
+            "browserVersion": "17.1"
+        }));
+        assert_eq!(info.browser_name, "safari");
+        assert_eq!(info.driver_version_parts(), Some(vec![17, 1]));
+        assert!(!info.is_mac());
+
+        let info = DriverInfo::from_capabilities(&json!({
+            "browserName": "chrome",
+            "platformName": "Mac"
+        }));
+        assert_eq!(info.platform_name, "mac");
+        assert!(info.is_mac());
+
+        assert_eq!(DriverInfo::from_capabilities(&json!({})), DriverInfo::default());
+    }
+
     #[test]
     fn test_element_ref() {
         let id = "daaea226-43aa-400f-896c-210e5af2ac62";
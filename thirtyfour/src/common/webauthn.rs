@@ -0,0 +1,275 @@
+use std::fmt;
+use std::sync::Arc;
+
+use base64::alphabet::URL_SAFE;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::IntoArcStr;
+
+/// The base64url encoding used for credential ids, keys and user handles.
+///
+/// Padding is omitted when encoding, and accepted but not required when decoding.
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(
+    &URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// The protocol a virtual authenticator speaks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AuthenticatorProtocol {
+    /// CTAP1/U2F.
+    #[serde(rename = "ctap1/u2f")]
+    Ctap1U2f,
+    /// CTAP2.
+    #[default]
+    #[serde(rename = "ctap2")]
+    Ctap2,
+    /// CTAP2.1.
+    #[serde(rename = "ctap2_1")]
+    Ctap2_1,
+}
+
+/// How a virtual authenticator is connected to the browser.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthenticatorTransport {
+    /// A USB security key.
+    Usb,
+    /// An NFC security key.
+    Nfc,
+    /// A Bluetooth Low Energy security key.
+    Ble,
+    /// A smart card.
+    SmartCard,
+    /// A phone, connected via the hybrid transport.
+    Hybrid,
+    /// An authenticator built into the device, such as a fingerprint reader.
+    #[default]
+    Internal,
+}
+
+/// The options for `WebDriver::add_virtual_authenticator()`.
+///
+/// The defaults are those of the WebAuthn spec, for a CTAP2 authenticator built into
+/// the device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualAuthenticatorOptions {
+    /// The protocol the authenticator speaks.
+    pub protocol: AuthenticatorProtocol,
+    /// How the authenticator is connected to the browser.
+    pub transport: AuthenticatorTransport,
+    /// True if the authenticator can store resident credentials, also known as
+    /// discoverable credentials or passkeys.
+    pub has_resident_key: bool,
+    /// True if the authenticator can verify the user, for example with a PIN.
+    pub has_user_verification: bool,
+    /// True if the user consents to every operation, as if they touched the
+    /// authenticator.
+    pub is_user_consenting: bool,
+    /// True if user verification succeeds. See `WebDriver::set_user_verified()`.
+    pub is_user_verified: bool,
+}
+
+impl Default for VirtualAuthenticatorOptions {
+    fn default() -> Self {
+        Self {
+            protocol: AuthenticatorProtocol::default(),
+            transport: AuthenticatorTransport::default(),
+            has_resident_key: false,
+            has_user_verification: false,
+            is_user_consenting: true,
+            is_user_verified: false,
+        }
+    }
+}
+
+/// The id of a virtual authenticator, returned by `WebDriver::add_virtual_authenticator()`.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct AuthenticatorId {
+    id: Arc<str>,
+}
+
+impl<S> From<S> for AuthenticatorId
+where
+    S: IntoArcStr,
+{
+    fn from(value: S) -> Self {
+        AuthenticatorId {
+            id: value.into(),
+        }
+    }
+}
+
+impl fmt::Display for AuthenticatorId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+/// A public key credential stored by a virtual authenticator.
+///
+/// The byte fields are sent to the WebDriver server base64url encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Credential {
+    /// The id of the credential.
+    #[serde(with = "base64_url")]
+    pub credential_id: Vec<u8>,
+    /// True if this is a resident credential, which can be found without knowing its id.
+    pub is_resident_credential: bool,
+    /// The id of the relying party the credential is for, usually the domain of the site.
+    pub rp_id: String,
+    /// The private key of the credential, as a PKCS#8 document.
+    #[serde(with = "base64_url")]
+    pub private_key: Vec<u8>,
+    /// The id of the user the credential is for. Resident credentials must have one.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "base64_url_option")]
+    pub user_handle: Option<Vec<u8>>,
+    /// The number of times the credential has been used to sign in.
+    #[serde(default)]
+    pub sign_count: u32,
+}
+
+impl Credential {
+    /// Create a resident credential, which the site can find without knowing its id.
+    pub fn resident(
+        credential_id: impl Into<Vec<u8>>,
+        rp_id: impl Into<String>,
+        private_key: impl Into<Vec<u8>>,
+        user_handle: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            credential_id: credential_id.into(),
+            is_resident_credential: true,
+            rp_id: rp_id.into(),
+            private_key: private_key.into(),
+            user_handle: Some(user_handle.into()),
+            sign_count: 0,
+        }
+    }
+
+    /// Create a non-resident credential, which the site must ask for by its id.
+    pub fn non_resident(
+        credential_id: impl Into<Vec<u8>>,
+        rp_id: impl Into<String>,
+        private_key: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            credential_id: credential_id.into(),
+            is_resident_credential: false,
+            rp_id: rp_id.into(),
+            private_key: private_key.into(),
+            user_handle: None,
+            sign_count: 0,
+        }
+    }
+}
+
+/// Encode a credential id for the path of a WebDriver command.
+pub(crate) fn encode_credential_id(credential_id: &[u8]) -> String {
+    BASE64_URL.encode(credential_id)
+}
+
+mod base64_url {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64_URL.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        BASE64_URL.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+mod base64_url_option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => base64_url::serialize(bytes, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(encoded) if !encoded.is_empty() => {
+                BASE64_URL.decode(encoded).map(Some).map_err(serde::de::Error::custom)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_virtual_authenticator_options() {
+        let options = VirtualAuthenticatorOptions {
+            protocol: AuthenticatorProtocol::Ctap1U2f,
+            transport: AuthenticatorTransport::SmartCard,
+            has_resident_key: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(options).unwrap(),
+            json!({
+                "protocol": "ctap1/u2f",
+                "transport": "smart-card",
+                "hasResidentKey": true,
+                "hasUserVerification": false,
+                "isUserConsenting": true,
+                "isUserVerified": false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_credential() {
+        let credential = Credential::resident([0xfb, 0xff], "localhost", [1, 2, 3], *b"alice");
+        let value = serde_json::to_value(&credential).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "credentialId": "-_8",
+                "isResidentCredential": true,
+                "rpId": "localhost",
+                "privateKey": "AQID",
+                "userHandle": "YWxpY2U",
+                "signCount": 0,
+            })
+        );
+        assert_eq!(serde_json::from_value::<Credential>(value).unwrap(), credential);
+
+        // Padding is accepted, and an empty user handle means there is none.
+        let credential: Credential = serde_json::from_value(json!({
+            "credentialId": "-_8=",
+            "isResidentCredential": false,
+            "rpId": "localhost",
+            "privateKey": "AQID",
+            "userHandle": "",
+            "signCount": 2,
+        }))
+        .unwrap();
+        assert_eq!(credential.credential_id, [0xfb, 0xff]);
+        assert_eq!((credential.user_handle, credential.sign_count), (None, 2));
+        assert_eq!(encode_credential_id(&[0xfb, 0xff]), "-_8");
+    }
+}
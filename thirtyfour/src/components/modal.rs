@@ -0,0 +1,380 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::components::{Component, ElementResolver, RootComponent};
+use crate::error::{no_such_element, TimeoutErrorBuilder, WebDriverErrorInner, WebDriverResult};
+use crate::extensions::query::{ElementPollerWithTimeout, IntoElementPoller};
+use crate::js::{CORNER_HIT_IS_ONE_OF, TOP_MODAL};
+use crate::{By, WebElement};
+
+/// The interval between checks in [`ModalComponent::wait_open`] and
+/// [`ModalComponent::wait_closed`].
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Return the top-most open element out of the specified candidates, if any are open.
+async fn top_modal(candidates: Vec<WebElement>) -> WebDriverResult<Option<WebElement>> {
+    let Some(first) = candidates.first() else {
+        return Ok(None);
+    };
+    let handle = first.handle.clone();
+    let candidates =
+        candidates.iter().map(WebElement::to_json).collect::<WebDriverResult<Vec<_>>>()?;
+    let ret = handle.execute(TOP_MODAL, vec![Value::Array(candidates)]).await?;
+    match ret.json() {
+        Value::Null => Ok(None),
+        _ => ret.element().map(Some),
+    }
+}
+
+/// Return the top-most open container matching `by`, relative to the base element.
+async fn top_container(base: &WebElement, by: &By) -> WebDriverResult<Option<WebElement>> {
+    top_modal(base.find_all(by.clone()).await?).await
+}
+
+/// Return true if the specified container is still open.
+async fn is_still_open(container: &WebElement) -> WebDriverResult<bool> {
+    match top_modal(vec![container.clone()]).await {
+        Ok(top) => Ok(top.is_some()),
+        Err(e) if matches!(e.as_inner(), WebDriverErrorInner::StaleElementReference(_)) => {
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Return true if a click at the top-left corner of the backdrop would reach the backdrop
+/// or the container itself, rather than the modal content or some other element.
+async fn is_corner_clickable(
+    backdrop: &WebElement,
+    container: &WebElement,
+) -> WebDriverResult<bool> {
+    let args = vec![backdrop.to_json()?, container.to_json()?];
+    backdrop.handle.execute(CORNER_HIT_IS_ONE_OF, args).await?.convert()
+}
+
+/// Create a resolver for the element matching `by` within the resolved container.
+fn within_container(
+    base: &WebElement,
+    container: &ElementResolver<WebElement>,
+    by: By,
+) -> ElementResolver<WebElement> {
+    let container = container.clone();
+    ElementResolver::new_custom(base.clone(), move |_| {
+        let container = container.clone();
+        let by = by.clone();
+        async move { container.resolve_present().await?.find(by).await }
+    })
+}
+
+/// Return the first of the specified elements that is displayed.
+async fn first_displayed(
+    elements: impl IntoIterator<Item = WebElement>,
+) -> WebDriverResult<Option<WebElement>> {
+    for elem in elements {
+        if elem.is_displayed().await? {
+            return Ok(Some(elem));
+        }
+    }
+    Ok(None)
+}
+
+/// A `Component` wrapping the modal dialogs within a page.
+///
+/// The base element is the element containing the modals, usually `<body>`, and
+/// [`ModalComponent::from_driver`](RootComponent::from_driver) uses that. Out of the elements
+/// matching the container selector, only the top-most open one is used, so stacked modals
+/// work as expected. Native `<dialog>` elements are open if their `open` property is set,
+/// and any other element is open if it is displayed. A dialog shown with `showModal()` is
+/// always on top, otherwise the container with the highest z-index wins, and the last one
+/// in the document wins a tie.
+///
+/// The default selectors suit Bootstrap-style modals as well as native `<dialog>` elements:
+///
+/// | Selector     | Default                                                                  |
+/// |--------------|--------------------------------------------------------------------------|
+/// | container    | `.modal, dialog`                                                         |
+/// | close button | `[data-bs-dismiss='modal'], [data-dismiss='modal'], .btn-close, .close, form[method='dialog'] button` |
+/// | backdrop     | `.modal-backdrop`                                                        |
+/// | title        | `.modal-title`                                                           |
+/// | body         | `.modal-body`                                                            |
+///
+/// The close button, title and body are found within the container. The backdrop is found
+/// within the base element.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use std::time::Duration;
+/// use thirtyfour::components::modal::ModalComponent;
+/// use thirtyfour::components::RootComponent;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let modal = ModalComponent::from_driver(&driver).await?;
+/// driver.find(By::Id("delete")).await?.click().await?;
+/// modal.wait_open(Duration::from_secs(5)).await?;
+/// assert_eq!(modal.title().resolve().await?.text().await?, "Are you sure?");
+/// modal.close().await?;
+/// modal.wait_closed(Duration::from_secs(5)).await?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModalComponent {
+    base: WebElement,
+    selectors: Selectors,
+    container: ElementResolver<WebElement>,
+    title: ElementResolver<WebElement>,
+    body: ElementResolver<WebElement>,
+}
+
+/// The selectors used by a `ModalComponent`.
+#[derive(Debug, Clone)]
+struct Selectors {
+    container: By,
+    close_button: Option<By>,
+    backdrop: Option<By>,
+    title: By,
+    body: By,
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        Self {
+            container: By::Css(".modal, dialog"),
+            close_button: Some(By::Css(
+                "[data-bs-dismiss='modal'], [data-dismiss='modal'], .btn-close, .close, \
+                 form[method='dialog'] button",
+            )),
+            backdrop: Some(By::ClassName("modal-backdrop")),
+            title: By::ClassName("modal-title"),
+            body: By::ClassName("modal-body"),
+        }
+    }
+}
+
+impl ModalComponent {
+    /// Create a new `ModalComponent` with the default selectors.
+    pub fn new(base: WebElement) -> Self {
+        Self::build(base, Selectors::default())
+    }
+
+    /// Create the resolvers for the specified selectors.
+    fn build(base: WebElement, selectors: Selectors) -> Self {
+        let container_by = selectors.container.clone();
+        let container = ElementResolver::new_custom(base.clone(), move |base: WebElement| {
+            let by = container_by.clone();
+            async move {
+                top_container(&base, &by)
+                    .await?
+                    .ok_or_else(|| no_such_element(format!("no open modal matching {by}")))
+            }
+        });
+        let title = within_container(&base, &container, selectors.title.clone());
+        let body = within_container(&base, &container, selectors.body.clone());
+        Self {
+            base,
+            selectors,
+            container,
+            title,
+            body,
+        }
+    }
+
+    /// Set the selector for the modal containers, relative to the base element.
+    pub fn set_container(mut self, by: By) -> Self {
+        self.selectors.container = by;
+        Self::build(self.base, self.selectors)
+    }
+
+    /// Set the selector for the close button, relative to the container.
+    ///
+    /// If this is `None`, [`ModalComponent::close`] only clicks the backdrop.
+    pub fn set_close_button(mut self, by: Option<By>) -> Self {
+        self.selectors.close_button = by;
+        self
+    }
+
+    /// Set the selector for the backdrop, relative to the base element.
+    ///
+    /// If this is `None`, [`ModalComponent::close`] only clicks the close button.
+    pub fn set_backdrop(mut self, by: Option<By>) -> Self {
+        self.selectors.backdrop = by;
+        self
+    }
+
+    /// Set the selector for the title, relative to the container.
+    pub fn set_title(mut self, by: By) -> Self {
+        self.selectors.title = by;
+        Self::build(self.base, self.selectors)
+    }
+
+    /// Set the selector for the body, relative to the container.
+    pub fn set_body(mut self, by: By) -> Self {
+        self.selectors.body = by;
+        Self::build(self.base, self.selectors)
+    }
+
+    /// The resolver for the top-most open container.
+    pub fn container(&self) -> &ElementResolver<WebElement> {
+        &self.container
+    }
+
+    /// The resolver for the title of the top-most open modal.
+    pub fn title(&self) -> &ElementResolver<WebElement> {
+        &self.title
+    }
+
+    /// The resolver for the body of the top-most open modal.
+    pub fn body(&self) -> &ElementResolver<WebElement> {
+        &self.body
+    }
+
+    /// Return true if any modal is currently open, without waiting.
+    pub async fn is_open(&self) -> WebDriverResult<bool> {
+        Ok(top_container(&self.base, &self.selectors.container).await?.is_some())
+    }
+
+    /// Wait until a modal is open, and return its container.
+    ///
+    /// The cached elements are invalidated first, so if another modal has been opened on top
+    /// of the current one, the new modal is returned.
+    pub async fn wait_open(&self, timeout: Duration) -> WebDriverResult<WebElement> {
+        self.invalidate_all();
+        let mut wait = TimeoutErrorBuilder::new("a modal to open");
+        let mut poller = ElementPollerWithTimeout::new(timeout, POLL_INTERVAL).start();
+        let poll = async {
+            loop {
+                wait.attempt();
+                match self.container.resolve().await {
+                    Ok(container) => return Ok(container),
+                    Err(e) if matches!(e.as_inner(), WebDriverErrorInner::NoSuchElement(_)) => {}
+                    Err(e) => return Err(e),
+                }
+                wait.observed(format!(
+                    "no open modal matching {}",
+                    self.selectors.container.describe()
+                ));
+
+                if !poller.tick().await {
+                    return Err(wait.build());
+                }
+            }
+        };
+        self.base.handle.report_errors(poll).await
+    }
+
+    /// Wait until the modal is closed.
+    ///
+    /// This waits for the container that was last resolved, for example by
+    /// [`ModalComponent::wait_open`] or [`ModalComponent::close`], so that any modals
+    /// underneath it do not count. If no container was resolved, this waits for the top-most
+    /// open modal, and returns immediately if there is none. The cached elements are
+    /// invalidated afterwards.
+    pub async fn wait_closed(&self, timeout: Duration) -> WebDriverResult<()> {
+        let handle = self.base.handle.clone();
+        handle.report_errors(self.wait_container_closed(timeout)).await?;
+        self.invalidate_all();
+        Ok(())
+    }
+
+    /// Wait until the container that was last resolved, if any, is closed.
+    async fn wait_container_closed(&self, timeout: Duration) -> WebDriverResult<()> {
+        let container = match self.container.resolve().await {
+            Ok(container) => container,
+            Err(e) if matches!(e.as_inner(), WebDriverErrorInner::NoSuchElement(_)) => {
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut wait = TimeoutErrorBuilder::new("the modal to close");
+        let mut poller = ElementPollerWithTimeout::new(timeout, POLL_INTERVAL).start();
+        loop {
+            wait.attempt();
+            if !is_still_open(&container).await? {
+                break;
+            }
+            wait.observed("the modal still open");
+
+            if !poller.tick().await {
+                return Err(wait.build());
+            }
+        }
+        Ok(())
+    }
+
+    /// Close the top-most open modal.
+    ///
+    /// This clicks the first displayed close button in the container. If there is no close
+    /// button, it clicks the top-left corner of the last displayed backdrop instead, which
+    /// closes modals that are dismissed by clicking outside them. The corner is only clicked
+    /// if the backdrop, or the container itself, is the top-most element there. Returns
+    /// `NoSuchElement` if there is no close button and the backdrop cannot be clicked.
+    ///
+    /// This does not wait for the modal to close. Use [`ModalComponent::wait_closed`] for that.
+    pub async fn close(&self) -> WebDriverResult<()> {
+        let container = self.container.resolve_present().await?;
+        if let Some(by) = &self.selectors.close_button {
+            if let Some(button) = first_displayed(container.find_all(by.clone()).await?).await? {
+                return button.click().await;
+            }
+        }
+
+        if let Some(by) = &self.selectors.backdrop {
+            let backdrops = self.base.find_all(by.clone()).await?;
+            if let Some(backdrop) = first_displayed(backdrops.into_iter().rev()).await? {
+                if !is_corner_clickable(&backdrop, &container).await? {
+                    return Err(no_such_element(
+                        "no close button found for the open modal, and its backdrop is \
+                         covered by another element"
+                            .to_string(),
+                    ));
+                }
+                // Offsets are relative to the centre of the element.
+                let rect = backdrop.rect().await?;
+                let x = 1 - (rect.width / 2.0) as i64;
+                let y = 1 - (rect.height / 2.0) as i64;
+                return backdrop
+                    .handle
+                    .action_chain()
+                    .move_to_element_with_offset(&backdrop, x, y)
+                    .click()
+                    .perform()
+                    .await;
+            }
+        }
+
+        Err(no_such_element("no close button or backdrop found for the open modal".to_string()))
+    }
+}
+
+impl From<WebElement> for ModalComponent {
+    fn from(base: WebElement) -> Self {
+        Self::new(base)
+    }
+}
+
+impl Component for ModalComponent {
+    fn base_element(&self) -> WebElement {
+        self.base.clone()
+    }
+
+    fn invalidate_all(&self) {
+        self.container.invalidate();
+        self.title.invalidate();
+        self.body.invalidate();
+    }
+}
+
+impl RootComponent for ModalComponent {
+    fn root_selector() -> By {
+        By::Tag("body")
+    }
+}
@@ -19,9 +19,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::{no_such_element, WebDriverErrorInner, WebDriverResult};
+use crate::components::Component;
+use crate::error::{
+    no_such_element, WebDriverError, WebDriverErrorInfo, WebDriverErrorInner, WebDriverResult,
+};
 use crate::{By, WebElement};
 use std::fmt::{Display, Formatter};
+use std::future::Future;
 
 /// Set the selection state of the specified element.
 async fn set_selected(element: &WebElement, select: bool) -> WebDriverResult<()> {
@@ -341,3 +345,257 @@ impl SelectElement {
         self.set_selection_by_partial_text(text, false).await
     }
 }
+
+/// Common operations for dropdowns, so that a native `<select>` and a custom dropdown can be
+/// used interchangeably.
+///
+/// [`SelectComponent`] implements this for `<select>` elements. Implement it for your own
+/// dropdown components, and use a type parameter to let other components use either.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use thirtyfour::components::select::{DropdownLike, SelectComponent};
+/// use thirtyfour::components::{Component, ElementResolver};
+///
+/// #[derive(Debug, Clone, Component)]
+/// pub struct AddressForm<D: DropdownLike + Clone + 'static> {
+///     base: WebElement,
+///     #[by(name = "country")]
+///     country: ElementResolver<D>,
+/// }
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let elem = driver.find(By::Id("address-form")).await?;
+/// let form = AddressForm::<SelectComponent>::new(elem);
+/// form.country.resolve().await?.select_text("New Zealand").await?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+pub trait DropdownLike: Component + Send + Sync {
+    /// Select the option with the specified value.
+    fn select_value(&self, value: &str) -> impl Future<Output = WebDriverResult<()>> + Send;
+
+    /// Select the option with the specified text.
+    fn select_text(&self, text: &str) -> impl Future<Output = WebDriverResult<()>> + Send;
+
+    /// Get the text of the selected option.
+    fn selected_text(&self) -> impl Future<Output = WebDriverResult<String>> + Send;
+
+    /// Get the text of every option.
+    fn options_text(&self) -> impl Future<Output = WebDriverResult<Vec<String>>> + Send;
+}
+
+/// The property used to match an option.
+#[derive(Debug, Clone, Copy)]
+enum OptionKey {
+    Value,
+    Text,
+}
+
+impl OptionKey {
+    async fn of(self, option: &WebElement) -> WebDriverResult<String> {
+        match self {
+            OptionKey::Value => option.value().await.map(Option::unwrap_or_default),
+            OptionKey::Text => option.text().await,
+        }
+    }
+}
+
+impl Display for OptionKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionKey::Value => write!(f, "value"),
+            OptionKey::Text => write!(f, "text"),
+        }
+    }
+}
+
+/// A `Component` wrapping a `<select>` element.
+///
+/// Unlike [`SelectElement`], this can be used as a field in other components, and every
+/// selection is verified by checking that the option is selected afterwards, which also
+/// means the `change` event has fired. The options are queried each time they are needed,
+/// so options that are added or removed after the component was created are always seen.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use thirtyfour::components::select::SelectComponent;
+/// use thirtyfour::components::{Component, ElementResolver};
+///
+/// #[derive(Debug, Clone, Component)]
+/// pub struct AddressForm {
+///     base: WebElement,
+///     #[by(name = "country")]
+///     country: ElementResolver<SelectComponent>,
+/// }
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let form = AddressForm::new(driver.find(By::Id("address-form")).await?);
+/// let country = form.country.resolve().await?;
+/// country.select_text("New Zealand").await?;
+/// assert_eq!(country.selected_text().await?, "New Zealand");
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SelectComponent {
+    base: WebElement,
+}
+
+impl SelectComponent {
+    /// Create a new `SelectComponent`. The base element must be a `<select>` element.
+    pub fn new(base: WebElement) -> Self {
+        Self {
+            base,
+        }
+    }
+
+    /// Get the `<option>` elements.
+    pub async fn options(&self) -> WebDriverResult<Vec<WebElement>> {
+        self.base.find_all(By::Tag("option")).await
+    }
+
+    /// Return true if this is a multi-select.
+    pub async fn is_multiple(&self) -> WebDriverResult<bool> {
+        Ok(self.base.prop("multiple").await?.is_some_and(|x| x == "true"))
+    }
+
+    /// Get the value of the select element, which is the value of the first selected option.
+    pub async fn value(&self) -> WebDriverResult<String> {
+        self.base.value().await.map(Option::unwrap_or_default)
+    }
+
+    /// Get the text of every option.
+    pub async fn options_text(&self) -> WebDriverResult<Vec<String>> {
+        let mut texts = Vec::new();
+        for option in self.options().await? {
+            texts.push(option.text().await?);
+        }
+        Ok(texts)
+    }
+
+    /// Get the text of the first selected option.
+    ///
+    /// Returns `NoSuchElement` if no option is selected.
+    pub async fn selected_text(&self) -> WebDriverResult<String> {
+        match self.selected(OptionKey::Text).await?.into_iter().next() {
+            Some(text) => Ok(text),
+            None => Err(no_such_element("No options are selected".to_string())),
+        }
+    }
+
+    /// Get the text of every selected option.
+    pub async fn selected_texts(&self) -> WebDriverResult<Vec<String>> {
+        self.selected(OptionKey::Text).await
+    }
+
+    /// Get the value of every selected option.
+    pub async fn selected_values(&self) -> WebDriverResult<Vec<String>> {
+        self.selected(OptionKey::Value).await
+    }
+
+    /// Select the first option with the specified value.
+    ///
+    /// For a multi-select, any other selected options stay selected.
+    pub async fn select_value(&self, value: &str) -> WebDriverResult<()> {
+        self.select(OptionKey::Value, value).await
+    }
+
+    /// Select the first option with the specified text.
+    ///
+    /// For a multi-select, any other selected options stay selected.
+    pub async fn select_text(&self, text: &str) -> WebDriverResult<()> {
+        self.select(OptionKey::Text, text).await
+    }
+
+    /// Deselect every option of a multi-select.
+    ///
+    /// Returns `InvalidElementState` if this is not a multi-select.
+    pub async fn deselect_all(&self) -> WebDriverResult<()> {
+        if !self.is_multiple().await? {
+            return Err(WebDriverError::InvalidElementState(WebDriverErrorInfo::new(
+                "only the options of a multi-select can be deselected".to_string(),
+            )));
+        }
+        for option in self.options().await? {
+            set_selected(&option, false).await?;
+        }
+        Ok(())
+    }
+
+    async fn selected(&self, key: OptionKey) -> WebDriverResult<Vec<String>> {
+        let mut selected = Vec::new();
+        for option in self.options().await? {
+            if option.is_selected().await? {
+                selected.push(key.of(&option).await?);
+            }
+        }
+        Ok(selected)
+    }
+
+    async fn select(&self, key: OptionKey, expected: &str) -> WebDriverResult<()> {
+        let mut found = None;
+        for option in self.options().await? {
+            if key.of(&option).await? == expected {
+                found = Some(option);
+                break;
+            }
+        }
+        let option = found.ok_or_else(|| {
+            no_such_element(format!("No option with {key} '{expected}' was found"))
+        })?;
+
+        set_selected(&option, true).await?;
+        if !option.is_selected().await? {
+            return Err(WebDriverError::InvalidElementState(WebDriverErrorInfo::new(format!(
+                "the option with {key} '{expected}' was clicked but is not selected, \
+                 it may be disabled"
+            ))));
+        }
+        Ok(())
+    }
+}
+
+impl From<WebElement> for SelectComponent {
+    fn from(base: WebElement) -> Self {
+        Self::new(base)
+    }
+}
+
+impl Component for SelectComponent {
+    fn base_element(&self) -> WebElement {
+        self.base.clone()
+    }
+}
+
+impl DropdownLike for SelectComponent {
+    async fn select_value(&self, value: &str) -> WebDriverResult<()> {
+        SelectComponent::select_value(self, value).await
+    }
+
+    async fn select_text(&self, text: &str) -> WebDriverResult<()> {
+        SelectComponent::select_text(self, text).await
+    }
+
+    async fn selected_text(&self) -> WebDriverResult<String> {
+        SelectComponent::selected_text(self).await
+    }
+
+    async fn options_text(&self) -> WebDriverResult<Vec<String>> {
+        SelectComponent::options_text(self).await
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::components::{Component, ElementResolver};
+use crate::error::{no_such_element, WebDriverResult};
+use crate::extensions::query::{ElementQueryOptions, ElementQueryWaitOptions};
+use crate::js::TABLE_CELLS;
+use crate::{By, WebElement};
+
+/// How a cell spanning multiple columns is expanded, so that there is one value per column.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Colspan {
+    /// Repeat the text of the cell in every column it spans.
+    #[default]
+    Repeat,
+    /// Use the text of the cell in the first column it spans, and an empty string in the rest.
+    Pad,
+}
+
+fn no_wait() -> ElementQueryOptions {
+    ElementQueryOptions::default().wait(ElementQueryWaitOptions::NoWait)
+}
+
+/// Get the text of the specified cells, with one value per column.
+async fn cell_texts(cells: &[WebElement], colspan: Colspan) -> WebDriverResult<Vec<String>> {
+    let Some(first) = cells.first() else {
+        return Ok(Vec::new());
+    };
+    let cells = cells.iter().map(WebElement::to_json).collect::<WebDriverResult<Vec<_>>>()?;
+    let ret = first.handle.execute(TABLE_CELLS, vec![Value::Array(cells)]).await?;
+    Ok(expand_colspans(ret.convert()?, colspan))
+}
+
+/// Expand the text and column span of each cell, to one value per column.
+fn expand_colspans(cells: Vec<(String, usize)>, colspan: Colspan) -> Vec<String> {
+    let mut texts = Vec::new();
+    for (text, span) in cells {
+        for i in 0..span.max(1) {
+            match (colspan, i) {
+                (Colspan::Pad, 1..) => texts.push(String::new()),
+                _ => texts.push(text.clone()),
+            }
+        }
+    }
+    texts
+}
+
+/// A `Component` wrapping a table row.
+///
+/// This is returned by [`TableComponent::find_row`], so you can interact with the row,
+/// for example by clicking a button in it.
+#[derive(Debug, Clone)]
+pub struct RowComponent {
+    base: WebElement,
+    cells: ElementResolver<Vec<WebElement>>,
+}
+
+impl RowComponent {
+    /// Create a new `RowComponent`. The base element must be a `<tr>` element.
+    pub fn new(base: WebElement) -> Self {
+        let cells = ElementResolver::<Vec<WebElement>>::new_allow_empty_opts(
+            base.clone(),
+            By::Css(":scope > td, :scope > th"),
+            no_wait(),
+        );
+        Self {
+            base,
+            cells,
+        }
+    }
+
+    /// The resolver for the `<td>` and `<th>` elements in this row.
+    pub fn cells(&self) -> &ElementResolver<Vec<WebElement>> {
+        &self.cells
+    }
+
+    /// Get the text of each cell in this row, with one value per column.
+    pub async fn texts(&self, colspan: Colspan) -> WebDriverResult<Vec<String>> {
+        cell_texts(&self.cells.resolve_present().await?, colspan).await
+    }
+}
+
+impl From<WebElement> for RowComponent {
+    fn from(base: WebElement) -> Self {
+        Self::new(base)
+    }
+}
+
+impl Component for RowComponent {
+    fn base_element(&self) -> WebElement {
+        self.base.clone()
+    }
+
+    fn invalidate_all(&self) {
+        self.cells.invalidate();
+    }
+}
+
+/// A `Component` wrapping a `<table>` element with a `<thead>` and `<tbody>`.
+///
+/// The headers are the `<th>` elements in the `<thead>`, and the rows are the `<tr>` elements
+/// in the `<tbody>`. Rows and columns are indexed from 0. The text of each cell is the text
+/// that is displayed, with leading and trailing whitespace removed.
+///
+/// Cells spanning multiple columns are repeated in each column by default. Use
+/// [`TableComponent::set_colspan`] to pad them with empty strings instead.
+///
+/// # Example:
+/// ```ignore
+/// let table = TableComponent::new(driver.find(By::Id("users")).await?);
+/// assert_eq!(table.headers().await?, ["Name", "Email", "Actions"]);
+/// assert_eq!(table.cell(0, table.column_index("Email").await?).await?, "ann@example.com");
+///
+/// let row = table.find_row(|row| row["Name"] == "Bob").await?.expect("Bob not found");
+/// row.base_element().find(By::Css("button.delete")).await?.click().await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TableComponent {
+    base: WebElement,
+    headers: ElementResolver<Vec<WebElement>>,
+    rows: ElementResolver<Vec<RowComponent>>,
+    colspan: Colspan,
+}
+
+impl TableComponent {
+    /// Create a new `TableComponent`. The base element must be a `<table>` element.
+    pub fn new(base: WebElement) -> Self {
+        let headers = ElementResolver::<Vec<WebElement>>::new_allow_empty_opts(
+            base.clone(),
+            By::Css(":scope > thead > tr > th"),
+            no_wait(),
+        );
+        let rows = ElementResolver::<Vec<RowComponent>>::new_allow_empty_opts(
+            base.clone(),
+            By::Css(":scope > tbody > tr"),
+            no_wait(),
+        );
+        Self {
+            base,
+            headers,
+            rows,
+            colspan: Colspan::default(),
+        }
+    }
+
+    /// Set how cells spanning multiple columns are expanded.
+    pub fn set_colspan(mut self, colspan: Colspan) -> Self {
+        self.colspan = colspan;
+        self
+    }
+
+    /// The resolver for the rows in the `<tbody>`.
+    pub fn rows(&self) -> &ElementResolver<Vec<RowComponent>> {
+        &self.rows
+    }
+
+    /// Get the text of each header, with one value per column.
+    pub async fn headers(&self) -> WebDriverResult<Vec<String>> {
+        cell_texts(&self.headers.resolve_present().await?, self.colspan).await
+    }
+
+    /// Get the number of rows in the `<tbody>`.
+    pub async fn row_count(&self) -> WebDriverResult<usize> {
+        Ok(self.rows.resolve_present().await?.len())
+    }
+
+    /// Get the index of the column with the specified header.
+    ///
+    /// Returns `NoSuchElement` if there is no such header.
+    pub async fn column_index(&self, header: &str) -> WebDriverResult<usize> {
+        self.headers()
+            .await?
+            .iter()
+            .position(|x| x == header)
+            .ok_or_else(|| no_such_element(format!("No column with header '{header}' was found")))
+    }
+
+    /// Get the text of the cell in the specified row and column.
+    ///
+    /// Returns `NoSuchElement` if there is no such cell.
+    pub async fn cell(&self, row: usize, col: usize) -> WebDriverResult<String> {
+        let rows = self.rows.resolve_present().await?;
+        let texts = match rows.get(row) {
+            Some(x) => x.texts(self.colspan).await?,
+            None => return Err(no_such_element(format!("Row {row} was not found"))),
+        };
+        texts
+            .into_iter()
+            .nth(col)
+            .ok_or_else(|| no_such_element(format!("Column {col} of row {row} was not found")))
+    }
+
+    /// Get every row as a map from header to cell text.
+    ///
+    /// Cells without a header are left out.
+    pub async fn rows_as_maps(&self) -> WebDriverResult<Vec<HashMap<String, String>>> {
+        Ok(self.row_maps().await?.into_iter().map(|(_, map)| map).collect())
+    }
+
+    /// Return the first row for which the predicate returns true, given the row as a map
+    /// from header to cell text.
+    pub async fn find_row<F>(&self, predicate: F) -> WebDriverResult<Option<RowComponent>>
+    where
+        F: Fn(&HashMap<String, String>) -> bool,
+    {
+        Ok(self.row_maps().await?.into_iter().find(|(_, map)| predicate(map)).map(|(row, _)| row))
+    }
+
+    async fn row_maps(&self) -> WebDriverResult<Vec<(RowComponent, HashMap<String, String>)>> {
+        let headers = self.headers().await?;
+        let mut maps = Vec::new();
+        for row in self.rows.resolve_present().await? {
+            let texts = row.texts(self.colspan).await?;
+            let map = headers.iter().cloned().zip(texts).collect();
+            maps.push((row, map));
+        }
+        Ok(maps)
+    }
+}
+
+impl From<WebElement> for TableComponent {
+    fn from(base: WebElement) -> Self {
+        Self::new(base)
+    }
+}
+
+impl Component for TableComponent {
+    fn base_element(&self) -> WebElement {
+        self.base.clone()
+    }
+
+    fn invalidate_all(&self) {
+        self.headers.invalidate();
+        self.rows.invalidate_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_colspans() {
+        let cells = || vec![("a".to_string(), 1), ("b".to_string(), 3), ("c".to_string(), 0)];
+        assert_eq!(expand_colspans(cells(), Colspan::Repeat), ["a", "b", "b", "b", "c"]);
+        assert_eq!(expand_colspans(cells(), Colspan::Pad), ["a", "b", "", "", "c"]);
+        assert!(expand_colspans(Vec::new(), Colspan::Pad).is_empty());
+    }
+}
@@ -1,8 +1,17 @@
 mod resolver;
+mod stream;
 
-use crate::WebElement;
+use std::future::Future;
+use std::sync::Arc;
 
+use crate::error::WebDriverResult;
+use crate::extensions::query::{ElementQueryOptions, ElementQueryable};
+use crate::session::handle::SessionHandle;
+use crate::{By, WebElement};
+
+use resolver::ready_component;
 pub use resolver::*;
+pub use stream::*;
 
 #[cfg(feature = "component")]
 pub use thirtyfour_macros::Component;
@@ -13,7 +22,118 @@ pub use thirtyfour_macros::Component;
 /// ElementResolver to take the resolved element as input, and return the specific type.
 ///
 /// There is also an implementation of ElementResolver for a Vec containing such types.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a component",
+    note = "use `#[derive(Component)]` on `{Self}`, or implement `Component` for it manually"
+)]
 pub trait Component: Sized + From<WebElement> {
     /// Get the base element for this component.
     fn base_element(&self) -> WebElement;
+
+    /// Wait until the component is ready to use.
+    ///
+    /// `ElementResolver` calls this after constructing the component and before returning it.
+    /// This does nothing by default. The `#[component(await_ready)]` attribute on the
+    /// `Component` derive implements it by calling [`ComponentLifecycle::on_resolved`].
+    fn await_ready(&self) -> impl Future<Output = WebDriverResult<()>> + Send {
+        std::future::ready(Ok(()))
+    }
+
+    /// Invalidate every cached element in this component, including those in nested
+    /// components, for example after the page has been re-rendered.
+    ///
+    /// This does nothing by default. The `Component` derive implements it by calling
+    /// [`ElementResolver::invalidate_all`] on each resolver field.
+    fn invalidate_all(&self) {}
+
+    /// Invalidate every cached element in this component, and then resolve them all again.
+    ///
+    /// This does nothing by default. The `Component` derive implements it by calling
+    /// [`ElementResolver::refresh_all`] on each resolver field, and returns a
+    /// `ComponentRefreshFailed` error naming the first field that could not be resolved.
+    fn refresh_all(&self) -> impl Future<Output = WebDriverResult<()>> + Send {
+        self.invalidate_all();
+        std::future::ready(Ok(()))
+    }
+}
+
+/// Lifecycle hooks for a `Component`.
+///
+/// Add the `#[component(await_ready)]` attribute to the `Component` derive for these to be
+/// called by `ElementResolver`.
+///
+/// # Example:
+/// ```ignore
+/// #[derive(Debug, Clone, Component)]
+/// #[component(await_ready)]
+/// pub struct ResultsComponent {
+///     base: WebElement,
+/// }
+///
+/// impl ComponentLifecycle for ResultsComponent {
+///     async fn on_resolved(&self) -> WebDriverResult<()> {
+///         // Wait for the loading spinner to disappear.
+///         self.base.query(By::ClassName("spinner")).not_exists().await?;
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait ComponentLifecycle: Component {
+    /// Called after the component has been resolved, before it is returned to the caller.
+    ///
+    /// If this returns an error, the resolver returns a `ComponentNotReady` error naming
+    /// the component type.
+    fn on_resolved(&self) -> impl Future<Output = WebDriverResult<()>> + Send;
+}
+
+/// A `Component` that can be resolved directly from the driver, using a root selector.
+///
+/// The `Component` derive implements this when the struct has a `#[component(root = "..")]`
+/// or `#[by(..)]` attribute.
+///
+/// # Example:
+/// ```ignore
+/// #[derive(Debug, Clone, Component)]
+/// #[component(root = "css:#app")]
+/// pub struct AppPage {
+///     base: WebElement,
+///     #[by(id = "login")]
+///     login_button: ElementResolver<WebElement>,
+/// }
+///
+/// let page = AppPage::from_driver(&driver).await?;
+/// ```
+pub trait RootComponent: Component + Send + Sync {
+    /// The selector for the base element of this component, relative to the document.
+    fn root_selector() -> By;
+
+    /// The options used when querying the base element.
+    ///
+    /// By default the type name of the component is used as the description, so that it
+    /// appears in `NoSuchElement` errors.
+    fn root_options() -> ElementQueryOptions {
+        ElementQueryOptions::default().description(std::any::type_name::<Self>())
+    }
+
+    /// Query the base element, waiting for it to appear, and construct the component.
+    ///
+    /// If the component implements `ComponentLifecycle` and opted in with
+    /// `#[component(await_ready)]`, this also waits until it is ready.
+    fn from_driver(
+        driver: &Arc<SessionHandle>,
+    ) -> impl Future<Output = WebDriverResult<Self>> + Send {
+        let query = driver.query(Self::root_selector()).options(Self::root_options());
+        async move {
+            let elem = query.single().await?;
+            ready_component(elem).await
+        }
+    }
+
+    /// Return true if the base element of this component currently exists, without waiting.
+    fn exists_in(
+        driver: &Arc<SessionHandle>,
+    ) -> impl Future<Output = WebDriverResult<bool>> + Send {
+        let query = driver.query(Self::root_selector()).options(Self::root_options()).nowait();
+        async move { query.exists().await }
+    }
 }
@@ -1,11 +1,12 @@
 use std::fmt::{Debug, Formatter};
+use std::future::Future;
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
 use tokio::sync::OnceCell;
 
 use crate::components::Component;
-use crate::error::WebDriverResult;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverErrorInner, WebDriverResult};
 use crate::extensions::query::ElementQueryOptions;
 use crate::prelude::ElementQueryable;
 use crate::{By, DynElementQueryFn, ElementQueryFn, WebElement};
@@ -34,11 +35,18 @@ macro_rules! resolve_present {
 /// Element resolver that can resolve a particular element or list of elements on demand.
 ///
 /// Once resolved, the result will be cached for later retrieval until manually invalidated.
+///
+/// If the cached element becomes stale, for example because the page re-rendered it,
+/// [`ElementResolver::resolve_present`] will resolve it again, and
+/// [`ElementResolver::with_resolved`] will invalidate the cache and resolve it again.
+/// The latter can be turned off per resolver with [`ElementResolver::set_auto_refresh`], or with
+/// the `no_auto_refresh` attribute when using the `Component` derive.
 #[derive(Clone)]
 pub struct ElementResolver<T> {
     base_element: WebElement,
     query_fn: Arc<DynElementQueryFn<T>>,
     element: Arc<ArcSwap<OnceCell<T>>>,
+    auto_refresh: bool,
 }
 
 impl<T: Debug> Debug for ElementResolver<T> {
@@ -47,6 +55,7 @@ impl<T: Debug> Debug for ElementResolver<T> {
         f.debug_struct("ElementResolver")
             .field("base_element", &self.base_element)
             .field("element", &guard.get())
+            .field("auto_refresh", &self.auto_refresh)
             .finish()
     }
 }
@@ -61,14 +70,51 @@ impl<T: Clone + 'static> ElementResolver<T> {
             base_element,
             query_fn: DynElementQueryFn::arc(query_fn),
             element: Arc::new(ArcSwap::from_pointee(OnceCell::new())),
+            auto_refresh: true,
         }
     }
 
+    /// Set whether stale elements are automatically re-resolved. The default is `true`.
+    ///
+    /// See [`ElementResolver::with_resolved`].
+    pub fn set_auto_refresh(mut self, auto_refresh: bool) -> Self {
+        self.auto_refresh = auto_refresh;
+        self
+    }
+
+    /// Run the query inside the shadow root of the base element, instead of the base element.
+    ///
+    /// The `description` is added to the `NoSuchShadowRoot` error returned when the base
+    /// element has no shadow root, or its shadow root is closed. The `Component` derive
+    /// uses this for the `shadow` attribute, with the component and field name.
+    pub fn in_shadow_root(mut self, description: &str) -> Self {
+        let query_fn = self.query_fn.clone();
+        let description: Arc<str> = Arc::from(description);
+        self.query_fn = DynElementQueryFn::arc(move |elem: WebElement| {
+            let query_fn = query_fn.clone();
+            let description = description.clone();
+            async move {
+                let root = elem.get_shadow_root().await.map_err(|e| match e.into_inner() {
+                    WebDriverErrorInner::NoSuchShadowRoot(mut info) => {
+                        info.error = format!("{}, while resolving '{description}'", info.error);
+                        WebDriverError::NoSuchShadowRoot(info)
+                    }
+                    e => e.into(),
+                })?;
+                query_fn.call(root).await
+            }
+        });
+        self
+    }
+
     fn peek(&self) -> Option<T> {
         self.element.load().get().cloned()
     }
 
     /// Return the cached element(s) if any, otherwise run the query and return the result.
+    ///
+    /// The cached element(s) are not checked to still be in the page. See
+    /// [`ElementResolver::resolve_present`] and [`ElementResolver::with_resolved`].
     pub async fn resolve(&self) -> WebDriverResult<T> {
         self.element
             .load()
@@ -77,13 +123,6 @@ impl<T: Clone + 'static> ElementResolver<T> {
             .cloned()
     }
 
-    /// Invalidate any cached element(s).
-    pub fn invalidate(&self) {
-        if self.element.load().initialized() {
-            self.element.store(Arc::new(OnceCell::new()));
-        }
-    }
-
     /// Run the query, ignoring any cached element(s).
     pub async fn resolve_force(&self) -> WebDriverResult<T>
     where
@@ -92,6 +131,44 @@ impl<T: Clone + 'static> ElementResolver<T> {
         self.invalidate();
         self.resolve().await
     }
+
+    /// Resolve the element(s) and pass them to the specified function, returning its result.
+    ///
+    /// If the function fails with a stale element error, the cached element(s) are
+    /// invalidated and resolved again, and the function is called once more with the new
+    /// element(s). If resolving them again fails, the original stale element error is
+    /// returned. This only happens once per call, and not at all if auto refresh has been
+    /// turned off for this resolver.
+    ///
+    /// # Example:
+    /// ```ignore
+    /// let text = self.label.with_resolved(|elem| async move { elem.text().await }).await?;
+    /// ```
+    pub async fn with_resolved<F, Fut, R>(&self, f: F) -> WebDriverResult<R>
+    where
+        F: Fn(T) -> Fut,
+        Fut: Future<Output = WebDriverResult<R>>,
+    {
+        match f(self.resolve().await?).await {
+            Err(e)
+                if self.auto_refresh
+                    && matches!(*e, WebDriverErrorInner::StaleElementReference(..)) =>
+            {
+                match self.resolve_force().await {
+                    Ok(value) => f(value).await,
+                    Err(_) => Err(e),
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Invalidate any cached element(s).
+    pub fn invalidate(&self) {
+        if self.element.load().initialized() {
+            self.element.store(Arc::new(OnceCell::new()));
+        }
+    }
 }
 
 mod sealed {
@@ -105,6 +182,14 @@ mod sealed {
 
     pub trait Resolve: Sized {
         fn is_present(&self) -> impl Future<Output = WebDriverResult<bool>> + Send;
+
+        /// Invalidate everything cached inside a resolved component.
+        fn invalidate_nested(&self) {}
+
+        /// Resolve everything inside a resolved component.
+        fn refresh_nested(&self) -> impl Future<Output = WebDriverResult<()>> + Send {
+            std::future::ready(Ok(()))
+        }
     }
 
     impl Resolve for WebElement {
@@ -117,6 +202,14 @@ mod sealed {
         async fn is_present(&self) -> WebDriverResult<bool> {
             self.base_element().is_present().await
         }
+
+        fn invalidate_nested(&self) {
+            self.invalidate_all();
+        }
+
+        fn refresh_nested(&self) -> impl Future<Output = WebDriverResult<()>> + Send {
+            self.refresh_all()
+        }
     }
 
     impl<T: Resolve + Sync> Resolve for Vec<T> {
@@ -127,6 +220,17 @@ mod sealed {
                 .buffer_unordered(self.len().min(16))
                 .try_all(std::future::ready)
         }
+
+        fn invalidate_nested(&self) {
+            self.iter().for_each(Resolve::invalidate_nested);
+        }
+
+        async fn refresh_nested(&self) -> WebDriverResult<()> {
+            for item in self {
+                item.refresh_nested().await?;
+            }
+            Ok(())
+        }
     }
 }
 
@@ -146,12 +250,40 @@ impl<T: Resolve + Clone + 'static> ElementResolver<T> {
     /// Validate the element or component and repeat the query if it is not present, returning the result.
     ///
     /// If the component is already present, the cached component will be returned without
-    /// performing an additional query.
+    /// performing an additional query. If the cached component is stale and the query
+    /// fails, a stale element error is returned that includes the query error.
     pub async fn resolve_present(&self) -> WebDriverResult<T> {
-        match self.validate().await? {
-            Some(component) => Ok(component),
-            None => self.resolve_force().await,
+        let Some(component) = self.peek() else {
+            return self.resolve().await;
+        };
+        if component.is_present().await? {
+            return Ok(component);
         }
+        self.resolve_force().await.map_err(|e| {
+            WebDriverError::StaleElementReference(WebDriverErrorInfo::new(format!(
+                "the cached element is stale, and resolving it again failed: {e}"
+            )))
+        })
+    }
+
+    /// Invalidate any cached element(s), including everything cached inside any cached
+    /// component(s).
+    ///
+    /// Clones of a cached component share its resolvers, so they are invalidated too.
+    pub fn invalidate_all(&self) {
+        if let Some(value) = self.element.load().get() {
+            value.invalidate_nested();
+        }
+        self.invalidate();
+    }
+
+    /// Invalidate everything with [`ElementResolver::invalidate_all`], then resolve the
+    /// element(s) again, along with every field of the resolved component(s).
+    pub async fn refresh_all(&self) -> WebDriverResult<T> {
+        self.invalidate_all();
+        let value = self.resolve().await?;
+        value.refresh_nested().await?;
+        Ok(value)
     }
 }
 
@@ -251,14 +383,34 @@ impl ElementResolver<Vec<WebElement>> {
     }
 }
 
-impl<T: Component + Clone + 'static> ElementResolver<T> {
+/// Construct a component from its base element, and wait until it is ready.
+pub(crate) async fn ready_component<T: Component + Sync>(elem: WebElement) -> WebDriverResult<T> {
+    let component = T::from(elem);
+    component.await_ready().await.map_err(|e| {
+        WebDriverError::ComponentNotReady(std::any::type_name::<T>().to_string(), e)
+    })?;
+    Ok(component)
+}
+
+/// Construct components from their base elements, and wait until they are all ready.
+async fn ready_components<T: Component + Send + Sync>(
+    elems: Vec<WebElement>,
+) -> WebDriverResult<Vec<T>> {
+    let mut components = Vec::with_capacity(elems.len());
+    for elem in elems {
+        components.push(ready_component(elem).await?);
+    }
+    Ok(components)
+}
+
+impl<T: Component + Clone + Send + Sync + 'static> ElementResolver<T> {
     /// Create a new element resolver that must return a single component.
     pub fn new_single(base_element: WebElement, by: By) -> Self {
         let resolver = move |elem: WebElement| {
             let by = by.clone();
             async move {
                 let elem = elem.query(by).single().await?;
-                Ok(elem.into())
+                ready_component(elem).await
             }
         };
         Self::new_custom(base_element, resolver)
@@ -271,7 +423,7 @@ impl<T: Component + Clone + 'static> ElementResolver<T> {
             let options = options.clone();
             async move {
                 let elem = elem.query(by).options(options).single().await?;
-                Ok(elem.into())
+                ready_component(elem).await
             }
         };
         Self::new_custom(base_element, resolver)
@@ -283,7 +435,7 @@ impl<T: Component + Clone + 'static> ElementResolver<T> {
             let by = by.clone();
             async move {
                 let elem = elem.query(by).first().await?;
-                Ok(elem.into())
+                ready_component(elem).await
             }
         };
         Self::new_custom(base_element, resolver)
@@ -296,14 +448,14 @@ impl<T: Component + Clone + 'static> ElementResolver<T> {
             let options = options.clone();
             async move {
                 let elem = elem.query(by).options(options).first().await?;
-                Ok(elem.into())
+                ready_component(elem).await
             }
         };
         Self::new_custom(base_element, resolver)
     }
 }
 
-impl<T: Component + Clone + 'static> ElementResolver<Vec<T>> {
+impl<T: Component + Clone + Send + Sync + 'static> ElementResolver<Vec<T>> {
     /// Create a new element resolver that returns all components, if any.
     ///
     /// If no components were found, this will resolve to an empty Vec.
@@ -312,7 +464,7 @@ impl<T: Component + Clone + 'static> ElementResolver<Vec<T>> {
             let by = by.clone();
             async move {
                 let elems = elem.query(by).all_from_selector().await?;
-                Ok(elems.into_iter().map(T::from).collect())
+                ready_components(elems).await
             }
         };
         Self::new_custom(base_element, resolver)
@@ -329,7 +481,7 @@ impl<T: Component + Clone + 'static> ElementResolver<Vec<T>> {
             let options = options.clone();
             async move {
                 let elems = elem.query(by).options(options).all_from_selector().await?;
-                Ok(elems.into_iter().map(T::from).collect())
+                ready_components(elems).await
             }
         };
         Self::new_custom(base_element, resolver)
@@ -344,7 +496,7 @@ impl<T: Component + Clone + 'static> ElementResolver<Vec<T>> {
             let by = by.clone();
             async move {
                 let elems = elem.query(by).all_from_selector_required().await?;
-                Ok(elems.into_iter().map(T::from).collect())
+                ready_components(elems).await
             }
         };
         Self::new_custom(base_element, resolver)
@@ -364,7 +516,7 @@ impl<T: Component + Clone + 'static> ElementResolver<Vec<T>> {
             let options = options.clone();
             async move {
                 let elems = elem.query(by).options(options).all_from_selector_required().await?;
-                Ok(elems.into_iter().map(T::from).collect())
+                ready_components(elems).await
             }
         };
         Self::new_custom(base_element, resolver)
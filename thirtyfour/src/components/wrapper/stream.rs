@@ -0,0 +1,191 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use futures_util::stream::{self, Stream};
+use serde_json::json;
+
+use crate::common::command::Selector;
+use crate::error::WebDriverResult;
+use crate::js::QUERY_PAGE;
+use crate::{By, DynElementQueryFn, ElementId, ElementQueryFn, WebElement};
+
+/// Lazily resolves a (possibly very long) list of elements or components as an async stream.
+///
+/// Unlike `ElementResolver<Vec<T>>`, which resolves every matching element up front,
+/// the elements are queried in pages of `page_size` as the stream is consumed, so callers
+/// can stop early without paying for the rest of the list. Elements are de-duplicated by
+/// element id, so a page that overlaps a previous one does not yield the same element twice.
+///
+/// For virtualized lists that only render the rows near the viewport, set a scroll function
+/// with [`ComponentStream::set_scroll_fn`]. When the rendered rows run out, the scroll function
+/// is called with the base element and the rows are queried again. The stream ends once a
+/// scroll no longer produces any new elements.
+///
+/// # Example:
+/// ```ignore
+/// #[derive(Debug, Clone, Component)]
+/// pub struct TableComponent {
+///     base: WebElement,
+///     #[by(css = "tbody tr", page_size = 50)]
+///     rows: ComponentStream<RowComponent>,
+/// }
+///
+/// let mut rows = std::pin::pin!(table.rows.stream());
+/// while let Some(row) = rows.next().await {
+///     if row?.is_selected().await? {
+///         break;
+///     }
+/// }
+/// ```
+pub struct ComponentStream<T> {
+    base_element: WebElement,
+    selector: Selector,
+    page_size: usize,
+    scroll_fn: Option<Arc<DynElementQueryFn<()>>>,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for ComponentStream<T> {
+    fn clone(&self) -> Self {
+        Self {
+            base_element: self.base_element.clone(),
+            selector: self.selector.clone(),
+            page_size: self.page_size,
+            scroll_fn: self.scroll_fn.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Debug for ComponentStream<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComponentStream")
+            .field("base_element", &self.base_element)
+            .field("selector", &self.selector)
+            .field("page_size", &self.page_size)
+            .field("scroll_fn", &self.scroll_fn.is_some())
+            .finish()
+    }
+}
+
+impl<T: From<WebElement> + Send + 'static> ComponentStream<T> {
+    /// The default number of elements requested per page.
+    pub const DEFAULT_PAGE_SIZE: usize = 25;
+
+    /// Create a new stream of the elements matching `by`, relative to the base element.
+    pub fn new(base_element: WebElement, by: By) -> Self {
+        Self {
+            base_element,
+            selector: by.into(),
+            page_size: Self::DEFAULT_PAGE_SIZE,
+            scroll_fn: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Set the number of elements requested per page. A page size of 0 is treated as 1.
+    pub fn set_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Set the function used to reveal more elements once the rendered ones are exhausted.
+    ///
+    /// The function is called with the base element, and typically scrolls it.
+    pub fn set_scroll_fn(mut self, scroll_fn: impl ElementQueryFn<()> + 'static) -> Self {
+        self.scroll_fn = Some(DynElementQueryFn::arc(scroll_fn));
+        self
+    }
+
+    /// Return a new stream of the matching elements or components, starting from the first.
+    ///
+    /// No queries are performed until the stream is polled. After an error, the stream ends.
+    pub fn stream(&self) -> impl Stream<Item = WebDriverResult<T>> + Send + 'static {
+        let pager = Pager {
+            source: self.clone(),
+            offset: 0,
+            seen: HashSet::new(),
+            buffer: VecDeque::new(),
+            at_end: false,
+            found_since_scroll: false,
+            done: false,
+        };
+
+        stream::unfold(pager, |mut pager| async move {
+            match pager.next_element().await {
+                Ok(Some(elem)) => Some((Ok(T::from(elem)), pager)),
+                Ok(None) => None,
+                Err(e) => {
+                    pager.done = true;
+                    pager.buffer.clear();
+                    Some((Err(e), pager))
+                }
+            }
+        })
+    }
+
+    /// Query a single page of elements.
+    async fn query_page(&self, offset: usize) -> WebDriverResult<Vec<WebElement>> {
+        let args = vec![
+            self.base_element.to_json()?,
+            json!(self.selector.name),
+            json!(self.selector.query),
+            json!(offset),
+            json!(self.page_size),
+        ];
+        self.base_element.handle.execute(QUERY_PAGE, args).await?.elements()
+    }
+}
+
+/// The paging state for a single call to `ComponentStream::stream()`.
+struct Pager<T> {
+    source: ComponentStream<T>,
+    offset: usize,
+    seen: HashSet<ElementId>,
+    buffer: VecDeque<WebElement>,
+    at_end: bool,
+    found_since_scroll: bool,
+    done: bool,
+}
+
+impl<T: From<WebElement> + Send + 'static> Pager<T> {
+    async fn next_element(&mut self) -> WebDriverResult<Option<WebElement>> {
+        loop {
+            if let Some(elem) = self.buffer.pop_front() {
+                return Ok(Some(elem));
+            }
+            if self.done {
+                return Ok(None);
+            }
+
+            if self.at_end {
+                // Only scroll once every element found so far has been handed out, since
+                // scrolling a virtualized list may remove them from the DOM.
+                match &self.source.scroll_fn {
+                    Some(scroll_fn) if self.found_since_scroll => {
+                        scroll_fn.call(self.source.base_element.clone()).await?;
+                        // Elements may have been added or recycled anywhere, so start again.
+                        // Elements we have already seen are skipped.
+                        self.offset = 0;
+                        self.at_end = false;
+                        self.found_since_scroll = false;
+                    }
+                    _ => self.done = true,
+                }
+                continue;
+            }
+
+            let page = self.source.query_page(self.offset).await?;
+            self.offset += page.len();
+            self.at_end = page.len() < self.source.page_size;
+            for elem in page {
+                if self.seen.insert(elem.element_id()) {
+                    self.found_since_scroll = true;
+                    self.buffer.push_back(elem);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use crate::error::{ErrorKind, WebDriverErrorInner};
+use crate::DriverInfo;
+
+/// The `browserName` values reported by Chromium-based browsers.
+const CHROMIUM: &[&str] =
+    &["chrome", "chromium", "chrome-headless-shell", "msedge", "microsoftedge"];
+/// The `browserName` values reported by Firefox.
+const FIREFOX: &[&str] = &["firefox"];
+/// The `browserName` values reported by Safari.
+const SAFARI: &[&str] = &["safari"];
+/// Any browser.
+const ANY: &[&str] = &[];
+
+/// A driver-specific error message and the kind of error it describes.
+struct DriverErrorPattern {
+    /// The browsers this applies to. Empty for every browser.
+    browsers: &'static [&'static str],
+    /// If set, this only applies to driver versions before this one.
+    before_version: Option<&'static [u32]>,
+    /// The error code this applies to. If this is `None`, it applies to errors without a
+    /// specific code, such as `unknown error`, unrecognised codes and responses that are
+    /// not WebDriver errors at all.
+    code: Option<&'static str>,
+    /// The lowercase text to look for in the message.
+    message: &'static str,
+    /// The kind of error this describes.
+    kind: ErrorKind,
+}
+
+const fn pattern(
+    browsers: &'static [&'static str],
+    before_version: Option<&'static [u32]>,
+    code: Option<&'static str>,
+    message: &'static str,
+    kind: ErrorKind,
+) -> DriverErrorPattern {
+    DriverErrorPattern {
+        browsers,
+        before_version,
+        code,
+        message,
+        kind,
+    }
+}
+
+/// Known driver-specific error messages. The first matching pattern wins.
+///
+/// When adding a pattern, add the payload it was recorded from to the tests below.
+const PATTERNS: &[DriverErrorPattern] = &[
+    // chromedriver before 75 did not use the W3C error codes for these.
+    pattern(CHROMIUM, Some(&[75]), None, "is not clickable at point", ErrorKind::ClickIntercepted),
+    pattern(
+        CHROMIUM,
+        Some(&[75]),
+        None,
+        "other element would receive the click",
+        ErrorKind::ClickIntercepted,
+    ),
+    pattern(CHROMIUM, Some(&[75]), None, "element not visible", ErrorKind::NotInteractable),
+    pattern(CHROMIUM, None, None, "is not attached to the page document", ErrorKind::Stale),
+    pattern(
+        CHROMIUM,
+        None,
+        None,
+        "session deleted because of page crash",
+        ErrorKind::InvalidSession,
+    ),
+    pattern(CHROMIUM, None, None, "chrome not reachable", ErrorKind::InvalidSession),
+    pattern(CHROMIUM, None, None, "not connected to devtools", ErrorKind::InvalidSession),
+    pattern(CHROMIUM, None, None, "timed out receiving message from renderer", ErrorKind::Timeout),
+    // geckodriver.
+    pattern(FIREFOX, None, None, "obscures it", ErrorKind::ClickIntercepted),
+    pattern(FIREFOX, None, None, "no longer connected to the dom", ErrorKind::Stale),
+    pattern(
+        FIREFOX,
+        None,
+        None,
+        "tried to run command without establishing a connection",
+        ErrorKind::InvalidSession,
+    ),
+    pattern(
+        FIREFOX,
+        None,
+        None,
+        "failed to decode response from marionette",
+        ErrorKind::InvalidSession,
+    ),
+    // safaridriver reports obscured elements as not interactable.
+    pattern(
+        SAFARI,
+        None,
+        Some("element not interactable"),
+        "is obscured",
+        ErrorKind::ClickIntercepted,
+    ),
+    // Any driver.
+    pattern(ANY, None, None, "stale element", ErrorKind::Stale),
+    pattern(ANY, None, None, "unable to locate element", ErrorKind::NoSuchElement),
+    pattern(ANY, None, None, "invalid session id", ErrorKind::InvalidSession),
+    pattern(ANY, None, None, "unexpected alert open", ErrorKind::UnexpectedAlert),
+];
+
+impl DriverErrorPattern {
+    fn applies_to(&self, driver: Option<&DriverInfo>) -> bool {
+        let Some(driver) = driver else {
+            return true;
+        };
+        if !self.browsers.is_empty() && !self.browsers.contains(&driver.browser_name.as_str()) {
+            return false;
+        }
+        match (self.before_version, driver.driver_version_parts()) {
+            (Some(before), Some(version)) => version.as_slice() < before,
+            // Assume an unknown version may be affected.
+            _ => true,
+        }
+    }
+}
+
+/// Get the kind of error described by a driver-specific error message, if it is known.
+///
+/// If the driver is `None`, the patterns for every driver are tried.
+pub(crate) fn classify(
+    driver: Option<&DriverInfo>,
+    error: &WebDriverErrorInner,
+) -> Option<ErrorKind> {
+    let (code, message) = match error {
+        WebDriverErrorInner::UnknownError(info) | WebDriverErrorInner::NotInSpec(info) => {
+            (None, info.value.message.as_str())
+        }
+        WebDriverErrorInner::UnknownResponse(_, body) => (None, body.as_str()),
+        _ => match error.info() {
+            Some(info) => (error.raw_code(), info.value.message.as_str()),
+            None => return None,
+        },
+    };
+    let message = message.to_lowercase();
+    PATTERNS
+        .iter()
+        .filter(|x| x.code == code && x.applies_to(driver))
+        .find(|x| message.contains(x.message))
+        .map(|x| x.kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WebDriverError;
+
+    fn driver(browser_name: &str, driver_version: &str) -> DriverInfo {
+        DriverInfo {
+            browser_name: browser_name.to_string(),
+            browser_version: String::new(),
+            driver_version: Some(driver_version.to_string()),
+            platform_name: String::new(),
+            devtools_address: None,
+        }
+    }
+
+    fn kind(driver: &DriverInfo, status: u16, body: &str) -> ErrorKind {
+        WebDriverError::parse(status, body.to_string()).normalize(driver).kind()
+    }
+
+    /// An obscured click recorded from chromedriver 74, before it used the W3C error code.
+    const CHROMEDRIVER_74_CLICK: &str = r#"{"value":{"error":"unknown error","message":"unknown error: Element <button id=\"submit\">...</button> is not clickable at point (90, 20). Other element would receive the click: <div class=\"overlay\"></div>\n  (Session info: chrome=74.0.3729.169)\n  (Driver info: chromedriver=74.0.3729.6 (255758eccf3d244491b8a1317aa76e1ce10d57e9-refs/branch-heads/3729@{#29}),platform=Linux 5.4.0 x86_64)"}}"#;
+
+    /// A crashed tab recorded from chromedriver.
+    const CHROMEDRIVER_CRASH: &str = r#"{"value":{"error":"unknown error","message":"unknown error: session deleted because of page crash\nfrom unknown error: cannot determine loading status\nfrom tab crashed\n  (Session info: chrome=120.0.6099.109)","stacktrace":""}}"#;
+
+    /// An obscured click recorded from geckodriver.
+    const GECKODRIVER_CLICK: &str = r#"{"value":{"error":"element click intercepted","message":"Element <button id=\"submit\"> is not clickable at point (90,20) because another element <div class=\"overlay\"> obscures it","stacktrace":""}}"#;
+
+    /// A lost connection to Firefox recorded from geckodriver.
+    const GECKODRIVER_LOST: &str = r#"{"value":{"error":"unknown error","message":"Failed to decode response from marionette","stacktrace":""}}"#;
+
+    /// An obscured click recorded from safaridriver.
+    const SAFARIDRIVER_CLICK: &str = r#"{"value":{"error":"element not interactable","message":"The element is obscured by another element.","stacktrace":""}}"#;
+
+    #[test]
+    fn test_chromedriver_errors() {
+        let legacy = driver("chrome", "74.0.3729.6");
+        let current = driver("chrome", "120.0.6099.109");
+        assert_eq!(kind(&legacy, 500, CHROMEDRIVER_74_CLICK), ErrorKind::ClickIntercepted);
+        // Newer versions use the W3C error code, so the message is not trusted.
+        assert_eq!(kind(&current, 500, CHROMEDRIVER_74_CLICK), ErrorKind::Other);
+        assert_eq!(kind(&current, 500, CHROMEDRIVER_CRASH), ErrorKind::InvalidSession);
+        // Edge uses the same driver.
+        assert_eq!(
+            kind(&driver("msedge", "120.0.2210.61"), 500, CHROMEDRIVER_CRASH),
+            ErrorKind::InvalidSession
+        );
+        // Chromium messages are not matched for other browsers.
+        assert_eq!(kind(&driver("firefox", "0.34.0"), 500, CHROMEDRIVER_CRASH), ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_geckodriver_errors() {
+        let gecko = driver("firefox", "0.34.0");
+        assert_eq!(kind(&gecko, 400, GECKODRIVER_CLICK), ErrorKind::ClickIntercepted);
+        assert_eq!(kind(&gecko, 500, GECKODRIVER_LOST), ErrorKind::InvalidSession);
+    }
+
+    #[test]
+    fn test_safaridriver_errors() {
+        let safari = driver("safari", "17.1");
+        assert_eq!(kind(&safari, 400, SAFARIDRIVER_CLICK), ErrorKind::ClickIntercepted);
+        assert_eq!(
+            kind(&driver("chrome", "120.0"), 400, SAFARIDRIVER_CLICK),
+            ErrorKind::NotInteractable
+        );
+    }
+
+    #[test]
+    fn test_unknown_message() {
+        let body = r#"{"value":{"error":"some vendor error","message":"something went wrong","stacktrace":""}}"#;
+        let err =
+            WebDriverError::parse(500, body.to_string()).normalize(&driver("chrome", "120.0"));
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::NotInSpec(_)));
+        assert_eq!(err.raw_code(), Some("some vendor error"));
+    }
+
+    #[test]
+    fn test_unknown_driver() {
+        // Without a session, every pattern is tried.
+        let err = WebDriverError::parse(500, CHROMEDRIVER_74_CLICK.to_string());
+        assert_eq!(err.kind(), ErrorKind::ClickIntercepted);
+        let err = err.normalize(&DriverInfo::default());
+        assert_eq!(err.kind(), ErrorKind::ClickIntercepted);
+    }
+}
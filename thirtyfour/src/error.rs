@@ -1,7 +1,13 @@
 use base64::DecodeError;
-use serde::Deserialize;
-use std::fmt::{Display, Formatter, Write};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter, Write};
 use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::common::requestdata::RequestData;
+use crate::driver_errors;
+use crate::{DriverInfo, LogEntry, SessionId};
 
 /// Type def for Result<T, WebDriverError>.
 pub type WebDriverResult<T> = Result<T, WebDriverError>;
@@ -111,10 +117,354 @@ impl Display for WebDriverErrorInfo {
     }
 }
 
+/// Struct describing image data that could not be decoded.
+#[derive(Debug, Clone)]
+pub struct ImageDecodeErrorInfo {
+    /// The error message from the decoder.
+    pub message: String,
+    /// The length of the image data, in bytes.
+    pub len: usize,
+    /// The first bytes of the image data.
+    pub head: Vec<u8>,
+}
+
+impl ImageDecodeErrorInfo {
+    /// Create a new ImageDecodeErrorInfo for the specified image data.
+    pub fn new(message: String, data: &[u8]) -> Self {
+        Self {
+            message,
+            len: data.len(),
+            head: data[..data.len().min(16)].to_vec(),
+        }
+    }
+}
+
+impl Display for ImageDecodeErrorInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({} bytes, starting with {:02x?})", self.message, self.len, self.head)
+    }
+}
+
+/// Context describing what was being done with an element when an error occurred.
+///
+/// Every field is optional. See [`WebDriverError::with_element_context`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ElementErrorContext {
+    /// The selector that was being looked for, for example "css `#submit`".
+    pub selector: Option<String>,
+    /// The element that the search was scoped to, for example "<form id=login>".
+    pub parent: Option<String>,
+    /// The name of the operation, for example "click".
+    pub operation: Option<String>,
+}
+
+impl ElementErrorContext {
+    /// Create a new ElementErrorContext containing only the operation name.
+    pub fn operation(operation: impl Into<String>) -> Self {
+        Self {
+            operation: Some(operation.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Fill in any fields that are not set yet, from `other`.
+    fn merge(&mut self, other: ElementErrorContext) {
+        self.selector = self.selector.take().or(other.selector);
+        self.parent = self.parent.take().or(other.parent);
+        self.operation = self.operation.take().or(other.operation);
+    }
+}
+
+impl Display for ElementErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(selector) = &self.selector {
+            parts.push(selector.clone());
+        }
+        if let Some(parent) = &self.parent {
+            parts.push(format!("within {parent}"));
+        }
+        if let Some(operation) = &self.operation {
+            parts.push(format!("(operation: {operation})"));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Artifacts captured when an error occurred, to help diagnose the failure.
+///
+/// These are only captured if `WebDriverConfigBuilder::capture_on_error()` was used. Any
+/// artifact that could not be captured in time is `None`. The `context` is recorded
+/// either way.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorAttachments {
+    /// The file containing a screenshot of the current window.
+    pub screenshot: Option<PathBuf>,
+    /// The file containing the page source.
+    pub page_source: Option<PathBuf>,
+    /// The URL of the current page.
+    pub url: Option<String>,
+    /// The entries in the browser console log.
+    pub console: Option<Vec<LogEntry>>,
+    /// The context the failing command was run in, if it was not the default, such as
+    /// `Firefox chrome` after `FirefoxTools::set_context()`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+impl ErrorAttachments {
+    /// True if any artifacts were captured, rather than only the context being recorded.
+    pub(crate) fn is_captured(&self) -> bool {
+        self.screenshot.is_some()
+            || self.page_source.is_some()
+            || self.url.is_some()
+            || self.console.is_some()
+    }
+}
+
+/// Builder for the error returned when a wait times out.
+///
+/// Every wait in thirtyfour builds its timeout error with this, so that the error describes
+/// the condition that was awaited, how long the wait took, how many attempts were made, and
+/// the state that was last observed. For example:
+///
+/// ```text
+/// operation timed out: waited 5.0s for an alert to open (11 attempts); last saw no alert
+/// ```
+///
+/// # Example:
+/// ```
+/// # use thirtyfour::error::TimeoutErrorBuilder;
+/// let mut wait = TimeoutErrorBuilder::new("the spinner to disappear");
+/// wait.attempt();
+/// wait.observed("2 spinners");
+/// let err = wait.build();
+/// assert!(err.to_string().contains("for the spinner to disappear (1 attempt); last saw 2 spinners"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeoutErrorBuilder {
+    condition: String,
+    started: Instant,
+    attempts: u32,
+    last_observed: Option<String>,
+}
+
+impl TimeoutErrorBuilder {
+    /// Start timing a wait for the specified condition.
+    ///
+    /// The condition should read naturally after "waited 5.0s for", for example
+    /// "element to be displayed".
+    pub fn new(condition: impl Into<String>) -> Self {
+        Self {
+            condition: condition.into(),
+            started: Instant::now(),
+            attempts: 0,
+            last_observed: None,
+        }
+    }
+
+    /// Record an attempt to check the condition.
+    pub fn attempt(&mut self) {
+        self.attempts += 1;
+    }
+
+    /// Record the state observed by the latest attempt.
+    ///
+    /// The state should read naturally after "last saw", for example "no alert".
+    pub fn observed(&mut self, state: impl Into<String>) {
+        self.last_observed = Some(state.into());
+    }
+
+    /// The number of attempts recorded so far.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Describe the failed wait, using the time elapsed since the builder was created.
+    pub fn message(&self) -> String {
+        self.render(self.started.elapsed())
+    }
+
+    /// Build the `Timeout` error.
+    pub fn build(&self) -> WebDriverError {
+        WebDriverError::Timeout(self.message())
+    }
+
+    fn render(&self, elapsed: Duration) -> String {
+        let plural = if self.attempts == 1 {
+            ""
+        } else {
+            "s"
+        };
+        let mut message = format!(
+            "waited {elapsed:.1?} for {} ({} attempt{plural})",
+            self.condition, self.attempts
+        );
+        if let Some(state) = &self.last_observed {
+            message.push_str("; last saw ");
+            message.push_str(state);
+        }
+        message
+    }
+}
+
+/// A normalized category of [`WebDriverError`], for use in match statements.
+///
+/// This is derived from the W3C error code returned by the WebDriver server, and from known
+/// driver-specific messages for errors that some drivers report as `unknown error`.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// No element matched the selector.
+    NoSuchElement,
+    /// The element is no longer attached to the document.
+    Stale,
+    /// An operation or wait timed out, including scripts and element queries.
+    Timeout,
+    /// The session no longer exists, for example because the browser crashed or was closed.
+    InvalidSession,
+    /// A click was received by another element covering the target element.
+    ClickIntercepted,
+    /// The element cannot be interacted with, for example because it is hidden.
+    NotInteractable,
+    /// A user prompt such as an alert was open.
+    UnexpectedAlert,
+    /// The WebDriver server could not be reached, or returned a response that was not from
+    /// the driver, such as a proxy error.
+    Connection,
+    /// No alert, frame, window, cookie or shadow root was found.
+    NotFound,
+    /// The request was invalid, for example an invalid selector or argument.
+    InvalidRequest,
+    /// A script returned an error.
+    Javascript,
+    /// Any other error.
+    Other,
+}
+
+/// The command that failed and the HTTP response for an error returned by the WebDriver
+/// server.
+#[derive(Debug)]
+struct ErrorResponse {
+    /// The method and path of the command, for example "POST /session/1234/url".
+    command: Option<String>,
+    http: HttpResponse,
+}
+
+impl ErrorResponse {
+    fn new(status: u16, body: &[u8]) -> Self {
+        Self {
+            command: None,
+            http: HttpResponse {
+                status,
+                body: body[..body.len().min(WebDriverError::MAX_RAW_BODY_LEN)].to_vec(),
+                body_len: body.len(),
+            },
+        }
+    }
+}
+
+impl Display for ErrorResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.command {
+            Some(command) => write!(f, "WebDriver command {command} failed"),
+            None => write!(f, "WebDriver command failed"),
+        }
+    }
+}
+
+impl std::error::Error for ErrorResponse {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.http)
+    }
+}
+
+/// The HTTP response for an error returned by the WebDriver server.
+struct HttpResponse {
+    status: u16,
+    body: Vec<u8>,
+    body_len: usize,
+}
+
+impl Debug for HttpResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpResponse")
+            .field("status", &self.status)
+            .field("body", &String::from_utf8_lossy(&self.body))
+            .field("body_len", &self.body_len)
+            .finish()
+    }
+}
+
+impl Display for HttpResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the server responded with HTTP {} and a {}-byte body",
+            self.status, self.body_len
+        )
+    }
+}
+
+impl std::error::Error for HttpResponse {}
+
 /// WebDriverError is the main error type for thirtyfour
-#[derive(Debug, thiserror::Error)]
-#[error(transparent)]
-pub struct WebDriverError(Box<WebDriverErrorInner>);
+pub struct WebDriverError(Box<WebDriverErrorRepr>);
+
+/// The error and everything known about it, boxed together so that results stay small.
+struct WebDriverErrorRepr {
+    inner: WebDriverErrorInner,
+    context: Option<ElementErrorContext>,
+    response: Option<ErrorResponse>,
+    attachments: Option<ErrorAttachments>,
+    kind: Option<ErrorKind>,
+    session_id: Option<SessionId>,
+    timestamp: SystemTime,
+}
+
+impl Debug for WebDriverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebDriverError")
+            .field("inner", &self.0.inner)
+            .field("context", &self.0.context)
+            .field("response", &self.0.response)
+            .field("attachments", &self.0.attachments)
+            .field("kind", &self.0.kind)
+            .field("session_id", &self.0.session_id)
+            .field("timestamp", &self.0.timestamp)
+            .finish()
+    }
+}
+
+impl Display for WebDriverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let (Some(context), Some(code)) = (&self.0.context, self.0.inner.element_error_code()) {
+            writeln!(f, "{code}: {context}")?;
+        }
+        Display::fmt(&self.0.inner, f)
+    }
+}
+
+impl std::error::Error for WebDriverError {
+    /// With the `anyhow` feature, the source of an error returned by the WebDriver server
+    /// describes the command that failed, and its source describes the HTTP response. The
+    /// source of a component error is the error it wraps.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        #[cfg(feature = "anyhow")]
+        {
+            if let Some(response) = &self.0.response {
+                return Some(response);
+            }
+            if let WebDriverErrorInner::ComponentNotReady(_, e)
+            | WebDriverErrorInner::ComponentRefreshFailed(_, e) = &self.0.inner
+            {
+                return Some(e);
+            }
+        }
+        self.0.inner.source()
+    }
+}
 
 macro_rules! make_enum_variant_func {
     ($enum_name: ident $variant_name: ident()) => {
@@ -215,6 +565,10 @@ webdriver_err! {
         Timeout(String),
         #[error("Unable to parse JSON: {0}")]
         Json(String),
+        #[error("image error: {0}")]
+        ImageError(String),
+        #[error("Unable to decode image: {0}")]
+        ImageDecodeError(ImageDecodeErrorInfo),
         #[error("Unable to decode base64: {0}")]
         DecodeError(#[from] DecodeError),
         #[error("IO Error: {0}")]
@@ -251,6 +605,8 @@ webdriver_err! {
         NoSuchCookie(WebDriverErrorInfo),
         #[error("Element not found: {0}")]
         NoSuchElement(WebDriverErrorInfo),
+        #[error("Shadow root not found: {0}")]
+        NoSuchShadowRoot(WebDriverErrorInfo),
         #[error("Frame not found: {0}")]
         NoSuchFrame(WebDriverErrorInfo),
         #[error("Window not found: {0}")]
@@ -261,6 +617,8 @@ webdriver_err! {
         SessionNotCreated(WebDriverErrorInfo),
         #[error("Element is stale: {0}")]
         StaleElementReference(WebDriverErrorInfo),
+        #[error("Shadow root is detached: {0}")]
+        DetachedShadowRoot(WebDriverErrorInfo),
         #[error("Operation timed out: {0}")]
         WebDriverTimeout(WebDriverErrorInfo),
         #[error("Unable to set cookie: {0}")]
@@ -281,26 +639,65 @@ webdriver_err! {
         FatalError(String),
         #[error("Failed to receive command: {0}")]
         CommandRecvError(String),
+        #[error("The download was cancelled: {0}")]
+        DownloadCanceled(String),
+        #[error("The JS heap grew too much: {0}")]
+        HeapGrowthExceeded(String),
         #[error("The command could not be sent to the session: {0}")]
         CommandSendError(String),
         #[error("Could not create session: {0}")]
         SessionCreateError(String),
+        #[error("Component {0} is not ready: {1}")]
+        ComponentNotReady(String, WebDriverError),
+        #[error("Could not refresh component field {0}: {1}")]
+        ComponentRefreshFailed(String, WebDriverError),
     }
 }
 
 impl WebDriverError {
+    /// The maximum number of bytes of the response body kept by errors returned by the
+    /// WebDriver server. See [`WebDriverError::raw_body`].
+    pub const MAX_RAW_BODY_LEN: usize = 64 * 1024;
+
     /// Create a new WebDriverError by parsing the response from the WebDriver server.
+    ///
+    /// The status and body are kept, see [`WebDriverError::raw_body`].
     pub fn parse(status: u16, body: String) -> Self {
-        let body_json = match serde_json::from_str(&body) {
+        Self::parse_body(status, &body).with_response(status, body.as_bytes())
+    }
+
+    /// Create a new WebDriverError by parsing the raw response body from the WebDriver
+    /// server, which may not be valid UTF-8.
+    ///
+    /// The status and body are kept, see [`WebDriverError::raw_body`].
+    pub fn from_response(status: u16, body: &[u8]) -> Self {
+        Self::parse_body(status, &String::from_utf8_lossy(body)).with_response(status, body)
+    }
+
+    fn with_response(mut self, status: u16, body: &[u8]) -> Self {
+        self.0.response = Some(ErrorResponse::new(status, body));
+        self
+    }
+
+    /// Record the command that returned this error.
+    pub(crate) fn with_command(mut self, request: &RequestData) -> Self {
+        if let Some(response) = &mut self.0.response {
+            response.command = Some(format!("{} {}", request.method, request.uri));
+        }
+        self
+    }
+
+    fn parse_body(status: u16, body: &str) -> Self {
+        let unknown_response =
+            || Self::from_inner(WebDriverErrorInner::UnknownResponse(status, body.to_string()));
+        let body_json = match serde_json::from_str(body) {
             Ok(x) => x,
-            Err(_) => {
-                return Self::from_inner(WebDriverErrorInner::UnknownResponse(status, body));
-            }
+            Err(_) => return unknown_response(),
         };
 
         let mut payload: WebDriverErrorInfo = match serde_json::from_value(body_json) {
             Ok(x) => x,
-            Err(_) => return Self::from_inner(WebDriverErrorInner::UnknownResponse(status, body)),
+            Err(_) => return unknown_response(),
         };
 
         payload.status = status;
@@ -326,11 +723,13 @@ impl WebDriverError {
             "no such alert" => WebDriverErrorInner::NoSuchAlert(payload),
             "no such cookie" => WebDriverErrorInner::NoSuchCookie(payload),
             "no such element" => WebDriverErrorInner::NoSuchElement(payload),
+            "no such shadow root" => WebDriverErrorInner::NoSuchShadowRoot(payload),
             "no such frame" => WebDriverErrorInner::NoSuchFrame(payload),
             "no such window" => WebDriverErrorInner::NoSuchWindow(payload),
             "script timeout" => WebDriverErrorInner::ScriptTimeout(payload),
             "session not created" => WebDriverErrorInner::SessionNotCreated(payload),
             "stale element reference" => WebDriverErrorInner::StaleElementReference(payload),
+            "detached shadow root" => WebDriverErrorInner::DetachedShadowRoot(payload),
             "timeout" => WebDriverErrorInner::WebDriverTimeout(payload),
             "unable to set cookie" => WebDriverErrorInner::UnableToSetCookie(payload),
             "unable to capture screen" => WebDriverErrorInner::UnableToCaptureScreen(payload),
@@ -350,12 +749,370 @@ impl WebDriverError {
 
     /// converts the underlying representation to the main representation
     pub fn from_inner(err: WebDriverErrorInner) -> Self {
-        Self(Box::new(err))
+        Self(Box::new(WebDriverErrorRepr {
+            inner: err,
+            context: None,
+            response: None,
+            attachments: None,
+            kind: None,
+            session_id: None,
+            timestamp: SystemTime::now(),
+        }))
     }
 
     /// converts this error to its underlying representation
+    ///
+    /// Any element context, HTTP response details and attachments are discarded.
     pub fn into_inner(self) -> WebDriverErrorInner {
-        *self.0
+        self.0.inner
+    }
+
+    /// Return true if this error relates to an element, such as `NoSuchElement` or
+    /// `StaleElementReference`. Only these errors can carry an [`ElementErrorContext`].
+    pub fn is_element_error(&self) -> bool {
+        self.0.inner.element_error_code().is_some()
+    }
+
+    /// Add context describing what was being done with an element.
+    ///
+    /// Other errors are returned unchanged. If the error already has context, only the
+    /// fields that are not set yet are filled in, so the context added closest to the
+    /// failing command wins.
+    pub fn with_element_context(mut self, context: ElementErrorContext) -> Self {
+        if self.is_element_error() {
+            match &mut self.0.context {
+                Some(existing) => existing.merge(context),
+                None => self.0.context = Some(context),
+            }
+        }
+        self
+    }
+
+    /// Get the context describing what was being done with an element, if any.
+    pub fn element_context(&self) -> Option<&ElementErrorContext> {
+        self.0.context.as_ref()
+    }
+
+    /// Attach the artifacts captured when this error occurred, replacing any existing ones.
+    ///
+    /// The `context` already recorded is kept if the new attachments do not have one.
+    pub fn with_attachments(mut self, mut attachments: ErrorAttachments) -> Self {
+        if attachments.context.is_none() {
+            attachments.context = self.0.attachments.take().and_then(|x| x.context);
+        }
+        self.0.attachments = Some(attachments);
+        self
+    }
+
+    /// Record the context the failing command was run in. See [`ErrorAttachments::context`].
+    pub(crate) fn with_context(mut self, context: String) -> Self {
+        self.0.attachments.get_or_insert_with(Default::default).context = Some(context);
+        self
+    }
+
+    /// Get the artifacts captured when this error occurred, if any.
+    ///
+    /// See [`ErrorAttachments`].
+    pub fn attachments(&self) -> Option<&ErrorAttachments> {
+        self.0.attachments.as_ref()
+    }
+
+    /// The session that returned this error, for errors returned by a session.
+    pub fn session_id(&self) -> Option<&SessionId> {
+        self.0.session_id.as_ref()
+    }
+
+    /// Record the session that returned this error.
+    pub(crate) fn with_session_id(mut self, session_id: &SessionId) -> Self {
+        self.0.session_id = Some(session_id.clone());
+        self
+    }
+
+    /// When this error was created.
+    pub fn timestamp(&self) -> SystemTime {
+        self.0.timestamp
+    }
+
+    /// The method and path of the command that returned this error, for example
+    /// "POST /session/1234/element/5678/click", for errors returned by the WebDriver server.
+    pub fn command(&self) -> Option<&str> {
+        self.0.response.as_ref()?.command.as_deref()
+    }
+
+    /// Convert this error to JSON, for example for reporting failures to a CI dashboard.
+    ///
+    /// The schema is stable, and every field is always present, with `null` for anything
+    /// that is not known:
+    ///
+    /// | Field         | Value                                                                |
+    /// |---------------|----------------------------------------------------------------------|
+    /// | `kind`        | The [`ErrorKind`] in snake case, for example `"click_intercepted"`   |
+    /// | `message`     | The message returned by the WebDriver server, or the error message   |
+    /// | `command`     | The command that failed. See [`WebDriverError::command`]             |
+    /// | `selector`    | The selector from the [`ElementErrorContext`]                        |
+    /// | `url`         | The URL of the current page, from the [`ErrorAttachments`]           |
+    /// | `http_status` | The HTTP status code returned by the WebDriver server                |
+    /// | `attachments` | The [`ErrorAttachments`], with paths as strings                      |
+    /// | `timestamp`   | When the error was created, in milliseconds since the Unix epoch     |
+    /// | `session_id`  | The session that returned the error                                  |
+    ///
+    /// See [`FailureReporter`](crate::FailureReporter) for writing these to a file.
+    pub fn to_json(&self) -> serde_json::Value {
+        let message = match self.0.inner.info() {
+            Some(info) => info.value.message.clone(),
+            None => self.0.inner.to_string(),
+        };
+        let timestamp = self.0.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+        serde_json::json!({
+            "kind": self.kind(),
+            "message": message,
+            "command": self.command(),
+            "selector": self.0.context.as_ref().and_then(|x| x.selector.as_deref()),
+            "url": self.0.attachments.as_ref().and_then(|x| x.url.as_deref()),
+            "http_status": self.http_status(),
+            "attachments": self.0.attachments,
+            "timestamp": timestamp.as_millis() as u64,
+            "session_id": self.0.session_id.as_ref().map(ToString::to_string),
+        })
+    }
+
+    /// The HTTP status code of the response, for errors returned by the WebDriver server.
+    pub fn http_status(&self) -> Option<u16> {
+        self.0.response.as_ref().map(|x| x.http.status)
+    }
+
+    /// The raw body of the response, for errors returned by the WebDriver server.
+    ///
+    /// This is usually JSON, but is kept as bytes since some servers and proxies return
+    /// other content. Only the first [`WebDriverError::MAX_RAW_BODY_LEN`] bytes are kept.
+    /// See [`WebDriverError::raw_body_truncated`].
+    pub fn raw_body(&self) -> Option<&[u8]> {
+        self.0.response.as_ref().map(|x| x.http.body.as_slice())
+    }
+
+    /// Return true if the raw body of the response was too long to be kept in full.
+    pub fn raw_body_truncated(&self) -> bool {
+        self.0.response.as_ref().is_some_and(|x| x.http.body_len > x.http.body.len())
+    }
+
+    /// The `stacktrace` field of the error returned by the WebDriver server, if any.
+    pub fn driver_stacktrace(&self) -> Option<&str> {
+        self.0.inner.info()?.value.stacktrace.as_deref()
+    }
+
+    /// The normalized kind of this error.
+    ///
+    /// # Example:
+    /// ```ignore
+    /// match elem.click().await {
+    ///     Err(e) if e.kind() == ErrorKind::ClickIntercepted => close_overlay().await?,
+    ///     result => result?,
+    /// }
+    /// ```
+    ///
+    /// Errors returned by a session are normalized using the browser and driver version
+    /// of the session, so driver-specific messages for errors without a specific error code
+    /// are only matched for the driver that uses them. Other errors are matched against
+    /// the messages for every driver. See [`WebDriverErrorInner::raw_code`] for the error code
+    /// returned by the driver.
+    pub fn kind(&self) -> ErrorKind {
+        self.0.kind.unwrap_or_else(|| self.0.inner.kind())
+    }
+
+    /// Set the kind of this error using the known messages for the specified driver.
+    pub(crate) fn normalize(mut self, driver: &DriverInfo) -> Self {
+        if !driver.browser_name.is_empty() && self.0.kind.is_none() {
+            self.0.kind = Some(self.0.inner.connection_kind().unwrap_or_else(|| {
+                driver_errors::classify(Some(driver), &self.0.inner)
+                    .unwrap_or_else(|| self.0.inner.base_kind())
+            }));
+        }
+        self
+    }
+
+    /// Return true if no element matched the selector.
+    pub fn is_no_such_element(&self) -> bool {
+        self.kind() == ErrorKind::NoSuchElement
+    }
+
+    /// Return true if the element is no longer attached to the document.
+    pub fn is_stale(&self) -> bool {
+        self.kind() == ErrorKind::Stale
+    }
+
+    /// Return true if an operation or wait timed out.
+    pub fn is_timeout(&self) -> bool {
+        self.kind() == ErrorKind::Timeout
+    }
+
+    /// Return true if the session no longer exists.
+    pub fn is_invalid_session(&self) -> bool {
+        self.kind() == ErrorKind::InvalidSession
+    }
+
+    /// Return true if a click was received by another element.
+    pub fn is_click_intercepted(&self) -> bool {
+        self.kind() == ErrorKind::ClickIntercepted
+    }
+
+    /// Return true if a user prompt such as an alert was open.
+    pub fn is_unexpected_alert(&self) -> bool {
+        self.kind() == ErrorKind::UnexpectedAlert
+    }
+
+    /// Return true if the WebDriver server could not be reached.
+    pub fn is_connection_error(&self) -> bool {
+        self.kind() == ErrorKind::Connection
+    }
+
+    /// The `data` field of the error returned by the WebDriver server, if any.
+    ///
+    /// For example, for `UnexpectedAlertOpen` errors this usually contains the alert text.
+    pub fn driver_data(&self) -> Option<&serde_json::Value> {
+        self.0.inner.info()?.value.data.as_ref()
+    }
+}
+
+impl WebDriverErrorInner {
+    /// The normalized kind of this error. See [`WebDriverError::kind`].
+    ///
+    /// Driver-specific messages are matched against the messages for every driver.
+    pub fn kind(&self) -> ErrorKind {
+        self.connection_kind()
+            .or_else(|| driver_errors::classify(None, self))
+            .unwrap_or_else(|| self.base_kind())
+    }
+
+    /// Proxies in front of the server, such as a Selenium Grid, return these when the
+    /// node cannot be reached.
+    fn connection_kind(&self) -> Option<ErrorKind> {
+        match self {
+            WebDriverErrorInner::UnknownResponse(502..=504, _) => Some(ErrorKind::Connection),
+            _ => None,
+        }
+    }
+
+    /// The kind of this error, based on the variant only.
+    fn base_kind(&self) -> ErrorKind {
+        use WebDriverErrorInner::*;
+        match self {
+            NoSuchElement(_) => ErrorKind::NoSuchElement,
+            StaleElementReference(_) | DetachedShadowRoot(_) => ErrorKind::Stale,
+            Timeout(_) | WebDriverTimeout(_) | ScriptTimeout(_) => ErrorKind::Timeout,
+            InvalidSessionId(_) => ErrorKind::InvalidSession,
+            ElementClickIntercepted(_) => ErrorKind::ClickIntercepted,
+            ElementNotInteractable(_) | InvalidElementState(_) | MoveTargetOutOfBounds(_) => {
+                ErrorKind::NotInteractable
+            }
+            UnexpectedAlertOpen(_) => ErrorKind::UnexpectedAlert,
+            RequestFailed(_) | HttpError(_) | IoError(_) | CommandSendError(_) => {
+                ErrorKind::Connection
+            }
+            NoSuchAlert(_) | NoSuchCookie(_) | NoSuchShadowRoot(_) | NoSuchFrame(_)
+            | NoSuchWindow(_) => ErrorKind::NotFound,
+            InvalidArgument(_) | InvalidSelector(_) | InvalidCookieDomain(_) | InvalidUrl(_) => {
+                ErrorKind::InvalidRequest
+            }
+            JavascriptError(_) => ErrorKind::Javascript,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// Get the error information returned by the WebDriver server, if this error has any.
+    pub fn info(&self) -> Option<&WebDriverErrorInfo> {
+        match self {
+            WebDriverErrorInner::NotInSpec(info)
+            | WebDriverErrorInner::ElementClickIntercepted(info)
+            | WebDriverErrorInner::ElementNotInteractable(info)
+            | WebDriverErrorInner::InsecureCertificate(info)
+            | WebDriverErrorInner::InvalidArgument(info)
+            | WebDriverErrorInner::InvalidCookieDomain(info)
+            | WebDriverErrorInner::InvalidElementState(info)
+            | WebDriverErrorInner::InvalidSelector(info)
+            | WebDriverErrorInner::InvalidSessionId(info)
+            | WebDriverErrorInner::JavascriptError(info)
+            | WebDriverErrorInner::MoveTargetOutOfBounds(info)
+            | WebDriverErrorInner::NoSuchAlert(info)
+            | WebDriverErrorInner::NoSuchCookie(info)
+            | WebDriverErrorInner::NoSuchElement(info)
+            | WebDriverErrorInner::NoSuchShadowRoot(info)
+            | WebDriverErrorInner::NoSuchFrame(info)
+            | WebDriverErrorInner::NoSuchWindow(info)
+            | WebDriverErrorInner::ScriptTimeout(info)
+            | WebDriverErrorInner::SessionNotCreated(info)
+            | WebDriverErrorInner::StaleElementReference(info)
+            | WebDriverErrorInner::DetachedShadowRoot(info)
+            | WebDriverErrorInner::WebDriverTimeout(info)
+            | WebDriverErrorInner::UnableToSetCookie(info)
+            | WebDriverErrorInner::UnableToCaptureScreen(info)
+            | WebDriverErrorInner::UnexpectedAlertOpen(info)
+            | WebDriverErrorInner::UnknownCommand(info)
+            | WebDriverErrorInner::UnknownError(info)
+            | WebDriverErrorInner::UnknownMethod(info)
+            | WebDriverErrorInner::UnsupportedOperation(info) => Some(info),
+            _ => None,
+        }
+    }
+
+    /// The error code returned by the WebDriver server, if any, even if it is not one
+    /// of the codes in the W3C spec.
+    pub fn raw_code(&self) -> Option<&str> {
+        let info = self.info()?;
+        match info.error.is_empty() {
+            true => info.value.error.as_deref().filter(|x| !x.is_empty()),
+            false => Some(&info.error),
+        }
+    }
+
+    /// The W3C error code for this error, if it is one of the errors in the W3C spec.
+    pub fn w3c_code(&self) -> Option<&'static str> {
+        use WebDriverErrorInner::*;
+        Some(match self {
+            ElementClickIntercepted(_) => "element click intercepted",
+            ElementNotInteractable(_) => "element not interactable",
+            InsecureCertificate(_) => "insecure certificate",
+            InvalidArgument(_) => "invalid argument",
+            InvalidCookieDomain(_) => "invalid cookie domain",
+            InvalidElementState(_) => "invalid element state",
+            InvalidSelector(_) => "invalid selector",
+            InvalidSessionId(_) => "invalid session id",
+            JavascriptError(_) => "javascript error",
+            MoveTargetOutOfBounds(_) => "move target out of bounds",
+            NoSuchAlert(_) => "no such alert",
+            NoSuchCookie(_) => "no such cookie",
+            NoSuchElement(_) => "no such element",
+            NoSuchShadowRoot(_) => "no such shadow root",
+            NoSuchFrame(_) => "no such frame",
+            NoSuchWindow(_) => "no such window",
+            ScriptTimeout(_) => "script timeout",
+            SessionNotCreated(_) => "session not created",
+            StaleElementReference(_) => "stale element reference",
+            DetachedShadowRoot(_) => "detached shadow root",
+            WebDriverTimeout(_) => "timeout",
+            UnableToSetCookie(_) => "unable to set cookie",
+            UnableToCaptureScreen(_) => "unable to capture screen",
+            UnexpectedAlertOpen(_) => "unexpected alert open",
+            UnknownCommand(_) => "unknown command",
+            UnknownError(_) => "unknown error",
+            UnknownMethod(_) => "unknown method",
+            UnsupportedOperation(_) => "unsupported operation",
+            _ => return None,
+        })
+    }
+
+    /// The W3C error code for errors relating to an element, otherwise None.
+    fn element_error_code(&self) -> Option<&'static str> {
+        match self {
+            WebDriverErrorInner::NoSuchElement(_) => Some("no such element"),
+            WebDriverErrorInner::StaleElementReference(_) => Some("stale element reference"),
+            WebDriverErrorInner::ElementNotInteractable(_) => Some("element not interactable"),
+            WebDriverErrorInner::ElementClickIntercepted(_) => Some("element click intercepted"),
+            WebDriverErrorInner::InvalidElementState(_) => Some("invalid element state"),
+            WebDriverErrorInner::InvalidSelector(_) => Some("invalid selector"),
+            WebDriverErrorInner::NoSuchShadowRoot(_) => Some("no such shadow root"),
+            WebDriverErrorInner::DetachedShadowRoot(_) => Some("detached shadow root"),
+            _ => None,
+        }
     }
 }
 
@@ -375,13 +1132,13 @@ impl Deref for WebDriverError {
     type Target = WebDriverErrorInner;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.0.inner
     }
 }
 
 impl DerefMut for WebDriverError {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.0.inner
     }
 }
 
@@ -411,3 +1168,564 @@ impl From<serde_json::Error> for WebDriverError {
         WebDriverError::Json(err.to_string())
     }
 }
+
+#[cfg(feature = "miette")]
+impl WebDriverError {
+    /// Suggest how to fix common errors.
+    fn help_text(&self) -> Option<&'static str> {
+        Some(match self.kind() {
+            ErrorKind::NoSuchElement => {
+                "check the selector, or wait for the element with query() or wait_until()"
+            }
+            ErrorKind::Stale => {
+                "the element was removed from the page; find it again, or use a component \
+                 resolver that finds it again when it goes stale"
+            }
+            ErrorKind::ClickIntercepted => {
+                "another element covered the target; try scroll_into_view() first, or \
+                 click_with_policy(&RetryPolicy::transient()) to wait for overlays to go away"
+            }
+            ErrorKind::NotInteractable => {
+                "the element may be hidden, disabled or animating; wait until it is displayed \
+                 and enabled, or retry with RetryPolicy::transient()"
+            }
+            ErrorKind::UnexpectedAlert => {
+                "accept or dismiss the alert first, or handle it automatically with on_alert()"
+            }
+            ErrorKind::InvalidSession => {
+                "the browser may have crashed or been closed; start a new session"
+            }
+            ErrorKind::Timeout => {
+                "check the condition that was being waited for, or increase the timeout"
+            }
+            ErrorKind::Connection => {
+                "check that the WebDriver server is running and reachable at the server URL"
+            }
+            _ => return None,
+        })
+    }
+
+    /// The selector or URL that the error relates to, if known.
+    fn related_source(&self) -> Option<(&String, &'static str)> {
+        if let Some(selector) = self.0.context.as_ref().and_then(|x| x.selector.as_ref()) {
+            return Some((selector, "this selector"));
+        }
+        let url = self.0.attachments.as_ref().and_then(|x| x.url.as_ref())?;
+        Some((url, "the page that was open"))
+    }
+}
+
+/// Errors are reported with the W3C error code (or the raw code returned by the driver),
+/// help text for common errors, and a label for the selector or URL if one is known.
+///
+/// # Example:
+/// ```ignore
+/// #[tokio::main]
+/// async fn main() -> miette::Result<()> {
+///     let driver = WebDriver::new("http://localhost:9515", DesiredCapabilities::chrome()).await?;
+///     driver.find(By::Id("submit")).await?.click().await?;
+///     driver.quit().await?;
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for WebDriverError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = self.0.inner.w3c_code().or_else(|| self.0.inner.raw_code())?;
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(self.help_text()?))
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        let (source, _) = self.related_source()?;
+        Some(source as &dyn miette::SourceCode)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let (source, label) = self.related_source()?;
+        // Selectors are described as "css `#submit`", so only label the part in backticks.
+        let span = match (source.find('`'), source.rfind('`')) {
+            (Some(start), Some(end)) if start < end => (start + 1)..end,
+            _ => 0..source.len(),
+        };
+        let label = miette::LabeledSpan::new_with_span(Some(label.to_string()), span);
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::By;
+
+    fn context() -> ElementErrorContext {
+        ElementErrorContext {
+            selector: Some(By::Css("#submit").describe()),
+            parent: Some("<form id=login>".to_string()),
+            operation: Some("click".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_error_size() {
+        // Everything known about an error is boxed, so results only carry one pointer.
+        assert_eq!(std::mem::size_of::<WebDriverError>(), std::mem::size_of::<usize>());
+        assert_eq!(std::mem::size_of::<WebDriverResult<()>>(), std::mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn test_element_context_display() {
+        assert_eq!(
+            context().to_string(),
+            "css `#submit` within <form id=login> (operation: click)"
+        );
+        assert_eq!(ElementErrorContext::operation("clear").to_string(), "(operation: clear)");
+        let context = ElementErrorContext {
+            selector: Some(By::XPath("//a").describe()),
+            ..Default::default()
+        };
+        assert_eq!(context.to_string(), "xpath `//a`");
+    }
+
+    #[test]
+    fn test_element_error_display() {
+        let err = no_such_element("element not found".to_string()).with_element_context(context());
+        let msg = err.to_string();
+        let (first, rest) = msg.split_once('\n').unwrap();
+        assert_eq!(
+            first,
+            "no such element: css `#submit` within <form id=login> (operation: click)"
+        );
+        assert!(rest.starts_with("Element not found:"));
+        assert_eq!(err.element_context(), Some(&context()));
+
+        // Errors without context are displayed as before.
+        let err = no_such_element("element not found".to_string());
+        assert!(err.to_string().starts_with("Element not found:"));
+    }
+
+    #[test]
+    fn test_error_context() {
+        let err = WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+            "not supported in chrome context".to_string(),
+        ));
+        let msg = err.to_string();
+        let err = err.with_context("Firefox chrome".to_string());
+        // The error itself is unchanged.
+        assert_eq!(err.to_string(), msg);
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::UnsupportedOperation(_)));
+        assert_eq!(err.attachments().unwrap().context.as_deref(), Some("Firefox chrome"));
+
+        // The context is kept when artifacts are attached later.
+        let err = err.with_attachments(ErrorAttachments {
+            url: Some("about:blank".to_string()),
+            ..Default::default()
+        });
+        let attachments = err.attachments().unwrap();
+        assert_eq!(attachments.context.as_deref(), Some("Firefox chrome"));
+        assert_eq!(attachments.url.as_deref(), Some("about:blank"));
+    }
+
+    #[test]
+    fn test_element_context_merge() {
+        // The context added first is kept, and missing fields are filled in.
+        let err = WebDriverError::StaleElementReference(WebDriverErrorInfo::new("stale".into()))
+            .with_element_context(ElementErrorContext::operation("find"))
+            .with_element_context(context());
+        let expected = ElementErrorContext {
+            operation: Some("find".to_string()),
+            ..context()
+        };
+        assert_eq!(err.element_context(), Some(&expected));
+        assert!(err.to_string().starts_with("stale element reference: css `#submit`"));
+    }
+
+    #[test]
+    fn test_element_context_ignored() {
+        let err = WebDriverError::Timeout("too slow".to_string()).with_element_context(context());
+        assert!(!err.is_element_error());
+        assert_eq!(err.element_context(), None);
+        assert_eq!(err.to_string(), "operation timed out: too slow");
+    }
+
+    /// A `no such element` error recorded from chromedriver.
+    const CHROMEDRIVER_NO_SUCH_ELEMENT: &str = r##"{"value":{"error":"no such element","message":"no such element: Unable to locate element: {\"method\":\"css selector\",\"selector\":\"#missing\"}\n  (Session info: chrome=120.0.6099.109)","stacktrace":"#0 0x55d0c5a1ef13 <unknown>\n#1 0x55d0c5705ccb <unknown>\n#2 0x55d0c57540d7 <unknown>\n"}}"##;
+
+    /// An `unexpected alert open` error recorded from geckodriver.
+    const GECKODRIVER_ALERT_OPEN: &str = r##"{"value":{"error":"unexpected alert open","message":"Dismissed user prompt dialog: Are you sure?","stacktrace":"RemoteError@chrome://remote/content/shared/RemoteError.sys.mjs:8:8\nWebDriverError@chrome://remote/content/shared/webdriver/Errors.sys.mjs:193:5\n","data":{"text":"Are you sure?"}}}"##;
+
+    #[test]
+    fn test_parse_chromedriver_error() {
+        let err = WebDriverError::from_response(404, CHROMEDRIVER_NO_SUCH_ELEMENT.as_bytes());
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::NoSuchElement(_)));
+        assert_eq!(err.http_status(), Some(404));
+        assert_eq!(err.raw_body(), Some(CHROMEDRIVER_NO_SUCH_ELEMENT.as_bytes()));
+        assert!(!err.raw_body_truncated());
+        assert!(err.driver_stacktrace().unwrap().starts_with("#0 0x55d0c5a1ef13"));
+        assert_eq!(err.driver_data(), None);
+
+        // The raw body is only shown by Debug.
+        assert!(!err.to_string().contains(r#"{"value""#));
+        assert!(format!("{err:?}").contains(r#"{\"value\""#));
+    }
+
+    #[test]
+    fn test_parse_geckodriver_error() {
+        let err = WebDriverError::parse(500, GECKODRIVER_ALERT_OPEN.to_string());
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::UnexpectedAlertOpen(_)));
+        assert_eq!(err.http_status(), Some(500));
+        assert!(err.driver_stacktrace().unwrap().starts_with("RemoteError@"));
+        assert_eq!(err.driver_data().unwrap()["text"], "Are you sure?");
+    }
+
+    #[test]
+    fn test_parse_invalid_body() {
+        // A proxy error page that is not valid UTF-8.
+        let body = b"\xff\xfe<html>502 Bad Gateway</html>";
+        let err = WebDriverError::from_response(502, body);
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::UnknownResponse(502, _)));
+        assert_eq!(err.http_status(), Some(502));
+        assert_eq!(err.raw_body(), Some(&body[..]));
+        assert_eq!(err.driver_stacktrace(), None);
+
+        // A response that was cut off.
+        let body = &CHROMEDRIVER_NO_SUCH_ELEMENT[..80];
+        let err = WebDriverError::from_response(404, body.as_bytes());
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::UnknownResponse(404, _)));
+        assert_eq!(err.raw_body(), Some(body.as_bytes()));
+    }
+
+    #[test]
+    fn test_raw_body_capped() {
+        let body = vec![b'x'; WebDriverError::MAX_RAW_BODY_LEN + 10];
+        let err = WebDriverError::from_response(500, &body);
+        assert_eq!(err.raw_body().unwrap().len(), WebDriverError::MAX_RAW_BODY_LEN);
+        assert!(err.raw_body_truncated());
+
+        // Errors that did not come from the server have no response.
+        let err = WebDriverError::Timeout("too slow".to_string());
+        assert_eq!(err.http_status(), None);
+        assert_eq!(err.raw_body(), None);
+        assert!(!err.raw_body_truncated());
+    }
+
+    #[test]
+    fn test_error_kind() {
+        // Payloads captured from chromedriver, geckodriver and safaridriver.
+        let cases: &[(u16, &str, ErrorKind)] = &[
+            (404, CHROMEDRIVER_NO_SUCH_ELEMENT, ErrorKind::NoSuchElement),
+            (500, GECKODRIVER_ALERT_OPEN, ErrorKind::UnexpectedAlert),
+            (
+                404,
+                r#"{"value":{"error":"stale element reference","message":"stale element reference: stale element not found in the current frame\n  (Session info: chrome=120.0.6099.109)","stacktrace":""}}"#,
+                ErrorKind::Stale,
+            ),
+            (
+                400,
+                r#"{"value":{"error":"element click intercepted","message":"element click intercepted: Element <button id=\"submit\">...</button> is not clickable at point (90, 20). Other element would receive the click: <div class=\"overlay\"></div>","stacktrace":""}}"#,
+                ErrorKind::ClickIntercepted,
+            ),
+            (
+                500,
+                r#"{"value":{"error":"unknown error","message":"unknown error: Element <button id=\"submit\">...</button> is not clickable at point (90, 20). Other element would receive the click: <div class=\"overlay\"></div>\n  (Session info: chrome=74.0.3729.169)"}}"#,
+                ErrorKind::ClickIntercepted,
+            ),
+            (
+                500,
+                r#"{"value":{"error":"unknown error","message":"unknown error: session deleted because of page crash\nfrom unknown error: cannot determine loading status\nfrom tab crashed","stacktrace":""}}"#,
+                ErrorKind::InvalidSession,
+            ),
+            (
+                404,
+                r#"{"value":{"error":"invalid session id","message":"invalid session id","stacktrace":""}}"#,
+                ErrorKind::InvalidSession,
+            ),
+            (
+                500,
+                r#"{"value":{"error":"timeout","message":"timeout: Timed out receiving message from renderer: 300.000","stacktrace":""}}"#,
+                ErrorKind::Timeout,
+            ),
+            (
+                404,
+                r#"{"value":{"error":"no such element","message":"Unable to locate element: #missing","stacktrace":"RemoteError@chrome://remote/content/shared/RemoteError.sys.mjs:8:8\n"}}"#,
+                ErrorKind::NoSuchElement,
+            ),
+            (
+                404,
+                r#"{"value":{"error":"stale element reference","message":"The element with the reference 8b2d6e0d-4e7b-4c2d-9f3a-2b1d2f5c7a10 is stale; either its node document is not the active document, or it is no longer connected to the DOM","stacktrace":""}}"#,
+                ErrorKind::Stale,
+            ),
+            (
+                404,
+                r#"{"value":{"error":"invalid session id","message":"Tried to run command without establishing a connection","stacktrace":""}}"#,
+                ErrorKind::InvalidSession,
+            ),
+            (
+                400,
+                r#"{"value":{"error":"element not interactable","message":"Element <input id=\"hidden\"> could not be scrolled into view","stacktrace":""}}"#,
+                ErrorKind::NotInteractable,
+            ),
+            (
+                404,
+                r#"{"value":{"error":"no such element","message":"","stacktrace":""}}"#,
+                ErrorKind::NoSuchElement,
+            ),
+            (
+                404,
+                r#"{"value":{"error":"no such alert","message":"","stacktrace":""}}"#,
+                ErrorKind::NotFound,
+            ),
+            (
+                500,
+                r#"{"value":{"error":"unexpected alert open","message":"","stacktrace":""}}"#,
+                ErrorKind::UnexpectedAlert,
+            ),
+            (
+                500,
+                r#"{"value":{"error":"javascript error","message":"A JavaScript exception occurred: ReferenceError: Can't find variable: foo","stacktrace":""}}"#,
+                ErrorKind::Javascript,
+            ),
+            (
+                500,
+                r#"{"value":{"error":"unknown error","message":"An unknown error occurred.","stacktrace":""}}"#,
+                ErrorKind::Other,
+            ),
+            (502, "<html>502 Bad Gateway</html>", ErrorKind::Connection),
+        ];
+        for (status, body, kind) in cases {
+            let err = WebDriverError::parse(*status, body.to_string());
+            assert_eq!(err.kind(), *kind, "{body}");
+        }
+    }
+
+    #[test]
+    fn test_error_predicates() {
+        let err = WebDriverError::from_response(404, CHROMEDRIVER_NO_SUCH_ELEMENT.as_bytes());
+        assert!(err.is_no_such_element());
+        assert!(!err.is_stale());
+        assert!(no_such_element("missing".to_string()).is_no_such_element());
+        assert!(WebDriverError::Timeout("too slow".to_string()).is_timeout());
+        assert!(WebDriverError::RequestFailed("refused".to_string()).is_connection_error());
+        assert!(
+            WebDriverError::parse(500, GECKODRIVER_ALERT_OPEN.to_string()).is_unexpected_alert()
+        );
+        let info = WebDriverErrorInfo::new("intercepted".to_string());
+        assert!(WebDriverError::ElementClickIntercepted(info.clone()).is_click_intercepted());
+        assert!(WebDriverError::InvalidSessionId(info.clone()).is_invalid_session());
+        assert!(WebDriverError::StaleElementReference(info).is_stale());
+    }
+
+    #[test]
+    fn test_timeout_error_builder() {
+        let mut wait = TimeoutErrorBuilder::new("an alert to open");
+        assert_eq!(
+            wait.render(Duration::from_millis(1500)),
+            "waited 1.5s for an alert to open (0 attempts)"
+        );
+        wait.attempt();
+        assert_eq!(wait.render(Duration::ZERO), "waited 0.0ns for an alert to open (1 attempt)");
+        wait.attempt();
+        wait.observed("no alert");
+        assert_eq!(wait.attempts(), 2);
+        assert_eq!(
+            wait.render(Duration::from_secs(5)),
+            "waited 5.0s for an alert to open (2 attempts); last saw no alert"
+        );
+        assert!(wait.build().is_timeout());
+    }
+
+    #[test]
+    fn test_error_attachments() {
+        let err = WebDriverError::Timeout("timeout".to_string());
+        assert!(err.attachments().is_none());
+
+        let attachments = ErrorAttachments {
+            screenshot: Some(PathBuf::from("errors/error-1.png")),
+            url: Some("http://localhost/".to_string()),
+            console: Some(vec![LogEntry {
+                level: "SEVERE".to_string(),
+                message: "boom".to_string(),
+                timestamp: 0,
+            }]),
+            ..Default::default()
+        };
+        let err = err.with_attachments(attachments.clone());
+        assert_eq!(err.attachments(), Some(&attachments));
+        let debug = format!("{err:?}");
+        assert!(debug.contains("errors/error-1.png"));
+        assert!(debug.contains("boom"));
+        // Attachments do not change the message.
+        assert_eq!(err.to_string(), "operation timed out: timeout");
+    }
+
+    #[test]
+    fn test_error_json() {
+        let body = r#"{"value":{"error":"no such element","message":"boom","stacktrace":""}}"#;
+        let request = RequestData::new(http::Method::POST, "/session/1234/element");
+        let mut err = WebDriverError::parse(404, body.to_string())
+            .with_command(&request)
+            .with_session_id(&SessionId::from("1234"))
+            .with_element_context(context())
+            .with_attachments(ErrorAttachments {
+                screenshot: Some(PathBuf::from("errors/error-1.png")),
+                page_source: None,
+                url: Some("http://localhost/login".to_string()),
+                console: Some(vec![LogEntry {
+                    level: "SEVERE".to_string(),
+                    message: "oops".to_string(),
+                    timestamp: 1700000000000,
+                }]),
+                context: None,
+            });
+        err.0.timestamp = UNIX_EPOCH + Duration::from_millis(1700000000123);
+
+        // The schema is consumed by CI dashboards, so any change to it must be deliberate.
+        let expected = r#"{
+  "kind": "no_such_element",
+  "message": "boom",
+  "command": "POST /session/1234/element",
+  "selector": "css `#submit`",
+  "url": "http://localhost/login",
+  "http_status": 404,
+  "attachments": {
+    "screenshot": "errors/error-1.png",
+    "page_source": null,
+    "url": "http://localhost/login",
+    "console": [
+      {
+        "level": "SEVERE",
+        "message": "oops",
+        "timestamp": 1700000000000
+      }
+    ]
+  },
+  "timestamp": 1700000000123,
+  "session_id": "1234"
+}"#;
+        assert_eq!(serde_json::to_string_pretty(&err.to_json()).unwrap(), expected);
+
+        let json = WebDriverError::Timeout("timeout".to_string()).to_json();
+        let keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            [
+                "kind",
+                "message",
+                "command",
+                "selector",
+                "url",
+                "http_status",
+                "attachments",
+                "timestamp",
+                "session_id"
+            ]
+        );
+        assert_eq!(json["kind"], "timeout");
+        assert!(json["command"].is_null());
+        assert!(json["attachments"].is_null());
+    }
+
+    #[cfg(feature = "miette")]
+    fn render_report(err: &WebDriverError) -> String {
+        let mut report = String::new();
+        miette::NarratableReportHandler::new().render_report(&mut report, err).unwrap();
+        report
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_diagnostic_report() {
+        let info = || WebDriverErrorInfo::new("boom".to_string());
+        let cases = [
+            (WebDriverError::NoSuchElement(info()), "no such element", "check the selector"),
+            (
+                WebDriverError::StaleElementReference(info()),
+                "stale element reference",
+                "find it again",
+            ),
+            (
+                WebDriverError::ElementClickIntercepted(info()),
+                "element click intercepted",
+                "try scroll_into_view()",
+            ),
+            (
+                WebDriverError::ElementNotInteractable(info()),
+                "element not interactable",
+                "RetryPolicy::transient()",
+            ),
+            (WebDriverError::UnexpectedAlertOpen(info()), "unexpected alert open", "on_alert()"),
+            (WebDriverError::InvalidSessionId(info()), "invalid session id", "start a new session"),
+            (WebDriverError::WebDriverTimeout(info()), "timeout", "increase the timeout"),
+        ];
+        for (err, code, help) in cases {
+            let report = render_report(&err);
+            assert!(report.contains(&format!("diagnostic code: {code}")), "{report}");
+            assert!(report.contains(help), "{report}");
+        }
+
+        // Errors that are not in the spec keep the raw code, and have no help.
+        let body = r#"{"value":{"error":"vendor error","message":"boom","stacktrace":""}}"#;
+        let report = render_report(&WebDriverError::parse(500, body.to_string()));
+        assert!(report.contains("diagnostic code: vendor error"), "{report}");
+        assert!(!report.contains("help:"), "{report}");
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_diagnostic_labels() {
+        use miette::Diagnostic;
+
+        let err = no_such_element("element not found".to_string()).with_element_context(context());
+        let report = render_report(&err);
+        assert!(report.contains("this selector"), "{report}");
+        let label = err.labels().unwrap().next().unwrap();
+        assert_eq!(label.offset(), "css `".len());
+        assert_eq!(label.len(), "#submit".len());
+
+        let err = WebDriverError::UnexpectedAlertOpen(WebDriverErrorInfo::new("boom".to_string()))
+            .with_attachments(ErrorAttachments {
+                url: Some("http://localhost/login".to_string()),
+                ..Default::default()
+            });
+        let label = err.labels().unwrap().next().unwrap();
+        assert_eq!(label.label(), Some("the page that was open"));
+        assert_eq!(label.len(), "http://localhost/login".len());
+
+        let err = WebDriverError::Timeout("timeout".to_string());
+        assert!(err.labels().is_none());
+    }
+
+    #[cfg(feature = "anyhow")]
+    #[test]
+    fn test_source_chain() {
+        use http::Method;
+
+        let body =
+            r#"{"value":{"error":"element click intercepted","message":"boom","stacktrace":""}}"#;
+        let request = RequestData::new(Method::POST, "/session/1234/element/5678/click");
+        let err = WebDriverError::parse(400, body.to_string())
+            .with_command(&request)
+            .with_element_context(ElementErrorContext::operation("click"));
+        let err = anyhow::Error::from(err);
+        let chain: Vec<String> = err.chain().map(|x| x.to_string()).collect();
+        assert_eq!(chain.len(), 3);
+        assert!(chain[0].starts_with("element click intercepted: (operation: click)"));
+        assert_eq!(chain[1], "WebDriver command POST /session/1234/element/5678/click failed");
+        assert_eq!(
+            chain[2],
+            format!("the server responded with HTTP 400 and a {}-byte body", body.len())
+        );
+
+        // Component errors chain to the error they wrap.
+        let err = WebDriverError::ComponentNotReady(
+            "LoginForm".to_string(),
+            WebDriverError::Timeout("timeout".to_string()),
+        );
+        let err = anyhow::Error::from(err);
+        assert_eq!(err.chain().count(), 2);
+        assert!(err.root_cause().to_string().contains("timeout"));
+    }
+}
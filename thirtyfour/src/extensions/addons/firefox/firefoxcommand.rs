@@ -1,8 +1,33 @@
+use std::fmt::{Display, Formatter};
+
 use http::Method;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::{common::command::FormatRequestData, RequestData};
 
+/// The context that Firefox commands are run in.
+///
+/// See `FirefoxTools::set_context()`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirefoxContext {
+    /// The web page in the current window. This is the default.
+    #[default]
+    Content,
+    /// The browser UI itself, where scripts run with chrome privileges.
+    Chrome,
+}
+
+impl Display for FirefoxContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FirefoxContext::Content => write!(f, "content"),
+            FirefoxContext::Chrome => write!(f, "chrome"),
+        }
+    }
+}
+
 /// Extra commands specific to Firefox.
 #[derive(Debug)]
 pub enum FirefoxCommand {
@@ -15,6 +40,10 @@ pub enum FirefoxCommand {
     },
     /// Take a full screenshot.
     FullScreenshot {},
+    /// Get the context that commands are run in.
+    GetContext,
+    /// Set the context that commands are run in.
+    SetContext(FirefoxContext),
 }
 
 impl FormatRequestData for FirefoxCommand {
@@ -34,6 +63,13 @@ impl FormatRequestData for FirefoxCommand {
                 Method::GET,
                 format!("/session/{}/moz/screenshot/full", session_id),
             ),
+            FirefoxCommand::GetContext => {
+                RequestData::new(Method::GET, format!("/session/{}/moz/context", session_id))
+            }
+            FirefoxCommand::SetContext(context) => {
+                RequestData::new(Method::POST, format!("/session/{}/moz/context", session_id))
+                    .add_body(json!({ "context": context }))
+            }
         }
     }
 }
@@ -1,11 +1,15 @@
+use std::future::Future;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 
-use super::FirefoxCommand;
-use crate::error::WebDriverResult;
+use serde_json::json;
+
+use super::{FirefoxCommand, FirefoxContext, PrefValue};
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::js::{FIREFOX_GET_PREF, FIREFOX_SET_PREF};
 use crate::session::handle::SessionHandle;
 use crate::support;
 
@@ -61,10 +65,153 @@ impl FirefoxTools {
         Ok(BASE64_STANDARD.decode(encoded)?)
     }
 
+    /// Get the context that commands are run in.
+    pub async fn get_context(&self) -> WebDriverResult<FirefoxContext> {
+        let context: FirefoxContext = self.handle.cmd(FirefoxCommand::GetContext).await?.value()?;
+        self.state().set_context(context);
+        Ok(context)
+    }
+
+    /// Set the context that commands are run in.
+    ///
+    /// In the chrome context, commands such as `execute()` script the browser UI itself
+    /// with chrome privileges, for example to read preferences. Many commands are not
+    /// supported in the chrome context. Their errors are returned unchanged, but
+    /// annotated with the context, see [`ErrorAttachments::context`].
+    ///
+    /// Prefer [`FirefoxTools::with_chrome_context`], which always restores the content
+    /// context.
+    ///
+    /// [`ErrorAttachments::context`]: crate::error::ErrorAttachments::context
+    pub async fn set_context(&self, context: FirefoxContext) -> WebDriverResult<()> {
+        self.handle.cmd(FirefoxCommand::SetContext(context)).await?;
+        self.state().set_context(context);
+        Ok(())
+    }
+
+    /// Run the specified function in the chrome context, and then switch back to the
+    /// content context, even if the function fails.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::firefox();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let homepage: String = driver
+    ///     .firefox()
+    ///     .with_chrome_context(|| async {
+    ///         let script = r#"return Services.prefs.getStringPref("browser.startup.homepage");"#;
+    ///         driver.execute(script, Vec::new()).await?.convert()
+    ///     })
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn with_chrome_context<F, Fut, T>(&self, f: F) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = WebDriverResult<T>> + Send,
+        T: Send,
+    {
+        self.set_context(FirefoxContext::Chrome).await?;
+        let result = f().await;
+        // Always restore the content context, even if the function failed.
+        let restored = self.set_context(FirefoxContext::Content).await;
+        let value = result?;
+        restored?;
+        Ok(value)
+    }
+
+    /// Get the value of the specified preference, or `None` if it is not set.
+    ///
+    /// This runs a privileged script in the chrome context, and then switches back to the
+    /// content context. Returns a `ParseError` if the preference has an unknown type.
+    pub async fn get_pref(&self, name: &str) -> WebDriverResult<Option<PrefValue>> {
+        let ret = self
+            .with_chrome_context(|| self.handle.execute(FIREFOX_GET_PREF, vec![json!(name)]))
+            .await?;
+        PrefValue::from_script(name, ret.json())
+    }
+
+    /// Set the specified preference for the rest of the session.
+    ///
+    /// This runs a privileged script in the chrome context, and then switches back to the
+    /// content context. Firefox returns a `JavascriptError` if the preference already
+    /// has a value of another type.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::addons::firefox::PrefValue;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::firefox();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// // Stop using the proxy.
+    /// driver.firefox().set_pref("network.proxy.type", PrefValue::Int(0)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_pref(&self, name: &str, value: impl Into<PrefValue>) -> WebDriverResult<()> {
+        let args = vec![json!(name), json!(value.into())];
+        self.with_chrome_context(|| self.handle.execute(FIREFOX_SET_PREF, args)).await?;
+        Ok(())
+    }
+
+    /// Take a screenshot of the whole page, beyond the viewport, and return it as PNG bytes.
+    ///
+    /// This uses geckodriver's native full-page screenshot, which captures the page in a
+    /// single pass and is more accurate than stitching several viewport screenshots.
+    ///
+    /// Returns an `UnsupportedOperation` error if the session is not a Firefox session.
+    /// Use [`SessionHandle::full_page_screenshot_png`] to fall back to other browsers.
+    pub async fn full_page_screenshot_png(&self) -> WebDriverResult<Vec<u8>> {
+        let browser = self.handle.driver_info().browser_name.as_str();
+        if !browser.is_empty() && browser != "firefox" {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(format!(
+                "native full-page screenshots require Firefox, not {browser}"
+            ))));
+        }
+        self.full_screenshot_as_png().await
+    }
+
     /// Take a full-page screenshot of the current window and write it to the specified filename.
     pub async fn full_screenshot(&self, path: &Path) -> WebDriverResult<()> {
         let png = self.full_screenshot_as_png().await?;
         support::write_file(path, png).await?;
         Ok(())
     }
+
+    /// The Firefox-specific state of this session.
+    fn state(&self) -> Arc<FirefoxState> {
+        self.handle.extension_state()
+    }
+}
+
+/// The state of a Firefox session, which the driver is not asked for on every command.
+#[derive(Debug, Default)]
+pub(crate) struct FirefoxState {
+    /// The context set via `FirefoxTools::set_context()`.
+    context: Mutex<FirefoxContext>,
+}
+
+impl FirefoxState {
+    /// The context set via `FirefoxTools::set_context()`.
+    pub(crate) fn context(&self) -> FirefoxContext {
+        *self.context.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn set_context(&self, context: FirefoxContext) {
+        *self.context.lock().unwrap_or_else(|e| e.into_inner()) = context;
+    }
 }
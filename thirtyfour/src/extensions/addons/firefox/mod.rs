@@ -1,5 +1,8 @@
 mod firefoxcommand;
 mod firefoxtools;
+mod prefs;
 
-pub use firefoxcommand::FirefoxCommand;
+pub use firefoxcommand::{FirefoxCommand, FirefoxContext};
+pub(crate) use firefoxtools::FirefoxState;
 pub use firefoxtools::FirefoxTools;
+pub use prefs::PrefValue;
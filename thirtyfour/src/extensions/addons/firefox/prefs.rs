@@ -0,0 +1,131 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::{WebDriverError, WebDriverResult};
+
+/// The `nsIPrefBranch.PREF_INVALID` type, for preferences that are not set.
+const PREF_INVALID: u64 = 0;
+/// The `nsIPrefBranch.PREF_STRING` type.
+const PREF_STRING: u64 = 32;
+/// The `nsIPrefBranch.PREF_INT` type.
+const PREF_INT: u64 = 64;
+/// The `nsIPrefBranch.PREF_BOOL` type.
+const PREF_BOOL: u64 = 128;
+
+/// The value of a Firefox preference.
+///
+/// See `FirefoxTools::set_pref()`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum PrefValue {
+    /// A string preference.
+    String(String),
+    /// An integer preference. Firefox stores these as 32-bit integers.
+    Int(i32),
+    /// A boolean preference.
+    Bool(bool),
+}
+
+impl PrefValue {
+    /// Get the value of the specified preference from the result of the
+    /// `FIREFOX_GET_PREF` script, or `None` if it is not set.
+    ///
+    /// Returns a `ParseError` with the observed type if the type is not known.
+    pub(crate) fn from_script(name: &str, result: &Value) -> WebDriverResult<Option<Self>> {
+        let value = &result["value"];
+        let pref = match result["type"].as_u64() {
+            Some(PREF_INVALID) => return Ok(None),
+            Some(PREF_STRING) => value.as_str().map(|x| Self::String(x.to_string())),
+            Some(PREF_INT) => value.as_i64().and_then(|x| i32::try_from(x).ok()).map(Self::Int),
+            Some(PREF_BOOL) => value.as_bool().map(Self::Bool),
+            _ => {
+                return Err(WebDriverError::ParseError(format!(
+                    "Firefox preference {name:?} has an unknown type: {}",
+                    result["type"]
+                )))
+            }
+        };
+        match pref {
+            Some(pref) => Ok(Some(pref)),
+            None => Err(WebDriverError::ParseError(format!(
+                "Firefox preference {name:?}: unexpected value {value} for type {}",
+                result["type"]
+            ))),
+        }
+    }
+}
+
+impl From<PrefValue> for Value {
+    /// The value as it is written in a profile, without its type.
+    fn from(value: PrefValue) -> Self {
+        match value {
+            PrefValue::String(x) => json!(x),
+            PrefValue::Int(x) => json!(x),
+            PrefValue::Bool(x) => json!(x),
+        }
+    }
+}
+
+impl From<&str> for PrefValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for PrefValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<i32> for PrefValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<bool> for PrefValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pref_value() {
+        let parse = |result: Value| PrefValue::from_script("test.pref", &result);
+        assert_eq!(parse(json!({ "type": 0, "value": null })).unwrap(), None);
+        assert_eq!(
+            parse(json!({ "type": 32, "value": "x" })).unwrap(),
+            Some(PrefValue::String("x".to_string()))
+        );
+        assert_eq!(parse(json!({ "type": 64, "value": -1 })).unwrap(), Some(PrefValue::Int(-1)));
+        assert_eq!(
+            parse(json!({ "type": 128, "value": true })).unwrap(),
+            Some(PrefValue::Bool(true))
+        );
+
+        let err = parse(json!({ "type": 16, "value": null })).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error: Firefox preference \"test.pref\" has an unknown type: 16"
+        );
+        assert!(parse(json!({ "type": 64, "value": "1" })).is_err());
+    }
+
+    #[test]
+    fn test_pref_value_serialize() {
+        assert_eq!(
+            serde_json::to_value(PrefValue::from(1)).unwrap(),
+            json!({ "type": "int", "value": 1 })
+        );
+        assert_eq!(
+            serde_json::to_value(PrefValue::from("x")).unwrap(),
+            json!({ "type": "string", "value": "x" })
+        );
+        assert_eq!(Value::from(PrefValue::from(true)), json!(true));
+    }
+}
@@ -0,0 +1,362 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::WebDriverResult;
+
+/// The roles of form controls that must have an accessible name.
+const FORM_CONTROL_ROLES: &[&str] = &[
+    "textbox",
+    "searchbox",
+    "combobox",
+    "listbox",
+    "checkbox",
+    "radio",
+    "slider",
+    "spinbutton",
+    "switch",
+];
+
+/// The accessibility tree of the page, returned by `WebDriver::accessibility_tree()`.
+///
+/// This is the tree exposed to assistive technologies such as screen readers, as computed
+/// by the browser. It only covers the top-level document, not its frames.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AxTree {
+    /// The nodes of the tree, in the order reported by the browser. The first is the root.
+    pub nodes: Vec<AxNode>,
+}
+
+/// A node in an [`AxTree`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AxNode {
+    /// The id of the node in the tree.
+    pub node_id: String,
+    /// True if the node is not exposed to assistive technologies, for example because it
+    /// is hidden or presentational.
+    pub ignored: bool,
+    /// The role of the node, for example `button`, `link` or `image`.
+    pub role: String,
+    /// The accessible name of the node, which is empty if it has none.
+    pub name: String,
+    /// The accessible description of the node, which is empty if it has none.
+    pub description: String,
+    /// The value of the node, for example the text of a text box.
+    pub value: String,
+    /// The other properties of the node by name, for example `focusable` or `checked`.
+    pub properties: Vec<(String, Value)>,
+    /// The id of the parent node, or `None` for the root.
+    pub parent_id: Option<String>,
+    /// The ids of the child nodes, in order.
+    pub child_ids: Vec<String>,
+    /// The id of the DOM node in the CDP `DOM` domain, if the node has one. See
+    /// `WebDriver::element_from_backend_node_id()`.
+    pub backend_node_id: Option<i64>,
+}
+
+impl AxNode {
+    /// The value of the specified property, if the node has it.
+    pub fn property(&self, name: &str) -> Option<&Value> {
+        self.properties.iter().find(|(x, _)| x == name).map(|(_, value)| value)
+    }
+
+    /// True if the accessible name is empty or only whitespace.
+    pub fn has_empty_name(&self) -> bool {
+        self.name.trim().is_empty()
+    }
+}
+
+impl AxTree {
+    /// Parse the result of the CDP `Accessibility.getFullAXTree` command.
+    pub(crate) fn from_cdp(result: Value) -> WebDriverResult<Self> {
+        let raw: RawTree = serde_json::from_value(result)?;
+        Ok(Self {
+            nodes: raw.nodes.into_iter().map(RawNode::resolve).collect(),
+        })
+    }
+
+    /// The root of the tree, if it is not empty.
+    pub fn root(&self) -> Option<&AxNode> {
+        self.nodes.first()
+    }
+
+    /// The node with the specified id.
+    pub fn node(&self, node_id: &str) -> Option<&AxNode> {
+        self.nodes.iter().find(|x| x.node_id == node_id)
+    }
+
+    /// The children of the specified node, in order.
+    pub fn children<'a>(&'a self, node: &'a AxNode) -> impl Iterator<Item = &'a AxNode> {
+        node.child_ids.iter().filter_map(|id| self.node(id))
+    }
+
+    /// The nodes that are exposed to assistive technologies.
+    pub fn exposed(&self) -> impl Iterator<Item = &AxNode> {
+        self.nodes.iter().filter(|x| !x.ignored)
+    }
+
+    /// The exposed nodes with the specified role, for example `button`.
+    pub fn find_by_role(&self, role: &str) -> Vec<&AxNode> {
+        self.exposed().filter(|x| x.role == role).collect()
+    }
+
+    /// The exposed nodes with the specified accessible name.
+    pub fn find_by_name(&self, name: &str) -> Vec<&AxNode> {
+        self.exposed().filter(|x| x.name == name).collect()
+    }
+
+    /// Check the exposed nodes for images without names, buttons and links with empty
+    /// names, and form controls without labels.
+    ///
+    /// These are only the most common problems found by tools such as axe, and a report
+    /// without violations does not mean that the page is accessible.
+    pub fn basic_audit(&self) -> AxAuditReport {
+        let violations = self
+            .exposed()
+            .filter(|node| node.has_empty_name())
+            .filter_map(|node| {
+                let kind = match node.role.as_str() {
+                    "image" | "img" => AxViolationKind::ImageWithoutName,
+                    "button" | "link" => AxViolationKind::EmptyButtonOrLink,
+                    role if FORM_CONTROL_ROLES.contains(&role) => {
+                        AxViolationKind::FormControlWithoutLabel
+                    }
+                    _ => return None,
+                };
+                Some(AxViolation {
+                    kind,
+                    role: node.role.clone(),
+                    node_id: node.node_id.clone(),
+                    backend_node_id: node.backend_node_id,
+                })
+            })
+            .collect();
+        AxAuditReport {
+            violations,
+        }
+    }
+}
+
+/// The kind of problem found by an [`AxAuditReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AxViolationKind {
+    /// An image without an accessible name, such as an `img` without `alt`. Decorative
+    /// images should have an empty `alt` so that they are ignored.
+    ImageWithoutName,
+    /// A button or link with an empty accessible name, such as one containing only an
+    /// icon.
+    EmptyButtonOrLink,
+    /// A form control without an accessible name, such as an `input` without a `label`.
+    FormControlWithoutLabel,
+}
+
+impl fmt::Display for AxViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            AxViolationKind::ImageWithoutName => "image without a name",
+            AxViolationKind::EmptyButtonOrLink => "button or link with an empty name",
+            AxViolationKind::FormControlWithoutLabel => "form control without a label",
+        };
+        write!(f, "{text}")
+    }
+}
+
+/// A problem found by an [`AxAuditReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AxViolation {
+    /// The kind of problem.
+    pub kind: AxViolationKind,
+    /// The role of the node.
+    pub role: String,
+    /// The id of the node in the [`AxTree`].
+    pub node_id: String,
+    /// The id of the DOM node, which can be resolved to a `WebElement` with
+    /// `WebDriver::element_from_backend_node_id()`.
+    pub backend_node_id: Option<i64>,
+}
+
+impl fmt::Display for AxViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (role {}, node {})", self.kind, self.role, self.node_id)
+    }
+}
+
+/// The result of `WebDriver::basic_a11y_audit()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AxAuditReport {
+    /// The problems found, in the order of the [`AxTree`].
+    pub violations: Vec<AxViolation>,
+}
+
+impl AxAuditReport {
+    /// True if no problems were found.
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// The problems of the specified kind.
+    pub fn of_kind(&self, kind: AxViolationKind) -> Vec<&AxViolation> {
+        self.violations.iter().filter(|x| x.kind == kind).collect()
+    }
+}
+
+impl fmt::Display for AxAuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.violations.len() {
+            0 => return write!(f, "no accessibility violations"),
+            1 => write!(f, "1 accessibility violation:")?,
+            n => write!(f, "{n} accessibility violations:")?,
+        }
+        for violation in &self.violations {
+            write!(f, "\n- {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct RawTree {
+    nodes: Vec<RawNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawNode {
+    node_id: String,
+    #[serde(default)]
+    ignored: bool,
+    role: Option<RawValue>,
+    name: Option<RawValue>,
+    description: Option<RawValue>,
+    value: Option<RawValue>,
+    #[serde(default)]
+    properties: Vec<RawProperty>,
+    parent_id: Option<String>,
+    #[serde(default)]
+    child_ids: Vec<String>,
+    #[serde(rename = "backendDOMNodeId")]
+    backend_dom_node_id: Option<i64>,
+}
+
+/// A CDP `Accessibility.AXValue`.
+#[derive(Deserialize)]
+struct RawValue {
+    #[serde(default)]
+    value: Value,
+}
+
+impl RawValue {
+    fn text(value: Option<Self>) -> String {
+        match value.map(|x| x.value) {
+            Some(Value::String(text)) => text,
+            None | Some(Value::Null) => String::new(),
+            Some(value) => value.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawProperty {
+    name: String,
+    value: RawValue,
+}
+
+impl RawNode {
+    fn resolve(self) -> AxNode {
+        AxNode {
+            node_id: self.node_id,
+            ignored: self.ignored,
+            role: RawValue::text(self.role),
+            name: RawValue::text(self.name),
+            description: RawValue::text(self.description),
+            value: RawValue::text(self.value),
+            properties: self.properties.into_iter().map(|x| (x.name, x.value.value)).collect(),
+            parent_id: self.parent_id,
+            child_ids: self.child_ids,
+            backend_node_id: self.backend_dom_node_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn tree() -> AxTree {
+        let node = |id: &str, role: &str, name: &str, backend: i64| {
+            json!({
+                "nodeId": id,
+                "ignored": false,
+                "role": { "type": "role", "value": role },
+                "name": { "type": "computedString", "value": name },
+                "parentId": "1",
+                "childIds": [],
+                "backendDOMNodeId": backend,
+            })
+        };
+        AxTree::from_cdp(json!({
+            "nodes": [
+                {
+                    "nodeId": "1",
+                    "ignored": false,
+                    "role": { "type": "internalRole", "value": "RootWebArea" },
+                    "name": { "type": "computedString", "value": "Audit" },
+                    "properties": [{ "name": "focusable", "value": { "type": "booleanOrUndefined", "value": true } }],
+                    "childIds": ["2", "3", "4", "5", "6", "7", "8"],
+                    "backendDOMNodeId": 1,
+                },
+                node("2", "image", "", 12),
+                node("3", "button", "  ", 13),
+                node("4", "link", "Home", 14),
+                node("5", "textbox", "", 15),
+                node("6", "checkbox", "Subscribe", 16),
+                { "nodeId": "7", "ignored": true, "role": { "type": "role", "value": "none" } },
+                node("8", "button", "Save", 18),
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ax_tree() {
+        let tree = tree();
+        let root = tree.root().unwrap();
+        assert_eq!((root.role.as_str(), root.name.as_str()), ("RootWebArea", "Audit"));
+        assert_eq!(root.property("focusable"), Some(&json!(true)));
+        assert_eq!(tree.children(root).count(), 7);
+
+        let buttons = tree.find_by_role("button");
+        assert_eq!(buttons.len(), 2);
+        assert_eq!(tree.find_by_name("Save"), [buttons[1]]);
+        assert_eq!(tree.find_by_name("Home")[0].backend_node_id, Some(14));
+        assert!(tree.find_by_role("none").is_empty());
+        let ignored = tree.node("7").unwrap();
+        assert!(ignored.ignored && ignored.name.is_empty() && ignored.parent_id.is_none());
+    }
+
+    #[test]
+    fn test_basic_audit() {
+        let report = tree().basic_audit();
+        let found: Vec<_> = report.violations.iter().map(|x| (x.kind, x.backend_node_id)).collect();
+        assert_eq!(
+            found,
+            [
+                (AxViolationKind::ImageWithoutName, Some(12)),
+                (AxViolationKind::EmptyButtonOrLink, Some(13)),
+                (AxViolationKind::FormControlWithoutLabel, Some(15)),
+            ]
+        );
+        assert_eq!(report.of_kind(AxViolationKind::EmptyButtonOrLink).len(), 1);
+        assert_eq!(
+            report.to_string(),
+            "3 accessibility violations:\n\
+             - image without a name (role image, node 2)\n\
+             - button or link with an empty name (role button, node 3)\n\
+             - form control without a label (role textbox, node 5)"
+        );
+        assert_eq!(AxAuditReport::default().to_string(), "no accessibility violations");
+    }
+}
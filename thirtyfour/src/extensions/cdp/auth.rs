@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+/// An HTTP authentication challenge, passed to the handler set via
+/// `ChromeDevTools::set_auth_handler()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthChallenge {
+    /// The origin of the server that sent the challenge, for example
+    /// `https://example.com`.
+    pub origin: String,
+    /// The URL of the request that was challenged.
+    pub url: String,
+    /// The authentication scheme, for example `basic` or `digest`.
+    pub scheme: String,
+    /// The realm of the challenge.
+    pub realm: String,
+    /// True if the challenge came from a proxy rather than the server.
+    pub is_proxy: bool,
+}
+
+impl AuthChallenge {
+    /// Get the challenge from the parameters of a `Fetch.authRequired` event.
+    pub(crate) fn from_event(params: &Value) -> Self {
+        let challenge = &params["authChallenge"];
+        let get = |x: &Value| x.as_str().unwrap_or_default().to_string();
+        Self {
+            origin: get(&challenge["origin"]),
+            url: get(&params["request"]["url"]),
+            scheme: get(&challenge["scheme"]),
+            realm: get(&challenge["realm"]),
+            is_proxy: challenge["source"] == "Proxy",
+        }
+    }
+}
+
+/// The credentials to answer an [`AuthChallenge`] with.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    /// The username.
+    pub username: String,
+    /// The password.
+    pub password: String,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Credentials {
+    /// Create new `Credentials`.
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+}
+
+pub(crate) type AuthHandlerFn = Arc<dyn Fn(&AuthChallenge) -> Option<Credentials> + Send + Sync>;
+
+/// Build the response for the CDP `Fetch.continueWithAuth` command.
+///
+/// The challenge is cancelled if there are no credentials.
+pub(crate) fn auth_response(credentials: Option<Credentials>) -> Value {
+    match credentials {
+        Some(x) => json!({
+            "response": "ProvideCredentials",
+            "username": x.username,
+            "password": x.password,
+        }),
+        None => json!({ "response": "CancelAuth" }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_challenge() {
+        let params = json!({
+            "requestId": "interception-job-1.0",
+            "request": { "url": "http://localhost:8081/basic_auth", "method": "GET" },
+            "authChallenge": {
+                "source": "Server",
+                "origin": "http://localhost:8081",
+                "scheme": "basic",
+                "realm": "thirtyfour",
+            },
+        });
+        let challenge = AuthChallenge::from_event(&params);
+        assert_eq!(challenge.origin, "http://localhost:8081");
+        assert_eq!(challenge.url, "http://localhost:8081/basic_auth");
+        assert_eq!(challenge.scheme, "basic");
+        assert_eq!(challenge.realm, "thirtyfour");
+        assert!(!challenge.is_proxy);
+
+        let credentials = Credentials::new("user", "hunter2");
+        assert!(!format!("{credentials:?}").contains("hunter2"));
+    }
+}
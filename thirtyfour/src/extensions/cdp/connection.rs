@@ -0,0 +1,543 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+
+use futures_util::{SinkExt, Stream, StreamExt};
+use http::{Method, Request};
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::AbortHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::console::ConsoleStream;
+use super::coverage::JsCoverageOptions;
+use super::downloads::DownloadTracker;
+use super::exceptions::{check_exceptions, ExceptionStream};
+use super::fetch::FetchHandlers;
+use super::filechooser::FileChooserHandlers;
+use super::trace::TraceReceiver;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::session::handle::SessionHandle;
+use crate::session::http::Body;
+
+/// An event sent by the browser over a [`CdpConnection`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdpEvent {
+    /// The name of the event, for example `Runtime.consoleAPICalled`.
+    pub method: String,
+    /// The parameters of the event.
+    pub params: Value,
+    /// The CDP session the event was sent for, if it was not sent for the browser itself.
+    pub session_id: Option<String>,
+}
+
+/// A stream of the events subscribed to via [`CdpConnection::subscribe`].
+///
+/// Events are buffered until they are read, so a slow reader never holds up the
+/// connection. The stream ends when the connection is closed.
+#[derive(Debug)]
+pub struct CdpEventStream {
+    receiver: mpsc::UnboundedReceiver<CdpEvent>,
+}
+
+impl CdpEventStream {
+    /// Return the next buffered event without waiting, or `None` if there are no events
+    /// buffered.
+    pub fn try_next_event(&mut self) -> Option<CdpEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Stream for CdpEventStream {
+    type Item = CdpEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// A stream of the events of a [`CdpConnection`], parsed into a more specific type, such
+/// as `ConsoleMessage`.
+///
+/// Events are buffered until they are read, so a slow reader never holds up the
+/// connection. The stream ends when the connection is closed.
+pub struct EventStream<T> {
+    events: CdpEventStream,
+    parse: fn(&CdpEvent) -> Option<T>,
+}
+
+impl<T> Debug for EventStream<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventStream").field("events", &self.events).finish()
+    }
+}
+
+impl<T> EventStream<T> {
+    /// Create a stream of the events that `parse` returns a value for.
+    pub(crate) fn new(events: CdpEventStream, parse: fn(&CdpEvent) -> Option<T>) -> Self {
+        Self {
+            events,
+            parse,
+        }
+    }
+
+    /// Return the next buffered item without waiting, or `None` if there are no items
+    /// buffered.
+    pub fn try_next(&mut self) -> Option<T> {
+        while let Some(event) = self.events.try_next_event() {
+            if let Some(item) = (self.parse)(&event) {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+impl<T> Stream for EventStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.events).poll_next(cx) {
+                Poll::Ready(Some(event)) => {
+                    if let Some(item) = (self.parse)(&event) {
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+type PendingCommand = oneshot::Sender<WebDriverResult<Value>>;
+
+struct Subscriber {
+    /// The events to send to this subscriber. Empty for every event.
+    methods: Vec<String>,
+    sender: mpsc::UnboundedSender<CdpEvent>,
+}
+
+/// The state shared with the thread that reads from the websocket.
+#[derive(Default)]
+struct Shared {
+    /// The commands waiting for a response, by id. `None` once the connection is closed.
+    pending: Mutex<Option<HashMap<u64, PendingCommand>>>,
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Shared {
+    /// Handle a message received from the browser.
+    fn dispatch(&self, text: &str) {
+        let Ok(mut message) = serde_json::from_str::<Value>(text) else {
+            tracing::warn!("ignoring invalid DevTools message: {text}");
+            return;
+        };
+        if let Some(id) = message.get("id").and_then(Value::as_u64) {
+            let Some(sender) = lock(&self.pending).as_mut().and_then(|x| x.remove(&id)) else {
+                return;
+            };
+            let result = match message.get("error") {
+                Some(error) => {
+                    let message = error["message"].as_str().unwrap_or("unknown error");
+                    Err(WebDriverError::UnknownError(WebDriverErrorInfo::new(format!(
+                        "CDP command failed: {message}"
+                    ))))
+                }
+                None => Ok(message["result"].take()),
+            };
+            let _ = sender.send(result);
+        } else if let Some(method) = message.get("method").and_then(Value::as_str) {
+            let event = CdpEvent {
+                method: method.to_string(),
+                session_id: message.get("sessionId").and_then(Value::as_str).map(String::from),
+                params: message["params"].take(),
+            };
+            // Drop the subscribers whose streams have been dropped.
+            lock(&self.subscribers).retain(|x| {
+                if !x.methods.is_empty() && !x.methods.contains(&event.method) {
+                    return !x.sender.is_closed();
+                }
+                x.sender.send(event.clone()).is_ok()
+            });
+        }
+    }
+
+    /// Fail every pending command and end every event stream.
+    fn close(&self) {
+        if let Some(pending) = lock(&self.pending).take() {
+            for (_, sender) in pending {
+                let _ = sender.send(Err(closed_error()));
+            }
+        }
+        lock(&self.subscribers).clear();
+    }
+}
+
+fn closed_error() -> WebDriverError {
+    WebDriverError::CommandRecvError("the DevTools connection was closed".to_string())
+}
+
+/// A websocket connection to the browser's DevTools.
+///
+/// Unlike `ChromeDevTools::execute_cdp()`, which sends each command via the WebDriver
+/// server, this connects to the browser directly, so that CDP events can be received.
+/// Use `ChromeDevTools::connection()` to get the connection for a session.
+///
+/// The connection is attached to the window that was current when it was opened.
+/// Commands sent with [`CdpConnection::send`] apply to that window, and events from that
+/// window are received.
+///
+/// The DevTools address is taken from the `se:cdp`, `debuggerAddress` or
+/// `moz:debuggerAddress` capabilities returned when the session was created, so the
+/// browser must be reachable from this machine at that address. Only `ws://` connections
+/// are supported.
+///
+/// Firefox only implements part of CDP, and commands it does not implement fail with the
+/// error it reports.
+pub struct CdpConnection {
+    outgoing: mpsc::UnboundedSender<String>,
+    shared: Arc<Shared>,
+    next_id: AtomicU64,
+    /// The CDP session for the attached window.
+    session_id: String,
+    /// The runtime that reads from the websocket, for tasks that handle events.
+    runtime: tokio::runtime::Handle,
+    /// The handlers for the requests paused by the `Fetch` domain.
+    pub(crate) fetch: FetchHandlers,
+    /// The console messages recorded for `assert_no_console_errors()`, once started.
+    pub(crate) console: tokio::sync::Mutex<Option<ConsoleStream>>,
+    /// The downloads recorded for `wait_for_download_complete()`, once download events
+    /// are enabled.
+    pub(crate) downloads: tokio::sync::Mutex<Option<DownloadTracker>>,
+    /// The exceptions recorded for `fail_on_js_exception()`, while enabled.
+    pub(crate) exceptions: Mutex<Option<ExceptionStream>>,
+    /// The handler set via `ChromeDevTools::set_file_chooser_handler()`.
+    pub(crate) file_chooser: FileChooserHandlers,
+    /// The options passed to `start_js_coverage()`, while JS coverage is running.
+    pub(crate) js_coverage: Mutex<Option<JsCoverageOptions>>,
+    /// The `CSS.styleSheetAdded` events, while CSS coverage is running.
+    pub(crate) style_sheets: Mutex<Option<CdpEventStream>>,
+    /// The trace being recorded, while tracing is running.
+    pub(crate) trace: Mutex<Option<TraceReceiver>>,
+}
+
+impl Debug for CdpConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CdpConnection").field("session_id", &self.session_id).finish()
+    }
+}
+
+impl CdpConnection {
+    /// Connect to the DevTools of the browser for the specified session, and attach to
+    /// its current window.
+    pub(crate) async fn connect(handle: &SessionHandle) -> WebDriverResult<Self> {
+        let url = websocket_url(handle).await?;
+        let window = handle.window().await?.to_string();
+        let firefox_url = match handle.driver_info().browser_name.as_str() {
+            "firefox" => Some(handle.current_url().await?.to_string()),
+            _ => None,
+        };
+
+        let shared = Arc::new(Shared::default());
+        *lock(&shared.pending) = Some(HashMap::new());
+        let (outgoing, runtime) = spawn_reader(url, shared.clone()).await?;
+        let mut connection = Self {
+            outgoing,
+            shared,
+            next_id: AtomicU64::new(1),
+            session_id: String::new(),
+            runtime,
+            fetch: FetchHandlers::default(),
+            console: tokio::sync::Mutex::new(None),
+            downloads: tokio::sync::Mutex::new(None),
+            exceptions: Mutex::new(None),
+            file_chooser: FileChooserHandlers::default(),
+            js_coverage: Mutex::new(None),
+            style_sheets: Mutex::new(None),
+            trace: Mutex::new(None),
+        };
+        let target_id = match firefox_url {
+            Some(url) => {
+                let targets = connection.send_to_browser("Target.getTargets", json!({})).await?;
+                firefox_target_id(&targets, &url).ok_or_else(|| {
+                    WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(format!(
+                        "Firefox did not report a DevTools target for {url}"
+                    )))
+                })?
+            }
+            // Older versions of chromedriver prefix the target id.
+            None => window.strip_prefix("CDwindow-").unwrap_or(&window).to_string(),
+        };
+        let attached = connection
+            .send_to_session(
+                None,
+                "Target.attachToTarget",
+                json!({ "targetId": target_id, "flatten": true }),
+            )
+            .await?;
+        connection.session_id = attached["sessionId"]
+            .as_str()
+            .ok_or_else(|| {
+                WebDriverError::ParseError("Target.attachToTarget: missing sessionId".to_string())
+            })?
+            .to_string();
+        Ok(connection)
+    }
+
+    /// The CDP session for the window this connection is attached to.
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Send the specified command to the attached window and return the result.
+    pub async fn send(&self, method: &str, params: Value) -> WebDriverResult<Value> {
+        self.send_to_session(Some(&self.session_id), method, params).await
+    }
+
+    /// Send the specified command to the browser itself, rather than to a window.
+    pub async fn send_to_browser(&self, method: &str, params: Value) -> WebDriverResult<Value> {
+        self.send_to_session(None, method, params).await
+    }
+
+    /// Send the specified command to the specified CDP session, such as the one an event
+    /// was received from.
+    pub async fn send_to_session(
+        &self,
+        session_id: Option<&str>,
+        method: &str,
+        params: Value,
+    ) -> WebDriverResult<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut message = json!({ "id": id, "method": method, "params": params });
+        if let Some(session_id) = session_id {
+            message["sessionId"] = json!(session_id);
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        match lock(&self.shared.pending).as_mut() {
+            Some(pending) => pending.insert(id, sender),
+            None => return Err(closed_error()),
+        };
+        if self.outgoing.send(message.to_string()).is_err() {
+            lock(&self.shared.pending).as_mut().and_then(|x| x.remove(&id));
+            return Err(closed_error());
+        }
+        receiver.await.unwrap_or_else(|_| Err(closed_error()))
+    }
+
+    /// Subscribe to the events with the specified names, for example
+    /// `Runtime.consoleAPICalled`. If no names are specified, every event is received.
+    ///
+    /// Most events are only sent once the corresponding domain is enabled, for example
+    /// with `Runtime.enable`. Events are received from the time this is called, until the
+    /// stream is dropped.
+    pub fn subscribe(&self, methods: &[&str]) -> CdpEventStream {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        lock(&self.shared.subscribers).push(Subscriber {
+            methods: methods.iter().map(ToString::to_string).collect(),
+            sender,
+        });
+        CdpEventStream {
+            receiver,
+        }
+    }
+
+    /// Return an error listing the exceptions recorded for `fail_on_js_exception()` since
+    /// this was last called, if there are any.
+    pub(crate) fn take_exceptions(&self) -> WebDriverResult<()> {
+        let mut recorded = lock(&self.exceptions);
+        let Some(exceptions) = recorded.as_mut() else {
+            return Ok(());
+        };
+        let exceptions: Vec<_> = std::iter::from_fn(|| exceptions.try_next()).collect();
+        check_exceptions(&exceptions)
+    }
+
+    /// Run the specified task on the thread that reads from the websocket, until it
+    /// finishes, is aborted, or the connection is closed.
+    pub(crate) fn spawn<F>(&self, task: F) -> AbortHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.runtime.spawn(task).abort_handle()
+    }
+}
+
+/// Get the websocket URL for the browser's DevTools.
+async fn websocket_url(handle: &SessionHandle) -> WebDriverResult<String> {
+    let Some(address) = handle.driver_info().devtools_address.as_deref() else {
+        return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+            "the browser did not report a DevTools address".to_string(),
+        )));
+    };
+    if address.starts_with("ws://") || address.starts_with("wss://") {
+        return Ok(address.to_string());
+    }
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri(format!("http://{address}/json/version"))
+        .body(Body::Empty)
+        .map_err(|e| WebDriverError::RequestFailed(format!("invalid DevTools address: {e}")))?;
+    let response = handle.client.send(request).await?;
+    let version: Value = serde_json::from_slice(response.body())?;
+    match version["webSocketDebuggerUrl"].as_str() {
+        Some(url) => Ok(url.to_string()),
+        None => Err(WebDriverError::ParseError(format!(
+            "DevTools at {address} did not report a websocket URL"
+        ))),
+    }
+}
+
+/// Find the target for the current window of a Firefox session in the result of
+/// `Target.getTargets`.
+///
+/// Firefox window handles are not DevTools target ids, so this picks the page showing
+/// the current URL, or the only page if there is just one.
+fn firefox_target_id(targets: &Value, url: &str) -> Option<String> {
+    let pages: Vec<&Value> = targets["targetInfos"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|x| x["type"] == "page")
+        .collect();
+    let page = match pages.iter().find(|x| x["url"] == url) {
+        Some(page) => page,
+        None if pages.len() == 1 => pages[0],
+        None => return None,
+    };
+    page["targetId"].as_str().map(String::from)
+}
+
+/// Connect to the websocket on a new thread, which then forwards messages in both
+/// directions until the connection is closed.
+///
+/// The websocket gets its own runtime so that it keeps running regardless of the
+/// runtime the caller uses.
+async fn spawn_reader(
+    url: String,
+    shared: Arc<Shared>,
+) -> WebDriverResult<(mpsc::UnboundedSender<String>, tokio::runtime::Handle)> {
+    let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+    let (ready, ready_rx) = oneshot::channel();
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let handle = runtime.handle().clone();
+    std::thread::Builder::new().name("thirtyfour-cdp".to_string()).spawn(move || {
+        runtime.block_on(async move {
+            let mut socket = match tokio_tungstenite::connect_async(&url).await {
+                Ok((socket, _)) => {
+                    let _ = ready.send(Ok(()));
+                    socket
+                }
+                Err(e) => {
+                    let _ = ready.send(Err(WebDriverError::RequestFailed(format!(
+                        "failed to connect to DevTools at {url}: {e}"
+                    ))));
+                    return;
+                }
+            };
+            loop {
+                tokio::select! {
+                    message = outgoing_rx.recv() => {
+                        let Some(message) = message else { break };
+                        if let Err(e) = socket.send(Message::Text(message)).await {
+                            tracing::debug!("DevTools connection failed: {e}");
+                            break;
+                        }
+                    }
+                    message = socket.next() => match message {
+                        Some(Ok(Message::Text(text))) => shared.dispatch(&text),
+                        Some(Ok(Message::Close(_))) | None => break,
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            tracing::debug!("DevTools connection failed: {e}");
+                            break;
+                        }
+                    }
+                }
+            }
+            shared.close();
+        });
+    })?;
+    ready_rx.await.unwrap_or_else(|_| Err(closed_error()))?;
+    Ok((outgoing, handle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_firefox_target_id() {
+        let page = |id: &str, url: &str| json!({ "targetId": id, "type": "page", "url": url });
+        let targets = json!({
+            "targetInfos": [
+                { "targetId": "b", "type": "browser", "url": "" },
+                page("1", "http://localhost/one.html"),
+                page("2", "http://localhost/two.html"),
+            ]
+        });
+        assert_eq!(firefox_target_id(&targets, "http://localhost/two.html").as_deref(), Some("2"));
+        assert_eq!(firefox_target_id(&targets, "about:blank"), None);
+
+        let targets = json!({ "targetInfos": [page("1", "about:blank")] });
+        assert_eq!(firefox_target_id(&targets, "http://localhost/").as_deref(), Some("1"));
+        assert_eq!(firefox_target_id(&json!({}), "about:blank"), None);
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let shared = Shared::default();
+        *lock(&shared.pending) = Some(HashMap::new());
+        let (ok, mut ok_rx) = oneshot::channel();
+        let (err, mut err_rx) = oneshot::channel();
+        lock(&shared.pending).as_mut().unwrap().extend([(1, ok), (2, err)]);
+
+        let (sender, mut all) = mpsc::unbounded_channel();
+        lock(&shared.subscribers).push(Subscriber {
+            methods: Vec::new(),
+            sender,
+        });
+        let (sender, mut logs) = mpsc::unbounded_channel();
+        lock(&shared.subscribers).push(Subscriber {
+            methods: vec!["Log.entryAdded".to_string()],
+            sender,
+        });
+
+        shared.dispatch(r#"{"id":1,"result":{"value":42}}"#);
+        shared.dispatch(r#"{"id":2,"error":{"code":-32601,"message":"'Foo.bar' wasn't found"}}"#);
+        shared.dispatch(r#"{"method":"Page.loadEventFired","params":{"timestamp":1.5}}"#);
+        shared.dispatch(r#"{"method":"Log.entryAdded","params":{},"sessionId":"abc"}"#);
+        assert_eq!(ok_rx.try_recv().unwrap().unwrap(), json!({ "value": 42 }));
+        let err = err_rx.try_recv().unwrap().unwrap_err();
+        assert!(err.to_string().contains("'Foo.bar' wasn't found"), "{err}");
+
+        assert_eq!(all.try_recv().unwrap().method, "Page.loadEventFired");
+        assert_eq!(all.try_recv().unwrap().session_id.as_deref(), Some("abc"));
+        let log = logs.try_recv().unwrap();
+        assert_eq!(log.method, "Log.entryAdded");
+        assert!(logs.try_recv().is_err());
+
+        // Dropped streams are unsubscribed.
+        drop(all);
+        shared.dispatch(r#"{"method":"Page.loadEventFired","params":{}}"#);
+        assert_eq!(lock(&shared.subscribers).len(), 1);
+
+        let (pending, mut pending_rx) = oneshot::channel();
+        lock(&shared.pending).as_mut().unwrap().insert(3, pending);
+        shared.close();
+        assert!(pending_rx.try_recv().unwrap().is_err());
+        assert!(logs.blocking_recv().is_none());
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+
+use super::connection::{CdpEvent, EventStream};
+
+/// The level of a [`ConsoleMessage`], from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConsoleLevel {
+    /// `console.debug()`, or a verbose browser message.
+    Debug,
+    /// `console.log()`, `console.info()` and similar.
+    Info,
+    /// `console.warn()`.
+    Warning,
+    /// `console.error()`, a failed `console.assert()`, or a browser error such as a
+    /// failed request.
+    Error,
+}
+
+impl ConsoleLevel {
+    fn from_cdp(level: &str) -> Self {
+        match level {
+            "debug" | "verbose" => ConsoleLevel::Debug,
+            "warning" => ConsoleLevel::Warning,
+            "error" | "assert" => ConsoleLevel::Error,
+            _ => ConsoleLevel::Info,
+        }
+    }
+}
+
+impl Display for ConsoleLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ConsoleLevel::Debug => "debug",
+            ConsoleLevel::Info => "info",
+            ConsoleLevel::Warning => "warning",
+            ConsoleLevel::Error => "error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A message written to the browser console, either by the page via the `console` API or
+/// by the browser itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsoleMessage {
+    /// The level of the message.
+    pub level: ConsoleLevel,
+    /// The text of the message. For `console` calls, this is the arguments separated by
+    /// spaces.
+    pub text: String,
+    /// The arguments of the `console` call. Arguments that cannot be represented as JSON,
+    /// such as objects and functions, are replaced by their description, for example
+    /// `"Object"`. This is empty for browser messages.
+    pub args: Vec<Value>,
+    /// The URL of the script or resource the message came from, if known.
+    pub url: Option<String>,
+    /// The one-based line number in `url`, if known.
+    pub line: Option<u32>,
+    /// When the message was written.
+    pub timestamp: SystemTime,
+}
+
+impl ConsoleMessage {
+    /// Get the message from a `Runtime.consoleAPICalled` or `Log.entryAdded` event.
+    ///
+    /// Returns `None` for other events, and for browser log entries that duplicate
+    /// `console` calls.
+    pub(crate) fn from_event(event: &CdpEvent) -> Option<Self> {
+        let params = &event.params;
+        let timestamp = |x: &Value| {
+            let millis = x.as_f64().filter(|x| x.is_finite() && *x >= 0.0).unwrap_or_default();
+            SystemTime::UNIX_EPOCH + Duration::from_secs_f64(millis / 1000.0)
+        };
+        let line = |x: &Value| x.as_u64().and_then(|x| u32::try_from(x + 1).ok());
+        let url = |x: &Value| x.as_str().filter(|x| !x.is_empty()).map(String::from);
+
+        match event.method.as_str() {
+            "Runtime.consoleAPICalled" => {
+                let args: Vec<Value> = params["args"]
+                    .as_array()
+                    .map(|args| args.iter().map(remote_object_value).collect())
+                    .unwrap_or_default();
+                let text = args
+                    .iter()
+                    .map(|x| match x {
+                        Value::String(x) => x.clone(),
+                        x => x.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let frame = &params["stackTrace"]["callFrames"][0];
+                Some(Self {
+                    level: ConsoleLevel::from_cdp(params["type"].as_str().unwrap_or_default()),
+                    text,
+                    args,
+                    url: url(&frame["url"]),
+                    line: line(&frame["lineNumber"]),
+                    timestamp: timestamp(&params["timestamp"]),
+                })
+            }
+            "Log.entryAdded" => {
+                let entry = &params["entry"];
+                if entry["source"] == "console-api" {
+                    return None;
+                }
+                Some(Self {
+                    level: ConsoleLevel::from_cdp(entry["level"].as_str().unwrap_or_default()),
+                    text: entry["text"].as_str().unwrap_or_default().to_string(),
+                    args: Vec::new(),
+                    url: url(&entry["url"]),
+                    line: line(&entry["lineNumber"]),
+                    timestamp: timestamp(&entry["timestamp"]),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Get the value of a CDP `Runtime.RemoteObject`, or its description if it has no JSON
+/// value.
+fn remote_object_value(object: &Value) -> Value {
+    if let Some(value) = object.get("value") {
+        return value.clone();
+    }
+    match object.get("unserializableValue").or_else(|| object.get("description")) {
+        Some(description) => description.clone(),
+        None => Value::String(object["type"].as_str().unwrap_or_default().to_string()),
+    }
+}
+
+impl Display for ConsoleMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.level, self.text)?;
+        match (&self.url, self.line) {
+            (Some(url), Some(line)) => write!(f, " ({url}:{line})"),
+            (Some(url), None) => write!(f, " ({url})"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A stream of the messages written to the browser console, returned by
+/// `ChromeDevTools::subscribe_console()`.
+pub type ConsoleStream = EventStream<ConsoleMessage>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(method: &str, params: Value) -> CdpEvent {
+        CdpEvent {
+            method: method.to_string(),
+            params,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn test_console_message() {
+        let message = ConsoleMessage::from_event(&event(
+            "Runtime.consoleAPICalled",
+            json!({
+                "type": "error",
+                "args": [
+                    { "type": "string", "value": "failed:" },
+                    { "type": "number", "value": 42, "description": "42" },
+                    { "type": "number", "unserializableValue": "NaN", "description": "NaN" },
+                    { "type": "object", "className": "Object", "description": "Object" },
+                ],
+                "timestamp": 1700000000123.5,
+                "stackTrace": {
+                    "callFrames": [{ "url": "http://localhost/app.js", "lineNumber": 9 }],
+                },
+            }),
+        ))
+        .unwrap();
+        assert_eq!(message.level, ConsoleLevel::Error);
+        assert_eq!(message.text, "failed: 42 NaN Object");
+        assert_eq!(message.args, vec![json!("failed:"), json!(42), json!("NaN"), json!("Object")]);
+        assert_eq!(
+            message.to_string(),
+            "[error] failed: 42 NaN Object (http://localhost/app.js:10)"
+        );
+        assert_eq!(
+            message.timestamp.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis(),
+            1700000000123
+        );
+
+        let message = ConsoleMessage::from_event(&event(
+            "Log.entryAdded",
+            json!({
+                "entry": {
+                    "source": "network",
+                    "level": "error",
+                    "text": "Failed to load resource: 404 (Not Found)",
+                    "url": "http://localhost/missing.png",
+                    "timestamp": 1700000000000.0,
+                },
+            }),
+        ))
+        .unwrap();
+        assert_eq!(message.level, ConsoleLevel::Error);
+        assert_eq!(message.line, None);
+        assert!(message.args.is_empty());
+
+        let duplicate = event(
+            "Log.entryAdded",
+            json!({ "entry": { "source": "console-api", "level": "info", "text": "x" } }),
+        );
+        assert_eq!(ConsoleMessage::from_event(&duplicate), None);
+        assert_eq!(ConsoleMessage::from_event(&event("Page.loadEventFired", json!({}))), None);
+        assert!(ConsoleLevel::Warning < ConsoleLevel::Error);
+    }
+}
@@ -0,0 +1,317 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Map, Value};
+use url::Url;
+
+use crate::error::WebDriverResult;
+use crate::{Cookie, SameSite};
+
+/// The priority of a [`CdpCookie`], used by the browser to decide which cookies to evict
+/// first when a domain has too many.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CookiePriority {
+    /// Evicted first.
+    Low,
+    /// The default.
+    Medium,
+    /// Evicted last.
+    High,
+}
+
+/// The scheme of the page that set a [`CdpCookie`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CookieSourceScheme {
+    /// Unknown, for example because the cookie was set before the browser recorded this.
+    Unset,
+    /// Set from an `http` page.
+    NonSecure,
+    /// Set from an `https` page.
+    Secure,
+}
+
+/// The partition of a partitioned (CHIPS) [`CdpCookie`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookiePartitionKey {
+    /// The top-level site the cookie is partitioned under, e.g. `https://example.com`.
+    pub top_level_site: String,
+    /// True if the cookie was set in a frame with a cross-site ancestor.
+    #[serde(default)]
+    pub has_cross_site_ancestor: bool,
+}
+
+/// A cookie as used by the CDP `Network.getCookies` and `Network.setCookie` commands,
+/// including the fields that the WebDriver cookie commands do not support, such as
+/// `http_only` and `priority`.
+///
+/// Converts to and from [`Cookie`], keeping the fields that both have.
+///
+/// See <https://chromedevtools.github.io/devtools-protocol/tot/Network/#type-Cookie>.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CdpCookie {
+    /// The name of the cookie.
+    pub name: String,
+    /// The value of the cookie.
+    pub value: String,
+    /// The domain of the cookie. When setting a cookie without a domain, it is scoped to
+    /// the current page.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub domain: String,
+    /// The path of the cookie.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub path: String,
+    /// The expiry time, in seconds since the Unix epoch, or `None` for a session cookie.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_expires",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expires: Option<f64>,
+    /// Whether the cookie is hidden from `document.cookie`.
+    #[serde(default)]
+    pub http_only: bool,
+    /// Whether the cookie is only sent over secure connections.
+    #[serde(default)]
+    pub secure: bool,
+    /// The sameSite attribute of the cookie.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<SameSite>,
+    /// The priority of the cookie.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<CookiePriority>,
+    /// The scheme of the page that set the cookie.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_scheme: Option<CookieSourceScheme>,
+    /// The port of the page that set the cookie, or -1 if unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_port: Option<i32>,
+    /// The partition of the cookie, if it is partitioned (CHIPS).
+    #[serde(
+        default,
+        deserialize_with = "deserialize_partition_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub partition_key: Option<CookiePartitionKey>,
+}
+
+impl CdpCookie {
+    /// Create a new `CdpCookie` with the specified name and value, scoped to the current
+    /// page.
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            domain: String::new(),
+            path: String::new(),
+            expires: None,
+            http_only: false,
+            secure: false,
+            same_site: None,
+            priority: None,
+            source_scheme: None,
+            source_port: None,
+            partition_key: None,
+        }
+    }
+
+    /// Build the parameters for the CDP `Network.setCookie` command.
+    ///
+    /// The `url` is used to scope the cookie if it does not specify a domain.
+    pub(crate) fn to_set_params(&self, url: &Url) -> WebDriverResult<Value> {
+        let mut params = serde_json::to_value(self)?;
+        if self.domain.is_empty() {
+            params["url"] = json!(url.as_str());
+        }
+        Ok(params)
+    }
+}
+
+/// Session cookies are reported with an expiry of -1.
+fn deserialize_expires<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let expires = Option::<f64>::deserialize(deserializer)?;
+    Ok(expires.filter(|x| *x >= 0.0))
+}
+
+/// Older versions of Chrome report the partition key as just the top-level site.
+fn deserialize_partition_key<'de, D>(
+    deserializer: D,
+) -> Result<Option<CookiePartitionKey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(site)) => Some(CookiePartitionKey {
+            top_level_site: site,
+            has_cross_site_ancestor: false,
+        }),
+        Some(key @ Value::Object(_)) => {
+            Some(serde_json::from_value(key).map_err(serde::de::Error::custom)?)
+        }
+        _ => None,
+    })
+}
+
+impl From<CdpCookie> for Cookie {
+    fn from(c: CdpCookie) -> Self {
+        let mut cookie = Cookie::new(c.name, c.value);
+        cookie.set_domain(c.domain);
+        cookie.set_path(c.path);
+        cookie.set_secure(c.secure);
+        if let Some(expires) = c.expires {
+            cookie.set_expiry(expires as i64);
+        }
+        cookie.same_site = c.same_site;
+        if let Some(partition_key) = c.partition_key {
+            cookie.set_partition_key(partition_key.top_level_site);
+        }
+        cookie
+    }
+}
+
+impl From<Cookie> for CdpCookie {
+    fn from(c: Cookie) -> Self {
+        let partition_key = c.partition_key.map(|site| CookiePartitionKey {
+            top_level_site: site,
+            has_cross_site_ancestor: false,
+        });
+        Self {
+            domain: c.domain.unwrap_or_default(),
+            path: c.path.unwrap_or_default(),
+            expires: c.expiry.map(|x| x as f64),
+            secure: c.secure.unwrap_or_default(),
+            same_site: c.same_site,
+            partition_key,
+            ..CdpCookie::new(c.name, c.value)
+        }
+    }
+}
+
+/// Build the parameters for the CDP `Network.setCookie` command.
+///
+/// The `url` is used to scope the cookie if it does not specify a domain.
+pub(crate) fn set_cookie_params(cookie: &Cookie, url: &Url) -> Value {
+    let mut params = Map::new();
+    params.insert("name".to_string(), json!(cookie.name));
+    params.insert("value".to_string(), json!(cookie.value));
+    match &cookie.domain {
+        Some(domain) => {
+            params.insert("domain".to_string(), json!(domain));
+        }
+        None => {
+            params.insert("url".to_string(), json!(url.as_str()));
+        }
+    }
+    if let Some(path) = &cookie.path {
+        params.insert("path".to_string(), json!(path));
+    }
+    if let Some(secure) = cookie.secure {
+        params.insert("secure".to_string(), json!(secure));
+    }
+    if let Some(expiry) = cookie.expiry {
+        params.insert("expires".to_string(), json!(expiry));
+    }
+    if let Some(same_site) = cookie.same_site {
+        params.insert("sameSite".to_string(), json!(same_site));
+    }
+    if let Some(partition_key) = &cookie.partition_key {
+        params.insert(
+            "partitionKey".to_string(),
+            json!({ "topLevelSite": partition_key, "hasCrossSiteAncestor": false }),
+        );
+    }
+    Value::Object(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_cookie_params() {
+        let url = Url::parse("https://example.com/page").unwrap();
+        let mut cookie = Cookie::new("name", "value");
+        assert_eq!(
+            set_cookie_params(&cookie, &url),
+            json!({ "name": "name", "value": "value", "url": "https://example.com/page" })
+        );
+
+        cookie.set_domain("example.com");
+        cookie.set_path("/");
+        cookie.set_secure(true);
+        cookie.set_same_site(SameSite::None);
+        cookie.set_partition_key("https://top.example");
+        assert_eq!(
+            set_cookie_params(&cookie, &url),
+            json!({
+                "name": "name",
+                "value": "value",
+                "domain": "example.com",
+                "path": "/",
+                "secure": true,
+                "sameSite": "None",
+                "partitionKey": { "topLevelSite": "https://top.example", "hasCrossSiteAncestor": false }
+            })
+        );
+    }
+
+    #[test]
+    fn test_cdp_cookie() {
+        let c: CdpCookie = serde_json::from_value(json!({
+            "name": "name",
+            "value": "value",
+            "domain": "example.com",
+            "path": "/",
+            "expires": -1,
+            "size": 9,
+            "httpOnly": false,
+            "secure": true,
+            "session": true,
+            "sameSite": "None",
+            "partitionKey": { "topLevelSite": "https://top.example", "hasCrossSiteAncestor": false }
+        }))
+        .unwrap();
+        assert_eq!(c.expires, None);
+        let cookie = Cookie::from(c.clone());
+        assert_eq!(cookie.domain.as_deref(), Some("example.com"));
+        assert_eq!(cookie.expiry, None);
+        assert_eq!(cookie.partition_key.as_deref(), Some("https://top.example"));
+        assert_eq!(CdpCookie::from(cookie), c);
+    }
+
+    #[test]
+    fn test_cdp_cookie_set_params() {
+        let url = Url::parse("https://example.com/page").unwrap();
+        let mut cookie = CdpCookie::new("token", "abc");
+        cookie.http_only = true;
+        cookie.priority = Some(CookiePriority::High);
+        cookie.source_scheme = Some(CookieSourceScheme::Secure);
+        assert_eq!(
+            cookie.to_set_params(&url).unwrap(),
+            json!({
+                "name": "token",
+                "value": "abc",
+                "httpOnly": true,
+                "secure": false,
+                "priority": "High",
+                "sourceScheme": "Secure",
+                "url": "https://example.com/page"
+            })
+        );
+
+        let legacy: CdpCookie = serde_json::from_value(json!({
+            "name": "name",
+            "value": "value",
+            "expires": 1700000000.5,
+            "partitionKey": "https://top.example"
+        }))
+        .unwrap();
+        assert_eq!(legacy.expires, Some(1700000000.5));
+        assert_eq!(
+            legacy.partition_key.map(|x| x.top_level_site).as_deref(),
+            Some("https://top.example")
+        );
+    }
+}
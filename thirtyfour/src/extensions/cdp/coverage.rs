@@ -0,0 +1,421 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Options for `ChromeDevTools::start_js_coverage()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsCoverageOptions {
+    /// Count how many times each range ran, rather than just whether it ran.
+    pub call_count: bool,
+    /// Report coverage for each block within a function, such as the branches of an
+    /// `if` statement, rather than just for each function.
+    pub detailed: bool,
+    /// Include the source of each script in the results, which is needed to convert
+    /// offsets to line numbers.
+    pub include_source: bool,
+}
+
+impl Default for JsCoverageOptions {
+    fn default() -> Self {
+        Self {
+            call_count: true,
+            detailed: true,
+            include_source: false,
+        }
+    }
+}
+
+impl JsCoverageOptions {
+    /// Create new `JsCoverageOptions` with call counts and block coverage, and without
+    /// script sources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to count how many times each range ran.
+    pub fn with_call_count(mut self, call_count: bool) -> Self {
+        self.call_count = call_count;
+        self
+    }
+
+    /// Set whether to report coverage for each block within a function.
+    pub fn with_detailed(mut self, detailed: bool) -> Self {
+        self.detailed = detailed;
+        self
+    }
+
+    /// Set whether to include the source of each script in the results.
+    pub fn with_include_source(mut self, include_source: bool) -> Self {
+        self.include_source = include_source;
+        self
+    }
+
+    /// Build the parameters for the CDP `Profiler.startPreciseCoverage` command.
+    pub(crate) fn to_params(&self) -> Value {
+        json!({ "callCount": self.call_count, "detailed": self.detailed })
+    }
+}
+
+/// A range of a script and how many times it ran.
+///
+/// Offsets are in UTF-16 code units from the start of the script source, and the end
+/// is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageRange {
+    /// The offset of the start of the range.
+    pub start_offset: usize,
+    /// The offset of the end of the range.
+    pub end_offset: usize,
+    /// How many times the range ran. Without call counts, this is 1 if it ran at all.
+    pub count: u64,
+}
+
+impl CoverageRange {
+    fn contains(&self, offset: usize) -> bool {
+        self.start_offset <= offset && offset < self.end_offset
+    }
+}
+
+/// The coverage of a function.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCoverage {
+    /// The name of the function, or an empty string for anonymous functions and the
+    /// top level of the script.
+    pub function_name: String,
+    /// The ranges of the function. The first range covers the whole function, and any
+    /// other ranges are blocks within it that ran a different number of times.
+    pub ranges: Vec<CoverageRange>,
+    /// True if there are ranges for the blocks within the function.
+    pub is_block_coverage: bool,
+}
+
+impl FunctionCoverage {
+    /// How many times the function was called.
+    pub fn count(&self) -> u64 {
+        self.ranges.first().map_or(0, |x| x.count)
+    }
+}
+
+/// The coverage of a script, returned by `ChromeDevTools::stop_js_coverage()`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptCoverage {
+    /// The id of the script.
+    pub script_id: String,
+    /// The URL of the script. For inline scripts, this is the URL of the page.
+    pub url: String,
+    /// The coverage of each function in the script.
+    pub functions: Vec<FunctionCoverage>,
+    /// The source of the script, if `JsCoverageOptions::include_source` was set.
+    #[serde(skip)]
+    pub source: Option<String>,
+}
+
+impl ScriptCoverage {
+    /// How many times the code at the specified offset ran, or `None` if the offset is
+    /// not in any function, for example because the script ran before coverage started.
+    ///
+    /// The offset is in UTF-16 code units. See [`CoverageRange`].
+    pub fn count_at(&self, offset: usize) -> Option<u64> {
+        // The innermost range containing the offset decides the count.
+        self.functions
+            .iter()
+            .flat_map(|x| &x.ranges)
+            .filter(|x| x.contains(offset))
+            .min_by_key(|x| x.end_offset - x.start_offset)
+            .map(|x| x.count)
+    }
+
+    /// Return true if the code at the specified offset ran.
+    pub fn is_covered(&self, offset: usize) -> bool {
+        self.count_at(offset).is_some_and(|x| x > 0)
+    }
+
+    /// The coverage of the function with the specified name, if there is one.
+    pub fn function(&self, name: &str) -> Option<&FunctionCoverage> {
+        self.functions.iter().find(|x| x.function_name == name)
+    }
+}
+
+/// A rule of a stylesheet, and whether it was used.
+///
+/// Offsets are in UTF-16 code units from the start of the stylesheet text, and the end
+/// is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CssRuleUsage {
+    /// The offset of the start of the rule.
+    pub start_offset: usize,
+    /// The offset of the end of the rule.
+    pub end_offset: usize,
+    /// True if the rule matched any element while coverage was being collected.
+    pub used: bool,
+}
+
+/// The coverage of a stylesheet, returned by `ChromeDevTools::stop_css_coverage()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CssCoverage {
+    /// The id of the stylesheet.
+    pub style_sheet_id: String,
+    /// The URL of the stylesheet. For inline stylesheets, this is the URL of the page.
+    pub url: String,
+    /// The rules of the stylesheet.
+    pub rules: Vec<CssRuleUsage>,
+    /// The text of the stylesheet, if it could still be read.
+    pub text: Option<String>,
+}
+
+impl CssCoverage {
+    /// The text of each rule that was not used.
+    pub fn unused_rules(&self) -> Vec<String> {
+        let Some(text) = &self.text else {
+            return Vec::new();
+        };
+        let text: Vec<u16> = text.encode_utf16().collect();
+        self.rules
+            .iter()
+            .filter(|x| !x.used)
+            .filter_map(|x| text.get(x.start_offset..x.end_offset))
+            .map(String::from_utf16_lossy)
+            .collect()
+    }
+}
+
+/// Convert the coverage to the JSON format used by Istanbul, as written to
+/// `coverage-final.json` by nyc, so that it can be reported with the Istanbul tools.
+///
+/// Scripts without a source are skipped, so set `JsCoverageOptions::include_source`.
+/// Each function is reported as a function, each line of a function as a statement, and
+/// each block within a function as a branch with a single location.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use thirtyfour::extensions::cdp::{to_istanbul, ChromeDevTools, JsCoverageOptions};
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+/// dev_tools.start_js_coverage(&JsCoverageOptions::new().with_include_source(true)).await?;
+/// driver.goto("https://www.rust-lang.org/").await?;
+/// let coverage = dev_tools.stop_js_coverage().await?;
+/// std::fs::write(".nyc_output/coverage.json", to_istanbul(&coverage).to_string())?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+#[cfg(feature = "istanbul")]
+pub fn to_istanbul(coverage: &[ScriptCoverage]) -> Value {
+    let mut files = serde_json::Map::new();
+    for script in coverage {
+        let Some(source) = &script.source else {
+            continue;
+        };
+        let lines = LineIndex::new(source);
+        let location = |range: &CoverageRange| {
+            json!({
+                "start": lines.position(range.start_offset),
+                "end": lines.position(range.end_offset),
+            })
+        };
+
+        let (mut fn_map, mut f) = (serde_json::Map::new(), serde_json::Map::new());
+        let (mut branch_map, mut b) = (serde_json::Map::new(), serde_json::Map::new());
+        let (mut statement_map, mut s) = (serde_json::Map::new(), serde_json::Map::new());
+        for function in &script.functions {
+            let Some(whole) = function.ranges.first() else {
+                continue;
+            };
+            // The top level of the script is not a function.
+            if !function.function_name.is_empty() || whole.start_offset > 0 {
+                let id = fn_map.len().to_string();
+                fn_map.insert(
+                    id.clone(),
+                    json!({
+                        "name": function.function_name,
+                        "decl": location(whole),
+                        "loc": location(whole),
+                        "line": lines.position(whole.start_offset)["line"],
+                    }),
+                );
+                f.insert(id, json!(whole.count));
+            }
+            for block in function.ranges.iter().skip(1) {
+                let id = branch_map.len().to_string();
+                branch_map.insert(
+                    id.clone(),
+                    json!({
+                        "type": "block",
+                        "loc": location(block),
+                        "locations": [location(block)],
+                        "line": lines.position(block.start_offset)["line"],
+                    }),
+                );
+                b.insert(id, json!([block.count]));
+            }
+        }
+        for (line, (start, end)) in lines.non_empty_lines(source) {
+            let count = script.count_at(start).unwrap_or_default();
+            let id = statement_map.len().to_string();
+            statement_map.insert(
+                id.clone(),
+                json!({
+                    "start": { "line": line, "column": 0 },
+                    "end": { "line": line, "column": end - start },
+                }),
+            );
+            s.insert(id, json!(count));
+        }
+
+        files.insert(
+            script.url.clone(),
+            json!({
+                "path": script.url,
+                "statementMap": statement_map,
+                "fnMap": fn_map,
+                "branchMap": branch_map,
+                "s": s,
+                "f": f,
+                "b": b,
+            }),
+        );
+    }
+    Value::Object(files)
+}
+
+/// Converts UTF-16 offsets to one-based lines and zero-based columns.
+#[cfg(feature = "istanbul")]
+struct LineIndex {
+    /// The UTF-16 offset of the start of each line.
+    starts: Vec<usize>,
+}
+
+#[cfg(feature = "istanbul")]
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let mut starts = vec![0];
+        let mut offset = 0;
+        for c in source.chars() {
+            offset += c.len_utf16();
+            if c == '\n' {
+                starts.push(offset);
+            }
+        }
+        Self {
+            starts,
+        }
+    }
+
+    fn position(&self, offset: usize) -> Value {
+        let line = self.starts.partition_point(|x| *x <= offset).max(1);
+        json!({ "line": line, "column": offset - self.starts[line - 1] })
+    }
+
+    /// The one-based number and UTF-16 offsets of each line that is not just whitespace.
+    fn non_empty_lines<'a>(
+        &'a self,
+        source: &'a str,
+    ) -> impl Iterator<Item = (usize, (usize, usize))> + 'a {
+        source.split('\n').enumerate().filter(|(_, x)| !x.trim().is_empty()).map(
+            move |(i, text)| {
+                let indent: usize =
+                    text.chars().take_while(|x| x.is_whitespace()).map(char::len_utf16).sum();
+                let start = self.starts[i];
+                (i + 1, (start + indent, start + text.encode_utf16().count()))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "function choose(side) {\n  if (side) {\n    return 1;\n  }\n  return 2;\n}\nfunction never() {}\nchoose(true);\n";
+
+    fn coverage() -> ScriptCoverage {
+        let offset = |x: &str| SOURCE.find(x).unwrap();
+        ScriptCoverage {
+            script_id: "42".to_string(),
+            url: "http://localhost/app.js".to_string(),
+            functions: serde_json::from_value(json!([
+                {
+                    "functionName": "",
+                    "isBlockCoverage": true,
+                    "ranges": [{ "startOffset": 0, "endOffset": SOURCE.len(), "count": 1 }],
+                },
+                {
+                    "functionName": "choose",
+                    "isBlockCoverage": true,
+                    "ranges": [
+                        { "startOffset": 0, "endOffset": offset("\nfunction never"), "count": 1 },
+                        { "startOffset": offset("\n  }\n  return 2"), "endOffset": offset("\n}\nfunction never"), "count": 0 },
+                    ],
+                },
+                {
+                    "functionName": "never",
+                    "isBlockCoverage": false,
+                    "ranges": [{ "startOffset": offset("function never"), "endOffset": offset("\nchoose("), "count": 0 }],
+                },
+            ]))
+            .unwrap(),
+            source: Some(SOURCE.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_script_coverage() {
+        let coverage = coverage();
+        assert!(coverage.is_covered(SOURCE.find("return 1").unwrap()));
+        assert!(!coverage.is_covered(SOURCE.find("return 2").unwrap()));
+        assert_eq!(coverage.function("never").map(|x| x.count()), Some(0));
+        assert_eq!(coverage.function("choose").map(|x| x.count()), Some(1));
+        assert_eq!(coverage.count_at(SOURCE.len() + 10), None);
+    }
+
+    #[test]
+    fn test_unused_rules() {
+        let text = ".used { color: red; }\n.unused { color: blue; }";
+        let coverage = CssCoverage {
+            style_sheet_id: "1".to_string(),
+            url: "http://localhost/app.css".to_string(),
+            rules: vec![
+                CssRuleUsage {
+                    start_offset: 0,
+                    end_offset: 21,
+                    used: true,
+                },
+                CssRuleUsage {
+                    start_offset: 22,
+                    end_offset: text.len(),
+                    used: false,
+                },
+            ],
+            text: Some(text.to_string()),
+        };
+        assert_eq!(coverage.unused_rules(), vec![".unused { color: blue; }".to_string()]);
+    }
+
+    #[cfg(feature = "istanbul")]
+    #[test]
+    fn test_to_istanbul() {
+        let istanbul = to_istanbul(&[coverage()]);
+        let file = &istanbul["http://localhost/app.js"];
+        assert_eq!(file["path"], "http://localhost/app.js");
+        assert_eq!(file["fnMap"]["0"]["name"], "choose");
+        assert_eq!(file["fnMap"]["1"]["name"], "never");
+        assert_eq!(file["f"], json!({ "0": 1, "1": 0 }));
+        assert_eq!(file["branchMap"]["0"]["loc"]["start"], json!({ "line": 3, "column": 13 }));
+        assert_eq!(file["b"], json!({ "0": [0] }));
+        // One statement per non-empty line.
+        assert_eq!(
+            file["s"],
+            json!({ "0": 1, "1": 1, "2": 1, "3": 0, "4": 0, "5": 1, "6": 0, "7": 1 })
+        );
+    }
+}
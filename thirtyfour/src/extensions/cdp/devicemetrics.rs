@@ -0,0 +1,213 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::{ClientHints, UserAgentOverride};
+
+/// The orientation of the screen, reported to the page via `screen.orientation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScreenOrientation {
+    /// Portrait, the right way up.
+    PortraitPrimary,
+    /// Portrait, upside down.
+    PortraitSecondary,
+    /// Landscape, rotated 90 degrees.
+    LandscapePrimary,
+    /// Landscape, rotated 270 degrees.
+    LandscapeSecondary,
+}
+
+impl ScreenOrientation {
+    /// The angle of the screen, in degrees.
+    pub fn angle(self) -> u32 {
+        match self {
+            ScreenOrientation::PortraitPrimary => 0,
+            ScreenOrientation::LandscapePrimary => 90,
+            ScreenOrientation::PortraitSecondary => 180,
+            ScreenOrientation::LandscapeSecondary => 270,
+        }
+    }
+}
+
+/// The screen to emulate with `ChromeDevTools::set_device_metrics()`.
+///
+/// See <https://chromedevtools.github.io/devtools-protocol/tot/Emulation/#method-setDeviceMetricsOverride>.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceMetrics {
+    /// The viewport width, in CSS pixels.
+    pub width: u32,
+    /// The viewport height, in CSS pixels.
+    pub height: u32,
+    /// The number of device pixels per CSS pixel, reported via `window.devicePixelRatio`.
+    /// Zero keeps the browser's own ratio.
+    pub device_scale_factor: f64,
+    /// True to emulate a mobile device, including the meta viewport tag and overlay
+    /// scrollbars.
+    pub mobile: bool,
+    /// The screen orientation, if any.
+    pub screen_orientation: Option<ScreenOrientation>,
+}
+
+impl DeviceMetrics {
+    /// Create new `DeviceMetrics` for a desktop screen of the specified size.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            device_scale_factor: 0.0,
+            mobile: false,
+            screen_orientation: None,
+        }
+    }
+
+    /// The screen of an iPhone 12.
+    pub fn iphone_12() -> Self {
+        Self {
+            width: 390,
+            height: 844,
+            device_scale_factor: 3.0,
+            mobile: true,
+            screen_orientation: Some(ScreenOrientation::PortraitPrimary),
+        }
+    }
+
+    /// The screen of a Pixel 7.
+    pub fn pixel_7() -> Self {
+        Self {
+            width: 412,
+            height: 915,
+            device_scale_factor: 2.625,
+            mobile: true,
+            screen_orientation: Some(ScreenOrientation::PortraitPrimary),
+        }
+    }
+
+    /// Set the device scale factor.
+    pub fn with_device_scale_factor(mut self, device_scale_factor: f64) -> Self {
+        self.device_scale_factor = device_scale_factor;
+        self
+    }
+
+    /// Set whether to emulate a mobile device.
+    pub fn with_mobile(mut self, mobile: bool) -> Self {
+        self.mobile = mobile;
+        self
+    }
+
+    /// Set the screen orientation. Switching between portrait and landscape also swaps
+    /// the width and height.
+    pub fn with_screen_orientation(mut self, orientation: ScreenOrientation) -> Self {
+        let is_landscape = |x: Option<ScreenOrientation>| x.is_some_and(|x| x.angle() % 180 != 0);
+        if self.screen_orientation.is_some()
+            && is_landscape(self.screen_orientation) != is_landscape(Some(orientation))
+        {
+            std::mem::swap(&mut self.width, &mut self.height);
+        }
+        self.screen_orientation = Some(orientation);
+        self
+    }
+
+    /// Build the parameters for the CDP `Emulation.setDeviceMetricsOverride` command.
+    pub(crate) fn to_params(&self) -> Value {
+        let mut params = json!({
+            "width": self.width,
+            "height": self.height,
+            "deviceScaleFactor": self.device_scale_factor,
+            "mobile": self.mobile,
+        });
+        if let Some(orientation) = self.screen_orientation {
+            params["screenOrientation"] = json!({
+                "type": orientation,
+                "angle": orientation.angle(),
+            });
+        }
+        params
+    }
+}
+
+/// A device to emulate with `ChromeDevTools::emulate_device()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    /// The screen of the device.
+    pub metrics: DeviceMetrics,
+    /// The user agent the device's browser reports.
+    pub user_agent: UserAgentOverride,
+    /// The maximum number of simultaneous touch points, or 0 if the device does not
+    /// have a touch screen.
+    pub max_touch_points: u32,
+}
+
+impl Device {
+    /// An iPhone 12, running Safari.
+    pub fn iphone_12() -> Self {
+        Self {
+            metrics: DeviceMetrics::iphone_12(),
+            // Safari does not send client hints.
+            user_agent: UserAgentOverride::new(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 14_7_1 like Mac OS X) \
+                 AppleWebKit/605.1.15 (KHTML, like Gecko) Version/14.1.2 \
+                 Mobile/15E148 Safari/604.1",
+            )
+            .with_platform("iPhone"),
+            max_touch_points: 5,
+        }
+    }
+
+    /// A Pixel 7, running Chrome.
+    pub fn pixel_7() -> Self {
+        Self {
+            metrics: DeviceMetrics::pixel_7(),
+            user_agent: UserAgentOverride::new(
+                "Mozilla/5.0 (Linux; Android 13; Pixel 7) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/116.0.0.0 Mobile Safari/537.36",
+            )
+            .with_platform("Linux armv81")
+            .with_client_hints(
+                ClientHints::new("Android", "13.0.0")
+                    .with_chrome_version("116.0.5845.114")
+                    .with_model("Pixel 7")
+                    .with_mobile(true),
+            ),
+            max_touch_points: 5,
+        }
+    }
+
+    /// Set the screen orientation. See [`DeviceMetrics::with_screen_orientation`].
+    pub fn with_screen_orientation(mut self, orientation: ScreenOrientation) -> Self {
+        self.metrics = self.metrics.with_screen_orientation(orientation);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_metrics_params() {
+        assert_eq!(
+            DeviceMetrics::iphone_12().to_params(),
+            json!({
+                "width": 390,
+                "height": 844,
+                "deviceScaleFactor": 3.0,
+                "mobile": true,
+                "screenOrientation": { "type": "portraitPrimary", "angle": 0 },
+            })
+        );
+        assert_eq!(
+            DeviceMetrics::new(800, 600).to_params(),
+            json!({ "width": 800, "height": 600, "deviceScaleFactor": 0.0, "mobile": false })
+        );
+
+        let landscape =
+            Device::pixel_7().with_screen_orientation(ScreenOrientation::LandscapePrimary);
+        let params = landscape.metrics.to_params();
+        assert_eq!((params["width"].as_u64(), params["height"].as_u64()), (Some(915), Some(412)));
+        assert_eq!(params["screenOrientation"]["type"], "landscapePrimary");
+        assert_eq!(params["screenOrientation"]["angle"], 90);
+        // Rotating within the same orientation does not swap the dimensions again.
+        let flipped = landscape.with_screen_orientation(ScreenOrientation::LandscapeSecondary);
+        assert_eq!(flipped.metrics.width, 915);
+    }
+}
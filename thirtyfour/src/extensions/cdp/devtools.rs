@@ -1,9 +1,43 @@
+use super::cookies::set_cookie_params;
+use super::media::EmulatedMedia;
+use super::performance::parse_metrics;
+use super::sensors::{orientation_params, validate_orientation, SENSOR_OVERRIDE_MIN_VERSION};
 use super::ChromeCommand;
-use super::NetworkConditions;
-use crate::error::WebDriverResult;
+use super::{
+    validate_locale, validate_media_type, validate_timezone, validate_url_pattern, CdpCookie,
+    Device, DeviceMetrics, DomSnapshot, DomSnapshotOptions, GeoLocation, MediaFeature,
+    NetworkConditions, NetworkThrottle, RequestRules, SensorReading, SensorType, UserAgentOverride,
+};
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverErrorInner, WebDriverResult};
 use crate::session::handle::SessionHandle;
+use crate::Cookie;
+#[cfg(feature = "cdp-events")]
+use futures_util::StreamExt;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
+#[cfg(feature = "cdp-events")]
+use std::{collections::HashSet, path::Path, time::Duration};
+
+#[cfg(feature = "cdp-events")]
+use super::auth::{AuthChallenge, Credentials};
+#[cfg(feature = "cdp-events")]
+use super::connection::lock;
+#[cfg(feature = "cdp-events")]
+use super::downloads::DownloadTracker;
+#[cfg(feature = "cdp-events")]
+use super::fetch::{update_fetch, AuthHandler};
+#[cfg(feature = "cdp-events")]
+use super::filechooser::start_file_chooser_task;
+#[cfg(feature = "cdp-events")]
+use super::trace::{start_trace_task, subscribe_trace};
+#[cfg(feature = "cdp-events")]
+use super::{
+    url_pattern_matches, CdpConnection, ConsoleLevel, ConsoleMessage, ConsoleStream, CssCoverage,
+    CssRuleUsage, DownloadEvent, DownloadStream, EventStream, ExceptionStream, FileChooser,
+    FileChooserAction, JsCoverageOptions, JsException, ScriptCoverage, Trace, TracingOptions,
+    WsEvent, WsStream,
+};
 
 /// The ChromeDevTools struct allows you to interact with Chromium-based browsers via
 /// the Chrome Devtools Protocol (CDP).
@@ -141,98 +175,1559 @@ impl ChromeDevTools {
         Ok(())
     }
 
-    /// Execute the specified command without parameters.
-    /// For commands that require parameters, use `execute_cdp_with_params()` instead.
+    /// Emulate the specified network conditions via the CDP
+    /// `Network.emulateNetworkConditions` command, replacing any conditions set before.
     ///
-    /// You can find documentation for the available commands here:
-    /// [https://chromedevtools.github.io/devtools-protocol/](https://chromedevtools.github.io/devtools-protocol/])
+    /// The `Network` domain is enabled first. Calling this again with the same conditions
+    /// has no further effect.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
-    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, NetworkThrottle};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.emulate_network(&NetworkThrottle::slow_3g()).await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// dev_tools.clear_network_emulation().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn emulate_network(&self, throttle: &NetworkThrottle) -> WebDriverResult<()> {
+        // Enabling a domain that is already enabled has no effect.
+        self.execute_cdp("Network.enable").await?;
+        self.execute_cdp_with_params("Network.emulateNetworkConditions", throttle.to_params())
+            .await?;
+        Ok(())
+    }
+
+    /// Stop emulating network conditions set via `emulate_network()`.
+    pub async fn clear_network_emulation(&self) -> WebDriverResult<()> {
+        self.emulate_network(&NetworkThrottle::new()).await
+    }
+
+    /// Block every request whose URL matches any of the specified patterns, using the
+    /// CDP `Network.setBlockedURLs` command. This replaces any patterns blocked previously.
     ///
+    /// Patterns match the whole URL, and `*` matches any number of characters. Returns an
+    /// `InvalidArgument` error without sending anything if a pattern is invalid.
+    /// See [`validate_url_pattern`](super::validate_url_pattern).
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
-    /// dev_tools.execute_cdp("Network.clearBrowserCache").await?;
+    /// dev_tools.block_urls(&["*.png", "*://www.google-analytics.com/*"]).await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// dev_tools.unblock_all().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn block_urls(&self, patterns: &[&str]) -> WebDriverResult<()> {
+        for pattern in patterns {
+            validate_url_pattern(pattern)?;
+        }
+        self.execute_cdp("Network.enable").await?;
+        self.execute_cdp_with_params("Network.setBlockedURLs", json!({ "urls": patterns })).await?;
+        Ok(())
+    }
+
+    /// Stop blocking the requests blocked via `block_urls()` or `apply_request_rules()`.
+    pub async fn unblock_all(&self) -> WebDriverResult<()> {
+        self.execute_cdp_with_params("Network.setBlockedURLs", json!({ "urls": [] })).await?;
+        Ok(())
+    }
+
+    /// Apply the specified rules to every request made by the browser, replacing any rules
+    /// applied previously.
     ///
-    /// // execute_cdp() can also return values as well.
-    /// let version_info = dev_tools.execute_cdp("Browser.getVersion").await?;
-    /// let user_agent = version_info["userAgent"].as_str().unwrap();
+    /// Returns an `InvalidArgument` error without sending anything if any of the rules
+    /// are invalid. See [`RequestRules::validate`]. Rules that rewrite URLs or remove
+    /// headers return an `UnsupportedOperation` error unless the `cdp-events` feature is
+    /// enabled.
+    pub async fn apply_request_rules(&self, rules: &RequestRules) -> WebDriverResult<()> {
+        rules.validate()?;
+        #[cfg(not(feature = "cdp-events"))]
+        if rules.intercepts() {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "rewriting URLs and removing headers requires the cdp-events feature".to_string(),
+            )));
+        }
+        let patterns: Vec<&str> = rules.blocked().iter().map(String::as_str).collect();
+        self.block_urls(&patterns).await?;
+        self.execute_cdp_with_params("Network.setExtraHTTPHeaders", rules.headers_params()).await?;
+        #[cfg(feature = "cdp-events")]
+        if rules.intercepts() || self.handle.cdp_connection.get().is_some() {
+            let connection = self.connection().await?;
+            connection.fetch.set_rules(Some(rules.clone()));
+            update_fetch(&connection).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove the rules applied via `apply_request_rules()`.
+    pub async fn clear_request_rules(&self) -> WebDriverResult<()> {
+        self.unblock_all().await?;
+        self.execute_cdp_with_params("Network.setExtraHTTPHeaders", json!({ "headers": {} }))
+            .await?;
+        #[cfg(feature = "cdp-events")]
+        if let Some(connection) = self.handle.cdp_connection.get() {
+            connection.fetch.set_rules(None);
+            update_fetch(connection).await?;
+        }
+        Ok(())
+    }
+
+    /// Report the specified position to the page via the geolocation API, using the CDP
+    /// `Emulation.setGeolocationOverride` command.
+    ///
+    /// The geolocation permission is also granted to every origin, so that
+    /// `navigator.geolocation.getCurrentPosition()` succeeds without a prompt.
+    ///
+    /// Returns an `InvalidArgument` error without sending anything if the coordinates are
+    /// out of range. See [`GeoLocation::validate`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, GeoLocation};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.set_geolocation(GeoLocation::new(-33.8568, 151.2153)).await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn execute_cdp(&self, cmd: &str) -> WebDriverResult<Value> {
-        self.execute_cdp_with_params(cmd, json!({})).await
+    pub async fn set_geolocation(&self, geo: GeoLocation) -> WebDriverResult<()> {
+        geo.validate()?;
+        self.execute_cdp_with_params(
+            "Browser.grantPermissions",
+            json!({ "permissions": ["geolocation"] }),
+        )
+        .await?;
+        self.execute_cdp_with_params("Emulation.setGeolocationOverride", geo.to_params()).await?;
+        self.state().set_geolocation(Some(geo));
+        Ok(())
     }
 
-    /// Execute the specified command with the specified parameter(s).
+    /// Stop overriding the position set via `set_geolocation()`.
     ///
-    /// You can find documentation for the available commands here:
-    /// [https://chromedevtools.github.io/devtools-protocol/](https://chromedevtools.github.io/devtools-protocol/])
+    /// The geolocation permission stays granted.
+    pub async fn clear_geolocation_override(&self) -> WebDriverResult<()> {
+        self.execute_cdp("Emulation.clearGeolocationOverride").await?;
+        self.state().set_geolocation(None);
+        Ok(())
+    }
+
+    /// Override the orientation reported by `deviceorientation` events, in degrees.
+    ///
+    /// Chrome 123 and later emulate the relative orientation sensor via the CDP
+    /// `Emulation.setSensorOverrideReadings` command. Older versions use the
+    /// `DeviceOrientation.setDeviceOrientationOverride` command.
+    ///
+    /// Returns an `InvalidArgument` error without sending anything if alpha is not in
+    /// [0, 360), beta in [-180, 180) or gamma in [-90, 90). Returns an
+    /// `UnsupportedOperation` error if the browser is not Chromium-based.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
     /// use thirtyfour::extensions::cdp::ChromeDevTools;
-    /// use serde_json::json;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
     /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
-    /// dev_tools.execute_cdp_with_params("Network.setCacheDisabled", json!({"cacheDisabled": true})).await?;
+    /// // Lying flat, then tilted towards the user.
+    /// dev_tools.set_device_orientation(0.0, 0.0, 0.0).await?;
+    /// dev_tools.set_device_orientation(0.0, 45.0, 0.0).await?;
+    /// dev_tools.clear_device_orientation().await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn execute_cdp_with_params(
+    pub async fn set_device_orientation(
         &self,
-        cmd: &str,
-        cmd_args: Value,
-    ) -> WebDriverResult<Value> {
-        let v =
-            self.handle.cmd(ChromeCommand::ExecuteCdpCommand(cmd.to_string(), cmd_args)).await?;
-        v.value()
+        alpha: f64,
+        beta: f64,
+        gamma: f64,
+    ) -> WebDriverResult<()> {
+        validate_orientation(alpha, beta, gamma)?;
+        let unsupported = |e| unsupported_command("device orientation overrides", e);
+        if self.uses_sensor_overrides() {
+            self.enable_sensor_override(SensorType::RelativeOrientation).await?;
+            self.execute_cdp_with_params(
+                "Emulation.setSensorOverrideReadings",
+                orientation_params(alpha, beta, gamma),
+            )
+            .await
+            .map_err(unsupported)?;
+        } else {
+            self.execute_cdp_with_params(
+                "DeviceOrientation.setDeviceOrientationOverride",
+                json!({ "alpha": alpha, "beta": beta, "gamma": gamma }),
+            )
+            .await
+            .map_err(unsupported)?;
+        }
+        Ok(())
     }
 
-    /// Get the list of sinks available for cast.
-    pub async fn get_sinks(&self) -> WebDriverResult<Value> {
-        let v = self.handle.cmd(ChromeCommand::GetSinks).await?;
-        v.value()
+    /// Stop overriding the orientation set via `set_device_orientation()`.
+    pub async fn clear_device_orientation(&self) -> WebDriverResult<()> {
+        if self.uses_sensor_overrides() {
+            self.clear_sensor_reading(SensorType::RelativeOrientation).await
+        } else {
+            self.execute_cdp("DeviceOrientation.clearDeviceOrientationOverride")
+                .await
+                .map_err(|e| unsupported_command("device orientation overrides", e))?;
+            Ok(())
+        }
     }
 
-    /// Get the issue message for any issue in a cast session.
-    pub async fn get_issue_message(&self) -> WebDriverResult<Value> {
-        let v = self.handle.cmd(ChromeCommand::GetIssueMessage).await?;
-        v.value()
+    /// Emulate a motion sensor reporting the specified reading, via the CDP
+    /// `Emulation.setSensorOverrideReadings` command.
+    ///
+    /// The emulated sensor replaces any real one, and is available to the Generic
+    /// Sensor API (such as `new Accelerometer()`) and to `devicemotion` events.
+    ///
+    /// Returns an `InvalidArgument` error without sending anything if the reading is not
+    /// finite. Returns an `UnsupportedOperation` error if the browser is not
+    /// Chromium-based or is older than Chrome 123.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, MotionReading, SensorReading};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let shake = MotionReading::new(12.0, 0.0, 9.8);
+    /// dev_tools.set_sensor_reading(SensorReading::Accelerometer(shake)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_sensor_reading(&self, reading: SensorReading) -> WebDriverResult<()> {
+        reading.validate()?;
+        self.enable_sensor_override(reading.sensor_type()).await?;
+        self.execute_cdp_with_params("Emulation.setSensorOverrideReadings", reading.to_params())
+            .await
+            .map_err(|e| unsupported_command("sensor overrides", e))?;
+        Ok(())
     }
 
-    /// Set the specified sink as the cast session receiver target.
-    pub async fn set_sink_to_use(&self, sink_name: &str) -> WebDriverResult<()> {
-        self.handle.cmd(ChromeCommand::SetSinkToUse(sink_name.to_string())).await?;
+    /// Stop emulating the specified sensor.
+    pub async fn clear_sensor_reading(&self, sensor: SensorType) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Emulation.setSensorOverrideEnabled",
+            json!({ "enabled": false, "type": sensor }),
+        )
+        .await
+        .map_err(|e| unsupported_command("sensor overrides", e))?;
         Ok(())
     }
 
-    /// Start a tab mirroring session on the specified receiver target.
-    pub async fn start_tab_mirroring(&self, sink_name: &str) -> WebDriverResult<()> {
-        self.handle.cmd(ChromeCommand::StartTabMirroring(sink_name.to_string())).await?;
+    /// Replace the specified sensor with an emulated one.
+    async fn enable_sensor_override(&self, sensor: SensorType) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Emulation.setSensorOverrideEnabled",
+            json!({ "enabled": true, "type": sensor }),
+        )
+        .await
+        .map_err(|e| unsupported_command("sensor overrides", e))?;
         Ok(())
     }
 
-    /// Stop the existing cast session on the specified receiver target.
-    pub async fn stop_casting(&self, sink_name: &str) -> WebDriverResult<()> {
-        self.handle.cmd(ChromeCommand::StopCasting(sink_name.to_string())).await?;
+    /// True if the browser supports the `Emulation` sensor override commands, assuming
+    /// that it does if its version is not known.
+    fn uses_sensor_overrides(&self) -> bool {
+        self.handle
+            .driver_info()
+            .browser_major_version()
+            .is_none_or(|major| major >= SENSOR_OVERRIDE_MIN_VERSION)
+    }
+
+    /// Override the size and pixel ratio of the screen, using the CDP
+    /// `Emulation.setDeviceMetricsOverride` command.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, DeviceMetrics};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.set_device_metrics(&DeviceMetrics::new(1024, 768)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_device_metrics(&self, metrics: &DeviceMetrics) -> WebDriverResult<()> {
+        self.execute_cdp_with_params("Emulation.setDeviceMetricsOverride", metrics.to_params())
+            .await?;
+        self.state().set_device_metrics(Some(metrics.clone()));
+        Ok(())
+    }
+
+    /// Stop overriding the device metrics set via `set_device_metrics()`.
+    pub async fn clear_device_metrics(&self) -> WebDriverResult<()> {
+        self.execute_cdp("Emulation.clearDeviceMetricsOverride").await?;
+        self.state().set_device_metrics(None);
+        Ok(())
+    }
+
+    /// Emulate a touch screen supporting up to `max_points` simultaneous touches, using
+    /// the CDP `Emulation.setTouchEmulationEnabled` command.
+    ///
+    /// Touch support is detected when a document is created, so this only affects pages
+    /// loaded afterwards.
+    ///
+    /// Returns an `InvalidArgument` error if `max_points` is not between 1 and 16.
+    pub async fn enable_touch_emulation(&self, max_points: u32) -> WebDriverResult<()> {
+        if !(1..=16).contains(&max_points) {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+                "max_points must be between 1 and 16, got {max_points}"
+            ))));
+        }
+        self.execute_cdp_with_params(
+            "Emulation.setTouchEmulationEnabled",
+            json!({ "enabled": true, "maxTouchPoints": max_points }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Stop emulating a touch screen.
+    pub async fn disable_touch_emulation(&self) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Emulation.setTouchEmulationEnabled",
+            json!({ "enabled": false }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Emulate the screen, touch screen and user agent of the specified device.
+    ///
+    /// Use `clear_device_emulation()` to undo all of these.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, Device};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.emulate_device(&Device::pixel_7()).await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn emulate_device(&self, device: &Device) -> WebDriverResult<()> {
+        self.set_device_metrics(&device.metrics).await?;
+        match device.max_touch_points {
+            0 => self.disable_touch_emulation().await?,
+            max_points => self.enable_touch_emulation(max_points.min(16)).await?,
+        }
+        self.set_user_agent(&device.user_agent).await
+    }
+
+    /// Stop emulating the device set via `emulate_device()`, restoring the default device
+    /// metrics, touch support and user agent.
+    pub async fn clear_device_emulation(&self) -> WebDriverResult<()> {
+        self.clear_device_metrics().await?;
+        self.disable_touch_emulation().await?;
+        // An empty user agent removes the override.
+        self.execute_cdp_with_params("Emulation.setUserAgentOverride", json!({ "userAgent": "" }))
+            .await?;
+        Ok(())
+    }
+
+    /// Report the specified user agent to the pages loaded from now on, including the
+    /// `navigator.platform` value and client hints if they are set.
+    ///
+    /// To apply the override to the first page a new browser loads, set it on the
+    /// capabilities instead, via `ChromiumLikeCapabilities::set_user_agent_override()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, UserAgentOverride};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.set_user_agent(&UserAgentOverride::pixel_7()).await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_user_agent(&self, user_agent: &UserAgentOverride) -> WebDriverResult<()> {
+        user_agent.validate()?;
+        self.execute_cdp_with_params("Emulation.setUserAgentOverride", user_agent.to_params()?)
+            .await?;
+        Ok(())
+    }
+
+    /// Stop reporting the user agent set via `set_user_agent()`.
+    pub async fn clear_user_agent_override(&self) -> WebDriverResult<()> {
+        // An empty user agent removes the override.
+        self.execute_cdp_with_params("Emulation.setUserAgentOverride", json!({ "userAgent": "" }))
+            .await?;
+        Ok(())
+    }
+
+    /// Emulate the specified IANA timezone, such as `America/New_York`, for the dates and
+    /// times the page sees, for example via `Date` and `Intl.DateTimeFormat`.
+    ///
+    /// Returns an `InvalidArgument` error, suggesting the closest names, if the timezone
+    /// is not an IANA timezone name. See [`validate_timezone`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.set_timezone("Pacific/Auckland").await?;
+    /// dev_tools.set_locale("en-NZ").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_timezone(&self, timezone: &str) -> WebDriverResult<()> {
+        validate_timezone(timezone)?;
+        self.execute_cdp_with_params(
+            "Emulation.setTimezoneOverride",
+            json!({ "timezoneId": timezone }),
+        )
+        .await?;
+        self.state().update_locale_overrides(|x| x.timezone = Some(timezone.to_string()));
+        Ok(())
+    }
+
+    /// Stop emulating the timezone set via `set_timezone()`.
+    pub async fn clear_timezone(&self) -> WebDriverResult<()> {
+        self.execute_cdp_with_params("Emulation.setTimezoneOverride", json!({ "timezoneId": "" }))
+            .await?;
+        self.state().update_locale_overrides(|x| x.timezone = None);
+        Ok(())
+    }
+
+    /// Emulate the specified locale, such as `de-DE`, for the page's `Intl` APIs and
+    /// number and date formatting.
+    ///
+    /// This does not change `navigator.language` or the `Accept-Language` header. Returns
+    /// an `InvalidArgument` error if the locale is not a BCP 47 language tag. See
+    /// [`validate_locale`].
+    pub async fn set_locale(&self, locale: &str) -> WebDriverResult<()> {
+        validate_locale(locale)?;
+        self.execute_cdp_with_params("Emulation.setLocaleOverride", json!({ "locale": locale }))
+            .await?;
+        self.state().update_locale_overrides(|x| x.locale = Some(locale.to_string()));
+        Ok(())
+    }
+
+    /// Stop emulating the locale set via `set_locale()`.
+    pub async fn clear_locale(&self) -> WebDriverResult<()> {
+        self.execute_cdp_with_params("Emulation.setLocaleOverride", json!({})).await?;
+        self.state().update_locale_overrides(|x| x.locale = None);
         Ok(())
     }
+
+    /// Emulate the specified CSS media features, such as `prefers-color-scheme: dark`, for
+    /// media queries and `matchMedia()` in the page. This replaces any features set
+    /// previously, and keeps the media type set via `set_emulated_media_type()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, ColorScheme, MediaFeature, ReducedMotion};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools
+    ///     .set_media_features(&[
+    ///         MediaFeature::PrefersColorScheme(ColorScheme::Dark),
+    ///         MediaFeature::PrefersReducedMotion(ReducedMotion::Reduce),
+    ///     ])
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_media_features(&self, features: &[MediaFeature]) -> WebDriverResult<()> {
+        let media = EmulatedMedia {
+            features: features.to_vec(),
+            ..self.state().emulated_media()
+        };
+        self.set_emulated_media(media).await
+    }
+
+    /// Emulate the specified CSS media type, either `screen` or `print`, for media queries
+    /// and stylesheets in the page. This keeps the features set via
+    /// `set_media_features()`.
+    ///
+    /// Returns an `InvalidArgument` error for other media types. See [`MEDIA_TYPES`].
+    ///
+    /// [`MEDIA_TYPES`]: super::MEDIA_TYPES
+    pub async fn set_emulated_media_type(&self, media_type: &str) -> WebDriverResult<()> {
+        validate_media_type(media_type)?;
+        let media = EmulatedMedia {
+            media_type: Some(media_type.to_string()),
+            ..self.state().emulated_media()
+        };
+        self.set_emulated_media(media).await
+    }
+
+    /// Stop emulating the media type and features set via `set_emulated_media_type()` and
+    /// `set_media_features()`.
+    pub async fn clear_media_emulation(&self) -> WebDriverResult<()> {
+        self.set_emulated_media(EmulatedMedia::default()).await
+    }
+
+    async fn set_emulated_media(&self, media: EmulatedMedia) -> WebDriverResult<()> {
+        self.execute_cdp_with_params("Emulation.setEmulatedMedia", media.to_params()).await?;
+        self.state().set_emulated_media(media);
+        Ok(())
+    }
+
+    /// Capture a snapshot of the DOM of the page and its frames, including the state of
+    /// form fields, the bounding box of each rendered node and the specified computed
+    /// styles, via the CDP `DOMSnapshot.captureSnapshot` command.
+    ///
+    /// This is useful for recording the state of the page when a test fails, as the
+    /// snapshot can be saved via [`DomSnapshot::save_json`] and inspected later.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, DomSnapshotOptions};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let options = DomSnapshotOptions::new().with_computed_styles(&["display", "visibility"]);
+    /// let snapshot = dev_tools.dom_snapshot(&options).await?;
+    /// for node in snapshot.find_nodes_by_attribute("role", "alert") {
+    ///     println!("alert: {}", snapshot.text_of(node));
+    /// }
+    /// snapshot.save_json("snapshot.json")?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn dom_snapshot(&self, options: &DomSnapshotOptions) -> WebDriverResult<DomSnapshot> {
+        let ret = self
+            .execute_cdp_with_params("DOMSnapshot.captureSnapshot", options.to_params())
+            .await?;
+        DomSnapshot::from_cdp(ret, options.computed_styles.clone())
+    }
+
+    /// Get the current runtime metrics of the page by name via the CDP
+    /// `Performance.getMetrics` command, including `JSHeapUsedSize` and
+    /// `JSHeapTotalSize` in bytes, and the number of `Documents`, DOM `Nodes` and
+    /// `JSEventListeners`.
+    ///
+    /// Returns an `UnsupportedOperation` error if the browser does not support the
+    /// command or reports no metrics.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let metrics = dev_tools.performance_metrics().await?;
+    /// println!("{} DOM nodes", metrics["Nodes"]);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn performance_metrics(&self) -> WebDriverResult<HashMap<String, f64>> {
+        let unsupported = |e| unsupported_command("performance metrics", e);
+        // Enabling a domain that is already enabled has no effect.
+        self.execute_cdp("Performance.enable").await.map_err(unsupported)?;
+        let ret = self.execute_cdp("Performance.getMetrics").await.map_err(unsupported)?;
+        let metrics = parse_metrics(&ret);
+        if metrics.is_empty() {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "the browser reported no performance metrics".to_string(),
+            )));
+        }
+        Ok(metrics)
+    }
+
+    /// Run a full garbage collection in the page via the CDP
+    /// `HeapProfiler.collectGarbage` command, so that the heap size only includes
+    /// objects that are still reachable.
+    pub async fn force_gc(&self) -> WebDriverResult<()> {
+        self.execute_cdp("HeapProfiler.collectGarbage")
+            .await
+            .map_err(|e| unsupported_command("garbage collection", e))?;
+        Ok(())
+    }
+
+    /// Get the websocket connection to the browser's DevTools, opening it if necessary.
+    ///
+    /// The connection is shared by every `ChromeDevTools` for the same session, and stays
+    /// open until the browser closes it. See [`CdpConnection`] for details.
+    #[cfg(feature = "cdp-events")]
+    pub async fn connection(&self) -> WebDriverResult<Arc<CdpConnection>> {
+        self.handle
+            .cdp_connection
+            .get_or_try_init(|| async { CdpConnection::connect(&self.handle).await.map(Arc::new) })
+            .await
+            .cloned()
+    }
+
+    /// Answer HTTP authentication challenges, such as basic and digest authentication,
+    /// using the specified handler instead of showing the browser's credentials dialog.
+    ///
+    /// The handler is called with each challenge to a request whose URL matches any of
+    /// the patterns, and returns the credentials to answer it with, or `None` to cancel
+    /// it. If the browser challenges the same request again because the credentials were
+    /// rejected, the challenge is cancelled. This replaces any handler set previously.
+    /// Challenges to other requests are left to the browser. See [`validate_url_pattern`]
+    /// for the syntax of the patterns.
+    ///
+    /// This uses the CDP `Fetch` domain, which pauses the matching requests in every
+    /// window until they are continued over the DevTools connection, so the patterns
+    /// should be as narrow as possible. See [`ChromeDevTools::connection`].
+    ///
+    /// Returns an `InvalidArgument` error if any of the patterns are invalid.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, Credentials};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let patterns = ["https://staging.example.com/*", "https://admin.example.com/*"];
+    /// dev_tools
+    ///     .set_auth_handler(&patterns, |challenge| match challenge.origin.as_str() {
+    ///         "https://staging.example.com" => Some(Credentials::new("staging", "secret")),
+    ///         "https://admin.example.com" => Some(Credentials::new("admin", "secret")),
+    ///         _ => None,
+    ///     })
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn set_auth_handler<F>(&self, patterns: &[&str], handler: F) -> WebDriverResult<()>
+    where
+        F: Fn(&AuthChallenge) -> Option<Credentials> + Send + Sync + 'static,
+    {
+        for pattern in patterns {
+            validate_url_pattern(pattern)?;
+        }
+        let connection = self.connection().await?;
+        connection.fetch.set_auth(Some(AuthHandler {
+            patterns: patterns.iter().map(ToString::to_string).collect(),
+            handler: Arc::new(handler),
+        }));
+        update_fetch(&connection).await
+    }
+
+    /// Stop answering the authentication challenges handled via `set_auth_handler()`.
+    #[cfg(feature = "cdp-events")]
+    pub async fn clear_auth_handler(&self) -> WebDriverResult<()> {
+        let connection = self.connection().await?;
+        connection.fetch.set_auth(None);
+        update_fetch(&connection).await
+    }
+
+    /// Set whether file choosers opened by the page, for example by clicking an
+    /// `<input type=file>`, are intercepted rather than showing the native file dialog,
+    /// which cannot be controlled via WebDriver.
+    ///
+    /// Intercepted choosers are passed to the handler set via
+    /// `set_file_chooser_handler()`, or cancelled if there is none.
+    ///
+    /// This uses the CDP `Page.setInterceptFileChooserDialog` command and the
+    /// `Page.fileChooserOpened` event over the DevTools connection. See
+    /// [`ChromeDevTools::connection`].
+    #[cfg(feature = "cdp-events")]
+    pub async fn intercept_file_chooser(&self, enabled: bool) -> WebDriverResult<()> {
+        let connection = self.connection().await?;
+        if enabled {
+            start_file_chooser_task(&connection);
+            connection.send("Page.enable", json!({})).await?;
+        }
+        connection
+            .send("Page.setInterceptFileChooserDialog", json!({ "enabled": enabled }))
+            .await?;
+        Ok(())
+    }
+
+    /// Answer the file choosers opened by the page using the specified handler instead of
+    /// showing the native file dialog, and start intercepting them if necessary.
+    ///
+    /// The handler is called with each chooser, and returns the files to choose or
+    /// [`FileChooserAction::Cancel`]. This replaces any handler set previously.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, FileChooserAction};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools
+    ///     .set_file_chooser_handler(|chooser| match chooser.multiple {
+    ///         true => FileChooserAction::Files(vec!["/tmp/a.png".into(), "/tmp/b.png".into()]),
+    ///         false => FileChooserAction::Files(vec!["/tmp/a.png".into()]),
+    ///     })
+    ///     .await?;
+    /// driver.find(By::Id("upload")).await?.click().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn set_file_chooser_handler<F>(&self, handler: F) -> WebDriverResult<()>
+    where
+        F: Fn(&FileChooser) -> FileChooserAction + Send + Sync + 'static,
+    {
+        let connection = self.connection().await?;
+        connection.file_chooser.set(Some(Arc::new(handler)));
+        self.intercept_file_chooser(true).await
+    }
+
+    /// Stop answering file choosers via the handler set via `set_file_chooser_handler()`,
+    /// and stop intercepting them.
+    #[cfg(feature = "cdp-events")]
+    pub async fn clear_file_chooser_handler(&self) -> WebDriverResult<()> {
+        self.intercept_file_chooser(false).await?;
+        self.connection().await?.file_chooser.set(None);
+        Ok(())
+    }
+
+    /// Save the files downloaded from now on to the specified directory, which must be
+    /// an absolute path on the machine the browser runs on, without asking where to
+    /// save them.
+    ///
+    /// If `events` is true, the browser reports the progress of each download, which can
+    /// be awaited via `wait_for_download_complete()` or read via `subscribe_downloads()`.
+    ///
+    /// This uses the CDP `Browser.setDownloadBehavior` command over the DevTools
+    /// connection. See [`ChromeDevTools::connection`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.set_download_behavior("/tmp/downloads", true).await?;
+    /// driver.find(By::Id("export-csv")).await?.click().await?;
+    /// let download =
+    ///     dev_tools.wait_for_download_complete("report.csv", Duration::from_secs(30)).await?;
+    /// println!("downloaded {} bytes", download.received_bytes);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn set_download_behavior(
+        &self,
+        dir: impl AsRef<Path>,
+        events: bool,
+    ) -> WebDriverResult<()> {
+        let dir = dir.as_ref();
+        if !dir.is_absolute() {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+                "the download directory must be an absolute path: {}",
+                dir.display()
+            ))));
+        }
+        let connection = self.connection().await?;
+        let mut downloads = connection.downloads.lock().await;
+        // Subscribe first, so that no events are missed.
+        if events && downloads.is_none() {
+            *downloads = Some(DownloadTracker::new(&connection, self.subscribe_downloads().await?));
+        }
+        let params = json!({
+            "behavior": "allow",
+            "downloadPath": dir.to_string_lossy(),
+            "eventsEnabled": events,
+        });
+        connection.send_to_browser("Browser.setDownloadBehavior", params).await?;
+        if !events {
+            *downloads = None;
+        }
+        Ok(())
+    }
+
+    /// Subscribe to the progress of the downloads that start from now on.
+    ///
+    /// The browser only reports downloads once `set_download_behavior()` has been called
+    /// with `events` set to true.
+    #[cfg(feature = "cdp-events")]
+    pub async fn subscribe_downloads(&self) -> WebDriverResult<DownloadStream> {
+        let connection = self.connection().await?;
+        Ok(EventStream::new(
+            connection.subscribe(&["Browser.downloadWillBegin", "Browser.downloadProgress"]),
+            DownloadEvent::from_event,
+        ))
+    }
+
+    /// Wait until the download with the specified guid, or else the newest download with
+    /// the specified suggested filename, completes, and return its final state. The
+    /// download may have completed before this was called, as long as it started after
+    /// `set_download_behavior()` enabled download events.
+    ///
+    /// Each download is only returned once, so waiting for the same filename again waits
+    /// for the next download of it.
+    ///
+    /// Returns a `DownloadCanceled` error if the download is cancelled, or a `Timeout`
+    /// error if it does not complete within the timeout.
+    #[cfg(feature = "cdp-events")]
+    pub async fn wait_for_download_complete(
+        &self,
+        guid_or_filename: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<DownloadEvent> {
+        let connection = self.connection().await?;
+        let Some(downloads) = connection.downloads.lock().await.as_ref().map(|x| x.waiter()) else {
+            return Err(WebDriverError::UnknownError(WebDriverErrorInfo::new(
+                "download events are not enabled, see set_download_behavior()".to_string(),
+            )));
+        };
+        downloads.wait_for_complete(guid_or_filename, timeout).await
+    }
+
+    /// Cancel the download with the specified guid.
+    #[cfg(feature = "cdp-events")]
+    pub async fn cancel_download(&self, guid: &str) -> WebDriverResult<()> {
+        let connection = self.connection().await?;
+        connection.send_to_browser("Browser.cancelDownload", json!({ "guid": guid })).await?;
+        Ok(())
+    }
+
+    /// Subscribe to the WebSockets the page opens from now on, and the messages sent and
+    /// received over them.
+    ///
+    /// Payloads longer than [`MAX_WS_PAYLOAD_LEN`](super::MAX_WS_PAYLOAD_LEN) are
+    /// truncated. See [`WsFrame::truncated`].
+    ///
+    /// This uses the CDP `Network.webSocket*` events over the DevTools connection. See
+    /// [`ChromeDevTools::connection`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use futures_util::StreamExt;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, WsEvent};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let mut websockets = dev_tools.subscribe_websockets().await?;
+    /// driver.goto("https://example.com/chat").await?;
+    /// while let Some(event) = websockets.next().await {
+    ///     if let WsEvent::FrameReceived { request_id, frame } = event {
+    ///         println!("{request_id}: {:?}", frame.text());
+    ///     }
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn subscribe_websockets(&self) -> WebDriverResult<WsStream> {
+        let connection = self.connection().await?;
+        let stream = EventStream::new(
+            connection.subscribe(&[
+                "Network.webSocketCreated",
+                "Network.webSocketFrameSent",
+                "Network.webSocketFrameReceived",
+                "Network.webSocketClosed",
+            ]),
+            WsEvent::from_event,
+        );
+        connection.send("Network.enable", json!({})).await?;
+        Ok(stream)
+    }
+
+    /// Collect the messages sent and received over the WebSockets that the page opens
+    /// within the specified duration, to URLs that match the specified URL pattern, such
+    /// as `*://localhost:*/live*`, in the order they were sent or received.
+    ///
+    /// Only the [`WsEvent::FrameSent`] and [`WsEvent::FrameReceived`] events are
+    /// returned. WebSockets opened before this was called are ignored, since their URLs
+    /// are not known.
+    ///
+    /// Returns an `InvalidArgument` error if the pattern is not valid. See
+    /// [`validate_url_pattern`](super::validate_url_pattern).
+    #[cfg(feature = "cdp-events")]
+    pub async fn collect_ws_frames(
+        &self,
+        url_filter: &str,
+        duration: Duration,
+    ) -> WebDriverResult<Vec<WsEvent>> {
+        validate_url_pattern(url_filter)?;
+        let mut events = self.subscribe_websockets().await?;
+        let deadline = tokio::time::Instant::now() + duration;
+        let mut matching = HashSet::new();
+        let mut frames = Vec::new();
+        while let Ok(Some(event)) = tokio::time::timeout_at(deadline, events.next()).await {
+            match &event {
+                WsEvent::Created {
+                    request_id,
+                    url,
+                } if url_pattern_matches(url_filter, url) => {
+                    matching.insert(request_id.clone());
+                }
+                WsEvent::FrameSent {
+                    request_id,
+                    ..
+                }
+                | WsEvent::FrameReceived {
+                    request_id,
+                    ..
+                } if matching.contains(request_id) => frames.push(event),
+                _ => {}
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Subscribe to the messages written to the browser console from now on, both by the
+    /// page via the `console` API and by the browser itself, such as failed requests.
+    ///
+    /// This uses the CDP `Runtime.consoleAPICalled` and `Log.entryAdded` events over the
+    /// DevTools connection. See [`ChromeDevTools::connection`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use futures_util::StreamExt;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let mut console = dev_tools.subscribe_console().await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// while let Some(message) = console.try_next() {
+    ///     println!("{message}");
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn subscribe_console(&self) -> WebDriverResult<ConsoleStream> {
+        let connection = self.connection().await?;
+        let stream = EventStream::new(
+            connection.subscribe(&["Runtime.consoleAPICalled", "Log.entryAdded"]),
+            ConsoleMessage::from_event,
+        );
+        // Enabling a domain that is already enabled has no effect.
+        connection.send("Runtime.enable", json!({})).await?;
+        connection.send("Log.enable", json!({})).await?;
+        Ok(stream)
+    }
+
+    /// Get the messages written to the browser console since this was last called, and
+    /// remove them from the buffer.
+    ///
+    /// The first call returns the messages the browser still has from before it was
+    /// called, such as those written while the current page loaded, and messages are
+    /// recorded from then on. See `WebDriver::assert_no_console_errors()`.
+    #[cfg(feature = "cdp-events")]
+    pub async fn drain_console(&self) -> WebDriverResult<Vec<ConsoleMessage>> {
+        let connection = self.connection().await?;
+        let mut recorded = connection.console.lock().await;
+        let console = match recorded.as_mut() {
+            Some(x) => x,
+            // Enabling the console reports the messages the browser already has.
+            None => recorded.insert(self.subscribe_console().await?),
+        };
+        // The browser sends events in order, so every message written before this
+        // command was sent arrives before its result.
+        connection.send("Runtime.evaluate", json!({ "expression": "0" })).await?;
+        Ok(std::iter::from_fn(|| console.try_next()).collect())
+    }
+
+    /// Return a `JavascriptError` listing the error-level messages written to the browser
+    /// console since this was last called, if there are any.
+    ///
+    /// See [`ChromeDevTools::drain_console`].
+    #[cfg(feature = "cdp-events")]
+    pub async fn assert_no_console_errors(&self) -> WebDriverResult<()> {
+        let errors: Vec<String> = self
+            .drain_console()
+            .await?
+            .into_iter()
+            .filter(|x| x.level == ConsoleLevel::Error)
+            .map(|x| format!("  {x}"))
+            .collect();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(WebDriverError::JavascriptError(WebDriverErrorInfo::new(format!(
+            "{} console error(s):\n{}",
+            errors.len(),
+            errors.join("\n")
+        ))))
+    }
+
+    /// Subscribe to the uncaught exceptions and unhandled promise rejections in the page
+    /// from now on.
+    ///
+    /// This uses the CDP `Runtime.exceptionThrown` event over the DevTools connection.
+    /// See [`ChromeDevTools::connection`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let mut exceptions = dev_tools.subscribe_exceptions().await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// if let Some(exception) = exceptions.try_next() {
+    ///     println!("{exception}\n{}", exception.stack.as_deref().unwrap_or_default());
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn subscribe_exceptions(&self) -> WebDriverResult<ExceptionStream> {
+        let connection = self.connection().await?;
+        let stream = EventStream::new(
+            connection.subscribe(&["Runtime.exceptionThrown"]),
+            JsException::from_event,
+        );
+        connection.send("Runtime.enable", json!({})).await?;
+        Ok(stream)
+    }
+
+    /// Start recording which JavaScript code runs, using the CDP `Profiler` domain.
+    ///
+    /// Block coverage is only recorded for scripts compiled after this is called, so call
+    /// it before navigating to the page under test. Coverage is recorded for the current
+    /// document only: the scripts of a document are discarded when the browser navigates
+    /// away from it, and their coverage with them. To cover several pages, call
+    /// `stop_js_coverage()` before each navigation and restart coverage afterwards.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, JsCoverageOptions};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.start_js_coverage(&JsCoverageOptions::new()).await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// for script in dev_tools.stop_js_coverage().await? {
+    ///     let unused = script.functions.iter().filter(|x| x.count() == 0).count();
+    ///     println!("{}: {unused} function(s) never called", script.url);
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn start_js_coverage(&self, options: &JsCoverageOptions) -> WebDriverResult<()> {
+        let connection = self.connection().await?;
+        connection.send("Profiler.enable", json!({})).await?;
+        connection.send("Profiler.startPreciseCoverage", options.to_params()).await?;
+        *lock(&connection.js_coverage) = Some(options.clone());
+        Ok(())
+    }
+
+    /// Stop recording JavaScript coverage, and return the coverage of each script with a
+    /// URL since `start_js_coverage()` was called.
+    ///
+    /// Scripts without a URL, such as those run via `WebDriver::execute()`, are skipped.
+    #[cfg(feature = "cdp-events")]
+    pub async fn stop_js_coverage(&self) -> WebDriverResult<Vec<ScriptCoverage>> {
+        let connection = self.connection().await?;
+        let options = lock(&connection.js_coverage).take().unwrap_or_default();
+        let mut result = connection.send("Profiler.takePreciseCoverage", json!({})).await?;
+        connection.send("Profiler.stopPreciseCoverage", json!({})).await?;
+        connection.send("Profiler.disable", json!({})).await?;
+
+        let mut scripts: Vec<ScriptCoverage> = serde_json::from_value(result["result"].take())?;
+        scripts.retain(|x| !x.url.is_empty());
+        if options.include_source {
+            // The debugger must be enabled to read script sources, but should not pause.
+            connection.send("Debugger.enable", json!({})).await?;
+            connection.send("Debugger.setSkipAllPauses", json!({ "skip": true })).await?;
+            for script in &mut scripts {
+                // The script may have been discarded since it ran.
+                let source = connection
+                    .send("Debugger.getScriptSource", json!({ "scriptId": script.script_id }))
+                    .await;
+                script.source =
+                    source.ok().and_then(|x| x["scriptSource"].as_str().map(String::from));
+            }
+            connection.send("Debugger.disable", json!({})).await?;
+        }
+        Ok(scripts)
+    }
+
+    /// Start recording which CSS rules are used, using the CDP `CSS` domain.
+    ///
+    /// As with `start_js_coverage()`, coverage is recorded for the current document only,
+    /// so call `stop_css_coverage()` before each navigation and restart coverage
+    /// afterwards.
+    #[cfg(feature = "cdp-events")]
+    pub async fn start_css_coverage(&self) -> WebDriverResult<()> {
+        let connection = self.connection().await?;
+        // The browser reports the existing stylesheets when the CSS domain is enabled.
+        let events = connection.subscribe(&["CSS.styleSheetAdded"]);
+        connection.send("DOM.enable", json!({})).await?;
+        connection.send("CSS.enable", json!({})).await?;
+        connection.send("CSS.startRuleUsageTracking", json!({})).await?;
+        *lock(&connection.style_sheets) = Some(events);
+        Ok(())
+    }
+
+    /// Stop recording CSS coverage, and return the coverage of each stylesheet with rules
+    /// since `start_css_coverage()` was called.
+    #[cfg(feature = "cdp-events")]
+    pub async fn stop_css_coverage(&self) -> WebDriverResult<Vec<CssCoverage>> {
+        let connection = self.connection().await?;
+        let mut result = connection.send("CSS.stopRuleUsageTracking", json!({})).await?;
+        let mut urls = std::collections::HashMap::new();
+        if let Some(mut events) = lock(&connection.style_sheets).take() {
+            while let Some(event) = events.try_next_event() {
+                let header = &event.params["header"];
+                if let Some(id) = header["styleSheetId"].as_str() {
+                    urls.insert(
+                        id.to_string(),
+                        header["sourceURL"].as_str().unwrap_or_default().to_string(),
+                    );
+                }
+            }
+        }
+
+        let mut sheets: Vec<CssCoverage> = Vec::new();
+        for usage in result["ruleUsage"].as_array_mut().map(std::mem::take).unwrap_or_default() {
+            let id = usage["styleSheetId"].as_str().unwrap_or_default().to_string();
+            let rule: CssRuleUsage = serde_json::from_value(usage)?;
+            match sheets.iter_mut().find(|x| x.style_sheet_id == id) {
+                Some(sheet) => sheet.rules.push(rule),
+                None => sheets.push(CssCoverage {
+                    url: urls.get(&id).cloned().unwrap_or_default(),
+                    style_sheet_id: id,
+                    rules: vec![rule],
+                    text: None,
+                }),
+            }
+        }
+        for sheet in &mut sheets {
+            sheet.rules.sort_by_key(|x| x.start_offset);
+            // The stylesheet may have been removed since it was used.
+            let text = connection
+                .send("CSS.getStyleSheetText", json!({ "styleSheetId": sheet.style_sheet_id }))
+                .await;
+            sheet.text = text.ok().and_then(|x| x["text"].as_str().map(String::from));
+        }
+        connection.send("CSS.disable", json!({})).await?;
+        connection.send("DOM.disable", json!({})).await?;
+        Ok(sheets)
+    }
+
+    /// Start recording a performance trace of the browser, with the specified trace
+    /// categories, or the browser's default categories if none are specified. See
+    /// [`TraceCategories`](super::TraceCategories) for presets.
+    ///
+    /// Trace events are received over the DevTools connection as they are recorded, and
+    /// are kept in memory, or written to a file if `TracingOptions::with_path()` is set.
+    /// Use `stop_tracing()` to get the trace.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{ChromeDevTools, TraceCategories, TracingOptions};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let options = TracingOptions::new().with_path("trace.json");
+    /// dev_tools.start_tracing(TraceCategories::web_vitals(), &options).await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// // Open trace.json in Perfetto or the DevTools Performance panel.
+    /// dev_tools.stop_tracing().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn start_tracing(
+        &self,
+        categories: &[&str],
+        options: &TracingOptions,
+    ) -> WebDriverResult<()> {
+        let connection = self.connection().await?;
+        let events = subscribe_trace(&connection);
+        connection.send("Tracing.start", options.to_params(categories)).await?;
+        let trace = match start_trace_task(&connection, events, options) {
+            Ok(x) => x,
+            Err(e) => {
+                // The trace file could not be created, so stop the trace nothing will read.
+                let _ = connection.send("Tracing.end", json!({})).await;
+                return Err(e);
+            }
+        };
+        *lock(&connection.trace) = Some(trace);
+        Ok(())
+    }
+
+    /// Stop recording the trace started via `start_tracing()`, and return it.
+    ///
+    /// If the trace was written to a file, this returns the path once the file is
+    /// complete.
+    #[cfg(feature = "cdp-events")]
+    pub async fn stop_tracing(&self) -> WebDriverResult<Trace> {
+        let connection = self.connection().await?;
+        connection.send("Tracing.end", json!({})).await?;
+        let Some(trace) = lock(&connection.trace).take() else {
+            return Err(WebDriverError::UnknownError(WebDriverErrorInfo::new(
+                "tracing was not started via start_tracing()".to_string(),
+            )));
+        };
+        trace.await.unwrap_or_else(|_| {
+            Err(WebDriverError::CommandRecvError("the DevTools connection was closed".to_string()))
+        })
+    }
+
+    /// Execute the specified command without parameters.
+    /// For commands that require parameters, use `execute_cdp_with_params()` instead.
+    ///
+    /// You can find documentation for the available commands here:
+    /// [https://chromedevtools.github.io/devtools-protocol/](https://chromedevtools.github.io/devtools-protocol/])
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    ///
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.execute_cdp("Network.clearBrowserCache").await?;
+    ///
+    /// // execute_cdp() can also return values as well.
+    /// let version_info = dev_tools.execute_cdp("Browser.getVersion").await?;
+    /// let user_agent = version_info["userAgent"].as_str().unwrap();
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn execute_cdp(&self, cmd: &str) -> WebDriverResult<Value> {
+        self.execute_cdp_with_params(cmd, json!({})).await
+    }
+
+    /// Execute the specified command with the specified parameter(s).
+    ///
+    /// You can find documentation for the available commands here:
+    /// [https://chromedevtools.github.io/devtools-protocol/](https://chromedevtools.github.io/devtools-protocol/])
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// use serde_json::json;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.execute_cdp_with_params("Network.setCacheDisabled", json!({"cacheDisabled": true})).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn execute_cdp_with_params(
+        &self,
+        cmd: &str,
+        cmd_args: Value,
+    ) -> WebDriverResult<Value> {
+        let v =
+            self.handle.cmd(ChromeCommand::ExecuteCdpCommand(cmd.to_string(), cmd_args)).await?;
+        v.value()
+    }
+
+    /// Set the specified cookie via the CDP `Network.setCookie` command.
+    ///
+    /// Unlike `WebDriver::add_cookie()`, this supports partitioned (CHIPS) cookies
+    /// via [`Cookie::set_partition_key`]. If the cookie has no domain, it will be scoped
+    /// to the current URL.
+    pub async fn set_cookie(&self, cookie: &Cookie) -> WebDriverResult<()> {
+        let url = self.handle.current_url().await?;
+        self.set_cookie_with_params(&cookie.name, set_cookie_params(cookie, &url)).await
+    }
+
+    /// Set the specified cookie via the CDP `Network.setCookie` command, including the
+    /// fields that `WebDriver::add_cookie()` does not support, such as
+    /// [`CdpCookie::http_only`] and [`CdpCookie::priority`]. If the cookie has no
+    /// domain, it will be scoped to the current URL.
+    ///
+    /// Returns an `UnableToSetCookie` error naming the cookie if the browser rejects it,
+    /// for example because it has `SameSite=None` without `secure`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::{CdpCookie, ChromeDevTools};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.goto("https://example.com").await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let mut session = CdpCookie::new("session", "secret");
+    /// session.http_only = true;
+    /// session.secure = true;
+    /// dev_tools.set_cookie_cdp(&session).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_cookie_cdp(&self, cookie: &CdpCookie) -> WebDriverResult<()> {
+        let url = self.handle.current_url().await?;
+        self.set_cookie_with_params(&cookie.name, cookie.to_set_params(&url)?).await
+    }
+
+    async fn set_cookie_with_params(&self, name: &str, params: Value) -> WebDriverResult<()> {
+        let unable = |detail: &str| {
+            WebDriverError::UnableToSetCookie(WebDriverErrorInfo::new(format!(
+                "unable to set cookie {name:?} via CDP{detail}"
+            )))
+        };
+        let ret = match self.execute_cdp_with_params("Network.setCookie", params).await {
+            Ok(ret) => ret,
+            // Newer versions of chrome report invalid cookies as command errors.
+            Err(e) => match e.as_inner() {
+                WebDriverErrorInner::UnknownError(info) => {
+                    return Err(unable(&format!(": {}", info.value.message)))
+                }
+                _ => return Err(e),
+            },
+        };
+        // Older versions of chrome report failure via the `success` field.
+        if ret["success"].as_bool() == Some(false) {
+            return Err(unable(""));
+        }
+        Ok(())
+    }
+
+    /// Get all cookies visible to the current page via the CDP `Network.getCookies` command.
+    ///
+    /// Unlike `WebDriver::get_all_cookies()`, this includes the partition key of
+    /// any partitioned (CHIPS) cookies.
+    pub async fn get_all_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
+        let mut ret = self.execute_cdp("Network.getCookies").await?;
+        let cookies: Vec<CdpCookie> = serde_json::from_value(ret["cookies"].take())?;
+        Ok(cookies.into_iter().map(Cookie::from).collect())
+    }
+
+    /// Get the cookies for the specified URLs via the CDP `Network.getCookies` command,
+    /// or every cookie in the browser if `urls` is `None`, including cookies for other
+    /// domains and `httpOnly` cookies.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// let sso = dev_tools.get_all_cookies_cdp(Some(&["https://login.example.com"])).await?;
+    /// let all = dev_tools.get_all_cookies_cdp(None).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn get_all_cookies_cdp(
+        &self,
+        urls: Option<&[&str]>,
+    ) -> WebDriverResult<Vec<CdpCookie>> {
+        let mut ret = match urls {
+            Some(urls) => {
+                self.execute_cdp_with_params("Network.getCookies", json!({ "urls": urls })).await?
+            }
+            None => self.execute_cdp("Storage.getCookies").await?,
+        };
+        Ok(serde_json::from_value(ret["cookies"].take())?)
+    }
+
+    /// Get the list of sinks available for cast.
+    pub async fn get_sinks(&self) -> WebDriverResult<Value> {
+        let v = self.handle.cmd(ChromeCommand::GetSinks).await?;
+        v.value()
+    }
+
+    /// Get the issue message for any issue in a cast session.
+    pub async fn get_issue_message(&self) -> WebDriverResult<Value> {
+        let v = self.handle.cmd(ChromeCommand::GetIssueMessage).await?;
+        v.value()
+    }
+
+    /// Set the specified sink as the cast session receiver target.
+    pub async fn set_sink_to_use(&self, sink_name: &str) -> WebDriverResult<()> {
+        self.handle.cmd(ChromeCommand::SetSinkToUse(sink_name.to_string())).await?;
+        Ok(())
+    }
+
+    /// Start a tab mirroring session on the specified receiver target.
+    pub async fn start_tab_mirroring(&self, sink_name: &str) -> WebDriverResult<()> {
+        self.handle.cmd(ChromeCommand::StartTabMirroring(sink_name.to_string())).await?;
+        Ok(())
+    }
+
+    /// Stop the existing cast session on the specified receiver target.
+    pub async fn stop_casting(&self, sink_name: &str) -> WebDriverResult<()> {
+        self.handle.cmd(ChromeCommand::StopCasting(sink_name.to_string())).await?;
+        Ok(())
+    }
+}
+
+/// Convert the error from a CDP command to an `UnsupportedOperation` error if the
+/// browser does not support CDP commands, such as Firefox.
+fn unsupported_command(feature: &str, e: WebDriverError) -> WebDriverError {
+    match e.as_inner() {
+        WebDriverErrorInner::UnknownCommand(_)
+        | WebDriverErrorInner::UnknownMethod(_)
+        | WebDriverErrorInner::UnknownResponse(..) => {
+            WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(format!(
+                "{feature} via CDP require a Chromium-based browser: {e}"
+            )))
+        }
+        _ => e,
+    }
 }
@@ -0,0 +1,420 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::ElementRect;
+
+/// The `nodeType` of text nodes.
+const TEXT_NODE: u32 = 3;
+
+/// Options for `ChromeDevTools::dom_snapshot()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DomSnapshotOptions {
+    /// The computed styles to capture for each rendered node, for example `display`.
+    pub computed_styles: Vec<String>,
+    /// Capture the offset, scroll and client rectangles of each rendered node, in addition
+    /// to its bounding box.
+    pub include_dom_rects: bool,
+}
+
+impl DomSnapshotOptions {
+    /// Create new `DomSnapshotOptions`, without computed styles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the computed styles to capture for each rendered node.
+    pub fn with_computed_styles(mut self, styles: &[&str]) -> Self {
+        self.computed_styles = styles.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Set whether to capture the offset, scroll and client rectangles of each node.
+    pub fn with_dom_rects(mut self, include_dom_rects: bool) -> Self {
+        self.include_dom_rects = include_dom_rects;
+        self
+    }
+
+    /// Build the parameters for the CDP `DOMSnapshot.captureSnapshot` command.
+    pub(crate) fn to_params(&self) -> Value {
+        json!({
+            "computedStyles": self.computed_styles,
+            "includeDOMRects": self.include_dom_rects,
+        })
+    }
+}
+
+/// A snapshot of the DOM and layout of the page and its frames, returned by
+/// `ChromeDevTools::dom_snapshot()`.
+///
+/// Unlike the page source, this includes the state of form fields, the layout of each
+/// rendered node and its computed styles, and the contents of frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomSnapshot {
+    /// The documents in the snapshot. The first is the page, followed by its frames.
+    pub documents: Vec<SnapshotDocument>,
+    /// The names of the computed styles in [`NodeLayout::styles`].
+    pub computed_styles: Vec<String>,
+}
+
+/// A document in a [`DomSnapshot`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDocument {
+    /// The URL of the document.
+    pub url: String,
+    /// The title of the document.
+    pub title: String,
+    /// The id of the frame that contains the document.
+    pub frame_id: String,
+    /// The nodes of the document, in document order. The first is the document itself.
+    pub nodes: Vec<SnapshotNode>,
+}
+
+/// A node in a [`SnapshotDocument`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotNode {
+    /// The index of the document that contains this node in [`DomSnapshot::documents`].
+    pub document: usize,
+    /// The index of this node in [`SnapshotDocument::nodes`].
+    pub index: usize,
+    /// The index of the parent node, or `None` for the document itself.
+    pub parent: Option<usize>,
+    /// The DOM `nodeType`, for example 1 for elements and 3 for text.
+    pub node_type: u32,
+    /// The DOM `nodeName`, for example `DIV` or `#text`.
+    pub name: String,
+    /// The DOM `nodeValue`, for example the text of a text node.
+    pub value: String,
+    /// The id of the node in the CDP `DOM` domain.
+    pub backend_node_id: i64,
+    /// The attributes of the element, in order.
+    pub attributes: Vec<(String, String)>,
+    /// The current value of a `textarea`, if this is one.
+    pub text_value: Option<String>,
+    /// The current value of an `input`, if this is one.
+    pub input_value: Option<String>,
+    /// True if this is a checked checkbox or radio button.
+    pub input_checked: bool,
+    /// True if this is a selected `option`.
+    pub option_selected: bool,
+    /// The index of the document in this frame in [`DomSnapshot::documents`], if this is a
+    /// frame.
+    pub content_document: Option<usize>,
+    /// The layout of the node, if it is rendered.
+    pub layout: Option<NodeLayout>,
+}
+
+impl SnapshotNode {
+    /// The value of the specified attribute, if the node has it.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(x, _)| x == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// The layout of a rendered [`SnapshotNode`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeLayout {
+    /// The bounding box of the node, relative to the document.
+    pub bounds: ElementRect,
+    /// The values of the computed styles, in the order of
+    /// [`DomSnapshot::computed_styles`].
+    pub styles: Vec<String>,
+    /// The rendered text of the node, if it is a text node.
+    pub text: String,
+}
+
+impl DomSnapshot {
+    /// Parse the result of the CDP `DOMSnapshot.captureSnapshot` command, resolving the
+    /// indexes into its string table.
+    pub(crate) fn from_cdp(result: Value, computed_styles: Vec<String>) -> WebDriverResult<Self> {
+        let raw: RawSnapshot = serde_json::from_value(result)?;
+        let strings = StringTable(&raw.strings);
+        let documents = raw
+            .documents
+            .iter()
+            .enumerate()
+            .map(|(i, x)| x.resolve(i, &strings))
+            .collect::<WebDriverResult<_>>()?;
+        Ok(Self {
+            documents,
+            computed_styles,
+        })
+    }
+
+    /// Every node in every document with the specified attribute value, for example
+    /// `data-testid` and `checkout`.
+    pub fn find_nodes_by_attribute(&self, name: &str, value: &str) -> Vec<&SnapshotNode> {
+        self.documents
+            .iter()
+            .flat_map(|x| &x.nodes)
+            .filter(|x| x.attribute(name) == Some(value))
+            .collect()
+    }
+
+    /// The text of the node and its descendants, like the DOM `textContent` property.
+    pub fn text_of(&self, node: &SnapshotNode) -> String {
+        let Some(document) = self.documents.get(node.document) else {
+            return String::new();
+        };
+        let nodes = &document.nodes;
+        if node.node_type == TEXT_NODE {
+            return node.value.clone();
+        }
+        // Nodes are in document order, so the descendants follow the node.
+        let is_descendant = |mut index: usize| loop {
+            match nodes[index].parent {
+                Some(parent) if parent == node.index => return true,
+                Some(parent) if parent > node.index => index = parent,
+                _ => return false,
+            }
+        };
+        (node.index + 1..nodes.len())
+            .take_while(|x| is_descendant(*x))
+            .filter(|x| nodes[*x].node_type == TEXT_NODE)
+            .map(|x| nodes[x].value.as_str())
+            .collect()
+    }
+
+    /// The value of the specified computed style of the node, if it is rendered and the
+    /// style was captured.
+    pub fn computed_style<'a>(&self, node: &'a SnapshotNode, name: &str) -> Option<&'a str> {
+        let index = self.computed_styles.iter().position(|x| x == name)?;
+        node.layout.as_ref()?.styles.get(index).map(String::as_str)
+    }
+
+    /// Write the snapshot to the specified file as JSON, with the strings resolved.
+    pub fn save_json(&self, path: impl AsRef<Path>) -> WebDriverResult<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`DomSnapshot::save_json`].
+    pub fn load_json(path: impl AsRef<Path>) -> WebDriverResult<Self> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+}
+
+/// The string table of a `DOMSnapshot.captureSnapshot` result. Strings are referred to by
+/// their index, or -1 for none.
+struct StringTable<'a>(&'a [String]);
+
+impl StringTable<'_> {
+    fn get(&self, index: i64) -> WebDriverResult<String> {
+        if index < 0 {
+            return Ok(String::new());
+        }
+        self.0.get(index as usize).cloned().ok_or_else(|| {
+            WebDriverError::ParseError(format!("DOM snapshot: invalid string index {index}"))
+        })
+    }
+
+    fn get_optional(&self, index: Option<&i64>) -> WebDriverResult<Option<String>> {
+        index.map(|x| self.get(*x)).transpose()
+    }
+}
+
+#[derive(Deserialize)]
+struct RawSnapshot {
+    documents: Vec<RawDocument>,
+    strings: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawDocument {
+    #[serde(rename = "documentURL")]
+    document_url: i64,
+    title: i64,
+    frame_id: i64,
+    nodes: RawNodes,
+    layout: RawLayout,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct RawNodes {
+    parent_index: Vec<i64>,
+    node_type: Vec<u32>,
+    node_name: Vec<i64>,
+    node_value: Vec<i64>,
+    backend_node_id: Vec<i64>,
+    attributes: Vec<Vec<i64>>,
+    text_value: RareData<i64>,
+    input_value: RareData<i64>,
+    input_checked: RareData<()>,
+    option_selected: RareData<()>,
+    content_document_index: RareData<usize>,
+}
+
+/// Values for only some nodes, by node index.
+#[derive(Deserialize)]
+#[serde(default)]
+struct RareData<T> {
+    index: Vec<usize>,
+    value: Vec<T>,
+}
+
+impl<T> Default for RareData<T> {
+    fn default() -> Self {
+        Self {
+            index: Vec::new(),
+            value: Vec::new(),
+        }
+    }
+}
+
+impl<T> RareData<T> {
+    fn get(&self, node: usize) -> Option<&T> {
+        self.index.iter().position(|x| *x == node).and_then(|x| self.value.get(x))
+    }
+
+    fn contains(&self, node: usize) -> bool {
+        self.index.contains(&node)
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct RawLayout {
+    node_index: Vec<usize>,
+    styles: Vec<Vec<i64>>,
+    bounds: Vec<Vec<f64>>,
+    text: Vec<i64>,
+}
+
+impl RawDocument {
+    fn resolve(&self, document: usize, strings: &StringTable) -> WebDriverResult<SnapshotDocument> {
+        let raw = &self.nodes;
+        let mut nodes = Vec::with_capacity(raw.node_type.len());
+        for index in 0..raw.node_type.len() {
+            let string = |x: &Vec<i64>| strings.get(x.get(index).copied().unwrap_or(-1));
+            let attributes = raw.attributes.get(index).map(Vec::as_slice).unwrap_or_default();
+            nodes.push(SnapshotNode {
+                document,
+                index,
+                parent: raw.parent_index.get(index).and_then(|x| usize::try_from(*x).ok()),
+                node_type: raw.node_type[index],
+                name: string(&raw.node_name)?,
+                value: string(&raw.node_value)?,
+                backend_node_id: raw.backend_node_id.get(index).copied().unwrap_or_default(),
+                attributes: attributes
+                    .chunks(2)
+                    .map(|x| Ok((strings.get(x[0])?, strings.get(*x.get(1).unwrap_or(&-1))?)))
+                    .collect::<WebDriverResult<_>>()?,
+                text_value: strings.get_optional(raw.text_value.get(index))?,
+                input_value: strings.get_optional(raw.input_value.get(index))?,
+                input_checked: raw.input_checked.contains(index),
+                option_selected: raw.option_selected.contains(index),
+                content_document: raw.content_document_index.get(index).copied(),
+                layout: None,
+            });
+        }
+
+        let layout = &self.layout;
+        for (i, node) in layout.node_index.iter().enumerate() {
+            let Some(node) = nodes.get_mut(*node) else {
+                continue;
+            };
+            let bounds = match layout.bounds.get(i).map(Vec::as_slice) {
+                Some([x, y, width, height]) => ElementRect {
+                    x: *x,
+                    y: *y,
+                    width: *width,
+                    height: *height,
+                },
+                _ => ElementRect {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 0.0,
+                    height: 0.0,
+                },
+            };
+            let styles = layout.styles.get(i).map(Vec::as_slice).unwrap_or_default();
+            node.layout = Some(NodeLayout {
+                bounds,
+                styles: styles.iter().map(|x| strings.get(*x)).collect::<WebDriverResult<_>>()?,
+                text: strings.get(layout.text.get(i).copied().unwrap_or(-1))?,
+            });
+        }
+
+        Ok(SnapshotDocument {
+            url: strings.get(self.document_url)?,
+            title: strings.get(self.title)?,
+            frame_id: strings.get(self.frame_id)?,
+            nodes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> DomSnapshot {
+        let result = json!({
+            "strings": [
+                "http://localhost/", "Title", "frame-1", "#document", "HTML", "BODY", "DIV",
+                "data-testid", "greeting", "#text", "Hello, ", "SPAN", "world", "block", "inline",
+                "id", "INPUT", "typed",
+            ],
+            "documents": [{
+                "documentURL": 0,
+                "title": 1,
+                "frameId": 2,
+                "nodes": {
+                    "parentIndex": [-1, 0, 1, 2, 3, 3, 5, 2],
+                    "nodeType": [9, 1, 1, 1, 3, 1, 3, 1],
+                    "nodeName": [3, 4, 5, 6, 9, 11, 9, 16],
+                    "nodeValue": [-1, -1, -1, -1, 10, -1, 12, -1],
+                    "backendNodeId": [1, 2, 3, 4, 5, 6, 7, 8],
+                    "attributes": [[], [], [], [7, 8], [], [15, 8], [], []],
+                    "inputValue": { "index": [7], "value": [17] },
+                    "inputChecked": { "index": [] },
+                },
+                "layout": {
+                    "nodeIndex": [3, 5],
+                    "styles": [[13], [14]],
+                    "bounds": [[8.0, 8.0, 200.0, 18.5], [50.0, 8.0, 40.0, 18.5]],
+                    "text": [-1, -1],
+                },
+            }],
+        });
+        DomSnapshot::from_cdp(result, vec!["display".to_string()]).unwrap()
+    }
+
+    #[test]
+    fn test_dom_snapshot() {
+        let snapshot = snapshot();
+        let document = &snapshot.documents[0];
+        assert_eq!(
+            (document.url.as_str(), document.title.as_str()),
+            ("http://localhost/", "Title")
+        );
+        assert_eq!(document.nodes[3].name, "DIV");
+        assert_eq!(document.nodes[7].input_value.as_deref(), Some("typed"));
+        assert_eq!(document.nodes[0].parent, None);
+
+        let found = snapshot.find_nodes_by_attribute("data-testid", "greeting");
+        assert_eq!(found.len(), 1);
+        let div = found[0];
+        assert_eq!(snapshot.text_of(div), "Hello, world");
+        assert_eq!(snapshot.computed_style(div, "display"), Some("block"));
+        assert_eq!(snapshot.computed_style(div, "color"), None);
+        assert_eq!(div.layout.as_ref().map(|x| x.bounds.width), Some(200.0));
+        assert_eq!(snapshot.text_of(&document.nodes[5]), "world");
+        assert_eq!(snapshot.text_of(&document.nodes[4]), "Hello, ");
+    }
+
+    #[test]
+    fn test_invalid_string_index() {
+        let result = json!({
+            "strings": [],
+            "documents": [{ "documentURL": 3, "title": -1, "frameId": -1, "nodes": {}, "layout": {} }],
+        });
+        assert!(DomSnapshot::from_cdp(result, Vec::new()).is_err());
+    }
+}
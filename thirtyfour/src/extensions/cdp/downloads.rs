@@ -0,0 +1,303 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
+
+use super::connection::{CdpConnection, CdpEvent, EventStream};
+use crate::error::{TimeoutErrorBuilder, WebDriverError, WebDriverResult};
+
+/// The state of a download, reported via [`DownloadEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DownloadState {
+    /// The download is about to begin.
+    Started,
+    /// Some of the file has been received.
+    InProgress,
+    /// The whole file has been received.
+    Completed,
+    /// The download was cancelled, by the page, the user or `Browser.cancelDownload`.
+    Canceled,
+}
+
+/// A change in the state of a download, from the CDP `Browser.downloadWillBegin` and
+/// `Browser.downloadProgress` events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadEvent {
+    /// The id of the download.
+    pub guid: String,
+    /// The URL of the file. Only reported when the download starts.
+    pub url: Option<String>,
+    /// The name the page suggested for the file. Only reported when the download starts.
+    pub suggested_filename: Option<String>,
+    /// The number of bytes received so far.
+    pub received_bytes: u64,
+    /// The size of the file in bytes, or 0 if it is not known.
+    pub total_bytes: u64,
+    /// The state of the download.
+    pub state: DownloadState,
+}
+
+impl DownloadEvent {
+    /// Get the download event from a `Browser.downloadWillBegin` or
+    /// `Browser.downloadProgress` event.
+    pub(crate) fn from_event(event: &CdpEvent) -> Option<Self> {
+        let params = &event.params;
+        let string = |name: &str| params[name].as_str().map(String::from);
+        let bytes = |name: &str| params[name].as_f64().unwrap_or_default().max(0.0) as u64;
+        let state = match event.method.as_str() {
+            "Browser.downloadWillBegin" => DownloadState::Started,
+            "Browser.downloadProgress" => match params["state"].as_str()? {
+                "inProgress" => DownloadState::InProgress,
+                "completed" => DownloadState::Completed,
+                "canceled" => DownloadState::Canceled,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(Self {
+            guid: string("guid")?,
+            url: string("url"),
+            suggested_filename: string("suggestedFilename"),
+            received_bytes: bytes("receivedBytes"),
+            total_bytes: bytes("totalBytes"),
+            state,
+        })
+    }
+}
+
+/// A stream of the changes in the state of downloads, returned by
+/// `ChromeDevTools::subscribe_downloads()`.
+pub type DownloadStream = EventStream<DownloadEvent>;
+
+/// The latest state of each download that has not been returned by
+/// `wait_for_download_complete()` yet, in the order they started, including the URL and
+/// filename from when they started.
+#[derive(Debug, Default)]
+struct Downloads {
+    downloads: Vec<DownloadEvent>,
+    /// True once the DevTools connection is closed, so no more events will be recorded.
+    closed: bool,
+}
+
+impl Downloads {
+    fn record(&mut self, mut event: DownloadEvent) {
+        match self.downloads.iter_mut().find(|x| x.guid == event.guid) {
+            Some(previous) => {
+                event.url = event.url.or_else(|| previous.url.take());
+                event.suggested_filename =
+                    event.suggested_filename.or_else(|| previous.suggested_filename.take());
+                *previous = event;
+            }
+            None => self.downloads.push(event),
+        }
+    }
+
+    /// The index of the download with the specified guid, or else of the newest download
+    /// with the specified suggested filename.
+    fn position(&self, guid_or_filename: &str) -> Option<usize> {
+        self.downloads.iter().position(|x| x.guid == guid_or_filename).or_else(|| {
+            self.downloads
+                .iter()
+                .rposition(|x| x.suggested_filename.as_deref() == Some(guid_or_filename))
+        })
+    }
+
+    /// The download with the specified guid, or else the newest download with the
+    /// specified suggested filename, if one has started.
+    fn find(&self, guid_or_filename: &str) -> Option<&DownloadEvent> {
+        self.position(guid_or_filename).map(|i| &self.downloads[i])
+    }
+
+    /// Remove and return the download found via `find()`, if it has completed or been
+    /// cancelled.
+    fn take_finished(&mut self, guid_or_filename: &str) -> Option<DownloadEvent> {
+        let i = self.position(guid_or_filename)?;
+        match self.downloads[i].state {
+            DownloadState::Completed | DownloadState::Canceled => Some(self.downloads.remove(i)),
+            DownloadState::Started | DownloadState::InProgress => None,
+        }
+    }
+}
+
+/// The downloads seen on a connection since `ChromeDevTools::set_download_behavior()`
+/// enabled download events, recorded by a task until this is dropped.
+#[derive(Debug)]
+pub(crate) struct DownloadTracker {
+    downloads: Arc<watch::Sender<Downloads>>,
+    task: AbortHandle,
+}
+
+impl DownloadTracker {
+    pub(crate) fn new(connection: &CdpConnection, mut events: DownloadStream) -> Self {
+        let downloads = Arc::new(watch::Sender::new(Downloads::default()));
+        let sender = downloads.clone();
+        let task = connection.spawn(async move {
+            while let Some(event) = events.next().await {
+                sender.send_modify(|x| x.record(event));
+            }
+            sender.send_modify(|x| x.closed = true);
+        });
+        Self {
+            downloads,
+            task,
+        }
+    }
+
+    /// A handle for waiting on the downloads, without borrowing the tracker.
+    pub(crate) fn waiter(&self) -> DownloadWaiter {
+        DownloadWaiter {
+            downloads: self.downloads.clone(),
+        }
+    }
+}
+
+impl Drop for DownloadTracker {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Waits on the downloads recorded by a [`DownloadTracker`].
+#[derive(Debug, Clone)]
+pub(crate) struct DownloadWaiter {
+    downloads: Arc<watch::Sender<Downloads>>,
+}
+
+impl DownloadWaiter {
+    /// Wait until the download with the specified guid, or else the newest download with
+    /// the specified suggested filename, completes, returning a `DownloadCanceled` error
+    /// if it is cancelled.
+    ///
+    /// Each download is only returned once, so waiting for the same filename again waits
+    /// for the next download of it.
+    pub(crate) async fn wait_for_complete(
+        &self,
+        guid_or_filename: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<DownloadEvent> {
+        let mut wait = TimeoutErrorBuilder::new(format!("download {guid_or_filename:?}"));
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut changes = self.downloads.subscribe();
+        loop {
+            wait.attempt();
+            let closed = {
+                let downloads = changes.borrow_and_update();
+                match downloads.find(guid_or_filename) {
+                    Some(x) => {
+                        wait.observed(format!("{} of {} bytes", x.received_bytes, x.total_bytes))
+                    }
+                    None => wait.observed("no such download"),
+                }
+                downloads.closed
+            };
+            let mut finished = None;
+            self.downloads.send_if_modified(|x| {
+                finished = x.take_finished(guid_or_filename);
+                false
+            });
+            match finished {
+                Some(x) if x.state == DownloadState::Canceled => {
+                    return Err(WebDriverError::DownloadCanceled(format!(
+                        "download {guid_or_filename:?} from {} was cancelled after {} bytes",
+                        x.url.as_deref().unwrap_or("unknown URL"),
+                        x.received_bytes
+                    )));
+                }
+                Some(x) => return Ok(x),
+                None if closed => {
+                    return Err(WebDriverError::CommandRecvError(
+                        "the DevTools connection was closed before the download completed"
+                            .to_string(),
+                    ))
+                }
+                None => {}
+            }
+            if tokio::time::timeout_at(deadline, changes.changed()).await.is_err() {
+                return Err(wait.build());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn event(method: &str, params: serde_json::Value) -> CdpEvent {
+        CdpEvent {
+            method: method.to_string(),
+            params,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn test_download_event() {
+        let started = DownloadEvent::from_event(&event(
+            "Browser.downloadWillBegin",
+            json!({
+                "frameId": "main",
+                "guid": "1234",
+                "url": "http://localhost/report.csv",
+                "suggestedFilename": "report.csv",
+            }),
+        ))
+        .unwrap();
+        assert_eq!(started.state, DownloadState::Started);
+        assert_eq!(started.suggested_filename.as_deref(), Some("report.csv"));
+
+        let progress = json!({ "guid": "1234", "totalBytes": 100, "receivedBytes": 40, "state": "inProgress" });
+        let progress =
+            DownloadEvent::from_event(&event("Browser.downloadProgress", progress)).unwrap();
+        assert_eq!(progress.state, DownloadState::InProgress);
+        assert_eq!((progress.received_bytes, progress.total_bytes), (40, 100));
+        assert_eq!(progress.url, None);
+
+        let unknown = json!({ "guid": "1234", "state": "paused" });
+        assert_eq!(DownloadEvent::from_event(&event("Browser.downloadProgress", unknown)), None);
+    }
+
+    #[test]
+    fn test_downloads() {
+        let started = |guid: &str| DownloadEvent {
+            guid: guid.to_string(),
+            url: Some(format!("http://localhost/{guid}/report.csv")),
+            suggested_filename: Some("report.csv".to_string()),
+            received_bytes: 0,
+            total_bytes: 0,
+            state: DownloadState::Started,
+        };
+        let completed = |guid: &str| DownloadEvent {
+            guid: guid.to_string(),
+            url: None,
+            suggested_filename: None,
+            received_bytes: 100,
+            total_bytes: 100,
+            state: DownloadState::Completed,
+        };
+        let mut downloads = Downloads::default();
+        downloads.record(started("1234"));
+        downloads.record(completed("1234"));
+        let found = downloads.find("report.csv").unwrap();
+        assert_eq!(found.state, DownloadState::Completed);
+        assert_eq!(found.url.as_deref(), Some("http://localhost/1234/report.csv"));
+        assert_eq!(downloads.find("1234"), Some(found));
+        assert_eq!(downloads.find("other.csv"), None);
+
+        // The newest download of a file is found, and finished downloads are only
+        // returned once.
+        downloads.record(started("5678"));
+        assert_eq!(downloads.find("report.csv").unwrap().guid, "5678");
+        assert_eq!(downloads.take_finished("report.csv"), None);
+        assert_eq!(downloads.take_finished("1234").unwrap().guid, "1234");
+        assert_eq!(downloads.find("1234"), None);
+        downloads.record(completed("5678"));
+        let taken = downloads.take_finished("report.csv").unwrap();
+        assert_eq!(taken.url.as_deref(), Some("http://localhost/5678/report.csv"));
+        assert_eq!(downloads.find("report.csv"), None);
+    }
+}
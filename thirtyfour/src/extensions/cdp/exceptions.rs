@@ -0,0 +1,202 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+
+use super::connection::{CdpEvent, EventStream};
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// The message browsers report instead of the real one for errors thrown by scripts from
+/// another origin that were loaded without CORS.
+const MASKED_MESSAGE: &str = "Script error.";
+
+/// An uncaught JavaScript exception or unhandled promise rejection in the page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsException {
+    /// The exception message, for example `TypeError: x is undefined`.
+    pub message: String,
+    /// The stack trace, if known.
+    pub stack: Option<String>,
+    /// The URL of the script that threw the exception, if known.
+    pub url: Option<String>,
+    /// The one-based line number in `url`, if known.
+    pub line: Option<u32>,
+    /// The one-based column number in `url`, if known.
+    pub column: Option<u32>,
+    /// True if this was an unhandled promise rejection rather than a thrown exception.
+    pub unhandled_rejection: bool,
+    /// True if the browser may have hidden the real exception, because it was thrown by
+    /// a script from another origin that was loaded without CORS. The message is then
+    /// just `Script error.`
+    pub possibly_masked: bool,
+    /// When the exception was thrown.
+    pub timestamp: SystemTime,
+}
+
+impl JsException {
+    /// Get the exception from a `Runtime.exceptionThrown` event.
+    pub(crate) fn from_event(event: &CdpEvent) -> Option<Self> {
+        if event.method != "Runtime.exceptionThrown" {
+            return None;
+        }
+        let details = &event.params["exceptionDetails"];
+        let exception = &details["exception"];
+        let text = details["text"].as_str().unwrap_or_default();
+        let description = exception["description"].as_str().filter(|x| !x.is_empty());
+
+        let message = match (description, &exception["value"]) {
+            (Some(description), _) => description.lines().next().unwrap_or_default().to_string(),
+            (None, Value::String(value)) => value.clone(),
+            (None, Value::Null) => text.to_string(),
+            (None, value) => value.to_string(),
+        };
+        // Error descriptions include the stack trace after the message.
+        let stack = match description {
+            Some(description) if description.contains('\n') => Some(description.to_string()),
+            _ => format_stack(&details["stackTrace"]),
+        };
+        let position = |x: &Value| x.as_u64().and_then(|x| u32::try_from(x + 1).ok());
+        let millis = event.params["timestamp"]
+            .as_f64()
+            .filter(|x| x.is_finite() && *x >= 0.0)
+            .unwrap_or_default();
+
+        Some(Self {
+            possibly_masked: message == MASKED_MESSAGE || text == MASKED_MESSAGE,
+            unhandled_rejection: text.contains("(in promise)"),
+            message,
+            stack,
+            url: details["url"].as_str().filter(|x| !x.is_empty()).map(String::from),
+            line: position(&details["lineNumber"]),
+            column: position(&details["columnNumber"]),
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs_f64(millis / 1000.0),
+        })
+    }
+}
+
+/// Format a CDP `Runtime.StackTrace` the way V8 does.
+fn format_stack(stack_trace: &Value) -> Option<String> {
+    let frames = stack_trace["callFrames"].as_array().filter(|x| !x.is_empty())?;
+    let lines: Vec<String> = frames
+        .iter()
+        .map(|frame| {
+            let name = frame["functionName"].as_str().filter(|x| !x.is_empty());
+            let location = format!(
+                "{}:{}:{}",
+                frame["url"].as_str().unwrap_or_default(),
+                frame["lineNumber"].as_u64().unwrap_or_default() + 1,
+                frame["columnNumber"].as_u64().unwrap_or_default() + 1
+            );
+            match name {
+                Some(name) => format!("    at {name} ({location})"),
+                None => format!("    at {location}"),
+            }
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+impl Display for JsException {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.unhandled_rejection {
+            write!(f, "unhandled rejection: {}", self.message)?;
+        } else {
+            write!(f, "uncaught exception: {}", self.message)?;
+        }
+        match (&self.url, self.line, self.column) {
+            (Some(url), Some(line), Some(column)) => write!(f, " ({url}:{line}:{column})")?,
+            (Some(url), _, _) => write!(f, " ({url})")?,
+            _ => {}
+        }
+        if self.possibly_masked {
+            write!(f, " [possibly masked: the script may be from another origin without CORS]")?;
+        }
+        Ok(())
+    }
+}
+
+/// A stream of the uncaught exceptions in the page, returned by
+/// `ChromeDevTools::subscribe_exceptions()`.
+pub type ExceptionStream = EventStream<JsException>;
+
+/// Return a `JavascriptError` listing the exceptions, if there are any.
+pub(crate) fn check_exceptions(exceptions: &[JsException]) -> WebDriverResult<()> {
+    if exceptions.is_empty() {
+        return Ok(());
+    }
+    let list: Vec<String> = exceptions.iter().map(|x| format!("  {x}")).collect();
+    Err(WebDriverError::JavascriptError(WebDriverErrorInfo::new(format!(
+        "{} JavaScript exception(s) in the page:\n{}",
+        exceptions.len(),
+        list.join("\n")
+    ))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn exception(params: Value) -> JsException {
+        JsException::from_event(&CdpEvent {
+            method: "Runtime.exceptionThrown".to_string(),
+            params,
+            session_id: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_js_exception() {
+        let thrown = exception(json!({
+            "timestamp": 1700000000000.0,
+            "exceptionDetails": {
+                "exceptionId": 1,
+                "text": "Uncaught",
+                "lineNumber": 4,
+                "columnNumber": 10,
+                "url": "http://localhost/app.js",
+                "exception": {
+                    "type": "object",
+                    "className": "Error",
+                    "description": "Error: from timer\n    at http://localhost/app.js:5:11",
+                },
+            },
+        }));
+        assert_eq!(thrown.message, "Error: from timer");
+        assert_eq!(
+            thrown.stack.as_deref(),
+            Some("Error: from timer\n    at http://localhost/app.js:5:11")
+        );
+        assert_eq!((thrown.line, thrown.column), (Some(5), Some(11)));
+        assert!(!thrown.unhandled_rejection && !thrown.possibly_masked);
+        assert_eq!(
+            thrown.to_string(),
+            "uncaught exception: Error: from timer (http://localhost/app.js:5:11)"
+        );
+
+        let rejected = exception(json!({
+            "exceptionDetails": {
+                "text": "Uncaught (in promise)",
+                "lineNumber": 0,
+                "columnNumber": 0,
+                "exception": { "type": "string", "value": "nope" },
+                "stackTrace": {
+                    "callFrames": [
+                        { "functionName": "load", "url": "http://localhost/a.js", "lineNumber": 1, "columnNumber": 2 },
+                    ],
+                },
+            },
+        }));
+        assert_eq!(rejected.message, "nope");
+        assert!(rejected.unhandled_rejection);
+        assert_eq!(rejected.stack.as_deref(), Some("    at load (http://localhost/a.js:2:3)"));
+
+        let masked = exception(json!({
+            "exceptionDetails": { "text": "Script error.", "lineNumber": 0, "columnNumber": 0 },
+        }));
+        assert_eq!(masked.message, "Script error.");
+        assert!(masked.possibly_masked);
+        assert!(masked.to_string().contains("possibly masked"));
+    }
+}
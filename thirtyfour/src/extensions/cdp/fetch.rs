@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use tokio::task::AbortHandle;
+
+use super::auth::{auth_response, AuthChallenge, AuthHandlerFn};
+use super::connection::{lock, CdpConnection};
+use super::RequestRules;
+use crate::error::WebDriverResult;
+
+/// The handler set via `ChromeDevTools::set_auth_handler()`, and the URL patterns of the
+/// requests it answers challenges for.
+#[derive(Clone)]
+pub(crate) struct AuthHandler {
+    pub(crate) patterns: Vec<String>,
+    pub(crate) handler: AuthHandlerFn,
+}
+
+/// The handlers for the requests paused by the CDP `Fetch` domain for a connection.
+#[derive(Default)]
+pub(crate) struct FetchHandlers {
+    /// The handler set via `ChromeDevTools::set_auth_handler()`.
+    auth: Mutex<Option<AuthHandler>>,
+    /// The rules applied via `ChromeDevTools::apply_request_rules()` that pause requests.
+    rules: Mutex<Option<RequestRules>>,
+    /// The task that answers paused requests, while requests are paused.
+    task: Mutex<Option<AbortHandle>>,
+}
+
+impl FetchHandlers {
+    fn auth_handler(&self) -> Option<AuthHandlerFn> {
+        lock(&self.auth).as_ref().map(|x| x.handler.clone())
+    }
+
+    pub(crate) fn set_auth(&self, handler: Option<AuthHandler>) {
+        *lock(&self.auth) = handler;
+    }
+
+    pub(crate) fn set_rules(&self, rules: Option<RequestRules>) {
+        *lock(&self.rules) = rules.filter(RequestRules::intercepts);
+    }
+
+    /// Build the parameters for the CDP `Fetch.continueRequest` command for the specified
+    /// `Fetch.requestPaused` event.
+    ///
+    /// Requests paused after the response was received are continued unchanged.
+    fn continue_params(&self, params: &Value) -> Value {
+        match lock(&self.rules).as_ref() {
+            Some(rules) if !is_response_stage(params) => rules.continue_params(params),
+            _ => json!({ "requestId": params["requestId"] }),
+        }
+    }
+
+    /// Build the parameters for the CDP `Fetch.enable` command, or `None` if no requests
+    /// need to be paused.
+    ///
+    /// Requests that may be challenged are paused once the response has been received,
+    /// after any challenges, which shows that the request is no longer being
+    /// authenticated.
+    fn enable_params(&self) -> Option<Value> {
+        let mut patterns = match lock(&self.rules).as_ref() {
+            Some(rules) => rules.fetch_patterns(),
+            None => Vec::new(),
+        };
+        let auth = lock(&self.auth);
+        if let Some(auth) = auth.as_ref() {
+            patterns.extend(
+                auth.patterns
+                    .iter()
+                    .map(|x| json!({ "urlPattern": x, "requestStage": "Response" })),
+            );
+        }
+        (!patterns.is_empty())
+            .then(|| json!({ "handleAuthRequests": auth.is_some(), "patterns": patterns }))
+    }
+}
+
+/// Return true if the `Fetch.requestPaused` event is for a request whose response has
+/// been received.
+fn is_response_stage(params: &Value) -> bool {
+    params.get("responseStatusCode").is_some() || params.get("responseErrorReason").is_some()
+}
+
+/// Pause the requests needed by the current authentication handler and request rules in
+/// every window, starting the task that answers them if necessary, or stop pausing
+/// requests if there are neither.
+///
+/// Requests are paused for the whole browser rather than the attached window, so that
+/// requests from windows opened later are handled too.
+pub(crate) async fn update_fetch(connection: &Arc<CdpConnection>) -> WebDriverResult<()> {
+    let Some(params) = connection.fetch.enable_params() else {
+        let result = connection.send_to_browser("Fetch.disable", json!({})).await;
+        if let Some(task) = lock(&connection.fetch.task).take() {
+            task.abort();
+        }
+        return result.map(|_| ());
+    };
+
+    let started = start_fetch_task(connection);
+    if let Err(e) = connection.send_to_browser("Fetch.enable", params).await {
+        if started {
+            if let Some(task) = lock(&connection.fetch.task).take() {
+                task.abort();
+            }
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Start answering the `Fetch` events for the connection, if that has not already
+/// been started. Returns true if the task was started.
+///
+/// Paused requests are continued with the current request rules applied, and
+/// authentication challenges are passed to the current handler. Challenges are cancelled
+/// if there is no handler, if it returns `None`, or if the credentials it returned were
+/// rejected.
+fn start_fetch_task(connection: &Arc<CdpConnection>) -> bool {
+    let mut task = lock(&connection.fetch.task);
+    if task.is_some() {
+        return false;
+    }
+    let mut events = connection.subscribe(&["Fetch.requestPaused", "Fetch.authRequired"]);
+    let connection_ref = connection.clone();
+    *task = Some(connection.spawn(async move {
+        let connection = connection_ref;
+        // The requests that credentials were provided for, until they are answered.
+        let mut attempted = HashSet::new();
+        while let Some(event) = events.next().await {
+            let request_id = event.params["requestId"].to_string();
+            let (method, params) = match event.method.as_str() {
+                "Fetch.requestPaused" => {
+                    if is_response_stage(&event.params) {
+                        attempted.remove(&request_id);
+                    }
+                    ("Fetch.continueRequest", connection.fetch.continue_params(&event.params))
+                }
+                _ => {
+                    let challenge = AuthChallenge::from_event(&event.params);
+                    // The same request is challenged again if the credentials were wrong.
+                    let credentials = if attempted.insert(request_id.clone()) {
+                        connection.fetch.auth_handler().and_then(|handler| handler(&challenge))
+                    } else {
+                        attempted.remove(&request_id);
+                        None
+                    };
+                    (
+                        "Fetch.continueWithAuth",
+                        json!({
+                            "requestId": event.params["requestId"],
+                            "authChallengeResponse": auth_response(credentials),
+                        }),
+                    )
+                }
+            };
+            let session_id = event.session_id.as_deref();
+            if let Err(e) = connection.send_to_session(session_id, method, params).await {
+                tracing::debug!("{method} failed: {e}");
+            }
+        }
+    }));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enable_params() {
+        let handlers = FetchHandlers::default();
+        assert_eq!(handlers.enable_params(), None);
+
+        handlers.set_auth(Some(AuthHandler {
+            patterns: vec!["http://localhost:8081/*".to_string()],
+            handler: Arc::new(|_| None),
+        }));
+        handlers.set_rules(Some(RequestRules::new().remove_header("*.js", "Cookie")));
+        assert_eq!(
+            handlers.enable_params(),
+            Some(json!({
+                "handleAuthRequests": true,
+                "patterns": [
+                    { "urlPattern": "*.js", "requestStage": "Request" },
+                    { "urlPattern": "http://localhost:8081/*", "requestStage": "Response" },
+                ],
+            }))
+        );
+
+        // Rules that do not pause requests are not kept.
+        handlers.set_auth(None);
+        handlers.set_rules(Some(RequestRules::new().block("*.js")));
+        assert_eq!(handlers.enable_params(), None);
+    }
+
+    #[test]
+    fn test_continue_params() {
+        let handlers = FetchHandlers::default();
+        handlers.set_rules(Some(RequestRules::new().rewrite("*", "http://localhost/")));
+        let mut params = json!({
+            "requestId": "interception-job-1.0",
+            "request": { "url": "http://example.com/", "headers": {} },
+        });
+        assert_eq!(
+            handlers.continue_params(&params),
+            json!({ "requestId": "interception-job-1.0", "url": "http://localhost/" })
+        );
+        params["responseStatusCode"] = json!(200);
+        assert_eq!(
+            handlers.continue_params(&params),
+            json!({ "requestId": "interception-job-1.0" })
+        );
+    }
+}
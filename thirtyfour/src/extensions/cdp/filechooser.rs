@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+
+use super::connection::CdpConnection;
+
+/// A file chooser that the page opened, passed to the handler set via
+/// `ChromeDevTools::set_file_chooser_handler()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChooser {
+    /// True if the chooser accepts several files, as for `<input type=file multiple>`.
+    pub multiple: bool,
+    /// The id of the file input in the CDP `DOM` domain.
+    pub backend_node_id: i64,
+    /// The id of the frame that contains the file input.
+    pub frame_id: String,
+}
+
+impl FileChooser {
+    /// Get the chooser from the parameters of a `Page.fileChooserOpened` event.
+    fn from_event(params: &Value) -> Self {
+        Self {
+            multiple: params["mode"] == "selectMultiple",
+            backend_node_id: params["backendNodeId"].as_i64().unwrap_or_default(),
+            frame_id: params["frameId"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+/// How to answer a [`FileChooser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChooserAction {
+    /// Choose the specified files. The paths must be absolute paths on the machine the
+    /// browser runs on. Only the first file is chosen if the chooser does not accept
+    /// several files.
+    Files(Vec<PathBuf>),
+    /// Close the chooser without choosing any files.
+    Cancel,
+}
+
+impl FileChooserAction {
+    /// Build the parameters for the CDP `DOM.setFileInputFiles` command, or `None` if
+    /// no files should be chosen.
+    fn to_params(&self, chooser: &FileChooser) -> Option<Value> {
+        let files = match self {
+            Self::Files(files) if !files.is_empty() => files,
+            _ => return None,
+        };
+        let count = if chooser.multiple {
+            files.len()
+        } else {
+            1
+        };
+        let files: Vec<String> =
+            files.iter().take(count).map(|x| x.to_string_lossy().into_owned()).collect();
+        Some(json!({ "files": files, "backendNodeId": chooser.backend_node_id }))
+    }
+}
+
+pub(crate) type FileChooserHandlerFn = Arc<dyn Fn(&FileChooser) -> FileChooserAction + Send + Sync>;
+
+/// The file chooser handler for a connection.
+#[derive(Default)]
+pub(crate) struct FileChooserHandlers {
+    handler: Mutex<Option<FileChooserHandlerFn>>,
+    /// True once the task that answers file choosers has been started.
+    started: AtomicBool,
+}
+
+impl FileChooserHandlers {
+    fn get(&self) -> Option<FileChooserHandlerFn> {
+        self.handler.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub(crate) fn set(&self, handler: Option<FileChooserHandlerFn>) {
+        *self.handler.lock().unwrap_or_else(|e| e.into_inner()) = handler;
+    }
+}
+
+/// Start answering the `Page.fileChooserOpened` events for the connection, if that has
+/// not already been started.
+///
+/// Each chooser is passed to the current handler. Choosers are cancelled if there is
+/// no handler.
+pub(crate) fn start_file_chooser_task(connection: &Arc<CdpConnection>) {
+    if connection.file_chooser.started.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let mut events = connection.subscribe(&["Page.fileChooserOpened"]);
+    let connection_ref = connection.clone();
+    connection.spawn(async move {
+        let connection = connection_ref;
+        while let Some(event) = events.next().await {
+            let chooser = FileChooser::from_event(&event.params);
+            let action = match connection.file_chooser.get() {
+                Some(handler) => handler(&chooser),
+                None => FileChooserAction::Cancel,
+            };
+            // The dialog is never shown while choosers are intercepted, so there is
+            // nothing to do to cancel it.
+            let Some(params) = action.to_params(&chooser) else {
+                continue;
+            };
+            let session_id = event.session_id.as_deref();
+            if let Err(e) =
+                connection.send_to_session(session_id, "DOM.setFileInputFiles", params).await
+            {
+                tracing::debug!("DOM.setFileInputFiles failed: {e}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_chooser() {
+        let params = json!({ "frameId": "main", "mode": "selectSingle", "backendNodeId": 12 });
+        let chooser = FileChooser::from_event(&params);
+        assert_eq!(chooser.backend_node_id, 12);
+        assert_eq!(chooser.frame_id, "main");
+        assert!(!chooser.multiple);
+
+        let action = FileChooserAction::Files(vec!["/tmp/a.txt".into(), "/tmp/b.txt".into()]);
+        assert_eq!(
+            action.to_params(&chooser),
+            Some(json!({ "files": ["/tmp/a.txt"], "backendNodeId": 12 }))
+        );
+        let multiple = FileChooser {
+            multiple: true,
+            ..chooser.clone()
+        };
+        assert_eq!(
+            action.to_params(&multiple).unwrap()["files"],
+            json!(["/tmp/a.txt", "/tmp/b.txt"])
+        );
+        assert_eq!(FileChooserAction::Cancel.to_params(&chooser), None);
+        assert_eq!(FileChooserAction::Files(Vec::new()).to_params(&chooser), None);
+    }
+}
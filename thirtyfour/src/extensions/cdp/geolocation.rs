@@ -0,0 +1,86 @@
+use serde_json::{json, Value};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// A position to report via the geolocation API, set with `ChromeDevTools::set_geolocation()`.
+///
+/// See <https://chromedevtools.github.io/devtools-protocol/tot/Emulation/#method-setGeolocationOverride>.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoLocation {
+    /// The latitude, in degrees. Must be between -90 and 90.
+    pub latitude: f64,
+    /// The longitude, in degrees. Must be between -180 and 180.
+    pub longitude: f64,
+    /// The accuracy of the position, in metres. Must not be negative.
+    pub accuracy: f64,
+}
+
+impl GeoLocation {
+    /// Create a new `GeoLocation`, accurate to within 1 metre.
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            accuracy: 1.0,
+        }
+    }
+
+    /// Set the accuracy, in metres.
+    pub fn with_accuracy(mut self, accuracy: f64) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Return an `InvalidArgument` error if any of the coordinates are out of range.
+    pub fn validate(&self) -> WebDriverResult<()> {
+        let invalid = |message: String| {
+            Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(message)))
+        };
+        if !(-90.0..=90.0).contains(&self.latitude) {
+            return invalid(format!("latitude must be between -90 and 90, got {}", self.latitude));
+        }
+        if !(-180.0..=180.0).contains(&self.longitude) {
+            return invalid(format!(
+                "longitude must be between -180 and 180, got {}",
+                self.longitude
+            ));
+        }
+        if !(self.accuracy >= 0.0 && self.accuracy.is_finite()) {
+            return invalid(format!("accuracy must not be negative, got {}", self.accuracy));
+        }
+        Ok(())
+    }
+
+    /// Build the parameters for the CDP `Emulation.setGeolocationOverride` command.
+    pub(crate) fn to_params(self) -> Value {
+        json!({
+            "latitude": self.latitude,
+            "longitude": self.longitude,
+            "accuracy": self.accuracy,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn test_geolocation_validate() {
+        assert!(GeoLocation::new(-33.8568, 151.2153).validate().is_ok());
+        assert!(GeoLocation::new(90.0, -180.0).with_accuracy(0.0).validate().is_ok());
+
+        for geo in [
+            GeoLocation::new(90.5, 0.0),
+            GeoLocation::new(-91.0, 0.0),
+            GeoLocation::new(f64::NAN, 0.0),
+            GeoLocation::new(0.0, 180.1),
+            GeoLocation::new(0.0, 0.0).with_accuracy(-1.0),
+            GeoLocation::new(0.0, 0.0).with_accuracy(f64::INFINITY),
+        ] {
+            let err = geo.validate().unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidRequest, "{geo:?}");
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use serde_json::{json, Value};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// The media types that can be passed to `ChromeDevTools::set_emulated_media_type()`.
+pub const MEDIA_TYPES: &[&str] = &["screen", "print"];
+
+/// A value of the `prefers-color-scheme` media feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    /// `light`
+    Light,
+    /// `dark`
+    Dark,
+}
+
+/// A value of the `prefers-reduced-motion` media feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReducedMotion {
+    /// `no-preference`
+    NoPreference,
+    /// `reduce`
+    Reduce,
+}
+
+/// A value of the `forced-colors` media feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ForcedColors {
+    /// `none`
+    None,
+    /// `active`
+    Active,
+}
+
+/// A value of the `prefers-contrast` media feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Contrast {
+    /// `no-preference`
+    NoPreference,
+    /// `more`
+    More,
+    /// `less`
+    Less,
+    /// `custom`
+    Custom,
+}
+
+/// A CSS media feature to emulate via `ChromeDevTools::set_media_features()`.
+///
+/// # Example:
+/// ```
+/// use thirtyfour::extensions::cdp::{ColorScheme, MediaFeature};
+///
+/// let feature = MediaFeature::PrefersColorScheme(ColorScheme::Dark);
+/// assert_eq!(MediaFeature::parse("prefers-color-scheme", "dark")?, feature);
+/// assert_eq!(feature.to_string(), "prefers-color-scheme: dark");
+/// # Ok::<(), thirtyfour::error::WebDriverError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaFeature {
+    /// `prefers-color-scheme`, for dark mode.
+    PrefersColorScheme(ColorScheme),
+    /// `prefers-reduced-motion`, for disabling animations.
+    PrefersReducedMotion(ReducedMotion),
+    /// `forced-colors`, for high contrast modes that replace the page's colors.
+    ForcedColors(ForcedColors),
+    /// `prefers-contrast`.
+    PrefersContrast(Contrast),
+}
+
+impl MediaFeature {
+    /// The allowed values of each media feature, by name.
+    const ALLOWED: &'static [(&'static str, &'static [&'static str])] = &[
+        ("prefers-color-scheme", &["light", "dark"]),
+        ("prefers-reduced-motion", &["no-preference", "reduce"]),
+        ("forced-colors", &["none", "active"]),
+        ("prefers-contrast", &["no-preference", "more", "less", "custom"]),
+    ];
+
+    /// Parse a media feature from its name and value, for example
+    /// `prefers-color-scheme` and `dark`.
+    ///
+    /// Returns an `InvalidArgument` error listing the allowed names or values if either
+    /// is not supported.
+    pub fn parse(name: &str, value: &str) -> WebDriverResult<Self> {
+        let invalid = |message: String| {
+            Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(message)))
+        };
+        let Some((_, allowed)) = Self::ALLOWED.iter().find(|(x, _)| *x == name) else {
+            let names: Vec<&str> = Self::ALLOWED.iter().map(|(x, _)| *x).collect();
+            return invalid(format!(
+                "unsupported media feature {name:?}, expected one of {names:?}"
+            ));
+        };
+        let feature = match (name, value) {
+            ("prefers-color-scheme", "light") => Self::PrefersColorScheme(ColorScheme::Light),
+            ("prefers-color-scheme", "dark") => Self::PrefersColorScheme(ColorScheme::Dark),
+            ("prefers-reduced-motion", "no-preference") => {
+                Self::PrefersReducedMotion(ReducedMotion::NoPreference)
+            }
+            ("prefers-reduced-motion", "reduce") => {
+                Self::PrefersReducedMotion(ReducedMotion::Reduce)
+            }
+            ("forced-colors", "none") => Self::ForcedColors(ForcedColors::None),
+            ("forced-colors", "active") => Self::ForcedColors(ForcedColors::Active),
+            ("prefers-contrast", "no-preference") => Self::PrefersContrast(Contrast::NoPreference),
+            ("prefers-contrast", "more") => Self::PrefersContrast(Contrast::More),
+            ("prefers-contrast", "less") => Self::PrefersContrast(Contrast::Less),
+            ("prefers-contrast", "custom") => Self::PrefersContrast(Contrast::Custom),
+            _ => {
+                let message = format!("invalid value {value:?} for media feature {name:?}");
+                return invalid(format!("{message}, expected one of {allowed:?}"));
+            }
+        };
+        Ok(feature)
+    }
+
+    /// The name of the media feature, for example `prefers-color-scheme`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PrefersColorScheme(_) => "prefers-color-scheme",
+            Self::PrefersReducedMotion(_) => "prefers-reduced-motion",
+            Self::ForcedColors(_) => "forced-colors",
+            Self::PrefersContrast(_) => "prefers-contrast",
+        }
+    }
+
+    /// The value of the media feature, for example `dark`.
+    pub fn value(&self) -> &'static str {
+        match self {
+            Self::PrefersColorScheme(ColorScheme::Light) => "light",
+            Self::PrefersColorScheme(ColorScheme::Dark) => "dark",
+            Self::PrefersReducedMotion(ReducedMotion::NoPreference) => "no-preference",
+            Self::PrefersReducedMotion(ReducedMotion::Reduce) => "reduce",
+            Self::ForcedColors(ForcedColors::None) => "none",
+            Self::ForcedColors(ForcedColors::Active) => "active",
+            Self::PrefersContrast(Contrast::NoPreference) => "no-preference",
+            Self::PrefersContrast(Contrast::More) => "more",
+            Self::PrefersContrast(Contrast::Less) => "less",
+            Self::PrefersContrast(Contrast::Custom) => "custom",
+        }
+    }
+}
+
+impl std::fmt::Display for MediaFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name(), self.value())
+    }
+}
+
+/// Return an `InvalidArgument` error if the media type is not one of [`MEDIA_TYPES`].
+pub fn validate_media_type(media_type: &str) -> WebDriverResult<()> {
+    if MEDIA_TYPES.contains(&media_type) {
+        return Ok(());
+    }
+    Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+        "unsupported media type {media_type:?}, expected one of {MEDIA_TYPES:?}"
+    ))))
+}
+
+/// The media type and features set via `ChromeDevTools`.
+///
+/// The browser replaces both with each `Emulation.setEmulatedMedia` command, so both are
+/// sent every time either changes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EmulatedMedia {
+    pub media_type: Option<String>,
+    pub features: Vec<MediaFeature>,
+}
+
+impl EmulatedMedia {
+    /// Build the parameters for the CDP `Emulation.setEmulatedMedia` command.
+    pub(crate) fn to_params(&self) -> Value {
+        let features: Vec<Value> =
+            self.features.iter().map(|x| json!({ "name": x.name(), "value": x.value() })).collect();
+        json!({ "media": self.media_type.as_deref().unwrap_or_default(), "features": features })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_feature() {
+        for (name, values) in MediaFeature::ALLOWED {
+            for value in *values {
+                let feature = MediaFeature::parse(name, value).unwrap();
+                assert_eq!((feature.name(), feature.value()), (*name, *value));
+            }
+        }
+        assert!(MediaFeature::parse("prefers-color-scheme", "blue").is_err());
+        assert!(MediaFeature::parse("prefers-colour-scheme", "dark").is_err());
+        assert!(validate_media_type("print").is_ok());
+        assert!(validate_media_type("tv").is_err());
+    }
+
+    #[test]
+    fn test_emulated_media_params() {
+        let media = EmulatedMedia {
+            media_type: None,
+            features: vec![MediaFeature::PrefersReducedMotion(ReducedMotion::Reduce)],
+        };
+        assert_eq!(
+            media.to_params(),
+            json!({ "media": "", "features": [{ "name": "prefers-reduced-motion", "value": "reduce" }] })
+        );
+    }
+}
@@ -1,7 +1,78 @@
+mod accessibility;
+#[cfg(feature = "cdp-events")]
+mod auth;
 mod chromecommand;
+#[cfg(feature = "cdp-events")]
+mod connection;
+#[cfg(feature = "cdp-events")]
+mod console;
+pub(crate) mod cookies;
+#[cfg(feature = "cdp-events")]
+mod coverage;
+mod devicemetrics;
 mod devtools;
+mod domsnapshot;
+#[cfg(feature = "cdp-events")]
+mod downloads;
+#[cfg(feature = "cdp-events")]
+mod exceptions;
+#[cfg(feature = "cdp-events")]
+mod fetch;
+#[cfg(feature = "cdp-events")]
+mod filechooser;
+mod geolocation;
+pub(crate) mod media;
 mod networkconditions;
+mod networkthrottle;
+pub(crate) mod performance;
+mod requestrules;
+mod sensors;
+pub(crate) mod state;
+pub(crate) mod timezone;
+#[cfg(feature = "cdp-events")]
+mod trace;
+mod useragent;
+#[cfg(feature = "cdp-events")]
+mod websockets;
 
+pub use accessibility::{AxAuditReport, AxNode, AxTree, AxViolation, AxViolationKind};
+#[cfg(feature = "cdp-events")]
+pub use auth::{AuthChallenge, Credentials};
 pub use chromecommand::ChromeCommand;
+#[cfg(feature = "cdp-events")]
+pub use connection::{CdpConnection, CdpEvent, CdpEventStream, EventStream};
+#[cfg(feature = "cdp-events")]
+pub use console::{ConsoleLevel, ConsoleMessage, ConsoleStream};
+pub use cookies::{CdpCookie, CookiePartitionKey, CookiePriority, CookieSourceScheme};
+#[cfg(all(feature = "cdp-events", feature = "istanbul"))]
+pub use coverage::to_istanbul;
+#[cfg(feature = "cdp-events")]
+pub use coverage::{
+    CoverageRange, CssCoverage, CssRuleUsage, FunctionCoverage, JsCoverageOptions, ScriptCoverage,
+};
+pub use devicemetrics::{Device, DeviceMetrics, ScreenOrientation};
 pub use devtools::ChromeDevTools;
-pub use networkconditions::NetworkConditions;
+pub use domsnapshot::{
+    DomSnapshot, DomSnapshotOptions, NodeLayout, SnapshotDocument, SnapshotNode,
+};
+#[cfg(feature = "cdp-events")]
+pub use downloads::{DownloadEvent, DownloadState, DownloadStream};
+#[cfg(feature = "cdp-events")]
+pub use exceptions::{ExceptionStream, JsException};
+#[cfg(feature = "cdp-events")]
+pub use filechooser::{FileChooser, FileChooserAction};
+pub use geolocation::GeoLocation;
+pub use media::{
+    validate_media_type, ColorScheme, Contrast, ForcedColors, MediaFeature, ReducedMotion,
+    MEDIA_TYPES,
+};
+pub use networkconditions::{ConnectionType, NetworkConditions};
+pub use networkthrottle::NetworkThrottle;
+pub use requestrules::{url_pattern_matches, validate_url_pattern, RequestRules};
+pub use sensors::{MotionReading, SensorReading, SensorType};
+pub use timezone::{timezone_names, validate_locale, validate_timezone};
+#[cfg(feature = "cdp-events")]
+pub use trace::{Trace, TraceCategories, TracingOptions};
+pub use useragent::{Brand, ClientHints, UserAgentOverride};
+#[cfg(feature = "cdp-events")]
+pub use websockets::{WsEvent, WsFrame, WsPayload, WsStream, MAX_WS_PAYLOAD_LEN};
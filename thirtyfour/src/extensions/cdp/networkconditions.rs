@@ -1,16 +1,28 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
-#[serde(rename = "lowercase")]
+/// The type of connection to emulate.
+///
+/// See <https://chromedevtools.github.io/devtools-protocol/tot/Network/#type-ConnectionType>.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ConnectionType {
+    /// No connection.
     None,
+    /// A 2G cellular connection.
     Cellular2G,
+    /// A 3G cellular connection.
     Cellular3G,
+    /// A 4G cellular connection.
     Cellular4G,
+    /// A bluetooth connection.
     Bluetooth,
+    /// An ethernet connection.
     Ethernet,
+    /// A wifi connection.
     Wifi,
+    /// A WiMAX connection.
     Wimax,
+    /// Any other connection.
     Other,
 }
 
@@ -0,0 +1,140 @@
+use serde_json::{json, Value};
+
+use super::networkconditions::ConnectionType;
+
+/// The network conditions to emulate with `ChromeDevTools::emulate_network()`.
+///
+/// The presets match the ones in the Chrome DevTools network panel.
+///
+/// See <https://chromedevtools.github.io/devtools-protocol/tot/Network/#method-emulateNetworkConditions>.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NetworkThrottle {
+    /// True to emulate the network being offline.
+    pub offline: bool,
+    /// The minimum latency of each request, in milliseconds.
+    pub latency_ms: u32,
+    /// The maximum download throughput, in bytes per second. `None` disables download
+    /// throttling.
+    pub download_bps: Option<u64>,
+    /// The maximum upload throughput, in bytes per second. `None` disables upload
+    /// throttling.
+    pub upload_bps: Option<u64>,
+    /// The connection type reported to the page via `navigator.connection`, if any.
+    pub connection_type: Option<ConnectionType>,
+}
+
+impl NetworkThrottle {
+    /// Create a new `NetworkThrottle` that does not throttle anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A slow 3G connection: 2 seconds of latency, and 50 kB/s in each direction.
+    pub fn slow_3g() -> Self {
+        Self {
+            offline: false,
+            latency_ms: 2000,
+            download_bps: Some(50_000),
+            upload_bps: Some(50_000),
+            connection_type: Some(ConnectionType::Cellular3G),
+        }
+    }
+
+    /// A fast 3G connection: 563 milliseconds of latency, 180 kB/s down and 84 kB/s up.
+    pub fn fast_3g() -> Self {
+        Self {
+            offline: false,
+            latency_ms: 563,
+            download_bps: Some(180_000),
+            upload_bps: Some(84_375),
+            connection_type: Some(ConnectionType::Cellular3G),
+        }
+    }
+
+    /// No connection at all.
+    pub fn offline() -> Self {
+        Self {
+            offline: true,
+            connection_type: Some(ConnectionType::None),
+            ..Self::default()
+        }
+    }
+
+    /// Set whether to emulate the network being offline.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Set the latency, in milliseconds.
+    pub fn with_latency_ms(mut self, latency_ms: u32) -> Self {
+        self.latency_ms = latency_ms;
+        self
+    }
+
+    /// Set the download throughput, in bytes per second.
+    pub fn with_download_bps(mut self, download_bps: Option<u64>) -> Self {
+        self.download_bps = download_bps;
+        self
+    }
+
+    /// Set the upload throughput, in bytes per second.
+    pub fn with_upload_bps(mut self, upload_bps: Option<u64>) -> Self {
+        self.upload_bps = upload_bps;
+        self
+    }
+
+    /// Set the connection type.
+    pub fn with_connection_type(mut self, connection_type: Option<ConnectionType>) -> Self {
+        self.connection_type = connection_type;
+        self
+    }
+
+    /// Build the parameters for the CDP `Network.emulateNetworkConditions` command.
+    pub(crate) fn to_params(&self) -> Value {
+        // CDP uses -1 to disable throttling.
+        let throughput = |bps: Option<u64>| bps.map_or(-1.0, |x| x as f64);
+        let mut params = json!({
+            "offline": self.offline,
+            "latency": self.latency_ms,
+            "downloadThroughput": throughput(self.download_bps),
+            "uploadThroughput": throughput(self.upload_bps),
+        });
+        if let Some(connection_type) = self.connection_type {
+            params["connectionType"] = json!(connection_type);
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_throttle_params() {
+        assert_eq!(
+            NetworkThrottle::slow_3g().to_params(),
+            json!({
+                "offline": false,
+                "latency": 2000,
+                "downloadThroughput": 50000.0,
+                "uploadThroughput": 50000.0,
+                "connectionType": "cellular3g",
+            })
+        );
+        assert_eq!(
+            NetworkThrottle::new().to_params(),
+            json!({
+                "offline": false,
+                "latency": 0,
+                "downloadThroughput": -1.0,
+                "uploadThroughput": -1.0,
+            })
+        );
+        let params = NetworkThrottle::offline().with_latency_ms(10).to_params();
+        assert_eq!(params["offline"], true);
+        assert_eq!(params["latency"], 10);
+        assert_eq!(params["connectionType"], "none");
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// The metric for the size of the live objects in the JS heap, in bytes.
+pub(crate) const JS_HEAP_USED_SIZE: &str = "JSHeapUsedSize";
+
+/// Get the metrics from the result of the CDP `Performance.getMetrics` command, by name.
+pub(crate) fn parse_metrics(result: &Value) -> HashMap<String, f64> {
+    result["metrics"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|x| Some((x["name"].as_str()?.to_string(), x["value"].as_f64()?)))
+        .collect()
+}
+
+/// The memory metrics compared by `assert_heap_growth_under()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct MemorySample {
+    pub heap: f64,
+    pub nodes: f64,
+    pub listeners: f64,
+    pub documents: f64,
+}
+
+impl MemorySample {
+    /// Get the sample from the metrics returned by `ChromeDevTools::performance_metrics()`.
+    ///
+    /// Returns an `UnsupportedOperation` error if the JS heap size is not reported.
+    pub(crate) fn from_metrics(metrics: &HashMap<String, f64>) -> WebDriverResult<Self> {
+        let Some(heap) = metrics.get(JS_HEAP_USED_SIZE) else {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(format!(
+                "the browser does not report the {JS_HEAP_USED_SIZE} performance metric"
+            ))));
+        };
+        let get = |name: &str| metrics.get(name).copied().unwrap_or_default();
+        Ok(Self {
+            heap: *heap,
+            nodes: get("Nodes"),
+            listeners: get("JSEventListeners"),
+            documents: get("Documents"),
+        })
+    }
+
+    /// Return a `HeapGrowthExceeded` error if the heap grew by more than the specified
+    /// number of bytes since the `before` sample.
+    pub(crate) fn check_growth(&self, before: &Self, max_growth_bytes: u64) -> WebDriverResult<()> {
+        let growth = self.heap - before.heap;
+        if growth <= max_growth_bytes as f64 {
+            return Ok(());
+        }
+        let mb = |bytes: f64| format!("{:.2} MB", bytes / 1_000_000.0);
+        Err(WebDriverError::HeapGrowthExceeded(format!(
+            "grew by {} (from {} to {}), more than the limit of {}; \
+             DOM nodes {} -> {}, event listeners {} -> {}, documents {} -> {}",
+            mb(growth),
+            mb(before.heap),
+            mb(self.heap),
+            mb(max_growth_bytes as f64),
+            before.nodes,
+            self.nodes,
+            before.listeners,
+            self.listeners,
+            before.documents,
+            self.documents
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_memory_sample() {
+        let metrics = parse_metrics(&json!({
+            "metrics": [
+                { "name": "Documents", "value": 2 },
+                { "name": "Nodes", "value": 120 },
+                { "name": "JSEventListeners", "value": 8 },
+                { "name": "JSHeapUsedSize", "value": 1500000 },
+            ],
+        }));
+        assert_eq!(metrics.get("Nodes"), Some(&120.0));
+        let before = MemorySample::from_metrics(&metrics).unwrap();
+        assert_eq!(before.heap, 1_500_000.0);
+
+        let after = MemorySample {
+            heap: 4_000_000.0,
+            nodes: 5120.0,
+            ..before
+        };
+        assert!(after.check_growth(&before, 3_000_000).is_ok());
+        let err = after.check_growth(&before, 1_000_000).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The JS heap grew too much: grew by 2.50 MB (from 1.50 MB to 4.00 MB), more than \
+             the limit of 1.00 MB; DOM nodes 120 -> 5120, event listeners 8 -> 8, documents 2 -> 2"
+        );
+
+        assert!(MemorySample::from_metrics(&HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use http::{HeaderName, HeaderValue};
+use serde_json::{json, Value};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// Return an `InvalidArgument` error if the pattern is not a valid CDP URL pattern.
+///
+/// Patterns match the whole URL, and `*` matches any number of characters, including
+/// none. For example, `*.png` matches every PNG image, and `*://analytics.example.com/*`
+/// matches every request to that host. Patterns must not be empty, and must not contain
+/// whitespace, control characters or backslashes, since the browser has no way to escape
+/// them.
+pub fn validate_url_pattern(pattern: &str) -> WebDriverResult<()> {
+    let problem = if pattern.is_empty() {
+        Some("must not be empty")
+    } else if pattern.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        Some("must not contain whitespace or control characters")
+    } else if pattern.contains('\\') {
+        Some("must not contain backslashes")
+    } else {
+        None
+    };
+    match problem {
+        Some(problem) => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+            "invalid URL pattern {pattern:?}: {problem}"
+        )))),
+        None => Ok(()),
+    }
+}
+
+/// Return true if the URL matches the specified CDP URL pattern.
+///
+/// See [`validate_url_pattern`] for the syntax.
+pub fn url_pattern_matches(pattern: &str, url: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = url.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcards, so the whole URL must match.
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Rules for the requests made by the browser, applied with
+/// `ChromeDevTools::apply_request_rules()`.
+///
+/// Requests can be blocked, redirected to another URL, or have headers removed by URL
+/// pattern, and extra headers can be added to every request.
+///
+/// Blocking and adding headers use the CDP `Network` domain. Redirecting requests and
+/// removing headers pause the matching requests with the CDP `Fetch` domain until they
+/// are continued over the DevTools connection, so they require the `cdp-events` feature.
+/// See [`ChromeDevTools::connection`].
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use thirtyfour::extensions::cdp::{ChromeDevTools, RequestRules};
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let rules = RequestRules::new()
+///     .block("*://www.google-analytics.com/*")
+///     .block("*.woff2")
+///     .rewrite("*/api/flags", "http://localhost:8080/flags.json")
+///     .remove_header("*://cdn.example.com/*", "Cookie")
+///     .header("X-Test-Run", "nightly");
+/// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+/// dev_tools.apply_request_rules(&rules).await?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+///
+/// [`ChromeDevTools::connection`]: super::ChromeDevTools::connection
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestRules {
+    blocked: Vec<String>,
+    headers: Vec<(String, String)>,
+    modified: Vec<(String, Modification)>,
+}
+
+/// A change made to the requests that match a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Modification {
+    Rewrite(String),
+    RemoveHeader(String),
+}
+
+impl RequestRules {
+    /// Create new `RequestRules` that leave every request unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Block every request whose URL matches the specified pattern.
+    ///
+    /// See [`validate_url_pattern`] for the syntax.
+    pub fn block(mut self, pattern: impl Into<String>) -> Self {
+        self.blocked.push(pattern.into());
+        self
+    }
+
+    /// Send every request whose URL matches the specified pattern to the specified URL
+    /// instead. The page is not told about the change, so it sees the response as coming
+    /// from the original URL.
+    ///
+    /// If several rewrite rules match a request, the first one added is used.
+    pub fn rewrite(mut self, pattern: impl Into<String>, url: impl Into<String>) -> Self {
+        self.modified.push((pattern.into(), Modification::Rewrite(url.into())));
+        self
+    }
+
+    /// Remove the specified header from every request whose URL matches the specified
+    /// pattern.
+    pub fn remove_header(mut self, pattern: impl Into<String>, name: impl Into<String>) -> Self {
+        self.modified.push((pattern.into(), Modification::RemoveHeader(name.into())));
+        self
+    }
+
+    /// Add the specified header to every request, replacing any existing value.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        self.headers.retain(|(x, _)| !x.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// The URL patterns to block.
+    pub fn blocked(&self) -> &[String] {
+        &self.blocked
+    }
+
+    /// Return true if a request to the specified URL would be blocked.
+    pub fn is_blocked(&self, url: &str) -> bool {
+        self.blocked.iter().any(|pattern| url_pattern_matches(pattern, url))
+    }
+
+    /// Return the URL that a request to the specified URL would be sent to instead, if
+    /// any.
+    pub fn rewritten_url(&self, url: &str) -> Option<&str> {
+        self.modified.iter().find_map(|(pattern, modification)| match modification {
+            Modification::Rewrite(to) if url_pattern_matches(pattern, url) => Some(to.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Return true if any requests need to be paused to apply these rules.
+    pub(crate) fn intercepts(&self) -> bool {
+        !self.modified.is_empty()
+    }
+
+    #[cfg(feature = "cdp-events")]
+    /// The patterns for the requests to pause, for the CDP `Fetch.enable` command.
+    pub(crate) fn fetch_patterns(&self) -> Vec<Value> {
+        self.modified
+            .iter()
+            .map(|(pattern, _)| json!({ "urlPattern": pattern, "requestStage": "Request" }))
+            .collect()
+    }
+
+    #[cfg(feature = "cdp-events")]
+    /// Build the parameters for the CDP `Fetch.continueRequest` command for the specified
+    /// `Fetch.requestPaused` event.
+    pub(crate) fn continue_params(&self, params: &Value) -> Value {
+        let request = &params["request"];
+        let url = request["url"].as_str().unwrap_or_default();
+        let mut continued = json!({ "requestId": params["requestId"] });
+        if let Some(to) = self.rewritten_url(url) {
+            continued["url"] = json!(to);
+        }
+
+        let removed: Vec<&str> = self
+            .modified
+            .iter()
+            .filter_map(|(pattern, modification)| match modification {
+                Modification::RemoveHeader(name) if url_pattern_matches(pattern, url) => {
+                    Some(name.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        if let (false, Some(headers)) = (removed.is_empty(), request["headers"].as_object()) {
+            let headers: Vec<Value> = headers
+                .iter()
+                .filter(|(name, _)| !removed.iter().any(|x| x.eq_ignore_ascii_case(name)))
+                .map(|(name, value)| json!({ "name": name, "value": value }))
+                .collect();
+            continued["headers"] = json!(headers);
+        }
+        continued
+    }
+
+    /// Return an `InvalidArgument` error if any pattern, URL, header name or header value
+    /// is invalid.
+    pub fn validate(&self) -> WebDriverResult<()> {
+        for pattern in self.blocked.iter().chain(self.modified.iter().map(|(x, _)| x)) {
+            validate_url_pattern(pattern)?;
+        }
+        for (_, modification) in &self.modified {
+            match modification {
+                Modification::Rewrite(url) => {
+                    if let Err(e) = url::Url::parse(url) {
+                        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                            format!("invalid URL {url:?}: {e}"),
+                        )));
+                    }
+                }
+                Modification::RemoveHeader(name) => validate_header_name(name)?,
+            }
+        }
+        for (name, value) in &self.headers {
+            validate_header_name(name)?;
+            if HeaderValue::from_str(value).is_err() {
+                return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+                    "invalid value for header {name:?}: {value:?}"
+                ))));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the parameters for the CDP `Network.setExtraHTTPHeaders` command.
+    pub(crate) fn headers_params(&self) -> Value {
+        let headers: serde_json::Map<String, Value> =
+            self.headers.iter().map(|(name, value)| (name.clone(), json!(value))).collect();
+        json!({ "headers": headers })
+    }
+}
+
+/// Return an `InvalidArgument` error if the header name is invalid.
+fn validate_header_name(name: &str) -> WebDriverResult<()> {
+    match HeaderName::from_bytes(name.as_bytes()) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+            "invalid header name {name:?}"
+        )))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_pattern_matches() {
+        assert!(url_pattern_matches("*.png", "http://localhost/a/b.png"));
+        assert!(!url_pattern_matches("*.png", "http://localhost/a/b.png?x=1"));
+        assert!(url_pattern_matches("*.png*", "http://localhost/a/b.png?x=1"));
+        assert!(url_pattern_matches("*://ads.example.com/*", "https://ads.example.com/x.js"));
+        assert!(!url_pattern_matches("*://ads.example.com/*", "https://example.com/x.js"));
+        assert!(url_pattern_matches("http://a/*/c", "http://a/b/c"));
+        assert!(!url_pattern_matches("http://a/*/c", "http://a/b/cd"));
+        assert!(url_pattern_matches("http://a", "http://a"));
+        assert!(!url_pattern_matches("http://a", "http://ab"));
+        assert!(url_pattern_matches("*", "anything"));
+        // Overlapping parts are not counted twice.
+        assert!(!url_pattern_matches("*ab*ba", "aba"));
+    }
+
+    #[test]
+    fn test_request_rules_validate() {
+        let rules = RequestRules::new().block("*.png").header("X-Test", "0").header("x-test", "1");
+        assert!(rules.validate().is_ok());
+        assert!(rules.is_blocked("http://localhost/pixel.png"));
+        assert!(!rules.is_blocked("http://localhost/index.html"));
+        assert_eq!(rules.headers_params(), json!({ "headers": { "x-test": "1" } }));
+
+        for rules in [
+            RequestRules::new().block(""),
+            RequestRules::new().block("*.png *.jpg"),
+            RequestRules::new().block("*\\*"),
+            RequestRules::new().header("X Test", "1"),
+            RequestRules::new().header("X-Test", "a\nb"),
+            RequestRules::new().rewrite("*.png", "not a url"),
+            RequestRules::new().rewrite("", "http://localhost/"),
+            RequestRules::new().remove_header("*", "X Test"),
+        ] {
+            let err = rules.validate().unwrap_err();
+            assert!(matches!(
+                err.as_inner(),
+                crate::error::WebDriverErrorInner::InvalidArgument(_)
+            ));
+        }
+    }
+
+    #[cfg(feature = "cdp-events")]
+    #[test]
+    fn test_request_rules_continue_params() {
+        let rules = RequestRules::new()
+            .block("*.gif")
+            .rewrite("*/flags", "http://localhost/flags.json")
+            .rewrite("*/flags", "http://localhost/ignored.json")
+            .remove_header("*://cdn.example.com/*", "cookie");
+        assert!(rules.intercepts());
+        assert!(!RequestRules::new().block("*.gif").intercepts());
+        assert_eq!(
+            rules.fetch_patterns(),
+            vec![
+                json!({ "urlPattern": "*/flags", "requestStage": "Request" }),
+                json!({ "urlPattern": "*/flags", "requestStage": "Request" }),
+                json!({ "urlPattern": "*://cdn.example.com/*", "requestStage": "Request" }),
+            ]
+        );
+
+        let paused = |url: &str| {
+            json!({
+                "requestId": "interception-job-1.0",
+                "request": { "url": url, "headers": { "Cookie": "a=1", "Accept": "*/*" } },
+            })
+        };
+        assert_eq!(
+            rules.continue_params(&paused("http://localhost/api/flags")),
+            json!({ "requestId": "interception-job-1.0", "url": "http://localhost/flags.json" })
+        );
+        assert_eq!(
+            rules.continue_params(&paused("https://cdn.example.com/app.js")),
+            json!({
+                "requestId": "interception-job-1.0",
+                "headers": [{ "name": "Accept", "value": "*/*" }],
+            })
+        );
+        assert_eq!(
+            rules.continue_params(&paused("http://localhost/index.html")),
+            json!({ "requestId": "interception-job-1.0" })
+        );
+    }
+}
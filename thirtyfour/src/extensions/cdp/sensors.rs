@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// The first Chrome version with the CDP `Emulation.setSensorOverrideEnabled` command.
+/// Older versions only support `DeviceOrientation.setDeviceOrientationOverride`.
+pub(crate) const SENSOR_OVERRIDE_MIN_VERSION: u32 = 123;
+
+/// A sensor that can be emulated with `ChromeDevTools::set_sensor_reading()`.
+///
+/// See <https://chromedevtools.github.io/devtools-protocol/tot/Emulation/#type-SensorType>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SensorType {
+    /// The acceleration of the device, including gravity, in m/s².
+    Accelerometer,
+    /// The acceleration of the device, excluding gravity, in m/s².
+    LinearAcceleration,
+    /// The acceleration due to gravity, in m/s².
+    Gravity,
+    /// The rate of rotation of the device, in rad/s.
+    Gyroscope,
+    /// The orientation of the device relative to the Earth.
+    AbsoluteOrientation,
+    /// The orientation of the device relative to an arbitrary stationary frame. This is
+    /// the sensor behind `deviceorientation` events.
+    RelativeOrientation,
+}
+
+/// A reading on each axis of a motion sensor.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MotionReading {
+    /// The reading along the X axis.
+    pub x: f64,
+    /// The reading along the Y axis.
+    pub y: f64,
+    /// The reading along the Z axis.
+    pub z: f64,
+}
+
+impl MotionReading {
+    /// Create a new `MotionReading`.
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self {
+            x,
+            y,
+            z,
+        }
+    }
+}
+
+/// A reading to report from an emulated motion sensor, set with
+/// `ChromeDevTools::set_sensor_reading()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorReading {
+    /// The acceleration of the device, including gravity, in m/s².
+    Accelerometer(MotionReading),
+    /// The acceleration of the device, excluding gravity, in m/s².
+    LinearAcceleration(MotionReading),
+    /// The acceleration due to gravity, in m/s².
+    Gravity(MotionReading),
+    /// The rate of rotation of the device, in rad/s.
+    Gyroscope(MotionReading),
+}
+
+impl SensorReading {
+    /// The sensor that reports this reading.
+    pub fn sensor_type(&self) -> SensorType {
+        match self {
+            SensorReading::Accelerometer(_) => SensorType::Accelerometer,
+            SensorReading::LinearAcceleration(_) => SensorType::LinearAcceleration,
+            SensorReading::Gravity(_) => SensorType::Gravity,
+            SensorReading::Gyroscope(_) => SensorType::Gyroscope,
+        }
+    }
+
+    /// Return an `InvalidArgument` error if any of the values are not finite.
+    pub fn validate(&self) -> WebDriverResult<()> {
+        let (SensorReading::Accelerometer(reading)
+        | SensorReading::LinearAcceleration(reading)
+        | SensorReading::Gravity(reading)
+        | SensorReading::Gyroscope(reading)) = self;
+        if [reading.x, reading.y, reading.z].iter().all(|x| x.is_finite()) {
+            return Ok(());
+        }
+        Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+            "sensor readings must be finite, got {self:?}"
+        ))))
+    }
+
+    /// Build the parameters for the CDP `Emulation.setSensorOverrideReadings` command.
+    pub(crate) fn to_params(self) -> Value {
+        let (SensorReading::Accelerometer(reading)
+        | SensorReading::LinearAcceleration(reading)
+        | SensorReading::Gravity(reading)
+        | SensorReading::Gyroscope(reading)) = self;
+        json!({
+            "type": self.sensor_type(),
+            "reading": { "xyz": { "x": reading.x, "y": reading.y, "z": reading.z } },
+        })
+    }
+}
+
+/// Return an `InvalidArgument` error if the device orientation angles are out of range.
+///
+/// The ranges are those of `DeviceOrientationEvent`: alpha in [0, 360), beta in
+/// [-180, 180) and gamma in [-90, 90), all in degrees.
+pub(crate) fn validate_orientation(alpha: f64, beta: f64, gamma: f64) -> WebDriverResult<()> {
+    let check = |name: &str, value: f64, min: f64, max: f64| {
+        if (min..max).contains(&value) {
+            return Ok(());
+        }
+        Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+            "{name} must be at least {min} and less than {max}, got {value}"
+        ))))
+    };
+    check("alpha", alpha, 0.0, 360.0)?;
+    check("beta", beta, -180.0, 180.0)?;
+    check("gamma", gamma, -90.0, 90.0)
+}
+
+/// Build the parameters for the CDP `Emulation.setSensorOverrideReadings` command, for
+/// the relative orientation sensor with the specified angles in degrees.
+///
+/// The angles are converted to a quaternion as intrinsic Z-X'-Y'' rotations, as
+/// described in the DeviceOrientation spec.
+pub(crate) fn orientation_params(alpha: f64, beta: f64, gamma: f64) -> Value {
+    let half = |degrees: f64| (degrees.to_radians() / 2.0).sin_cos();
+    let (s_z, c_z) = half(alpha);
+    let (s_x, c_x) = half(beta);
+    let (s_y, c_y) = half(gamma);
+    json!({
+        "type": SensorType::RelativeOrientation,
+        "reading": {
+            "quaternion": {
+                "x": s_x * c_y * c_z - c_x * s_y * s_z,
+                "y": c_x * s_y * c_z + s_x * c_y * s_z,
+                "z": c_x * c_y * s_z + s_x * s_y * c_z,
+                "w": c_x * c_y * c_z - s_x * s_y * s_z,
+            }
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensor_reading() {
+        let reading = SensorReading::Gyroscope(MotionReading::new(0.5, 0.0, -1.0));
+        assert!(reading.validate().is_ok());
+        assert_eq!(
+            reading.to_params(),
+            json!({
+                "type": "gyroscope",
+                "reading": { "xyz": { "x": 0.5, "y": 0.0, "z": -1.0 } },
+            })
+        );
+        let reading = SensorReading::LinearAcceleration(MotionReading::new(f64::NAN, 0.0, 0.0));
+        assert!(reading.validate().is_err());
+        assert_eq!(
+            serde_json::to_value(SensorType::RelativeOrientation).unwrap(),
+            json!("relative-orientation")
+        );
+    }
+
+    #[test]
+    fn test_orientation() {
+        assert!(validate_orientation(0.0, -180.0, 89.9).is_ok());
+        for (alpha, beta, gamma) in
+            [(360.0, 0.0, 0.0), (-1.0, 0.0, 0.0), (0.0, 180.0, 0.0), (0.0, 0.0, f64::NAN)]
+        {
+            assert!(validate_orientation(alpha, beta, gamma).is_err(), "{alpha} {beta} {gamma}");
+        }
+
+        let quaternion = |alpha, beta, gamma| {
+            let params = orientation_params(alpha, beta, gamma);
+            let q = &params["reading"]["quaternion"];
+            ["x", "y", "z", "w"].map(|k| (q[k].as_f64().unwrap() * 1e6).round() / 1e6)
+        };
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        let half = (half * 1e6).round() / 1e6;
+        assert_eq!(quaternion(0.0, 0.0, 0.0), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(quaternion(90.0, 0.0, 0.0), [0.0, 0.0, half, half]);
+        assert_eq!(quaternion(0.0, 90.0, 0.0), [half, 0.0, 0.0, half]);
+        assert_eq!(quaternion(0.0, 0.0, -90.0), [0.0, -half, 0.0, half]);
+        // Alpha then beta: 90° about Z, then 90° about the new X axis.
+        assert_eq!(quaternion(90.0, 90.0, 0.0), [0.5, 0.5, 0.5, 0.5]);
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::{Arc, Mutex};
+
+use super::media::EmulatedMedia;
+use super::timezone::LocaleOverrides;
+use super::{ChromeDevTools, DeviceMetrics, GeoLocation};
+
+/// The overrides set via `ChromeDevTools` for a session, which the browser cannot report
+/// back.
+#[derive(Debug, Default)]
+pub(crate) struct CdpState {
+    /// The metrics set via `ChromeDevTools::set_device_metrics()`, if any.
+    device_metrics: Mutex<Option<DeviceMetrics>>,
+    /// The position set via `ChromeDevTools::set_geolocation()`, if any.
+    geolocation: Mutex<Option<GeoLocation>>,
+    /// The timezone and locale set via `ChromeDevTools::set_timezone()` and
+    /// `ChromeDevTools::set_locale()`.
+    locale_overrides: Mutex<LocaleOverrides>,
+    /// The media type and features set via `ChromeDevTools::set_emulated_media_type()` and
+    /// `ChromeDevTools::set_media_features()`.
+    emulated_media: Mutex<EmulatedMedia>,
+}
+
+impl CdpState {
+    /// The metrics set via `ChromeDevTools::set_device_metrics()`, if any.
+    pub(crate) fn device_metrics(&self) -> Option<DeviceMetrics> {
+        self.device_metrics.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub(crate) fn set_device_metrics(&self, metrics: Option<DeviceMetrics>) {
+        *self.device_metrics.lock().unwrap_or_else(|e| e.into_inner()) = metrics;
+    }
+
+    /// The position set via `ChromeDevTools::set_geolocation()`, if any.
+    pub(crate) fn geolocation(&self) -> Option<GeoLocation> {
+        *self.geolocation.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn set_geolocation(&self, geo: Option<GeoLocation>) {
+        *self.geolocation.lock().unwrap_or_else(|e| e.into_inner()) = geo;
+    }
+
+    /// The timezone and locale set via `ChromeDevTools::set_timezone()` and
+    /// `ChromeDevTools::set_locale()`.
+    pub(crate) fn locale_overrides(&self) -> LocaleOverrides {
+        self.locale_overrides.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub(crate) fn update_locale_overrides(&self, f: impl FnOnce(&mut LocaleOverrides)) {
+        f(&mut self.locale_overrides.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    /// The media type and features set via `ChromeDevTools::set_emulated_media_type()` and
+    /// `ChromeDevTools::set_media_features()`.
+    pub(crate) fn emulated_media(&self) -> EmulatedMedia {
+        self.emulated_media.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub(crate) fn set_emulated_media(&self, media: EmulatedMedia) {
+        *self.emulated_media.lock().unwrap_or_else(|e| e.into_inner()) = media;
+    }
+}
+
+impl ChromeDevTools {
+    /// The overrides set via `ChromeDevTools` for this session.
+    pub(crate) fn state(&self) -> Arc<CdpState> {
+        self.handle.extension_state()
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// The IANA timezone names, one per line, from the tz database.
+const TIMEZONES: &str = include_str!("timezones.txt");
+
+/// The IANA timezone names that can be passed to `ChromeDevTools::set_timezone()`, such
+/// as `Europe/Berlin`.
+pub fn timezone_names() -> impl Iterator<Item = &'static str> {
+    TIMEZONES.lines()
+}
+
+/// Return an `InvalidArgument` error if the timezone is not an IANA timezone name, such
+/// as `America/New_York` or `UTC`. Names are case-sensitive.
+///
+/// The error suggests the closest names, if any are close.
+pub fn validate_timezone(timezone: &str) -> WebDriverResult<()> {
+    if timezone_names().any(|x| x == timezone) {
+        return Ok(());
+    }
+    let suggestions = closest_timezones(timezone);
+    let hint = match suggestions.as_slice() {
+        [] => String::new(),
+        [x] => format!(", did you mean {x:?}?"),
+        x => format!(", did you mean one of {x:?}?"),
+    };
+    Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+        "unknown timezone {timezone:?}{hint}"
+    ))))
+}
+
+/// The timezone names closest to the specified name, best first, comparing either the
+/// whole name or just the location, such as `New_York`.
+fn closest_timezones(timezone: &str) -> Vec<&'static str> {
+    let wanted = timezone.trim().replace(' ', "_").to_lowercase();
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+    let max_distance = (wanted.len() / 4).max(1);
+    let mut scored: Vec<(usize, &str)> = timezone_names()
+        .filter_map(|name| {
+            let lower = name.to_lowercase();
+            let location = lower.rsplit('/').next().unwrap_or_default();
+            let distance = edit_distance(&wanted, &lower).min(edit_distance(&wanted, location));
+            (distance <= max_distance).then_some((distance, name))
+        })
+        .collect();
+    scored.sort();
+    scored.into_iter().take(3).map(|(_, name)| name).collect()
+}
+
+/// The Levenshtein distance between the strings, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let cost = usize::from(a != *b);
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Return an `InvalidArgument` error if the locale is not a BCP 47 language tag, such as
+/// `en`, `de-DE` or `zh-Hant-TW`.
+///
+/// Only the syntax is checked. The browser falls back to a supported locale if the
+/// language is not supported.
+pub fn validate_locale(locale: &str) -> WebDriverResult<()> {
+    let is_alpha = |x: &str, len: std::ops::RangeInclusive<usize>| {
+        len.contains(&x.len()) && x.chars().all(|c| c.is_ascii_alphabetic())
+    };
+    let mut subtags = locale.split('-');
+    let language = subtags.next().unwrap_or_default();
+    let valid = (is_alpha(language, 2..=3) || is_alpha(language, 5..=8))
+        && subtags
+            .all(|x| (1..=8).contains(&x.len()) && x.chars().all(|c| c.is_ascii_alphanumeric()));
+    if valid {
+        return Ok(());
+    }
+    // POSIX locales such as `en_US.UTF-8` are a common mistake.
+    let posix = locale.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+    let hint = if posix != locale && validate_locale(&posix).is_ok() {
+        format!(", did you mean {posix:?}?")
+    } else {
+        String::new()
+    };
+    Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+        "invalid locale {locale:?}: expected a BCP 47 language tag such as \"en-US\"{hint}"
+    ))))
+}
+
+/// The timezone and locale set via `ChromeDevTools`, if any.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LocaleOverrides {
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WebDriverErrorInner;
+
+    fn message(result: WebDriverResult<()>) -> String {
+        let err = result.unwrap_err();
+        match err.as_inner() {
+            WebDriverErrorInner::InvalidArgument(e) => e.value.message.clone(),
+            _ => panic!("expected InvalidArgument, got {err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_timezone() {
+        for timezone in ["UTC", "America/New_York", "Asia/Kolkata", "Etc/GMT+5"] {
+            assert!(validate_timezone(timezone).is_ok(), "{timezone}");
+        }
+        assert_eq!(
+            message(validate_timezone("America/New_Yrok")),
+            r#"unknown timezone "America/New_Yrok", did you mean "America/New_York"?"#
+        );
+        assert!(message(validate_timezone("new york")).contains(r#""America/New_York""#));
+        assert!(message(validate_timezone("europe/berlin")).contains(r#""Europe/Berlin""#));
+        assert_eq!(message(validate_timezone("")), r#"unknown timezone """#);
+        assert_eq!(
+            message(validate_timezone("Mars/Olympus_Mons")),
+            r#"unknown timezone "Mars/Olympus_Mons""#
+        );
+    }
+
+    #[test]
+    fn test_validate_locale() {
+        for locale in ["en", "de-DE", "zh-Hant-TW", "es-419", "sr-Latn-RS"] {
+            assert!(validate_locale(locale).is_ok(), "{locale}");
+        }
+        assert!(message(validate_locale("en_US.UTF-8")).ends_with(r#"did you mean "en-US"?"#));
+        for locale in ["", "e", "en-", "englishlanguage-US", "en US"] {
+            assert!(validate_locale(locale).is_err(), "{locale}");
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use tokio::sync::oneshot;
+
+use super::connection::{CdpConnection, CdpEventStream};
+use crate::error::{WebDriverError, WebDriverResult};
+
+/// Preset trace categories for `ChromeDevTools::start_tracing()`.
+#[derive(Debug)]
+pub struct TraceCategories;
+
+impl TraceCategories {
+    /// The categories the DevTools Performance panel records, which cover page loading,
+    /// scripting, layout and paint, and user timing marks. These are needed to see the
+    /// Web Vitals, such as Largest Contentful Paint, in the trace.
+    pub fn web_vitals() -> &'static [&'static str] {
+        &[
+            "devtools.timeline",
+            "disabled-by-default-devtools.timeline",
+            "disabled-by-default-devtools.timeline.frame",
+            "disabled-by-default-devtools.timeline.stack",
+            "loading",
+            "latencyInfo",
+            "blink.user_timing",
+            "v8.execute",
+            "disabled-by-default-v8.cpu_profiler",
+        ]
+    }
+
+    /// The categories for the rendering pipeline: style, layout, paint, compositing and
+    /// frame production, for investigating jank.
+    pub fn rendering() -> &'static [&'static str] {
+        &[
+            "devtools.timeline",
+            "disabled-by-default-devtools.timeline.frame",
+            "disabled-by-default-devtools.timeline.invalidationTracking",
+            "blink",
+            "cc",
+            "gpu",
+            "viz",
+            "benchmark",
+        ]
+    }
+}
+
+/// Options for `ChromeDevTools::start_tracing()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TracingOptions {
+    /// The file to write the trace to, or `None` to keep it in memory.
+    pub path: Option<PathBuf>,
+    /// Include screenshots of the page in the trace.
+    pub screenshots: bool,
+}
+
+impl TracingOptions {
+    /// Create new `TracingOptions` that keep the trace in memory, without screenshots.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the trace to the specified file as it is received, rather than keeping it
+    /// in memory. The file is created, or truncated if it exists.
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set whether to include screenshots of the page in the trace.
+    pub fn with_screenshots(mut self, screenshots: bool) -> Self {
+        self.screenshots = screenshots;
+        self
+    }
+
+    /// Build the parameters for the CDP `Tracing.start` command.
+    pub(crate) fn to_params(&self, categories: &[&str]) -> Value {
+        let mut categories: Vec<&str> = categories.to_vec();
+        if self.screenshots {
+            categories.push("disabled-by-default-devtools.screenshot");
+        }
+        let mut config = json!({ "recordMode": "recordUntilFull" });
+        // Without categories, the browser records its default categories.
+        if !categories.is_empty() {
+            config["includedCategories"] = json!(categories);
+        }
+        json!({ "traceConfig": config, "transferMode": "ReportEvents" })
+    }
+}
+
+/// The trace returned by `ChromeDevTools::stop_tracing()`, as a JSON array of trace
+/// events in the Trace Event Format that Perfetto and the DevTools Performance panel can
+/// open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trace {
+    /// The trace, kept in memory.
+    Events(Vec<u8>),
+    /// The file the trace was written to, set via `TracingOptions::with_path()`.
+    File(PathBuf),
+}
+
+/// Writes trace events as a JSON array, in the Trace Event Format that Perfetto and the
+/// DevTools Performance panel can open.
+struct TraceWriter {
+    output: TraceOutput,
+    empty: bool,
+}
+
+enum TraceOutput {
+    Memory(Vec<u8>),
+    File(PathBuf, BufWriter<File>),
+}
+
+impl TraceWriter {
+    fn new(options: &TracingOptions) -> WebDriverResult<Self> {
+        let output = match &options.path {
+            Some(path) => TraceOutput::File(path.clone(), BufWriter::new(File::create(path)?)),
+            None => TraceOutput::Memory(Vec::new()),
+        };
+        let mut writer = Self {
+            output,
+            empty: true,
+        };
+        writer.out().write_all(b"[")?;
+        Ok(writer)
+    }
+
+    fn out(&mut self) -> &mut dyn Write {
+        match &mut self.output {
+            TraceOutput::Memory(x) => x,
+            TraceOutput::File(_, x) => x,
+        }
+    }
+
+    /// Write the events of a `Tracing.dataCollected` event.
+    fn write_events(&mut self, events: &[Value]) -> WebDriverResult<()> {
+        for event in events {
+            if !std::mem::take(&mut self.empty) {
+                self.out().write_all(b",\n")?;
+            }
+            serde_json::to_writer(self.out(), event)?;
+        }
+        Ok(())
+    }
+
+    /// End the array, and return the trace, or the file it was written to.
+    fn finish(mut self) -> WebDriverResult<Trace> {
+        self.out().write_all(b"]")?;
+        match self.output {
+            TraceOutput::Memory(x) => Ok(Trace::Events(x)),
+            TraceOutput::File(path, mut x) => {
+                x.flush()?;
+                Ok(Trace::File(path))
+            }
+        }
+    }
+}
+
+/// The trace being recorded for a connection, which is sent once tracing completes.
+pub(crate) type TraceReceiver = oneshot::Receiver<WebDriverResult<Trace>>;
+
+/// Subscribe to the trace events for the connection.
+///
+/// Call this before `Tracing.start`, so that no events are missed.
+pub(crate) fn subscribe_trace(connection: &CdpConnection) -> CdpEventStream {
+    connection.subscribe(&["Tracing.dataCollected", "Tracing.tracingComplete"])
+}
+
+/// Start collecting the trace events subscribed to via `subscribe_trace()`, until
+/// `Tracing.tracingComplete`, creating the trace file if there is one.
+pub(crate) fn start_trace_task(
+    connection: &Arc<CdpConnection>,
+    events: CdpEventStream,
+    options: &TracingOptions,
+) -> WebDriverResult<TraceReceiver> {
+    let writer = TraceWriter::new(options)?;
+    let (sender, receiver) = oneshot::channel();
+    connection.spawn(async move {
+        let _ = sender.send(collect_trace(events, writer).await);
+    });
+    Ok(receiver)
+}
+
+async fn collect_trace(
+    mut events: CdpEventStream,
+    mut writer: TraceWriter,
+) -> WebDriverResult<Trace> {
+    while let Some(event) = events.next().await {
+        if event.method == "Tracing.tracingComplete" {
+            return writer.finish();
+        }
+        if let Some(values) = event.params["value"].as_array() {
+            writer.write_events(values)?;
+        }
+    }
+    Err(WebDriverError::CommandRecvError(
+        "the DevTools connection was closed before tracing completed".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_writer() {
+        let mut writer = TraceWriter::new(&TracingOptions::new()).unwrap();
+        writer.write_events(&[json!({ "name": "a", "ph": "X" })]).unwrap();
+        writer.write_events(&[]).unwrap();
+        writer.write_events(&[json!({ "name": "b", "ph": "B" })]).unwrap();
+        let Trace::Events(trace) = writer.finish().unwrap() else {
+            panic!("the trace should be kept in memory");
+        };
+        let trace: Vec<Value> = serde_json::from_slice(&trace).unwrap();
+        assert_eq!(
+            trace,
+            vec![json!({ "name": "a", "ph": "X" }), json!({ "name": "b", "ph": "B" })]
+        );
+
+        let empty = TraceWriter::new(&TracingOptions::new()).unwrap().finish().unwrap();
+        assert_eq!(empty, Trace::Events(b"[]".to_vec()));
+
+        let path = std::env::temp_dir().join(format!("thirtyfour-{}.json", std::process::id()));
+        let writer = TraceWriter::new(&TracingOptions::new().with_path(&path)).unwrap();
+        assert_eq!(writer.finish().unwrap(), Trace::File(path.clone()));
+        assert_eq!(std::fs::read(&path).unwrap(), b"[]");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tracing_params() {
+        let params = TracingOptions::new().with_screenshots(true).to_params(&["loading"]);
+        assert_eq!(
+            params["traceConfig"]["includedCategories"],
+            json!(["loading", "disabled-by-default-devtools.screenshot"])
+        );
+        assert_eq!(params["transferMode"], "ReportEvents");
+        let params = TracingOptions::new().to_params(&[]);
+        assert_eq!(params["traceConfig"].get("includedCategories"), None);
+    }
+}
@@ -0,0 +1,261 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::Device;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// A browser brand and version, as reported via the `Sec-CH-UA` client hint and
+/// `navigator.userAgentData.brands`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Brand {
+    /// The brand, for example `Google Chrome`.
+    pub brand: String,
+    /// The version, for example `116` or `116.0.5845.114`.
+    pub version: String,
+}
+
+impl Brand {
+    /// Create a new `Brand`.
+    pub fn new(brand: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            brand: brand.into(),
+            version: version.into(),
+        }
+    }
+}
+
+/// The user agent client hints reported via the `Sec-CH-UA-*` headers and
+/// `navigator.userAgentData`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientHints {
+    /// The brands with their major versions.
+    pub brands: Vec<Brand>,
+    /// The brands with their full versions.
+    pub full_version_list: Vec<Brand>,
+    /// The operating system, for example `Android` or `Windows`.
+    pub platform: String,
+    /// The operating system version, for example `13.0.0`.
+    pub platform_version: String,
+    /// The CPU architecture, for example `x86` or `arm`, or empty if unknown.
+    pub architecture: String,
+    /// The device model, for example `Pixel 7`, or empty for desktops.
+    pub model: String,
+    /// True for mobile devices.
+    pub mobile: bool,
+    /// The CPU bitness, for example `64`, or empty if unknown.
+    pub bitness: String,
+    /// True if the browser is a 32-bit build running on 64-bit Windows.
+    pub wow64: bool,
+}
+
+impl ClientHints {
+    /// Create new `ClientHints` for the specified operating system, with no brands.
+    pub fn new(platform: impl Into<String>, platform_version: impl Into<String>) -> Self {
+        Self {
+            brands: Vec::new(),
+            full_version_list: Vec::new(),
+            platform: platform.into(),
+            platform_version: platform_version.into(),
+            architecture: String::new(),
+            model: String::new(),
+            mobile: false,
+            bitness: String::new(),
+            wow64: false,
+        }
+    }
+
+    /// Report the brands of Google Chrome with the specified full version, such as
+    /// `116.0.5845.114`, in both `brands` and `full_version_list`.
+    pub fn with_chrome_version(mut self, version: &str) -> Self {
+        let major = version.split('.').next().unwrap_or(version);
+        let brands = |version: &str| {
+            vec![
+                Brand::new("Chromium", version),
+                Brand::new("Not)A;Brand", "24"),
+                Brand::new("Google Chrome", version),
+            ]
+        };
+        self.brands = brands(major);
+        self.full_version_list = brands(version);
+        self
+    }
+
+    /// Set the CPU architecture and bitness.
+    pub fn with_architecture(
+        mut self,
+        architecture: impl Into<String>,
+        bitness: impl Into<String>,
+    ) -> Self {
+        self.architecture = architecture.into();
+        self.bitness = bitness.into();
+        self
+    }
+
+    /// Set the device model.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    /// Set whether the device is mobile.
+    pub fn with_mobile(mut self, mobile: bool) -> Self {
+        self.mobile = mobile;
+        self
+    }
+}
+
+/// A user agent to report via `ChromeDevTools::set_user_agent()`, including the parts
+/// that the user agent string alone does not change, such as `navigator.platform` and
+/// `navigator.userAgentData`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAgentOverride {
+    /// The user agent string, sent in the `User-Agent` header and reported via
+    /// `navigator.userAgent`.
+    pub user_agent: String,
+    /// The `Accept-Language` header, for example `en-GB,en;q=0.9`, if it should change.
+    pub accept_language: Option<String>,
+    /// The `navigator.platform` value, for example `Win32`, if it should change.
+    pub platform: Option<String>,
+    /// The client hints. Without them, Chrome derives the client hints from its own
+    /// version and platform, which may contradict the user agent string.
+    pub ua_metadata: Option<ClientHints>,
+}
+
+impl UserAgentOverride {
+    /// Create a new `UserAgentOverride` that changes only the user agent string.
+    pub fn new(user_agent: impl Into<String>) -> Self {
+        Self {
+            user_agent: user_agent.into(),
+            accept_language: None,
+            platform: None,
+            ua_metadata: None,
+        }
+    }
+
+    /// Chrome on a Pixel 7, the user agent of [`Device::pixel_7`].
+    pub fn pixel_7() -> Self {
+        Device::pixel_7().user_agent
+    }
+
+    /// Safari on an iPhone 12, the user agent of [`Device::iphone_12`]. Safari does not
+    /// send client hints, so `navigator.userAgentData` keeps the browser's own values.
+    pub fn iphone_12() -> Self {
+        Device::iphone_12().user_agent
+    }
+
+    /// Chrome on 64-bit Windows 10 or 11.
+    pub fn chrome_windows() -> Self {
+        Self::new(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/116.0.0.0 Safari/537.36",
+        )
+        .with_platform("Win32")
+        .with_client_hints(
+            ClientHints::new("Windows", "15.0.0")
+                .with_chrome_version("116.0.5845.111")
+                .with_architecture("x86", "64"),
+        )
+    }
+
+    /// Chrome on an Intel Mac.
+    pub fn chrome_mac() -> Self {
+        Self::new(
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/116.0.0.0 Safari/537.36",
+        )
+        .with_platform("MacIntel")
+        .with_client_hints(
+            ClientHints::new("macOS", "13.5.0")
+                .with_chrome_version("116.0.5845.110")
+                .with_architecture("x86", "64"),
+        )
+    }
+
+    /// True if the client hints report a mobile device.
+    pub(crate) fn is_mobile(&self) -> bool {
+        self.ua_metadata.as_ref().is_some_and(|x| x.mobile)
+    }
+
+    /// Set the `Accept-Language` header.
+    pub fn with_accept_language(mut self, accept_language: impl Into<String>) -> Self {
+        self.accept_language = Some(accept_language.into());
+        self
+    }
+
+    /// Set the `navigator.platform` value.
+    pub fn with_platform(mut self, platform: impl Into<String>) -> Self {
+        self.platform = Some(platform.into());
+        self
+    }
+
+    /// Set the client hints.
+    pub fn with_client_hints(mut self, hints: ClientHints) -> Self {
+        self.ua_metadata = Some(hints);
+        self
+    }
+
+    /// Return an `InvalidArgument` error if the user agent or `Accept-Language` header is
+    /// empty or is not a valid header value.
+    pub fn validate(&self) -> WebDriverResult<()> {
+        let check = |name: &str, value: &str| {
+            if value.trim().is_empty() {
+                return Err(format!("{name} must not be empty"));
+            }
+            match http::HeaderValue::from_str(value) {
+                Ok(_) => Ok(()),
+                Err(_) => Err(format!("{name} is not a valid header value: {value:?}")),
+            }
+        };
+        check("user agent", &self.user_agent)
+            .and_then(|_| match &self.accept_language {
+                Some(x) => check("Accept-Language", x),
+                None => Ok(()),
+            })
+            .map_err(|e| WebDriverError::InvalidArgument(WebDriverErrorInfo::new(e)))
+    }
+
+    /// Build the parameters for the CDP `Emulation.setUserAgentOverride` command.
+    pub(crate) fn to_params(&self) -> WebDriverResult<Value> {
+        let mut params = json!({ "userAgent": self.user_agent });
+        if let Some(accept_language) = &self.accept_language {
+            params["acceptLanguage"] = json!(accept_language);
+        }
+        if let Some(platform) = &self.platform {
+            params["platform"] = json!(platform);
+        }
+        if let Some(hints) = &self.ua_metadata {
+            params["userAgentMetadata"] = serde_json::to_value(hints)?;
+        }
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_agent_params() {
+        let params =
+            UserAgentOverride::pixel_7().with_accept_language("en-AU").to_params().unwrap();
+        assert_eq!(params["platform"], "Linux armv81");
+        assert_eq!(params["acceptLanguage"], "en-AU");
+        let metadata = &params["userAgentMetadata"];
+        assert_eq!(metadata["platformVersion"], "13.0.0");
+        assert_eq!(metadata["mobile"], true);
+        assert_eq!(metadata["brands"][2], json!({ "brand": "Google Chrome", "version": "116" }));
+        assert_eq!(metadata["fullVersionList"][0]["version"], "116.0.5845.114");
+
+        let params = UserAgentOverride::new("custom").to_params().unwrap();
+        assert_eq!(params, json!({ "userAgent": "custom" }));
+    }
+
+    #[test]
+    fn test_user_agent_validate() {
+        assert!(UserAgentOverride::chrome_windows().validate().is_ok());
+        assert!(UserAgentOverride::new("").validate().is_err());
+        assert!(UserAgentOverride::new("bot\r\nX-Injected: 1").validate().is_err());
+        assert!(UserAgentOverride::new("bot").with_accept_language(" ").validate().is_err());
+    }
+}
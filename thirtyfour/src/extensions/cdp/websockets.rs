@@ -0,0 +1,243 @@
+use serde_json::Value;
+
+use super::connection::{CdpEvent, EventStream};
+use crate::support::base64_decode;
+
+/// The largest payload kept for each WebSocket message, in bytes.
+///
+/// Longer payloads are truncated to this length, with [`WsFrame::truncated`] set and
+/// [`WsFrame::len`] giving the full length, so that a page that streams large messages
+/// does not fill memory with buffered events.
+pub const MAX_WS_PAYLOAD_LEN: usize = 1024 * 1024;
+
+/// The payload of a [`WsFrame`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsPayload {
+    /// A text message.
+    Text(String),
+    /// A binary message, decoded from the base64 the browser reports it as.
+    Binary(Vec<u8>),
+}
+
+/// A WebSocket message sent or received by the page.
+///
+/// The browser reports whole messages rather than the frames they were split into on
+/// the wire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsFrame {
+    /// The WebSocket opcode, 1 for text and 2 for binary messages.
+    pub opcode: u8,
+    /// True if the payload was masked, as it is for every message sent by the page.
+    pub masked: bool,
+    /// The payload, truncated to [`MAX_WS_PAYLOAD_LEN`] bytes.
+    pub payload: WsPayload,
+    /// The full length of the payload in bytes, before truncation.
+    pub len: usize,
+    /// True if the payload was longer than [`MAX_WS_PAYLOAD_LEN`] and was truncated.
+    pub truncated: bool,
+}
+
+impl WsFrame {
+    /// Get the message from a CDP `Network.WebSocketFrame`.
+    fn from_cdp(frame: &Value) -> Option<Self> {
+        let opcode = u8::try_from(frame["opcode"].as_u64()?).ok()?;
+        let data = frame["payloadData"].as_str().unwrap_or_default();
+        let (payload, len, truncated) = if opcode == 2 {
+            let mut bytes = base64_decode(data).ok()?;
+            let len = bytes.len();
+            bytes.truncate(MAX_WS_PAYLOAD_LEN);
+            (WsPayload::Binary(bytes), len, len > MAX_WS_PAYLOAD_LEN)
+        } else {
+            let mut end = data.len().min(MAX_WS_PAYLOAD_LEN);
+            while !data.is_char_boundary(end) {
+                end -= 1;
+            }
+            (WsPayload::Text(data[..end].to_string()), data.len(), end < data.len())
+        };
+        Some(Self {
+            opcode,
+            masked: frame["mask"].as_bool().unwrap_or_default(),
+            payload,
+            len,
+            truncated,
+        })
+    }
+
+    /// The payload as text, if this is a text message.
+    pub fn text(&self) -> Option<&str> {
+        match &self.payload {
+            WsPayload::Text(x) => Some(x),
+            WsPayload::Binary(_) => None,
+        }
+    }
+}
+
+/// An event for a WebSocket opened by the page, from the CDP `Network.webSocket*`
+/// events.
+///
+/// Each WebSocket is identified by the id of the request that opened it, so that the
+/// messages of each WebSocket can be grouped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsEvent {
+    /// The page opened a WebSocket to the specified URL.
+    Created {
+        /// The id of the WebSocket.
+        request_id: String,
+        /// The URL of the WebSocket, for example `wss://example.com/live`.
+        url: String,
+    },
+    /// The page sent a message.
+    FrameSent {
+        /// The id of the WebSocket.
+        request_id: String,
+        /// The message.
+        frame: WsFrame,
+    },
+    /// The page received a message.
+    FrameReceived {
+        /// The id of the WebSocket.
+        request_id: String,
+        /// The message.
+        frame: WsFrame,
+    },
+    /// The WebSocket was closed.
+    Closed {
+        /// The id of the WebSocket.
+        request_id: String,
+    },
+}
+
+impl WsEvent {
+    /// The id of the WebSocket the event is for.
+    pub fn request_id(&self) -> &str {
+        match self {
+            Self::Created {
+                request_id,
+                ..
+            }
+            | Self::FrameSent {
+                request_id,
+                ..
+            }
+            | Self::FrameReceived {
+                request_id,
+                ..
+            }
+            | Self::Closed {
+                request_id,
+            } => request_id,
+        }
+    }
+
+    /// The message, if this event is for a message.
+    pub fn frame(&self) -> Option<&WsFrame> {
+        match self {
+            Self::FrameSent {
+                frame,
+                ..
+            }
+            | Self::FrameReceived {
+                frame,
+                ..
+            } => Some(frame),
+            _ => None,
+        }
+    }
+
+    /// Get the WebSocket event from a CDP `Network.webSocket*` event.
+    pub(crate) fn from_event(event: &CdpEvent) -> Option<Self> {
+        let params = &event.params;
+        let request_id = params["requestId"].as_str()?.to_string();
+        let ws_event = match event.method.as_str() {
+            "Network.webSocketCreated" => Self::Created {
+                request_id,
+                url: params["url"].as_str().unwrap_or_default().to_string(),
+            },
+            "Network.webSocketFrameSent" => Self::FrameSent {
+                request_id,
+                frame: WsFrame::from_cdp(&params["response"])?,
+            },
+            "Network.webSocketFrameReceived" => Self::FrameReceived {
+                request_id,
+                frame: WsFrame::from_cdp(&params["response"])?,
+            },
+            "Network.webSocketClosed" => Self::Closed {
+                request_id,
+            },
+            _ => return None,
+        };
+        Some(ws_event)
+    }
+}
+
+/// A stream of the WebSocket events of the page, returned by
+/// `ChromeDevTools::subscribe_websockets()`.
+pub type WsStream = EventStream<WsEvent>;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn event(method: &str, params: Value) -> Option<WsEvent> {
+        WsEvent::from_event(&CdpEvent {
+            method: method.to_string(),
+            params,
+            session_id: None,
+        })
+    }
+
+    #[test]
+    fn test_ws_event() {
+        let created = event(
+            "Network.webSocketCreated",
+            json!({ "requestId": "7.1", "url": "ws://localhost/echo" }),
+        );
+        assert_eq!(
+            created,
+            Some(WsEvent::Created {
+                request_id: "7.1".to_string(),
+                url: "ws://localhost/echo".to_string()
+            })
+        );
+
+        let response = json!({ "opcode": 1, "mask": true, "payloadData": "hello" });
+        let sent = event(
+            "Network.webSocketFrameSent",
+            json!({ "requestId": "7.1", "timestamp": 1.0, "response": response }),
+        )
+        .unwrap();
+        assert!(matches!(sent, WsEvent::FrameSent { .. }));
+        assert_eq!(sent.request_id(), "7.1");
+        let frame = sent.frame().unwrap();
+        assert_eq!((frame.text(), frame.len, frame.masked), (Some("hello"), 5, true));
+
+        // "AAEC" is [0, 1, 2] in base64.
+        let response = json!({ "opcode": 2, "mask": false, "payloadData": "AAEC" });
+        let received = event(
+            "Network.webSocketFrameReceived",
+            json!({ "requestId": "7.1", "timestamp": 1.0, "response": response }),
+        )
+        .unwrap();
+        assert_eq!(received.frame().unwrap().payload, WsPayload::Binary(vec![0, 1, 2]));
+
+        let closed = event("Network.webSocketClosed", json!({ "requestId": "7.1" })).unwrap();
+        assert_eq!(closed.frame(), None);
+    }
+
+    #[test]
+    fn test_ws_frame_truncated() {
+        let text = "é".repeat(MAX_WS_PAYLOAD_LEN);
+        let frame =
+            WsFrame::from_cdp(&json!({ "opcode": 1, "mask": false, "payloadData": text })).unwrap();
+        assert!(frame.truncated);
+        assert_eq!(frame.len, text.len());
+        assert_eq!(frame.text().unwrap().len(), MAX_WS_PAYLOAD_LEN);
+
+        let frame =
+            WsFrame::from_cdp(&json!({ "opcode": 1, "mask": false, "payloadData": "short" }))
+                .unwrap();
+        assert!(!frame.truncated);
+    }
+}
@@ -59,6 +59,20 @@ pub fn element_is_not_clickable(ignore_errors: bool) -> impl ElementPredicate {
     move |elem: WebElement| async move { negate(elem.is_clickable().await, ignore_errors) }
 }
 
+/// Predicate that returns true for elements whose center is covered by another element.
+pub fn element_is_obscured(ignore_errors: bool) -> impl ElementPredicate {
+    move |elem: WebElement| async move {
+        handle_errors(elem.obscured_by().await.map(|x| x.is_some()), ignore_errors)
+    }
+}
+
+/// Predicate that returns true for elements whose center is not covered by another element.
+pub fn element_is_not_obscured(ignore_errors: bool) -> impl ElementPredicate {
+    move |elem: WebElement| async move {
+        negate(elem.obscured_by().await.map(|x| x.is_some()), ignore_errors)
+    }
+}
+
 /// Predicate that returns true for elements that have the specified class name.
 /// See the `Needle` documentation for more details on text matching rules.
 /// In particular, it is recommended to use StringMatch or Regex to perform a whole-word search.
@@ -1,8 +1,12 @@
 use super::conditions::{collect_arg_slice, handle_errors, negate};
-use super::{conditions, ElementPollerNoWait, ElementPollerWithTimeout, IntoElementPoller};
-use crate::error::{WebDriverError, WebDriverErrorInner};
+use super::{
+    conditions, ElementPollerNoWait, ElementPollerWithDeadline, ElementPollerWithTimeout,
+    IntoElementPoller,
+};
+use crate::error::{ElementErrorContext, TimeoutErrorBuilder, WebDriverError, WebDriverErrorInner};
 use crate::prelude::WebDriverResult;
 use crate::session::handle::SessionHandle;
+use crate::support::Deadline;
 use crate::IntoArcStr;
 use crate::{By, DynElementPredicate, ElementPredicate, WebElement};
 use indexmap::IndexMap;
@@ -31,8 +35,12 @@ fn get_selector_summary(selectors: &[ElementSelector]) -> String {
     format!("[{}]", Criteria(selectors))
 }
 
-/// Helper function to return the NoSuchElement error struct.
-fn no_such_element(selectors: &[ElementSelector], description: &str) -> WebDriverError {
+/// Helper function to return the NoSuchElement error struct, describing the wait.
+fn no_such_element(
+    selectors: &[ElementSelector],
+    description: &str,
+    wait: &TimeoutErrorBuilder,
+) -> WebDriverError {
     let element_description: Cow<str> = if description.is_empty() {
         "element(s)".into()
     } else {
@@ -40,13 +48,31 @@ fn no_such_element(selectors: &[ElementSelector], description: &str) -> WebDrive
     };
 
     crate::error::no_such_element(format!(
-        "no such element: {element_description} not found using selectors: {}",
-        get_selector_summary(selectors)
+        "no such element: {element_description} not found using selectors: {}; {}",
+        get_selector_summary(selectors),
+        wait.message()
     ))
 }
 
+/// Describe the elements found by a selector during a single attempt, for example
+/// "2 elements matching css `.item`, none passed the filters".
+fn describe_matches(selector: &ElementSelector, found: usize, kept: usize) -> String {
+    let plural = if found == 1 {
+        ""
+    } else {
+        "s"
+    };
+    let matching = format!("{found} element{plural} matching {}", selector.by.describe());
+    match (found, kept) {
+        (0, _) => matching,
+        _ if selector.filters.is_empty() => matching,
+        (_, 0) => format!("{matching}, none passed the filters"),
+        _ => format!("{matching}, {kept} passed the filters"),
+    }
+}
+
 /// Filter the specified elements using the specified filters.
-pub async fn filter_elements<'a, I, P, Ref>(
+pub async fn filter_elements<I, P, Ref>(
     mut elements: Vec<WebElement>,
     filters: I,
 ) -> WebDriverResult<Vec<WebElement>>
@@ -120,9 +146,10 @@ pub enum ElementQuerySource {
 }
 
 /// Options for wait characteristics for an element query.
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone)]
 pub enum ElementQueryWaitOptions {
     /// Use the default poller.
+    #[default]
     WaitDefault,
     /// Use a poller with the specified timeout and interval.
     Wait {
@@ -135,12 +162,6 @@ pub enum ElementQueryWaitOptions {
     NoWait,
 }
 
-impl Default for ElementQueryWaitOptions {
-    fn default() -> Self {
-        Self::WaitDefault
-    }
-}
-
 /// All options applicable to an ElementQuery.
 ///
 /// These are stored in a separate struct so that they can be constructed
@@ -223,10 +244,9 @@ pub struct ElementQuery {
 }
 
 macro_rules! disallow_empty {
-    ($elements: expr, $self: expr) => {
+    ($elements: expr, $self: expr, $operation: expr, $wait: expr) => {
         if $elements.is_empty() {
-            let desc: &str = $self.options.description.as_deref().unwrap_or("");
-            Err(no_such_element(&$self.selectors, desc))
+            Err($self.not_found_error($operation, $wait).await)
         } else {
             Ok($elements)
         }
@@ -301,6 +321,13 @@ impl ElementQuery {
         self.with_poller(Arc::new(ElementPollerWithTimeout::new(timeout, interval)))
     }
 
+    /// Force this ElementQuery to wait until the specified deadline, polling once after
+    /// each interval. Queries and waits given the same deadline share it. This will
+    /// override the poller for this ElementQuery only.
+    pub fn wait_until(self, deadline: impl Into<Deadline>, interval: Duration) -> Self {
+        self.with_poller(Arc::new(ElementPollerWithDeadline::new(deadline, interval)))
+    }
+
     /// Force this ElementQuery to not wait for the specified condition(s).
     /// This will override the poller for this ElementQuery only.
     pub fn nowait(self) -> Self {
@@ -353,12 +380,11 @@ impl ElementQuery {
     ///
     /// Returns Err(WebDriverError::NoSuchElement) if no elements match.
     pub async fn first(&self) -> WebDriverResult<WebElement> {
-        let mut elements = self.run_poller(true, false).await?;
+        let mut wait = TimeoutErrorBuilder::new("a matching element");
+        let mut elements = self.poll_elements(true, false, &mut wait).await?;
 
         if elements.is_empty() {
-            let desc: &str = self.options.description.as_deref().unwrap_or("");
-            let err = no_such_element(&self.selectors, desc);
-            Err(err)
+            Err(self.not_found_error("first", &wait).await)
         } else {
             Ok(elements.remove(0))
         }
@@ -377,14 +403,13 @@ impl ElementQuery {
     /// By requiring that only one element is matched, you can be more sure that it is the
     /// one you intended.
     pub async fn single(&self) -> WebDriverResult<WebElement> {
-        let mut elements = self.run_poller(false, false).await?;
+        let mut wait = TimeoutErrorBuilder::new("exactly one matching element");
+        let mut elements = self.poll_elements(false, false, &mut wait).await?;
 
         if elements.len() == 1 {
             Ok(elements.remove(0))
         } else {
-            let desc: &str = self.options.description.as_deref().unwrap_or("");
-            let err = no_such_element(&self.selectors, desc);
-            Err(err)
+            Err(self.not_found_error("single", &wait).await)
         }
     }
 
@@ -403,8 +428,9 @@ impl ElementQuery {
     ///
     /// Returns Err(WebDriverError::NoSuchElement) if no elements match.
     pub async fn any_required(&self) -> WebDriverResult<Vec<WebElement>> {
-        let elements = self.run_poller(false, false).await?;
-        disallow_empty!(elements, self)
+        let mut wait = TimeoutErrorBuilder::new("a matching element");
+        let elements = self.poll_elements(false, false, &mut wait).await?;
+        disallow_empty!(elements, self, "any_required", &wait)
     }
 
     /// Return all WebElements that match any single selector (including filters).
@@ -436,12 +462,26 @@ impl ElementQuery {
     ///
     /// Returns Err(WebDriverError::NoSuchElement) if no elements match.
     pub async fn all_from_selector_required(&self) -> WebDriverResult<Vec<WebElement>> {
-        let elements = self.run_poller(true, false).await?;
-        disallow_empty!(elements, self)
+        let mut wait = TimeoutErrorBuilder::new("a matching element");
+        let elements = self.poll_elements(true, false, &mut wait).await?;
+        disallow_empty!(elements, self, "all_from_selector_required", &wait)
     }
 
     /// Run the poller for this ElementQuery and return the Vec of WebElements matched.
     ///
+    /// See [`ElementQuery::poll_elements`] for the parameters.
+    async fn run_poller(
+        &self,
+        short_circuit: bool,
+        stop_on_miss: bool,
+    ) -> WebDriverResult<Vec<WebElement>> {
+        let mut wait = TimeoutErrorBuilder::new("a matching element");
+        self.poll_elements(short_circuit, stop_on_miss, &mut wait).await
+    }
+
+    /// Run the poller for this ElementQuery and return the Vec of WebElements matched,
+    /// recording each attempt and the elements last seen.
+    ///
     /// NOTE: This function doesn't return a no_such_element error and the caller must handle it.
     ///
     /// The parameters are as follows:
@@ -453,15 +493,27 @@ impl ElementQuery {
     ///   - if true, the condition is true if no elements were found.
     ///   - if false, the condition is true if at least one element was found.
     ///
-    async fn run_poller(
+    async fn poll_elements(
         &self,
         short_circuit: bool,
         stop_on_miss: bool,
+        wait: &mut TimeoutErrorBuilder,
+    ) -> WebDriverResult<Vec<WebElement>> {
+        let handle = self.handle();
+        handle.report_errors(self.poll_source(short_circuit, stop_on_miss, wait)).await
+    }
+
+    /// Poll the source until the condition is met or the poller times out.
+    ///
+    /// See [`ElementQuery::poll_elements`] for the parameters.
+    async fn poll_source(
+        &self,
+        short_circuit: bool,
+        stop_on_miss: bool,
+        wait: &mut TimeoutErrorBuilder,
     ) -> WebDriverResult<Vec<WebElement>> {
-        let desc: &str = self.options.description.as_deref().unwrap_or("");
-        let no_such_element_error = no_such_element(&self.selectors, desc);
         if self.selectors.is_empty() {
-            return Err(no_such_element_error);
+            return Err(self.not_found_error("query", wait).await);
         }
 
         // Start the poller.
@@ -469,6 +521,8 @@ impl ElementQuery {
 
         let mut elements = IndexMap::new();
         loop {
+            wait.attempt();
+            let mut seen = Vec::new();
             for selector in &self.selectors {
                 let mut new_elements =
                     match self.fetch_elements_from_source(selector.by.clone()).await {
@@ -477,9 +531,12 @@ impl ElementQuery {
                         Err(e) => return Err(e),
                     };
 
+                let found = new_elements.len();
                 if !new_elements.is_empty() {
                     new_elements = filter_elements(new_elements, &selector.filters).await?;
                 }
+                seen.push(describe_matches(selector, found, new_elements.len()));
+                wait.observed(seen.join(" and "));
 
                 // Stop early?
                 if short_circuit && (stop_on_miss == new_elements.is_empty()) {
@@ -504,6 +561,36 @@ impl ElementQuery {
         }
     }
 
+    /// Return the NoSuchElement error for this query, with the selectors, the source element
+    /// and the operation name as context, and any failure artifacts attached.
+    ///
+    /// The error is passed to the error hooks for the session.
+    async fn not_found_error(&self, operation: &str, wait: &TimeoutErrorBuilder) -> WebDriverError {
+        let desc: &str = self.options.description.as_deref().unwrap_or("");
+        let selector = self.selectors.iter().map(|s| s.by.describe()).collect::<Vec<_>>();
+        let parent = match &self.source {
+            ElementQuerySource::Driver(_) => None,
+            ElementQuerySource::Element(element) => element.describe().await,
+        };
+        let error = no_such_element(&self.selectors, desc, wait).with_element_context(
+            ElementErrorContext {
+                selector: (!selector.is_empty()).then(|| selector.join(" or ")),
+                parent,
+                operation: Some(operation.to_string()),
+            },
+        );
+        let handle = self.handle();
+        handle.report_error(handle.attach_error_artifacts(error).await)
+    }
+
+    /// The session that this query runs in.
+    fn handle(&self) -> &Arc<SessionHandle> {
+        match &self.source {
+            ElementQuerySource::Driver(driver) => driver,
+            ElementQuerySource::Element(element) => &element.handle,
+        }
+    }
+
     /// Execute the specified selector and return any matched WebElements.
     async fn fetch_elements_from_source(&self, by: By) -> WebDriverResult<Vec<WebElement>> {
         match &self.source {
@@ -565,6 +652,9 @@ impl ElementQuery {
     }
 
     /// Only match elements that are clickable.
+    ///
+    /// This does not check whether the element is covered by another element. Combine it
+    /// with [`ElementQuery::and_not_obscured()`] for that.
     pub fn and_clickable(self) -> Self {
         let ignore_errors = self.options.ignore_errors.unwrap_or_default();
         self.with_filter(conditions::element_is_clickable(ignore_errors))
@@ -576,6 +666,21 @@ impl ElementQuery {
         self.with_filter(conditions::element_is_not_clickable(ignore_errors))
     }
 
+    /// Only match elements whose center is covered by another element, such as an
+    /// overlay. See [`WebElement::obscured_by()`] for details.
+    pub fn and_obscured(self) -> Self {
+        let ignore_errors = self.options.ignore_errors.unwrap_or_default();
+        self.with_filter(conditions::element_is_obscured(ignore_errors))
+    }
+
+    /// Only match elements whose center is NOT covered by another element. Combine this
+    /// with [`ElementQuery::and_clickable()`] to only match elements that a click would
+    /// reach.
+    pub fn and_not_obscured(self) -> Self {
+        let ignore_errors = self.options.ignore_errors.unwrap_or_default();
+        self.with_filter(conditions::element_is_not_obscured(ignore_errors))
+    }
+
     //
     // By alternative helper selectors
     //
@@ -1,11 +1,12 @@
-use super::conditions::{collect_arg_slice, handle_errors};
-use super::{conditions, ElementPollerWithTimeout, IntoElementPoller};
-use crate::error::WebDriverError;
+use super::conditions::collect_arg_slice;
+use super::{conditions, ElementPollerWithDeadline, ElementPollerWithTimeout, IntoElementPoller};
+use crate::error::TimeoutErrorBuilder;
 use crate::prelude::WebDriverResult;
+use crate::support::Deadline;
 use crate::IntoArcStr;
 use crate::{DynElementPredicate, ElementPredicate, WebElement};
 use std::ops::Deref;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use stringmatch::Needle;
 
@@ -56,7 +57,8 @@ impl ElementWaiter {
         self
     }
 
-    /// Provide a human-readable error message to be returned in the case of timeout.
+    /// Provide a human-readable description of the condition, to be used in the error
+    /// returned in the case of timeout instead of the default description.
     pub fn error(mut self, message: &str) -> Self {
         self.message = message.to_string();
         self
@@ -77,60 +79,149 @@ impl ElementWaiter {
         self.with_poller(Arc::new(ElementPollerWithTimeout::new(timeout, interval)))
     }
 
-    async fn run_poller<'a, F, I, P>(&self, conditions: F) -> WebDriverResult<bool>
+    /// Force this ElementWaiter to wait until the specified deadline, polling once after
+    /// each interval. Queries and waits given the same deadline share it. This will
+    /// override the poller for this ElementWaiter only.
+    pub fn wait_until(self, deadline: impl Into<Deadline>, interval: Duration) -> Self {
+        self.with_poller(Arc::new(ElementPollerWithDeadline::new(deadline, interval)))
+    }
+
+    async fn run_poller<'a, F, I, P>(
+        &self,
+        wait: &mut TimeoutErrorBuilder,
+        conditions: F,
+    ) -> WebDriverResult<bool>
     where
         F: Fn() -> I,
         I: IntoIterator<Item = &'a P>,
         P: ElementPredicate + ?Sized + 'a,
     {
         let mut poller = self.poller.start();
-        loop {
-            let mut conditions_met = true;
-            for f in conditions() {
-                if !f.call(self.element.clone()).await? {
-                    conditions_met = false;
-                    break;
+        let poll = async {
+            loop {
+                wait.attempt();
+                let mut conditions_met = true;
+                for f in conditions() {
+                    if !f.call(self.element.clone()).await? {
+                        conditions_met = false;
+                        break;
+                    }
                 }
-            }
 
-            if conditions_met {
-                return Ok(true);
-            }
+                if conditions_met {
+                    return Ok(true);
+                }
 
-            if !poller.tick().await {
-                return Ok(false);
+                if !poller.tick().await {
+                    return Ok(false);
+                }
             }
+        };
+        self.element.handle.report_errors(poll).await
+    }
+
+    /// Start timing a wait for the specified condition. The message set with
+    /// [`ElementWaiter::error`] takes precedence over the description.
+    fn start_wait(&self, description: &str) -> TimeoutErrorBuilder {
+        match self.message.is_empty() {
+            true => TimeoutErrorBuilder::new(description),
+            false => TimeoutErrorBuilder::new(self.message.clone()),
         }
     }
 
-    fn timeout(self) -> WebDriverResult<()> {
-        Err(WebDriverError::Timeout(format!("element condition timed out: {}", self.message)))
+    /// Fail the wait, describing the specified state if any, or else the element's state.
+    async fn timeout(
+        self,
+        mut wait: TimeoutErrorBuilder,
+        observed: Option<String>,
+    ) -> WebDriverResult<()> {
+        let observed = match observed {
+            Some(observed) => observed,
+            None => observe_element(&self.element).await,
+        };
+        wait.observed(observed);
+        Err(self.element.handle.report_error(wait.build()))
     }
 
-    /// Wait for the specified condition to be true.
-    pub async fn condition(self, f: impl ElementPredicate) -> WebDriverResult<()> {
-        match self.run_poller(|| [&f].into_iter()).await? {
+    /// Wait for the specified condition, described in the timeout error.
+    async fn wait_for(self, description: &str, f: impl ElementPredicate) -> WebDriverResult<()> {
+        self.wait_for_observing(description, f, &Mutex::new(None)).await
+    }
+
+    /// Wait for the specified condition, as [`ElementWaiter::wait_for`] does. If the
+    /// condition records the state it last saw in `last_state`, the timeout error
+    /// describes that state instead of the element's state.
+    async fn wait_for_observing(
+        self,
+        description: &str,
+        f: impl ElementPredicate,
+        last_state: &Mutex<Option<String>>,
+    ) -> WebDriverResult<()> {
+        let mut wait = self.start_wait(description);
+        match self.run_poller(&mut wait, || [&f].into_iter()).await? {
             true => Ok(()),
-            false => self.timeout(),
+            false => {
+                let observed = last_state.lock().unwrap_or_else(|e| e.into_inner()).take();
+                self.timeout(wait, observed).await
+            }
         }
     }
 
+    /// Wait for the specified condition to be true.
+    pub async fn condition(self, f: impl ElementPredicate) -> WebDriverResult<()> {
+        self.wait_for("element to meet the condition", f).await
+    }
+
+    /// Wait for the specified condition to be true, using the description in the timeout
+    /// error, for example "element to finish animating".
+    pub async fn condition_described(
+        self,
+        description: &str,
+        f: impl ElementPredicate,
+    ) -> WebDriverResult<()> {
+        self.wait_for(description, f).await
+    }
+
     /// Wait for the specified conditions to be true.
     pub async fn conditions(
         self,
         conditions: Vec<Box<DynElementPredicate>>,
     ) -> WebDriverResult<()> {
-        match self.run_poller(|| conditions.iter().map(Box::deref)).await? {
+        let mut wait = self.start_wait("element to meet the conditions");
+        match self.run_poller(&mut wait, || conditions.iter().map(Box::deref)).await? {
             true => Ok(()),
-            false => self.timeout(),
+            false => self.timeout(wait, None).await,
         }
     }
 
-    /// Wait for the element to become stale.
+    /// Wait for the element to become stale, for example because the page navigated away
+    /// after a form was submitted.
+    ///
+    /// This polls the element until the driver reports a stale element reference. Any
+    /// other error is returned immediately, even if errors are ignored, because it does
+    /// not show whether the element is stale.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let submit = driver.find(By::Id("submit")).await?;
+    /// submit.click().await?;
+    /// // Wait for the next page before querying it.
+    /// submit.wait_until().stale().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
     pub async fn stale(self) -> WebDriverResult<()> {
-        let ignore_errors = self.ignore_errors;
-        self.condition(move |elem: WebElement| async move {
-            handle_errors(elem.is_present().await.map(|x| !x), ignore_errors)
+        self.wait_for("element to become stale", |elem: WebElement| async move {
+            elem.is_present().await.map(|x| !x)
         })
         .await
     }
@@ -138,49 +229,98 @@ impl ElementWaiter {
     /// Wait for the element to be displayed.
     pub async fn displayed(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_is_displayed(ignore_errors)).await
+        self.wait_for("element to be displayed", conditions::element_is_displayed(ignore_errors))
+            .await
     }
 
     /// Wait for the element to not be displayed.
     pub async fn not_displayed(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_is_not_displayed(ignore_errors)).await
+        self.wait_for(
+            "element to not be displayed",
+            conditions::element_is_not_displayed(ignore_errors),
+        )
+        .await
     }
 
     /// Wait for the element to be selected.
     pub async fn selected(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_is_selected(ignore_errors)).await
+        self.wait_for("element to be selected", conditions::element_is_selected(ignore_errors))
+            .await
     }
 
     /// Wait for the element to not be selected.
     pub async fn not_selected(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_is_not_selected(ignore_errors)).await
+        self.wait_for(
+            "element to not be selected",
+            conditions::element_is_not_selected(ignore_errors),
+        )
+        .await
     }
 
     /// Wait for the element to be enabled.
     pub async fn enabled(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_is_enabled(ignore_errors)).await
+        self.wait_for("element to be enabled", conditions::element_is_enabled(ignore_errors)).await
     }
 
     /// Wait for the element to not be enabled.
     pub async fn not_enabled(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_is_not_enabled(ignore_errors)).await
+        self.wait_for(
+            "element to not be enabled",
+            conditions::element_is_not_enabled(ignore_errors),
+        )
+        .await
     }
 
     /// Wait for the element to be clickable.
+    ///
+    /// This does not check whether the element is covered by another element. Use
+    /// [`ElementWaiter::clickable_and_not_obscured()`] for that.
     pub async fn clickable(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_is_clickable(ignore_errors)).await
+        self.wait_for("element to be clickable", conditions::element_is_clickable(ignore_errors))
+            .await
     }
 
     /// Wait for the element to not be clickable.
     pub async fn not_clickable(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_is_not_clickable(ignore_errors)).await
+        self.wait_for(
+            "element to not be clickable",
+            conditions::element_is_not_clickable(ignore_errors),
+        )
+        .await
+    }
+
+    /// Wait for the element to be clickable and not covered by another element, such as a
+    /// modal backdrop or a loading overlay.
+    ///
+    /// This checks that the element is displayed, then enabled, then that its center is
+    /// not covered, as described in [`WebElement::obscured_by()`]. The timeout error
+    /// names the check that failed last, for example "the element displayed and enabled
+    /// but obscured by <div id=modal-backdrop>".
+    pub async fn clickable_and_not_obscured(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        let last_state = Mutex::new(None);
+        let clickable = |elem: WebElement| {
+            let last_state = &last_state;
+            async move {
+                let state = match unclickable_state(&elem).await {
+                    Ok(None) => return Ok(true),
+                    Ok(Some(state)) => state,
+                    Err(e) if ignore_errors => format!("an error: {e}"),
+                    Err(e) => return Err(e),
+                };
+                *last_state.lock().unwrap_or_else(|e| e.into_inner()) = Some(state);
+                Ok(false)
+            }
+        };
+        self.wait_for_observing("element to be clickable and not obscured", clickable, &last_state)
+            .await
     }
 
     /// Wait until the element has the specified class.
@@ -189,7 +329,11 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_class(class_name, ignore_errors)).await
+        self.wait_for(
+            "element to have the class",
+            conditions::element_has_class(class_name, ignore_errors),
+        )
+        .await
     }
 
     /// Wait until the element lacks the specified class.
@@ -198,7 +342,11 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_class(class_name, ignore_errors)).await
+        self.wait_for(
+            "element to lack the class",
+            conditions::element_lacks_class(class_name, ignore_errors),
+        )
+        .await
     }
 
     /// Wait until the element has the specified text.
@@ -207,7 +355,8 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_text(text, ignore_errors)).await
+        self.wait_for("element to have the text", conditions::element_has_text(text, ignore_errors))
+            .await
     }
 
     /// Wait until the element lacks the specified text.
@@ -216,7 +365,11 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_text(text, ignore_errors)).await
+        self.wait_for(
+            "element to lack the text",
+            conditions::element_lacks_text(text, ignore_errors),
+        )
+        .await
     }
 
     /// Wait until the element has the specified value.
@@ -225,7 +378,11 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_value(value, ignore_errors)).await
+        self.wait_for(
+            "element to have the value",
+            conditions::element_has_value(value, ignore_errors),
+        )
+        .await
     }
 
     /// Wait until the element lacks the specified value.
@@ -234,7 +391,11 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_value(value, ignore_errors)).await
+        self.wait_for(
+            "element to lack the value",
+            conditions::element_lacks_value(value, ignore_errors),
+        )
+        .await
     }
 
     /// Wait until the element has the specified attribute.
@@ -244,11 +405,10 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_attribute(
-            attribute_name.into(),
-            value,
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to have the attribute",
+            conditions::element_has_attribute(attribute_name.into(), value, ignore_errors),
+        )
         .await
     }
 
@@ -259,11 +419,10 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_attribute(
-            attribute_name.into(),
-            value,
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to lack the attribute",
+            conditions::element_lacks_attribute(attribute_name.into(), value, ignore_errors),
+        )
         .await
     }
 
@@ -277,10 +436,13 @@ impl ElementWaiter {
         N: Needle + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_attributes(
-            collect_arg_slice(desired_attributes),
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to have the attributes",
+            conditions::element_has_attributes(
+                collect_arg_slice(desired_attributes),
+                ignore_errors,
+            ),
+        )
         .await
     }
 
@@ -294,10 +456,13 @@ impl ElementWaiter {
         N: Needle + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_attributes(
-            collect_arg_slice(desired_attributes),
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to lack the attributes",
+            conditions::element_lacks_attributes(
+                collect_arg_slice(desired_attributes),
+                ignore_errors,
+            ),
+        )
         .await
     }
 
@@ -308,8 +473,11 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_property(property_name.into(), value, ignore_errors))
-            .await
+        self.wait_for(
+            "element to have the property",
+            conditions::element_has_property(property_name.into(), value, ignore_errors),
+        )
+        .await
     }
 
     /// Wait until the element lacks the specified property.
@@ -319,11 +487,10 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_property(
-            property_name.into(),
-            value,
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to lack the property",
+            conditions::element_lacks_property(property_name.into(), value, ignore_errors),
+        )
         .await
     }
 
@@ -339,7 +506,11 @@ impl ElementWaiter {
         let desired_properties: Arc<[(Arc<str>, N)]> =
             desired_properties.into_iter().map(|(a, b)| (a.into(), b)).collect();
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_properties(desired_properties, ignore_errors)).await
+        self.wait_for(
+            "element to have the properties",
+            conditions::element_has_properties(desired_properties, ignore_errors),
+        )
+        .await
     }
 
     /// Wait until the element lacks all the specified properties.
@@ -352,10 +523,13 @@ impl ElementWaiter {
         N: Needle + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_properties(
-            collect_arg_slice(desired_properties),
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to lack the properties",
+            conditions::element_lacks_properties(
+                collect_arg_slice(desired_properties),
+                ignore_errors,
+            ),
+        )
         .await
     }
 
@@ -366,11 +540,10 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_css_property(
-            css_property_name.into(),
-            value,
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to have the CSS property",
+            conditions::element_has_css_property(css_property_name.into(), value, ignore_errors),
+        )
         .await
     }
 
@@ -385,11 +558,10 @@ impl ElementWaiter {
         N: Needle + Clone + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_css_property(
-            css_property_name.into(),
-            value,
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to lack the CSS property",
+            conditions::element_lacks_css_property(css_property_name.into(), value, ignore_errors),
+        )
         .await
     }
 
@@ -403,10 +575,13 @@ impl ElementWaiter {
         N: Needle + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_has_css_properties(
-            collect_arg_slice(desired_css_properties),
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to have the CSS properties",
+            conditions::element_has_css_properties(
+                collect_arg_slice(desired_css_properties),
+                ignore_errors,
+            ),
+        )
         .await
     }
 
@@ -420,14 +595,51 @@ impl ElementWaiter {
         N: Needle + Send + Sync + 'static,
     {
         let ignore_errors = self.ignore_errors;
-        self.condition(conditions::element_lacks_css_properties(
-            collect_arg_slice(desired_css_properties),
-            ignore_errors,
-        ))
+        self.wait_for(
+            "element to lack the CSS properties",
+            conditions::element_lacks_css_properties(
+                collect_arg_slice(desired_css_properties),
+                ignore_errors,
+            ),
+        )
         .await
     }
 }
 
+/// Describe the current state of the element, for timeout errors.
+async fn observe_element(element: &WebElement) -> String {
+    match element.is_present().await {
+        Ok(true) => {}
+        Ok(false) => return "a stale element".to_string(),
+        Err(e) => return format!("an error: {e}"),
+    }
+
+    let state = |result: WebDriverResult<bool>, yes: &'static str, no: &'static str| match result {
+        Ok(true) => yes,
+        Ok(false) => no,
+        Err(_) => "unknown",
+    };
+    let description = element.describe().await.unwrap_or_else(|| "element".to_string());
+    let displayed = state(element.is_displayed().await, "displayed", "not displayed");
+    let enabled = state(element.is_enabled().await, "enabled", "not enabled");
+    let selected = state(element.is_selected().await, "selected", "not selected");
+    format!("{description}, {displayed}, {enabled}, {selected}")
+}
+
+/// Describe why the element cannot be clicked, or None if it can.
+async fn unclickable_state(element: &WebElement) -> WebDriverResult<Option<String>> {
+    if !element.is_displayed().await? {
+        return Ok(Some("the element not displayed".to_string()));
+    }
+    if !element.is_enabled().await? {
+        return Ok(Some("the element displayed but not enabled".to_string()));
+    }
+    Ok(element
+        .obscured_by()
+        .await?
+        .map(|cover| format!("the element displayed and enabled but obscured by {cover}")))
+}
+
 /// Trait for enabling the ElementWaiter interface.
 pub trait ElementWaitable {
     /// Wait until the element meets one or more conditions.
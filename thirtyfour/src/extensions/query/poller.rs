@@ -1,4 +1,4 @@
-use crate::support::sleep;
+use crate::support::{sleep, Deadline};
 use std::fmt::Debug;
 use std::time::{Duration, Instant};
 
@@ -80,6 +80,56 @@ impl IntoElementPoller for ElementPollerWithTimeout {
     }
 }
 
+/// Poll until the specified deadline, with the specified interval being the minimum time
+/// elapsed between the start of each poll attempt, like [`ElementPollerWithTimeout`].
+///
+/// Unlike a timeout, the deadline does not restart each time the poller is started, so
+/// several queries and waits given the same deadline take no longer than it allows
+/// together. The last sleep is cut short at the deadline.
+#[derive(Debug, Clone, Copy)]
+pub struct ElementPollerWithDeadline {
+    deadline: Deadline,
+    interval: Duration,
+    start: tokio::time::Instant,
+    cur_tries: u32,
+}
+
+impl ElementPollerWithDeadline {
+    /// Create a new `ElementPollerWithDeadline`.
+    pub fn new(deadline: impl Into<Deadline>, interval: Duration) -> Self {
+        Self {
+            deadline: deadline.into(),
+            interval,
+            start: tokio::time::Instant::now(),
+            cur_tries: 0,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ElementPoller for ElementPollerWithDeadline {
+    async fn tick(&mut self) -> bool {
+        self.cur_tries += 1;
+
+        if self.deadline.is_expired() {
+            return false;
+        }
+
+        let minimum_elapsed = self.interval * self.cur_tries;
+        let actual_elapsed = self.start.elapsed();
+        if actual_elapsed < minimum_elapsed {
+            sleep((minimum_elapsed - actual_elapsed).min(self.deadline.remaining())).await;
+        }
+        true
+    }
+}
+
+impl IntoElementPoller for ElementPollerWithDeadline {
+    fn start(&self) -> Box<dyn ElementPoller + Send + Sync> {
+        Box::new(Self::new(self.deadline, self.interval))
+    }
+}
+
 /// No polling, single attempt.
 #[derive(Debug)]
 pub struct ElementPollerNoWait;
@@ -112,6 +162,25 @@ mod tests {
         assert!(!poller.tick().await);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_poller_with_deadline() {
+        let deadline = Deadline::after(Duration::from_secs(1));
+        let started = tokio::time::Instant::now();
+        let mut poller =
+            ElementPollerWithDeadline::new(deadline, Duration::from_millis(300)).start();
+        for _ in 0..4 {
+            assert!(poller.tick().await);
+        }
+        // The last sleep was cut short at the deadline.
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+        assert!(!poller.tick().await);
+
+        // Starting the poller again does not extend the deadline.
+        let mut poller =
+            ElementPollerWithDeadline::new(deadline, Duration::from_millis(300)).start();
+        assert!(!poller.tick().await);
+    }
+
     #[tokio::test]
     async fn test_poller_nowait() {
         let mut poller = ElementPollerNoWait;
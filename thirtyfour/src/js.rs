@@ -47,3 +47,374 @@ function simulateDragDrop(sourceNode, destinationNode) {
 }
 
 simulateDragDrop(arguments[0], arguments[1]);"#;
+
+/// A javascript function dispatching the HTML5 drag and drop events to drag the first
+/// argument to the second, with a shared `DataTransfer`.
+///
+/// Returns null on success, or a message if the source is not draggable or the target did
+/// not accept the drop.
+pub const HTML5_DRAG_AND_DROP: &str = r#"
+const [source, target] = arguments;
+if (!source.draggable) {
+    return "the source element is not draggable";
+}
+const center = (el) => {
+    const rect = el.getBoundingClientRect();
+    return { x: rect.left + rect.width / 2, y: rect.top + rect.height / 2 };
+};
+const dataTransfer = new DataTransfer();
+const fire = (el, type, at) => el.dispatchEvent(new DragEvent(type, {
+    bubbles: true,
+    cancelable: type !== "dragleave" && type !== "dragend",
+    composed: true,
+    clientX: at.x,
+    clientY: at.y,
+    dataTransfer,
+}));
+const from = center(source);
+const to = center(target);
+if (!fire(source, "dragstart", from)) {
+    return "the dragstart event was cancelled";
+}
+fire(source, "drag", from);
+fire(target, "dragenter", to);
+// A drop zone cancels dragover to accept the drop.
+if (fire(target, "dragover", to)) {
+    fire(target, "dragleave", to);
+    fire(source, "dragend", from);
+    return "the target element did not accept the drop";
+}
+fire(target, "drop", to);
+fire(source, "dragend", from);
+return null;
+"#;
+
+/// A javascript function returning the center of an element in the viewport, as the
+/// integer `[x, y]` that WebDriver uses for the in-view center point.
+pub const ELEMENT_CENTER: &str = r#"
+const rect = arguments[0].getBoundingClientRect();
+return [Math.floor(rect.left + rect.width / 2), Math.floor(rect.top + rect.height / 2)];
+"#;
+
+/// A javascript function returning a description of the element that covers the center
+/// of an element, such as a modal backdrop, in the form `<div id=backdrop>`.
+///
+/// Returns null if the element or one of its descendants is at its center, or if its
+/// center is outside the viewport and cannot be checked.
+pub const ELEMENT_OBSCURED_BY: &str = r#"
+const elem = arguments[0];
+const rect = elem.getBoundingClientRect();
+const x = rect.left + rect.width / 2;
+const y = rect.top + rect.height / 2;
+const root = elem.getRootNode();
+const hit = (root.elementFromPoint ? root : document).elementFromPoint(x, y);
+if (!hit || elem.contains(hit)) {
+    return null;
+}
+const tag = hit.tagName.toLowerCase();
+return hit.id ? `<${tag} id=${hit.id}>` : `<${tag}>`;
+"#;
+
+/// A javascript function returning true if the top-most element one pixel inside the
+/// top-left corner of the first argument is one of the arguments.
+pub const CORNER_HIT_IS_ONE_OF: &str = r#"
+const elem = arguments[0];
+const rect = elem.getBoundingClientRect();
+const x = rect.left + 1;
+const y = rect.top + 1;
+const root = elem.getRootNode();
+const hit = (root.elementFromPoint ? root : document).elementFromPoint(x, y);
+return Array.prototype.includes.call(arguments, hit);
+"#;
+
+/// A javascript function writing the first argument to the clipboard.
+///
+/// Returns null on success, or a message if the clipboard API is not available or access
+/// was denied.
+pub const WRITE_CLIPBOARD: &str = r#"
+if (!navigator.clipboard || !navigator.clipboard.writeText) {
+    return "the clipboard API is not available";
+}
+return navigator.clipboard.writeText(arguments[0]).then(() => null, (e) => String(e));
+"#;
+
+/// A javascript snippet that prevents `beforeunload` handlers from showing a dialog.
+///
+/// This clears `onbeforeunload` and registers a capturing listener that stops any other
+/// listeners from running, for the top-level window and all same-origin frames.
+/// Cross-origin frames cannot be accessed and are skipped.
+pub const DISCARD_BEFOREUNLOAD: &str = r#"
+(function discard(win) {
+    try {
+        win.onbeforeunload = null;
+        win.addEventListener("beforeunload", function (event) {
+            event.stopImmediatePropagation();
+        }, true);
+    } catch (e) {
+        return;
+    }
+    for (let i = 0; i < win.frames.length; i++) {
+        discard(win.frames[i]);
+    }
+})(window.top);
+"#;
+
+/// A javascript function returning one page of the elements matching a WebDriver locator.
+///
+/// The arguments are the element to search from, the locator strategy and value, the offset
+/// of the first element to return and the maximum number of elements to return.
+pub const QUERY_PAGE: &str = r#"
+const [base, using, value, offset, limit] = arguments;
+switch (using) {
+    case "css selector":
+        return Array.prototype.slice.call(base.querySelectorAll(value), offset, offset + limit);
+    case "xpath": {
+        const result = document.evaluate(
+            value, base, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null
+        );
+        const elems = [];
+        const end = Math.min(result.snapshotLength, offset + limit);
+        for (let i = offset; i < end; i++) {
+            elems.push(result.snapshotItem(i));
+        }
+        return elems;
+    }
+    case "link text":
+    case "partial link text":
+        return Array.from(base.querySelectorAll("a"))
+            .filter((a) => {
+                const text = a.innerText.trim();
+                return using === "link text" ? text === value : text.includes(value);
+            })
+            .slice(offset, offset + limit);
+    default:
+        throw new Error("unsupported locator strategy: " + using);
+}
+"#;
+
+/// A javascript function returning the visible part of an element's client rect.
+///
+/// The rect is clipped to the viewport and to any ancestors that clip their overflow.
+#[cfg(feature = "screenshot-crop")]
+pub const ELEMENT_VISIBLE_RECT: &str = r#"
+const elem = arguments[0];
+const rect = elem.getBoundingClientRect();
+let left = Math.max(rect.left, 0);
+let top = Math.max(rect.top, 0);
+let right = Math.min(rect.right, window.innerWidth);
+let bottom = Math.min(rect.bottom, window.innerHeight);
+for (let node = elem.parentElement; node; node = node.parentElement) {
+    const style = window.getComputedStyle(node);
+    if (style.overflowX === "visible" && style.overflowY === "visible") {
+        continue;
+    }
+    const clip = node.getBoundingClientRect();
+    const clipLeft = clip.left + node.clientLeft;
+    const clipTop = clip.top + node.clientTop;
+    if (style.overflowX !== "visible") {
+        left = Math.max(left, clipLeft);
+        right = Math.min(right, clipLeft + node.clientWidth);
+    }
+    if (style.overflowY !== "visible") {
+        top = Math.max(top, clipTop);
+        bottom = Math.min(bottom, clipTop + node.clientHeight);
+    }
+}
+return {
+    x: left,
+    y: top,
+    width: Math.max(right - left, 0),
+    height: Math.max(bottom - top, 0),
+    elementWidth: rect.width,
+    elementHeight: rect.height,
+    devicePixelRatio: window.devicePixelRatio || 1
+};
+"#;
+
+/// A javascript function returning the text and column span of each of the specified cells.
+pub const TABLE_CELLS: &str = r#"
+return arguments[0].map((cell) => [cell.innerText.trim(), cell.colSpan || 1]);
+"#;
+
+/// A javascript function returning the top-most open modal out of the specified elements,
+/// or null if none of them are open.
+///
+/// Native `<dialog>` elements are open if their `open` property is set, and other elements
+/// are open if they are displayed. Dialogs shown with `showModal()` are always on top,
+/// otherwise the element with the highest z-index wins, and later elements win ties.
+pub const TOP_MODAL: &str = r#"
+const isOpen = (elem) => {
+    if (elem instanceof HTMLDialogElement) {
+        return elem.open;
+    }
+    const style = window.getComputedStyle(elem);
+    return style.display !== "none" && style.visibility !== "hidden" && elem.getClientRects().length > 0;
+};
+const layer = (elem) => {
+    if (elem instanceof HTMLDialogElement && elem.matches(":modal")) {
+        return Infinity;
+    }
+    const zIndex = parseInt(window.getComputedStyle(elem).zIndex, 10);
+    return isNaN(zIndex) ? 0 : zIndex;
+};
+let top = null;
+for (const elem of arguments[0].filter(isOpen)) {
+    if (top === null || layer(elem) >= layer(top)) {
+        top = elem;
+    }
+}
+return top;
+"#;
+
+/// A privileged javascript function returning the type and value of a Firefox preference.
+///
+/// The argument is the name of the preference. The type is the `PREF_*` constant of
+/// `nsIPrefBranch`, and the value is null if the type is not known.
+pub const FIREFOX_GET_PREF: &str = r#"
+const prefs = Services.prefs;
+const name = arguments[0];
+const type = prefs.getPrefType(name);
+switch (type) {
+    case prefs.PREF_STRING:
+        return { type, value: prefs.getStringPref(name) };
+    case prefs.PREF_INT:
+        return { type, value: prefs.getIntPref(name) };
+    case prefs.PREF_BOOL:
+        return { type, value: prefs.getBoolPref(name) };
+    default:
+        return { type, value: null };
+}
+"#;
+
+/// A privileged javascript function setting a Firefox preference.
+///
+/// The arguments are the name of the preference and its value, as serialized by
+/// `PrefValue`.
+pub const FIREFOX_SET_PREF: &str = r#"
+const prefs = Services.prefs;
+const [name, pref] = arguments;
+switch (pref.type) {
+    case "string":
+        prefs.setStringPref(name, pref.value);
+        break;
+    case "int":
+        prefs.setIntPref(name, pref.value);
+        break;
+    case "bool":
+        prefs.setBoolPref(name, pref.value);
+        break;
+    default:
+        throw new Error(`unknown preference type: ${pref.type}`);
+}
+"#;
+
+/// A javascript function expression installing a fake clock as `window.__thirtyfourClock`.
+///
+/// The argument is the starting time, in milliseconds since the Unix epoch. The clock
+/// replaces `Date`, `setTimeout`, `setInterval` and `performance.now`, so that time only
+/// moves when the clock is ticked. Installing it again in the same document does nothing.
+pub const MOCK_CLOCK: &str = r#"
+(function (start) {
+    if (window.__thirtyfourClock) {
+        return;
+    }
+    const real = {
+        Date: window.Date,
+        setTimeout: window.setTimeout,
+        clearTimeout: window.clearTimeout,
+        setInterval: window.setInterval,
+        clearInterval: window.clearInterval,
+    };
+    const perfStart = performance.now();
+    const timers = new Map();
+    let now = start;
+    let nextId = 1;
+
+    function addTimer(callback, delay, args, repeat) {
+        const id = nextId++;
+        delay = Math.max(0, Number(delay) || 0);
+        timers.set(id, { id, callback, args, at: now + delay, interval: repeat ? delay : null });
+        return id;
+    }
+
+    // The earliest timer due at or before the specified time, in the order they were added.
+    function nextTimer(until) {
+        let next = null;
+        for (const timer of timers.values()) {
+            if (timer.at <= until && (next === null || timer.at < next.at)) {
+                next = timer;
+            }
+        }
+        return next;
+    }
+
+    // Fire the timers due at or before the specified time, rethrowing the first error.
+    function fireUntil(until, limit) {
+        let fired = 0;
+        let error = null;
+        for (let timer = nextTimer(until); timer !== null; timer = nextTimer(until)) {
+            if (++fired > limit) {
+                throw new Error(`aborted after running ${limit} timers`);
+            }
+            now = Math.max(now, timer.at);
+            if (timer.interval === null) {
+                timers.delete(timer.id);
+            } else {
+                timer.at += Math.max(1, timer.interval);
+            }
+            try {
+                if (typeof timer.callback === "function") {
+                    timer.callback.apply(window, timer.args);
+                } else {
+                    (0, eval)(String(timer.callback));
+                }
+            } catch (e) {
+                error = error || e;
+            }
+        }
+        if (error) {
+            throw error;
+        }
+        return fired;
+    }
+
+    function FakeDate(...args) {
+        if (!new.target) {
+            return new real.Date(now).toString();
+        }
+        return args.length === 0 ? new real.Date(now) : new real.Date(...args);
+    }
+    FakeDate.prototype = real.Date.prototype;
+    FakeDate.now = () => now;
+    FakeDate.parse = real.Date.parse;
+    FakeDate.UTC = real.Date.UTC;
+
+    window.Date = FakeDate;
+    window.setTimeout = (callback, delay, ...args) => addTimer(callback, delay, args, false);
+    window.setInterval = (callback, delay, ...args) => addTimer(callback, delay, args, true);
+    window.clearTimeout = (id) => timers.delete(id);
+    window.clearInterval = (id) => timers.delete(id);
+    performance.now = () => perfStart + (now - start);
+
+    window.__thirtyfourClock = {
+        tick(ms) {
+            const until = now + ms;
+            const fired = fireUntil(until, Infinity);
+            now = until;
+            return fired;
+        },
+        runAll() {
+            return fireUntil(Infinity, 1000);
+        },
+        now() {
+            return now;
+        },
+        uninstall() {
+            Object.assign(window, real);
+            delete performance.now;
+            timers.clear();
+            delete window.__thirtyfourClock;
+        },
+    };
+})
+"#;
@@ -32,6 +32,16 @@
 //! * `rustls-tls`: (Default) Use rustls to provide TLS support (via reqwest).
 //! * `native-tls`: Use native TLS (via reqwest).
 //! * `component`: (Default) Enable the `Component` derive macro (via thirtyfour-macros).
+//! * `cdp-events`: Enable the websocket connection to the browser's DevTools,
+//!   for handling CDP events (via tokio-tungstenite).
+//! * `istanbul`: Enable converting JavaScript coverage to the Istanbul format, via
+//!   `extensions::cdp::to_istanbul()`.
+//! * `image`: Enable decoding and comparing screenshots (via image).
+//! * `screenshot-crop`: Enable `WebElement::screenshot_cropped_png()` (via image).
+//! * `miette`: Implement `miette::Diagnostic` for `WebDriverError`, with error codes and help
+//!   text (via miette).
+//! * `anyhow`: Include the failed command and HTTP response in the `source()` chain of
+//!   `WebDriverError`, for reports from anyhow or eyre.
 //!
 //! ## Example
 //!
@@ -149,10 +159,11 @@
 pub use stringmatch;
 
 // Export types at root level.
-pub use alert::Alert;
+pub use alert::{Alert, AlertDecision, AlertHandlerId, PromptKind};
 pub use common::cookie;
 pub use common::{
     capabilities::{
+        a11y::A11yProfile,
         chrome::ChromeCapabilities,
         chromium::{ChromiumCapabilities, ChromiumLikeCapabilities},
         desiredcapabilities::*,
@@ -162,11 +173,16 @@ pub use common::{
         opera::OperaCapabilities,
         safari::SafariCapabilities,
     },
+    capture::*,
     command::By,
     cookie::*,
     keys::*,
+    permissions::*,
+    report::*,
     requestdata::*,
+    retry::*,
     types::*,
+    webauthn::*,
 };
 pub use switch_to::SwitchTo;
 pub use web_driver::WebDriver;
@@ -204,7 +220,10 @@ pub mod extensions;
 pub mod session;
 /// Miscellaneous support functions for `thirtyfour` tests.
 pub mod support;
+/// Helpers for running each test of a test suite in its own session.
+pub mod testing;
 
+mod driver_errors;
 mod js;
 mod switch_to;
 mod web_driver;
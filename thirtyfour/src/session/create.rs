@@ -10,17 +10,29 @@ use crate::{
     },
     prelude::WebDriverResult,
     session::http::run_webdriver_cmd,
-    Capabilities, SessionId, TimeoutConfiguration,
+    Capabilities, DriverInfo, SessionId, TimeoutConfiguration,
 };
 
-/// Start a new WebDriver session, returning the session id and the
-/// capabilities JSON that was received back from the server.
+/// Start a new WebDriver session, returning the session id.
 pub async fn start_session(
     http_client: &dyn HttpClient,
     server_url: &Url,
     config: &WebDriverConfig,
     capabilities: Capabilities,
 ) -> WebDriverResult<SessionId> {
+    let (session_id, _) =
+        start_session_with_info(http_client, server_url, config, capabilities).await?;
+    Ok(session_id)
+}
+
+/// Start a new WebDriver session, returning the session id and the browser and driver
+/// described by the capabilities that were received back from the server.
+pub async fn start_session_with_info(
+    http_client: &dyn HttpClient,
+    server_url: &Url,
+    config: &WebDriverConfig,
+    capabilities: Capabilities,
+) -> WebDriverResult<(SessionId, DriverInfo)> {
     let request_data = Command::NewSession(serde_json::Value::Object(capabilities))
         .format_request(&SessionId::null());
 
@@ -46,8 +58,8 @@ pub async fn start_session(
     struct ConnectionData {
         #[serde(default, rename(deserialize = "sessionId"))]
         session_id: String,
-        // #[serde(default)]
-        // capabilities: serde_json::Value,
+        #[serde(default)]
+        capabilities: serde_json::Value,
     }
 
     #[derive(Debug, Deserialize)]
@@ -59,6 +71,7 @@ pub async fn start_session(
 
     let resp: ConnectionResp = serde_json::from_value(v.body)?;
     let data = resp.value;
+    let driver = DriverInfo::from_capabilities(&data.capabilities);
     let session_id = SessionId::from(if resp.session_id.is_empty() {
         data.session_id
     } else {
@@ -70,5 +83,5 @@ pub async fn start_session(
         Command::SetTimeouts(TimeoutConfiguration::default()).format_request(&session_id);
     run_webdriver_cmd(http_client, &request_data, server_url, config).await?;
 
-    Ok(session_id)
+    Ok((session_id, driver))
 }
@@ -1,28 +1,86 @@
-use serde_json::Value;
+use futures_util::stream::{self, Stream};
+use serde_json::{json, Value};
 use std::fmt::{Debug, Display, Formatter};
 use std::future::Future;
-use std::path::Path;
-use std::sync::Arc;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::OnceCell;
 use url::{ParseError, Url};
 
 use crate::action_chain::ActionChain;
+use crate::alert::AlertHandlers;
+use crate::common::action::PointerActionType;
+use crate::common::capture::{CaptureOptions, ErrorCaptureOptions, TimedFrame};
 use crate::common::command::{Command, FormatRequestData};
 use crate::common::config::WebDriverConfig;
-use crate::common::cookie::Cookie;
+use crate::common::cookie::{Cookie, CookieChange};
 use crate::common::print::PrintParameters;
-use crate::error::WebDriverResult;
+use crate::common::report::{ErrorHookId, ErrorHooks};
+use crate::common::requestdata::RequestData;
+use crate::error::{
+    ElementErrorContext, ErrorAttachments, TimeoutErrorBuilder, WebDriverErrorInfo,
+    WebDriverErrorInner, WebDriverResult,
+};
+use crate::extensions::addons::firefox::{FirefoxContext, FirefoxState, FirefoxTools};
+use crate::extensions::cdp::cookies::set_cookie_params;
+use crate::extensions::cdp::performance::MemorySample;
+use crate::extensions::cdp::state::CdpState;
+use crate::extensions::cdp::{validate_locale, validate_timezone};
+use crate::extensions::cdp::{AxAuditReport, AxTree, ChromeCommand, ChromeDevTools, GeoLocation};
+#[cfg(feature = "cdp-events")]
+use crate::extensions::cdp::{
+    CdpConnection, Credentials, DownloadEvent, FileChooser, FileChooserAction,
+};
+use crate::extensions::query::{ElementPollerWithTimeout, IntoElementPoller};
+use crate::js::{DISCARD_BEFOREUNLOAD, MOCK_CLOCK};
 use crate::prelude::WebDriverError;
 use crate::session::scriptret::ScriptRet;
 use crate::support::base64_decode;
+use crate::switch_to::WindowHistory;
 use crate::web_driver::AlreadyQuit;
-use crate::{support, By, OptionRect, Rect, SessionId, SwitchTo, WebDriverStatus, WebElement};
+use crate::{
+    support, By, DriverInfo, LogEntry, OptionRect, Rect, SessionId, SwitchTo, WebDriverStatus,
+    WebElement,
+};
+use crate::{AuthenticatorId, Credential, VirtualAuthenticatorOptions};
 use crate::{IntoArcStr, IntoUrl};
+use crate::{PermissionName, PermissionState};
 use crate::{TimeoutConfiguration, WindowHandle};
 
 use super::http::{run_webdriver_cmd, CmdResponse, HttpClient};
 
+tokio::task_local! {
+    /// Set while an operation is running that reports its own errors to the error hooks.
+    static DEFER_ERROR_HOOKS: ();
+}
+
+/// True if this is running within an operation started via `report_errors()`.
+fn in_operation() -> bool {
+    DEFER_ERROR_HOOKS.try_with(|_| ()).is_ok()
+}
+
+/// Run a step of capturing failure artifacts, giving up at the deadline.
+///
+/// Failures are logged and otherwise ignored, since they should not hide the original error.
+async fn capture_step<T>(
+    deadline: tokio::time::Instant,
+    step: &str,
+    f: impl Future<Output = WebDriverResult<T>>,
+) -> Option<T> {
+    match tokio::time::timeout_at(deadline, f).await {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(error)) => {
+            tracing::debug!(step, %error, "failed to capture error artifact");
+            None
+        }
+        Err(_) => {
+            tracing::debug!(step, "timed out capturing error artifact");
+            None
+        }
+    }
+}
+
 /// The SessionHandle contains a shared reference to the HTTP client
 /// to allow sending commands to the underlying WebDriver.
 pub struct SessionHandle {
@@ -36,6 +94,19 @@ pub struct SessionHandle {
     config: WebDriverConfig,
     /// quit session flag
     quit: Arc<OnceCell<()>>,
+    /// The alert handlers registered via `on_alert()`.
+    alert_handlers: Arc<AlertHandlers>,
+    /// The windows switched to during this session.
+    window_history: Arc<WindowHistory>,
+    /// The browser and driver for this session.
+    driver: Arc<DriverInfo>,
+    /// The hooks registered via `on_error()`.
+    error_hooks: Arc<ErrorHooks>,
+    /// The state kept by extensions for this session, by type.
+    extension_state: Arc<Mutex<http::Extensions>>,
+    /// The DevTools connection, opened when first used.
+    #[cfg(feature = "cdp-events")]
+    pub(crate) cdp_connection: Arc<OnceCell<Arc<CdpConnection>>>,
 }
 
 impl Debug for SessionHandle {
@@ -54,7 +125,13 @@ impl SessionHandle {
         server_url: impl IntoUrl,
         session_id: SessionId,
     ) -> WebDriverResult<Self> {
-        Self::new_with_config(client, server_url, session_id, WebDriverConfig::default())
+        Self::new_with_config(
+            client,
+            server_url,
+            session_id,
+            DriverInfo::default(),
+            WebDriverConfig::default(),
+        )
     }
 
     /// Create new `SessionHandle` with the specified `WebDriverConfig`.
@@ -62,6 +139,7 @@ impl SessionHandle {
         client: Arc<dyn HttpClient>,
         server_url: impl IntoUrl,
         session_id: SessionId,
+        driver: DriverInfo,
         config: WebDriverConfig,
     ) -> WebDriverResult<Self> {
         Ok(Self {
@@ -70,6 +148,13 @@ impl SessionHandle {
             session_id,
             config,
             quit: Arc::new(OnceCell::new()),
+            alert_handlers: Arc::new(AlertHandlers::default()),
+            window_history: Arc::new(WindowHistory::default()),
+            driver: Arc::new(driver),
+            error_hooks: Arc::new(ErrorHooks::default()),
+            extension_state: Arc::new(Mutex::new(http::Extensions::new())),
+            #[cfg(feature = "cdp-events")]
+            cdp_connection: Arc::new(OnceCell::new()),
         })
     }
 
@@ -82,6 +167,13 @@ impl SessionHandle {
             server_url: Arc::clone(&self.server_url),
             session_id: self.session_id.clone(),
             quit: Arc::clone(&self.quit),
+            alert_handlers: Arc::clone(&self.alert_handlers),
+            window_history: Arc::clone(&self.window_history),
+            driver: Arc::clone(&self.driver),
+            error_hooks: Arc::clone(&self.error_hooks),
+            extension_state: Arc::clone(&self.extension_state),
+            #[cfg(feature = "cdp-events")]
+            cdp_connection: Arc::clone(&self.cdp_connection),
             config,
         }
     }
@@ -102,10 +194,186 @@ impl SessionHandle {
         &self.config
     }
 
+    /// The browser and driver for this session, as reported when the session was created.
+    ///
+    /// This is empty if the handle was created with [`SessionHandle::new`].
+    pub fn driver_info(&self) -> &DriverInfo {
+        &self.driver
+    }
+
+    /// The address of the browser's DevTools, as reported when the session was created.
+    ///
+    /// This is a `host:port` address or a websocket URL, used to open the DevTools
+    /// connection of `ChromeDevTools`. Firefox only reports it if it was requested via
+    /// `FirefoxCapabilities::enable_debugger_address()`. See [`DriverInfo::devtools_address`].
+    pub fn debugger_address(&self) -> Option<String> {
+        self.driver.devtools_address.clone()
+    }
+
+    /// Get the Firefox-specific commands for this session.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::firefox();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let png = driver.firefox().full_page_screenshot_png().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn firefox(self: &Arc<SessionHandle>) -> FirefoxTools {
+        FirefoxTools::new(self.clone())
+    }
+
     /// Send the specified command to the webdriver server.
+    ///
+    /// If the command fails because an alert is open, the handlers registered via
+    /// `on_alert()` are consulted and the command may be retried once. If the command
+    /// still fails, the error is passed to the hooks registered via `on_error()`, unless
+    /// the command was sent by an operation that reports its own errors.
     pub async fn cmd(&self, command: impl FormatRequestData) -> WebDriverResult<CmdResponse> {
+        if !in_operation() {
+            self.take_js_exceptions().map_err(|e| self.report_error(e))?;
+        }
         let request_data = command.format_request(&self.session_id);
-        run_webdriver_cmd(&*self.client, &request_data, &self.server_url, &self.config).await
+        let result = match self.run_cmd(&request_data).await {
+            Err(e) if matches!(e.as_inner(), WebDriverErrorInner::UnexpectedAlertOpen(_)) => {
+                match self.handle_unexpected_alert(&e).await {
+                    Ok(true) => self.run_cmd(&request_data).await,
+                    Ok(false) => Err(e),
+                    Err(e) => Err(e),
+                }
+            }
+            result => result,
+        };
+        self.call_error_hooks(result)
+    }
+
+    /// Send the specified command to the webdriver server, bypassing the alert handlers.
+    pub(crate) async fn cmd_unhandled(
+        &self,
+        command: impl FormatRequestData,
+    ) -> WebDriverResult<CmdResponse> {
+        let result = self.run_cmd(&command.format_request(&self.session_id)).await;
+        self.call_error_hooks(result)
+    }
+
+    async fn run_cmd(&self, request_data: &RequestData) -> WebDriverResult<CmdResponse> {
+        run_webdriver_cmd(&*self.client, request_data, &self.server_url, &self.config)
+            .await
+            .map_err(|e| {
+                let e = e.normalize(&self.driver).with_session_id(&self.session_id);
+                match self.extension_state::<FirefoxState>().context() {
+                    FirefoxContext::Content => e,
+                    context => e.with_context(format!("Firefox {context}")),
+                }
+            })
+    }
+
+    fn call_error_hooks<T>(&self, result: WebDriverResult<T>) -> WebDriverResult<T> {
+        result.map_err(|e| self.report_error(e))
+    }
+
+    /// Pass the error to the hooks registered via `on_error()`, unless it is raised within
+    /// `report_errors()`, in which case the operation reports it once it is complete.
+    pub(crate) fn report_error(&self, error: WebDriverError) -> WebDriverError {
+        if !in_operation() {
+            self.error_hooks.call(&error);
+        }
+        error
+    }
+
+    /// Run the operation, passing its error to the hooks registered via `on_error()`
+    /// once, after the operation has added its context and attachments.
+    ///
+    /// The failed commands and polls within the operation are not reported individually.
+    /// Nested operations are reported by the outermost one.
+    pub(crate) async fn report_errors<T>(
+        &self,
+        operation: impl Future<Output = WebDriverResult<T>>,
+    ) -> WebDriverResult<T> {
+        if in_operation() {
+            return operation.await;
+        }
+        let operation = async {
+            self.take_js_exceptions()?;
+            operation.await
+        };
+        DEFER_ERROR_HOOKS.scope((), operation).await.map_err(|e| self.report_error(e))
+    }
+
+    /// Return a `JavascriptError` listing the exceptions recorded for
+    /// `fail_on_js_exception()` since they were last reported, if there are any.
+    ///
+    /// This is checked when the caller starts a command or an operation, and not for the
+    /// commands sent within an operation, so that exceptions do not interrupt a wait or
+    /// the capture of failure artifacts.
+    fn take_js_exceptions(&self) -> WebDriverResult<()> {
+        #[cfg(feature = "cdp-events")]
+        if let Some(connection) = self.cdp_connection.get() {
+            return connection.take_exceptions();
+        }
+        Ok(())
+    }
+
+    /// Register a hook that is called with the error whenever an operation fails.
+    ///
+    /// Each failed operation is reported once, after the selector, the failure artifacts
+    /// and any other context have been attached, so retried commands and element queries
+    /// that poll until a timeout produce a single report.
+    ///
+    /// Hooks are called in the order they were registered, on the task that sent the
+    /// command, so they should return quickly. Use [`FailureReporter`] to write the errors
+    /// to a file without blocking.
+    ///
+    /// Hooks are shared by all clones of this session and stay registered until removed
+    /// via `remove_error_hook()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let id = driver.on_error(|error| eprintln!("{}", error.to_json()));
+    /// driver.find(By::Id("button")).await?.click().await?;
+    /// driver.remove_error_hook(id);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
+    /// [`FailureReporter`]: crate::FailureReporter
+    pub fn on_error<F>(&self, hook: F) -> ErrorHookId
+    where
+        F: Fn(&WebDriverError) + Send + Sync + 'static,
+    {
+        self.error_hooks.add(Arc::new(hook))
+    }
+
+    /// Remove an error hook registered via `on_error()`.
+    ///
+    /// Returns false if the hook was already removed.
+    pub fn remove_error_hook(&self, id: ErrorHookId) -> bool {
+        self.error_hooks.remove(id)
+    }
+
+    pub(crate) fn alert_handlers(&self) -> &AlertHandlers {
+        &self.alert_handlers
+    }
+
+    pub(crate) fn window_history(&self) -> &WindowHistory {
+        &self.window_history
     }
 
     /// Get the WebDriver status.
@@ -201,6 +469,75 @@ impl SessionHandle {
         Ok(())
     }
 
+    /// Navigate to the specified URL without being blocked by `beforeunload` handlers.
+    ///
+    /// The handlers are discarded via `discard_unload_handlers()` before navigating.
+    /// If a dialog is shown anyway, it is accepted so that the navigation can proceed.
+    ///
+    /// **NOTE**: Handlers registered by cross-origin iframes cannot be discarded.
+    ///       Accepting the resulting dialog requires the `unhandledPromptBehavior`
+    ///       capability to leave it open (e.g. "ignore"). Otherwise the driver handles it
+    ///       and the error is returned, and any residual dialog can be handled via
+    ///       `WebDriver::wait_for_alert()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.goto_discarding_unload("https://www.rust-lang.org").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn goto_discarding_unload(&self, url: impl IntoArcStr) -> WebDriverResult<()> {
+        let url = url.into();
+        self.discard_unload_handlers().await?;
+        match self.goto(url.clone()).await {
+            Ok(()) => Ok(()),
+            Err(e) => match self.has_alert().await {
+                Ok(true) => {
+                    self.accept_alert().await?;
+                    self.goto(url).await
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Prevent `beforeunload` handlers in the current window from showing a dialog.
+    ///
+    /// This clears `window.onbeforeunload` and stops any `beforeunload` listeners from
+    /// running, for the top-level document and all same-origin frames.
+    ///
+    /// **NOTE**: Handlers registered by cross-origin iframes cannot be discarded.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.discard_unload_handlers().await?;
+    /// driver.close_window().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn discard_unload_handlers(&self) -> WebDriverResult<()> {
+        self.cmd(Command::ExecuteScript(DISCARD_BEFOREUNLOAD.into(), Arc::from([]))).await?;
+        Ok(())
+    }
+
     /// Navigate to the specified URL. Alias of goto().
     pub async fn get(&self, url: impl IntoArcStr) -> WebDriverResult<()> {
         self.goto(url).await
@@ -253,8 +590,17 @@ impl SessionHandle {
     /// # }
     /// ```
     pub async fn find(self: &Arc<Self>, by: By) -> WebDriverResult<WebElement> {
-        let r = self.cmd(Command::FindElement(by.into())).await?;
-        r.element(self.clone())
+        let find = self.config.retry_policy.run_find("find", || async {
+            let r = self.cmd(Command::FindElement(by.clone().into())).await.map_err(|e| {
+                e.with_element_context(ElementErrorContext {
+                    selector: Some(by.describe()),
+                    operation: Some("find".to_string()),
+                    ..Default::default()
+                })
+            })?;
+            r.element(self.clone())
+        });
+        self.report_errors(find).await
     }
 
     /// Search for an element on the current page using the specified selector.
@@ -289,8 +635,17 @@ impl SessionHandle {
     /// # }
     /// ```
     pub async fn find_all(self: &Arc<Self>, by: By) -> WebDriverResult<Vec<WebElement>> {
-        let r = self.cmd(Command::FindElements(by.into())).await?;
-        r.elements(self.clone())
+        let find = self.config.retry_policy.run_find("find_all", || async {
+            let r = self.cmd(Command::FindElements(by.clone().into())).await.map_err(|e| {
+                e.with_element_context(ElementErrorContext {
+                    selector: Some(by.describe()),
+                    operation: Some("find_all".to_string()),
+                    ..Default::default()
+                })
+            })?;
+            r.elements(self.clone())
+        });
+        self.report_errors(find).await
     }
 
     /// Search for all elements on the current page that match the specified selector.
@@ -921,6 +1276,50 @@ impl SessionHandle {
         ActionChain::new_with_delay(self.clone(), key_delay, pointer_delay)
     }
 
+    /// Create a new action chain for this session, with the specified pointer type.
+    ///
+    /// Use [`PointerActionType::Pen`] for pen input with properties such as pressure and
+    /// tilt, or [`PointerActionType::Touch`] for touch input.
+    pub fn action_chain_with_pointer_type(
+        self: &Arc<SessionHandle>,
+        pointer_type: PointerActionType,
+    ) -> ActionChain {
+        ActionChain::new_with_pointer_type(self.clone(), pointer_type)
+    }
+
+    /// Create a new action chain for this session, with the specified ids for its key and
+    /// pointer input sources.
+    ///
+    /// The driver keeps the state of each input source between chains by id, so a button
+    /// held by one chain can be released by another chain with the same pointer id. See
+    /// [`ActionChain::new_named()`] for details.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// driver.action_chain_named("keys", "mouse").click_and_hold_element(&elem).perform().await?;
+    /// // Later, release the button held by the first chain.
+    /// driver.action_chain_named("keys", "mouse").release().perform().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub fn action_chain_named(
+        self: &Arc<SessionHandle>,
+        key_id: &str,
+        pointer_id: &str,
+    ) -> ActionChain {
+        ActionChain::new_named(self.clone(), key_id, pointer_id)
+    }
+
     /// Get all cookies.
     ///
     /// # Example:
@@ -1025,6 +1424,10 @@ impl SessionHandle {
 
     /// Add the specified cookie.
     ///
+    /// If the cookie has a [`Cookie::partition_key`] and the driver of a Chromium-based
+    /// browser rejects it, the cookie will instead be set via the Chrome DevTools
+    /// Protocol. If that fails too, the error from the driver is returned.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -1046,143 +1449,1445 @@ impl SessionHandle {
     /// # }
     /// ```
     pub async fn add_cookie(&self, cookie: Cookie) -> WebDriverResult<()> {
-        self.cmd(Command::AddCookie(cookie)).await?;
-        Ok(())
-    }
-
-    /// Print the current window and return it as a PDF.
-    pub async fn print_page(&self, parameters: PrintParameters) -> WebDriverResult<Vec<u8>> {
-        base64_decode(&self.print_page_base64(parameters).await?)
-    }
-
-    /// Print the current window and return it as a PDF, base64 encoded.
-    pub async fn print_page_base64(&self, parameters: PrintParameters) -> WebDriverResult<String> {
-        self.cmd(Command::PrintPage(parameters)).await?.value()
-    }
-
-    /// Take a screenshot of the current window and return it as PNG, base64 encoded.
-    pub async fn screenshot_as_png_base64(&self) -> WebDriverResult<String> {
-        self.cmd(Command::TakeScreenshot).await?.value()
-    }
-
-    /// Take a screenshot of the current window and return it as PNG bytes.
-    pub async fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
-        base64_decode(&self.screenshot_as_png_base64().await?)
-    }
-
-    /// Take a screenshot of the current window and write it to the specified filename.
-    pub async fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
-        let png = self.screenshot_as_png().await?;
-        support::write_file(path, png).await?;
-        Ok(())
-    }
+        if cookie.partition_key.is_none() || !self.driver_info().is_chromium() {
+            self.cmd(Command::AddCookie(cookie)).await?;
+            return Ok(());
+        }
 
-    /// Return a SwitchTo struct for switching to another window or frame.
-    #[deprecated(
-        since = "0.30.0",
-        note = "SwitchTo has been deprecated. Use WebDriver::switch_to_*() methods instead"
-    )]
-    pub fn switch_to(self: &Arc<SessionHandle>) -> SwitchTo {
-        SwitchTo::new(self.clone())
+        match self.cmd(Command::AddCookie(cookie.clone())).await {
+            Ok(_) => Ok(()),
+            Err(e)
+                if matches!(
+                    e.as_inner(),
+                    WebDriverErrorInner::InvalidArgument(_)
+                        | WebDriverErrorInner::UnableToSetCookie(_)
+                        | WebDriverErrorInner::UnsupportedOperation(_)
+                ) =>
+            {
+                let fallback = async {
+                    let url = self.current_url().await?;
+                    let params = set_cookie_params(&cookie, &url);
+                    let command =
+                        ChromeCommand::ExecuteCdpCommand("Network.setCookie".to_string(), params);
+                    self.cmd(command).await
+                };
+                match fallback.await {
+                    Ok(_) => Ok(()),
+                    Err(fallback_error) => {
+                        tracing::debug!(error = %fallback_error, "failed to set partitioned cookie via CDP");
+                        Err(e)
+                    }
+                }
+            }
+            Err(e) => Err(e),
+        }
     }
 
-    /// Set the current window name.
+    /// Watch the specified cookie for changes.
     ///
-    /// Useful for switching between windows/tabs using [`WebDriver::switch_to_named_window`]
-    ///
-    /// [`WebDriver::switch_to_named_window`]: SessionHandle::switch_to_named_window
+    /// The returned stream polls all cookies at the specified interval and yields a
+    /// [`CookieChange`] each time the named cookie is added, updated or removed, compared
+    /// to its state when this method was called. Polling stops when the stream is dropped.
+    /// If fetching the cookies fails, the error is yielded and the stream ends.
     ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use futures_util::StreamExt;
+    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// // Get the current window handle.
-    /// let handle = driver.window().await?;
-    /// driver.set_window_name("main").await?;
-    ///
-    /// // Open a new tab.
-    /// let new_handle = driver.new_tab().await?;
-    ///
-    /// // Get window handles and switch to the new tab.
-    /// driver.switch_to_window(new_handle).await?;
-    ///
-    /// // We are now controlling the new tab.
-    /// driver.goto("https://www.rust-lang.org").await?;
-    /// assert_ne!(driver.window().await?, handle);
-    ///
-    /// // Switch back to original tab using window name.
-    /// driver.switch_to_named_window("main").await?;
-    /// assert_eq!(driver.window().await?, handle);
+    /// let changes = driver.watch_cookie("session", Duration::from_millis(100)).await?;
+    /// let mut changes = std::pin::pin!(changes);
+    /// while let Some(change) = changes.next().await {
+    ///     println!("cookie changed: {:?}", change?);
+    /// }
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn set_window_name(
-        self: &Arc<SessionHandle>,
-        window_name: impl Display,
-    ) -> WebDriverResult<()> {
-        let script = format!(r#"window.name = "{}""#, window_name);
-        self.execute(script, Vec::new()).await?;
-        Ok(())
+    pub async fn watch_cookie(
+        self: &Arc<Self>,
+        name: impl IntoArcStr,
+        poll_interval: Duration,
+    ) -> WebDriverResult<impl Stream<Item = WebDriverResult<CookieChange>> + Send + 'static> {
+        let name = name.into();
+        let handle = Arc::clone(self);
+        let last = self.get_all_cookies().await?.into_iter().find(|c| *c.name == *name);
+
+        Ok(stream::unfold(Some((handle, last)), move |state| {
+            let name = name.clone();
+            async move {
+                let (handle, mut last) = state?;
+                loop {
+                    support::sleep(poll_interval).await;
+                    let current = match handle.get_all_cookies().await {
+                        Ok(cookies) => cookies.into_iter().find(|c| *c.name == *name),
+                        Err(e) => return Some((Err(e), None)),
+                    };
+                    let change = CookieChange::diff(last.as_ref(), current.as_ref());
+                    last = current;
+                    if let Some(change) = change {
+                        return Some((Ok(change), Some((handle, last))));
+                    }
+                }
+            }
+        }))
     }
 
-    /// Execute the specified function in a new browser tab, closing the tab when complete.
+    /// Wait until the specified cookie exists and satisfies the predicate.
     ///
-    /// The return value will be that of the supplied function, unless an error occurs while
-    /// opening or closing the tab.
+    /// Returns a `Timeout` error if the predicate was not satisfied within the timeout.
     ///
+    /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
     /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     block_on(async {
     /// #         let caps = DesiredCapabilities::chrome();
     /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
-    /// let window_title = driver.in_new_tab(|| async {
-    ///     driver.goto("https://www.google.com").await?;
-    ///     driver.title().await
-    /// }).await?;
-    /// assert_eq!(window_title, "Google");
+    /// let old = driver.get_named_cookie("session").await?;
+    /// let new = driver
+    ///     .wait_for_cookie("session", |c| c.value != old.value, Duration::from_secs(10))
+    ///     .await?;
     /// #         driver.quit().await?;
     /// #         Ok(())
     /// #     })
     /// # }
     /// ```
-    pub async fn in_new_tab<F, Fut, T>(&self, f: F) -> WebDriverResult<T>
+    pub async fn wait_for_cookie<F>(
+        &self,
+        name: impl IntoArcStr,
+        predicate: F,
+        timeout: Duration,
+    ) -> WebDriverResult<Cookie>
     where
-        F: FnOnce() -> Fut + Send,
-        Fut: Future<Output = WebDriverResult<T>> + Send,
-        T: Send,
+        F: Fn(&Cookie) -> bool + Send + Sync,
     {
-        let handle = self.window().await?;
+        let name = name.into();
+        let mut wait = TimeoutErrorBuilder::new(format!("cookie '{name}' to match the predicate"));
+        let mut poller = ElementPollerWithTimeout::new(timeout, Duration::from_millis(500)).start();
+        let poll = async {
+            loop {
+                wait.attempt();
+                let mut cookies = self.get_all_cookies().await?;
+                cookies.retain(|c| *c.name == *name);
+                if let Some(i) = cookies.iter().position(&predicate) {
+                    return Ok(cookies.swap_remove(i));
+                }
+                wait.observed(match cookies.first() {
+                    Some(cookie) => format!("cookie '{name}' with value {:?}", cookie.value),
+                    None => format!("no cookie named '{name}'"),
+                });
 
-        // Open new tab.
-        let tab_handle = self.new_tab().await?;
+                if !poller.tick().await {
+                    return Err(wait.build());
+                }
+            }
+        };
+        self.report_errors(poll).await
+    }
+
+    /// Add a virtual authenticator, for testing WebAuthn and passkey sign-in without a
+    /// real security key. Returns the id of the new authenticator.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::VirtualAuthenticatorOptions;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let authenticator = driver
+    ///     .add_virtual_authenticator(VirtualAuthenticatorOptions {
+    ///         has_resident_key: true,
+    ///         has_user_verification: true,
+    ///         is_user_verified: true,
+    ///         ..Default::default()
+    ///     })
+    ///     .await?;
+    /// // Sign up on the site, which creates a credential.
+    /// let credentials = driver.get_credentials(&authenticator).await?;
+    /// driver.remove_virtual_authenticator(&authenticator).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn add_virtual_authenticator(
+        &self,
+        options: VirtualAuthenticatorOptions,
+    ) -> WebDriverResult<AuthenticatorId> {
+        let id: String = self.cmd(Command::AddVirtualAuthenticator(options)).await?.value()?;
+        Ok(AuthenticatorId::from(id))
+    }
+
+    /// Remove the specified virtual authenticator, and the credentials it stores.
+    pub async fn remove_virtual_authenticator(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> WebDriverResult<()> {
+        self.cmd(Command::RemoveVirtualAuthenticator(authenticator_id.clone())).await?;
+        Ok(())
+    }
+
+    /// Add the specified credential to a virtual authenticator.
+    pub async fn add_credential(
+        &self,
+        authenticator_id: &AuthenticatorId,
+        credential: Credential,
+    ) -> WebDriverResult<()> {
+        self.cmd(Command::AddCredential(authenticator_id.clone(), credential)).await?;
+        Ok(())
+    }
+
+    /// Get the credentials stored by a virtual authenticator.
+    pub async fn get_credentials(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> WebDriverResult<Vec<Credential>> {
+        self.cmd(Command::GetCredentials(authenticator_id.clone())).await?.value()
+    }
+
+    /// Remove the credential with the specified id from a virtual authenticator.
+    pub async fn remove_credential(
+        &self,
+        authenticator_id: &AuthenticatorId,
+        credential_id: &[u8],
+    ) -> WebDriverResult<()> {
+        self.cmd(Command::RemoveCredential(authenticator_id.clone(), credential_id.into())).await?;
+        Ok(())
+    }
+
+    /// Remove every credential from a virtual authenticator.
+    pub async fn remove_all_credentials(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> WebDriverResult<()> {
+        self.cmd(Command::RemoveAllCredentials(authenticator_id.clone())).await?;
+        Ok(())
+    }
+
+    /// Set whether user verification, such as a fingerprint or PIN, succeeds on a virtual
+    /// authenticator.
+    pub async fn set_user_verified(
+        &self,
+        authenticator_id: &AuthenticatorId,
+        verified: bool,
+    ) -> WebDriverResult<()> {
+        self.cmd(Command::SetUserVerified(authenticator_id.clone(), verified)).await?;
+        Ok(())
+    }
+
+    /// Set the state of the specified permission for the current origin, as if the user
+    /// had chosen it.
+    ///
+    /// If the driver does not implement the WebDriver permissions command, the CDP
+    /// `Browser.setPermission` command is used instead. Returns an `UnsupportedOperation`
+    /// error if neither is available.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::{PermissionName, PermissionState};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.set_permission(PermissionName::Notifications, PermissionState::Denied).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_permission(
+        &self,
+        name: PermissionName,
+        state: PermissionState,
+    ) -> WebDriverResult<()> {
+        let e = match self.cmd(Command::SetPermission(name, state)).await {
+            Ok(_) => return Ok(()),
+            Err(e) if is_unknown_command(&e) => e,
+            Err(e) => return Err(e),
+        };
+        let params = json!({ "permission": { "name": name }, "setting": state });
+        match self.cdp("Browser.setPermission", params).await {
+            Ok(_) => Ok(()),
+            Err(cdp_e) if is_unknown_command(&cdp_e) => {
+                Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(format!(
+                    "setting permissions is not supported by this driver: {e}"
+                ))))
+            }
+            Err(cdp_e) => Err(cdp_e),
+        }
+    }
+
+    /// Grant each of the specified permissions for the current origin.
+    ///
+    /// See [`SessionHandle::set_permission`].
+    pub async fn grant_permissions(&self, names: &[PermissionName]) -> WebDriverResult<()> {
+        for name in names {
+            self.set_permission(*name, PermissionState::Granted).await?;
+        }
+        Ok(())
+    }
+
+    /// Print the current window and return it as a PDF.
+    pub async fn print_page(&self, parameters: PrintParameters) -> WebDriverResult<Vec<u8>> {
+        base64_decode(&self.print_page_base64(parameters).await?)
+    }
+
+    /// Print the current window and return it as a PDF, base64 encoded.
+    pub async fn print_page_base64(&self, parameters: PrintParameters) -> WebDriverResult<String> {
+        self.cmd(Command::PrintPage(parameters)).await?.value()
+    }
+
+    /// Take a screenshot of the current window and return it as PNG, base64 encoded.
+    pub async fn screenshot_as_png_base64(&self) -> WebDriverResult<String> {
+        self.cmd(Command::TakeScreenshot).await?.value()
+    }
+
+    /// Take a screenshot of the current window and return it as PNG bytes.
+    pub async fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
+        base64_decode(&self.screenshot_as_png_base64().await?)
+    }
+
+    /// Take a screenshot of the current window and decode it as an image.
+    ///
+    /// Requires the `image` feature.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let image = driver.screenshot_image().await?;
+    /// println!("{}x{}", image.width(), image.height());
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "image")]
+    pub async fn screenshot_image(&self) -> WebDriverResult<image::DynamicImage> {
+        support::decode_png(&self.screenshot_as_png().await?)
+    }
+
+    /// Take a screenshot of the current window and write it to the specified filename.
+    pub async fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
+        let png = self.screenshot_as_png().await?;
+        support::write_file(path, png).await?;
+        Ok(())
+    }
+
+    /// Capture the specified number of screenshots of the current window, one per interval.
+    ///
+    /// This is useful for debugging animations. See `capture_while()` for more options.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let frames = driver.capture_sequence(10, Duration::from_millis(50)).await?;
+    /// for frame in &frames {
+    ///     println!("{:?}: {} bytes", frame.at - frames[0].at, frame.png.len());
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn capture_sequence(
+        &self,
+        count: usize,
+        interval: Duration,
+    ) -> WebDriverResult<Vec<TimedFrame>> {
+        let options = CaptureOptions {
+            interval,
+            max_frames: count,
+            output_dir: None,
+        };
+        self.capture_while(std::future::pending::<()>(), options).await
+    }
+
+    /// Capture screenshots of the current window on an interval until the specified
+    /// future resolves, or until `options.max_frames` screenshots have been captured.
+    ///
+    /// If `options.output_dir` is set, each screenshot is written to a new file in that
+    /// directory as soon as it is captured, rather than being kept in memory.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::CaptureOptions;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("menu")).await?;
+    /// let options = CaptureOptions {
+    ///     output_dir: Some("target/frames".into()),
+    ///     ..Default::default()
+    /// };
+    /// let frames = driver.capture_while(elem.wait_until().displayed(), options).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn capture_while<F>(
+        &self,
+        condition: F,
+        options: CaptureOptions,
+    ) -> WebDriverResult<Vec<TimedFrame>>
+    where
+        F: Future,
+    {
+        let mut condition = std::pin::pin!(condition);
+        // A zero interval would panic, and screenshots take far longer than a millisecond.
+        let mut interval = tokio::time::interval(options.interval.max(Duration::from_millis(1)));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        let mut frames = Vec::new();
+        while frames.len() < options.max_frames {
+            tokio::select! {
+                biased;
+                _ = &mut condition => break,
+                _ = interval.tick() => {}
+            }
+
+            let png = self.screenshot_as_png().await?;
+            let at = Instant::now();
+            let frame = match &options.output_dir {
+                Some(dir) => TimedFrame {
+                    at,
+                    png: Vec::new(),
+                    path: Some(support::write_unique_file(dir, "frame", "png", png).await?),
+                },
+                None => TimedFrame {
+                    at,
+                    png,
+                    path: None,
+                },
+            };
+            frames.push(frame);
+        }
+        Ok(frames)
+    }
+
+    /// Take a screenshot of the whole page, beyond the viewport, and return it as PNG bytes.
+    ///
+    /// This uses the native full-page screenshot of geckodriver for Firefox sessions, and
+    /// [`SessionHandle::full_page_screenshot_cdp`] otherwise.
+    ///
+    /// Returns an `UnsupportedOperation` error if the browser is neither Firefox nor
+    /// Chromium-based.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let png = driver.full_page_screenshot_png().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn full_page_screenshot_png(self: &Arc<SessionHandle>) -> WebDriverResult<Vec<u8>> {
+        if self.driver_info().browser_name == "firefox" {
+            return self.firefox().full_page_screenshot_png().await;
+        }
+        self.full_page_screenshot_cdp().await
+    }
+
+    /// Take a screenshot of the whole page, beyond the viewport, and return it as PNG bytes.
+    ///
+    /// This uses the Chrome DevTools Protocol to temporarily resize the viewport to the
+    /// size of the page content, which avoids the glitches of stitching several viewport
+    /// screenshots on pages with sticky or parallax elements. The device metrics are
+    /// restored afterwards, including when capturing fails: metrics set via
+    /// [`ChromeDevTools::set_device_metrics`] or [`ChromeDevTools::emulate_device`] are
+    /// applied again, and otherwise the override is cleared. The browser cannot report an
+    /// override set with a raw `Emulation.setDeviceMetricsOverride` command, so such an
+    /// override is lost.
+    ///
+    /// Returns an `UnsupportedOperation` error if the browser is not Chromium-based.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let png = driver.full_page_screenshot_cdp().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn full_page_screenshot_cdp(&self) -> WebDriverResult<Vec<u8>> {
+        let metrics = match self.cdp("Page.getLayoutMetrics", json!({})).await {
+            Ok(metrics) => metrics,
+            Err(e) if is_unknown_command(&e) => {
+                return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                    format!("full-page screenshots via CDP require a Chromium-based browser: {e}"),
+                )));
+            }
+            Err(e) => return Err(e),
+        };
+        let content_size = metrics.get("cssContentSize").or_else(|| metrics.get("contentSize"));
+        let dimension = |name: &str| {
+            content_size.and_then(|size| size.get(name)).and_then(Value::as_f64).ok_or_else(|| {
+                WebDriverError::ParseError(format!("Page.getLayoutMetrics: missing {name}"))
+            })
+        };
+        let (width, height) = (dimension("width")?.ceil(), dimension("height")?.ceil());
+
+        let previous = self.extension_state::<CdpState>().device_metrics();
+        self.cdp(
+            "Emulation.setDeviceMetricsOverride",
+            json!({ "width": width, "height": height, "deviceScaleFactor": 0, "mobile": false }),
+        )
+        .await?;
+        let screenshot = self
+            .cdp(
+                "Page.captureScreenshot",
+                json!({ "format": "png", "captureBeyondViewport": true, "fromSurface": true }),
+            )
+            .await;
+        // Always restore the original metrics, even if capturing failed.
+        let restored = match previous {
+            Some(metrics) => {
+                self.cdp("Emulation.setDeviceMetricsOverride", metrics.to_params()).await
+            }
+            None => self.cdp("Emulation.clearDeviceMetricsOverride", json!({})).await,
+        };
+        let screenshot = screenshot?;
+        restored?;
+
+        match screenshot.get("data").and_then(Value::as_str) {
+            Some(data) => base64_decode(data),
+            None => {
+                Err(WebDriverError::ParseError("Page.captureScreenshot: missing data".to_string()))
+            }
+        }
+    }
+
+    /// Get the accessibility tree of the page, as exposed to assistive technologies such
+    /// as screen readers, via the CDP `Accessibility.getFullAXTree` command.
+    ///
+    /// Returns an `UnsupportedOperation` error if the browser is not Chromium-based.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let tree = driver.accessibility_tree().await?;
+    /// for button in tree.find_by_role("button") {
+    ///     println!("button: {}", button.name);
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn accessibility_tree(&self) -> WebDriverResult<AxTree> {
+        match self.cdp("Accessibility.getFullAXTree", json!({})).await {
+            Ok(ret) => AxTree::from_cdp(ret),
+            Err(e) if is_unknown_command(&e) => {
+                Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(format!(
+                    "accessibility trees via CDP require a Chromium-based browser: {e}"
+                ))))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Check the accessibility tree of the page for images without names, buttons and
+    /// links with empty names, and form controls without labels. See
+    /// [`AxTree::basic_audit`].
+    ///
+    /// Returns an `UnsupportedOperation` error if the browser is not Chromium-based.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let report = driver.basic_a11y_audit().await?;
+    /// for violation in &report.violations {
+    ///     if let Some(id) = violation.backend_node_id {
+    ///         let elem = driver.element_from_backend_node_id(id).await?;
+    ///         println!("{violation}: {}", elem.outer_html().await?);
+    ///     }
+    /// }
+    /// assert!(report.is_empty(), "{report}");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn basic_a11y_audit(&self) -> WebDriverResult<AxAuditReport> {
+        Ok(self.accessibility_tree().await?.basic_audit())
+    }
+
+    /// Get the element with the specified id in the CDP `DOM` domain, such as
+    /// [`AxNode::backend_node_id`], via the CDP `DOM.resolveNode` command.
+    ///
+    /// The element must be in the current frame.
+    ///
+    /// [`AxNode::backend_node_id`]: crate::extensions::cdp::AxNode::backend_node_id
+    pub async fn element_from_backend_node_id(
+        self: &Arc<SessionHandle>,
+        backend_node_id: i64,
+    ) -> WebDriverResult<WebElement> {
+        let node = self.cdp("DOM.resolveNode", json!({ "backendNodeId": backend_node_id })).await?;
+        let Some(object_id) = node["object"]["objectId"].as_str() else {
+            return Err(WebDriverError::ParseError(
+                "DOM.resolveNode: missing objectId".to_string(),
+            ));
+        };
+        // Hand the node over to a WebDriver script via the page, as CDP object ids are not
+        // WebDriver element references.
+        let params = json!({
+            "objectId": object_id,
+            "functionDeclaration": "function () { window.__thirtyfourNode = this; }",
+        });
+        let stored = self.cdp("Runtime.callFunctionOn", params).await;
+        let _ = self.cdp("Runtime.releaseObject", json!({ "objectId": object_id })).await;
+        stored?;
+        let script = "const node = window.__thirtyfourNode; \
+                      delete window.__thirtyfourNode; \
+                      return node;";
+        self.execute(script, Vec::new()).await?.element()
+    }
+
+    /// Execute the specified CDP command and return the result.
+    async fn cdp(&self, command: &str, params: Value) -> WebDriverResult<Value> {
+        self.cmd(ChromeCommand::ExecuteCdpCommand(command.to_string(), params)).await?.value()
+    }
+
+    /// Take a screenshot of the current window and return it as PNG, base64 encoded.
+    /// Alias of screenshot_as_png_base64().
+    pub async fn screenshot_as_base64(&self) -> WebDriverResult<String> {
+        self.screenshot_as_png_base64().await
+    }
+
+    /// Take a screenshot of the current window and write it to a new file in the
+    /// specified directory, creating the directory if it does not exist.
+    ///
+    /// The file is named `{prefix}-{timestamp}-{counter}.png` so that concurrent
+    /// screenshots never overwrite each other. Returns the path of the new file.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let path = driver.screenshot_to_dir("target/screenshots", "checkout").await?;
+    /// println!("saved screenshot to {}", path.display());
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn screenshot_to_dir(
+        &self,
+        dir: impl AsRef<Path>,
+        prefix: &str,
+    ) -> WebDriverResult<PathBuf> {
+        let png = self.screenshot_as_png().await?;
+        Ok(support::write_unique_file(dir, prefix, "png", png).await?)
+    }
+
+    /// Get the entries in the browser console log since it was last read.
+    ///
+    /// This is not part of the W3C WebDriver spec. It is supported by chromedriver and
+    /// other Chromium-based drivers, so an `UnsupportedOperation` error is returned for
+    /// other browsers.
+    pub async fn console_logs(&self) -> WebDriverResult<Vec<LogEntry>> {
+        if !self.driver_info().is_chromium() {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "the console log requires a Chromium-based browser".to_string(),
+            )));
+        }
+        self.cmd(GetLog("browser")).await?.value()
+    }
+
+    /// Capture the failure artifacts configured with
+    /// `WebDriverConfigBuilder::capture_on_error()`, and attach them to the error.
+    ///
+    /// The error is returned unchanged if error capture is not configured, if it already
+    /// has attachments, or if the session or the connection to the driver has been lost.
+    /// See [`WebDriverError::attachments`].
+    pub async fn attach_error_artifacts(&self, error: WebDriverError) -> WebDriverError {
+        let Some(options) = &self.config.error_capture else {
+            return error;
+        };
+        if error.attachments().is_some_and(ErrorAttachments::is_captured)
+            || error.is_invalid_session()
+            || error.is_connection_error()
+        {
+            return error;
+        }
+
+        let attachments = DEFER_ERROR_HOOKS.scope((), self.capture_error_artifacts(options)).await;
+        error.with_attachments(attachments)
+    }
+
+    /// Run the operation, attaching failure artifacts to its error and then passing the
+    /// error to the hooks registered via `on_error()`.
+    pub(crate) async fn capture_on_error<T>(
+        &self,
+        operation: impl Future<Output = WebDriverResult<T>>,
+    ) -> WebDriverResult<T> {
+        self.report_errors(async {
+            match operation.await {
+                Err(e) => Err(self.attach_error_artifacts(e).await),
+                ok => ok,
+            }
+        })
+        .await
+    }
+
+    /// Capture the specified artifacts, giving up on any that are not done within the timeout.
+    async fn capture_error_artifacts(&self, options: &ErrorCaptureOptions) -> ErrorAttachments {
+        let deadline = tokio::time::Instant::now() + options.timeout;
+        let mut attachments = ErrorAttachments {
+            url: capture_step(deadline, "url", self.current_url()).await.map(String::from),
+            ..Default::default()
+        };
+        if options.screenshot {
+            let screenshot = self.screenshot_to_dir(&options.output_dir, "error");
+            attachments.screenshot = capture_step(deadline, "screenshot", screenshot).await;
+        }
+        if options.page_source {
+            let page_source = async {
+                let source = self.source().await?;
+                Ok(support::write_unique_file(&options.output_dir, "error", "html", source).await?)
+            };
+            attachments.page_source = capture_step(deadline, "page_source", page_source).await;
+        }
+        if options.console && self.driver_info().is_chromium() {
+            attachments.console = capture_step(deadline, "console", self.console_logs()).await;
+        }
+        attachments
+    }
+
+    /// Return a SwitchTo struct for switching to another window or frame.
+    #[deprecated(
+        since = "0.30.0",
+        note = "SwitchTo has been deprecated. Use WebDriver::switch_to_*() methods instead"
+    )]
+    pub fn switch_to(self: &Arc<SessionHandle>) -> SwitchTo {
+        SwitchTo::new(self.clone())
+    }
+
+    /// Set the current window name.
+    ///
+    /// Useful for switching between windows/tabs using [`WebDriver::switch_to_named_window`]
+    ///
+    /// [`WebDriver::switch_to_named_window`]: SessionHandle::switch_to_named_window
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// // Get the current window handle.
+    /// let handle = driver.window().await?;
+    /// driver.set_window_name("main").await?;
+    ///
+    /// // Open a new tab.
+    /// let new_handle = driver.new_tab().await?;
+    ///
+    /// // Get window handles and switch to the new tab.
+    /// driver.switch_to_window(new_handle).await?;
+    ///
+    /// // We are now controlling the new tab.
+    /// driver.goto("https://www.rust-lang.org").await?;
+    /// assert_ne!(driver.window().await?, handle);
+    ///
+    /// // Switch back to original tab using window name.
+    /// driver.switch_to_named_window("main").await?;
+    /// assert_eq!(driver.window().await?, handle);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn set_window_name(
+        self: &Arc<SessionHandle>,
+        window_name: impl Display,
+    ) -> WebDriverResult<()> {
+        let script = format!(r#"window.name = "{}""#, window_name);
+        self.execute(script, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Execute the specified function in a new browser tab, closing the tab when complete.
+    ///
+    /// The return value will be that of the supplied function, unless an error occurs while
+    /// opening or closing the tab.
+    ///
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let window_title = driver.in_new_tab(|| async {
+    ///     driver.goto("https://www.google.com").await?;
+    ///     driver.title().await
+    /// }).await?;
+    /// assert_eq!(window_title, "Google");
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn in_new_tab<F, Fut, T>(&self, f: F) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = WebDriverResult<T>> + Send,
+        T: Send,
+    {
+        let handle = self.window().await?;
+
+        // Open new tab.
+        let tab_handle = self.new_tab().await?;
         self.switch_to_window(tab_handle).await?;
 
         let result = f().await;
 
-        // Close tab.
-        self.close_window().await?;
-        self.switch_to_window(handle).await?;
+        // Close tab.
+        self.close_window().await?;
+        self.switch_to_window(handle).await?;
+
+        result
+    }
+
+    /// Replace `Date`, `setTimeout`, `setInterval` and `performance.now` in the page with a
+    /// fake clock, so that time only moves when it is advanced with `advance_clock()` or
+    /// `run_all_timers()`.
+    ///
+    /// The clock starts at the specified time, or the current time if `None`. With
+    /// Chromium-based browsers, the clock is also installed in every document loaded
+    /// afterwards, via the CDP `Page.addScriptToEvaluateOnNewDocument` command, starting
+    /// again at the same time. With other browsers it is only installed in the current
+    /// document, and again in whichever document is current when the clock is advanced,
+    /// at the time the clock had reached.
+    ///
+    /// Installing the clock again replaces it.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.install_mock_clock(None).await?;
+    /// driver.find(By::Id("search")).await?.send_keys("rust").await?;
+    /// // Fire the debounced search without waiting for it.
+    /// driver.advance_clock(Duration::from_millis(500)).await?;
+    /// driver.uninstall_mock_clock().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn install_mock_clock(
+        self: &Arc<SessionHandle>,
+        start: Option<SystemTime>,
+    ) -> WebDriverResult<()> {
+        let start = start.unwrap_or_else(SystemTime::now);
+        let Ok(since_epoch) = start.duration_since(UNIX_EPOCH) else {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                "the mock clock cannot start before the Unix epoch".to_string(),
+            )));
+        };
+        self.uninstall_mock_clock().await?;
+
+        let start_ms = since_epoch.as_millis() as u64;
+        let mut clock = MockClock {
+            now_ms: start_ms,
+            script_id: None,
+        };
+        let params = json!({ "source": format!("{MOCK_CLOCK}({start_ms});") });
+        clock.script_id = match self.cdp("Page.addScriptToEvaluateOnNewDocument", params).await {
+            Ok(ret) => ret["identifier"].as_str().map(ToString::to_string),
+            Err(e) if is_unknown_command(&e) => None,
+            Err(e) => return Err(e),
+        };
+        let script = clock.script();
+        self.set_mock_clock(Some(clock));
+        self.execute(script, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Move the clock installed via `install_mock_clock()` forward by the specified
+    /// duration, running the timers that are due in order.
+    ///
+    /// Returns an `InvalidArgument` error if the clock is not installed. If a timer
+    /// throws, the other timers still run and the first error is returned.
+    pub async fn advance_clock(
+        self: &Arc<SessionHandle>,
+        duration: Duration,
+    ) -> WebDriverResult<()> {
+        self.run_mock_clock(&format!("tick({})", duration.as_millis())).await
+    }
+
+    /// Run every timer of the clock installed via `install_mock_clock()`, including
+    /// those added by the timers themselves, moving the clock forward to each.
+    ///
+    /// Returns an `InvalidArgument` error if the clock is not installed, and a
+    /// `JavascriptError` if more than 1000 timers run, as happens with `setInterval()`.
+    pub async fn run_all_timers(self: &Arc<SessionHandle>) -> WebDriverResult<()> {
+        self.run_mock_clock("runAll()").await
+    }
+
+    /// Restore the real clock replaced via `install_mock_clock()`, dropping any timers
+    /// that have not run. Does nothing if the clock is not installed.
+    pub async fn uninstall_mock_clock(self: &Arc<SessionHandle>) -> WebDriverResult<()> {
+        let Some(clock) = self.mock_clock() else {
+            return Ok(());
+        };
+        self.set_mock_clock(None);
+        if let Some(identifier) = clock.script_id {
+            let params = json!({ "identifier": identifier });
+            self.cdp("Page.removeScriptToEvaluateOnNewDocument", params).await?;
+        }
+        let script = "if (window.__thirtyfourClock) { window.__thirtyfourClock.uninstall(); }";
+        self.execute(script, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Call the specified method of the mock clock, installing it in the current
+    /// document first if necessary.
+    async fn run_mock_clock(self: &Arc<SessionHandle>, call: &str) -> WebDriverResult<()> {
+        let Some(clock) = self.mock_clock() else {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                "the mock clock is not installed; call install_mock_clock() first".to_string(),
+            )));
+        };
+        let script = format!(
+            "{}\nwindow.__thirtyfourClock.{call};\nreturn window.__thirtyfourClock.now();",
+            clock.script()
+        );
+        let now_ms: u64 = self.execute(script, Vec::new()).await?.convert()?;
+        // Navigating may replace the document, so install the clock at this time next.
+        if let Some(clock) = self
+            .extension_state::<MockClockState>()
+            .0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_mut()
+        {
+            clock.now_ms = now_ms;
+        }
+        Ok(())
+    }
+
+    /// Report the specified position to the page via the geolocation API while running
+    /// the specified function, and then restore the previous position.
+    ///
+    /// This uses [`ChromeDevTools::set_geolocation`], so it only works with Chromium-based
+    /// browsers. The previous position is restored even if the function fails, in which
+    /// case the function's error is returned. The geolocation permission stays granted.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::GeoLocation;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let sydney = GeoLocation::new(-33.8568, 151.2153);
+    /// let store = driver.with_geolocation(sydney, || async {
+    ///     driver.goto("https://example.com/stores").await?;
+    ///     driver.find(By::Id("nearest")).await?.text().await
+    /// }).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
+    /// [`ChromeDevTools::set_geolocation`]: crate::extensions::cdp::ChromeDevTools::set_geolocation
+    pub async fn with_geolocation<F, Fut, T>(
+        self: &Arc<SessionHandle>,
+        geo: GeoLocation,
+        f: F,
+    ) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = WebDriverResult<T>> + Send,
+        T: Send,
+    {
+        let dev_tools = ChromeDevTools::new(self.clone());
+        let previous = dev_tools.state().geolocation();
+        dev_tools.set_geolocation(geo).await?;
+
+        let result = f().await;
+
+        let restored = match previous {
+            Some(previous) => dev_tools.set_geolocation(previous).await,
+            None => dev_tools.clear_geolocation_override().await,
+        };
+        first_error(result, restored, "geolocation")
+    }
 
-        result
+    /// Emulate the specified timezone and locale, such as `Asia/Tokyo` and `ja-JP`, while
+    /// running the specified function, and then restore the previous timezone and locale.
+    ///
+    /// This uses [`ChromeDevTools::set_timezone`] and [`ChromeDevTools::set_locale`], so it
+    /// only works with Chromium-based browsers. Both names are validated before either
+    /// is set, and the previous values are restored even if the function fails, in which
+    /// case the function's error is returned.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let date = driver.with_timezone_locale("Europe/Berlin", "de-DE", || async {
+    ///     driver.goto("https://example.com/booking").await?;
+    ///     driver.find(By::Id("date")).await?.text().await
+    /// }).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
+    /// [`ChromeDevTools::set_timezone`]: crate::extensions::cdp::ChromeDevTools::set_timezone
+    /// [`ChromeDevTools::set_locale`]: crate::extensions::cdp::ChromeDevTools::set_locale
+    pub async fn with_timezone_locale<F, Fut, T>(
+        self: &Arc<SessionHandle>,
+        timezone: &str,
+        locale: &str,
+        f: F,
+    ) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = WebDriverResult<T>> + Send,
+        T: Send,
+    {
+        validate_timezone(timezone)?;
+        validate_locale(locale)?;
+        let dev_tools = ChromeDevTools::new(self.clone());
+        let previous = dev_tools.state().locale_overrides();
+        dev_tools.set_timezone(timezone).await?;
+        let result = match dev_tools.set_locale(locale).await {
+            Ok(()) => f().await,
+            Err(e) => {
+                let restored = restore_timezone(&dev_tools, previous.timezone).await;
+                return first_error(Err(e), restored, "timezone");
+            }
+        };
+
+        let restored = restore_timezone(&dev_tools, previous.timezone).await;
+        let result = first_error(result, restored, "timezone");
+        let restored = match previous.locale {
+            Some(previous) => dev_tools.set_locale(&previous).await,
+            None => dev_tools.clear_locale().await,
+        };
+        first_error(result, restored, "locale")
+    }
+
+    /// Run the specified function, and return a `HeapGrowthExceeded` error if the JS heap
+    /// of the page grew by more than the specified number of bytes, for example to guard
+    /// against memory leaks in a page that stays open for a long time.
+    ///
+    /// Garbage is collected before the heap is measured, both before and after running
+    /// the function, so only objects that are still reachable count. The error includes
+    /// the heap size and the number of DOM nodes, event listeners and documents, before
+    /// and after.
+    ///
+    /// This uses [`ChromeDevTools::performance_metrics`], so it only works with
+    /// Chromium-based browsers. Other browsers return an `UnsupportedOperation` error.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.goto("https://example.com/dashboard").await?;
+    /// driver.assert_heap_growth_under(|| async {
+    ///     for _ in 0..10 {
+    ///         driver.find(By::Id("refresh")).await?.click().await?;
+    ///     }
+    ///     Ok(())
+    /// }, 1_000_000).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
+    /// [`ChromeDevTools::performance_metrics`]: crate::extensions::cdp::ChromeDevTools::performance_metrics
+    pub async fn assert_heap_growth_under<F, Fut, T>(
+        self: &Arc<SessionHandle>,
+        f: F,
+        max_growth_bytes: u64,
+    ) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = WebDriverResult<T>> + Send,
+        T: Send,
+    {
+        let dev_tools = ChromeDevTools::new(self.clone());
+        dev_tools.force_gc().await?;
+        let before = MemorySample::from_metrics(&dev_tools.performance_metrics().await?)?;
+
+        let result = f().await?;
+
+        dev_tools.force_gc().await?;
+        let after = MemorySample::from_metrics(&dev_tools.performance_metrics().await?)?;
+        after.check_growth(&before, max_growth_bytes)?;
+        Ok(result)
+    }
+
+    /// Answer HTTP authentication challenges from the specified origin, such as
+    /// `https://example.com`, with the specified username and password, instead of
+    /// showing the browser's credentials dialog.
+    ///
+    /// Only the requests to the origin are paused to answer challenges, and challenges
+    /// from other origins are left to the browser. This replaces any handler set
+    /// previously. To answer challenges from several origins, use
+    /// [`ChromeDevTools::set_auth_handler`].
+    ///
+    /// Returns an `InvalidArgument` error if the origin is not a valid URL.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.set_basic_auth("https://intranet.example.com", "user", "password").await?;
+    /// driver.goto("https://intranet.example.com/reports").await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn set_basic_auth(
+        self: &Arc<SessionHandle>,
+        origin: &str,
+        username: &str,
+        password: &str,
+    ) -> WebDriverResult<()> {
+        let origin = Url::parse(origin)
+            .map_err(|e| {
+                WebDriverError::InvalidArgument(WebDriverErrorInfo::new(format!(
+                    "invalid origin {origin:?}: {e}"
+                )))
+            })?
+            .origin()
+            .ascii_serialization();
+        let credentials = Credentials::new(username, password);
+        let pattern = format!("{origin}/*");
+        ChromeDevTools::new(self.clone())
+            .set_auth_handler(&[&pattern], move |challenge| {
+                (challenge.origin == origin).then(|| credentials.clone())
+            })
+            .await
+    }
+
+    /// Answer the file choosers opened by the page, for example by a button that clicks
+    /// a hidden `<input type=file>`, using the specified handler instead of showing the
+    /// native file dialog, which would otherwise block the session.
+    ///
+    /// This replaces any handler set previously. This uses
+    /// [`ChromeDevTools::set_file_chooser_handler`], so it only works with Chromium-based
+    /// browsers.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::extensions::cdp::FileChooserAction;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.on_file_chooser(|_| FileChooserAction::Files(vec!["/tmp/avatar.png".into()])).await?;
+    /// driver.find(By::Id("change-avatar")).await?.click().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
+    /// [`ChromeDevTools::set_file_chooser_handler`]: crate::extensions::cdp::ChromeDevTools::set_file_chooser_handler
+    #[cfg(feature = "cdp-events")]
+    pub async fn on_file_chooser<F>(self: &Arc<SessionHandle>, handler: F) -> WebDriverResult<()>
+    where
+        F: Fn(&FileChooser) -> FileChooserAction + Send + Sync + 'static,
+    {
+        ChromeDevTools::new(self.clone()).set_file_chooser_handler(handler).await
+    }
+
+    /// Wait until the download with the specified guid or suggested filename, such as
+    /// `report.csv`, completes, and return its final state.
+    ///
+    /// Download events must be enabled first via [`ChromeDevTools::set_download_behavior`],
+    /// so this only works with Chromium-based browsers. Returns a `DownloadCanceled` error
+    /// if the download is cancelled, or a `Timeout` error if it does not complete in time.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// use thirtyfour::extensions::cdp::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let dev_tools = ChromeDevTools::new(driver.handle.clone());
+    /// dev_tools.set_download_behavior("/tmp/downloads", true).await?;
+    /// driver.find(By::Id("export-csv")).await?.click().await?;
+    /// driver.wait_for_download_complete("report.csv", Duration::from_secs(30)).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
+    /// [`ChromeDevTools::set_download_behavior`]: crate::extensions::cdp::ChromeDevTools::set_download_behavior
+    #[cfg(feature = "cdp-events")]
+    pub async fn wait_for_download_complete(
+        self: &Arc<SessionHandle>,
+        guid_or_filename: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<DownloadEvent> {
+        ChromeDevTools::new(self.clone())
+            .wait_for_download_complete(guid_or_filename, timeout)
+            .await
+    }
+
+    /// Return a `JavascriptError` listing the error-level messages written to the browser
+    /// console since this was last called, if there are any.
+    ///
+    /// The first call also checks the messages the browser still has from before it was
+    /// called, such as those written while the current page loaded. This uses
+    /// [`ChromeDevTools::drain_console`], so it only works with Chromium-based browsers.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.assert_no_console_errors().await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// driver.find(By::Id("get-started")).await?.click().await?;
+    /// driver.assert_no_console_errors().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn assert_no_console_errors(self: &Arc<SessionHandle>) -> WebDriverResult<()> {
+        ChromeDevTools::new(self.clone()).assert_no_console_errors().await
+    }
+
+    /// Record the uncaught exceptions and unhandled promise rejections in the page, and
+    /// fail the next command with a `JavascriptError` listing them.
+    ///
+    /// The exceptions are checked before each command or operation the caller starts,
+    /// such as `find()` or a wait, rather than before each command sent while it runs.
+    /// Exceptions are reported by the browser asynchronously, so an exception thrown by
+    /// one command may not be noticed until a later one. Use `check_js_exceptions()` to
+    /// check for them explicitly, for example at the end of a test. Disabling this
+    /// discards any exceptions recorded but not yet reported.
+    ///
+    /// This uses [`ChromeDevTools::subscribe_exceptions`], so it only works with
+    /// Chromium-based browsers.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.fail_on_js_exception(true).await?;
+    /// driver.goto("https://www.rust-lang.org/").await?;
+    /// driver.find(By::Id("get-started")).await?.click().await?;
+    /// driver.check_js_exceptions().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-events")]
+    pub async fn fail_on_js_exception(
+        self: &Arc<SessionHandle>,
+        enabled: bool,
+    ) -> WebDriverResult<()> {
+        if !enabled {
+            if let Some(connection) = self.cdp_connection.get() {
+                *connection.exceptions.lock().unwrap_or_else(|e| e.into_inner()) = None;
+            }
+            return Ok(());
+        }
+        let dev_tools = ChromeDevTools::new(self.clone());
+        let connection = dev_tools.connection().await?;
+        let recording = connection.exceptions.lock().unwrap_or_else(|e| e.into_inner()).is_some();
+        if !recording {
+            let exceptions = dev_tools.subscribe_exceptions().await?;
+            let mut recorded = connection.exceptions.lock().unwrap_or_else(|e| e.into_inner());
+            recorded.get_or_insert(exceptions);
+        }
+        Ok(())
+    }
+
+    /// Return a `JavascriptError` listing the exceptions recorded since they were last
+    /// reported, if `fail_on_js_exception()` is enabled and there are any.
+    #[cfg(feature = "cdp-events")]
+    pub async fn check_js_exceptions(&self) -> WebDriverResult<()> {
+        let Some(connection) = self.cdp_connection.get() else {
+            return Ok(());
+        };
+        // The browser sends events in order, so every exception thrown before this
+        // command was sent arrives before its result.
+        connection.send("Runtime.evaluate", json!({ "expression": "0" })).await?;
+        connection.take_exceptions()
+    }
+
+    /// The state of type `T` kept by an extension for this session, created with its
+    /// default value when first used.
+    pub(crate) fn extension_state<T: Default + Send + Sync + 'static>(&self) -> Arc<T> {
+        let mut extensions = self.extension_state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = extensions.get::<Arc<T>>() {
+            return Arc::clone(state);
+        }
+        let state = Arc::new(T::default());
+        extensions.insert(Arc::clone(&state));
+        state
+    }
+
+    /// The clock installed via `install_mock_clock()`, if any.
+    fn mock_clock(&self) -> Option<MockClock> {
+        self.extension_state::<MockClockState>().0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn set_mock_clock(&self, clock: Option<MockClock>) {
+        *self.extension_state::<MockClockState>().0.lock().unwrap_or_else(|e| e.into_inner()) =
+            clock;
     }
 
     pub(crate) async fn quit(&self) -> WebDriverResult<()> {
         self.quit
-            .get_or_try_init(|| async { self.cmd(Command::DeleteSession).await.map(drop) })
+            .get_or_try_init(|| async {
+                // Exceptions in the page should not prevent the session from closing.
+                #[cfg(feature = "cdp-events")]
+                if let Some(connection) = self.cdp_connection.get() {
+                    *connection.exceptions.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                }
+                if self.config.discard_unload_on_quit {
+                    self.discard_all_unload_handlers().await;
+                }
+                self.cmd(Command::DeleteSession).await.map(drop)
+            })
             .await?;
         Ok(())
     }
 
+    /// Discard `beforeunload` handlers in every window, ignoring any errors.
+    async fn discard_all_unload_handlers(&self) {
+        let Ok(handles) = self.windows().await else {
+            return;
+        };
+        for handle in handles {
+            if self.cmd(Command::SwitchToWindow(handle)).await.is_ok() {
+                let _ = self.discard_unload_handlers().await;
+            }
+        }
+    }
+
     pub(crate) fn leak(&self) -> Result<(), AlreadyQuit> {
         self.quit.set(()).map_err(|_| AlreadyQuit(()))
     }
@@ -1208,6 +2913,13 @@ impl Drop for SessionHandle {
             quit: Arc::clone(&self.quit),
             session_id: self.session_id.clone(),
             config: self.config.clone(),
+            alert_handlers: Arc::clone(&self.alert_handlers),
+            window_history: Arc::clone(&self.window_history),
+            driver: Arc::clone(&self.driver),
+            error_hooks: Arc::clone(&self.error_hooks),
+            extension_state: Arc::clone(&self.extension_state),
+            #[cfg(feature = "cdp-events")]
+            cdp_connection: Arc::clone(&self.cdp_connection),
         };
         support::spawn_blocked_future(|spawned| async move {
             if spawned {
@@ -1218,3 +2930,77 @@ impl Drop for SessionHandle {
         });
     }
 }
+
+/// True if the error means that the driver does not implement the command.
+fn is_unknown_command(e: &WebDriverError) -> bool {
+    matches!(
+        e.as_inner(),
+        WebDriverErrorInner::UnknownCommand(_)
+            | WebDriverErrorInner::UnknownMethod(_)
+            | WebDriverErrorInner::UnknownResponse(..)
+    )
+}
+
+/// Restore the timezone emulated before `SessionHandle::with_timezone_locale()`.
+async fn restore_timezone(
+    dev_tools: &ChromeDevTools,
+    timezone: Option<String>,
+) -> WebDriverResult<()> {
+    match timezone {
+        Some(timezone) => dev_tools.set_timezone(&timezone).await,
+        None => dev_tools.clear_timezone().await,
+    }
+}
+
+/// Return the error of the function run while an override was set, if any, otherwise the
+/// error restoring the override. A restore error that would hide the function's error
+/// is logged instead.
+fn first_error<T>(
+    result: WebDriverResult<T>,
+    restored: WebDriverResult<()>,
+    what: &str,
+) -> WebDriverResult<T> {
+    match (result, restored) {
+        (result, Ok(())) => result,
+        (Ok(_), Err(e)) => Err(e),
+        (Err(e), Err(restore)) => {
+            tracing::warn!("failed to restore the previous {what}: {restore}");
+            Err(e)
+        }
+    }
+}
+
+/// The fake clock installed via `SessionHandle::install_mock_clock()`.
+#[derive(Debug, Clone)]
+struct MockClock {
+    /// The time the clock started at, or had reached when it was last advanced, in
+    /// milliseconds since the Unix epoch.
+    now_ms: u64,
+    /// The identifier of the CDP script installing the clock in new documents, if any.
+    script_id: Option<String>,
+}
+
+impl MockClock {
+    /// The script installing the clock at the time it had reached, if it is not already
+    /// installed.
+    fn script(&self) -> String {
+        format!("{MOCK_CLOCK}({});", self.now_ms)
+    }
+}
+
+/// Get the entries in the specified log, such as "browser", since it was last read.
+///
+/// This is only supported by Chromium-based drivers.
+#[derive(Debug)]
+struct GetLog(&'static str);
+
+impl FormatRequestData for GetLog {
+    fn format_request(&self, session_id: &SessionId) -> RequestData {
+        RequestData::new(http::Method::POST, format!("/session/{session_id}/se/log"))
+            .add_body(json!({ "type": self.0 }))
+    }
+}
+
+/// The state of `SessionHandle::install_mock_clock()`.
+#[derive(Debug, Default)]
+struct MockClockState(Mutex<Option<MockClock>>);
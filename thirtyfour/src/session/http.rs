@@ -46,7 +46,7 @@ pub trait HttpClient: Send + Sync + 'static {
     /// or couldn't prove its availability
     /// this isn't a simple clone,
     /// this new client needs to be able to run in a new runtime even if the old runtime has been destroyed
-
+    //
     // needed for object safety
     #[allow(clippy::new_ret_no_self)]
     #[allow(clippy::wrong_self_convention)]
@@ -195,14 +195,17 @@ pub(crate) async fn run_webdriver_cmd(
     let lossy_response = String::from_utf8_lossy(response.body());
     tracing::debug!("webdriver response: {status} {lossy_response}");
     match status {
-        200..=399 => match serde_json::from_slice(response.body()) {
-            Ok(v) => Ok(CmdResponse {
-                body: v,
-                status,
-            }),
-            Err(_) => Err(WebDriverError::parse(status, lossy_response.into_owned())),
-        },
-        _ => Err(WebDriverError::parse(status, lossy_response.into_owned())),
+        200..=399 => {
+            match serde_json::from_slice(response.body()) {
+                Ok(v) => Ok(CmdResponse {
+                    body: v,
+                    status,
+                }),
+                Err(_) => Err(WebDriverError::from_response(status, response.body())
+                    .with_command(request_data)),
+            }
+        }
+        _ => Err(WebDriverError::from_response(status, response.body()).with_command(request_data)),
     }
 }
 
@@ -1,13 +1,29 @@
 use crate::error::WebDriverResult;
 use base64::{prelude::BASE64_STANDARD, Engine};
 use std::convert::Infallible;
+use std::fs::OpenOptions;
 use std::future::Future;
+use std::io::Write;
 use std::panic::AssertUnwindSafe;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::LazyLock;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{io, thread};
 
+#[cfg(feature = "image")]
+mod image_diff;
+#[cfg(feature = "image")]
+mod image_ext;
+#[cfg(feature = "image")]
+pub use image_diff::*;
+#[cfg(feature = "image")]
+pub(crate) use image_ext::decode_png;
+#[cfg(feature = "image")]
+pub use image_ext::ImageExt;
+mod wait;
+pub use wait::*;
+
 // used in drop code so its really bad to have a stack overflow then
 const BOX_FUTURE_THRESHOLD: usize = 512;
 
@@ -164,6 +180,49 @@ pub(crate) async fn write_file(
     inner(path.as_ref(), bytes.into()).await
 }
 
+#[cfg(feature = "image")]
+pub(crate) async fn read_file(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let path = path.as_ref().to_owned();
+    tokio::task::spawn_blocking(move || std::fs::read(path)).await?
+}
+
+/// Write the bytes to a new file in the specified directory, creating the directory if needed.
+///
+/// The file is named `{prefix}-{timestamp}-{counter}.{extension}`. Existing files are never
+/// overwritten. Returns the path of the new file.
+pub(crate) async fn write_unique_file(
+    dir: impl AsRef<Path>,
+    prefix: &str,
+    extension: &str,
+    bytes: impl Into<Vec<u8>>,
+) -> io::Result<PathBuf> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn inner(dir: &Path, prefix: &str, extension: &str, bytes: &[u8]) -> io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let timestamp =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        loop {
+            let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = dir.join(format!("{prefix}-{timestamp}-{counter}.{extension}"));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(bytes)?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    let dir = dir.as_ref().to_owned();
+    let prefix = prefix.to_string();
+    let extension = extension.to_string();
+    let bytes = bytes.into();
+    tokio::task::spawn_blocking(move || inner(&dir, &prefix, &extension, &bytes)).await?
+}
+
 /// Helper to sleep asynchronously for the specified duration.
 pub async fn sleep(duration: Duration) {
     tokio::time::sleep(duration).await
@@ -179,3 +238,26 @@ pub fn base64_decode(data: &str) -> WebDriverResult<Vec<u8>> {
     let value = BASE64_STANDARD.decode(data)?;
     Ok(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_unique_file() {
+        let dir = std::env::temp_dir()
+            .join(format!("thirtyfour-write-unique-{}", std::process::id()))
+            .join("nested");
+        let paths: Vec<PathBuf> = block_on(async {
+            let writes = (0..5u8).map(|i| write_unique_file(&dir, "shot", "png", vec![i]));
+            futures_util::future::try_join_all(writes).await
+        })
+        .unwrap();
+
+        for (i, path) in paths.iter().enumerate() {
+            assert!(path.file_name().unwrap().to_str().unwrap().starts_with("shot-"));
+            assert_eq!(std::fs::read(path).unwrap(), vec![i as u8]);
+        }
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,233 @@
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, Rgba, RgbaImage};
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::Rect;
+
+/// What to do when the compared images have different dimensions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DimensionMismatch {
+    /// Return an `ImageError`.
+    #[default]
+    Error,
+    /// Scale the second image to the dimensions of the first.
+    Scale,
+}
+
+/// Options for `compare_images()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageCompareOptions {
+    /// The maximum difference allowed per color channel before pixels are considered different.
+    pub channel_tolerance: u8,
+    /// Regions of the first image to ignore, in pixels.
+    pub ignore_regions: Vec<Rect>,
+    /// What to do when the images have different dimensions.
+    pub dimension_mismatch: DimensionMismatch,
+    /// The maximum ratio of different pixels for the images to be considered a match.
+    pub max_diff_ratio: f64,
+}
+
+impl Default for ImageCompareOptions {
+    fn default() -> Self {
+        Self {
+            channel_tolerance: 0,
+            ignore_regions: Vec::new(),
+            dimension_mismatch: DimensionMismatch::default(),
+            max_diff_ratio: 0.0,
+        }
+    }
+}
+
+/// The result of `compare_images()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageDiff {
+    /// The number of pixels that differ, excluding ignored regions.
+    pub different_pixels: u64,
+    /// The ratio of different pixels to compared pixels.
+    pub ratio: f64,
+    /// A PNG highlighting the different pixels in red, if any pixels differ.
+    pub diff_png: Option<Vec<u8>>,
+}
+
+impl ImageDiff {
+    /// Return true if the ratio of different pixels is within the specified options.
+    pub fn is_match(&self, options: &ImageCompareOptions) -> bool {
+        self.ratio <= options.max_diff_ratio
+    }
+}
+
+fn image_error(e: image::ImageError) -> WebDriverError {
+    WebDriverError::ImageError(e.to_string())
+}
+
+fn decode_rgba(png: &[u8]) -> WebDriverResult<RgbaImage> {
+    Ok(super::decode_png(png)?.to_rgba8())
+}
+
+fn is_ignored(x: u32, y: u32, regions: &[Rect]) -> bool {
+    let (x, y) = (i64::from(x), i64::from(y));
+    regions.iter().any(|r| x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height)
+}
+
+/// Compare two PNG images pixel by pixel.
+///
+/// Pixels are different if any color channel differs by more than the channel tolerance.
+/// The diff image shows different pixels in red over a faded copy of the first image.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// use thirtyfour::support::{compare_images, ImageCompareOptions};
+/// # fn main() -> WebDriverResult<()> {
+/// let a = std::fs::read("before.png")?;
+/// let b = std::fs::read("after.png")?;
+/// let options = ImageCompareOptions {
+///     channel_tolerance: 4,
+///     ..Default::default()
+/// };
+/// let diff = compare_images(&a, &b, &options)?;
+/// assert!(diff.is_match(&options), "{} pixels differ", diff.different_pixels);
+/// #     Ok(())
+/// # }
+/// ```
+pub fn compare_images(
+    a: &[u8],
+    b: &[u8],
+    options: &ImageCompareOptions,
+) -> WebDriverResult<ImageDiff> {
+    let a = decode_rgba(a)?;
+    let mut b = decode_rgba(b)?;
+    if a.dimensions() != b.dimensions() {
+        match options.dimension_mismatch {
+            DimensionMismatch::Error => {
+                return Err(WebDriverError::ImageError(format!(
+                    "image dimensions differ: {}x{} vs {}x{}",
+                    a.width(),
+                    a.height(),
+                    b.width(),
+                    b.height()
+                )));
+            }
+            DimensionMismatch::Scale => {
+                b = imageops::resize(&b, a.width(), a.height(), FilterType::Triangle);
+            }
+        }
+    }
+
+    let mut diff = RgbaImage::new(a.width(), a.height());
+    let mut compared_pixels = 0u64;
+    let mut different_pixels = 0u64;
+    for (x, y, pa) in a.enumerate_pixels() {
+        let [red, green, blue, _] = pa.0;
+        let faded = Rgba([red / 4 + 191, green / 4 + 191, blue / 4 + 191, 255]);
+        if is_ignored(x, y, &options.ignore_regions) {
+            diff.put_pixel(x, y, faded);
+            continue;
+        }
+
+        compared_pixels += 1;
+        let pb = b.get_pixel(x, y);
+        if pa.0.iter().zip(pb.0).any(|(ca, cb)| ca.abs_diff(cb) > options.channel_tolerance) {
+            different_pixels += 1;
+            diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        } else {
+            diff.put_pixel(x, y, faded);
+        }
+    }
+
+    let diff_png = if different_pixels > 0 {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        diff.write_to(&mut buf, ImageFormat::Png).map_err(image_error)?;
+        Some(buf.into_inner())
+    } else {
+        None
+    };
+
+    Ok(ImageDiff {
+        different_pixels,
+        ratio: match compared_pixels {
+            0 => 0.0,
+            n => different_pixels as f64 / n as f64,
+        },
+        diff_png,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small PNG filled with one color, with the specified pixels changed.
+    fn png(width: u32, height: u32, changed: &[(u32, u32, [u8; 4])]) -> Vec<u8> {
+        let mut img = RgbaImage::from_pixel(width, height, Rgba([10, 20, 30, 255]));
+        for &(x, y, color) in changed {
+            img.put_pixel(x, y, Rgba(color));
+        }
+        let mut buf = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut buf, ImageFormat::Png).unwrap();
+        buf.into_inner()
+    }
+
+    #[test]
+    fn test_identical() {
+        let a = png(4, 4, &[]);
+        let diff = compare_images(&a, &a, &ImageCompareOptions::default()).unwrap();
+        assert_eq!(diff.different_pixels, 0);
+        assert_eq!(diff.ratio, 0.0);
+        assert!(diff.diff_png.is_none());
+        assert!(diff.is_match(&ImageCompareOptions::default()));
+    }
+
+    #[test]
+    fn test_different_pixels() {
+        let a = png(4, 4, &[]);
+        let b = png(4, 4, &[(0, 0, [255, 255, 255, 255]), (3, 3, [12, 20, 30, 255])]);
+        let diff = compare_images(&a, &b, &ImageCompareOptions::default()).unwrap();
+        assert_eq!(diff.different_pixels, 2);
+        assert_eq!(diff.ratio, 2.0 / 16.0);
+
+        let diff_img = decode_rgba(diff.diff_png.as_ref().unwrap()).unwrap();
+        assert_eq!(*diff_img.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_ne!(*diff_img.get_pixel(1, 0), Rgba([255, 0, 0, 255]));
+
+        // The second pixel is within the tolerance.
+        let options = ImageCompareOptions {
+            channel_tolerance: 2,
+            ..Default::default()
+        };
+        let diff = compare_images(&a, &b, &options).unwrap();
+        assert_eq!(diff.different_pixels, 1);
+        assert!(!diff.is_match(&options));
+        assert!(diff.is_match(&ImageCompareOptions {
+            max_diff_ratio: 0.1,
+            ..options
+        }));
+    }
+
+    #[test]
+    fn test_ignore_regions() {
+        let a = png(4, 4, &[]);
+        let b = png(4, 4, &[(1, 1, [255, 255, 255, 255]), (3, 0, [255, 255, 255, 255])]);
+        let options = ImageCompareOptions {
+            ignore_regions: vec![Rect::new(0, 0, 2, 2)],
+            ..Default::default()
+        };
+        let diff = compare_images(&a, &b, &options).unwrap();
+        assert_eq!(diff.different_pixels, 1);
+        assert_eq!(diff.ratio, 1.0 / 12.0);
+    }
+
+    #[test]
+    fn test_dimension_mismatch() {
+        let a = png(4, 4, &[]);
+        let b = png(8, 8, &[]);
+        assert!(compare_images(&a, &b, &ImageCompareOptions::default()).is_err());
+
+        let options = ImageCompareOptions {
+            dimension_mismatch: DimensionMismatch::Scale,
+            ..Default::default()
+        };
+        let diff = compare_images(&a, &b, &options).unwrap();
+        assert_eq!(diff.different_pixels, 0);
+    }
+}
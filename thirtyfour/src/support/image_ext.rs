@@ -0,0 +1,89 @@
+use image::{DynamicImage, ImageFormat};
+
+use crate::error::{ImageDecodeErrorInfo, WebDriverError, WebDriverResult};
+use crate::ElementRect;
+
+/// Decode a PNG screenshot.
+pub(crate) fn decode_png(png: &[u8]) -> WebDriverResult<DynamicImage> {
+    image::load_from_memory_with_format(png, ImageFormat::Png).map_err(|e| {
+        WebDriverError::ImageDecodeError(ImageDecodeErrorInfo::new(e.to_string(), png))
+    })
+}
+
+/// Extension methods for screenshots decoded via the `image` crate.
+pub trait ImageExt {
+    /// Crop the image to the specified rect, scaled by the device pixel ratio.
+    ///
+    /// The rect is in CSS pixels, e.g. as returned by `WebElement::rect()` for a screenshot
+    /// of the viewport. The scaled rect is expanded to whole pixels and clamped to the image,
+    /// so the result may be empty if the rect lies outside the image.
+    fn crop_to_rect(&self, rect: &ElementRect, device_pixel_ratio: f64) -> DynamicImage;
+
+    /// Encode the image as PNG bytes.
+    fn to_png_bytes(&self) -> WebDriverResult<Vec<u8>>;
+}
+
+impl ImageExt for DynamicImage {
+    fn crop_to_rect(&self, rect: &ElementRect, device_pixel_ratio: f64) -> DynamicImage {
+        let scale = |v: f64, max: u32| (v * device_pixel_ratio).max(0.0).min(max as f64);
+        let left = scale(rect.x, self.width()).floor() as u32;
+        let top = scale(rect.y, self.height()).floor() as u32;
+        let right = scale(rect.x + rect.width, self.width()).ceil() as u32;
+        let bottom = scale(rect.y + rect.height, self.height()).ceil() as u32;
+        self.crop_imm(left, top, right.saturating_sub(left), bottom.saturating_sub(top))
+    }
+
+    fn to_png_bytes(&self) -> WebDriverResult<Vec<u8>> {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        self.write_to(&mut buf, ImageFormat::Png)
+            .map_err(|e| WebDriverError::ImageError(e.to_string()))?;
+        Ok(buf.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WebDriverErrorInner;
+    use image::{GenericImageView, RgbaImage};
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> ElementRect {
+        ElementRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn screenshot() -> DynamicImage {
+        let png = DynamicImage::ImageRgba8(RgbaImage::new(200, 100)).to_png_bytes().unwrap();
+        decode_png(&png).unwrap()
+    }
+
+    #[test]
+    fn test_decode_png() {
+        assert_eq!(screenshot().dimensions(), (200, 100));
+
+        let err = decode_png(b"not a png").unwrap_err();
+        match err.as_inner() {
+            WebDriverErrorInner::ImageDecodeError(info) => {
+                assert_eq!(info.len, 9);
+                assert_eq!(info.head, b"not a png");
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn test_crop_to_rect() {
+        let img = screenshot();
+        assert_eq!(img.crop_to_rect(&rect(10.0, 5.0, 20.0, 10.0), 1.0).dimensions(), (20, 10));
+        assert_eq!(img.crop_to_rect(&rect(10.0, 5.0, 20.0, 10.0), 2.0).dimensions(), (40, 20));
+        // 12.5..37.5 x 6.25..18.75 expands to 12..38 x 6..19.
+        assert_eq!(img.crop_to_rect(&rect(10.0, 5.0, 20.0, 10.0), 1.25).dimensions(), (26, 13));
+        // Clamped to the image.
+        assert_eq!(img.crop_to_rect(&rect(90.0, 40.0, 50.0, 50.0), 2.0).dimensions(), (20, 20));
+        assert_eq!(img.crop_to_rect(&rect(150.0, 0.0, 10.0, 10.0), 2.0).dimensions(), (0, 20));
+    }
+}
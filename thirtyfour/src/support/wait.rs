@@ -0,0 +1,424 @@
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::error::{TimeoutErrorBuilder, WebDriverError, WebDriverResult};
+use crate::extensions::query::{ElementPollerWithDeadline, IntoElementPoller};
+
+/// The interval between attempts when no poller is specified.
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The point in time by which a wait must finish.
+///
+/// A `Duration` converts to the deadline that far from now. Passing the same deadline to
+/// nested waits makes them share a single budget, so that together they take no longer
+/// than it allows. Element queries and waiters accept a deadline too, via
+/// `ElementQuery::wait_until()` and `ElementWaiter::wait_until()`.
+///
+/// This uses the tokio clock, so it follows `tokio::time::pause()` in tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    /// The deadline the specified time from now.
+    pub fn after(timeout: Duration) -> Self {
+        Self(Instant::now() + timeout)
+    }
+
+    /// The time left until the deadline, which is zero once it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    /// True if the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.0
+    }
+
+    /// The earlier of this deadline and the specified time from now.
+    pub fn min_after(self, timeout: Duration) -> Self {
+        self.min(Self::after(timeout))
+    }
+}
+
+impl From<Duration> for Deadline {
+    fn from(timeout: Duration) -> Self {
+        Self::after(timeout)
+    }
+}
+
+/// Repeat an arbitrary async operation until it produces a value, or the deadline passes.
+///
+/// Each attempt returns `Ok(Some(value))` to finish the wait, `Ok(None)` to keep
+/// waiting, or an error. By default an error ends the wait immediately. Use
+/// [`Wait::retry_errors`] to keep waiting after errors that may go away.
+///
+/// Attempts are spaced out by an element poller, the same as for element queries. The
+/// wait also ends if the poller gives up, for example because its own timeout is shorter.
+/// When the wait times out, the `Timeout` error describes the condition, how many attempts
+/// were made and what the last attempt saw, as described in [`TimeoutErrorBuilder`].
+///
+/// See also [`wait_for`] and [`wait_for_value`].
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use std::time::Duration;
+/// use thirtyfour::support::Wait;
+/// use thirtyfour::is_transient;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let orders: u64 = Wait::new(Duration::from_secs(10))
+///     .describe("the order to be saved")
+///     .retry_errors(is_transient)
+///     .until(|| async {
+///         let count: u64 = driver.execute("return window.savedOrders;", Vec::new()).await?.convert()?;
+///         Ok((count > 0).then_some(count))
+///     })
+///     .await?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Wait {
+    deadline: Deadline,
+    poller: Option<Arc<dyn IntoElementPoller + Send + Sync>>,
+    condition: String,
+    retryable: Arc<dyn Fn(&WebDriverError) -> bool + Send + Sync>,
+}
+
+impl Debug for Wait {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Wait")
+            .field("deadline", &self.deadline)
+            .field("poller", &self.poller)
+            .field("condition", &self.condition)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Wait {
+    /// Wait until the specified deadline, or for the specified duration.
+    pub fn new(deadline: impl Into<Deadline>) -> Self {
+        Self {
+            deadline: deadline.into(),
+            poller: None,
+            condition: "the operation to succeed".to_string(),
+            retryable: Arc::new(|_| false),
+        }
+    }
+
+    /// Set the poller spacing out the attempts. By default they are 500 milliseconds apart.
+    pub fn poller(mut self, poller: impl IntoElementPoller + Send + Sync + 'static) -> Self {
+        self.poller = Some(Arc::new(poller));
+        self
+    }
+
+    /// Describe the awaited condition for the timeout error. It should read naturally
+    /// after "waited 5.0s for", for example "the API to respond".
+    pub fn describe(mut self, condition: impl Into<String>) -> Self {
+        self.condition = condition.into();
+        self
+    }
+
+    /// Keep waiting after errors for which this returns true, such as
+    /// [`is_transient`](crate::is_transient). Other errors end the wait immediately.
+    pub fn retry_errors(
+        mut self,
+        retryable: impl Fn(&WebDriverError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retryable = Arc::new(retryable);
+        self
+    }
+
+    /// Repeat the operation until it returns a value, or the wait times out.
+    pub async fn until<T, F>(self, mut op: impl FnMut() -> F) -> WebDriverResult<T>
+    where
+        F: Future<Output = WebDriverResult<Option<T>>>,
+    {
+        self.poll(|| {
+            let attempt = op();
+            async move { attempt.await.map(|value| value.ok_or(None)) }
+        })
+        .await
+    }
+
+    /// Repeat the operation until its result matches the predicate, and return that
+    /// result, or time out. The timeout error shows the last result.
+    pub async fn until_value<T, F>(
+        self,
+        mut op: impl FnMut() -> F,
+        predicate: impl Fn(&T) -> bool,
+    ) -> WebDriverResult<T>
+    where
+        T: Debug,
+        F: Future<Output = WebDriverResult<T>>,
+    {
+        let predicate = &predicate;
+        self.poll(|| {
+            let attempt = op();
+            async move {
+                let value = attempt.await?;
+                match predicate(&value) {
+                    true => Ok(Ok(value)),
+                    false => Ok(Err(Some(format!("{value:?}")))),
+                }
+            }
+        })
+        .await
+    }
+
+    /// The polling loop. Each attempt returns the value, or what it observed instead.
+    async fn poll<T, F>(self, mut op: impl FnMut() -> F) -> WebDriverResult<T>
+    where
+        F: Future<Output = WebDriverResult<Result<T, Option<String>>>>,
+    {
+        let mut wait = TimeoutErrorBuilder::new(self.condition);
+        let mut poller = match self.poller {
+            Some(poller) => poller.start(),
+            None => ElementPollerWithDeadline::new(self.deadline, DEFAULT_INTERVAL).start(),
+        };
+        loop {
+            wait.attempt();
+            match op().await {
+                Ok(Ok(value)) => return Ok(value),
+                Ok(Err(Some(state))) => wait.observed(state),
+                Ok(Err(None)) => {}
+                Err(e) if (self.retryable)(&e) => wait.observed(format!("error: {e}")),
+                Err(e) => return Err(e),
+            }
+
+            // The poller may sleep, but never past the deadline.
+            let remaining = self.deadline.remaining();
+            if remaining.is_zero()
+                || !matches!(tokio::time::timeout(remaining, poller.tick()).await, Ok(true))
+            {
+                return Err(wait.build());
+            }
+        }
+    }
+}
+
+/// Repeat an arbitrary async operation until it returns `Ok(Some(value))`, spacing out
+/// the attempts with the specified poller, and return the value.
+///
+/// `Ok(None)` means keep waiting, and an error ends the wait immediately. This is
+/// shorthand for `Wait::new(timeout).poller(poller).until(op)`. See [`Wait`] for
+/// retrying errors and describing the condition.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// # use thirtyfour::support::block_on;
+/// use std::time::Duration;
+/// use thirtyfour::extensions::query::ElementPollerWithTimeout;
+/// use thirtyfour::support::wait_for;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     block_on(async {
+/// #         let caps = DesiredCapabilities::chrome();
+/// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+/// let timeout = Duration::from_secs(5);
+/// let poller = ElementPollerWithTimeout::new(timeout, Duration::from_millis(100));
+/// let title = wait_for(timeout, poller, || async {
+///     let title = driver.title().await?;
+///     Ok((!title.is_empty()).then_some(title))
+/// })
+/// .await?;
+/// #         driver.quit().await?;
+/// #         Ok(())
+/// #     })
+/// # }
+/// ```
+pub async fn wait_for<T, F>(
+    timeout: impl Into<Deadline>,
+    poller: impl IntoElementPoller + Send + Sync + 'static,
+    op: impl FnMut() -> F,
+) -> WebDriverResult<T>
+where
+    F: Future<Output = WebDriverResult<Option<T>>>,
+{
+    Wait::new(timeout).poller(poller).until(op).await
+}
+
+/// Repeat an arbitrary async operation until its result matches the predicate, with the
+/// attempts 500 milliseconds apart, and return that result.
+///
+/// An error ends the wait immediately. This is shorthand for
+/// `Wait::new(timeout).until_value(op, predicate)`. Use [`Wait::poller`] to space out
+/// the attempts differently.
+pub async fn wait_for_value<T, F>(
+    timeout: impl Into<Deadline>,
+    op: impl FnMut() -> F,
+    predicate: impl Fn(&T) -> bool,
+) -> WebDriverResult<T>
+where
+    T: Debug,
+    F: Future<Output = WebDriverResult<T>>,
+{
+    Wait::new(timeout).until_value(op, predicate).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::error::{ErrorKind, WebDriverErrorInfo};
+    use crate::extensions::query::{ElementPoller, ElementPollerNoWait};
+
+    /// Sleeps for the same interval on every tick, on the tokio clock.
+    #[derive(Debug, Clone, Copy)]
+    struct FixedPoller(Duration);
+
+    #[async_trait::async_trait]
+    impl ElementPoller for FixedPoller {
+        async fn tick(&mut self) -> bool {
+            tokio::time::sleep(self.0).await;
+            true
+        }
+    }
+
+    impl IntoElementPoller for FixedPoller {
+        fn start(&self) -> Box<dyn ElementPoller + Send + Sync> {
+            Box::new(*self)
+        }
+    }
+
+    fn stale() -> WebDriverError {
+        WebDriverError::StaleElementReference(WebDriverErrorInfo::new("stale".to_string()))
+    }
+
+    const INTERVAL: FixedPoller = FixedPoller(Duration::from_millis(300));
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_flaky() {
+        let calls = AtomicU32::new(0);
+        let started = Instant::now();
+        let value = wait_for(Duration::from_secs(5), INTERVAL, || async {
+            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok((n == 3).then_some(n))
+        })
+        .await
+        .unwrap();
+        assert_eq!(value, 3);
+        assert_eq!(started.elapsed(), Duration::from_millis(600));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_timeout() {
+        let calls = AtomicU32::new(0);
+        let started = Instant::now();
+        let err = Wait::new(Duration::from_secs(1))
+            .poller(INTERVAL)
+            .describe("the job to finish")
+            .until(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(None::<()>)
+            })
+            .await
+            .unwrap_err();
+        // Attempts at 0, 300, 600 and 900ms, then the deadline cuts the last sleep short.
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+        assert!(err.is_timeout());
+        assert!(err.to_string().contains("for the job to finish (4 attempts)"), "{err}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_errors() {
+        // By default the first error ends the wait.
+        let calls = AtomicU32::new(0);
+        let err = wait_for(Duration::from_secs(5), INTERVAL, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<Option<()>, _>(stale())
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Stale);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Retryable errors are retried, and other errors still end the wait.
+        let calls = AtomicU32::new(0);
+        let err = Wait::new(Duration::from_secs(5))
+            .poller(INTERVAL)
+            .retry_errors(|e| e.kind() == ErrorKind::Stale)
+            .until(|| async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err::<Option<()>, _>(stale()),
+                    _ => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                        "bad".to_string(),
+                    ))),
+                }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // A timeout after retryable errors shows the last one.
+        let retried = ErrorKind::Stale;
+        let err = Wait::new(Duration::from_secs(1))
+            .poller(INTERVAL)
+            .retry_errors(move |e| e.kind() == retried)
+            .until(|| async { Err::<Option<()>, _>(stale()) })
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout());
+        assert!(err.to_string().contains("(4 attempts); last saw error: "), "{err}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_value() {
+        let calls = AtomicU32::new(0);
+        let value = wait_for_value(
+            Duration::from_secs(5),
+            || async { Ok(calls.fetch_add(1, Ordering::SeqCst)) },
+            |n| *n >= 2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(value, 2);
+
+        let err = Wait::new(Duration::from_secs(1))
+            .poller(INTERVAL)
+            .describe("the count to reach 10")
+            .until_value(|| async { Ok(vec![1, 2]) }, |x| x.len() == 10)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("(4 attempts); last saw [1, 2]"), "{err}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_deadline() {
+        let deadline = Deadline::after(Duration::from_secs(2));
+        assert_eq!(deadline.min_after(Duration::from_secs(5)), deadline);
+        assert!(deadline.min_after(Duration::from_secs(1)) < deadline);
+
+        // Nested waits share the outer deadline.
+        tokio::time::advance(Duration::from_millis(1500)).await;
+        assert_eq!(deadline.remaining(), Duration::from_millis(500));
+        let started = Instant::now();
+        let err = wait_for(deadline, INTERVAL, || async { Ok(None::<()>) }).await.unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(started.elapsed(), Duration::from_millis(500));
+        assert!(deadline.is_expired());
+        assert_eq!(deadline.remaining(), Duration::ZERO);
+
+        // A poller that gives up ends the wait early.
+        let err =
+            wait_for(Duration::from_secs(5), ElementPollerNoWait, || async { Ok(None::<()>) })
+                .await
+                .unwrap_err();
+        assert!(err.to_string().contains("(1 attempt)"), "{err}");
+    }
+}
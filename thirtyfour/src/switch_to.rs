@@ -1,12 +1,39 @@
 use crate::common::command::Command;
-use crate::error::WebDriverErrorInfo;
+use crate::error::{TimeoutErrorBuilder, WebDriverErrorInfo, WebDriverErrorInner};
+use crate::extensions::query::{ElementPollerWithTimeout, IntoElementPoller};
 use crate::session::handle::SessionHandle;
-use crate::WindowHandle;
 use crate::{
     error::{WebDriverError, WebDriverResult},
     Alert, WebElement,
 };
-use std::sync::Arc;
+use crate::{By, FrameSelector, WindowHandle, WindowInfo};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The window handles switched to during a session, least recently used first.
+#[derive(Debug, Default)]
+pub(crate) struct WindowHistory {
+    handles: Mutex<Vec<WindowHandle>>,
+}
+
+impl WindowHistory {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<WindowHandle>> {
+        self.handles.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, handle: &WindowHandle) {
+        let mut handles = self.lock();
+        handles.retain(|h| h != handle);
+        handles.push(handle.clone());
+    }
+
+    /// The most recently used handle out of the specified handles, if any.
+    fn most_recent(&self, candidates: &[WindowHandle]) -> Option<WindowHandle> {
+        let mut handles = self.lock();
+        handles.retain(|h| candidates.contains(h));
+        handles.last().cloned()
+    }
+}
 
 /// Struct for switching between frames/windows/alerts.
 #[derive(Debug)]
@@ -180,6 +207,47 @@ impl SessionHandle {
         Ok(())
     }
 
+    /// Switch to the top-level document. Alias of `enter_default_frame()`.
+    pub async fn switch_to_default_content(&self) -> WebDriverResult<()> {
+        self.enter_default_frame().await
+    }
+
+    /// Follow focus into any (nested) iframes and return the element with focus.
+    ///
+    /// If the active element is an iframe, this switches into that iframe and repeats
+    /// until the active element is not an iframe. The session remains switched to the
+    /// frame containing the returned element.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.switch_to_active_element_frame().await?;
+    /// elem.send_keys("thirtyfour").await?;
+    /// driver.switch_to_default_content().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn switch_to_active_element_frame(
+        self: &Arc<SessionHandle>,
+    ) -> WebDriverResult<WebElement> {
+        loop {
+            let elem = self.active_element().await?;
+            let tag_name = elem.tag_name().await?.to_ascii_lowercase();
+            if tag_name != "iframe" && tag_name != "frame" {
+                return Ok(elem);
+            }
+            self.switch_to_frame_element(&elem).await?;
+        }
+    }
+
     /// Switch to an iframe by index. The first iframe on the page has index 0.
     ///
     /// # Example:
@@ -236,6 +304,126 @@ impl SessionHandle {
         Ok(())
     }
 
+    /// Switch to the parent frame. Alias of `enter_parent_frame()`.
+    pub async fn switch_to_parent_frame(&self) -> WebDriverResult<()> {
+        self.enter_parent_frame().await
+    }
+
+    /// Switch to the specified iframe element.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem_iframe = driver.find(By::Id("iframeid1")).await?;
+    /// driver.switch_to_frame_element(&elem_iframe).await?;
+    /// // We can now search for elements within the iframe.
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn switch_to_frame_element(&self, frame_element: &WebElement) -> WebDriverResult<()> {
+        self.cmd(Command::SwitchToFrameElement(frame_element.element_id())).await?;
+        Ok(())
+    }
+
+    /// Switch to the iframe with the specified `name` or `id` attribute.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.switch_to_frame_named("content").await?;
+    /// // We can now search for elements within the iframe.
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn switch_to_frame_named(
+        self: &Arc<SessionHandle>,
+        name_or_id: &str,
+    ) -> WebDriverResult<()> {
+        let value = name_or_id.replace('\\', "\\\\").replace('"', "\\\"");
+        let css = ["iframe", "frame"]
+            .iter()
+            .flat_map(|tag| ["name", "id"].map(|attr| format!(r#"{tag}[{attr}="{value}"]"#)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let frame_element = self.find(By::Css(css)).await.map_err(|e| {
+            WebDriverError::NoSuchFrame(WebDriverErrorInfo::new(format!(
+                "unable to find frame with name or id '{name_or_id}': {e}"
+            )))
+        })?;
+        self.switch_to_frame_element(&frame_element).await
+    }
+
+    /// Switch into a nested frame by following the specified path from the top-level document.
+    ///
+    /// Each step may be an index, a `name` or `id` attribute, or a selector for the frame element.
+    /// If any step fails, a `NoSuchFrame` error describing the failed step is returned.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use thirtyfour::FrameSelector;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver
+    ///     .switch_into_path(&[
+    ///         FrameSelector::Index(0),
+    ///         FrameSelector::Name("sidebar".to_string()),
+    ///         FrameSelector::By(By::Css("iframe.widget")),
+    ///     ])
+    ///     .await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn switch_into_path(
+        self: &Arc<SessionHandle>,
+        path: &[FrameSelector],
+    ) -> WebDriverResult<()> {
+        self.enter_default_frame().await?;
+        for (i, step) in path.iter().enumerate() {
+            let result = match step {
+                FrameSelector::Index(index) => self.enter_frame(*index).await,
+                FrameSelector::Name(name) => self.switch_to_frame_named(name).await,
+                FrameSelector::By(by) => match self.find(by.clone()).await {
+                    Ok(elem) => self.switch_to_frame_element(&elem).await,
+                    Err(e) => Err(e),
+                },
+            };
+
+            if let Err(e) = result {
+                return Err(WebDriverError::NoSuchFrame(WebDriverErrorInfo::new(format!(
+                    "unable to switch to frame at step {} of {} ({step}): {e}",
+                    i + 1,
+                    path.len()
+                ))));
+            }
+        }
+        Ok(())
+    }
+
     /// Switch to the specified window.
     ///
     /// # Example:
@@ -262,10 +450,120 @@ impl SessionHandle {
     /// # }
     /// ```
     pub async fn switch_to_window(&self, handle: WindowHandle) -> WebDriverResult<()> {
-        self.cmd(Command::SwitchToWindow(handle)).await?;
+        self.cmd(Command::SwitchToWindow(handle.clone())).await?;
+        self.window_history().record(&handle);
         Ok(())
     }
 
+    /// Switch to the specified window and bring it to the foreground.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let handle = driver.new_window().await?;
+    /// driver.focus_window(handle).await?;
+    /// let screenshot = driver.screenshot_as_png().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn focus_window(
+        self: &Arc<SessionHandle>,
+        handle: WindowHandle,
+    ) -> WebDriverResult<()> {
+        self.switch_to_window(handle).await?;
+        self.execute(r#"window.focus();"#, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Close every window except the current one.
+    ///
+    /// Returns the number of windows that were closed.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// driver.new_tab().await?;
+    /// driver.new_tab().await?;
+    /// assert_eq!(driver.close_other_windows().await?, 2);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn close_other_windows(&self) -> WebDriverResult<usize> {
+        let current = self.window().await?;
+        let mut closed = 0;
+        for handle in self.windows().await? {
+            if handle == current {
+                continue;
+            }
+            self.switch_to_window(handle).await?;
+            self.close_window().await?;
+            closed += 1;
+        }
+        self.switch_to_window(current).await?;
+        Ok(closed)
+    }
+
+    /// Close the current window and switch to the most recently used remaining window.
+    ///
+    /// Windows are tracked as they are switched to via this `WebDriver`. If none of the
+    /// remaining windows were switched to, the first remaining window is used.
+    ///
+    /// Returns an `UnsupportedOperation` error without closing anything if the current
+    /// window is the last one. Use `WebDriver::quit()` to end the session instead.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let original = driver.window().await?;
+    /// let handle = driver.new_tab().await?;
+    /// driver.switch_to_window(handle).await?;
+    /// let current = driver.close_window_and_switch_back().await?;
+    /// assert_eq!(current, original);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn close_window_and_switch_back(&self) -> WebDriverResult<WindowHandle> {
+        let current = self.window().await?;
+        let remaining: Vec<WindowHandle> =
+            self.windows().await?.into_iter().filter(|h| *h != current).collect();
+        if remaining.is_empty() {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "refusing to close the last window: there would be no window to switch back to"
+                    .to_string(),
+            )));
+        }
+
+        self.close_window().await?;
+        let handle =
+            self.window_history().most_recent(&remaining).unwrap_or_else(|| remaining[0].clone());
+        self.switch_to_window(handle.clone()).await?;
+        Ok(handle)
+    }
+
     /// Switch to the window with the specified name. This uses the `window.name` property.
     /// You can set a window name via `WebDriver::set_window_name("someName").await?`.
     ///
@@ -319,6 +617,131 @@ impl SessionHandle {
         ))))
     }
 
+    /// Get the title and URL of every window.
+    ///
+    /// This switches to each window in turn, and then switches back to the current window.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// for info in driver.windows_info().await? {
+    ///     println!("{}: {}", info.title, info.url);
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn windows_info(&self) -> WebDriverResult<Vec<WindowInfo>> {
+        let original_handle = self.window().await?;
+        let mut infos = Vec::new();
+        for handle in self.windows().await? {
+            if let Some(info) = self.window_info(handle).await? {
+                infos.push(info);
+            }
+        }
+        self.cmd(Command::SwitchToWindow(original_handle)).await?;
+        Ok(infos)
+    }
+
+    /// Switch to the first window matching the predicate, waiting up to the specified
+    /// timeout for one to appear.
+    ///
+    /// The windows are enumerated repeatedly, so this can be used to wait for a pop-up
+    /// to open or to finish navigating. If no window matches before the timeout, the
+    /// original window is restored and a `NoSuchWindow` error describing the wait and
+    /// listing every window seen is returned.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let info = driver
+    ///     .switch_to_window_where(
+    ///         |info| info.url.path().contains("/checkout") && !info.title.is_empty(),
+    ///         Duration::from_secs(10),
+    ///     )
+    ///     .await?;
+    /// assert_eq!(driver.window().await?, info.handle);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn switch_to_window_where<F>(
+        &self,
+        predicate: F,
+        timeout: Duration,
+    ) -> WebDriverResult<WindowInfo>
+    where
+        F: Fn(&WindowInfo) -> bool,
+    {
+        let original_handle = self.window().await?;
+        let mut seen: Vec<WindowInfo> = Vec::new();
+        let mut wait = TimeoutErrorBuilder::new("a window matching the predicate");
+        let mut poller = ElementPollerWithTimeout::new(timeout, Duration::from_millis(500)).start();
+        let poll = async {
+            loop {
+                wait.attempt();
+                for handle in self.windows().await? {
+                    let Some(info) = self.window_info(handle).await? else {
+                        continue;
+                    };
+                    if predicate(&info) {
+                        self.window_history().record(&info.handle);
+                        return Ok(info);
+                    }
+
+                    match seen.iter_mut().find(|x| x.handle == info.handle) {
+                        Some(x) => *x = info,
+                        None => seen.push(info),
+                    }
+                }
+
+                if !poller.tick().await {
+                    self.cmd(Command::SwitchToWindow(original_handle)).await?;
+                    let seen: Vec<String> = seen.iter().map(ToString::to_string).collect();
+                    wait.observed(format!("windows [{}]", seen.join(", ")));
+                    return Err(WebDriverError::NoSuchWindow(WebDriverErrorInfo::new(
+                        wait.message(),
+                    )));
+                }
+            }
+        };
+        self.report_errors(poll).await
+    }
+
+    /// Switch to the specified window and get its title and URL.
+    ///
+    /// Returns `None` if the window was closed in the meantime.
+    async fn window_info(&self, handle: WindowHandle) -> WebDriverResult<Option<WindowInfo>> {
+        // Switch without recording the window as used.
+        match self.cmd(Command::SwitchToWindow(handle.clone())).await {
+            Ok(_) => {}
+            Err(e) if matches!(e.as_inner(), WebDriverErrorInner::NoSuchWindow(_)) => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        }
+        Ok(Some(WindowInfo {
+            handle,
+            title: self.title().await?,
+            url: self.current_url().await?,
+        }))
+    }
+
     /// Switch to a new window.
     ///
     /// # Example:
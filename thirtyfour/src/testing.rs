@@ -0,0 +1,395 @@
+use std::fmt::{self, Display};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::common::config::WebDriverConfig;
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::{support, Capabilities, LogEntry, WebDriver};
+
+/// The configuration for [`TestSession::start`].
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// use thirtyfour::testing::TestConfig;
+/// #
+/// # async fn run() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let session = TestConfig::new("http://localhost:4444", caps)
+///     .name("checkout")
+///     .artifacts_dir("target/test-artifacts")
+///     .start()
+///     .await?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestConfig {
+    /// The URL of the WebDriver server.
+    pub server_url: String,
+    /// The capabilities of the new session.
+    pub capabilities: Capabilities,
+    /// The configuration of the new `WebDriver`.
+    pub webdriver_config: WebDriverConfig,
+    /// The name of the test. If not set, the name of the current thread is used, which
+    /// is the name of the test when running under `cargo test`.
+    pub name: Option<String>,
+    /// The directory in which each test gets a directory for its artifacts.
+    pub artifacts_dir: PathBuf,
+}
+
+impl TestConfig {
+    /// The default directory for test artifacts.
+    pub const DEFAULT_ARTIFACTS_DIR: &'static str = "target/test-artifacts";
+
+    /// Create the configuration for a session with the specified server and capabilities.
+    pub fn new(server_url: impl Into<String>, capabilities: impl Into<Capabilities>) -> Self {
+        Self {
+            server_url: server_url.into(),
+            capabilities: capabilities.into(),
+            webdriver_config: WebDriverConfig::default(),
+            name: None,
+            artifacts_dir: PathBuf::from(Self::DEFAULT_ARTIFACTS_DIR),
+        }
+    }
+
+    /// Set the name of the test.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the directory in which each test gets a directory for its artifacts.
+    pub fn artifacts_dir(mut self, artifacts_dir: impl Into<PathBuf>) -> Self {
+        self.artifacts_dir = artifacts_dir.into();
+        self
+    }
+
+    /// Set the configuration of the new `WebDriver`.
+    pub fn webdriver_config(mut self, config: WebDriverConfig) -> Self {
+        self.webdriver_config = config;
+        self
+    }
+
+    /// Start the session. Shorthand for [`TestSession::start`].
+    pub async fn start(self) -> WebDriverResult<TestSession> {
+        TestSession::start(self).await
+    }
+}
+
+/// A WebDriver session for a single test.
+///
+/// The session is tagged with the name of the test, using the `se:name` capability
+/// understood by Selenium Grid, and the `name` of the `sauce:options` capability if it
+/// is set. When the test is done, pass its result to [`TestSession::finish`], which:
+///
+/// - replaces the test's directory in the artifacts directory,
+/// - if the test failed, writes `screenshot.png` and `console.json` (browser console
+///   log, supported by Chromium-based drivers only) there,
+/// - writes `result.json`, containing the name, the status (`"passed"` or `"failed"`)
+///   and the error of the test,
+/// - reports the status to Sauce Labs if the `sauce:options` capability is set,
+/// - quits the session.
+///
+/// The test's directory is named after the test, with every character other than
+/// letters, digits, `-`, `_` and `.` replaced by `_`.
+///
+/// If the session is dropped without calling `finish()`, the same is done, blocking the
+/// current thread, and the test is recorded as failed if the thread is panicking.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour::prelude::*;
+/// use thirtyfour::testing::{TestConfig, TestSession};
+///
+/// #[tokio::test]
+/// async fn checkout() -> WebDriverResult<()> {
+///     let caps = DesiredCapabilities::chrome();
+///     let session = TestSession::start(TestConfig::new("http://localhost:4444", caps)).await?;
+///     let result: WebDriverResult<()> = async {
+///         let driver = session.driver();
+///         driver.goto("https://example.com").await?;
+///         assert_eq!(driver.title().await?, "Example Domain");
+///         Ok(())
+///     }
+///     .await;
+///     session.finish(result).await
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TestSession {
+    driver: Option<WebDriver>,
+    name: String,
+    artifacts_dir: PathBuf,
+    sauce: bool,
+}
+
+impl TestSession {
+    /// Start a new session for the current test.
+    pub async fn start(config: TestConfig) -> WebDriverResult<Self> {
+        let name = test_name(config.name);
+        let mut capabilities = config.capabilities;
+        capabilities.insert("se:name".to_string(), json!(name));
+        let sauce = match capabilities.get_mut("sauce:options") {
+            Some(Value::Object(options)) => {
+                options.insert("name".to_string(), json!(name));
+                true
+            }
+            _ => false,
+        };
+
+        let driver =
+            WebDriver::new_with_config(config.server_url, capabilities, config.webdriver_config)
+                .await?;
+        Ok(Self {
+            driver: Some(driver),
+            artifacts_dir: config.artifacts_dir.join(dir_name(&name)),
+            name,
+            sauce,
+        })
+    }
+
+    /// The driver for the session.
+    pub fn driver(&self) -> &WebDriver {
+        self.driver.as_ref().expect("the session is only finished when it is consumed")
+    }
+
+    /// The name of the test.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The directory the artifacts of this test are written to.
+    pub fn artifacts_dir(&self) -> &Path {
+        &self.artifacts_dir
+    }
+
+    /// Record the result of the test, capture artifacts if it failed, and quit the session.
+    ///
+    /// Returns the result of the test unchanged if it failed. If it passed, returns any
+    /// error writing the artifacts or quitting the session. Failures to capture the
+    /// screenshot or console log are logged and otherwise ignored, so that they do not
+    /// hide the failure of the test.
+    pub async fn finish<T, E>(mut self, result: Result<T, E>) -> Result<T, E>
+    where
+        E: Display + From<WebDriverError>,
+    {
+        let driver = self.driver.take().expect("the session is only finished once");
+        let error = result.as_ref().err().map(ToString::to_string);
+        let finished = finish(driver, self.report(error), self.sauce).await;
+        match (result, finished) {
+            (Ok(_), Err(e)) => Err(e.into()),
+            (Err(e), Err(finished)) => {
+                tracing::warn!("failed to finish the session of test {}: {finished}", self.name);
+                Err(e)
+            }
+            (result, Ok(())) => result,
+        }
+    }
+
+    fn report(&self, error: Option<String>) -> TestReport {
+        TestReport {
+            name: self.name.clone(),
+            dir: self.artifacts_dir.clone(),
+            error,
+        }
+    }
+}
+
+impl Drop for TestSession {
+    fn drop(&mut self) {
+        let Some(driver) = self.driver.take() else {
+            return;
+        };
+        let error = std::thread::panicking().then(|| "the test panicked".to_string());
+        let report = self.report(error);
+        let sauce = self.sauce;
+        support::spawn_blocked_future(|_| async move {
+            let name = report.name.clone();
+            if let Err(e) = finish(driver, report, sauce).await {
+                tracing::warn!("failed to finish the session of test {name}: {e}");
+            }
+        });
+    }
+}
+
+/// The outcome of a test, and where to write its artifacts.
+#[derive(Debug, Clone)]
+struct TestReport {
+    name: String,
+    dir: PathBuf,
+    error: Option<String>,
+}
+
+impl TestReport {
+    fn status(&self) -> &'static str {
+        match self.error {
+            None => "passed",
+            Some(_) => "failed",
+        }
+    }
+}
+
+impl Display for TestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "test {} {}", self.name, self.status())
+    }
+}
+
+/// The artifacts captured from the browser when a test fails.
+#[derive(Debug, Default)]
+struct Artifacts {
+    screenshot: Option<Vec<u8>>,
+    console: Option<Vec<LogEntry>>,
+}
+
+#[derive(Serialize)]
+struct ResultFile<'a> {
+    name: &'a str,
+    status: &'a str,
+    error: Option<&'a str>,
+}
+
+async fn finish(driver: WebDriver, report: TestReport, sauce: bool) -> WebDriverResult<()> {
+    let result = record(&driver, &report, sauce).await;
+    let quit = driver.quit().await;
+    result.and(quit)
+}
+
+async fn record(driver: &WebDriver, report: &TestReport, sauce: bool) -> WebDriverResult<()> {
+    let mut artifacts = Artifacts::default();
+    if report.error.is_some() {
+        match driver.screenshot_as_png().await {
+            Ok(png) => artifacts.screenshot = Some(png),
+            Err(e) => tracing::warn!("failed to capture a screenshot for {report}: {e}"),
+        }
+        match driver.console_logs().await {
+            Ok(console) => artifacts.console = Some(console),
+            Err(e) => tracing::debug!("failed to capture the console log for {report}: {e}"),
+        }
+    }
+
+    let written = {
+        let report = report.clone();
+        tokio::task::spawn_blocking(move || write_artifacts(&report, &artifacts)).await
+    };
+    if sauce {
+        let script = format!("sauce:job-result={}", report.status());
+        if let Err(e) = driver.execute(script, Vec::new()).await {
+            tracing::warn!("failed to report the result of {report} to Sauce Labs: {e}");
+        }
+    }
+    Ok(written.map_err(io::Error::from)??)
+}
+
+/// Replace the test's directory with one containing the result and artifacts.
+fn write_artifacts(report: &TestReport, artifacts: &Artifacts) -> io::Result<()> {
+    match std::fs::remove_dir_all(&report.dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    std::fs::create_dir_all(&report.dir)?;
+
+    if let Some(png) = &artifacts.screenshot {
+        std::fs::write(report.dir.join("screenshot.png"), png)?;
+    }
+    if let Some(console) = &artifacts.console {
+        std::fs::write(report.dir.join("console.json"), serde_json::to_vec_pretty(console)?)?;
+    }
+    let result = ResultFile {
+        name: &report.name,
+        status: report.status(),
+        error: report.error.as_deref(),
+    };
+    std::fs::write(report.dir.join("result.json"), serde_json::to_vec_pretty(&result)?)
+}
+
+/// The explicit name of the test, or else the name of the current thread.
+fn test_name(name: Option<String>) -> String {
+    name.or_else(|| {
+        std::thread::current().name().filter(|name| *name != "main").map(ToString::to_string)
+    })
+    .unwrap_or_else(|| "unnamed".to_string())
+}
+
+/// The name of the directory for the artifacts of a test.
+fn dir_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("thirtyfour-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn read_json(path: PathBuf) -> Value {
+        serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_test_name() {
+        assert_eq!(test_name(Some("explicit".to_string())), "explicit");
+        assert_eq!(test_name(None), "testing::tests::test_test_name");
+        assert_eq!(dir_name("testing::tests::test_test_name"), "testing__tests__test_test_name");
+        assert_eq!(dir_name("with space/and-dash.v2"), "with_space_and-dash.v2");
+    }
+
+    #[test]
+    fn test_write_artifacts() {
+        let root = temp_dir("artifacts");
+        let report = TestReport {
+            name: "suite::checkout".to_string(),
+            dir: root.join(dir_name("suite::checkout")),
+            error: Some("element not found".to_string()),
+        };
+        let console = LogEntry {
+            level: "SEVERE".to_string(),
+            message: "Uncaught TypeError".to_string(),
+            timestamp: 1,
+        };
+        let artifacts = Artifacts {
+            screenshot: Some(b"\x89PNG".to_vec()),
+            console: Some(vec![console.clone()]),
+        };
+        write_artifacts(&report, &artifacts).unwrap();
+
+        let dir = root.join("suite__checkout");
+        assert_eq!(std::fs::read(dir.join("screenshot.png")).unwrap(), b"\x89PNG");
+        let logged: Vec<LogEntry> =
+            serde_json::from_value(read_json(dir.join("console.json"))).unwrap();
+        assert_eq!(logged, [console]);
+        assert_eq!(
+            read_json(dir.join("result.json")),
+            json!({ "name": "suite::checkout", "status": "failed", "error": "element not found" })
+        );
+
+        // Passing replaces the artifacts of the previous run.
+        let report = TestReport {
+            error: None,
+            ..report
+        };
+        write_artifacts(&report, &Artifacts::default()).unwrap();
+        let mut files: Vec<_> =
+            std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        files.sort();
+        assert_eq!(files, ["result.json"]);
+        assert_eq!(
+            read_json(dir.join("result.json")),
+            json!({ "name": "suite::checkout", "status": "passed", "error": null })
+        );
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use crate::common::config::WebDriverConfig;
 use crate::error::WebDriverResult;
 use crate::prelude::WebDriverError;
-use crate::session::create::start_session;
+use crate::session::create::start_session_with_info;
 use crate::session::handle::SessionHandle;
 #[cfg(feature = "reqwest")]
 use crate::session::http::create_reqwest_client;
@@ -123,9 +123,11 @@ impl WebDriver {
             .map_err(|e| WebDriverError::ParseError(format!("invalid url: {e}")))?;
 
         let client = Arc::new(client);
-        let session_id = start_session(client.as_ref(), &server_url, &config, capabilities).await?;
+        let (session_id, driver) =
+            start_session_with_info(client.as_ref(), &server_url, &config, capabilities).await?;
 
-        let handle = SessionHandle::new_with_config(client, server_url, session_id, config)?;
+        let handle =
+            SessionHandle::new_with_config(client, server_url, session_id, driver, config)?;
         Ok(Self {
             handle: Arc::new(handle),
         })
@@ -1,14 +1,18 @@
 use serde::ser::{Serialize, Serializer};
 use serde_json::Value;
 use std::fmt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::common::command::Command;
-use crate::error::{WebDriverError, WebDriverErrorInner};
-use crate::js::SIMULATE_DRAG_AND_DROP;
+use crate::error::{ElementErrorContext, WebDriverError, WebDriverErrorInfo, WebDriverErrorInner};
+#[cfg(feature = "screenshot-crop")]
+use crate::js::ELEMENT_VISIBLE_RECT;
+use crate::js::{ELEMENT_OBSCURED_BY, SIMULATE_DRAG_AND_DROP};
 use crate::session::handle::SessionHandle;
+use crate::session::http::CmdResponse;
 use crate::support::base64_decode;
+use crate::RetryPolicy;
 use crate::{common::types::ElementRect, error::WebDriverResult, By, ElementRef};
 use crate::{support, IntoArcStr};
 use crate::{ElementId, TypingData};
@@ -121,6 +125,42 @@ impl WebElement {
         })?)
     }
 
+    /// Run a command for this element, adding the operation name to any element-related error.
+    async fn element_cmd(&self, operation: &str, command: Command) -> WebDriverResult<CmdResponse> {
+        self.handle
+            .report_errors(async {
+                self.handle
+                    .cmd(command)
+                    .await
+                    .map_err(|e| e.with_element_context(ElementErrorContext::operation(operation)))
+            })
+            .await
+    }
+
+    /// Add the selector, this element and the operation name to an element-related error
+    /// from a search scoped to this element.
+    async fn find_error(&self, e: WebDriverError, by: &By, operation: &str) -> WebDriverError {
+        if !e.is_element_error() {
+            return e;
+        }
+        e.with_element_context(ElementErrorContext {
+            selector: Some(by.describe()),
+            parent: self.describe().await,
+            operation: Some(operation.to_string()),
+        })
+    }
+
+    /// Describe this element for error messages, for example "<form id=login>".
+    ///
+    /// Returns None if the element cannot be inspected, for example because it is stale.
+    pub(crate) async fn describe(&self) -> Option<String> {
+        let tag = self.tag_name().await.ok()?.to_lowercase();
+        match self.id().await.ok().flatten().filter(|id| !id.is_empty()) {
+            Some(id) => Some(format!("<{tag} id={id}>")),
+            None => Some(format!("<{tag}>")),
+        }
+    }
+
     /// Get the internal element id for this element.
     ///
     /// NOTE: If you want the `id` property of an element,
@@ -148,7 +188,7 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn rect(&self) -> WebDriverResult<ElementRect> {
-        let r = self.handle.cmd(Command::GetElementRect(self.element_id.clone())).await?;
+        let r = self.element_cmd("rect", Command::GetElementRect(self.element_id.clone())).await?;
         r.value()
     }
 
@@ -177,7 +217,9 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn tag_name(&self) -> WebDriverResult<String> {
-        self.handle.cmd(Command::GetElementTagName(self.element_id.clone())).await?.value()
+        self.element_cmd("tag_name", Command::GetElementTagName(self.element_id.clone()))
+            .await?
+            .value()
     }
 
     /// Get the class name for this WebElement.
@@ -226,6 +268,8 @@ impl WebElement {
 
     /// Get the text contents for this WebElement.
     ///
+    /// Transient errors are retried according to the [`RetryPolicy`] for the session.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -244,7 +288,19 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn text(&self) -> WebDriverResult<String> {
-        self.handle.cmd(Command::GetElementText(self.element_id.clone())).await?.value()
+        self.text_with_policy(&self.handle.config().retry_policy).await
+    }
+
+    /// Get the text contents for this WebElement, retrying transient errors according to
+    /// the specified policy instead of the policy for the session.
+    pub async fn text_with_policy(&self, policy: &RetryPolicy) -> WebDriverResult<String> {
+        self.handle
+            .capture_on_error(policy.run("text", || async {
+                self.element_cmd("text", Command::GetElementText(self.element_id.clone()))
+                    .await?
+                    .value()
+            }))
+            .await
     }
 
     /// Convenience method for getting the (optional) value property of this element.
@@ -254,6 +310,8 @@ impl WebElement {
 
     /// Click the WebElement.
     ///
+    /// Transient errors are retried according to the [`RetryPolicy`] for the session.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -271,12 +329,45 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn click(&self) -> WebDriverResult<()> {
-        self.handle.cmd(Command::ElementClick(self.element_id.clone())).await?;
-        Ok(())
+        self.click_with_policy(&self.handle.config().retry_policy).await
+    }
+
+    /// Click the WebElement, retrying transient errors according to the specified policy
+    /// instead of the policy for the session.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// # use thirtyfour::{is_transient, RetryPolicy};
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// // Keep trying while another element is animating over the button.
+    /// let policy = RetryPolicy::new(is_transient, 10, Duration::from_millis(200));
+    /// elem.click_with_policy(&policy).await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn click_with_policy(&self, policy: &RetryPolicy) -> WebDriverResult<()> {
+        self.handle
+            .capture_on_error(policy.run("click", || async {
+                self.element_cmd("click", Command::ElementClick(self.element_id.clone())).await?;
+                Ok(())
+            }))
+            .await
     }
 
     /// Clear the WebElement contents.
     ///
+    /// Transient errors are retried according to the [`RetryPolicy`] for the session.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -294,8 +385,18 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn clear(&self) -> WebDriverResult<()> {
-        self.handle.cmd(Command::ElementClear(self.element_id.clone())).await?;
-        Ok(())
+        self.clear_with_policy(&self.handle.config().retry_policy).await
+    }
+
+    /// Clear the WebElement contents, retrying transient errors according to the specified
+    /// policy instead of the policy for the session.
+    pub async fn clear_with_policy(&self, policy: &RetryPolicy) -> WebDriverResult<()> {
+        self.handle
+            .capture_on_error(policy.run("clear", || async {
+                self.element_cmd("clear", Command::ElementClear(self.element_id.clone())).await?;
+                Ok(())
+            }))
+            .await
     }
 
     /// Get the specified property.
@@ -322,8 +423,7 @@ impl WebElement {
     /// ```
     pub async fn prop(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
         let resp = self
-            .handle
-            .cmd(Command::GetElementProperty(self.element_id.clone(), name.into()))
+            .element_cmd("prop", Command::GetElementProperty(self.element_id.clone(), name.into()))
             .await?;
         match resp.value()? {
             Value::String(v) => Ok(Some(v)),
@@ -362,8 +462,7 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn attr(&self, name: impl IntoArcStr) -> WebDriverResult<Option<String>> {
-        self.handle
-            .cmd(Command::GetElementAttribute(self.element_id.clone(), name.into()))
+        self.element_cmd("attr", Command::GetElementAttribute(self.element_id.clone(), name.into()))
             .await?
             .value()
     }
@@ -397,10 +496,12 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn css_value(&self, name: impl IntoArcStr) -> WebDriverResult<String> {
-        self.handle
-            .cmd(Command::GetElementCssValue(self.element_id.clone(), name.into()))
-            .await?
-            .value()
+        self.element_cmd(
+            "css_value",
+            Command::GetElementCssValue(self.element_id.clone(), name.into()),
+        )
+        .await?
+        .value()
     }
 
     /// Get the specified CSS property.
@@ -411,7 +512,9 @@ impl WebElement {
 
     /// Return true if the WebElement is currently selected, otherwise false.
     pub async fn is_selected(&self) -> WebDriverResult<bool> {
-        self.handle.cmd(Command::IsElementSelected(self.element_id.clone())).await?.value()
+        self.element_cmd("is_selected", Command::IsElementSelected(self.element_id.clone()))
+            .await?
+            .value()
     }
 
     /// Return true if the WebElement is currently displayed, otherwise false.
@@ -433,7 +536,9 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn is_displayed(&self) -> WebDriverResult<bool> {
-        self.handle.cmd(Command::IsElementDisplayed(self.element_id.clone())).await?.value()
+        self.element_cmd("is_displayed", Command::IsElementDisplayed(self.element_id.clone()))
+            .await?
+            .value()
     }
 
     /// Return true if the WebElement is currently enabled, otherwise false.
@@ -455,7 +560,9 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn is_enabled(&self) -> WebDriverResult<bool> {
-        self.handle.cmd(Command::IsElementEnabled(self.element_id.clone())).await?.value()
+        self.element_cmd("is_enabled", Command::IsElementEnabled(self.element_id.clone()))
+            .await?
+            .value()
     }
 
     /// Return true if the WebElement is currently clickable (visible and enabled),
@@ -481,6 +588,35 @@ impl WebElement {
         Ok(self.is_displayed().await? && self.is_enabled().await?)
     }
 
+    /// Return a description of the element covering the center of this WebElement, such
+    /// as `<div id=modal-backdrop>`, or None if nothing covers it.
+    ///
+    /// A click at the center of this element would go to the covering element instead.
+    /// This uses `elementFromPoint()`, so an element whose center is outside the viewport
+    /// is not checked and returns None.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("button1")).await?;
+    /// if let Some(cover) = elem.obscured_by().await? {
+    ///     println!("button is covered by {cover}");
+    /// }
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn obscured_by(&self) -> WebDriverResult<Option<String>> {
+        self.handle.execute(ELEMENT_OBSCURED_BY, vec![self.to_json()?]).await?.convert()
+    }
+
     /// Return true if the WebElement is currently (still) present
     /// and not stale.
     ///
@@ -511,7 +647,7 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn is_present(&self) -> WebDriverResult<bool> {
-        let present = match self.tag_name().await {
+        let present = match self.handle.report_errors(self.tag_name()).await {
             Ok(..) => true,
             Err(e) if matches!(*e, WebDriverErrorInner::StaleElementReference(..)) => false,
             Err(e) => return Err(e),
@@ -543,11 +679,16 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn find(&self, by: By) -> WebDriverResult<WebElement> {
-        let r = self
-            .handle
-            .cmd(Command::FindElementFromElement(self.element_id.clone(), by.into()))
-            .await?;
-        r.element(self.handle.clone())
+        let policy = &self.handle.config().retry_policy;
+        let find = policy.run_find("find", || async {
+            let command =
+                Command::FindElementFromElement(self.element_id.clone(), by.clone().into());
+            match self.handle.cmd(command).await {
+                Ok(r) => r.element(self.handle.clone()),
+                Err(e) => Err(self.find_error(e, &by, "find").await),
+            }
+        });
+        self.handle.report_errors(find).await
     }
 
     /// Search for a child element of this WebElement using the specified selector.
@@ -583,11 +724,16 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn find_all(&self, by: By) -> WebDriverResult<Vec<WebElement>> {
-        let r = self
-            .handle
-            .cmd(Command::FindElementsFromElement(self.element_id.clone(), by.into()))
-            .await?;
-        r.elements(self.handle.clone())
+        let policy = &self.handle.config().retry_policy;
+        let find = policy.run_find("find_all", || async {
+            let command =
+                Command::FindElementsFromElement(self.element_id.clone(), by.clone().into());
+            match self.handle.cmd(command).await {
+                Ok(r) => r.elements(self.handle.clone()),
+                Err(e) => Err(self.find_error(e, &by, "find_all").await),
+            }
+        });
+        self.handle.report_errors(find).await
     }
 
     /// Search for all child elements of this WebElement that match the specified selector.
@@ -598,6 +744,8 @@ impl WebElement {
 
     /// Send the specified input.
     ///
+    /// Transient errors are retried according to the [`RetryPolicy`] for the session.
+    ///
     /// # Example:
     /// ```no_run
     /// # use thirtyfour::prelude::*;
@@ -634,13 +782,34 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn send_keys(&self, key: impl Into<TypingData>) -> WebDriverResult<()> {
-        self.handle.cmd(Command::ElementSendKeys(self.element_id.clone(), key.into())).await?;
-        Ok(())
+        self.send_keys_with_policy(key, &self.handle.config().retry_policy).await
+    }
+
+    /// Send the specified input, retrying transient errors according to the specified policy
+    /// instead of the policy for the session.
+    pub async fn send_keys_with_policy(
+        &self,
+        key: impl Into<TypingData>,
+        policy: &RetryPolicy,
+    ) -> WebDriverResult<()> {
+        let key = key.into();
+        self.handle
+            .capture_on_error(policy.run("send_keys", || async {
+                let command = Command::ElementSendKeys(self.element_id.clone(), key.clone());
+                self.element_cmd("send_keys", command).await?;
+                Ok(())
+            }))
+            .await
     }
 
     /// Take a screenshot of this WebElement and return it as PNG, base64 encoded.
     pub async fn screenshot_as_png_base64(&self) -> WebDriverResult<String> {
-        self.handle.cmd(Command::TakeElementScreenshot(self.element_id.clone())).await?.value()
+        self.element_cmd(
+            "screenshot_as_png_base64",
+            Command::TakeElementScreenshot(self.element_id.clone()),
+        )
+        .await?
+        .value()
     }
 
     /// Take a screenshot of this WebElement and return it as PNG bytes.
@@ -648,6 +817,80 @@ impl WebElement {
         base64_decode(&self.screenshot_as_png_base64().await?)
     }
 
+    /// Take a screenshot of this WebElement by cropping a screenshot of the viewport.
+    ///
+    /// This is a fallback for drivers whose element screenshot returns the wrong region,
+    /// for example for elements inside scroll containers. The element is scrolled into
+    /// view and the visible part of its client rect is cropped from the viewport
+    /// screenshot, scaled by the device pixel ratio.
+    ///
+    /// Returns an `UnableToCaptureScreen` error if the element has zero area or is
+    /// entirely clipped by the viewport or by ancestors that hide their overflow.
+    ///
+    /// Requires the `screenshot-crop` feature.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("my-element-id")).await?;
+    /// let png = elem.screenshot_cropped_png().await?;
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "screenshot-crop")]
+    pub async fn screenshot_cropped_png(&self) -> WebDriverResult<Vec<u8>> {
+        self.scroll_into_view().await?;
+        let rect: VisibleRect =
+            self.handle.execute(ELEMENT_VISIBLE_RECT, vec![self.to_json()?]).await?.convert()?;
+        if rect.element_width <= 0.0 || rect.element_height <= 0.0 {
+            return Err(WebDriverError::UnableToCaptureScreen(WebDriverErrorInfo::new(
+                "element has zero area".to_string(),
+            )));
+        }
+        if rect.width <= 0.0 || rect.height <= 0.0 {
+            return Err(WebDriverError::UnableToCaptureScreen(WebDriverErrorInfo::new(
+                "element is not visible: it is clipped by the viewport or an ancestor".to_string(),
+            )));
+        }
+
+        let png = self.handle.screenshot_as_png().await?;
+        crop_png(&png, &rect)
+    }
+
+    /// Take a screenshot of this WebElement and decode it as an image.
+    ///
+    /// Requires the `image` feature.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("logo")).await?;
+    /// let image = elem.screenshot_image().await?;
+    /// println!("{}x{}", image.width(), image.height());
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "image")]
+    pub async fn screenshot_image(&self) -> WebDriverResult<image::DynamicImage> {
+        support::decode_png(&self.screenshot_as_png().await?)
+    }
+
     /// Take a screenshot of this WebElement and write it to the specified filename.
     pub async fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
         let png = self.screenshot_as_png().await?;
@@ -655,6 +898,71 @@ impl WebElement {
         Ok(())
     }
 
+    /// Take a screenshot of this WebElement and compare it against a golden PNG image.
+    ///
+    /// Returns true if the ratio of different pixels is within `options.max_diff_ratio`.
+    /// Otherwise the diff image is written next to the golden image, with the extension
+    /// `.diff.png`, and false is returned.
+    ///
+    /// Requires the `image` feature. See `support::compare_images()`.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour::prelude::*;
+    /// # use thirtyfour::support::block_on;
+    /// use std::path::Path;
+    /// use thirtyfour::support::ImageCompareOptions;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     block_on(async {
+    /// #         let caps = DesiredCapabilities::chrome();
+    /// #         let driver = WebDriver::new("http://localhost:4444", caps).await?;
+    /// let elem = driver.find(By::Id("logo")).await?;
+    /// let options = ImageCompareOptions::default();
+    /// assert!(elem.matches_golden(Path::new("golden/logo.png"), &options).await?);
+    /// #         driver.quit().await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    #[cfg(feature = "image")]
+    pub async fn matches_golden(
+        &self,
+        path: &Path,
+        options: &support::ImageCompareOptions,
+    ) -> WebDriverResult<bool> {
+        let golden = support::read_file(path).await?;
+        let png = self.screenshot_as_png().await?;
+        let diff = support::compare_images(&golden, &png, options)?;
+        if diff.is_match(options) {
+            return Ok(true);
+        }
+
+        if let Some(diff_png) = diff.diff_png {
+            support::write_file(path.with_extension("diff.png"), diff_png).await?;
+        }
+        Ok(false)
+    }
+
+    /// Take a screenshot of this WebElement and return it as PNG, base64 encoded.
+    /// Alias of screenshot_as_png_base64().
+    pub async fn screenshot_as_base64(&self) -> WebDriverResult<String> {
+        self.screenshot_as_png_base64().await
+    }
+
+    /// Take a screenshot of this WebElement and write it to a new file in the
+    /// specified directory, creating the directory if it does not exist.
+    ///
+    /// See `WebDriver::screenshot_to_dir()`.
+    pub async fn screenshot_to_dir(
+        &self,
+        dir: impl AsRef<Path>,
+        prefix: &str,
+    ) -> WebDriverResult<PathBuf> {
+        let png = self.screenshot_as_png().await?;
+        Ok(support::write_unique_file(dir, prefix, "png", png).await?)
+    }
+
     /// Focus this WebElement using JavaScript.
     ///
     /// # Example:
@@ -754,9 +1062,17 @@ impl WebElement {
     ///
     /// Call this method on the element containing the `#shadowRoot` node.
     /// You can then use the returned `WebElement` to query elements within the shadowRoot node.
+    ///
+    /// Returns a `NoSuchShadowRoot` error if the element has no shadow root, or if its
+    /// shadow root is closed, since closed shadow roots are not accessible from scripts.
     pub async fn get_shadow_root(&self) -> WebDriverResult<WebElement> {
         let ret =
             self.handle.execute("return arguments[0].shadowRoot", vec![self.to_json()?]).await?;
+        if ret.json().is_null() {
+            return Err(WebDriverError::NoSuchShadowRoot(WebDriverErrorInfo::new(
+                "no such shadow root: the element has no shadow root, or it is closed".to_string(),
+            )));
+        }
         ret.element()
     }
 
@@ -782,7 +1098,8 @@ impl WebElement {
     /// # }
     /// ```
     pub async fn enter_frame(self) -> WebDriverResult<()> {
-        self.handle.cmd(Command::SwitchToFrameElement(self.element_id.clone())).await?;
+        self.element_cmd("enter_frame", Command::SwitchToFrameElement(self.element_id.clone()))
+            .await?;
         Ok(())
     }
 
@@ -849,3 +1166,73 @@ impl Serialize for WebElement {
         self.element_id.serialize(serializer)
     }
 }
+
+/// The visible part of an element's client rect, in CSS pixels.
+#[cfg(feature = "screenshot-crop")]
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VisibleRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    element_width: f64,
+    element_height: f64,
+    device_pixel_ratio: f64,
+}
+
+/// Crop the specified rect out of a PNG screenshot of the viewport.
+#[cfg(feature = "screenshot-crop")]
+fn crop_png(png: &[u8], rect: &VisibleRect) -> WebDriverResult<Vec<u8>> {
+    use support::ImageExt;
+
+    let rect_css = ElementRect {
+        x: rect.x,
+        y: rect.y,
+        width: rect.width,
+        height: rect.height,
+    };
+    let cropped = support::decode_png(png)?.crop_to_rect(&rect_css, rect.device_pixel_ratio);
+    if cropped.width() == 0 || cropped.height() == 0 {
+        return Err(WebDriverError::UnableToCaptureScreen(WebDriverErrorInfo::new(
+            "element is outside the screenshot".to_string(),
+        )));
+    }
+    cropped.to_png_bytes()
+}
+
+#[cfg(all(test, feature = "screenshot-crop"))]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, ImageFormat, RgbaImage};
+
+    fn rect(x: f64, y: f64, width: f64, height: f64, device_pixel_ratio: f64) -> VisibleRect {
+        VisibleRect {
+            x,
+            y,
+            width,
+            height,
+            element_width: width,
+            element_height: height,
+            device_pixel_ratio,
+        }
+    }
+
+    #[test]
+    fn test_crop_png() {
+        let mut png = std::io::Cursor::new(Vec::new());
+        RgbaImage::new(200, 100).write_to(&mut png, ImageFormat::Png).unwrap();
+        let png = png.into_inner();
+
+        let cropped = crop_png(&png, &rect(10.0, 5.0, 20.0, 10.0, 2.0)).unwrap();
+        let cropped = image::load_from_memory(&cropped).unwrap();
+        assert_eq!(cropped.dimensions(), (40, 20));
+
+        // The crop is clamped to the screenshot.
+        let cropped = crop_png(&png, &rect(90.0, 40.0, 50.0, 50.0, 2.0)).unwrap();
+        let cropped = image::load_from_memory(&cropped).unwrap();
+        assert_eq!(cropped.dimensions(), (20, 20));
+
+        assert!(crop_png(&png, &rect(150.0, 0.0, 10.0, 10.0, 2.0)).is_err());
+    }
+}
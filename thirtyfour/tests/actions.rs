@@ -3,8 +3,12 @@
 use crate::common::*;
 use assert_matches::assert_matches;
 use rstest::rstest;
+use std::time::{Duration, Instant};
+use thirtyfour::action_chain::{ActionChain, ActionRecording, SwipeDirection, TypingSpeed};
+use thirtyfour::common::action::{MouseButton, PenProperties, PointerActionType};
 use thirtyfour::error::WebDriverErrorInner;
-use thirtyfour::{prelude::*, support::block_on};
+use thirtyfour::extensions::cdp::{ChromeDevTools, Device};
+use thirtyfour::{prelude::*, support::block_on, PermissionName, PermissionState};
 
 mod common;
 
@@ -46,6 +50,43 @@ fn actions_mouse(test_harness: TestHarness) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn actions_mouse_buttons(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+        // Cancelling mouseup for the back and forward buttons stops them navigating.
+        c.execute(
+            "window.buttons = []; \
+             document.addEventListener('mouseup', (e) => { \
+                 window.buttons.push(e.button); \
+                 if (e.button > 2) e.preventDefault(); \
+             });",
+            Vec::new(),
+        )
+        .await?;
+
+        // Middle-clicking a link opens it in a new tab, and leaves this one alone.
+        let link = c.find(By::Id("other_page_id")).await?;
+        c.action_chain().middle_click_element(&link).perform().await?;
+        let windows = c.windows().await?;
+        assert_eq!(windows.len(), 2);
+        assert_eq!(c.current_url().await?.as_str(), sample_url);
+
+        let elem = c.find(By::Id("footer")).await?;
+        c.action_chain()
+            .move_to_element_center(&elem)
+            .click_button(MouseButton::Back)
+            .click_button(MouseButton::Forward)
+            .perform()
+            .await?;
+        let buttons: Vec<i64> = c.execute("return window.buttons;", Vec::new()).await?.convert()?;
+        assert_eq!(buttons, [1, 3, 4]);
+        Ok(())
+    })
+}
+
 #[rstest]
 fn actions_mouse_move(test_harness: TestHarness) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -82,6 +123,101 @@ fn actions_mouse_move(test_harness: TestHarness) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn actions_key_chord(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+
+        let elem = c.find(By::Id("text-input")).await?;
+        elem.send_keys("abc").await?;
+        c.execute(
+            "window.keys = []; \
+             const log = (e) => window.keys.push(`${e.type === 'keydown' ? '+' : '-'}${e.key}`); \
+             arguments[0].addEventListener('keydown', log); \
+             arguments[0].addEventListener('keyup', log);",
+            vec![elem.to_json()?],
+        )
+        .await?;
+
+        // Select all and replace it, then type a capital letter.
+        c.action_chain()
+            .key_chord([Key::Control], 'a')
+            .send_keys("x")
+            .key_chord([Key::Shift], 'y')
+            .perform()
+            .await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), "xY");
+
+        // Modifiers are released in reverse order.
+        c.execute("window.keys = [];", Vec::new()).await?;
+        c.action_chain().key_chord([Key::Control, Key::Alt], 'z').perform().await?;
+        let keys: Vec<String> = c.execute("return window.keys;", Vec::new()).await?.convert()?;
+        assert_eq!(keys, ["+Control", "+Alt", "+z", "-z", "-Alt", "-Control"]);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_click_with_modifier(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&select_url()).await?;
+
+        let options = c.find_all(By::Css("#toppings option")).await?;
+        c.action_chain()
+            .click_element(&options[0])
+            .move_to_element_center(&options[2])
+            .click_with_modifier(Key::Control)
+            .perform()
+            .await?;
+        let selected: Vec<String> = c
+            .execute(
+                "return [...document.getElementById('toppings').selectedOptions].map(x => x.value);",
+                Vec::new(),
+            )
+            .await?
+            .convert()?;
+        assert_eq!(selected, ["cheese", "olives"]);
+
+        // Without the modifier, a click replaces the selection.
+        c.action_chain().click_element(&options[1]).perform().await?;
+        let selected: Vec<String> = c
+            .execute(
+                "return [...document.getElementById('toppings').selectedOptions].map(x => x.value);",
+                Vec::new(),
+            )
+            .await?
+            .convert()?;
+        assert_eq!(selected, ["ham"]);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_send_keys_with_delay(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+
+        let elem = c.find(By::Id("text-input")).await?;
+        let started = Instant::now();
+        c.action_chain()
+            .click_element(&elem)
+            .send_keys_with_delay("abcde", Duration::from_millis(100))
+            .perform()
+            .await?;
+        assert!(started.elapsed() >= Duration::from_millis(400));
+        assert_eq!(elem.prop("value").await?.unwrap(), "abcde");
+
+        c.action_chain().send_keys_with_delay("fg", TypingSpeed::Fast).perform().await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), "abcdefg");
+        Ok(())
+    })
+}
+
 #[rstest]
 fn actions_release(test_harness: TestHarness) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -116,6 +252,33 @@ fn actions_release(test_harness: TestHarness) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn actions_pause(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+
+        let elem = c.find(By::Id("text-input")).await?;
+        elem.click().await?;
+
+        // Shift stays held down during the pause.
+        let started = Instant::now();
+        c.action_chain()
+            .key_down(Key::Shift)
+            .pause(Duration::from_millis(500))
+            .key_down('a')
+            .key_up('a')
+            .pause(Duration::ZERO)
+            .key_up(Key::Shift)
+            .perform()
+            .await?;
+        assert!(started.elapsed() >= Duration::from_millis(500));
+        assert_eq!(elem.prop("value").await?.unwrap(), "A");
+        Ok(())
+    })
+}
+
 #[rstest]
 fn actions_drag_and_drop(test_harness: TestHarness) -> WebDriverResult<()> {
     let browser = test_harness.browser().to_string();
@@ -147,3 +310,865 @@ fn actions_drag_and_drop(test_harness: TestHarness) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+#[rstest]
+fn actions_wheel(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.set_window_rect(0, 0, 800, 800).await?;
+        c.goto(&scroll_url()).await?;
+
+        // Scroll the page.
+        c.action_chain().scroll_by(0, 300).perform().await?;
+        let scroll_y: i64 = c.execute("return window.scrollY;", Vec::new()).await?.convert()?;
+        assert_eq!(scroll_y, 300);
+        c.execute("window.scrollTo(0, 0);", Vec::new()).await?;
+
+        // Scroll the feed, which loads more items on wheel events.
+        let feed = c.find(By::Id("feed")).await?;
+        assert_eq!(feed.find_all(By::ClassName("item")).await?.len(), 5);
+        c.action_chain()
+            .move_to_element_center(&feed)
+            .scroll_from_element(&feed, 0, 200)
+            .scroll_from_element(&feed, 0, 200)
+            .perform()
+            .await?;
+        assert_eq!(feed.find_all(By::ClassName("item")).await?.len(), 15);
+        let feed_top: i64 =
+            c.execute("return arguments[0].scrollTop;", vec![feed.to_json()?]).await?.convert()?;
+        assert_eq!(feed_top, 400);
+        let page_y: i64 = c.execute("return window.scrollY;", Vec::new()).await?.convert()?;
+        assert_eq!(page_y, 0);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_pen(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&canvas_url()).await?;
+
+        // Draw a diagonal line across the canvas, which is at the top left of the page.
+        let pen = |pressure| PenProperties {
+            pressure: Some(pressure),
+            tilt_x: Some(20),
+            tilt_y: Some(-10),
+            twist: Some(45),
+            ..Default::default()
+        };
+        c.action_chain_with_pointer_type(PointerActionType::Pen)
+            .move_to(50, 50)
+            .pen_down_with_pressure(0.3)
+            .move_to_with_pen_properties(150, 150, pen(0.6))
+            .move_to_with_pen_properties(250, 250, pen(0.9))
+            .release()
+            .perform()
+            .await?;
+
+        // The stroke passes through the middle of the canvas.
+        let alpha: i64 = c
+            .execute(
+                "return document.getElementById('canvas').getContext('2d')\
+                    .getImageData(200, 200, 1, 1).data[3];",
+                Vec::new(),
+            )
+            .await?
+            .convert()?;
+        assert!(alpha > 0, "no stroke at (200, 200)");
+        let blank: i64 = c
+            .execute(
+                "return document.getElementById('canvas').getContext('2d')\
+                    .getImageData(250, 50, 1, 1).data[3];",
+                Vec::new(),
+            )
+            .await?
+            .convert()?;
+        assert_eq!(blank, 0);
+
+        // The pen properties reached the page.
+        let events = c.execute("return window.penEvents;", Vec::new()).await?;
+        let events = events.json().as_array().unwrap();
+        assert!(events.iter().all(|x| x["pointerType"] == "pen"));
+        let down = events.iter().find(|x| x["type"] == "pointerdown").unwrap();
+        assert!((down["pressure"].as_f64().unwrap() - 0.3).abs() < 0.01);
+        let moved = events.iter().rfind(|x| x["type"] == "pointermove").unwrap();
+        assert!((moved["pressure"].as_f64().unwrap() - 0.9).abs() < 0.01);
+        assert_eq!((moved["tiltX"].as_i64(), moved["tiltY"].as_i64()), (Some(20), Some(-10)));
+        assert_eq!(moved["twist"].as_i64(), Some(45));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_multi_touch(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&touch_url()).await?;
+
+        // Pinch out with two fingers, and type with the keyboard in between.
+        let mut chain = c.action_chain();
+        let finger1 = chain.add_pointer_source("finger1", PointerActionType::Touch);
+        let finger2 = chain.add_pointer_source("finger2", PointerActionType::Touch);
+        chain
+            .touch_down_at(finger1, 180, 180)
+            .key_down(Key::Shift)
+            .key_up(Key::Shift)
+            .touch_down_at(finger2, 220, 220)
+            .touch_move_together(&[(finger1, 100, 100), (finger2, 300, 300)])
+            .touch_move_to(finger2, 350, 350)
+            .touch_up(finger1)
+            .touch_up(finger2)
+            .perform()
+            .await?;
+
+        let state = c.execute("return window.touchState;", Vec::new()).await?;
+        let state = state.json();
+        assert_eq!(state["maxTouches"].as_i64(), Some(2));
+        let start = state["startDistance"].as_f64().unwrap();
+        let end = state["endDistance"].as_f64().unwrap();
+        assert!((start - 56.6).abs() < 1.0, "start distance {start}");
+        assert!((end - 353.6).abs() < 1.0, "end distance {end}");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_scroll_and_click(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.set_window_rect(0, 0, 800, 800).await?;
+        c.goto(&scroll_url()).await?;
+
+        // The button is below the fold, at the bottom of a scrollable panel.
+        let button = c.find(By::Id("deep-button")).await?;
+        let in_view = r#"
+            const rect = arguments[0].getBoundingClientRect();
+            const panel = document.getElementById("panel").getBoundingClientRect();
+            return rect.top >= panel.top && rect.bottom <= panel.bottom
+                && rect.bottom <= window.innerHeight;
+        "#;
+        let visible: bool = c.execute(in_view, vec![button.to_json()?]).await?.convert()?;
+        assert!(!visible);
+
+        c.action_chain().scroll_to_element(&button).perform().await?;
+        let visible: bool = c.execute(in_view, vec![button.to_json()?]).await?.convert()?;
+        assert!(visible);
+
+        c.execute("window.scrollTo(0, 0);", Vec::new()).await?;
+        c.action_chain().scroll_and_click_element(&button).perform().await?;
+        assert_eq!(button.text().await?, "Clicked");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_drag_and_drop_html5(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&dnd_url()).await?;
+
+        let card = c.find(By::Id("card")).await?;
+        let done = c.find(By::Id("done")).await?;
+        c.action_chain().drag_and_drop_element_html5(&card, &done).await?;
+        assert_eq!(card.parent().await?.id().await?.as_deref(), Some("done"));
+
+        // The target must accept the drop.
+        let archive = c.find(By::Id("archive")).await?;
+        let err = c.action_chain().drag_and_drop_element_html5(&card, &archive).await.unwrap_err();
+        assert_matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(..));
+        assert!(err.to_string().contains("did not accept the drop"), "{err}");
+
+        // The source must be draggable.
+        let fixed = c.find(By::Id("fixed")).await?;
+        let err = c.action_chain().drag_and_drop_element_html5(&fixed, &done).await.unwrap_err();
+        assert!(err.to_string().contains("not draggable"), "{err}");
+        assert_eq!(fixed.parent().await?.id().await?.as_deref(), Some("todo"));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_to_json(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let chain = c
+            .action_chain_with_delay(None, Some(Duration::from_millis(100)))
+            .move_to(10, 20)
+            .key_down('a');
+        let payload = chain.to_json();
+        let sources = payload["actions"].as_array().unwrap();
+        let ids: Vec<_> = sources.iter().map(|x| x["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["key", "pointer"]);
+        assert_eq!(
+            sources[1]["actions"][0],
+            serde_json::json!({
+                "type": "pointerMove",
+                "duration": 100,
+                "origin": "viewport",
+                "x": 10,
+                "y": 20
+            })
+        );
+        assert_eq!(sources[0]["actions"][1], serde_json::json!({"type": "keyDown", "value": "a"}));
+        assert!(sources.iter().all(|x| x["actions"].as_array().unwrap().len() == 2));
+
+        let mut chain = chain;
+        chain.add_pointer_source("finger", PointerActionType::Touch);
+        let text = chain.to_string();
+        assert!(text.contains("\"id\": \"finger\""), "{text}");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&text)?, chain.to_json());
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_reuse_chain(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&touch_url()).await?;
+        c.execute(
+            "window.swipes = 0; \
+             document.getElementById('surface').addEventListener('pointerup', () => window.swipes++);",
+            Vec::new(),
+        )
+        .await?;
+
+        // Replay a prepared swipe.
+        let mut chain = c.action_chain();
+        let finger = chain.add_pointer_source("finger", PointerActionType::Touch);
+        let swipe =
+            chain.touch_down_at(finger, 300, 200).touch_move_to(finger, 50, 200).touch_up(finger);
+        for _ in 0..3 {
+            swipe.clone().perform().await?;
+        }
+        let swipes: i64 = c.execute("return window.swipes;", Vec::new()).await?.convert()?;
+        assert_eq!(swipes, 3);
+
+        // After clearing, the chain is empty and its sources are still aligned.
+        let mut chain = swipe;
+        chain.perform_and_clear().await?;
+        let payload = chain.to_json();
+        assert!(payload["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|x| x["actions"] == serde_json::json!([])));
+        let mut chain = chain.key_down('a').touch_down_at(finger, 100, 100).touch_up(finger);
+        let payload = chain.to_json();
+        let lengths: Vec<_> = payload["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["actions"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(lengths, [4, 4, 4, 4]);
+        assert_eq!(payload["actions"][3]["actions"][1]["type"], "pointerMove");
+        chain.perform_and_clear().await?;
+        let swipes: i64 = c.execute("return window.swipes;", Vec::new()).await?.convert()?;
+        assert_eq!(swipes, 5);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_long_press(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&long_press_url()).await?;
+        let item = c.find(By::Id("item")).await?;
+        let menu = c.find(By::Id("menu")).await?;
+
+        // A short hold does not open the menu.
+        let chain = c.action_chain().long_press_element(&item, Duration::from_millis(100));
+        let lengths: Vec<_> = chain.to_json()["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["actions"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(lengths, [4, 4, 4]);
+        chain.perform().await?;
+        assert!(!menu.is_displayed().await?);
+
+        c.action_chain().long_press_element(&item, Duration::from_secs(1)).perform().await?;
+        assert!(menu.is_displayed().await?);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_move_smooth(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&canvas_url()).await?;
+        c.execute(
+            "window.moves = []; document.getElementById('canvas').addEventListener(\
+                'pointermove', (e) => window.moves.push([e.clientX, e.clientY]));",
+            Vec::new(),
+        )
+        .await?;
+
+        let chain = c.action_chain().move_to(10, 10).move_to_smooth(110, 60, 5, Duration::ZERO);
+        let lengths: Vec<_> = chain.to_json()["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["actions"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(lengths, [6, 6, 6]);
+        chain.perform().await?;
+        let moves: Vec<(i64, i64)> =
+            c.execute("return window.moves;", Vec::new()).await?.convert()?;
+        assert!(moves.len() >= 5, "expected at least 5 moves, got {moves:?}");
+        assert_eq!(moves.last(), Some(&(110, 60)));
+
+        // Moving to the center of the canvas from the last position.
+        let canvas = c.find(By::Id("canvas")).await?;
+        c.action_chain()
+            .move_to(10, 10)
+            .move_to_element_smooth(&canvas, 3, Duration::from_millis(60))
+            .await?
+            .perform()
+            .await?;
+        let moves: Vec<(i64, i64)> =
+            c.execute("return window.moves;", Vec::new()).await?.convert()?;
+        assert_eq!(moves.last(), Some(&(150, 150)));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_swipe(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&swipe_url()).await?;
+        let content = c.find(By::Id("row-content")).await?;
+        let delete = c.find(By::Id("delete")).await?;
+
+        // A short swipe does not open the row.
+        c.action_chain_with_pointer_type(PointerActionType::Touch)
+            .swipe(200, 30, 160, 30, Duration::from_millis(100))
+            .perform()
+            .await?;
+        assert!(!delete.is_displayed().await?);
+
+        c.action_chain_with_pointer_type(PointerActionType::Touch)
+            .swipe_element(&content, SwipeDirection::Left, 150, Duration::from_millis(200))
+            .await?
+            .perform()
+            .await?;
+        assert!(delete.is_displayed().await?);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_then(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let elem = c.find(By::Id("text-input")).await?;
+
+        let type_text = c.action_chain().click_element(&elem).send_keys("abc");
+        let replace_all = c.action_chain().key_chord([Key::Control], 'a').send_keys("x");
+        type_text.then(replace_all).perform().await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), "x");
+
+        // Additional pointers with the same name are joined, and padded to stay aligned.
+        let mut first = c.action_chain_with_pointer_type(PointerActionType::Touch);
+        let finger = first.add_pointer_source("finger2", PointerActionType::Touch);
+        let first = first.move_to(10, 10).touch_down_at(finger, 20, 20);
+        let mut second = c.action_chain_with_pointer_type(PointerActionType::Touch);
+        let finger = second.add_pointer_source("finger2", PointerActionType::Touch);
+        let second = second.pause(Duration::ZERO).touch_up(finger);
+        let json = first.then(second).to_json();
+        let sources = json["actions"].as_array().unwrap();
+        let summary: Vec<_> = sources
+            .iter()
+            .map(|x| (x["id"].as_str().unwrap(), x["actions"].as_array().unwrap().len()))
+            .collect();
+        assert_eq!(summary, [("key", 5), ("pointer", 5), ("finger2", 5)]);
+        let types: Vec<_> = sources[2]["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["pause", "pointerMove", "pointerDown", "pause", "pointerUp"]);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_release_all(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let elem = c.find(By::Id("text-input")).await?;
+        c.execute(
+            "window.keys = []; \
+             const log = (e) => window.keys.push(`${e.type === 'keydown' ? '+' : '-'}${e.key}`); \
+             arguments[0].addEventListener('keydown', log); \
+             arguments[0].addEventListener('keyup', log);",
+            vec![elem.to_json()?],
+        )
+        .await?;
+
+        let chain = c
+            .action_chain()
+            .click_element(&elem)
+            .key_down(Key::Control)
+            .key_down(Key::Shift)
+            .click_and_hold()
+            .release_all();
+        let json = chain.to_json();
+        let key_ups: Vec<_> = json["actions"][0]["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|x| x["type"] == "keyUp")
+            .map(|x| x["value"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            key_ups,
+            [char::from(Key::Shift).to_string(), char::from(Key::Control).to_string()]
+        );
+        let pointer: Vec<_> = json["actions"][1]["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["type"].as_str().unwrap())
+            .filter(|x| *x != "pause")
+            .collect();
+        assert_eq!(pointer.last(), Some(&"pointerUp"));
+
+        chain.perform().await?;
+        let keys: Vec<String> = c.execute("return window.keys;", Vec::new()).await?.convert()?;
+        assert_eq!(keys, ["+Control", "+Shift", "-Shift", "-Control"]);
+
+        // Nothing is left held down, so typing is not affected.
+        elem.clear().await?;
+        c.action_chain().send_keys("ab").perform().await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), "ab");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_triple_click(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&paragraphs_url()).await?;
+        let first = c.find(By::Id("first")).await?;
+
+        // Triple-clicking selects the whole paragraph, and nothing else.
+        c.action_chain().triple_click_element(&first).perform().await?;
+        let selected: String =
+            c.execute("return window.getSelection().toString();", Vec::new()).await?.convert()?;
+        assert_eq!(selected.trim(), "The quick brown fox jumps over the lazy dog.");
+        let clicks: Vec<i64> = c.execute("return window.clicks;", Vec::new()).await?.convert()?;
+        assert_eq!(clicks, [1, 2, 3]);
+
+        // The clicks are not separated by the pointer delay.
+        let chain = c.action_chain_with_delay(None, Some(Duration::from_secs(1))).click_n_times(2);
+        let json = chain.to_json();
+        let pointer = json["actions"][1]["actions"].as_array().unwrap();
+        assert_eq!(pointer.len(), 4);
+        assert!(pointer.iter().all(|x| x["duration"] == 0));
+        assert_eq!(json["actions"][0]["actions"].as_array().unwrap().len(), 4);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_replay_recording(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let elem = c.find(By::Id("text-input")).await?;
+        let recording = c.action_chain().click_element(&elem).send_keys("abc").to_recording();
+        let json = serde_json::to_string(&recording)?;
+
+        // The element ids are stale after a reload, so find the element again.
+        c.refresh().await?;
+        let recording: ActionRecording = serde_json::from_str(&json)?;
+        assert_eq!(recording.element_ids(), [elem.element_id()]);
+        let recording = recording.resolve_elements(|_| c.find(By::Id("text-input"))).await?;
+        ActionChain::from_recording(c.handle.clone(), recording).perform().await?;
+        let elem = c.find(By::Id("text-input")).await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), "abc");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_with_next_delay(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        c.execute(
+            "window.events = []; \
+             const log = (e) => window.events.push([e.type, performance.now()]); \
+             document.addEventListener('mousedown', log); \
+             document.addEventListener('mouseup', log);",
+            Vec::new(),
+        )
+        .await?;
+        let elem = c.find(By::Id("footer")).await?;
+
+        let chain = c
+            .action_chain_with_delay(None, Some(Duration::ZERO))
+            .click_and_hold_element(&elem)
+            .with_next_delay(Duration::from_millis(300))
+            .move_by_offset(5, 0)
+            .release();
+        let pointer: Vec<_> = chain.to_json()["actions"][1]["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| (x["type"].as_str().unwrap().to_string(), x["duration"].as_u64()))
+            .collect();
+        assert_eq!(pointer[2], ("pause".to_string(), Some(300)));
+        assert_eq!(pointer[3], ("pointerMove".to_string(), Some(0)));
+        chain.perform().await?;
+
+        let events: Vec<(String, f64)> =
+            c.execute("return window.events;", Vec::new()).await?.convert()?;
+        assert_eq!(events.len(), 2);
+        assert!(events[1].1 - events[0].1 >= 300.0, "{events:?}");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_paste_text(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(permissions_url()).await?;
+        c.set_permission(PermissionName::ClipboardWrite, PermissionState::Granted).await?;
+        let elem = c.find(By::Id("text")).await?;
+        let text = "lorem ipsum ".repeat(500);
+        c.action_chain().click_element(&elem).paste_text(&text).perform().await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), text);
+
+        // Each text is written to the clipboard just before it is pasted.
+        elem.clear().await?;
+        c.action_chain().click_element(&elem).paste_text("a").paste_text("b").perform().await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), "ab");
+
+        // Without access to the clipboard, the text is typed instead.
+        c.set_permission(PermissionName::ClipboardWrite, PermissionState::Denied).await?;
+        elem.clear().await?;
+        c.action_chain().click_element(&elem).paste_text("typed").perform().await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), "typed");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_click_at(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&canvas_url()).await?;
+        // Cancelling contextmenu stops the menu from opening.
+        c.execute(
+            "window.clicks = []; \
+             const log = (e) => { window.clicks.push([e.type, e.clientX, e.clientY]); \
+                                  if (e.type === 'contextmenu') e.preventDefault(); }; \
+             ['click', 'dblclick', 'contextmenu'].forEach((x) => document.addEventListener(x, log));",
+            Vec::new(),
+        )
+        .await?;
+
+        let chain = c.action_chain().click_at(10, 20);
+        let lengths: Vec<_> = chain.to_json()["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["actions"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(lengths, [3, 3, 3]);
+        chain.context_click_at(30, 40).double_click_at(50, 60).perform().await?;
+
+        let clicks: Vec<(String, i64, i64)> =
+            c.execute("return window.clicks;", Vec::new()).await?.convert()?;
+        let expected = [
+            ("click", 10, 20),
+            ("contextmenu", 30, 40),
+            ("click", 50, 60),
+            ("click", 50, 60),
+            ("dblclick", 50, 60),
+        ];
+        let expected: Vec<_> = expected.map(|(kind, x, y)| (kind.to_string(), x, y)).into();
+        assert_eq!(clicks, expected);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_click_element_with_offset(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&canvas_url()).await?;
+        // Record where each click landed within the 300x300 canvas.
+        c.execute(
+            "window.clicks = []; \
+             const canvas = document.getElementById('canvas'); \
+             const log = (e) => { window.clicks.push([e.type, e.offsetX, e.offsetY]); \
+                                  if (e.type === 'contextmenu') e.preventDefault(); }; \
+             ['click', 'dblclick', 'contextmenu'].forEach((x) => canvas.addEventListener(x, log));",
+            Vec::new(),
+        )
+        .await?;
+        let canvas = c.find(By::Id("canvas")).await?;
+
+        // Offsets are from the center of the canvas, at (150, 150).
+        c.action_chain()
+            .click_element_with_offset(&canvas, -100, -50)
+            .context_click_element_with_offset(&canvas, 20, 0)
+            .double_click_element_with_offset(&canvas, 0, 100)
+            .perform()
+            .await?;
+
+        let clicks: Vec<(String, i64, i64)> =
+            c.execute("return window.clicks;", Vec::new()).await?.convert()?;
+        let expected = [
+            ("click", 50, 100),
+            ("contextmenu", 170, 150),
+            ("click", 150, 250),
+            ("click", 150, 250),
+            ("dblclick", 150, 250),
+        ];
+        let expected: Vec<_> = expected.map(|(kind, x, y)| (kind.to_string(), x, y)).into();
+        assert_eq!(clicks, expected);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_with_key_held(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&multi_select_url()).await?;
+        let item1 = c.find(By::Id("item1")).await?;
+        let item3 = c.find(By::Id("item3")).await?;
+        let selected = || async {
+            let items = c.find_all(By::Css(".item.selected")).await?;
+            let mut ids = Vec::new();
+            for item in items {
+                ids.push(item.id().await?.unwrap_or_default());
+            }
+            WebDriverResult::Ok(ids)
+        };
+
+        // Without Shift, dragging only selects the first item.
+        c.action_chain().drag_and_drop_element(&item1, &item3).perform().await?;
+        assert_eq!(selected().await?, ["item1"]);
+
+        c.action_chain()
+            .with_key_held(Key::Shift, |chain| chain.drag_and_drop_element(&item1, &item3))
+            .perform()
+            .await?;
+        assert_eq!(selected().await?, ["item1", "item2", "item3"]);
+
+        // Nested keys are released in reverse order, even if the inner actions hold a button.
+        let chain = c.action_chain().with_key_held(Key::Control, |chain| {
+            chain.with_key_held(Key::Shift, |chain| chain.click_and_hold())
+        });
+        let json = chain.to_json();
+        let keys: Vec<_> = json["actions"][0]["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|x| x["type"] != "pause")
+            .map(|x| (x["type"].as_str().unwrap(), x["value"].as_str().unwrap()))
+            .collect();
+        let (control, shift) =
+            (char::from(Key::Control).to_string(), char::from(Key::Shift).to_string());
+        assert_eq!(
+            keys,
+            [
+                ("keyDown", control.as_str()),
+                ("keyDown", shift.as_str()),
+                ("keyUp", shift.as_str()),
+                ("keyUp", control.as_str())
+            ]
+        );
+        chain.release().perform().await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_scroll_within_element(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.set_window_rect(0, 0, 800, 800).await?;
+        c.goto(&nested_scroll_url()).await?;
+        let outer = c.find(By::Id("outer")).await?;
+        let rows = c.find(By::Id("rows")).await?;
+        let scroll_top = |elem: WebElement| async move {
+            let top: i64 = c
+                .execute("return arguments[0].scrollTop;", vec![elem.to_json()?])
+                .await?
+                .convert()?;
+            WebDriverResult::Ok(top)
+        };
+
+        // The center of the outer container is over its header, so offset into the rows.
+        c.action_chain().scroll_within_element_with_offset(&outer, 0, 100, 0, 50).perform().await?;
+        assert_eq!(scroll_top(rows.clone()).await?, 50);
+        assert_eq!(scroll_top(outer.clone()).await?, 0);
+
+        c.action_chain().scroll_within_element(&rows, 0, 25).perform().await?;
+        assert_eq!(scroll_top(rows.clone()).await?, 75);
+        assert_eq!(scroll_top(outer).await?, 0);
+        let page_y: i64 = c.execute("return window.scrollY;", Vec::new()).await?.convert()?;
+        assert_eq!(page_y, 0);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_perform_timed(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let elem = c.find(By::Id("text-input")).await?;
+        let chain = c.action_chain().click_element(&elem).send_keys("ab");
+        let telemetry = chain.perform_timed().await?;
+        assert_eq!(elem.prop("value").await?.unwrap(), "ab");
+
+        // Move, click, then a key down and up for each character.
+        assert_eq!(telemetry.ticks, 7);
+        assert_eq!(telemetry.source_actions, [("key".to_string(), 7), ("pointer".to_string(), 3)]);
+        assert_eq!(telemetry.payload_size, chain.to_json().to_string().len());
+        assert!(telemetry.duration > Duration::ZERO);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_key_down_repeating(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&listbox_url()).await?;
+        let listbox = c.find(By::Id("listbox")).await?;
+        c.action_chain()
+            .click_element(&listbox)
+            .key_down_repeating(Key::Down, 20, Duration::from_millis(30))
+            .key_up(Key::Down)
+            .perform()
+            .await?;
+        let highlighted = c.find(By::ClassName("highlighted")).await?;
+        assert_eq!(highlighted.id().await?.unwrap(), "option21");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_pinch(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        dev_tools.emulate_device(&Device::pixel_7()).await?;
+        c.goto(&map_url()).await?;
+        let map = c.find(By::Id("map")).await?;
+        let zoom = || async {
+            let zoom: f64 = c.execute("return window.mapZoom;", Vec::new()).await?.convert()?;
+            WebDriverResult::Ok(zoom)
+        };
+
+        // The fingers start 75px apart and end 150px apart, either side of the center.
+        c.action_chain()
+            .zoom_element(&map, 2.0, Duration::from_millis(300))
+            .await?
+            .perform()
+            .await?;
+        let zoomed = zoom().await?;
+        assert!((zoomed - 2.0).abs() < 0.05, "zoom {zoomed}");
+
+        c.action_chain().pinch(150, 150, 150, 50, Duration::from_millis(300)).perform().await?;
+        let zoomed = zoom().await?;
+        assert!((zoomed - 2.0 / 3.0).abs() < 0.05, "zoom {zoomed}");
+
+        let err =
+            c.action_chain().zoom_element(&map, 0.0, Duration::from_millis(300)).await.unwrap_err();
+        assert_matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_));
+        dev_tools.clear_device_emulation().await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_named_devices(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&canvas_url()).await?;
+        let events = || async {
+            let events: Vec<String> = c
+                .execute("return window.penEvents.map((e) => e.type);", Vec::new())
+                .await?
+                .convert()?;
+            WebDriverResult::Ok(events)
+        };
+
+        let chain = c.action_chain_named("keys-a", "mouse-a").move_to(50, 50).click_and_hold();
+        let ids: Vec<_> = chain.to_json()["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["id"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(ids, ["keys-a", "mouse-a"]);
+        chain.perform().await?;
+        assert_eq!(events().await?.last().unwrap(), "pointerdown");
+
+        // A separate chain with the same device name releases the button that is still held.
+        c.action_chain_named("keys-a", "mouse-a").release().perform().await?;
+        assert_eq!(events().await?.last().unwrap(), "pointerup");
+        c.action_chain().reset_actions().await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn actions_focus_element_and_send_keys(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&autocomplete_url()).await?;
+        let search = c.find(By::Id("search")).await?;
+        let clicks = || async {
+            let clicks: i64 =
+                c.execute("return window.searchClicks;", Vec::new()).await?.convert()?;
+            WebDriverResult::Ok(clicks)
+        };
+
+        let chain = c.action_chain().focus_element_and_send_keys(&search, "abc");
+        let pointer = &chain.to_json()["actions"][1]["actions"];
+        assert!(pointer.as_array().unwrap().iter().all(|x| x["type"] == "pause"));
+        // The element is only focused when the chain is performed.
+        let focused = "return document.activeElement === arguments[0];";
+        let is_focused =
+            || async { c.execute(focused, vec![search.to_json()?]).await?.convert::<bool>() };
+        assert!(!is_focused().await?);
+        chain.perform().await?;
+        assert!(is_focused().await?);
+        assert_eq!(search.prop("value").await?.unwrap(), "abc");
+        assert_eq!(clicks().await?, 0);
+
+        // Clicking first clears the field.
+        c.action_chain().send_keys_to_element(&search, "d").perform().await?;
+        assert_eq!(search.prop("value").await?.unwrap(), "d");
+        assert_eq!(clicks().await?, 1);
+        Ok(())
+    })
+}
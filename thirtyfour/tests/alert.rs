@@ -3,8 +3,12 @@
 use crate::common::*;
 use assert_matches::assert_matches;
 use rstest::rstest;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use thirtyfour::common::config::WebDriverConfig;
 use thirtyfour::error::WebDriverErrorInner;
-use thirtyfour::{prelude::*, support::block_on};
+use thirtyfour::{prelude::*, support::block_on, AlertDecision, PromptKind};
 
 mod common;
 
@@ -82,3 +86,149 @@ fn alert_text(test_harness: TestHarness) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+#[rstest]
+fn alert_wait(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+        assert!(!c.has_alert().await?);
+
+        c.execute(r#"setTimeout(() => alert("Delayed alert"), 1000);"#, Vec::new()).await?;
+        c.wait_for_alert(Duration::from_secs(5)).await?;
+        assert!(c.has_alert().await?);
+        assert_eq!(c.get_alert_text().await?, "Delayed alert");
+        c.accept_alert().await?;
+        assert!(!c.has_alert().await?);
+
+        assert_matches!(
+            c.wait_for_alert(Duration::from_millis(500)).await.map_err(WebDriverError::into_inner),
+            Err(WebDriverErrorInner::Timeout(..))
+        );
+
+        Ok(())
+    })
+}
+
+#[rstest]
+fn alert_prompt_kind(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+
+        c.find(By::Id("button-prompt")).await?.click().await?;
+        let alert = c.wait_for_alert(Duration::from_secs(5)).await?;
+        assert_eq!(alert.prompt_kind().await?, PromptKind::Prompt);
+        alert.accept_with_text("Thirtyfour").await?;
+        assert_eq!(c.find(By::Id("alert-answer")).await?.text().await?, "Thirtyfour");
+
+        c.find(By::Id("button-confirm")).await?.click().await?;
+        let alert = c.wait_for_alert(Duration::from_secs(5)).await?;
+        assert_eq!(alert.prompt_kind().await?, PromptKind::AlertOrConfirm);
+        assert_matches!(
+            c.send_alert_text("Thirtyfour").await.map_err(WebDriverError::into_inner),
+            Err(WebDriverErrorInner::ElementNotInteractable(..))
+        );
+        c.dismiss_alert().await?;
+        assert_eq!(c.find(By::Id("alert-answer")).await?.text().await?, "Cancel");
+
+        // A known default value can be kept while classifying the prompt.
+        let script = r#"setTimeout(() => {
+            document.getElementById("alert-answer").innerHTML = prompt("Name?", "guest");
+        });"#;
+        c.execute(script, Vec::new()).await?;
+        let alert = c.wait_for_alert(Duration::from_secs(5)).await?;
+        assert_eq!(alert.prompt_kind_with_text("guest").await?, PromptKind::Prompt);
+        c.accept_alert().await?;
+        assert_eq!(c.find(By::Id("alert-answer")).await?.text().await?, "guest");
+
+        Ok(())
+    })
+}
+
+#[rstest]
+fn alert_prompt_kind_beforeunload(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(beforeunload_url()).await?;
+        // The beforeunload guard only fires after a user interaction.
+        c.find(By::Id("button-guard")).await?.click().await?;
+
+        let sample_url = sample_page_url();
+        c.execute("window.location.href = arguments[0];", vec![sample_url.into()]).await?;
+        let alert = c.wait_for_alert(Duration::from_secs(5)).await?;
+        assert_eq!(alert.prompt_kind().await?, PromptKind::BeforeUnload);
+        c.accept_alert().await?;
+
+        Ok(())
+    })
+}
+
+#[rstest]
+fn alert_on_alert_handler(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(alert_interval_url()).await?;
+
+        let ignored = Arc::new(AtomicUsize::new(0));
+        let handled = Arc::new(AtomicUsize::new(0));
+        let ignored_count = ignored.clone();
+        let accept_id = c.on_alert(move |text| {
+            assert_eq!(text, "Session expiring. Stay signed in?");
+            handled.fetch_add(1, Ordering::SeqCst);
+            AlertDecision::Accept
+        });
+        // Handlers stack, so this one is consulted first.
+        let ignore_id = c.on_alert(move |_| {
+            ignored_count.fetch_add(1, Ordering::SeqCst);
+            AlertDecision::Ignore
+        });
+
+        let button = c.find(By::Id("button-increment")).await?;
+        for _ in 0..20 {
+            button.click().await?;
+            thirtyfour::support::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(c.find(By::Id("click-count")).await?.text().await?, "20");
+        assert!(ignored.load(Ordering::SeqCst) > 0);
+
+        assert!(c.remove_alert_handler(ignore_id));
+        assert!(c.remove_alert_handler(accept_id));
+        assert!(!c.remove_alert_handler(accept_id));
+
+        Ok(())
+    })
+}
+
+#[rstest]
+fn goto_discarding_unload(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(beforeunload_url()).await?;
+        c.find(By::Id("button-guard")).await?.click().await?;
+
+        let sample_url = sample_page_url();
+        c.goto_discarding_unload(&sample_url).await?;
+        assert!(!c.has_alert().await?);
+        assert_eq!(c.current_url().await?.as_str(), sample_url);
+
+        Ok(())
+    })
+}
+
+#[rstest]
+fn quit_discarding_unload(test_harness: TestHarness) -> WebDriverResult<()> {
+    let config = WebDriverConfig::builder().discard_unload_on_quit(true).build()?;
+    let c = test_harness.driver().clone_with_config(config);
+    block_on(async {
+        c.goto(beforeunload_url()).await?;
+        c.find(By::Id("button-guard")).await?.click().await?;
+        c.quit().await
+    })
+}
@@ -0,0 +1,1009 @@
+//! Chrome DevTools Protocol tests.
+#[cfg(feature = "cdp-events")]
+use std::path::PathBuf;
+#[cfg(feature = "cdp-events")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "cdp-events")]
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "cdp-events")]
+use futures_util::StreamExt;
+use rstest::rstest;
+use thirtyfour::error::WebDriverErrorInner;
+use thirtyfour::extensions::cdp::{
+    AxViolationKind, CdpCookie, ChromeDevTools, ColorScheme, Device, DeviceMetrics, DomSnapshot,
+    DomSnapshotOptions, GeoLocation, MediaFeature, MotionReading, NetworkThrottle, ReducedMotion,
+    RequestRules, SensorReading, SensorType, UserAgentOverride,
+};
+#[cfg(feature = "cdp-events")]
+use thirtyfour::extensions::cdp::{
+    ConsoleLevel, Credentials, DownloadState, FileChooserAction, JsCoverageOptions, Trace,
+    TraceCategories, TracingOptions, WsEvent, WsPayload,
+};
+use thirtyfour::{prelude::*, support::block_on};
+
+use crate::common::*;
+
+mod common;
+
+/// Load the large fixture page, returning how long it took.
+async fn time_page_load(c: &WebDriver, attempt: u32) -> WebDriverResult<Duration> {
+    // Use a different URL each time so that the page is not cached.
+    let url = format!("{}?attempt={attempt}", large_page_url());
+    let started = Instant::now();
+    c.goto(&url).await?;
+    let elapsed = started.elapsed();
+    c.find(By::Id("end")).await?;
+    Ok(elapsed)
+}
+
+/// Ask the geolocation fixture page for the current position.
+async fn locate(c: &WebDriver) -> WebDriverResult<(f64, f64, f64)> {
+    c.find(By::Id("locate")).await?.click().await?;
+    let status = c.find(By::Id("status")).await?;
+    status.wait_until().has_text("located").await?;
+    let read = |id: &'static str| async move {
+        let text = c.find(By::Id(id)).await?.text().await?;
+        Ok::<f64, WebDriverError>(text.parse().expect("number"))
+    };
+    Ok((read("latitude").await?, read("longitude").await?, read("accuracy").await?))
+}
+
+#[rstest]
+fn emulate_network(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let unthrottled = time_page_load(c, 1).await?;
+
+        // Emulating the same conditions twice has no further effect.
+        dev_tools.emulate_network(&NetworkThrottle::slow_3g()).await?;
+        dev_tools.emulate_network(&NetworkThrottle::slow_3g()).await?;
+        let throttled = time_page_load(c, 2).await?;
+        assert!(
+            throttled > unthrottled * 3 && throttled > Duration::from_secs(2),
+            "throttled load took {throttled:?}, unthrottled took {unthrottled:?}"
+        );
+
+        dev_tools.clear_network_emulation().await?;
+        let cleared = time_page_load(c, 3).await?;
+        assert!(cleared < throttled / 2, "load after clearing took {cleared:?}");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn geolocation(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        c.goto(&geolocation_url()).await?;
+
+        let sydney = GeoLocation::new(-33.8568, 151.2153).with_accuracy(25.0);
+        dev_tools.set_geolocation(sydney).await?;
+        assert_eq!(locate(c).await?, (-33.8568, 151.2153, 25.0));
+
+        // The previous position is restored afterwards.
+        let london = GeoLocation::new(51.5007, -0.1246);
+        let inner = c.with_geolocation(london, || locate(c)).await?;
+        assert_eq!(inner, (51.5007, -0.1246, 1.0));
+        assert_eq!(locate(c).await?, (-33.8568, 151.2153, 25.0));
+
+        dev_tools.clear_geolocation_override().await?;
+        Ok(())
+    })
+}
+
+/// Wait for the page to report values within 0.1 of the expected ones.
+async fn wait_for_reading(c: &WebDriver, name: &str, expected: [f64; 3]) -> WebDriverResult<()> {
+    let mut last = None;
+    for _ in 0..50 {
+        let ret = c.execute(format!("return window.{name};"), Vec::new()).await?;
+        let reading: Option<[f64; 3]> = ret.convert()?;
+        if reading.is_some_and(|x| x.iter().zip(expected).all(|(a, b)| (a - b).abs() < 0.1)) {
+            return Ok(());
+        }
+        last = reading;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("expected {name} to be {expected:?}, last saw {last:?}");
+}
+
+#[rstest]
+fn sensors(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        c.goto(&sensors_url()).await?;
+        if test_harness.browser() != "chrome" {
+            let err = dev_tools.set_device_orientation(30.0, 45.0, -20.0).await.unwrap_err();
+            assert!(
+                matches!(err.as_inner(), WebDriverErrorInner::UnsupportedOperation(_)),
+                "{err:?}"
+            );
+            return Ok(());
+        }
+
+        dev_tools.set_device_orientation(30.0, 45.0, -20.0).await?;
+        wait_for_reading(c, "lastOrientation", [30.0, 45.0, -20.0]).await?;
+        dev_tools.set_device_orientation(120.0, -10.0, 60.0).await?;
+        wait_for_reading(c, "lastOrientation", [120.0, -10.0, 60.0]).await?;
+        dev_tools.clear_device_orientation().await?;
+
+        let reading = SensorReading::Accelerometer(MotionReading::new(1.5, -2.0, 9.8));
+        dev_tools.set_sensor_reading(reading).await?;
+        c.execute("startAccelerometer();", Vec::new()).await?;
+        wait_for_reading(c, "lastAcceleration", [1.5, -2.0, 9.8]).await?;
+        dev_tools.clear_sensor_reading(SensorType::Accelerometer).await?;
+
+        let err = dev_tools.set_device_orientation(0.0, 0.0, 90.0).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn geolocation_invalid(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let err = dev_tools.set_geolocation(GeoLocation::new(90.5, 0.0)).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        let err = c.with_geolocation(GeoLocation::new(0.0, -181.0), || async { Ok(()) }).await;
+        assert!(err.is_err());
+        Ok(())
+    })
+}
+
+/// The device pixel ratio, viewport width, touch support and user agent of the page.
+async fn device_state(c: &WebDriver) -> WebDriverResult<(f64, u64, bool, String)> {
+    c.goto(&sample_page_url()).await?;
+    let script = "return [window.devicePixelRatio, window.innerWidth, \
+                  'ontouchstart' in window, navigator.userAgent];";
+    c.execute(script, Vec::new()).await?.convert()
+}
+
+#[rstest]
+fn emulate_device(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let default = device_state(c).await?;
+        assert!(!default.2, "touch should not be supported by default");
+
+        dev_tools
+            .set_device_metrics(&DeviceMetrics::new(600, 400).with_device_scale_factor(2.0))
+            .await?;
+        dev_tools.enable_touch_emulation(1).await?;
+        let (ratio, width, touch, _) = device_state(c).await?;
+        assert_eq!((ratio, width, touch), (2.0, 600, true));
+
+        let pixel = Device::pixel_7();
+        dev_tools.emulate_device(&pixel).await?;
+        let (ratio, width, touch, user_agent) = device_state(c).await?;
+        assert_eq!((ratio, width, touch), (2.625, 412, true));
+        assert_eq!(user_agent, pixel.user_agent.user_agent);
+
+        dev_tools.clear_device_emulation().await?;
+        assert_eq!(device_state(c).await?, default);
+
+        let err = dev_tools.enable_touch_emulation(0).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        Ok(())
+    })
+}
+
+/// The natural width of the image on the image fixture page, which is 0 if it failed to
+/// load.
+async fn image_width(c: &WebDriver) -> WebDriverResult<u64> {
+    c.goto(&image_url()).await?;
+    let script = "const img = document.getElementById('pixel'); \
+                  return img.decode().then(() => img.naturalWidth, () => img.naturalWidth);";
+    c.execute(script, Vec::new()).await?.convert()
+}
+
+#[rstest]
+fn block_urls(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        assert_eq!(image_width(c).await?, 16);
+
+        dev_tools.block_urls(&["*/pixel.svg*"]).await?;
+        assert_eq!(image_width(c).await?, 0);
+
+        dev_tools.unblock_all().await?;
+        assert_eq!(image_width(c).await?, 16);
+
+        let err = dev_tools.block_urls(&["*.svg", ""]).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn request_rules(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let rules = RequestRules::new().block("*.svg").header("X-Test-Run", "nightly");
+        dev_tools.apply_request_rules(&rules).await?;
+        assert_eq!(image_width(c).await?, 0);
+
+        c.goto(&echo_headers_url()).await?;
+        let headers = c.find(By::Tag("body")).await?.text().await?;
+        assert!(headers.contains("x-test-run: nightly"), "{headers}");
+
+        // Headers are only removed from the requests that match the pattern.
+        #[cfg(feature = "cdp-events")]
+        {
+            let rules = RequestRules::new()
+                .header("X-Test-Run", "nightly")
+                .rewrite("*/rewritten", format!("{}?rewritten", echo_headers_url()))
+                .remove_header("*?removed", "X-Test-Run");
+            dev_tools.apply_request_rules(&rules).await?;
+            c.goto(&format!("{}/rewritten", server_origin())).await?;
+            let headers = c.find(By::Tag("body")).await?.text().await?;
+            assert!(headers.contains("x-test-run: nightly"), "{headers}");
+            c.goto(&format!("{}?removed", echo_headers_url())).await?;
+            let headers = c.find(By::Tag("body")).await?.text().await?;
+            assert!(!headers.contains("x-test-run"), "{headers}");
+        }
+
+        dev_tools.clear_request_rules().await?;
+        c.refresh().await?;
+        let headers = c.find(By::Tag("body")).await?.text().await?;
+        assert!(!headers.contains("x-test-run"), "{headers}");
+        assert_eq!(image_width(c).await?, 16);
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn basic_auth(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.set_basic_auth(&server_origin(), "user", "pass").await?;
+        c.goto(&basic_auth_url()).await?;
+        assert_eq!(c.find(By::Id("secret")).await?.text().await?, "Authenticated");
+
+        // Windows opened later are handled too.
+        let original = c.window().await?;
+        let tab = c.new_tab().await?;
+        c.switch_to_window(tab).await?;
+        c.goto(&format!("{}?tab=1", basic_auth_url())).await?;
+        assert_eq!(c.find(By::Id("secret")).await?.text().await?, "Authenticated");
+        c.close_window().await?;
+        c.switch_to_window(original).await?;
+
+        // Challenges from other origins are left to the browser, which shows the 401 page.
+        c.set_basic_auth("http://127.0.0.1:1", "user", "pass").await?;
+        c.goto(&format!("{}?attempt=2", basic_auth_url())).await?;
+        assert_eq!(c.find(By::Id("denied")).await?.text().await?, "Unauthorized");
+
+        // Rejected credentials are not retried forever.
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let pattern = format!("{}/*", server_origin());
+        dev_tools
+            .set_auth_handler(&[&pattern], |_| Some(Credentials::new("user", "wrong")))
+            .await?;
+        c.goto(&format!("{}?attempt=3", basic_auth_url())).await?;
+        assert_eq!(c.find(By::Id("denied")).await?.text().await?, "Unauthorized");
+
+        dev_tools.clear_auth_handler().await?;
+        let err = c.set_basic_auth("not an origin", "user", "pass").await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        let err = dev_tools.set_auth_handler(&[""], |_| None).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn subscribe_console(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let mut console = dev_tools.subscribe_console().await?;
+        let script = r#"console.log("one"); console.warn("two"); console.error("three", 3);"#;
+        c.execute(script, Vec::new()).await?;
+
+        let mut messages = Vec::new();
+        while messages.len() < 3 {
+            match tokio::time::timeout(Duration::from_secs(5), console.next()).await {
+                Ok(Some(message)) => messages.push(message),
+                _ => panic!("expected 3 console messages, got {messages:?}"),
+            }
+        }
+        let levels: Vec<_> = messages.iter().map(|x| (x.level, x.text.as_str())).collect();
+        assert_eq!(
+            levels,
+            [
+                (ConsoleLevel::Info, "one"),
+                (ConsoleLevel::Warning, "two"),
+                (ConsoleLevel::Error, "three 3")
+            ]
+        );
+        assert_eq!(messages[2].args, [serde_json::json!("three"), serde_json::json!(3)]);
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn assert_no_console_errors(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        // Errors written before the first check are reported too.
+        c.execute(r#"console.error("early");"#, Vec::new()).await?;
+        let err = c.assert_no_console_errors().await.unwrap_err();
+        assert!(err.to_string().contains("[error] early"), "{err}");
+        c.assert_no_console_errors().await?;
+
+        c.execute(r#"console.warn("just a warning");"#, Vec::new()).await?;
+        c.assert_no_console_errors().await?;
+
+        c.execute(r#"console.error("boom");"#, Vec::new()).await?;
+        let err = c.assert_no_console_errors().await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::JavascriptError(_)), "{err:?}");
+        assert!(err.to_string().contains("[error] boom"), "{err}");
+
+        // The buffer was drained.
+        c.assert_no_console_errors().await?;
+        Ok(())
+    })
+}
+
+/// Throw an exception from a timer, and leave a promise rejection unhandled.
+#[cfg(feature = "cdp-events")]
+const THROW_SCRIPT: &str = r#"
+    setTimeout(() => { throw new Error("from timer"); }, 0);
+    Promise.reject(new Error("from promise"));
+"#;
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn subscribe_exceptions(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let mut exceptions = dev_tools.subscribe_exceptions().await?;
+        c.execute(THROW_SCRIPT, Vec::new()).await?;
+
+        let mut captured = Vec::new();
+        while captured.len() < 2 {
+            match tokio::time::timeout(Duration::from_secs(5), exceptions.next()).await {
+                Ok(Some(exception)) => captured.push(exception),
+                _ => panic!("expected 2 exceptions, got {captured:?}"),
+            }
+        }
+        captured.sort_by_key(|x| x.unhandled_rejection);
+        assert_eq!(captured[0].message, "Error: from timer");
+        assert!(!captured[0].unhandled_rejection);
+        assert_eq!(captured[1].message, "Error: from promise");
+        assert!(captured[1].unhandled_rejection);
+        assert!(captured.iter().all(|x| x.stack.is_some() && !x.possibly_masked));
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn fail_on_js_exception(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        c.fail_on_js_exception(true).await?;
+        // Events are sent to every subscriber at once, so once this stream has seen an
+        // exception, so has the recording.
+        let mut thrown = ChromeDevTools::new(c.handle.clone()).subscribe_exceptions().await?;
+        let timeout = Duration::from_secs(5);
+        c.execute(THROW_SCRIPT, Vec::new()).await?;
+        let both = thrown.by_ref().take(2).collect::<Vec<_>>();
+        assert_eq!(tokio::time::timeout(timeout, both).await.expect("exceptions").len(), 2);
+
+        // The next command fails, listing both exceptions.
+        let err = c.title().await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::JavascriptError(_)), "{err:?}");
+        let message = err.to_string();
+        assert!(message.contains("uncaught exception: Error: from timer"), "{message}");
+        assert!(message.contains("unhandled rejection: Error: from promise"), "{message}");
+        c.title().await?;
+
+        c.execute(r#"setTimeout(() => { throw new Error("again"); }, 0);"#, Vec::new()).await?;
+        tokio::time::timeout(timeout, thrown.next()).await.expect("exception");
+        let err = c.check_js_exceptions().await.unwrap_err();
+        assert!(err.to_string().contains("again"), "{err}");
+        c.check_js_exceptions().await?;
+
+        c.fail_on_js_exception(false).await?;
+        c.execute(THROW_SCRIPT, Vec::new()).await?;
+        c.check_js_exceptions().await?;
+        c.title().await?;
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn coverage(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        dev_tools.start_js_coverage(&JsCoverageOptions::new().with_include_source(true)).await?;
+        dev_tools.start_css_coverage().await?;
+        c.goto(&coverage_url()).await?;
+        c.find(By::Id("left")).await?.click().await?;
+        let scripts = dev_tools.stop_js_coverage().await?;
+        let sheets = dev_tools.stop_css_coverage().await?;
+
+        let script = scripts.iter().find(|x| x.url == coverage_url()).expect("inline script");
+        let source = script.source.as_deref().expect("script source");
+        let offset = |text: &str| source.find(text).expect("text in script");
+        assert!(script.is_covered(offset("went left")));
+        assert!(!script.is_covered(offset("went right")));
+        assert_eq!(script.function("choose").map(|x| x.count()), Some(1));
+        assert_eq!(script.function("neverCalled").map(|x| x.count()), Some(0));
+        assert!(!script.is_covered(offset("untouched")));
+
+        let sheet = sheets.iter().find(|x| x.url == coverage_url()).expect("inline stylesheet");
+        assert_eq!(sheet.rules.iter().filter(|x| x.used).count(), 1);
+        let unused = sheet.unused_rules();
+        assert_eq!(unused.len(), 1);
+        assert!(unused[0].starts_with(".unused"), "{unused:?}");
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn tracing(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        dev_tools.start_tracing(TraceCategories::web_vitals(), &TracingOptions::new()).await?;
+        c.goto(&sample_page_url()).await?;
+        let Trace::Events(trace) = dev_tools.stop_tracing().await? else {
+            panic!("the trace should be kept in memory");
+        };
+
+        let events: Vec<serde_json::Value> = serde_json::from_slice(&trace)?;
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|x| x["ph"].is_string() && x["name"].is_string()));
+        Ok(())
+    })
+}
+
+async fn date_settings(c: &WebDriver) -> WebDriverResult<(i64, String, String)> {
+    let script = r#"
+        const options = Intl.DateTimeFormat().resolvedOptions();
+        return [new Date(2024, 0, 15).getTimezoneOffset(), options.timeZone, options.locale];
+    "#;
+    c.execute(script, Vec::new()).await?.convert()
+}
+
+#[rstest]
+fn timezone_locale(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let original = date_settings(c).await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        dev_tools.set_timezone("Asia/Kolkata").await?;
+        dev_tools.set_locale("de-DE").await?;
+        assert_eq!(
+            date_settings(c).await?,
+            (-330, "Asia/Kolkata".to_string(), "de-DE".to_string())
+        );
+
+        let inner = c
+            .with_timezone_locale("America/New_York", "fr-CA", || async { date_settings(c).await })
+            .await?;
+        assert_eq!(inner, (300, "America/New_York".to_string(), "fr-CA".to_string()));
+        assert_eq!(
+            date_settings(c).await?,
+            (-330, "Asia/Kolkata".to_string(), "de-DE".to_string())
+        );
+
+        dev_tools.clear_timezone().await?;
+        dev_tools.clear_locale().await?;
+        assert_eq!(date_settings(c).await?, original);
+
+        let err = dev_tools.set_timezone("Europe/Londn").await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        assert!(err.to_string().contains("Europe/London"), "{err:?}");
+        Ok(())
+    })
+}
+
+/// Whether the page prefers dark mode and reduced motion, and the color of the target.
+async fn media_state(c: &WebDriver) -> WebDriverResult<(bool, bool, String)> {
+    let script = "return [matchMedia('(prefers-color-scheme: dark)').matches, \
+                  matchMedia('(prefers-reduced-motion: reduce)').matches, \
+                  getComputedStyle(document.getElementById('target')).color];";
+    c.execute(script, Vec::new()).await?.convert()
+}
+
+#[rstest]
+fn media_emulation(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&media_url()).await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        dev_tools
+            .set_media_features(&[MediaFeature::PrefersColorScheme(ColorScheme::Light)])
+            .await?;
+        let screen = "rgb(0, 0, 255)".to_string();
+        assert_eq!(media_state(c).await?, (false, false, screen.clone()));
+
+        dev_tools
+            .set_media_features(&[
+                MediaFeature::PrefersColorScheme(ColorScheme::Dark),
+                MediaFeature::PrefersReducedMotion(ReducedMotion::Reduce),
+            ])
+            .await?;
+        assert_eq!(media_state(c).await?, (true, true, screen.clone()));
+
+        dev_tools.set_emulated_media_type("print").await?;
+        assert_eq!(media_state(c).await?, (true, true, "rgb(255, 0, 0)".to_string()));
+
+        dev_tools.clear_media_emulation().await?;
+        let (_, _, color) = media_state(c).await?;
+        assert_eq!(color, screen);
+
+        let err = dev_tools.set_emulated_media_type("tv").await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        Ok(())
+    })
+}
+
+/// The user agent, platform and whether the page reports a mobile device.
+async fn user_agent_state(c: &WebDriver) -> WebDriverResult<(String, String, bool)> {
+    let script =
+        "return [navigator.userAgent, navigator.platform, navigator.userAgentData.mobile];";
+    c.execute(script, Vec::new()).await?.convert()
+}
+
+#[rstest]
+fn user_agent_override(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let pixel = UserAgentOverride::pixel_7();
+        dev_tools.set_user_agent(&pixel).await?;
+        c.goto(&sample_page_url()).await?;
+        assert_eq!(
+            user_agent_state(c).await?,
+            (pixel.user_agent.clone(), "Linux armv81".to_string(), true)
+        );
+
+        dev_tools.clear_user_agent_override().await?;
+        c.refresh().await?;
+        let (user_agent, _, mobile) = user_agent_state(c).await?;
+        assert_ne!(user_agent, pixel.user_agent);
+        assert!(!mobile);
+
+        let err = dev_tools.set_user_agent(&UserAgentOverride::new("")).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn http_only_cookies(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let mut cookie = CdpCookie::new("hidden", "secret");
+        cookie.http_only = true;
+        dev_tools.set_cookie_cdp(&cookie).await?;
+
+        let document_cookie: String =
+            c.execute("return document.cookie;", Vec::new()).await?.convert()?;
+        assert!(!document_cookie.contains("hidden"), "{document_cookie}");
+
+        let url = sample_page_url();
+        for cookies in [
+            dev_tools.get_all_cookies_cdp(Some(&[url.as_str()])).await?,
+            dev_tools.get_all_cookies_cdp(None).await?,
+        ] {
+            let hidden = cookies.iter().find(|x| x.name == "hidden").expect("hidden cookie");
+            assert_eq!(hidden.value, "secret");
+            assert!(hidden.http_only);
+            assert_eq!(hidden.domain, "localhost");
+        }
+        let cookie = c.get_named_cookie("hidden").await?;
+        assert_eq!(cookie.value, "secret");
+
+        // Chrome rejects SameSite=None cookies that are not secure.
+        let mut rejected = CdpCookie::new("rejected", "value");
+        rejected.same_site = Some(SameSite::None);
+        let err = dev_tools.set_cookie_cdp(&rejected).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::UnableToSetCookie(_)), "{err:?}");
+        assert!(err.to_string().contains("rejected"), "{err:?}");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn accessibility_audit(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&a11y_audit_url()).await?;
+        if test_harness.browser() != "chrome" {
+            let err = c.basic_a11y_audit().await.unwrap_err();
+            assert!(
+                matches!(err.as_inner(), WebDriverErrorInner::UnsupportedOperation(_)),
+                "{err:?}"
+            );
+            return Ok(());
+        }
+
+        let tree = c.accessibility_tree().await?;
+        assert_eq!(tree.find_by_role("heading")[0].name, "Accessibility audit");
+        assert_eq!(tree.find_by_name("Back to top")[0].role, "link");
+        assert_eq!(tree.find_by_name("Email")[0].role, "textbox");
+
+        let report = c.basic_a11y_audit().await?;
+        let expected = [
+            (AxViolationKind::ImageWithoutName, "broken-image"),
+            (AxViolationKind::EmptyButtonOrLink, "broken-button"),
+            (AxViolationKind::FormControlWithoutLabel, "broken-input"),
+        ];
+        assert_eq!(report.violations.len(), expected.len(), "{report}");
+        for (violation, (kind, id)) in report.violations.iter().zip(expected) {
+            assert_eq!(violation.kind, kind, "{report}");
+            let backend_node_id = violation.backend_node_id.expect("a DOM node");
+            let elem = c.element_from_backend_node_id(backend_node_id).await?;
+            assert_eq!(elem.id().await?.as_deref(), Some(id));
+        }
+        Ok(())
+    })
+}
+
+#[rstest]
+fn dom_snapshot(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&snapshot_url()).await?;
+        c.find(By::Id("name")).await?.send_keys("typed").await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let options = DomSnapshotOptions::new().with_computed_styles(&["display", "position"]);
+        let snapshot = dev_tools.dom_snapshot(&options).await?;
+        assert_eq!(snapshot.documents[0].title, "Snapshot");
+
+        let card = snapshot.find_nodes_by_attribute("data-testid", "card");
+        assert_eq!(card.len(), 1);
+        let card = card[0];
+        assert_eq!(card.name, "DIV");
+        assert_eq!(snapshot.text_of(card), "Hello, world");
+        assert_eq!(snapshot.computed_style(card, "display"), Some("flex"));
+        assert_eq!(snapshot.computed_style(card, "position"), Some("absolute"));
+        let bounds = &card.layout.as_ref().expect("card layout").bounds;
+        assert_eq!((bounds.x, bounds.y, bounds.width, bounds.height), (20.0, 30.0, 200.0, 50.0));
+
+        // Nodes that are not rendered have no layout.
+        let hidden = snapshot.find_nodes_by_attribute("data-testid", "hidden");
+        assert!(hidden[0].layout.is_none());
+        let input = snapshot.find_nodes_by_attribute("id", "name");
+        assert_eq!(input[0].input_value.as_deref(), Some("typed"));
+
+        let path = std::env::temp_dir().join("thirtyfour-dom-snapshot.json");
+        snapshot.save_json(&path)?;
+        let loaded = DomSnapshot::load_json(&path)?;
+        std::fs::remove_file(&path)?;
+        let card = &loaded.find_nodes_by_attribute("data-testid", "card")[0];
+        assert_eq!(loaded.computed_style(card, "display"), Some("flex"));
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn file_chooser(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&file_chooser_url()).await?;
+        let dir = std::env::temp_dir();
+        let files: Vec<PathBuf> = ["first.txt", "second.txt"]
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                std::fs::write(&path, name).map(|_| path)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let chosen = files.clone();
+        c.on_file_chooser(move |_| FileChooserAction::Files(chosen.clone())).await?;
+        c.find(By::Id("choose-multiple")).await?.click().await?;
+        let multiple = c.find(By::Id("multiple-files")).await?;
+        multiple.wait_until().has_text("first.txt,second.txt").await?;
+
+        // Only the first file is chosen if the input does not accept several files.
+        c.find(By::Id("choose-single")).await?.click().await?;
+        let single = c.find(By::Id("single-files")).await?;
+        single.wait_until().has_text("first.txt").await?;
+
+        // Cancelling keeps the files chosen before.
+        let cancelled = Arc::new(AtomicUsize::new(0));
+        let count = cancelled.clone();
+        c.on_file_chooser(move |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+            FileChooserAction::Cancel
+        })
+        .await?;
+        c.find(By::Id("choose-multiple")).await?.click().await?;
+        let started = Instant::now();
+        while cancelled.load(Ordering::SeqCst) == 0 {
+            assert!(started.elapsed() < Duration::from_secs(10), "file chooser not opened");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let script = "return document.getElementById('multiple').files.length;";
+        let count: u32 = c.execute(script, Vec::new()).await?.convert()?;
+        assert_eq!(count, 2);
+        assert_eq!(multiple.text().await?, "first.txt,second.txt");
+
+        ChromeDevTools::new(c.handle.clone()).clear_file_chooser_handler().await?;
+        for path in files {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn downloads(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        let dir = std::env::temp_dir().join("thirtyfour-downloads");
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::create_dir_all(&dir)?;
+        c.goto(&download_page_url()).await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        dev_tools.set_download_behavior(&dir, true).await?;
+        let mut events = dev_tools.subscribe_downloads().await?;
+
+        c.find(By::Id("download")).await?.click().await?;
+        let download = c.wait_for_download_complete("report.csv", Duration::from_secs(30)).await?;
+        assert_eq!(download.state, DownloadState::Completed);
+        assert_eq!(download.url, Some(format!("{}/download", server_origin())));
+        assert_eq!(download.received_bytes, DOWNLOAD_LEN as u64);
+        assert_eq!(download.total_bytes, DOWNLOAD_LEN as u64);
+        assert_eq!(std::fs::read(dir.join("report.csv"))?.len(), DOWNLOAD_LEN);
+        let states: Vec<DownloadState> =
+            std::iter::from_fn(|| events.try_next()).map(|x| x.state).collect();
+        assert_eq!(states.first(), Some(&DownloadState::Started), "{states:?}");
+        assert_eq!(states.last(), Some(&DownloadState::Completed), "{states:?}");
+
+        // Cancelled downloads fail the wait.
+        c.find(By::Id("slow-download")).await?.click().await?;
+        let in_progress = loop {
+            let event = tokio::time::timeout(Duration::from_secs(30), events.next())
+                .await
+                .expect("download progress")
+                .expect("download event");
+            if event.state == DownloadState::InProgress {
+                break event;
+            }
+        };
+        dev_tools.cancel_download(&in_progress.guid).await?;
+        let err = c
+            .wait_for_download_complete(&in_progress.guid, Duration::from_secs(30))
+            .await
+            .unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::DownloadCanceled(_)), "{err:?}");
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn websockets(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&websocket_url()).await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let mut events = dev_tools.subscribe_websockets().await?;
+        c.find(By::Id("connect")).await?.click().await?;
+        c.find(By::Id("status")).await?.wait_until().has_text("closed").await?;
+
+        let mut received = Vec::new();
+        while let Some(event) = tokio::time::timeout(Duration::from_secs(10), events.next())
+            .await
+            .expect("websocket event")
+        {
+            let closed = matches!(event, WsEvent::Closed { .. });
+            received.push(event);
+            if closed {
+                break;
+            }
+        }
+        let request_id = received[0].request_id().to_string();
+        assert!(received.iter().all(|x| x.request_id() == request_id), "{received:?}");
+        let summary: Vec<(&str, Option<&WsPayload>)> = received
+            .iter()
+            .map(|x| match x {
+                WsEvent::Created {
+                    ..
+                } => ("created", None),
+                WsEvent::FrameSent {
+                    frame,
+                    ..
+                } => ("sent", Some(&frame.payload)),
+                WsEvent::FrameReceived {
+                    frame,
+                    ..
+                } => ("received", Some(&frame.payload)),
+                WsEvent::Closed {
+                    ..
+                } => ("closed", None),
+            })
+            .collect();
+        let hello = WsPayload::Text("hello".to_string());
+        let binary = WsPayload::Binary(vec![0, 1, 2]);
+        assert_eq!(
+            summary,
+            vec![
+                ("created", None),
+                ("sent", Some(&hello)),
+                ("received", Some(&hello)),
+                ("sent", Some(&binary)),
+                ("received", Some(&binary)),
+                ("closed", None),
+            ]
+        );
+
+        // Only frames for matching URLs are collected.
+        let collect = dev_tools.collect_ws_frames("*/ws_echo", Duration::from_secs(3));
+        let click = async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            c.find(By::Id("connect")).await?.click().await
+        };
+        let (frames, clicked) = tokio::join!(collect, click);
+        clicked?;
+        let frames = frames?;
+        assert_eq!(frames.len(), 4, "{frames:?}");
+        assert!(
+            matches!(&frames[0], WsEvent::FrameSent { frame, .. } if frame.text() == Some("hello"))
+        );
+        let frames = dev_tools.collect_ws_frames("*/other", Duration::from_millis(100)).await?;
+        assert!(frames.is_empty());
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[rstest]
+fn heap_growth(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&memory_url()).await?;
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let metrics = dev_tools.performance_metrics().await?;
+        for name in ["JSHeapUsedSize", "Documents", "Nodes", "JSEventListeners"] {
+            assert!(metrics.get(name).is_some_and(|x| *x > 0.0), "{name}: {metrics:?}");
+        }
+
+        // Garbage does not count as growth.
+        let total: u64 = c
+            .assert_heap_growth_under(
+                || async { c.execute("return churn();", Vec::new()).await?.convert() },
+                2_000_000,
+            )
+            .await?;
+        assert_eq!(total, 1_250_000);
+
+        let err = c
+            .assert_heap_growth_under(
+                || async {
+                    c.execute("leak();", Vec::new()).await?;
+                    Ok(())
+                },
+                2_000_000,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::HeapGrowthExceeded(_)), "{err:?}");
+        assert!(err.to_string().contains("DOM nodes"), "{err}");
+        Ok(())
+    })
+}
@@ -16,6 +16,9 @@ static LOGINIT: OnceLock<()> = OnceLock::new();
 const ASSETS_DIR: &str = "tests/test_html";
 const PORT: u16 = 8081;
 
+/// The size of the files served by `/download` and `/slow_download`.
+pub const DOWNLOAD_LEN: usize = 20 * 64 * 1024;
+
 /// Create the Capabilities struct for the specified browser.
 pub fn make_capabilities(s: &str) -> Capabilities {
     match s {
@@ -58,6 +61,11 @@ pub fn start_server() -> Arc<JoinHandle<()>> {
                     let addr = SocketAddr::from(([127, 0, 0, 1], PORT));
                     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
                     let app = axum::Router::new()
+                        .route("/echo_headers", axum::routing::get(echo_headers))
+                        .route("/basic_auth", axum::routing::get(basic_auth))
+                        .route("/download", axum::routing::get(download))
+                        .route("/slow_download", axum::routing::get(slow_download))
+                        .route("/ws_echo", axum::routing::get(ws_echo))
                         .nest_service("/", tower_http::services::ServeDir::new(ASSETS_DIR));
                     axum::serve(listener, app).await.unwrap();
                 });
@@ -67,6 +75,69 @@ pub fn start_server() -> Arc<JoinHandle<()>> {
         .clone()
 }
 
+/// Respond with the request headers, one `name: value` pair per line.
+async fn echo_headers(headers: axum::http::HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {}\n", value.to_str().unwrap_or_default()))
+        .collect()
+}
+
+/// Require basic authentication as `user`, with the password `pass`.
+async fn basic_auth(headers: axum::http::HeaderMap) -> axum::response::Response {
+    use axum::response::{Html, IntoResponse};
+
+    // "user:pass", base64 encoded.
+    if headers.get("authorization").is_some_and(|x| x == "Basic dXNlcjpwYXNz") {
+        return Html("<p id=\"secret\">Authenticated</p>").into_response();
+    }
+    (
+        axum::http::StatusCode::UNAUTHORIZED,
+        [("www-authenticate", "Basic realm=\"thirtyfour\"")],
+        Html("<p id=\"denied\">Unauthorized</p>"),
+    )
+        .into_response()
+}
+
+/// Respond with a file to download, named `report.csv`.
+async fn download() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let headers = [("content-disposition", "attachment; filename=\"report.csv\"")];
+    (headers, vec![b'x'; DOWNLOAD_LEN]).into_response()
+}
+
+/// Respond with a file to download, named `slow.csv`, sent in chunks over several
+/// seconds.
+async fn slow_download() -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let chunks = futures_util::stream::unfold(0, |sent| async move {
+        if sent == DOWNLOAD_LEN {
+            return None;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let chunk = vec![b'x'; 64 * 1024];
+        Some((Ok::<_, std::convert::Infallible>(chunk), sent + 64 * 1024))
+    });
+    let headers = [
+        ("content-disposition", "attachment; filename=\"slow.csv\"".to_string()),
+        ("content-length", DOWNLOAD_LEN.to_string()),
+    ];
+    (headers, axum::body::Body::from_stream(chunks)).into_response()
+}
+
+/// Accept a WebSocket, and send back every message received on it.
+async fn ws_echo(ws: axum::extract::WebSocketUpgrade) -> axum::response::Response {
+    ws.on_upgrade(|mut socket| async move {
+        while let Some(Ok(message)) = socket.recv().await {
+            if socket.send(message).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
 pub fn init_logging() {
     LOGINIT.get_or_init(|| {
         use tracing_subscriber::{fmt, prelude::*, EnvFilter};
@@ -87,7 +158,7 @@ pub fn get_limiter() -> &'static Semaphore {
 /// Locks the Firefox browser for exclusive use.
 ///
 /// This ensures there is only ever one Firefox browser running at a time.
-pub async fn lock_firefox<'a>(browser: &str) -> Option<SemaphorePermit<'static>> {
+pub async fn lock_firefox(browser: &str) -> Option<SemaphorePermit<'static>> {
     if browser == "firefox" {
         Some(get_limiter().acquire().await.unwrap())
     } else {
@@ -163,3 +234,179 @@ pub fn other_page_url() -> String {
 pub fn drag_to_url() -> String {
     format!("http://localhost:{PORT}/drag_to.html")
 }
+
+pub fn iframe_page_url() -> String {
+    format!("http://localhost:{PORT}/iframe_outer.html")
+}
+
+pub fn nested_frames_url() -> String {
+    format!("http://localhost:{PORT}/nested_frames.html")
+}
+
+pub fn beforeunload_url() -> String {
+    format!("http://localhost:{PORT}/beforeunload.html")
+}
+
+pub fn alert_interval_url() -> String {
+    format!("http://localhost:{PORT}/alert_interval.html")
+}
+
+pub fn screenshot_crop_url() -> String {
+    format!("http://localhost:{PORT}/screenshot_crop.html")
+}
+
+pub fn transition_url() -> String {
+    format!("http://localhost:{PORT}/transition.html")
+}
+
+pub fn rows_url() -> String {
+    format!("http://localhost:{PORT}/rows.html")
+}
+
+pub fn spinner_url() -> String {
+    format!("http://localhost:{PORT}/spinner.html")
+}
+
+pub fn shadow_url() -> String {
+    format!("http://localhost:{PORT}/shadow.html")
+}
+
+pub fn select_url() -> String {
+    format!("http://localhost:{PORT}/select.html")
+}
+
+pub fn table_url() -> String {
+    format!("http://localhost:{PORT}/table.html")
+}
+
+pub fn modal_url() -> String {
+    format!("http://localhost:{PORT}/modal.html")
+}
+
+pub fn flaky_url() -> String {
+    format!("http://localhost:{PORT}/flaky.html")
+}
+
+pub fn large_page_url() -> String {
+    format!("http://localhost:{PORT}/large_page.html")
+}
+
+pub fn geolocation_url() -> String {
+    format!("http://localhost:{PORT}/geolocation.html")
+}
+
+pub fn sensors_url() -> String {
+    format!("http://localhost:{PORT}/sensors.html")
+}
+
+pub fn image_url() -> String {
+    format!("http://localhost:{PORT}/image.html")
+}
+
+pub fn echo_headers_url() -> String {
+    format!("http://localhost:{PORT}/echo_headers")
+}
+
+pub fn basic_auth_url() -> String {
+    format!("http://localhost:{PORT}/basic_auth")
+}
+
+pub fn coverage_url() -> String {
+    format!("http://localhost:{PORT}/coverage.html")
+}
+
+pub fn media_url() -> String {
+    format!("http://localhost:{PORT}/media.html")
+}
+
+pub fn file_chooser_url() -> String {
+    format!("http://localhost:{PORT}/file_chooser.html")
+}
+
+pub fn download_page_url() -> String {
+    format!("http://localhost:{PORT}/download.html")
+}
+
+pub fn websocket_url() -> String {
+    format!("http://localhost:{PORT}/websocket.html")
+}
+
+pub fn memory_url() -> String {
+    format!("http://localhost:{PORT}/memory.html")
+}
+
+pub fn webauthn_url() -> String {
+    format!("http://localhost:{PORT}/webauthn.html")
+}
+
+pub fn permissions_url() -> String {
+    format!("http://localhost:{PORT}/permissions.html")
+}
+
+pub fn clock_url() -> String {
+    format!("http://localhost:{PORT}/clock.html")
+}
+
+pub fn a11y_audit_url() -> String {
+    format!("http://localhost:{PORT}/a11y_audit.html")
+}
+
+pub fn scroll_url() -> String {
+    format!("http://localhost:{PORT}/scroll.html")
+}
+
+pub fn canvas_url() -> String {
+    format!("http://localhost:{PORT}/canvas.html")
+}
+
+pub fn touch_url() -> String {
+    format!("http://localhost:{PORT}/touch.html")
+}
+
+pub fn swipe_url() -> String {
+    format!("http://localhost:{PORT}/swipe.html")
+}
+
+pub fn paragraphs_url() -> String {
+    format!("http://localhost:{PORT}/paragraphs.html")
+}
+
+pub fn multi_select_url() -> String {
+    format!("http://localhost:{PORT}/multi_select.html")
+}
+
+pub fn dnd_url() -> String {
+    format!("http://localhost:{PORT}/dnd.html")
+}
+
+pub fn long_press_url() -> String {
+    format!("http://localhost:{PORT}/long_press.html")
+}
+
+pub fn snapshot_url() -> String {
+    format!("http://localhost:{PORT}/snapshot.html")
+}
+
+pub fn server_origin() -> String {
+    format!("http://localhost:{PORT}")
+}
+
+pub fn nested_scroll_url() -> String {
+    format!("http://localhost:{PORT}/nested_scroll.html")
+}
+
+pub fn listbox_url() -> String {
+    format!("http://localhost:{PORT}/listbox.html")
+}
+
+pub fn map_url() -> String {
+    format!("http://localhost:{PORT}/map.html")
+}
+
+pub fn autocomplete_url() -> String {
+    format!("http://localhost:{PORT}/autocomplete.html")
+}
+
+pub fn overlay_url() -> String {
+    format!("http://localhost:{PORT}/overlay.html")
+}
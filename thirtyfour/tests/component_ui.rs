@@ -0,0 +1,7 @@
+#[cfg(feature = "component")]
+#[test]
+fn component_derive() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/component/pass_*.rs");
+    t.compile_fail("tests/ui/component/fail_*.rs");
+}
@@ -4,9 +4,15 @@ mod common;
 mod feature_component {
     use super::common::*;
     use assert_matches::assert_matches;
+    use futures_util::StreamExt;
     use rstest::rstest;
-    use std::time::Instant;
-    use thirtyfour::components::{Component, ElementResolver};
+    use std::time::{Duration, Instant};
+    use thirtyfour::components::modal::ModalComponent;
+    use thirtyfour::components::select::{DropdownLike, SelectComponent};
+    use thirtyfour::components::table::{Colspan, TableComponent};
+    use thirtyfour::components::{
+        Component, ComponentLifecycle, ComponentStream, ElementResolver, RootComponent,
+    };
     use thirtyfour::error::WebDriverErrorInner;
     use thirtyfour::extensions::query::ElementQueryOptions;
     use thirtyfour::support::block_on;
@@ -162,6 +168,633 @@ mod feature_component {
         })
     }
 
+    /// This page object shows how to nest components more than one level deep.
+    #[derive(Debug, Clone, Component)]
+    pub struct SamplePage {
+        base: WebElement,
+        #[by(id = "checkbox-section")]
+        checkbox_section: ElementResolver<CheckboxSectionComponent>,
+        #[by(tag = "label", first)]
+        first_checkbox: ElementResolver<CheckboxComponent>,
+        #[by(css = "#checkbox-section label")]
+        checkboxes: thirtyfour::components::ElementResolver<Vec<CheckboxComponent>>,
+        #[by(tag = "notfound", allow_empty, nowait)]
+        missing: ElementResolver<Vec<CheckboxComponent>>,
+    }
+
+    #[rstest]
+    fn nested_component(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = sample_page_url();
+            c.goto(&url).await?;
+
+            let page = SamplePage::new(c.find(By::Tag("body")).await?);
+
+            let section = resolve!(page.checkbox_section);
+            assert_eq!(section.base.id().await?.unwrap(), "checkbox-section");
+            let boxes = resolve!(section.boxes);
+            assert_eq!(boxes.len(), 3);
+
+            let checkbox = resolve!(page.first_checkbox);
+            checkbox.tick().await?;
+            assert!(checkbox.is_ticked().await?);
+            assert!(boxes[0].is_ticked().await?);
+
+            assert_eq!(resolve!(page.checkboxes).len(), 3);
+            assert!(resolve!(page.missing).is_empty());
+
+            Ok(())
+        })
+    }
+
+    #[derive(Debug, Clone, Component)]
+    pub struct RefreshComponent {
+        base: WebElement,
+        #[by(css = "input[type='checkbox']", nowait)]
+        input: ElementResolver<WebElement>,
+        #[by(css = "input[type='checkbox']", nowait, no_auto_refresh)]
+        input_no_refresh: ElementResolver<WebElement>,
+    }
+
+    #[rstest]
+    fn resolver_auto_refresh(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = sample_page_url();
+            c.goto(&url).await?;
+
+            let label = c.query(By::Css("#checkbox-section label")).first().await?;
+            let component = RefreshComponent::new(label.clone());
+            let get_id = |elem: WebElement| async move { elem.id().await };
+            assert_eq!(component.input.with_resolved(get_id).await?.unwrap(), "checkbox-option-1");
+            component.input_no_refresh.resolve().await?;
+
+            // Re-render the contents of the label, so the cached inputs become stale.
+            c.execute("arguments[0].innerHTML = arguments[0].innerHTML;", vec![label.to_json()?])
+                .await?;
+            assert_eq!(component.input.with_resolved(get_id).await?.unwrap(), "checkbox-option-1");
+            let result = component.input_no_refresh.with_resolved(get_id).await;
+            assert_matches!(
+                result.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::StaleElementReference(_))
+            );
+
+            // Resolving the field via resolve_present() also replaces the stale element.
+            c.execute("arguments[0].innerHTML = arguments[0].innerHTML;", vec![label.to_json()?])
+                .await?;
+            assert!(!resolve!(component.input).is_present().await?);
+            assert_eq!(resolve_present!(component.input).id().await?.unwrap(), "checkbox-option-1");
+
+            // If the element cannot be resolved again, the original error is returned.
+            c.execute("arguments[0].querySelector('input').remove();", vec![label.to_json()?])
+                .await?;
+            let result = component.input.with_resolved(get_id).await;
+            assert_matches!(
+                result.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::StaleElementReference(_))
+            );
+
+            Ok(())
+        })
+    }
+
+    /// This component shows how to wrap a table row.
+    #[derive(Debug, Clone, Component)]
+    pub struct RowComponent {
+        base: WebElement,
+    }
+
+    #[derive(Debug, Clone, Component)]
+    pub struct RowsPage {
+        base: WebElement,
+        #[by(css = "#table tr", page_size = 10)]
+        rows: ComponentStream<RowComponent>,
+        #[by(css = "#virtual-table tr", stream, page_size = 20, scroll = scroll_virtual_table)]
+        virtual_rows: ComponentStream<RowComponent>,
+    }
+
+    async fn scroll_virtual_table(elem: WebElement) -> WebDriverResult<()> {
+        elem.handle.execute("window.scrollVirtualTable();", Vec::new()).await?;
+        Ok(())
+    }
+
+    async fn query_count(c: &WebDriver) -> WebDriverResult<u64> {
+        c.execute("return window.queryCount;", Vec::new()).await?.convert()
+    }
+
+    #[rstest]
+    fn component_stream_early_termination(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = rows_url();
+            c.goto(&url).await?;
+
+            let page = RowsPage::new(c.find(By::Tag("body")).await?);
+            let before = query_count(c).await?;
+
+            let mut rows = std::pin::pin!(page.rows.stream());
+            let mut count = 0;
+            while let Some(row) = rows.next().await {
+                assert_eq!(row?.base.text().await?, format!("Row {count}"));
+                count += 1;
+                if count == 15 {
+                    break;
+                }
+            }
+
+            // Only the first two pages of 10 should have been requested.
+            assert_eq!(query_count(c).await? - before, 2);
+
+            Ok(())
+        })
+    }
+
+    #[rstest]
+    fn component_stream_virtual_scroll(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = rows_url();
+            c.goto(&url).await?;
+
+            let page = RowsPage::new(c.find(By::Tag("body")).await?);
+            let mut rows = std::pin::pin!(page.virtual_rows.stream());
+            let mut count = 0;
+            while let Some(row) = rows.next().await {
+                // Each row must still be in the DOM when it is handed out.
+                assert_eq!(row?.base.text().await?, format!("Row {count}"));
+                count += 1;
+            }
+            assert_eq!(count, 1000);
+
+            Ok(())
+        })
+    }
+
+    #[derive(Debug, Component, Clone)]
+    pub struct TestComponentTimeouts {
+        base: WebElement,
+        #[by(id = "delayed", wait(timeout_ms = 10000, interval_ms = 100))]
+        delayed: ElementResolver<WebElement>,
+        #[by(id = "delayed", nowait, description = "delayed element")]
+        delayed_nowait: ElementResolver<WebElement>,
+        #[by(
+            id = "notfound",
+            wait(timeout_ms = 1000, interval_ms = 100),
+            description = "missing element"
+        )]
+        missing: ElementResolver<WebElement>,
+    }
+
+    #[rstest]
+    fn component_field_timeouts(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = sample_page_url();
+            c.goto(&url).await?;
+
+            let tc = TestComponentTimeouts::new(c.find(By::Tag("body")).await?);
+            c.execute(
+                r#"setTimeout(() => {
+                    const elem = document.createElement("div");
+                    elem.id = "delayed";
+                    document.body.appendChild(elem);
+                }, 1000);"#,
+                Vec::new(),
+            )
+            .await?;
+
+            let start = Instant::now();
+            let result = tc.delayed_nowait.resolve().await;
+            assert_matches!(result.map_err(WebDriverError::into_inner), Err(WebDriverErrorInner::NoSuchElement(x)) if x.error.contains("delayed element"));
+            assert!(start.elapsed().as_millis() < 1000);
+
+            let elem = tc.delayed.resolve().await?;
+            assert_eq!(elem.id().await?.unwrap(), "delayed");
+
+            let start = Instant::now();
+            let result = tc.missing.resolve().await;
+            assert_matches!(result.map_err(WebDriverError::into_inner), Err(WebDriverErrorInner::NoSuchElement(x)) if x.error.contains("missing element"));
+            assert!(start.elapsed().as_millis() >= 1000);
+            assert!(start.elapsed().as_secs() < 10);
+
+            Ok(())
+        })
+    }
+
+    /// This component shows how to wait until a component is ready before using it.
+    #[derive(Debug, Clone, Component)]
+    #[component(await_ready)]
+    pub struct PanelComponent {
+        base: WebElement,
+        #[by(class = "content")]
+        content: ElementResolver<WebElement>,
+    }
+
+    impl ComponentLifecycle for PanelComponent {
+        async fn on_resolved(&self) -> WebDriverResult<()> {
+            let spinner = self.base.query(By::ClassName("spinner"));
+            match spinner
+                .wait(Duration::from_secs(2), Duration::from_millis(100))
+                .not_exists()
+                .await?
+            {
+                true => Ok(()),
+                false => Err(WebDriverError::Timeout("spinner is still visible".to_string())),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Component)]
+    pub struct SpinnerPage {
+        base: WebElement,
+        #[by(id = "panel")]
+        panel: ElementResolver<PanelComponent>,
+        #[by(id = "stuck-panel")]
+        stuck_panel: ElementResolver<PanelComponent>,
+        #[by(css = "div[id$='panel']")]
+        panels: ElementResolver<Vec<PanelComponent>>,
+    }
+
+    #[rstest]
+    fn component_lifecycle(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = spinner_url();
+            c.goto(&url).await?;
+
+            let page = SpinnerPage::new(c.find(By::Tag("body")).await?);
+            let panel = resolve!(page.panel);
+            assert_eq!(resolve!(panel.content).text().await?, "Loaded");
+
+            let result = page.stuck_panel.resolve().await;
+            assert_matches!(
+                result.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::ComponentNotReady(name, e))
+                    if name.ends_with("PanelComponent")
+                        && matches!(e.as_inner(), WebDriverErrorInner::Timeout(_))
+            );
+
+            let result = page.panels.resolve().await;
+            assert_matches!(
+                result.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::ComponentNotReady(..))
+            );
+
+            Ok(())
+        })
+    }
+
+    #[derive(Debug, Clone, Component)]
+    #[component(root = "css:#checkbox-section")]
+    pub struct CheckboxSectionPage {
+        base: WebElement,
+        #[by(tag = "label")]
+        boxes: ElementResolver<Vec<CheckboxComponent>>,
+    }
+
+    #[derive(Debug, Clone, Component)]
+    #[by(id = "missing-section", wait(timeout_ms = 1000, interval_ms = 100))]
+    pub struct MissingPage {
+        base: WebElement,
+    }
+
+    #[rstest]
+    fn root_component(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = sample_page_url();
+            c.goto(&url).await?;
+
+            let page = CheckboxSectionPage::from_driver(c).await?;
+            assert_eq!(page.base.id().await?.unwrap(), "checkbox-section");
+            assert_eq!(resolve!(page.boxes).len(), 3);
+            assert!(CheckboxSectionPage::exists_in(c).await?);
+
+            // The original construction still works.
+            let elem = c.find(By::Id("checkbox-section")).await?;
+            let _page = CheckboxSectionPage::from(elem);
+
+            assert!(!MissingPage::exists_in(c).await?);
+            let result = MissingPage::from_driver(c).await;
+            assert_matches!(
+                result.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::NoSuchElement(x))
+                    if x.error.contains("MissingPage") && x.error.contains("missing-section")
+            );
+
+            Ok(())
+        })
+    }
+
+    #[derive(Debug, Clone, Component)]
+    pub struct RenderedSection {
+        base: WebElement,
+        #[by(tag = "label", nowait)]
+        boxes: ElementResolver<Vec<CheckboxComponent>>,
+    }
+
+    #[derive(Debug, Clone, Component)]
+    pub struct RenderedPage {
+        base: WebElement,
+        #[by(id = "checkbox-section", nowait)]
+        section: ElementResolver<RenderedSection>,
+        #[by(tag = "label", first, nowait)]
+        first_checkbox: ElementResolver<CheckboxComponent>,
+    }
+
+    #[rstest]
+    fn component_invalidate_all(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = sample_page_url();
+            c.goto(&url).await?;
+
+            let page = RenderedPage::new(c.find(By::Tag("body")).await?);
+            let section = resolve!(page.section);
+            let boxes = resolve!(section.boxes);
+            assert!(!boxes[0].is_ticked().await?);
+            assert!(!resolve!(page.first_checkbox).is_ticked().await?);
+
+            // Re-render the section, so every element cached inside it becomes stale.
+            let rerender_section = "let s = document.getElementById('checkbox-section'); \
+                                    s.innerHTML = s.innerHTML;";
+            c.execute(rerender_section, vec![]).await?;
+            assert_matches!(
+                boxes[0].is_ticked().await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::StaleElementReference(_))
+            );
+
+            // The section element itself was kept, and the resolvers nested inside it
+            // were invalidated along with the page.
+            page.invalidate_all();
+            let boxes = resolve!(section.boxes);
+            assert_eq!(boxes.len(), 3);
+            boxes[0].tick().await?;
+            assert!(resolve!(page.first_checkbox).is_ticked().await?);
+
+            // Re-render everything, then resolve it all again up front.
+            c.execute("document.body.innerHTML = document.body.innerHTML;", vec![]).await?;
+            page.refresh_all().await?;
+            let section = resolve!(page.section);
+            let boxes = resolve!(section.boxes);
+            assert_eq!(boxes.len(), 3);
+            boxes[0].tick().await?;
+            assert!(resolve!(page.first_checkbox).is_ticked().await?);
+
+            // A field that cannot be resolved is named in the error, along with the path to it.
+            c.execute(
+                "document.querySelectorAll('#checkbox-section label').forEach(e => e.remove());",
+                vec![],
+            )
+            .await?;
+            let result = page.refresh_all().await;
+            assert_matches!(
+                result.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::ComponentRefreshFailed(field, e))
+                    if field.ends_with("RenderedPage.section")
+                        && matches!(
+                            e.as_inner(),
+                            WebDriverErrorInner::ComponentRefreshFailed(field, _)
+                                if field.ends_with("RenderedSection.boxes")
+                        )
+            );
+
+            Ok(())
+        })
+    }
+
+    /// This component shows how to wrap a web component with a shadow root.
+    #[derive(Debug, Clone, Component)]
+    pub struct PickerComponent {
+        base: WebElement,
+        // Slotted elements are children of the host element, so they don't need `shadow`.
+        #[by(css = "[slot='label']")]
+        label: ElementResolver<WebElement>,
+        #[by(css = "button.toggle", shadow, nowait)]
+        toggle: ElementResolver<WebElement>,
+        #[by(css = ".option", shadow, nowait)]
+        options: ElementResolver<Vec<CheckboxComponent>>,
+        #[by(css = ".option", shadow, first, nowait)]
+        first_option: ElementResolver<CheckboxComponent>,
+    }
+
+    #[rstest]
+    fn shadow_component(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = shadow_url();
+            c.goto(&url).await?;
+
+            let picker = PickerComponent::new(c.find(By::Id("open-picker")).await?);
+            assert_eq!(resolve!(picker.label).text().await?, "Colour");
+            assert_eq!(resolve!(picker.toggle).text().await?, "Choose");
+            let options = resolve!(picker.options);
+            assert_eq!(options.len(), 3);
+            options[1].tick().await?;
+            assert!(options[1].is_ticked().await?);
+            assert!(!resolve!(picker.first_option).is_ticked().await?);
+
+            // Closed shadow roots cannot be accessed, and the error names the field.
+            let closed = PickerComponent::new(c.find(By::Id("closed-picker")).await?);
+            assert_eq!(resolve!(closed.label).text().await?, "Size");
+            assert_matches!(
+                closed.toggle.resolve().await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::NoSuchShadowRoot(x))
+                    if x.error.contains("PickerComponent.toggle")
+            );
+            assert_matches!(
+                closed.options.resolve().await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::NoSuchShadowRoot(x))
+                    if x.error.contains("PickerComponent.options")
+            );
+
+            Ok(())
+        })
+    }
+
+    #[derive(Debug, Clone, Component)]
+    pub struct OrderForm<D: DropdownLike + Clone + 'static> {
+        base: WebElement,
+        #[by(name = "size")]
+        size: ElementResolver<D>,
+        #[by(name = "toppings")]
+        toppings: ElementResolver<SelectComponent>,
+        #[by(id = "changes")]
+        changes: ElementResolver<WebElement>,
+    }
+
+    /// Choose an option in any kind of dropdown.
+    async fn choose<D: DropdownLike>(dropdown: &D, text: &str) -> WebDriverResult<String> {
+        dropdown.select_text(text).await?;
+        dropdown.selected_text().await
+    }
+
+    #[rstest]
+    fn select_component(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = select_url();
+            c.goto(&url).await?;
+
+            let form = OrderForm::<SelectComponent>::new(c.find(By::Id("order-form")).await?);
+            let size = resolve!(form.size);
+            assert!(!size.is_multiple().await?);
+            assert_eq!(size.options_text().await?, ["Small", "Medium", "Large", "Extra Large"]);
+            assert_eq!(size.selected_text().await?, "Medium");
+
+            size.select_value("l").await?;
+            assert_eq!(size.value().await?, "l");
+            assert_eq!(size.selected_text().await?, "Large");
+            assert_eq!(choose(&size, "Small").await?, "Small");
+            assert_eq!(size.selected_values().await?, ["s"]);
+            assert_eq!(resolve!(form.changes).text().await?, "size=l;size=s;");
+
+            // Disabled and missing options are reported, and the selection is unchanged.
+            assert_matches!(
+                size.select_text("Extra Large").await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::InvalidElementState(_))
+            );
+            assert_matches!(
+                size.select_value("xxl").await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::NoSuchElement(_))
+            );
+            assert_eq!(size.value().await?, "s");
+            assert_matches!(
+                size.deselect_all().await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::InvalidElementState(_))
+            );
+
+            let toppings = resolve!(form.toppings);
+            assert!(toppings.is_multiple().await?);
+            assert!(toppings.selected_texts().await?.is_empty());
+            assert_matches!(
+                toppings.selected_text().await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::NoSuchElement(_))
+            );
+            toppings.select_value("cheese").await?;
+            toppings.select_text("Olives").await?;
+            // Selecting an option that is already selected leaves it selected.
+            toppings.select_value("olives").await?;
+            assert_eq!(toppings.selected_texts().await?, ["Cheese", "Olives"]);
+            assert_eq!(toppings.selected_values().await?, ["cheese", "olives"]);
+            toppings.deselect_all().await?;
+            assert!(toppings.selected_values().await?.is_empty());
+
+            // Options added after the options were last read are found too.
+            c.execute(
+                "arguments[0].add(new Option('Pineapple', 'pineapple'));",
+                vec![toppings.base_element().to_json()?],
+            )
+            .await?;
+            toppings.select_text("Pineapple").await?;
+            assert_eq!(toppings.selected_values().await?, ["pineapple"]);
+
+            Ok(())
+        })
+    }
+
+    #[rstest]
+    fn table_component(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = table_url();
+            c.goto(&url).await?;
+
+            let table = TableComponent::new(c.find(By::Id("users")).await?);
+            assert_eq!(table.headers().await?, ["Name", "Email", "Role", "Actions"]);
+            assert_eq!(table.row_count().await?, 20);
+            assert_eq!(table.column_index("Email").await?, 1);
+            assert_eq!(table.cell(2, 1).await?, "user3@example.com");
+            assert_matches!(
+                table.column_index("Phone").await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::NoSuchElement(_))
+            );
+            assert_matches!(
+                table.cell(20, 0).await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::NoSuchElement(_))
+            );
+
+            let maps = table.rows_as_maps().await?;
+            assert_eq!(maps.len(), 20);
+            assert_eq!(maps[4]["Name"], "User 5");
+            assert_eq!(maps.iter().filter(|x| x["Role"] == "Admin").count(), 4);
+
+            // Interact with a row found by its contents.
+            let row = table.find_row(|x| x["Email"] == "user7@example.com").await?.unwrap();
+            row.base_element().find(By::Css("button.delete")).await?.click().await?;
+            assert_eq!(c.find(By::Id("deleted")).await?.text().await?, "7");
+            assert_eq!(table.row_count().await?, 19);
+            assert!(table.find_row(|x| x["Name"] == "User 7").await?.is_none());
+
+            // Cells spanning multiple columns are repeated, or padded.
+            let totals = TableComponent::new(c.find(By::Id("totals")).await?);
+            assert_eq!(totals.headers().await?, ["Item", "Price", "Price"]);
+            assert_eq!(totals.cell(1, 1).await?, "Total");
+            let totals = totals.set_colspan(Colspan::Pad);
+            assert_eq!(totals.headers().await?, ["Item", "Price", ""]);
+            let maps = totals.rows_as_maps().await?;
+            assert_eq!(maps[1]["Item"], "Total");
+            assert_eq!(maps[1]["Price"], "");
+            assert_eq!(maps[1][""], "4.50");
+
+            Ok(())
+        })
+    }
+
+    #[rstest]
+    fn modal_component(test_harness: TestHarness) -> WebDriverResult<()> {
+        let c = test_harness.driver();
+        block_on(async {
+            let url = modal_url();
+            c.goto(&url).await?;
+
+            let timeout = Duration::from_secs(5);
+            let modal = ModalComponent::from_driver(c).await?;
+            assert!(!modal.is_open().await?);
+            modal.wait_closed(timeout).await?;
+            assert_matches!(
+                modal
+                    .wait_open(Duration::from_millis(200))
+                    .await
+                    .map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::Timeout(_))
+            );
+
+            // A Bootstrap-style modal.
+            c.find(By::Id("open-confirm")).await?.click().await?;
+            modal.wait_open(timeout).await?;
+            assert_eq!(modal.title().resolve().await?.text().await?, "Confirm");
+
+            // A modal stacked on top of it is used instead, until it is closed by clicking
+            // the backdrop.
+            modal.body().resolve().await?.find(By::Id("open-details")).await?.click().await?;
+            let details = modal.wait_open(timeout).await?;
+            assert_eq!(details.id().await?.as_deref(), Some("details"));
+            assert_eq!(modal.title().resolve().await?.text().await?, "Details");
+            modal.close().await?;
+            modal.wait_closed(timeout).await?;
+            assert!(modal.is_open().await?);
+            assert_eq!(modal.title().resolve().await?.text().await?, "Confirm");
+
+            // The close button fades the modal out.
+            modal.close().await?;
+            modal.wait_closed(timeout).await?;
+            assert!(!modal.is_open().await?);
+
+            // A native dialog.
+            c.find(By::Id("open-native")).await?.click().await?;
+            modal.wait_open(timeout).await?;
+            assert_eq!(modal.title().resolve().await?.text().await?, "Native");
+            assert_eq!(modal.body().resolve().await?.text().await?, "A native dialog.");
+            modal.close().await?;
+            modal.wait_closed(timeout).await?;
+            assert!(!modal.is_open().await?);
+
+            Ok(())
+        })
+    }
+
     #[derive(Debug, Component, Clone)]
     pub struct TestComponentCustomFn {
         base: WebElement,
@@ -1,7 +1,14 @@
 //! Element tests
 use crate::common::sample_page_url;
+use assert_matches::assert_matches;
 use common::*;
 use rstest::rstest;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use thirtyfour::common::config::WebDriverConfig;
+use thirtyfour::error::WebDriverErrorInner;
+use thirtyfour::{is_transient, ErrorCaptureOptions, FailureReporter, RetryPolicy};
 use thirtyfour::{prelude::*, support::block_on};
 
 mod common;
@@ -28,6 +35,183 @@ fn element_js(test_harness: TestHarness) -> WebDriverResult<()> {
     })
 }
 
+#[rstest]
+fn element_error_context(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let sample_url = sample_page_url();
+        c.goto(&sample_url).await?;
+        let form = c.find(By::Id("textarea-form")).await?;
+
+        let err = form.find(By::Css("#missing")).await.unwrap_err();
+        let context = err.element_context().unwrap();
+        assert_eq!(context.selector.as_deref(), Some("css `#missing`"));
+        assert_eq!(context.parent.as_deref(), Some("<form id=textarea-form>"));
+        assert_eq!(context.operation.as_deref(), Some("find"));
+        assert!(err
+            .to_string()
+            .starts_with("no such element: css `#missing` within <form id=textarea-form>"));
+
+        let err = form.query(By::Id("missing")).nowait().first().await.unwrap_err();
+        let context = err.element_context().unwrap();
+        assert_eq!(context.selector.as_deref(), Some("id `missing`"));
+        assert_eq!(context.operation.as_deref(), Some("first"));
+
+        let err = c.find(By::XPath("//missing")).await.unwrap_err();
+        let context = err.element_context().unwrap();
+        assert_eq!(context.parent, None);
+        assert_eq!(context.operation.as_deref(), Some("find"));
+        Ok(())
+    })
+}
+
+/// Create a retry policy that counts its retries.
+fn counting_policy(max_attempts: u32, backoff: Duration) -> (RetryPolicy, Arc<AtomicU32>) {
+    let retries = Arc::new(AtomicU32::new(0));
+    let policy = RetryPolicy::new(is_transient, max_attempts, backoff).on_retry({
+        let retries = retries.clone();
+        move |_| {
+            retries.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    (policy, retries)
+}
+
+#[rstest]
+fn element_retry_policy(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&flaky_url()).await?;
+
+        // The click is intercepted until the overlay is removed.
+        c.find(By::Id("start")).await?.click().await?;
+        let (policy, retries) = counting_policy(20, Duration::from_millis(100));
+        c.find(By::Id("covered")).await?.click_with_policy(&policy).await?;
+        assert!(retries.load(Ordering::SeqCst) >= 1);
+        assert_eq!(c.find(By::Id("clicked")).await?.text().await?, "clicked");
+
+        // A hidden input is never interactable, so every attempt is made.
+        let input = c.find(By::Id("hidden-input")).await?;
+        let (policy, retries) = counting_policy(3, Duration::ZERO);
+        assert_matches!(
+            input.send_keys_with_policy("abc", &policy).await.map_err(WebDriverError::into_inner),
+            Err(WebDriverErrorInner::ElementNotInteractable(_))
+        );
+        assert_eq!(retries.load(Ordering::SeqCst), 2);
+
+        // Errors that are not retryable are returned straight away.
+        let (policy, retries) = counting_policy(3, Duration::ZERO);
+        let policy = RetryPolicy {
+            retryable: |_| false,
+            ..policy
+        };
+        assert!(input.click_with_policy(&policy).await.is_err());
+        assert_eq!(retries.load(Ordering::SeqCst), 0);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn session_retry_policy(test_harness: TestHarness) -> WebDriverResult<()> {
+    let (policy, retries) = counting_policy(4, Duration::ZERO);
+    let config = WebDriverConfig::builder().retry_policy(policy).build()?;
+    let c = test_harness.driver().clone_with_config(config);
+    block_on(async {
+        c.goto(&flaky_url()).await?;
+        let input = c.find(By::Id("hidden-input")).await?;
+        assert!(input.clear().await.is_err());
+        assert_eq!(retries.load(Ordering::SeqCst), 3);
+
+        // Finds are only retried if enabled, and a missing element is not transient anyway.
+        assert!(c.find(By::Id("missing")).await.is_err());
+        assert_eq!(retries.load(Ordering::SeqCst), 3);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn error_attachments(test_harness: TestHarness) -> WebDriverResult<()> {
+    let dir = std::env::temp_dir().join(format!("thirtyfour-error-capture-{}", std::process::id()));
+    let options = ErrorCaptureOptions::new(&dir).timeout(Duration::from_secs(10));
+    let config = WebDriverConfig::builder().capture_on_error(options).build()?;
+    let c = test_harness.driver().clone_with_config(config);
+    block_on(async {
+        c.goto(&flaky_url()).await?;
+        let input = c.find(By::Id("hidden-input")).await?;
+
+        // Successful operations capture nothing.
+        input.text().await?;
+        assert!(!dir.exists());
+
+        let err = input.click().await.unwrap_err();
+        let attachments = err.attachments().expect("click error should have attachments");
+        assert!(std::fs::metadata(attachments.screenshot.as_ref().unwrap())?.len() > 0);
+        let source = std::fs::read_to_string(attachments.page_source.as_ref().unwrap())?;
+        assert!(source.contains("hidden-input"));
+        assert_eq!(attachments.url.as_deref(), Some(flaky_url().as_str()));
+        assert!(format!("{err:?}").contains("attachments: Some"));
+
+        let err = c.query(By::Id("missing")).nowait().first().await.unwrap_err();
+        assert!(err.attachments().is_some());
+
+        // Errors outside element operations and queries are not captured.
+        let err = c.find(By::Id("missing")).await.unwrap_err();
+        assert!(err.attachments().is_none());
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn failure_report(test_harness: TestHarness) -> WebDriverResult<()> {
+    let path =
+        std::env::temp_dir().join(format!("thirtyfour-failures-{}.ndjson", std::process::id()));
+    let dir =
+        std::env::temp_dir().join(format!("thirtyfour-report-capture-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let options = ErrorCaptureOptions::new(&dir).timeout(Duration::from_secs(10));
+    let config = WebDriverConfig::builder().capture_on_error(options).build()?;
+    let c = test_harness.driver().clone_with_config(config);
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let reporter = FailureReporter::new(&path)?;
+        let id = c.on_error({
+            let reporter = reporter.clone();
+            move |error| reporter.report(error)
+        });
+        c.find(By::Id("missing")).await.unwrap_err();
+        c.query(By::Id("missing")).nowait().first().await.unwrap_err();
+        // A query that polls until the timeout is reported once.
+        c.query(By::Id("missing"))
+            .wait(Duration::from_millis(300), Duration::from_millis(50))
+            .first()
+            .await
+            .unwrap_err();
+        c.remove_error_hook(id);
+        c.find(By::Id("missing")).await.unwrap_err();
+        reporter.flush();
+
+        let contents = std::fs::read_to_string(&path)?;
+        let lines: Vec<serde_json::Value> =
+            contents.lines().map(serde_json::from_str).collect::<Result<_, _>>()?;
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["kind"], "no_such_element");
+        assert_eq!(lines[0]["http_status"], 404);
+        assert_eq!(lines[0]["session_id"], c.session_id().to_string());
+        assert!(lines[0]["command"].as_str().unwrap().starts_with("POST "));
+        assert!(lines[0]["selector"].as_str().unwrap().contains("missing"));
+        for line in &lines[1..] {
+            assert_eq!(line["kind"], "no_such_element");
+            assert!(line["selector"].as_str().unwrap().contains("missing"));
+            assert_eq!(line["url"], sample_page_url());
+        }
+        assert!(lines[2]["message"].as_str().unwrap().contains("attempts"));
+        std::fs::remove_file(&path)?;
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    })
+}
+
 #[rstest]
 fn element_attr(test_harness: TestHarness) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -260,3 +444,37 @@ fn element_get_parent(test_harness: TestHarness) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+/// Read the dimensions from the IHDR chunk of a PNG.
+#[cfg(feature = "screenshot-crop")]
+fn png_dimensions(png: &[u8]) -> (u32, u32) {
+    let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+    (width, height)
+}
+
+#[cfg(feature = "screenshot-crop")]
+#[rstest]
+fn element_screenshot_cropped(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(screenshot_crop_url()).await?;
+        let ratio: f64 =
+            c.execute("return window.devicePixelRatio;", Vec::new()).await?.convert()?;
+
+        for id in ["plain", "inner"] {
+            let elem = c.find(By::Id(id)).await?;
+            let png = elem.screenshot_cropped_png().await?;
+            let rect = elem.rect().await?;
+            let (width, height) = png_dimensions(&png);
+            assert!((width as f64 - rect.width * ratio).abs() <= 2.0, "{id}: width {width}");
+            assert!((height as f64 - rect.height * ratio).abs() <= 2.0, "{id}: height {height}");
+        }
+
+        for id in ["clipped", "empty"] {
+            let elem = c.find(By::Id(id)).await?;
+            assert!(elem.screenshot_cropped_png().await.is_err(), "{id}");
+        }
+        Ok(())
+    })
+}
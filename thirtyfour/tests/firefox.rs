@@ -0,0 +1,128 @@
+//! Firefox-specific tests.
+#[cfg(feature = "cdp-events")]
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+#[cfg(feature = "cdp-events")]
+use futures_util::StreamExt;
+use rstest::rstest;
+use thirtyfour::error::WebDriverErrorInner;
+use thirtyfour::extensions::addons::firefox::{FirefoxContext, PrefValue};
+#[cfg(feature = "cdp-events")]
+use thirtyfour::extensions::cdp::ChromeDevTools;
+use thirtyfour::{prelude::*, support::block_on};
+
+use crate::common::*;
+
+mod common;
+
+#[rstest]
+fn chrome_context(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "firefox" {
+        return Ok(());
+    }
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(sample_page_url()).await?;
+        let firefox = c.firefox();
+        assert_eq!(firefox.get_context().await?, FirefoxContext::Content);
+
+        let script = r#"
+            Services.prefs.setStringPref("thirtyfour.test", "from chrome");
+            return Services.prefs.getStringPref("thirtyfour.test");
+        "#;
+        let pref: String = firefox
+            .with_chrome_context(|| async {
+                assert_eq!(firefox.get_context().await?, FirefoxContext::Chrome);
+                c.execute(script, Vec::new()).await?.convert()
+            })
+            .await?;
+        assert_eq!(pref, "from chrome");
+        assert_eq!(firefox.get_context().await?, FirefoxContext::Content);
+
+        // Privileged scripts fail in the content context, without the annotation.
+        let err = c.execute(script, Vec::new()).await.unwrap_err();
+        assert_eq!(err.attachments().and_then(|x| x.context.as_deref()), None);
+
+        // Navigating is only supported in the content context.
+        let err = firefox
+            .with_chrome_context(|| async { c.goto(sample_page_url()).await })
+            .await
+            .unwrap_err();
+        assert_eq!(err.attachments().and_then(|x| x.context.as_deref()), Some("Firefox chrome"));
+        assert_matches!(err.as_inner(), WebDriverErrorInner::UnsupportedOperation(_));
+        assert_eq!(firefox.get_context().await?, FirefoxContext::Content);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn prefs(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "firefox" {
+        return Ok(());
+    }
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(sample_page_url()).await?;
+        let firefox = c.firefox();
+        assert_eq!(firefox.get_pref("thirtyfour.missing").await?, None);
+
+        let prefs = [
+            ("thirtyfour.string", PrefValue::from("hello")),
+            ("thirtyfour.int", PrefValue::Int(42)),
+            ("thirtyfour.bool", PrefValue::Bool(true)),
+        ];
+        for (name, value) in prefs {
+            firefox.set_pref(name, value.clone()).await?;
+            assert_eq!(firefox.get_pref(name).await?, Some(value));
+            assert_eq!(firefox.get_context().await?, FirefoxContext::Content);
+        }
+
+        // Changing the type of a preference throws, but still restores the context.
+        let err = firefox.set_pref("thirtyfour.int", "not an int").await.unwrap_err();
+        assert_eq!(err.attachments().and_then(|x| x.context.as_deref()), Some("Firefox chrome"));
+        assert_eq!(firefox.get_context().await?, FirefoxContext::Content);
+        assert_eq!(firefox.get_pref("thirtyfour.int").await?, Some(PrefValue::Int(42)));
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cdp-events")]
+#[test]
+fn debugger_address() -> WebDriverResult<()> {
+    let browser = std::env::var("THIRTYFOUR_BROWSER").unwrap_or_else(|_| "chrome".to_string());
+    if browser != "firefox" {
+        return Ok(());
+    }
+    init_logging();
+    let _server = start_server();
+    block_on(async {
+        let _guard = lock_firefox(&browser).await;
+        let mut caps = DesiredCapabilities::firefox();
+        caps.set_headless()?;
+        caps.enable_debugger_address()?;
+        let c = WebDriver::new(webdriver_url(&browser), caps).await?;
+
+        let result = async {
+            assert!(c.debugger_address().is_some());
+            c.goto(sample_page_url()).await?;
+            let dev_tools = ChromeDevTools::new(c.handle.clone());
+            let mut console = dev_tools.subscribe_console().await?;
+            c.execute(r#"console.log("from firefox");"#, Vec::new()).await?;
+            let message = tokio::time::timeout(Duration::from_secs(5), console.next())
+                .await
+                .expect("a console message")
+                .expect("the connection to stay open");
+            assert_eq!(message.text, "from firefox");
+
+            // Domains that Firefox does not implement fail with its error.
+            let connection = dev_tools.connection().await?;
+            let err = connection.send("Fetch.enable", serde_json::json!({})).await.unwrap_err();
+            assert_matches!(err.as_inner(), WebDriverErrorInner::UnknownError(_));
+            Ok(())
+        }
+        .await;
+        c.quit().await?;
+        result
+    })
+}
@@ -1,8 +1,15 @@
 //! Tests that don't make use of external websites.
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use futures_util::{StreamExt, TryStreamExt};
 use rstest::rstest;
-use thirtyfour::{prelude::*, support::block_on, SameSite};
+use thirtyfour::error::WebDriverErrorInner;
+use thirtyfour::extensions::cdp::ChromeDevTools;
+use thirtyfour::testing::TestConfig;
+use thirtyfour::{
+    prelude::*, support::block_on, A11yProfile, CookieChange, PermissionName, PermissionState,
+    SameSite, VirtualAuthenticatorOptions,
+};
 
 use crate::common::*;
 
@@ -35,6 +42,55 @@ fn resolve_execute_async_value(test_harness: TestHarness) -> WebDriverResult<()>
     })
 }
 
+#[test]
+fn a11y_profile() -> WebDriverResult<()> {
+    let browser = std::env::var("THIRTYFOUR_BROWSER").unwrap_or_else(|_| "chrome".to_string());
+    init_logging();
+    let _server = start_server();
+    let profile = A11yProfile {
+        forced_colors: true,
+        reduced_motion: true,
+        ..Default::default()
+    };
+    let caps: Capabilities = match browser.as_str() {
+        "firefox" => {
+            let mut caps = DesiredCapabilities::firefox();
+            caps.set_headless()?;
+            caps.apply_a11y_profile(profile)?;
+            caps.into()
+        }
+        _ => {
+            let mut caps = DesiredCapabilities::chrome();
+            caps.set_headless()?;
+            caps.set_no_sandbox()?;
+            caps.set_disable_gpu()?;
+            caps.set_disable_dev_shm_usage()?;
+            caps.apply_a11y_profile(profile)?;
+            caps.into()
+        }
+    };
+
+    block_on(async {
+        let _guard = lock_firefox(&browser).await;
+        let c = WebDriver::new(webdriver_url(&browser), caps).await?;
+        let result = async {
+            c.goto(sample_page_url()).await?;
+            let script = r#"
+                return [
+                    matchMedia("(forced-colors: active)").matches,
+                    matchMedia("(prefers-reduced-motion: reduce)").matches,
+                ];
+            "#;
+            let matches: (bool, bool) = c.execute(script, Vec::new()).await?.convert()?;
+            assert_eq!(matches, (true, true));
+            Ok(())
+        }
+        .await;
+        c.quit().await?;
+        result
+    })
+}
+
 #[rstest]
 fn status(test_harness: TestHarness) -> WebDriverResult<()> {
     let c = test_harness.driver();
@@ -153,3 +209,255 @@ fn test_cookies(test_harness: TestHarness) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+// Verifies that partitioned cookies are kept out of the top-level partition.
+#[rstest]
+fn test_partitioned_cookie(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        // Partitioned cookies are only supported via CDP.
+        return Ok(());
+    }
+
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(iframe_page_url()).await?;
+
+        let mut cookie = Cookie::new("partitioned", "thirtyfour");
+        cookie.set_path("/");
+        cookie.set_secure(true);
+        cookie.set_same_site(SameSite::None);
+        cookie.set_partition_key("https://example.com");
+        c.add_cookie(cookie).await?;
+
+        // The cookie was partitioned under a different top-level site.
+        let cookies = c.get_all_cookies().await?;
+        assert!(!cookies.iter().any(|x| x.name == "partitioned"));
+
+        // The browser kept the partition key.
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        let cookies = dev_tools.get_all_cookies_cdp(None).await?;
+        let cookie = cookies.iter().find(|x| x.name == "partitioned").expect("partitioned cookie");
+        let partition_key = cookie.partition_key.as_ref().expect("partition key");
+        assert_eq!(partition_key.top_level_site, "https://example.com");
+        assert_eq!(
+            Cookie::from(cookie.clone()).partition_key.as_deref(),
+            Some("https://example.com")
+        );
+
+        Ok(())
+    })
+}
+
+// Verifies that cookie changes are observed in order.
+#[rstest]
+fn test_watch_cookie(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(sample_page_url()).await?;
+        c.delete_all_cookies().await?;
+
+        let changes = c.watch_cookie("watched", Duration::from_millis(50)).await?;
+        c.execute(
+            r#"
+            setTimeout(() => document.cookie = "watched=1", 200);
+            setTimeout(() => document.cookie = "watched=2", 600);
+            setTimeout(() => document.cookie = "watched=; expires=Thu, 01 Jan 1970 00:00:00 GMT", 1000);
+            "#,
+            Vec::new(),
+        )
+        .await?;
+
+        let changes: Vec<CookieChange> = changes.take(3).try_collect().await?;
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(&changes[0], CookieChange::Added(x) if x.value == "1"));
+        assert!(matches!(
+            &changes[1],
+            CookieChange::Updated { old, new } if old.value == "1" && new.value == "2"
+        ));
+        assert!(matches!(&changes[2], CookieChange::Removed(x) if x.value == "2"));
+
+        c.execute(r#"setTimeout(() => document.cookie = "watched=3", 200);"#, Vec::new()).await?;
+        let cookie =
+            c.wait_for_cookie("watched", |x| x.value == "3", Duration::from_secs(5)).await?;
+        assert_eq!(cookie.value, "3");
+
+        Ok(())
+    })
+}
+
+#[rstest]
+fn virtual_authenticator(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(webauthn_url()).await?;
+        let authenticator = c
+            .add_virtual_authenticator(VirtualAuthenticatorOptions {
+                has_resident_key: true,
+                has_user_verification: true,
+                is_user_verified: true,
+                ..Default::default()
+            })
+            .await?;
+        let status = c.find(By::Id("status")).await?;
+
+        c.find(By::Id("register")).await?.click().await?;
+        status.wait_until().has_text("registered").await?;
+        let credentials = c.get_credentials(&authenticator).await?;
+        assert_eq!(credentials.len(), 1);
+        let credential = &credentials[0];
+        assert!(credential.is_resident_credential);
+        assert_eq!(credential.rp_id, "localhost");
+        assert_eq!(credential.user_handle.as_deref(), Some(&b"alice"[..]));
+
+        c.find(By::Id("sign-in")).await?.click().await?;
+        status.wait_until().has_text("signed in as alice").await?;
+        let credentials = c.get_credentials(&authenticator).await?;
+        assert!(credentials[0].sign_count > credential.sign_count);
+
+        // The credential can be added back after removing it.
+        c.remove_all_credentials(&authenticator).await?;
+        assert!(c.get_credentials(&authenticator).await?.is_empty());
+        c.add_credential(&authenticator, credential.clone()).await?;
+        c.find(By::Id("sign-in")).await?.click().await?;
+        status.wait_until().has_text("signed in as alice").await?;
+
+        c.set_user_verified(&authenticator, false).await?;
+        c.find(By::Id("sign-in")).await?.click().await?;
+        status.wait_until().has_text("error: NotAllowedError").await?;
+
+        c.remove_credential(&authenticator, &credential.credential_id).await?;
+        assert!(c.get_credentials(&authenticator).await?.is_empty());
+        c.remove_virtual_authenticator(&authenticator).await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn permissions(test_harness: TestHarness) -> WebDriverResult<()> {
+    if test_harness.browser() != "chrome" {
+        return Ok(());
+    }
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(permissions_url()).await?;
+        let state = |name: &'static str| async move {
+            c.execute_async(
+                "permissionState(arguments[0]).then(arguments[1], e => arguments[1](e.name))",
+                vec![name.into()],
+            )
+            .await?
+            .convert::<String>()
+        };
+
+        c.grant_permissions(&[PermissionName::ClipboardRead, PermissionName::ClipboardWrite])
+            .await?;
+        assert_eq!(state("clipboard-read").await?, "granted");
+        // The clipboard can only be read while the page has focus.
+        c.find(By::Id("text")).await?.click().await?;
+        let text: String = c
+            .execute_async(
+                "roundTripClipboard(arguments[0]).then(arguments[1], e => arguments[1](e.name))",
+                vec!["from thirtyfour".into()],
+            )
+            .await?
+            .convert()?;
+        assert_eq!(text, "from thirtyfour");
+
+        c.set_permission(PermissionName::ClipboardRead, PermissionState::Denied).await?;
+        assert_eq!(state("clipboard-read").await?, "denied");
+        Ok(())
+    })
+}
+
+#[test]
+fn test_session_artifacts() -> WebDriverResult<()> {
+    let browser = std::env::var("THIRTYFOUR_BROWSER").unwrap_or_else(|_| "chrome".to_string());
+    init_logging();
+    let _server = start_server();
+    let root = std::env::temp_dir().join(format!("thirtyfour-artifacts-{}", std::process::id()));
+    let config =
+        TestConfig::new(webdriver_url(&browser), make_capabilities(&browser)).artifacts_dir(&root);
+    let files = |dir: &std::path::Path| {
+        let mut files: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        files
+    };
+    block_on(async {
+        let _guard = lock_firefox(&browser).await;
+
+        // The test is named after the current thread by default.
+        let session = config.clone().start().await?;
+        assert_eq!(session.name(), "test_session_artifacts");
+        let dir = session.artifacts_dir().to_path_buf();
+        assert_eq!(dir, root.join("test_session_artifacts"));
+        let result: WebDriverResult<()> = async {
+            session.driver().goto(sample_page_url()).await?;
+            session.driver().find(By::Id("missing")).await?;
+            Ok(())
+        }
+        .await;
+        assert!(session.finish(result).await.is_err());
+        assert!(files(&dir).contains(&"screenshot.png".to_string()));
+        let report: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(dir.join("result.json"))?)?;
+        assert_eq!(report["status"], "failed");
+
+        // Passing replaces the artifacts of the failed run.
+        let session = config.name("suite::passing").start().await?;
+        session.driver().goto(sample_page_url()).await?;
+        session.finish(Ok::<_, WebDriverError>(())).await?;
+        assert_eq!(files(&root.join("suite__passing")), ["result.json"]);
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn mock_clock(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(clock_url()).await?;
+        let now = || async { c.execute("return Date.now();", Vec::new()).await?.convert::<u64>() };
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        c.install_mock_clock(Some(start)).await?;
+        assert_eq!(now().await?, 1_700_000_000_000);
+
+        // The debounced handler only runs once 5 seconds have passed on the mock clock.
+        c.find(By::Id("search")).await?.send_keys("rust").await?;
+        let status = c.find(By::Id("status")).await?;
+        c.advance_clock(Duration::from_millis(4999)).await?;
+        assert_eq!(status.text().await?, "");
+        c.advance_clock(Duration::from_millis(1)).await?;
+        assert_eq!(status.text().await?, "saved at 1700000005000");
+
+        let script = "setTimeout(() => setTimeout(() => document.title = 'done', 1000), 1000);";
+        c.execute(script, Vec::new()).await?;
+        c.run_all_timers().await?;
+        assert_eq!(c.title().await?, "done");
+        assert_eq!(now().await?, 1_700_000_007_000);
+
+        if test_harness.browser() == "chrome" {
+            // The clock is installed again in new documents, from the start.
+            c.goto(clock_url()).await?;
+            assert_eq!(now().await?, 1_700_000_000_000);
+        } else {
+            // The clock is installed again when it is advanced, from the time it reached.
+            c.goto(clock_url()).await?;
+            c.advance_clock(Duration::from_secs(1)).await?;
+            assert_eq!(now().await?, 1_700_000_008_000);
+        }
+
+        c.uninstall_mock_clock().await?;
+        let real_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        assert!(now().await?.abs_diff(real_now) < 60_000);
+        let err = c.advance_clock(Duration::from_secs(1)).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::InvalidArgument(_)), "{err:?}");
+        Ok(())
+    })
+}
@@ -0,0 +1,243 @@
+use common::*;
+use rstest::rstest;
+use std::time::Duration;
+use thirtyfour::components::modal::ModalComponent;
+use thirtyfour::error::WebDriverErrorInner;
+use thirtyfour::{prelude::*, support::block_on};
+
+mod common;
+
+/// Return the description of the wait from the error, with the elapsed time replaced by
+/// `<elapsed>` so that it can be compared.
+fn wait_message(err: &WebDriverError) -> String {
+    let message = match err.as_inner() {
+        WebDriverErrorInner::Timeout(message) => message.clone(),
+        inner => inner.info().expect("error should have info").value.message.clone(),
+    };
+    let start = message.find("waited ").expect("error should describe the wait");
+    let message = &message[start..];
+    let end = message.find(" for ").expect("error should describe the condition");
+    format!("waited <elapsed>{}", &message[end..])
+}
+
+#[rstest]
+fn query_timeout_message(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+
+        let err = c.query(By::Css("nav a")).and_not_displayed().nowait().first().await.unwrap_err();
+        assert!(err.is_no_such_element());
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for a matching element (1 attempt); \
+             last saw 2 elements matching css `nav a`, none passed the filters"
+        );
+
+        let err = c.query(By::Css("nav a")).nowait().single().await.unwrap_err();
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for exactly one matching element (1 attempt); \
+             last saw 2 elements matching css `nav a`"
+        );
+
+        let err = c
+            .query(By::Id("missing"))
+            .or(By::Css("nav a"))
+            .and_not_displayed()
+            .nowait()
+            .any_required()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for a matching element (1 attempt); last saw 0 elements matching \
+             id `missing` and 2 elements matching css `nav a`, none passed the filters"
+        );
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_waiter_timeout_message(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let elem = c.find(By::Id("checkbox-hidden")).await?;
+
+        let err = elem.wait_until().wait(Duration::ZERO, Duration::ZERO).displayed().await;
+        let err = err.unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for element to be displayed (1 attempt); \
+             last saw <input id=checkbox-hidden>, not displayed, enabled, not selected"
+        );
+
+        let err = elem
+            .wait_until()
+            .wait(Duration::ZERO, Duration::ZERO)
+            .condition_described("element to turn blue", |_: WebElement| async { Ok(false) })
+            .await
+            .unwrap_err();
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for element to turn blue (1 attempt); \
+             last saw <input id=checkbox-hidden>, not displayed, enabled, not selected"
+        );
+
+        // The message set with error() replaces the description.
+        let err = elem
+            .wait_until()
+            .wait(Duration::ZERO, Duration::ZERO)
+            .error("the checkbox to be ticked")
+            .selected()
+            .await
+            .unwrap_err();
+        assert!(wait_message(&err)
+            .starts_with("waited <elapsed> for the checkbox to be ticked (1 attempt); last saw "));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn alert_timeout_message(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let err = c.wait_for_alert(Duration::ZERO).await.unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for an alert to open (1 attempt); last saw no alert"
+        );
+        Ok(())
+    })
+}
+
+#[rstest]
+fn cookie_timeout_message(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        c.delete_all_cookies().await?;
+        c.execute(r#"document.cookie = "flavour=plain";"#, Vec::new()).await?;
+
+        let err = c
+            .wait_for_cookie("flavour", |x| x.value == "chocolate", Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for cookie 'flavour' to match the predicate (1 attempt); \
+             last saw cookie 'flavour' with value \"plain\""
+        );
+
+        let err = c.wait_for_cookie("missing", |_| true, Duration::ZERO).await.unwrap_err();
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for cookie 'missing' to match the predicate (1 attempt); \
+             last saw no cookie named 'missing'"
+        );
+        Ok(())
+    })
+}
+
+#[rstest]
+fn window_timeout_message(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let err = c.switch_to_window_where(|_| false, Duration::ZERO).await.unwrap_err();
+        assert!(matches!(err.as_inner(), WebDriverErrorInner::NoSuchWindow(_)));
+        let message = wait_message(&err);
+        assert!(message.starts_with(
+            "waited <elapsed> for a window matching the predicate (1 attempt); last saw windows ["
+        ));
+        assert!(message.contains("sample_page.html"));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn modal_timeout_message(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&modal_url()).await?;
+        let modal = ModalComponent::new(c.find(By::Tag("body")).await?);
+        let err = modal.wait_open(Duration::ZERO).await.unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(
+            wait_message(&err),
+            "waited <elapsed> for a modal to open (1 attempt); \
+             last saw no open modal matching css `.modal, dialog`"
+        );
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_waiter_clickable_and_not_obscured(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&overlay_url()).await?;
+        let save = c.find(By::Id("save")).await?;
+        assert!(save.is_clickable().await?);
+        assert_eq!(save.obscured_by().await?.as_deref(), Some("<div id=modal-backdrop>"));
+
+        let failure = |id: &'static str| async move {
+            let elem = c.find(By::Id(id)).await?;
+            let err = elem
+                .wait_until()
+                .wait(Duration::ZERO, Duration::ZERO)
+                .clickable_and_not_obscured()
+                .await
+                .unwrap_err();
+            WebDriverResult::Ok(wait_message(&err))
+        };
+        let prefix = "waited <elapsed> for element to be clickable and not obscured (1 attempt); \
+                      last saw the element";
+        assert_eq!(
+            failure("save").await?,
+            format!("{prefix} displayed and enabled but obscured by <div id=modal-backdrop>")
+        );
+        assert_eq!(failure("disabled").await?, format!("{prefix} displayed but not enabled"));
+        assert_eq!(failure("hidden").await?, format!("{prefix} not displayed"));
+
+        let covered =
+            c.query(By::Tag("button")).and_clickable().and_obscured().all_from_selector().await?;
+        assert_eq!(covered.len(), 1);
+
+        c.execute("window.hideBackdrop(300);", Vec::new()).await?;
+        save.wait_until()
+            .wait(Duration::from_secs(5), Duration::from_millis(100))
+            .clickable_and_not_obscured()
+            .await?;
+        let ready = c.query(By::Tag("button")).and_clickable().and_not_obscured().first().await?;
+        assert_eq!(ready.id().await?.as_deref(), Some("save"));
+        Ok(())
+    })
+}
+
+#[rstest]
+fn element_waiter_stale(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(&sample_page_url()).await?;
+        let link = c.find(By::Id("other_page_id")).await?;
+
+        let err = link.wait_until().wait(Duration::ZERO, Duration::ZERO).stale().await.unwrap_err();
+        assert!(err.is_timeout());
+        assert!(wait_message(&err).starts_with(
+            "waited <elapsed> for element to become stale (1 attempt); \
+             last saw <a id=other_page_id>, displayed"
+        ));
+
+        link.click().await?;
+        link.wait_until().wait(Duration::from_secs(5), Duration::from_millis(100)).stale().await?;
+        assert_eq!(c.current_url().await?.as_str(), other_page_url());
+
+        Ok(())
+    })
+}
@@ -0,0 +1,16 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct CardForm {
+    base: WebElement,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct CheckoutPage {
+    base: WebElement,
+    #[by(id = "card-form", allow_empty)]
+    card_form: ElementResolver<CardForm>,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct LineItem {
+    base: WebElement,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct CheckoutPage {
+    base: WebElement,
+    #[by(css = ".line-item", first)]
+    items: ElementResolver<Vec<LineItem>>,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use thirtyfour::components::{Component, ComponentStream};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct Row {
+    base: WebElement,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct Table {
+    base: WebElement,
+    #[by(css = "tr", first)]
+    rows: ComponentStream<Row>,
+}
+
+fn main() {}
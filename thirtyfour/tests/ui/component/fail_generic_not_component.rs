@@ -0,0 +1,21 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+/// Any row that can be listed in a `ListPanel`.
+pub trait RowLike: Component + Clone + Send + Sync + 'static {}
+
+#[derive(Debug, Clone, Component)]
+pub struct ListPanel<T: RowLike> {
+    base: WebElement,
+    #[by(css = "tbody tr")]
+    rows: ElementResolver<Vec<T>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NotAComponent;
+
+fn assert_component<C: Component>() {}
+
+fn main() {
+    assert_component::<ListPanel<NotAComponent>>();
+}
@@ -0,0 +1,11 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct LoginForm {
+    base: WebElement,
+    #[by(id = "login", ignore_errors(timeout))]
+    button: ElementResolver<WebElement>,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct Row {
+    base: WebElement,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct Table {
+    base: WebElement,
+    #[by(css = "tr", page_size = 10)]
+    rows: ElementResolver<Vec<Row>>,
+}
+
+fn main() {}
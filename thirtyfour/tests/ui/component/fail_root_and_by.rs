@@ -0,0 +1,11 @@
+use thirtyfour::components::Component;
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+#[component(root = "css:#app")]
+#[by(id = "app")]
+pub struct AppPage {
+    base: WebElement,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thirtyfour::components::Component;
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+#[component(root = "#app")]
+pub struct AppPage {
+    base: WebElement,
+}
+
+fn main() {}
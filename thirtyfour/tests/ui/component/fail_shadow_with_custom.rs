@@ -0,0 +1,15 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+async fn find_toggle(elem: WebElement) -> WebDriverResult<WebElement> {
+    elem.get_shadow_root().await?.find(By::Css("button")).await
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct Picker {
+    base: WebElement,
+    #[by(custom = find_toggle, shadow)]
+    toggle: ElementResolver<WebElement>,
+}
+
+fn main() {}
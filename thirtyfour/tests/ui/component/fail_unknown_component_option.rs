@@ -0,0 +1,10 @@
+use thirtyfour::components::Component;
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+#[component(wait_ready)]
+pub struct Results {
+    base: WebElement,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thirtyfour::components::Component;
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+#[component(root = "selector:#app")]
+pub struct AppPage {
+    base: WebElement,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct LoginForm {
+    base: WebElement,
+    #[by(id = "login", wait(timeout_ms = 5000, interval_ms = 100), nowait)]
+    button: ElementResolver<WebElement>,
+}
+
+fn main() {}
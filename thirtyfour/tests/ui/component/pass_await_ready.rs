@@ -0,0 +1,26 @@
+use thirtyfour::components::{Component, ComponentLifecycle, ElementResolver};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+#[component(await_ready)]
+pub struct Results {
+    base: WebElement,
+}
+
+impl ComponentLifecycle for Results {
+    async fn on_resolved(&self) -> WebDriverResult<()> {
+        self.base.query(By::ClassName("spinner")).not_exists().await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct SearchPage {
+    base: WebElement,
+    #[by(id = "results")]
+    results: ElementResolver<Results>,
+    #[by(class = "results", allow_empty)]
+    all_results: ElementResolver<Vec<Results>>,
+}
+
+fn main() {}
@@ -0,0 +1,66 @@
+use std::marker::PhantomData;
+
+use thirtyfour::components::{Component, ComponentStream, ElementResolver};
+use thirtyfour::prelude::*;
+
+/// Any row that can be listed in a `ListPanel`.
+pub trait RowLike: Component + Clone + Send + Sync + 'static {}
+
+#[derive(Debug, Clone, Component)]
+pub struct UserRow {
+    base: WebElement,
+    #[by(class = "name")]
+    name: ElementResolver<WebElement>,
+}
+
+impl RowLike for UserRow {}
+
+#[derive(Debug, Clone, Component)]
+pub struct OrderRow {
+    base: WebElement,
+    #[by(class = "total")]
+    total: ElementResolver<WebElement>,
+}
+
+impl RowLike for OrderRow {}
+
+#[derive(Debug, Clone, Component)]
+pub struct ListPanel<T: RowLike> {
+    base: WebElement,
+    #[by(css = "tbody tr")]
+    rows: ElementResolver<Vec<T>>,
+    #[by(css = "tbody tr", first)]
+    first_row: ElementResolver<T>,
+    #[by(css = "tfoot")]
+    footer: ElementResolver<WebElement>,
+}
+
+/// Bounds are only required where the fields need them.
+#[derive(Debug, Clone, Component)]
+pub struct LazyPanel<'a, R, M>
+where
+    R: Clone,
+{
+    base: WebElement,
+    #[by(css = "li", allow_empty)]
+    items: ElementResolver<Vec<R>>,
+    #[by(css = "li", stream)]
+    item_stream: ComponentStream<R>,
+    #[by(custom = resolve_marked)]
+    marked: ElementResolver<Vec<M>>,
+    label: PhantomData<&'a str>,
+}
+
+async fn resolve_marked<M>(_elem: WebElement) -> WebDriverResult<Vec<M>> {
+    Ok(Vec::new())
+}
+
+fn assert_component<C: Component>() {}
+
+fn main() {
+    assert_component::<ListPanel<UserRow>>();
+    assert_component::<ListPanel<OrderRow>>();
+    assert_component::<LazyPanel<'static, UserRow, String>>();
+    // Custom fields are refreshed with resolve_force(), whatever they resolve to.
+    let _ = |panel: LazyPanel<'static, UserRow, String>| async move { panel.refresh_all().await };
+}
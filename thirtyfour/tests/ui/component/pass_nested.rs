@@ -0,0 +1,33 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct CardForm {
+    base: WebElement,
+    #[by(css = "input[name='card-number']")]
+    number: ElementResolver<WebElement>,
+    #[by(css = "input[name='card-expiry']", no_auto_refresh)]
+    expiry: ElementResolver<WebElement>,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct LineItem {
+    base: WebElement,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct CheckoutPage {
+    base: WebElement,
+    #[by(id = "card-form")]
+    card_form: ElementResolver<CardForm>,
+    #[by(class = "card-form", first)]
+    first_card_form: ElementResolver<CardForm>,
+    #[by(css = ".line-item")]
+    items: ElementResolver<Vec<LineItem>>,
+    #[by(css = ".discount", allow_empty, nowait)]
+    discounts: thirtyfour::components::ElementResolver<Vec<LineItem>>,
+    #[by(id = "summary", single, description = "order summary")]
+    summary: ::thirtyfour::components::ElementResolver<LineItem>,
+}
+
+fn main() {}
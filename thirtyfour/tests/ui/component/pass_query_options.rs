@@ -0,0 +1,17 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+const TIMEOUT_MS: u64 = 5000;
+
+#[derive(Debug, Clone, Component)]
+pub struct LoginForm {
+    base: WebElement,
+    #[by(id = "login", wait(timeout_ms = TIMEOUT_MS, interval_ms = 100), description = "login button")]
+    button: ElementResolver<WebElement>,
+    #[by(id = "error", nowait, ignore_errors(stale))]
+    error: ElementResolver<WebElement>,
+    #[by(class = "field", allow_empty, ignore_errors, wait(timeout_ms = 1000, interval_ms = 50))]
+    fields: ElementResolver<Vec<WebElement>>,
+}
+
+fn main() {}
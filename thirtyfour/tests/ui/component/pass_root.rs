@@ -0,0 +1,24 @@
+use thirtyfour::components::{Component, ElementResolver, RootComponent};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+#[component(root = "css:#app")]
+pub struct AppPage {
+    base: WebElement,
+    #[by(id = "login")]
+    login: ElementResolver<WebElement>,
+}
+
+#[derive(Debug, Clone, Component)]
+#[by(xpath = "//main", description = "main content", wait(timeout_ms = 1000, interval_ms = 100))]
+pub struct MainPage {
+    base: WebElement,
+}
+
+async fn _open(driver: &WebDriver) -> WebDriverResult<()> {
+    let _app = AppPage::from_driver(driver).await?;
+    let _exists = MainPage::exists_in(driver).await?;
+    Ok(())
+}
+
+fn main() {}
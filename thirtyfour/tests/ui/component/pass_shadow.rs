@@ -0,0 +1,22 @@
+use thirtyfour::components::{Component, ElementResolver};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct PickerOption {
+    base: WebElement,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct Picker {
+    base: WebElement,
+    #[by(css = "button.toggle", shadow)]
+    toggle: ElementResolver<WebElement>,
+    #[by(css = ".option", shadow, allow_empty, nowait)]
+    options: ElementResolver<Vec<PickerOption>>,
+    #[by(css = ".selected", shadow, first, no_auto_refresh)]
+    selected: ElementResolver<PickerOption>,
+    #[by(css = "[slot='label']")]
+    label: ElementResolver<WebElement>,
+}
+
+fn main() {}
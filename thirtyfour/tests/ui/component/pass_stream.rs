@@ -0,0 +1,26 @@
+use thirtyfour::components::{Component, ComponentStream};
+use thirtyfour::prelude::*;
+
+#[derive(Debug, Clone, Component)]
+pub struct Row {
+    base: WebElement,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct Table {
+    base: WebElement,
+    #[by(css = "tr")]
+    rows: ComponentStream<Row>,
+    #[by(css = "tr", stream, page_size = 50)]
+    rows_explicit: thirtyfour::components::ComponentStream<Row>,
+    #[by(xpath = ".//tr", page_size = 10, scroll = scroll)]
+    rows_scrolled: ComponentStream<Row>,
+    #[by(tag = "td")]
+    cells: ComponentStream<WebElement>,
+}
+
+async fn scroll(elem: WebElement) -> WebDriverResult<()> {
+    elem.scroll_into_view().await
+}
+
+fn main() {}
@@ -1,6 +1,12 @@
+use assert_matches::assert_matches;
 use common::*;
 use rstest::rstest;
-use thirtyfour::{common::print::PrintParameters, prelude::*, support::block_on};
+use std::time::Duration;
+use thirtyfour::error::WebDriverErrorInner;
+use thirtyfour::extensions::cdp::{ChromeDevTools, DeviceMetrics};
+use thirtyfour::{
+    common::print::PrintParameters, prelude::*, support::block_on, CaptureOptions, FrameSelector,
+};
 
 mod common;
 
@@ -258,3 +264,299 @@ fn print_page(test_harness: TestHarness) -> WebDriverResult<()> {
         Ok(())
     })
 }
+
+#[rstest]
+fn iframe_switch_nested(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(nested_frames_url()).await?;
+
+        // Switch one level at a time.
+        c.switch_to_frame_named("level1").await?;
+        c.find(By::Id("level1_button")).await?;
+        let level2 = c.find(By::Id("level2")).await?;
+        c.switch_to_frame_element(&level2).await?;
+        c.find(By::Id("level2_button")).await?;
+        c.switch_to_frame_named("level3").await?;
+        c.find(By::Id("iframe_button")).await?;
+        c.switch_to_parent_frame().await?;
+        c.find(By::Id("level2_button")).await?;
+
+        // Switch the whole path from the top.
+        c.switch_into_path(&[
+            FrameSelector::Index(0),
+            FrameSelector::By(By::Css("iframe.nested")),
+            FrameSelector::Name("level3".to_string()),
+        ])
+        .await?;
+        c.find(By::Id("iframe_button")).await?.click().await?;
+
+        // A failed step reports which step failed.
+        let err = c
+            .switch_into_path(&[FrameSelector::Name("level1".to_string()), "missing".into()])
+            .await
+            .expect_err("the second step should fail");
+        assert!(err.to_string().contains("step 2 of 2"));
+
+        c.enter_default_frame().await?;
+        c.find(By::Id("top_button")).await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn iframe_follow_active_element(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(iframe_page_url()).await?;
+        c.execute(
+            r#"document.getElementById("iframe").contentDocument.getElementById("iframe_input").focus();"#,
+            Vec::new(),
+        )
+        .await?;
+
+        // Focus is on the iframe from the perspective of the top-level document.
+        assert_eq!(c.active_element().await?.tag_name().await?, "iframe");
+
+        let elem = c.switch_to_active_element_frame().await?;
+        assert_eq!(elem.id().await?.as_deref(), Some("iframe_input"));
+        elem.send_keys("thirtyfour").await?;
+        assert_eq!(
+            c.find(By::Id("iframe_input")).await?.value().await?.as_deref(),
+            Some("thirtyfour")
+        );
+
+        c.switch_to_default_content().await?;
+        c.find(By::Id("root_button")).await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn close_window_bookkeeping(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        let window_1 = c.window().await?;
+        let window_2 = c.new_tab().await?;
+        let window_3 = c.new_tab().await?;
+        assert_eq!(c.windows().await?.len(), 3);
+
+        // Use window 3, then window 2, then window 1.
+        c.switch_to_window(window_3.clone()).await?;
+        c.focus_window(window_2.clone()).await?;
+        assert_eq!(c.window().await?, window_2);
+        c.switch_to_window(window_1.clone()).await?;
+
+        // Closing window 1 should switch back to window 2, the most recently used.
+        assert_eq!(c.close_window_and_switch_back().await?, window_2);
+        assert_eq!(c.window().await?, window_2);
+        assert_eq!(c.windows().await?.len(), 2);
+
+        assert_eq!(c.close_other_windows().await?, 1);
+        assert_eq!(c.windows().await?, vec![window_2.clone()]);
+        assert_eq!(c.window().await?, window_2);
+
+        // Closing the last window is refused.
+        assert!(c.close_window_and_switch_back().await.is_err());
+        assert_eq!(c.window().await?, window_2);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn switch_to_window_where(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(sample_page_url()).await?;
+        let original = c.window().await?;
+        // Open a blank pop-up that only navigates after a delay.
+        c.execute(
+            r#"
+            const popup = window.open("about:blank", "_blank");
+            setTimeout(() => { popup.location.href = arguments[0]; }, 1000);
+            "#,
+            vec![serde_json::to_value(other_page_url()).unwrap()],
+        )
+        .await?;
+
+        let info = c
+            .switch_to_window_where(
+                |info| info.url.path().ends_with("/other_page.html") && !info.title.is_empty(),
+                Duration::from_secs(10),
+            )
+            .await?;
+        assert_ne!(info.handle, original);
+        assert_eq!(c.window().await?, info.handle);
+        assert_eq!(c.windows_info().await?.len(), 2);
+        assert_eq!(c.window().await?, info.handle);
+
+        // If nothing matches, the original window is restored.
+        c.switch_to_window(original.clone()).await?;
+        let result = c.switch_to_window_where(|_| false, Duration::from_secs(1)).await;
+        assert!(result.is_err());
+        assert_eq!(c.window().await?, original);
+        Ok(())
+    })
+}
+
+#[rstest]
+fn screenshot_to_dir(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(sample_page_url()).await?;
+        let dir = std::env::temp_dir()
+            .join(format!("thirtyfour-screenshots-{}", std::process::id()))
+            .join("nested");
+        let elem = c.find(By::Id("button-alert")).await?;
+
+        let (window_paths, element_paths) = futures_util::future::try_join(
+            futures_util::future::try_join_all((0..3).map(|_| c.screenshot_to_dir(&dir, "window"))),
+            futures_util::future::try_join_all(
+                (0..3).map(|_| elem.screenshot_to_dir(&dir, "element")),
+            ),
+        )
+        .await?;
+
+        let mut paths: Vec<_> = window_paths.into_iter().chain(element_paths).collect();
+        for path in &paths {
+            let png = std::fs::read(path)?;
+            assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"), "{} is not a PNG", path.display());
+        }
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 6);
+        assert_eq!(std::fs::read_dir(&dir)?.count(), 6);
+
+        assert!(!c.screenshot_as_base64().await?.is_empty());
+        std::fs::remove_dir_all(dir.parent().unwrap())?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn full_page_screenshot_cdp(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(sample_page_url()).await?;
+        if test_harness.browser() != "chrome" {
+            assert_matches!(
+                c.full_page_screenshot_cdp().await.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::UnsupportedOperation(..))
+            );
+            return Ok(());
+        }
+
+        c.execute(r#"document.body.style.minHeight = "3000px";"#, Vec::new()).await?;
+        let viewport = r#"return [window.innerWidth, window.innerHeight];"#;
+        let before: Vec<i64> = c.execute(viewport, Vec::new()).await?.convert()?;
+        let (height, ratio): (f64, f64) = c
+            .execute(
+                r#"return [document.documentElement.scrollHeight, window.devicePixelRatio];"#,
+                Vec::new(),
+            )
+            .await?
+            .convert()?;
+
+        let png = c.full_page_screenshot_cdp().await?;
+        assert!(png.starts_with(b"\x89PNG"));
+        let png_height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert!((png_height as f64 - height * ratio).abs() <= 2.0, "height {png_height}");
+
+        let after: Vec<i64> = c.execute(viewport, Vec::new()).await?.convert()?;
+        assert_eq!(before, after);
+
+        // Metrics set via ChromeDevTools are applied again, rather than cleared.
+        let dev_tools = ChromeDevTools::new(c.handle.clone());
+        dev_tools.set_device_metrics(&DeviceMetrics::new(600, 400)).await?;
+        c.full_page_screenshot_cdp().await?;
+        let after: Vec<i64> = c.execute(viewport, Vec::new()).await?.convert()?;
+        assert_eq!(after, [600, 400]);
+        dev_tools.clear_device_metrics().await?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn full_page_screenshot_png(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(large_page_url()).await?;
+        let (viewport_height, ratio): (f64, f64) = c
+            .execute(r#"return [window.innerHeight, window.devicePixelRatio];"#, Vec::new())
+            .await?
+            .convert()?;
+
+        let png = c.full_page_screenshot_png().await?;
+        assert!(png.starts_with(b"\x89PNG"));
+        let png_height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert!(png_height as f64 > viewport_height * ratio, "height {png_height}");
+
+        let native = c.firefox().full_page_screenshot_png().await;
+        if test_harness.browser() == "firefox" {
+            assert!(native?.starts_with(b"\x89PNG"));
+        } else {
+            assert_matches!(
+                native.map_err(WebDriverError::into_inner),
+                Err(WebDriverErrorInner::UnsupportedOperation(..))
+            );
+        }
+        Ok(())
+    })
+}
+
+#[rstest]
+fn capture_sequence(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(transition_url()).await?;
+        c.find(By::Id("button-expand")).await?.click().await?;
+
+        let frames = c.capture_sequence(4, Duration::from_millis(300)).await?;
+        assert_eq!(frames.len(), 4);
+        assert!(frames.windows(2).all(|w| w[0].at < w[1].at));
+        assert!(frames.windows(2).any(|w| w[0].png != w[1].png), "all frames are identical");
+        Ok(())
+    })
+}
+
+#[rstest]
+fn capture_while_to_dir(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(transition_url()).await?;
+        c.find(By::Id("button-expand")).await?.click().await?;
+
+        let dir = std::env::temp_dir().join(format!("thirtyfour-frames-{}", std::process::id()));
+        let options = CaptureOptions {
+            interval: Duration::from_millis(100),
+            max_frames: 50,
+            output_dir: Some(dir.clone()),
+        };
+        let frames =
+            c.capture_while(thirtyfour::support::sleep(Duration::from_secs(1)), options).await?;
+        assert!(!frames.is_empty() && frames.len() < 50);
+        for frame in &frames {
+            assert!(frame.png.is_empty());
+            assert!(frame.path.as_ref().unwrap().exists());
+        }
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    })
+}
+
+#[rstest]
+fn capture_while_zero_interval(test_harness: TestHarness) -> WebDriverResult<()> {
+    let c = test_harness.driver();
+    block_on(async {
+        c.goto(transition_url()).await?;
+        let options = CaptureOptions {
+            interval: Duration::ZERO,
+            max_frames: 3,
+            output_dir: None,
+        };
+        let frames = c.capture_while(std::future::pending::<()>(), options).await?;
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|frame| !frame.png.is_empty()));
+        Ok(())
+    })
+}